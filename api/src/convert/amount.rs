@@ -1,7 +1,9 @@
 //! Convert to/from external::Amount
 
 use crate::{convert::ConversionError, external};
-use mc_transaction_core::{CompressedCommitment, MaskedAmount};
+use mc_transaction_core::{
+    ring_signature::CurveScalar, AmountMasks, CompressedCommitment, MaskedAmount,
+};
 use mc_util_repr_bytes::ReprBytes;
 use std::convert::TryFrom;
 
@@ -31,3 +33,25 @@ impl TryFrom<&external::MaskedAmount> for MaskedAmount {
         Ok(amount)
     }
 }
+
+impl From<&AmountMasks> for external::AmountMasks {
+    fn from(source: &AmountMasks) -> Self {
+        let mut masks = external::AmountMasks::new();
+        masks.set_value_mask(source.value_mask);
+        masks.set_token_id_mask(source.token_id_mask);
+        masks.set_blinding((&source.blinding).into());
+        masks
+    }
+}
+
+impl TryFrom<&external::AmountMasks> for AmountMasks {
+    type Error = ConversionError;
+
+    fn try_from(source: &external::AmountMasks) -> Result<Self, Self::Error> {
+        Ok(AmountMasks {
+            value_mask: source.get_value_mask(),
+            token_id_mask: source.get_token_id_mask(),
+            blinding: CurveScalar::try_from(source.get_blinding())?,
+        })
+    }
+}
@@ -47,6 +47,12 @@ impl From<&SignerSet<Ed25519Public>> for external::Ed25519SignerSet {
                 .collect(),
         );
         dst.set_threshold(src.threshold());
+        dst.set_multi_signers(
+            src.multi_signers()
+                .iter()
+                .map(external::Ed25519SignerSet::from)
+                .collect(),
+        );
         dst
     }
 }
@@ -62,9 +68,15 @@ impl TryFrom<&external::Ed25519SignerSet> for SignerSet<Ed25519Public> {
             .map(Ed25519Public::try_from)
             .collect::<Result<Vec<_>, _>>()?;
 
+        let multi_signers: Vec<SignerSet<Ed25519Public>> = source
+            .get_multi_signers()
+            .iter()
+            .map(SignerSet::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+
         let threshold = source.get_threshold();
 
-        Ok(Self::new(signers, threshold))
+        Ok(Self::new_with_multi(signers, multi_signers, threshold))
     }
 }
 
@@ -95,6 +107,14 @@ pub mod tests {
         )
     }
 
+    // Generate a signer set with a nested signer set for testing purposes.
+    pub fn test_nested_signer_set() -> SignerSet<Ed25519Public> {
+        let mut rng = Hc128Rng::from_seed([2u8; 32]);
+        let signer1 = Ed25519Pair::from_random(&mut rng);
+
+        SignerSet::new_with_multi(vec![signer1.public_key()], vec![test_signer_set()], 2)
+    }
+
     // Generate a multi sig for testing purpses.
     pub fn test_multi_sig() -> MultiSig<Ed25519Signature> {
         let mut rng = Hc128Rng::from_seed([1u8; 32]);
@@ -114,37 +134,37 @@ pub mod tests {
     // SignerSet<Ed25519Public> -> external::Ed25519SignerSet ->
     // SignerSet<Ed25519Public> should be the identity function.
     fn test_convert_ed25519_signer_set() {
-        let source = test_signer_set();
-
-        // decode(encode(source)) should be the identity function.
-        {
-            let bytes = encode(&source);
-            let recovered = decode(&bytes).unwrap();
-            assert_eq!(source, recovered);
-        }
-
-        // SignerSet<Ed25519Public> -> external::Ed25519SignerSet ->
-        // SignerSet<Ed25519Public> should be the identity function.
-        {
-            let external = external::Ed25519SignerSet::from(&source);
-            let recovered = SignerSet::try_from(&external).unwrap();
-            assert_eq!(source, recovered);
-        }
-
-        // Encoding with prost, decoding with protobuf should be the identity
-        // function.
-        {
-            let bytes = encode(&source);
-            let recovered = external::Ed25519SignerSet::parse_from_bytes(&bytes).unwrap();
-            assert_eq!(recovered, external::Ed25519SignerSet::from(&source));
-        }
-
-        // Encoding with protobuf, decoding with prost should be the identity function.
-        {
-            let external = external::Ed25519SignerSet::from(&source);
-            let bytes = external.write_to_bytes().unwrap();
-            let recovered: SignerSet<Ed25519Public> = decode(&bytes).unwrap();
-            assert_eq!(source, recovered);
+        for source in [test_signer_set(), test_nested_signer_set()] {
+            // decode(encode(source)) should be the identity function.
+            {
+                let bytes = encode(&source);
+                let recovered = decode(&bytes).unwrap();
+                assert_eq!(source, recovered);
+            }
+
+            // SignerSet<Ed25519Public> -> external::Ed25519SignerSet ->
+            // SignerSet<Ed25519Public> should be the identity function.
+            {
+                let external = external::Ed25519SignerSet::from(&source);
+                let recovered = SignerSet::try_from(&external).unwrap();
+                assert_eq!(source, recovered);
+            }
+
+            // Encoding with prost, decoding with protobuf should be the identity
+            // function.
+            {
+                let bytes = encode(&source);
+                let recovered = external::Ed25519SignerSet::parse_from_bytes(&bytes).unwrap();
+                assert_eq!(recovered, external::Ed25519SignerSet::from(&source));
+            }
+
+            // Encoding with protobuf, decoding with prost should be the identity function.
+            {
+                let external = external::Ed25519SignerSet::from(&source);
+                let bytes = external.write_to_bytes().unwrap();
+                let recovered: SignerSet<Ed25519Public> = decode(&bytes).unwrap();
+                assert_eq!(source, recovered);
+            }
         }
     }
 
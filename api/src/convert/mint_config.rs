@@ -4,7 +4,7 @@
 
 use crate::{convert::ConversionError, external};
 use mc_crypto_multisig::{MultiSig, SignerSet};
-use mc_transaction_core::mint::{MintConfig, MintConfigTx, MintConfigTxPrefix};
+use mc_transaction_core::mint::{MintConfig, MintConfigTx, MintConfigTxPrefix, MintLimitWindow};
 
 use std::convert::TryFrom;
 
@@ -15,6 +15,12 @@ impl From<&MintConfig> for external::MintConfig {
         dst.set_token_id(src.token_id);
         dst.set_signer_set((&src.signer_set).into());
         dst.set_mint_limit(src.mint_limit);
+        dst.set_mint_limit_windows(
+            src.mint_limit_windows
+                .iter()
+                .map(external::MintLimitWindow::from)
+                .collect(),
+        );
         dst
     }
 }
@@ -29,10 +35,35 @@ impl TryFrom<&external::MintConfig> for MintConfig {
             token_id: source.get_token_id(),
             signer_set,
             mint_limit: source.get_mint_limit(),
+            mint_limit_windows: source
+                .get_mint_limit_windows()
+                .iter()
+                .map(MintLimitWindow::from)
+                .collect(),
         })
     }
 }
 
+/// Convert MintLimitWindow --> external::MintLimitWindow.
+impl From<&MintLimitWindow> for external::MintLimitWindow {
+    fn from(src: &MintLimitWindow) -> Self {
+        let mut dst = external::MintLimitWindow::new();
+        dst.set_num_blocks(src.num_blocks);
+        dst.set_limit(src.limit);
+        dst
+    }
+}
+
+/// Convert external::MintLimitWindow --> MintLimitWindow.
+impl From<&external::MintLimitWindow> for MintLimitWindow {
+    fn from(source: &external::MintLimitWindow) -> Self {
+        Self {
+            num_blocks: source.get_num_blocks(),
+            limit: source.get_limit(),
+        }
+    }
+}
+
 /// Convert MintConfigTxPrefix --> external::MintConfigTxPrefix.
 impl From<&MintConfigTxPrefix> for external::MintConfigTxPrefix {
     fn from(src: &MintConfigTxPrefix) -> Self {
@@ -104,6 +135,16 @@ mod tests {
             token_id: 123,
             signer_set: test_signer_set(),
             mint_limit: 10000,
+            mint_limit_windows: vec![
+                MintLimitWindow {
+                    num_blocks: 100,
+                    limit: 1000,
+                },
+                MintLimitWindow {
+                    num_blocks: 1000,
+                    limit: 5000,
+                },
+            ],
         };
 
         // decode(encode(source)) should be the identity function.
@@ -150,11 +191,13 @@ mod tests {
                         token_id: 123,
                         signer_set: test_signer_set(),
                         mint_limit: 10000,
+                        mint_limit_windows: vec![],
                     },
                     MintConfig {
                         token_id: 456,
                         signer_set: test_signer_set(),
                         mint_limit: 20000,
+                        mint_limit_windows: vec![],
                     },
                 ],
                 nonce: vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16],
@@ -28,10 +28,12 @@ mod ed25519_signature;
 mod key_image;
 mod mint_config;
 mod mint_tx;
+mod partial_tx;
 mod public_address;
 mod ring_mlsag;
 mod ristretto_private;
 mod signature_rct_bulletproofs;
+mod signed_contingent_input;
 mod tx;
 mod tx_hash;
 mod tx_in;
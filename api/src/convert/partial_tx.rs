@@ -0,0 +1,144 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! Convert to/from external::PartialTx and external::PartialTxOutput.
+
+use crate::{convert::ConversionError, external};
+use mc_transaction_core::{
+    tx::TxOut, PartialTx, PartialTxOutput, SignedContingentInput, UnmaskedAmount,
+};
+use std::convert::TryFrom;
+
+impl From<&PartialTxOutput> for external::PartialTxOutput {
+    fn from(source: &PartialTxOutput) -> Self {
+        let mut output = external::PartialTxOutput::new();
+        output.set_tx_out(external::TxOut::from(&source.tx_out));
+        output.set_amount(external::UnmaskedAmount::from(&source.amount));
+        output
+    }
+}
+
+impl TryFrom<&external::PartialTxOutput> for PartialTxOutput {
+    type Error = ConversionError;
+
+    fn try_from(source: &external::PartialTxOutput) -> Result<Self, Self::Error> {
+        Ok(PartialTxOutput {
+            tx_out: TxOut::try_from(source.get_tx_out())?,
+            amount: UnmaskedAmount::try_from(source.get_amount())?,
+        })
+    }
+}
+
+impl From<&PartialTx> for external::PartialTx {
+    fn from(source: &PartialTx) -> Self {
+        let mut partial_tx = external::PartialTx::new();
+        partial_tx.set_version(source.version);
+        partial_tx.set_block_version(source.block_version);
+        partial_tx.set_fee_value(source.fee_value);
+        partial_tx.set_fee_token_id(source.fee_token_id);
+        partial_tx.set_tombstone_block(source.tombstone_block);
+        partial_tx.set_signed_inputs(
+            source
+                .signed_inputs
+                .iter()
+                .map(external::SignedContingentInput::from)
+                .collect(),
+        );
+        partial_tx.set_outputs(
+            source
+                .outputs
+                .iter()
+                .map(external::PartialTxOutput::from)
+                .collect(),
+        );
+        partial_tx.set_fill_values(source.fill_values.clone());
+        partial_tx
+    }
+}
+
+impl TryFrom<&external::PartialTx> for PartialTx {
+    type Error = ConversionError;
+
+    fn try_from(source: &external::PartialTx) -> Result<Self, Self::Error> {
+        let signed_inputs = source
+            .get_signed_inputs()
+            .iter()
+            .map(SignedContingentInput::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+        let outputs = source
+            .get_outputs()
+            .iter()
+            .map(PartialTxOutput::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(PartialTx {
+            version: source.get_version(),
+            block_version: source.get_block_version(),
+            fee_value: source.get_fee_value(),
+            fee_token_id: source.get_fee_token_id(),
+            tombstone_block: source.get_tombstone_block(),
+            signed_inputs,
+            outputs,
+            fill_values: source.get_fill_values().to_vec(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mc_crypto_keys::RistrettoPublic;
+    use mc_transaction_core::{
+        encrypted_fog_hint::ENCRYPTED_FOG_HINT_LEN, ring_signature::CurveScalar, tokens::Mob,
+        Amount, BlockVersion, MaskedAmount, Token,
+    };
+    use mc_util_from_random::FromRandom;
+    use mc_util_serial::{decode, encode};
+    use protobuf::Message;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    // PartialTx --> external::PartialTx --> PartialTx
+    fn test_partial_tx_round_trip() {
+        let mut rng: StdRng = SeedableRng::from_seed([7u8; 32]);
+        let amount = Amount::new(1234, Mob::ID);
+        let mut source = PartialTx::new(BlockVersion::MAX, Amount::new(400, Mob::ID), 100).unwrap();
+        source.outputs.push(PartialTxOutput {
+            tx_out: TxOut {
+                masked_amount: MaskedAmount::new(amount, &RistrettoPublic::from_random(&mut rng))
+                    .unwrap(),
+                target_key: RistrettoPublic::from_random(&mut rng).into(),
+                public_key: RistrettoPublic::from_random(&mut rng).into(),
+                e_fog_hint: (&[0u8; ENCRYPTED_FOG_HINT_LEN]).into(),
+                e_memo: None,
+            },
+            amount: UnmaskedAmount {
+                value: amount.value,
+                token_id: *amount.token_id,
+                blinding: CurveScalar::from_random(&mut rng),
+            },
+        });
+        source.fill_values.push(7);
+
+        // Roundtrip from protobuf should return the same object
+        {
+            let external = external::PartialTx::from(&source);
+            let recovered = PartialTx::try_from(&external).unwrap();
+            assert_eq!(source, recovered);
+        }
+
+        // Encoding with prost, decoding with protobuf should produce the same object
+        {
+            let bytes = encode(&source);
+            let recovered = external::PartialTx::parse_from_bytes(&bytes).unwrap();
+            assert_eq!(recovered, external::PartialTx::from(&source));
+        }
+
+        // Encoding with protobuf, decoding with prost should produce the same object
+        {
+            let external = external::PartialTx::from(&source);
+            let bytes = external.write_to_bytes().unwrap();
+            let recovered: PartialTx = decode(&bytes).unwrap();
+            assert_eq!(source, recovered);
+        }
+    }
+}
@@ -0,0 +1,116 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! Convert to/from external::SignedContingentInput and
+//! external::UnmaskedAmount.
+
+use crate::{convert::ConversionError, external};
+use mc_transaction_core::{
+    ring_signature::{CurveScalar, RingMLSAG},
+    tx::TxIn,
+    SignedContingentInput, UnmaskedAmount,
+};
+use std::convert::TryFrom;
+
+impl From<&UnmaskedAmount> for external::UnmaskedAmount {
+    fn from(source: &UnmaskedAmount) -> Self {
+        let mut unmasked_amount = external::UnmaskedAmount::new();
+        unmasked_amount.set_value(source.value);
+        unmasked_amount.set_token_id(source.token_id);
+        unmasked_amount.set_blinding(external::CurveScalar::from(&source.blinding));
+        unmasked_amount
+    }
+}
+
+impl TryFrom<&external::UnmaskedAmount> for UnmaskedAmount {
+    type Error = ConversionError;
+
+    fn try_from(source: &external::UnmaskedAmount) -> Result<Self, Self::Error> {
+        Ok(UnmaskedAmount {
+            value: source.get_value(),
+            token_id: source.get_token_id(),
+            blinding: CurveScalar::try_from(source.get_blinding())?,
+        })
+    }
+}
+
+impl From<&SignedContingentInput> for external::SignedContingentInput {
+    fn from(source: &SignedContingentInput) -> Self {
+        let mut sci = external::SignedContingentInput::new();
+        sci.set_block_version(source.block_version);
+        sci.set_tx_in(external::TxIn::from(&source.tx_in));
+        sci.set_mlsag(external::RingMLSAG::from(&source.mlsag));
+        sci.set_pseudo_output_amount(external::UnmaskedAmount::from(&source.pseudo_output_amount));
+        sci.set_required_output_amounts(
+            source
+                .required_output_amounts
+                .iter()
+                .map(external::UnmaskedAmount::from)
+                .collect(),
+        );
+        sci.set_tx_out_global_indices(source.tx_out_global_indices.clone());
+        sci
+    }
+}
+
+impl TryFrom<&external::SignedContingentInput> for SignedContingentInput {
+    type Error = ConversionError;
+
+    fn try_from(source: &external::SignedContingentInput) -> Result<Self, Self::Error> {
+        let required_output_amounts = source
+            .get_required_output_amounts()
+            .iter()
+            .map(UnmaskedAmount::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(SignedContingentInput {
+            block_version: source.get_block_version(),
+            tx_in: TxIn::try_from(source.get_tx_in())?,
+            mlsag: RingMLSAG::try_from(source.get_mlsag())?,
+            pseudo_output_amount: UnmaskedAmount::try_from(source.get_pseudo_output_amount())?,
+            required_output_amounts,
+            tx_out_global_indices: source.get_tx_out_global_indices().to_vec(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mc_util_from_random::FromRandom;
+    use mc_util_serial::{decode, encode};
+    use protobuf::Message;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    // UnmaskedAmount --> external::UnmaskedAmount --> UnmaskedAmount
+    fn test_unmasked_amount_round_trip() {
+        let mut rng: StdRng = SeedableRng::from_seed([7u8; 32]);
+        let source = UnmaskedAmount {
+            value: 1234,
+            token_id: 5,
+            blinding: CurveScalar::from_random(&mut rng),
+        };
+
+        // Roundtrip from protobuf should return the same object
+        {
+            let external = external::UnmaskedAmount::from(&source);
+            let recovered = UnmaskedAmount::try_from(&external).unwrap();
+            assert_eq!(source, recovered);
+        }
+
+        // Encoding with prost, decoding with protobuf should produce the same object
+        {
+            let bytes = encode(&source);
+            let recovered = external::UnmaskedAmount::parse_from_bytes(&bytes).unwrap();
+            assert_eq!(recovered, external::UnmaskedAmount::from(&source));
+        }
+
+        // Encoding with protobuf, decoding with prost should produce the same object
+        {
+            let external = external::UnmaskedAmount::from(&source);
+            let bytes = external.write_to_bytes().unwrap();
+            let recovered: UnmaskedAmount = decode(&bytes).unwrap();
+            assert_eq!(source, recovered);
+        }
+    }
+}
@@ -53,11 +53,13 @@ mod tests {
                             token_id: 123,
                             signer_set: test_signer_set(),
                             mint_limit: 10000,
+                            mint_limit_windows: vec![],
                         },
                         MintConfig {
                             token_id: 456,
                             signer_set: test_signer_set(),
                             mint_limit: 20000,
+                            mint_limit_windows: vec![],
                         },
                     ],
                     nonce: vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16],
@@ -6,7 +6,9 @@
 use crate::printable;
 use crc::Crc;
 use displaydoc::Display;
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
 use protobuf::Message;
+use std::collections::BTreeSet;
 
 /// Decoding / encoding errors
 #[derive(Clone, Debug, Eq, PartialEq, Display)]
@@ -25,6 +27,15 @@ pub enum Error {
 
     /// Not enough bytes in the decoded vector {0}
     InsufficientBytes(usize),
+
+    /// URI does not use the mob: scheme
+    UriScheme,
+
+    /// URI does not start with a public address
+    UriPublicAddress,
+
+    /// Invalid URI parameter: {0}
+    UriParameter(String),
 }
 
 impl std::error::Error for Error {}
@@ -71,6 +82,113 @@ impl printable::PrintableWrapper {
     }
 }
 
+/// The scheme of payment request URIs.
+pub const PAYMENT_REQUEST_URI_SCHEME: &str = "mob";
+
+/// Characters escaped in URI parameter values: everything but the unreserved
+/// characters of RFC 3986.
+const URI_VALUE_ESCAPE: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+/// The URI form of a payment request is
+/// `mob:<b58 public
+/// address>?amount=..&token_id=..&payment_request_id=..&memo=..&expiry=..`
+/// where the public address is b58 encoded as a PrintableWrapper, and
+/// parameters holding a default value are omitted.
+impl printable::PaymentRequest {
+    /// Encodes the payment request as a `mob:` URI
+    pub fn uri_encode(&self) -> Result<String, Error> {
+        let mut wrapper = printable::PrintableWrapper::new();
+        wrapper.set_public_address(self.get_public_address().clone());
+
+        let mut params = Vec::new();
+        if self.get_value() != 0 {
+            params.push(format!("amount={}", self.get_value()));
+        }
+        if self.get_token_id() != 0 {
+            params.push(format!("token_id={}", self.get_token_id()));
+        }
+        if self.get_payment_request_id() != 0 {
+            params.push(format!(
+                "payment_request_id={}",
+                self.get_payment_request_id()
+            ));
+        }
+        if !self.get_memo().is_empty() {
+            params.push(format!(
+                "memo={}",
+                utf8_percent_encode(self.get_memo(), URI_VALUE_ESCAPE)
+            ));
+        }
+        if self.get_expiry() != 0 {
+            params.push(format!("expiry={}", self.get_expiry()));
+        }
+
+        let mut uri = format!("{}:{}", PAYMENT_REQUEST_URI_SCHEME, wrapper.b58_encode()?);
+        if !params.is_empty() {
+            uri.push('?');
+            uri.push_str(&params.join("&"));
+        }
+        Ok(uri)
+    }
+
+    /// Decodes a `mob:` URI into a payment request.
+    /// Unknown parameters are ignored, so that new ones can be added without
+    /// breaking older clients.
+    pub fn uri_decode(uri: &str) -> Result<Self, Error> {
+        let (scheme, rest) = uri.split_once(':').ok_or(Error::UriScheme)?;
+        if !scheme.eq_ignore_ascii_case(PAYMENT_REQUEST_URI_SCHEME) {
+            return Err(Error::UriScheme);
+        }
+        let (b58_code, query) = rest.split_once('?').unwrap_or((rest, ""));
+
+        let mut wrapper = printable::PrintableWrapper::b58_decode(b58_code.to_string())?;
+        if !wrapper.has_public_address() {
+            return Err(Error::UriPublicAddress);
+        }
+
+        let mut payment_request = Self::new();
+        payment_request.set_public_address(wrapper.take_public_address());
+
+        let mut seen = BTreeSet::new();
+        for param in query.split('&').filter(|param| !param.is_empty()) {
+            let (key, value) = param.split_once('=').unwrap_or((param, ""));
+            if !seen.insert(key) {
+                return Err(Error::UriParameter(format!("duplicate {}", key)));
+            }
+            let parse_u64 = || {
+                value
+                    .parse::<u64>()
+                    .map_err(|err| Error::UriParameter(format!("{}: {}", key, err)))
+            };
+            match key {
+                "amount" => payment_request.set_value(parse_u64()?),
+                "token_id" => payment_request.set_token_id(parse_u64()?),
+                "payment_request_id" => payment_request.set_payment_request_id(parse_u64()?),
+                "expiry" => payment_request.set_expiry(parse_u64()?),
+                "memo" => payment_request.set_memo(
+                    percent_decode_str(value)
+                        .decode_utf8()
+                        .map_err(|err| Error::UriParameter(format!("memo: {}", err)))?
+                        .into_owned(),
+                ),
+                _ => {}
+            }
+        }
+
+        Ok(payment_request)
+    }
+
+    /// Whether the request has expired at the given unix timestamp (in
+    /// seconds).
+    pub fn is_expired(&self, now: u64) -> bool {
+        self.get_expiry() != 0 && now >= self.get_expiry()
+    }
+}
+
 #[cfg(test)]
 mod display_tests {
     use super::Error;
@@ -186,6 +304,9 @@ mod display_tests {
         payment_request.set_public_address(public_address);
         payment_request.set_value(10);
         payment_request.set_memo("Please me pay!".to_string());
+        payment_request.set_token_id(1);
+        payment_request.set_payment_request_id(42);
+        payment_request.set_expiry(1_700_000_000);
 
         let mut wrapper = PrintableWrapper::new();
         wrapper.set_payment_request(payment_request);
@@ -194,6 +315,78 @@ mod display_tests {
         assert_eq!(wrapper, decoded);
     }
 
+    #[test]
+    fn test_payment_request_uri_roundtrip() {
+        let mut payment_request = PaymentRequest::new();
+        payment_request.set_public_address(sample_public_address());
+        payment_request.set_value(10);
+        payment_request.set_memo("Pay me & 100% of it, ☕?".to_string());
+        payment_request.set_token_id(1);
+        payment_request.set_payment_request_id(42);
+        payment_request.set_expiry(1_700_000_000);
+
+        let uri = payment_request.uri_encode().unwrap();
+        assert!(uri.starts_with("mob:"));
+        assert!(uri.contains("amount=10&token_id=1&payment_request_id=42&memo="));
+        assert!(uri.ends_with("&expiry=1700000000"));
+        assert_eq!(PaymentRequest::uri_decode(&uri).unwrap(), payment_request);
+
+        // A request without parameters is just the address.
+        let mut address_only = PaymentRequest::new();
+        address_only.set_public_address(sample_public_address());
+        let uri = address_only.uri_encode().unwrap();
+        assert!(!uri.contains('?'));
+        assert_eq!(PaymentRequest::uri_decode(&uri).unwrap(), address_only);
+
+        // Unknown parameters are ignored.
+        let uri = format!("{}?amount=5&label=shop", uri);
+        assert_eq!(PaymentRequest::uri_decode(&uri).unwrap().get_value(), 5);
+    }
+
+    #[test]
+    fn test_payment_request_uri_errors() {
+        let mut wrapper = PrintableWrapper::new();
+        wrapper.set_public_address(sample_public_address());
+        let b58_code = wrapper.b58_encode().unwrap();
+
+        assert_eq!(
+            PaymentRequest::uri_decode(&format!("bitcoin:{}", b58_code)).err(),
+            Some(Error::UriScheme)
+        );
+        assert_eq!(
+            PaymentRequest::uri_decode(&b58_code).err(),
+            Some(Error::UriScheme)
+        );
+        assert!(matches!(
+            PaymentRequest::uri_decode(&format!("mob:{}?amount=ten", b58_code)),
+            Err(Error::UriParameter(_))
+        ));
+        assert!(matches!(
+            PaymentRequest::uri_decode(&format!("mob:{}?amount=1&amount=2", b58_code)),
+            Err(Error::UriParameter(_))
+        ));
+
+        let mut payment_request = PaymentRequest::new();
+        payment_request.set_public_address(sample_public_address());
+        let mut wrapper = PrintableWrapper::new();
+        wrapper.set_payment_request(payment_request);
+        let uri = format!("mob:{}", wrapper.b58_encode().unwrap());
+        assert_eq!(
+            PaymentRequest::uri_decode(&uri).err(),
+            Some(Error::UriPublicAddress)
+        );
+    }
+
+    #[test]
+    fn test_payment_request_expiry() {
+        let mut payment_request = PaymentRequest::new();
+        assert!(!payment_request.is_expired(u64::MAX));
+
+        payment_request.set_expiry(100);
+        assert!(!payment_request.is_expired(99));
+        assert!(payment_request.is_expired(100));
+    }
+
     #[test]
     fn test_transfer_payload_roundtrip() {
         let mut transfer_payload = TransferPayload::new();
@@ -67,6 +67,33 @@ impl From<Error> for ProposeTxResult {
             Error::InputRule(InputRuleError::MaxTombstoneBlockExceeded) => {
                 Self::InputRuleMaxTombstoneBlockExceeded
             }
+            Error::InputRule(InputRuleError::PartialFillRulesNotAllowed) => {
+                Self::InputRulePartialFillRulesNotAllowed
+            }
+            Error::InputRule(InputRuleError::MissingPartialFillChange) => {
+                Self::InputRuleMissingPartialFillChange
+            }
+            Error::InputRule(InputRuleError::ZeroPartialFillChange) => {
+                Self::InputRuleZeroPartialFillChange
+            }
+            Error::InputRule(InputRuleError::InvalidRevealedTxOut) => {
+                Self::InputRuleInvalidRevealedTxOut
+            }
+            Error::InputRule(InputRuleError::MissingFractionalOutput) => {
+                Self::InputRuleMissingFractionalOutput
+            }
+            Error::InputRule(InputRuleError::InvalidFractionalOutput) => {
+                Self::InputRuleInvalidFractionalOutput
+            }
+            Error::InputRule(InputRuleError::FractionalChangeExceedsPartialFillChange) => {
+                Self::InputRuleFractionalChangeExceedsPartialFillChange
+            }
+            Error::InputRule(InputRuleError::MinPartialFillValueNotReached) => {
+                Self::InputRuleMinPartialFillValueNotReached
+            }
+            Error::InputRule(InputRuleError::FractionalOutputValueTooLow) => {
+                Self::InputRuleFractionalOutputValueTooLow
+            }
         }
     }
 }
@@ -123,6 +150,33 @@ impl TryInto<Error> for ProposeTxResult {
             Self::InputRuleMaxTombstoneBlockExceeded => {
                 Ok(Error::InputRule(InputRuleError::MaxTombstoneBlockExceeded))
             }
+            Self::InputRulePartialFillRulesNotAllowed => {
+                Ok(Error::InputRule(InputRuleError::PartialFillRulesNotAllowed))
+            }
+            Self::InputRuleMissingPartialFillChange => {
+                Ok(Error::InputRule(InputRuleError::MissingPartialFillChange))
+            }
+            Self::InputRuleZeroPartialFillChange => {
+                Ok(Error::InputRule(InputRuleError::ZeroPartialFillChange))
+            }
+            Self::InputRuleInvalidRevealedTxOut => {
+                Ok(Error::InputRule(InputRuleError::InvalidRevealedTxOut))
+            }
+            Self::InputRuleMissingFractionalOutput => {
+                Ok(Error::InputRule(InputRuleError::MissingFractionalOutput))
+            }
+            Self::InputRuleInvalidFractionalOutput => {
+                Ok(Error::InputRule(InputRuleError::InvalidFractionalOutput))
+            }
+            Self::InputRuleFractionalChangeExceedsPartialFillChange => Ok(Error::InputRule(
+                InputRuleError::FractionalChangeExceedsPartialFillChange,
+            )),
+            Self::InputRuleMinPartialFillValueNotReached => Ok(Error::InputRule(
+                InputRuleError::MinPartialFillValueNotReached,
+            )),
+            Self::InputRuleFractionalOutputValueTooLow => Ok(Error::InputRule(
+                InputRuleError::FractionalOutputValueTooLow,
+            )),
         }
     }
 }
@@ -183,6 +237,24 @@ impl From<MintValidationError> for MintValidationResult {
                 code: MintValidationResultCode::NoMatchingMintConfig,
                 ..Default::default()
             },
+            MintValidationError::NestedSignerSetsNotSupported(block_version) => Self {
+                code: MintValidationResultCode::NestedSignerSetsNotSupported,
+                block_version: *block_version,
+                ..Default::default()
+            },
+            MintValidationError::MintLimitWindowsNotSupported(block_version) => Self {
+                code: MintValidationResultCode::MintLimitWindowsNotSupported,
+                block_version: *block_version,
+                ..Default::default()
+            },
+            MintValidationError::InvalidMintLimitWindow => Self {
+                code: MintValidationResultCode::InvalidMintLimitWindow,
+                ..Default::default()
+            },
+            MintValidationError::AmountExceedsMintLimitWindow => Self {
+                code: MintValidationResultCode::AmountExceedsMintLimitWindow,
+                ..Default::default()
+            },
         }
     }
 }
@@ -226,6 +298,22 @@ impl TryInto<MintValidationError> for MintValidationResult {
             MintValidationResultCode::NoMatchingMintConfig => {
                 Ok(MintValidationError::NoMatchingMintConfig)
             }
+            MintValidationResultCode::NestedSignerSetsNotSupported => {
+                Ok(MintValidationError::NestedSignerSetsNotSupported(
+                    BlockVersion::try_from(self.block_version).map_err(|err| err.to_string())?,
+                ))
+            }
+            MintValidationResultCode::MintLimitWindowsNotSupported => {
+                Ok(MintValidationError::MintLimitWindowsNotSupported(
+                    BlockVersion::try_from(self.block_version).map_err(|err| err.to_string())?,
+                ))
+            }
+            MintValidationResultCode::InvalidMintLimitWindow => {
+                Ok(MintValidationError::InvalidMintLimitWindow)
+            }
+            MintValidationResultCode::AmountExceedsMintLimitWindow => {
+                Ok(MintValidationError::AmountExceedsMintLimitWindow)
+            }
         }
     }
 }
@@ -262,6 +350,7 @@ impl From<&mc_ledger_db::ActiveMintConfigs> for consensus_config::ActiveMintConf
         let mut dst = Self::new();
         dst.set_configs(src.configs.iter().map(|config| config.into()).collect());
         dst.set_mint_config_tx((&src.mint_config_tx).into());
+        dst.set_recent_mints(src.recent_mints.iter().map(|mint| mint.into()).collect());
         dst
     }
 }
@@ -278,18 +367,58 @@ impl TryFrom<&consensus_config::ActiveMintConfigs> for mc_ledger_db::ActiveMintC
             .map(TryInto::try_into)
             .collect::<Result<Vec<_>, _>>()?;
         let mint_config_tx = src.get_mint_config_tx().try_into()?;
+        let recent_mints = src
+            .get_recent_mints()
+            .iter()
+            .map(mc_ledger_db::RecentMint::from)
+            .collect();
         Ok(Self {
             configs,
             mint_config_tx,
+            recent_mints,
         })
     }
 }
 
+/// Convert mc_ledger_db::RecentMint --> consensus_config::RecentMint
+impl From<&mc_ledger_db::RecentMint> for consensus_config::RecentMint {
+    fn from(src: &mc_ledger_db::RecentMint) -> Self {
+        let mut dst = Self::new();
+        dst.set_config_index(src.config_index);
+        dst.set_block_index(src.block_index);
+        dst.set_amount(src.amount);
+        dst
+    }
+}
+
+/// Convert consensus_config::RecentMint --> mc_ledger_db::RecentMint
+impl From<&consensus_config::RecentMint> for mc_ledger_db::RecentMint {
+    fn from(src: &consensus_config::RecentMint) -> Self {
+        Self {
+            config_index: src.get_config_index(),
+            block_index: src.get_block_index(),
+            amount: src.get_amount(),
+        }
+    }
+}
+
+/// Convert mc_ledger_db::MintLimitWindowCapacity -->
+/// consensus_config::MintLimitWindowCapacity
+impl From<&mc_ledger_db::MintLimitWindowCapacity> for consensus_config::MintLimitWindowCapacity {
+    fn from(src: &mc_ledger_db::MintLimitWindowCapacity) -> Self {
+        let mut dst = Self::new();
+        dst.set_window((&src.window).into());
+        dst.set_minted(src.minted);
+        dst.set_remaining(src.remaining);
+        dst
+    }
+}
+
 #[cfg(test)]
 mod conversion_tests {
     use super::*;
     use mc_crypto_multisig::SignerSet;
-    use mc_transaction_core::mint::MintConfig;
+    use mc_transaction_core::mint::{MintConfig, MintLimitWindow};
     use mc_transaction_core_test_utils::create_mint_config_tx_and_signers;
     use mc_util_serial::{decode, encode};
     use protobuf::Message;
@@ -307,6 +436,7 @@ mod conversion_tests {
                 token_id: 123,
                 signer_set,
                 mint_limit: 10000,
+                mint_limit_windows: vec![],
             },
             total_minted: 102,
         };
@@ -356,10 +486,19 @@ mod conversion_tests {
                     token_id: 123,
                     signer_set,
                     mint_limit: 10000,
+                    mint_limit_windows: vec![MintLimitWindow {
+                        num_blocks: 100,
+                        limit: 1000,
+                    }],
                 },
                 total_minted: 102,
             }],
             mint_config_tx,
+            recent_mints: vec![mc_ledger_db::RecentMint {
+                config_index: 0,
+                block_index: 5,
+                amount: 102,
+            }],
         };
 
         // decode(encode(source)) should be the identity function.
@@ -221,6 +221,10 @@ pub struct TxContext {
 /// local enclave
 pub type SealedBlockSigningKey = Vec<u8>;
 
+/// A type alias for the SGX sealed version of a well-formed transaction, which
+/// can be persisted outside of the enclave and survives enclave restarts.
+pub type SealedTx = Vec<u8>;
+
 /// PublicAddress is not serializable with serde currently, and rather than
 /// pollute dependencies, we simply pass the View and Spend public keys as
 /// RistrettoPublic.
@@ -339,6 +343,17 @@ pub trait ConsensusEnclave: ReportableEnclave {
         peer: &PeerSession,
     ) -> Result<EnclaveMessage<PeerSession>>;
 
+    /// Seal a well-formed transaction so that it can be persisted outside of
+    /// the enclave, and reloaded by a later instance of the enclave.
+    fn seal_well_formed_tx(&self, encrypted_tx: &WellFormedEncryptedTx) -> Result<SealedTx>;
+
+    /// Unseal a transaction sealed by `seal_well_formed_tx`, re-encrypting it
+    /// for the local enclave.
+    ///
+    /// The returned context must go through `tx_is_well_formed` again, since
+    /// the ledger may have changed since the transaction was sealed.
+    fn unseal_tx(&self, sealed_tx: SealedTx) -> Result<TxContext>;
+
     /// Redact txs in order to form a new block.
     /// Returns a block, the block contents, and a signature over the block's
     /// digest.
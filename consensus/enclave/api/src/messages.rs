@@ -4,7 +4,7 @@
 
 use crate::{
     BlockchainConfig, FormBlockInputs, LocallyEncryptedTx, ResponderId, SealedBlockSigningKey,
-    WellFormedEncryptedTx,
+    SealedTx, WellFormedEncryptedTx,
 };
 use alloc::vec::Vec;
 use mc_attest_core::{Quote, Report, TargetInfo, VerificationReport};
@@ -134,6 +134,16 @@ pub enum EnclaveCall {
     /// Re-encrypt the given transactions for transmission to a peer.
     TxsForPeer(Vec<WellFormedEncryptedTx>, Vec<u8>, PeerSession),
 
+    /// The [ConsensusEnclave::seal_well_formed_tx()] method.
+    ///
+    /// Seal a well-formed transaction so that it can be persisted.
+    SealWellFormedTx(WellFormedEncryptedTx),
+
+    /// The [ConsensusEnclave::unseal_tx()] method.
+    ///
+    /// Unseal a persisted transaction and re-encrypt it for the local enclave.
+    UnsealTx(SealedTx),
+
     /// The [ConsensusEnclave::form_block()] method.
     ///
     /// Converts a list of inputs into a block, block contents and a signature.
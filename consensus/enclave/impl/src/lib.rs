@@ -48,7 +48,7 @@ use mc_common::{
 use mc_consensus_enclave_api::{
     BlockchainConfig, BlockchainConfigWithDigest, ConsensusEnclave, Error, FeeMap, FeePublicKey,
     FormBlockInputs, GovernorsVerifier, LocallyEncryptedTx, Result, SealedBlockSigningKey,
-    TxContext, WellFormedEncryptedTx, WellFormedTxContext, SMALLEST_MINIMUM_FEE_LOG2,
+    SealedTx, TxContext, WellFormedEncryptedTx, WellFormedTxContext, SMALLEST_MINIMUM_FEE_LOG2,
 };
 use mc_crypto_ake_enclave::AkeEnclaveState;
 use mc_crypto_digestible::{DigestTranscript, Digestible, MerlinTranscript};
@@ -766,6 +766,46 @@ impl ConsensusEnclave for SgxConsensusEnclave {
         Ok(self.ake.peer_encrypt(peer, aad, &serialized_txs)?)
     }
 
+    fn seal_well_formed_tx(&self, encrypted_tx: &WellFormedEncryptedTx) -> Result<SealedTx> {
+        let well_formed_tx = self.decrypt_well_formed_tx(encrypted_tx)?;
+        let tx_bytes = mc_util_serial::encode(well_formed_tx.tx());
+
+        // Seal the tx, so that it remains readable by this enclave after a restart.
+        let sealed = IntelSealed::seal_raw(&tx_bytes, &[])?;
+
+        Ok(sealed.as_ref().to_vec())
+    }
+
+    fn unseal_tx(&self, sealed_tx: SealedTx) -> Result<TxContext> {
+        let sealed = IntelSealed::try_from(sealed_tx)?;
+        let (tx_bytes, _mac) = sealed.unseal_raw()?;
+
+        // Try and deserialize.
+        let tx: Tx = mc_util_serial::decode(&tx_bytes)?;
+
+        // Convert to TxContext
+        let maybe_locally_encrypted_tx: Result<LocallyEncryptedTx> = {
+            let mut cipher = self.locally_encrypted_tx_cipher.lock()?;
+            let mut rng = McRng::default();
+
+            Ok(LocallyEncryptedTx(cipher.encrypt_bytes(&mut rng, tx_bytes)))
+        };
+        let locally_encrypted_tx = maybe_locally_encrypted_tx?;
+
+        let tx_hash = tx.tx_hash();
+        let highest_indices = tx.get_membership_proof_highest_indices();
+        let key_images: Vec<KeyImage> = tx.key_images();
+        let output_public_keys = tx.output_public_keys();
+
+        Ok(TxContext {
+            locally_encrypted_tx,
+            tx_hash,
+            highest_indices,
+            key_images,
+            output_public_keys,
+        })
+    }
+
     fn form_block(
         &self,
         parent_block: &Block,
@@ -8,7 +8,7 @@ pub use mock_consensus_enclave::MockConsensusEnclave;
 
 pub use mc_consensus_enclave_api::{
     BlockchainConfig, ConsensusEnclave, ConsensusEnclaveProxy, Error, FeePublicKey,
-    FormBlockInputs, LocallyEncryptedTx, Result, SealedBlockSigningKey, TxContext,
+    FormBlockInputs, LocallyEncryptedTx, Result, SealedBlockSigningKey, SealedTx, TxContext,
     WellFormedEncryptedTx, WellFormedTxContext,
 };
 
@@ -227,6 +227,23 @@ impl ConsensusEnclave for ConsensusServiceMockEnclave {
         Ok(EnclaveMessage::default())
     }
 
+    fn seal_well_formed_tx(&self, encrypted_tx: &WellFormedEncryptedTx) -> Result<SealedTx> {
+        // The "encrypted" tx is just a serialized Tx, and is "sealed" as-is.
+        Ok(encrypted_tx.0.clone())
+    }
+
+    fn unseal_tx(&self, sealed_tx: SealedTx) -> Result<TxContext> {
+        let tx: Tx = mc_util_serial::decode(&sealed_tx)?;
+
+        Ok(TxContext {
+            tx_hash: tx.tx_hash(),
+            highest_indices: tx.get_membership_proof_highest_indices(),
+            key_images: tx.key_images(),
+            output_public_keys: tx.output_public_keys(),
+            locally_encrypted_tx: LocallyEncryptedTx(sealed_tx),
+        })
+    }
+
     fn form_block(
         &self,
         parent_block: &Block,
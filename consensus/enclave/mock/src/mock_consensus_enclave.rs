@@ -10,8 +10,8 @@ use mc_blockchain_types::{Block, BlockContents, BlockSignature};
 use mc_common::ResponderId;
 use mc_consensus_enclave_api::{
     BlockchainConfig, ConsensusEnclave, FeePublicKey, FormBlockInputs, LocallyEncryptedTx,
-    Result as ConsensusEnclaveResult, SealedBlockSigningKey, TxContext, WellFormedEncryptedTx,
-    WellFormedTxContext,
+    Result as ConsensusEnclaveResult, SealedBlockSigningKey, SealedTx, TxContext,
+    WellFormedEncryptedTx, WellFormedTxContext,
 };
 use mc_crypto_keys::{Ed25519Public, X25519Public};
 use mc_sgx_report_cache_api::{ReportableEnclave, Result as SgxReportResult};
@@ -79,6 +79,10 @@ mock! {
             peer: &PeerSession,
         ) -> ConsensusEnclaveResult<EnclaveMessage<PeerSession>>;
 
+        fn seal_well_formed_tx(&self, encrypted_tx: &WellFormedEncryptedTx) -> ConsensusEnclaveResult<SealedTx>;
+
+        fn unseal_tx(&self, sealed_tx: SealedTx) -> ConsensusEnclaveResult<TxContext>;
+
         fn form_block(
             &self,
             parent_block: &Block,
@@ -4,7 +4,7 @@
 
 pub use mc_consensus_enclave_api::{
     BlockchainConfig, ConsensusEnclave, ConsensusEnclaveProxy, EnclaveCall, Error, FeeMap,
-    FeeMapError, FeePublicKey, FormBlockInputs, GovernorsMap, LocallyEncryptedTx, Result,
+    FeeMapError, FeePublicKey, FormBlockInputs, GovernorsMap, LocallyEncryptedTx, Result, SealedTx,
     TxContext, WellFormedEncryptedTx, WellFormedTxContext,
 };
 
@@ -255,6 +255,19 @@ impl ConsensusEnclave for ConsensusServiceSgxEnclave {
         mc_util_serial::deserialize(&outbuf[..])?
     }
 
+    fn seal_well_formed_tx(&self, encrypted_tx: &WellFormedEncryptedTx) -> Result<SealedTx> {
+        let inbuf =
+            mc_util_serial::serialize(&EnclaveCall::SealWellFormedTx(encrypted_tx.clone()))?;
+        let outbuf = self.enclave_call(&inbuf)?;
+        mc_util_serial::deserialize(&outbuf[..])?
+    }
+
+    fn unseal_tx(&self, sealed_tx: SealedTx) -> Result<TxContext> {
+        let inbuf = mc_util_serial::serialize(&EnclaveCall::UnsealTx(sealed_tx))?;
+        let outbuf = self.enclave_call(&inbuf)?;
+        mc_util_serial::deserialize(&outbuf[..])?
+    }
+
     fn form_block(
         &self,
         parent_block: &Block,
@@ -75,6 +75,10 @@ pub fn ecall_dispatcher(inbuf: &[u8]) -> Result<Vec<u8>, sgx_status_t> {
         EnclaveCall::TxsForPeer(txs, aad, peer) => {
             serialize(&ENCLAVE.txs_for_peer(&txs, &aad, &peer))
         }
+        EnclaveCall::SealWellFormedTx(encrypted_tx) => {
+            serialize(&ENCLAVE.seal_well_formed_tx(&encrypted_tx))
+        }
+        EnclaveCall::UnsealTx(sealed_tx) => serialize(&ENCLAVE.unseal_tx(sealed_tx)),
         EnclaveCall::FormBlock(parent_block, inputs, root_element) => {
             serialize(&ENCLAVE.form_block(&parent_block, inputs, &root_element))
         }
@@ -0,0 +1,85 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! A dashboard reporting on the health of a consensus network.
+
+use clap::Parser;
+use mc_common::logger::{create_app_logger, log, o};
+use mc_consensus_health_dashboard::{
+    config::HealthDashboardConfig, monitor::HealthMonitor, network_health::NetworkHealthReport,
+};
+use mc_util_grpc::AdminServer;
+use rocket::{get, response::content, routes, serde::json::Json};
+
+struct State {
+    pub monitor: HealthMonitor,
+}
+
+#[get("/")]
+fn index() -> content::RawHtml<String> {
+    content::RawHtml(include_str!("../../templates/index.html").to_owned())
+}
+
+#[get("/status")]
+fn status(state: &rocket::State<State>) -> Json<NetworkHealthReport> {
+    Json(state.monitor.report())
+}
+
+#[get("/metrics")]
+fn metrics() -> Result<String, String> {
+    let metric_families = prometheus::gather();
+    let encoder = prometheus::TextEncoder::new();
+    let mut buffer = vec![];
+    prometheus::Encoder::encode(&encoder, &metric_families, &mut buffer)
+        .map_err(|err| format!("failed encoding metrics: {}", err))?;
+    String::from_utf8(buffer).map_err(|err| format!("from_utf8 failed: {}", err))
+}
+
+#[rocket::main]
+async fn main() -> Result<(), rocket::Error> {
+    mc_common::setup_panic_handler();
+    let _sentry_guard = mc_common::sentry::init();
+
+    let config = HealthDashboardConfig::parse();
+
+    let (logger, _global_logger_guard) = create_app_logger(o!());
+    log::info!(
+        logger,
+        "Starting consensus health dashboard on {}:{}",
+        config.listen_host,
+        config.listen_port,
+    );
+
+    let nodes_config = config.nodes_config();
+    let monitor = HealthMonitor::start(
+        &nodes_config,
+        config.poll_interval,
+        config.poll_timeout,
+        config.stuck_threshold,
+        config.lagging_blocks_threshold,
+        logger.clone(),
+    );
+
+    let _admin_server = config.admin_listen_uri.as_ref().map(|admin_listen_uri| {
+        AdminServer::start(
+            None,
+            admin_listen_uri,
+            "Consensus Health Dashboard".to_owned(),
+            admin_listen_uri.to_string(),
+            None,
+            logger.clone(),
+        )
+        .expect("Failed starting admin server")
+    });
+
+    let figment = rocket::Config::figment()
+        .merge(("port", config.listen_port))
+        .merge(("address", config.listen_host.clone()));
+
+    let _rocket = rocket::custom(figment)
+        .mount("/", routes![index, status, metrics])
+        .manage(State { monitor })
+        .launch()
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,124 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! Configuration parameters for the consensus health dashboard.
+
+use clap::Parser;
+use mc_util_parse::parse_duration_in_seconds;
+use mc_util_uri::{AdminUri, ConsensusPeerUri};
+use serde::{Deserialize, Serialize};
+use std::{fs, path::PathBuf, time::Duration};
+
+/// Configuration for the consensus health dashboard.
+#[derive(Clone, Debug, Parser)]
+#[clap(
+    name = "mc-consensus-health-dashboard",
+    about = "Aggregate SCP state across consensus nodes and report on network health."
+)]
+pub struct HealthDashboardConfig {
+    /// The location of the nodes.toml file. This file configures the list of
+    /// consensus nodes that are being monitored.
+    #[clap(long, env = "MC_NODES_PATH")]
+    pub nodes_path: PathBuf,
+
+    /// How many seconds to wait between polling.
+    #[clap(long, default_value = "1", parse(try_from_str = parse_duration_in_seconds), env = "MC_POLL_INTERVAL")]
+    pub poll_interval: Duration,
+
+    /// How many seconds to wait for a node to answer each request before
+    /// considering it unreachable.
+    #[clap(long, default_value = "5", parse(try_from_str = parse_duration_in_seconds), env = "MC_POLL_TIMEOUT")]
+    pub poll_timeout: Duration,
+
+    /// A node that has been working on the same slot for this many seconds is
+    /// considered stuck.
+    #[clap(long, default_value = "30", parse(try_from_str = parse_duration_in_seconds), env = "MC_STUCK_THRESHOLD")]
+    pub stuck_threshold: Duration,
+
+    /// A node whose ledger is this many blocks behind the highest ledger in
+    /// the network is considered lagging.
+    #[clap(long, default_value = "2", env = "MC_LAGGING_BLOCKS_THRESHOLD")]
+    pub lagging_blocks_threshold: u64,
+
+    /// Host to serve the HTTP status page on.
+    #[clap(long, default_value = "127.0.0.1", env = "MC_LISTEN_HOST")]
+    pub listen_host: String,
+
+    /// Port to serve the HTTP status page on.
+    #[clap(long, default_value = "9091", env = "MC_LISTEN_PORT")]
+    pub listen_port: u16,
+
+    /// Optional admin service listening URI.
+    #[clap(long, env = "MC_ADMIN_LISTEN_URI")]
+    pub admin_listen_uri: Option<AdminUri>,
+}
+
+impl HealthDashboardConfig {
+    /// Load the nodes configuration file.
+    pub fn nodes_config(&self) -> NodesConfig {
+        // Read configuration file.
+        let data = fs::read_to_string(&self.nodes_path)
+            .unwrap_or_else(|err| panic!("failed reading {:?}: {:?}", self.nodes_path, err));
+
+        // Parse configuration file.
+        toml::from_str(&data)
+            .unwrap_or_else(|err| panic!("failed TOML parsing {:?}: {:?}", self.nodes_path, err))
+    }
+}
+
+/// A single monitored consensus node.
+#[derive(Clone, Debug, Deserialize, Serialize, Eq, PartialEq, Hash)]
+pub struct NodeConfig {
+    /// Peer URI of the node, used for fetching its latest SCP message and
+    /// last block info.
+    pub peer_uri: ConsensusPeerUri,
+
+    /// (Optional) Admin URI of the node, used for fetching its sync status.
+    pub admin_uri: Option<AdminUri>,
+}
+
+/// Monitored nodes configuration.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct NodesConfig {
+    /// List of monitored nodes.
+    pub nodes: Vec<NodeConfig>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn nodes_config_from_toml() {
+        let nodes: NodesConfig = toml::from_str(
+            r#"
+            [[nodes]]
+            peer_uri = "mcp://node1.test.mobilecoin.com/"
+            admin_uri = "insecure-mca://node1.test.mobilecoin.com:9090/"
+
+            [[nodes]]
+            peer_uri = "mcp://node2.test.mobilecoin.com/"
+        "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            nodes.nodes,
+            vec![
+                NodeConfig {
+                    peer_uri: ConsensusPeerUri::from_str("mcp://node1.test.mobilecoin.com/")
+                        .unwrap(),
+                    admin_uri: Some(
+                        AdminUri::from_str("insecure-mca://node1.test.mobilecoin.com:9090/")
+                            .unwrap()
+                    ),
+                },
+                NodeConfig {
+                    peer_uri: ConsensusPeerUri::from_str("mcp://node2.test.mobilecoin.com/")
+                        .unwrap(),
+                    admin_uri: None,
+                },
+            ]
+        );
+    }
+}
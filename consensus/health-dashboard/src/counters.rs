@@ -0,0 +1,65 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! Prometheus counters.
+
+use crate::network_health::NetworkHealthReport;
+use mc_util_metrics::{IntGauge, OpMetrics};
+
+lazy_static::lazy_static! {
+    /// Prometheus counters.
+    pub static ref OP_COUNTERS: OpMetrics = OpMetrics::new_and_registered("consensus_health");
+
+    /// Highest number of blocks seen in any node's ledger.
+    pub static ref HIGHEST_NUM_BLOCKS: IntGauge = OP_COUNTERS.gauge("highest_num_blocks");
+
+    /// Number of healthy nodes.
+    pub static ref HEALTHY_NODES: IntGauge = OP_COUNTERS.gauge("healthy_nodes");
+}
+
+/// Publish a health report as Prometheus metrics.
+pub fn publish(report: &NetworkHealthReport) {
+    HIGHEST_NUM_BLOCKS.set(report.highest_num_blocks as i64);
+    HEALTHY_NODES.set(report.healthy_nodes as i64);
+
+    for node in report.nodes.iter() {
+        let peer = node.responder_id.as_str();
+        OP_COUNTERS
+            .peer_gauge("status", peer)
+            .set(node.status.as_metric());
+        OP_COUNTERS
+            .peer_gauge("blocks_behind", peer)
+            .set(node.blocks_behind as i64);
+        OP_COUNTERS
+            .peer_gauge("nomination_rounds", peer)
+            .set(node.nomination_rounds as i64);
+        OP_COUNTERS
+            .peer_gauge("ballot_counter_growth", peer)
+            .set(node.ballot_counter_growth as i64);
+        if let Some(num_blocks) = node.num_blocks {
+            OP_COUNTERS
+                .peer_gauge("num_blocks", peer)
+                .set(num_blocks as i64);
+        }
+        if let Some(scp) = node.scp.as_ref() {
+            OP_COUNTERS
+                .peer_gauge("slot_index", peer)
+                .set(scp.slot_index as i64);
+            OP_COUNTERS
+                .peer_gauge("ballot_counter", peer)
+                .set(scp.ballot_counter as i64);
+        }
+        OP_COUNTERS
+            .peer_gauge("current_slot_age_ms", peer)
+            .set(node.current_slot_age_ms.unwrap_or(0) as i64);
+        if let Some(last_slot_duration_ms) = node.last_slot_duration_ms {
+            OP_COUNTERS
+                .peer_gauge("last_slot_duration_ms", peer)
+                .set(last_slot_duration_ms as i64);
+        }
+        if let Some(peers) = node.peers.as_ref() {
+            OP_COUNTERS
+                .peer_gauge("disconnected_peers", peer)
+                .set(peers.iter().filter(|status| !status.connected).count() as i64);
+        }
+    }
+}
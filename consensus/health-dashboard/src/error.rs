@@ -0,0 +1,43 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! Error data types
+
+use displaydoc::Display;
+use mc_util_serial::decode::Error as DecodeError;
+
+/// Errors encountered while polling a node.
+#[derive(Debug, Display)]
+pub enum Error {
+    /// gRPC: {0}
+    Grpc(grpcio::Error),
+
+    /// Failed decoding consensus message: {0}
+    Decode(DecodeError),
+
+    /// Failed parsing admin config JSON: {0}
+    Json(serde_json::Error),
+
+    /// Failed spawning poller thread: {0}
+    Spawn(std::io::Error),
+
+    /// Poller thread panicked
+    PollerPanicked,
+}
+
+impl From<grpcio::Error> for Error {
+    fn from(src: grpcio::Error) -> Self {
+        Self::Grpc(src)
+    }
+}
+
+impl From<DecodeError> for Error {
+    fn from(src: DecodeError) -> Self {
+        Self::Decode(src)
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(src: serde_json::Error) -> Self {
+        Self::Json(src)
+    }
+}
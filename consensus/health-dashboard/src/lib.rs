@@ -0,0 +1,12 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+#![doc = include_str!("../README.md")]
+#![deny(missing_docs)]
+#![forbid(unsafe_code)]
+
+pub mod config;
+pub mod counters;
+pub mod error;
+pub mod monitor;
+pub mod network_health;
+pub mod node_poller;
@@ -0,0 +1,184 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! Background thread that periodically polls all monitored nodes.
+
+use crate::{
+    config::NodesConfig,
+    counters,
+    error::Error,
+    network_health::{NetworkHealth, NetworkHealthReport, NodeObservation},
+    node_poller::NodePoller,
+};
+use grpcio::EnvBuilder;
+use mc_common::logger::{log, Logger};
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Periodically polls the monitored nodes and keeps an up-to-date
+/// [NetworkHealthReport].
+pub struct HealthMonitor {
+    join_handle: Option<thread::JoinHandle<()>>,
+    stop_requested: Arc<AtomicBool>,
+    report: Arc<Mutex<NetworkHealthReport>>,
+}
+
+impl HealthMonitor {
+    /// Start polling.
+    ///
+    /// # Arguments
+    /// * `nodes_config` - The nodes to monitor.
+    /// * `poll_interval` - Time between polls.
+    /// * `poll_timeout` - Deadline for each request made to a node.
+    /// * `stuck_threshold` - How long a node may work on a slot before it is
+    ///   considered stuck.
+    /// * `lagging_blocks_threshold` - How many blocks a node may be behind
+    ///   before it is considered lagging.
+    /// * `logger` - Logger.
+    pub fn start(
+        nodes_config: &NodesConfig,
+        poll_interval: Duration,
+        poll_timeout: Duration,
+        stuck_threshold: Duration,
+        lagging_blocks_threshold: u64,
+        logger: Logger,
+    ) -> Self {
+        let env = Arc::new(
+            EnvBuilder::new()
+                .name_prefix("HealthMonitor-RPC".to_string())
+                .build(),
+        );
+        let pollers: Vec<Arc<NodePoller>> = nodes_config
+            .nodes
+            .iter()
+            .map(|node_config| {
+                Arc::new(NodePoller::new(
+                    node_config,
+                    poll_timeout,
+                    env.clone(),
+                    &logger,
+                ))
+            })
+            .collect();
+
+        let network_health = NetworkHealth::new(
+            pollers
+                .iter()
+                .map(|poller| poller.responder_id().to_string())
+                .collect(),
+            stuck_threshold,
+            lagging_blocks_threshold,
+        );
+        let report = Arc::new(Mutex::new(network_health.report()));
+
+        let stop_requested = Arc::new(AtomicBool::new(false));
+        let thread_stop_requested = stop_requested.clone();
+        let thread_report = report.clone();
+        let join_handle = Some(
+            thread::Builder::new()
+                .name("HealthMonitor".into())
+                .spawn(move || {
+                    Self::thread_entrypoint(
+                        pollers,
+                        network_health,
+                        poll_interval,
+                        thread_stop_requested,
+                        thread_report,
+                        logger,
+                    )
+                })
+                .expect("Failed spawning HealthMonitor thread"),
+        );
+
+        Self {
+            join_handle,
+            stop_requested,
+            report,
+        }
+    }
+
+    /// Get the most recent health report.
+    pub fn report(&self) -> NetworkHealthReport {
+        self.report.lock().expect("mutex poisoned").clone()
+    }
+
+    /// Stop polling.
+    pub fn stop(&mut self) {
+        self.stop_requested.store(true, Ordering::SeqCst);
+        if let Some(thread) = self.join_handle.take() {
+            thread.join().expect("HealthMonitor thread join failed");
+        }
+    }
+
+    fn thread_entrypoint(
+        pollers: Vec<Arc<NodePoller>>,
+        mut network_health: NetworkHealth,
+        poll_interval: Duration,
+        stop_requested: Arc<AtomicBool>,
+        report: Arc<Mutex<NetworkHealthReport>>,
+        logger: Logger,
+    ) {
+        log::debug!(logger, "HealthMonitor thread has started.");
+
+        loop {
+            if stop_requested.load(Ordering::SeqCst) {
+                log::debug!(logger, "HealthMonitor stop requested.");
+                break;
+            }
+
+            let results = Self::poll_all(&pollers, &logger);
+            network_health.update(results, Instant::now());
+
+            let new_report = network_health.report();
+            counters::publish(&new_report);
+            *report.lock().expect("mutex poisoned") = new_report;
+
+            thread::sleep(poll_interval);
+        }
+    }
+
+    /// Poll all nodes concurrently, so that a slow node does not delay
+    /// observing the others. Results are returned in polling order.
+    fn poll_all(
+        pollers: &[Arc<NodePoller>],
+        logger: &Logger,
+    ) -> Vec<Result<NodeObservation, String>> {
+        let join_handles: Vec<_> = pollers
+            .iter()
+            .map(|poller| {
+                let poller = poller.clone();
+                thread::Builder::new()
+                    .name(format!("HealthMonitor-{}", poller.responder_id()))
+                    .spawn(move || poller.poll())
+            })
+            .collect();
+
+        pollers
+            .iter()
+            .zip(join_handles)
+            .map(|(poller, join_handle)| {
+                let result = match join_handle {
+                    Ok(join_handle) => join_handle
+                        .join()
+                        .unwrap_or_else(|_| Err(Error::PollerPanicked)),
+                    Err(err) => Err(Error::Spawn(err)),
+                };
+                result.map_err(|err| {
+                    log::debug!(logger, "Failed polling {}: {}", poller.responder_id(), err);
+                    err.to_string()
+                })
+            })
+            .collect()
+    }
+}
+
+impl Drop for HealthMonitor {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
@@ -0,0 +1,594 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! Aggregates observations of individual consensus nodes into a view of the
+//! network's health.
+//!
+//! Nodes only expose their latest SCP message, so everything here is derived
+//! from periodic snapshots: slot timing is measured from the first poll that
+//! saw a node working on a slot until the first poll that saw it externalize,
+//! and nomination rounds are counted as the number of distinct nomination
+//! payloads (the sets of voted and accepted values, compared by hash) observed
+//! for a slot. Both are therefore only as accurate as the polling interval.
+
+use mc_consensus_scp::{msg::NominatePayload, Msg, Topic, Value};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{hash_map::DefaultHasher, HashSet},
+    hash::{Hash, Hasher},
+    time::{Duration, Instant},
+};
+
+/// The SCP phase a node is in.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+pub enum ScpPhase {
+    /// Nominating values.
+    Nominate,
+    /// Nominating values and preparing a ballot.
+    NominatePrepare,
+    /// Preparing a ballot.
+    Prepare,
+    /// Committing a ballot.
+    Commit,
+    /// The slot was externalized.
+    Externalize,
+}
+
+/// A summary of the latest SCP message issued by a node.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct ScpSnapshot {
+    /// The slot the message is about.
+    pub slot_index: u64,
+
+    /// The phase of the message.
+    pub phase: ScpPhase,
+
+    /// The counter of the node's current ballot, or 0 if the node is only
+    /// nominating.
+    pub ballot_counter: u32,
+
+    /// Number of values the node voted to nominate.
+    pub voted_values: usize,
+
+    /// Number of values the node accepted as nominated.
+    pub accepted_values: usize,
+
+    /// A hash of the voted and accepted values, if the node is nominating.
+    /// Used to tell nomination payloads apart.
+    #[serde(skip)]
+    pub nomination_hash: Option<u64>,
+}
+
+fn nomination_hash<V: Value>(nominate: &NominatePayload<V>) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    nominate.X.hash(&mut hasher);
+    nominate.Y.hash(&mut hasher);
+    hasher.finish()
+}
+
+impl<V: Value> From<&Msg<V>> for ScpSnapshot {
+    fn from(msg: &Msg<V>) -> Self {
+        let (phase, ballot_counter, nominate) = match &msg.topic {
+            Topic::Nominate(nominate) => (ScpPhase::Nominate, 0, Some(nominate)),
+            Topic::NominatePrepare(nominate, prepare) => {
+                (ScpPhase::NominatePrepare, prepare.B.N, Some(nominate))
+            }
+            Topic::Prepare(prepare) => (ScpPhase::Prepare, prepare.B.N, None),
+            Topic::Commit(commit) => (ScpPhase::Commit, commit.B.N, None),
+            Topic::Externalize(externalize) => (ScpPhase::Externalize, externalize.HN, None),
+        };
+
+        Self {
+            slot_index: msg.slot_index,
+            phase,
+            ballot_counter,
+            voted_values: nominate.map_or(0, |nominate| nominate.X.len()),
+            accepted_values: nominate.map_or(0, |nominate| nominate.Y.len()),
+            nomination_hash: nominate.map(nomination_hash),
+        }
+    }
+}
+
+/// The status of a node's connection to one of its peers, as reported by the
+/// node's admin API.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct PeerConnectionStatus {
+    /// The peer's responder id.
+    pub responder_id: String,
+
+    /// Seconds since the node last heard from the peer.
+    pub last_heard_secs: u64,
+
+    /// Whether the node heard from the peer recently.
+    pub connected: bool,
+}
+
+/// A single poll of a consensus node.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct NodeObservation {
+    /// Number of blocks in the node's ledger.
+    pub num_blocks: u64,
+
+    /// The node's latest SCP message, if it issued any.
+    pub scp: Option<ScpSnapshot>,
+
+    /// The sync status reported by the node's admin API, if available.
+    pub sync_status: Option<String>,
+
+    /// The status of the node's peer connections reported by the node's admin
+    /// API, if available.
+    pub peers: Option<Vec<PeerConnectionStatus>>,
+}
+
+/// Overall status of a node.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+pub enum NodeStatus {
+    /// Nothing unusual was observed.
+    Healthy,
+    /// The node's ledger is behind the rest of the network.
+    Lagging,
+    /// The node has been working on the same slot for too long.
+    Stuck,
+    /// The node could not be polled.
+    Unreachable,
+}
+
+impl NodeStatus {
+    /// A numeric representation, for reporting as a metric.
+    pub fn as_metric(&self) -> i64 {
+        match self {
+            Self::Healthy => 0,
+            Self::Lagging => 1,
+            Self::Stuck => 2,
+            Self::Unreachable => 3,
+        }
+    }
+}
+
+/// The health of a single node, as reported on the status page.
+#[derive(Clone, Debug, Serialize)]
+pub struct NodeHealth {
+    /// The node's peer responder id.
+    pub responder_id: String,
+
+    /// Overall status.
+    pub status: NodeStatus,
+
+    /// Number of blocks in the node's ledger, if it was ever polled
+    /// successfully.
+    pub num_blocks: Option<u64>,
+
+    /// How many blocks the node is behind the highest ledger in the network.
+    pub blocks_behind: u64,
+
+    /// The node's latest SCP message.
+    pub scp: Option<ScpSnapshot>,
+
+    /// The sync status reported by the node's admin API.
+    pub sync_status: Option<String>,
+
+    /// The status of the node's peer connections reported by the node's admin
+    /// API.
+    pub peers: Option<Vec<PeerConnectionStatus>>,
+
+    /// How long the node has been working on its current slot, if it has
+    /// not externalized it yet.
+    pub current_slot_age_ms: Option<u64>,
+
+    /// How long it took the node to externalize the last slot we observed
+    /// from start to finish.
+    pub last_slot_duration_ms: Option<u64>,
+
+    /// Number of distinct nomination payloads observed for the current slot.
+    pub nomination_rounds: u32,
+
+    /// How much the ballot counter grew since the node started balloting on
+    /// the current slot. Growth indicates ballot timeouts.
+    pub ballot_counter_growth: u32,
+
+    /// The error encountered during the last poll, if any.
+    pub last_error: Option<String>,
+}
+
+/// Per-node tracking state.
+struct NodeTracker {
+    health: NodeHealth,
+
+    /// When we first saw the node working on its current slot.
+    slot_started_at: Option<Instant>,
+
+    /// Hashes of the nomination payloads seen for the current slot.
+    nominations: HashSet<u64>,
+
+    /// The first ballot counter seen for the current slot.
+    first_ballot_counter: Option<u32>,
+}
+
+impl NodeTracker {
+    fn new(responder_id: String) -> Self {
+        Self {
+            health: NodeHealth {
+                responder_id,
+                status: NodeStatus::Unreachable,
+                num_blocks: None,
+                blocks_behind: 0,
+                scp: None,
+                sync_status: None,
+                peers: None,
+                current_slot_age_ms: None,
+                last_slot_duration_ms: None,
+                nomination_rounds: 0,
+                ballot_counter_growth: 0,
+                last_error: None,
+            },
+            slot_started_at: None,
+            nominations: HashSet::new(),
+            first_ballot_counter: None,
+        }
+    }
+
+    fn observe(&mut self, observation: NodeObservation, now: Instant) {
+        self.health.num_blocks = Some(observation.num_blocks);
+        self.health.sync_status = observation.sync_status;
+        self.health.peers = observation.peers;
+        self.health.last_error = None;
+
+        let scp = match observation.scp {
+            Some(scp) => scp,
+            None => return,
+        };
+
+        let new_slot = self
+            .health
+            .scp
+            .as_ref()
+            .map_or(true, |prev| prev.slot_index != scp.slot_index);
+        if new_slot {
+            // Seeing a newer slot also means the previous one was externalized.
+            self.finish_slot(now);
+            self.health.nomination_rounds = 0;
+            self.health.ballot_counter_growth = 0;
+            self.nominations.clear();
+            self.first_ballot_counter = None;
+        }
+
+        if scp.phase == ScpPhase::Externalize {
+            self.finish_slot(now);
+        } else {
+            self.slot_started_at.get_or_insert(now);
+
+            if let Some(nomination_hash) = scp.nomination_hash {
+                self.nominations.insert(nomination_hash);
+                self.health.nomination_rounds = self.nominations.len() as u32;
+            }
+
+            if scp.ballot_counter > 0 {
+                let first = *self.first_ballot_counter.get_or_insert(scp.ballot_counter);
+                self.health.ballot_counter_growth = scp.ballot_counter.saturating_sub(first);
+            }
+        }
+
+        self.health.current_slot_age_ms = self
+            .slot_started_at
+            .map(|started_at| now.saturating_duration_since(started_at).as_millis() as u64);
+        self.health.scp = Some(scp);
+    }
+
+    fn observe_error(&mut self, error: String) {
+        self.health.last_error = Some(error);
+    }
+
+    fn finish_slot(&mut self, now: Instant) {
+        if let Some(started_at) = self.slot_started_at.take() {
+            self.health.last_slot_duration_ms =
+                Some(now.saturating_duration_since(started_at).as_millis() as u64);
+        }
+    }
+
+    fn update_status(
+        &mut self,
+        highest_num_blocks: u64,
+        stuck_threshold: Duration,
+        lagging_blocks_threshold: u64,
+        now: Instant,
+    ) {
+        self.health.blocks_behind =
+            highest_num_blocks.saturating_sub(self.health.num_blocks.unwrap_or(0));
+
+        self.health.status = if self.health.last_error.is_some() || self.health.num_blocks.is_none()
+        {
+            NodeStatus::Unreachable
+        } else if self.slot_started_at.map_or(false, |started_at| {
+            now.saturating_duration_since(started_at) > stuck_threshold
+        }) {
+            NodeStatus::Stuck
+        } else if self.health.blocks_behind >= lagging_blocks_threshold
+            || self.health.sync_status.as_deref() == Some("catchup")
+        {
+            NodeStatus::Lagging
+        } else {
+            NodeStatus::Healthy
+        };
+    }
+}
+
+/// A network-wide health report.
+#[derive(Clone, Debug, Serialize)]
+pub struct NetworkHealthReport {
+    /// The highest number of blocks seen in any node's ledger.
+    pub highest_num_blocks: u64,
+
+    /// Number of healthy nodes.
+    pub healthy_nodes: usize,
+
+    /// Per-node health.
+    pub nodes: Vec<NodeHealth>,
+}
+
+/// Tracks the health of all monitored nodes.
+pub struct NetworkHealth {
+    nodes: Vec<NodeTracker>,
+    stuck_threshold: Duration,
+    lagging_blocks_threshold: u64,
+}
+
+impl NetworkHealth {
+    /// Create a new tracker.
+    ///
+    /// # Arguments
+    /// * `responder_ids` - The monitored nodes, in polling order.
+    /// * `stuck_threshold` - How long a node may work on a slot before it is
+    ///   considered stuck.
+    /// * `lagging_blocks_threshold` - How many blocks a node may be behind
+    ///   before it is considered lagging.
+    pub fn new(
+        responder_ids: Vec<String>,
+        stuck_threshold: Duration,
+        lagging_blocks_threshold: u64,
+    ) -> Self {
+        Self {
+            nodes: responder_ids.into_iter().map(NodeTracker::new).collect(),
+            stuck_threshold,
+            lagging_blocks_threshold,
+        }
+    }
+
+    /// Record the results of polling every node, in the same order the nodes
+    /// were given in.
+    pub fn update(&mut self, results: Vec<Result<NodeObservation, String>>, now: Instant) {
+        assert_eq!(results.len(), self.nodes.len(), "one result per node");
+
+        for (node, result) in self.nodes.iter_mut().zip(results) {
+            match result {
+                Ok(observation) => node.observe(observation, now),
+                Err(error) => node.observe_error(error),
+            }
+        }
+
+        let highest_num_blocks = self.highest_num_blocks();
+        for node in self.nodes.iter_mut() {
+            node.update_status(
+                highest_num_blocks,
+                self.stuck_threshold,
+                self.lagging_blocks_threshold,
+                now,
+            );
+        }
+    }
+
+    /// Get a report of the current network health.
+    pub fn report(&self) -> NetworkHealthReport {
+        NetworkHealthReport {
+            highest_num_blocks: self.highest_num_blocks(),
+            healthy_nodes: self
+                .nodes
+                .iter()
+                .filter(|node| node.health.status == NodeStatus::Healthy)
+                .count(),
+            nodes: self.nodes.iter().map(|node| node.health.clone()).collect(),
+        }
+    }
+
+    fn highest_num_blocks(&self) -> u64 {
+        self.nodes
+            .iter()
+            .filter_map(|node| node.health.num_blocks)
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mc_consensus_scp::{test_utils::test_node_id, QuorumSet};
+
+    fn observation(
+        num_blocks: u64,
+        slot_index: u64,
+        phase: ScpPhase,
+        counter: u32,
+    ) -> NodeObservation {
+        NodeObservation {
+            num_blocks,
+            scp: Some(ScpSnapshot {
+                slot_index,
+                phase,
+                ballot_counter: counter,
+                voted_values: counter as usize,
+                accepted_values: 0,
+                nomination_hash: matches!(phase, ScpPhase::Nominate | ScpPhase::NominatePrepare)
+                    .then(|| counter as u64),
+            }),
+            sync_status: None,
+            peers: None,
+        }
+    }
+
+    #[test]
+    fn tracks_slot_timing_and_ballot_growth() {
+        let start = Instant::now();
+        let mut health = NetworkHealth::new(vec!["a".into()], Duration::from_secs(30), 2);
+
+        health.update(vec![Ok(observation(5, 5, ScpPhase::Nominate, 0))], start);
+        health.update(
+            vec![Ok(observation(5, 5, ScpPhase::NominatePrepare, 1))],
+            start + Duration::from_secs(1),
+        );
+        health.update(
+            vec![Ok(observation(5, 5, ScpPhase::Prepare, 3))],
+            start + Duration::from_secs(2),
+        );
+
+        let node = &health.report().nodes[0];
+        assert_eq!(node.status, NodeStatus::Healthy);
+        assert_eq!(node.nomination_rounds, 2);
+        assert_eq!(node.ballot_counter_growth, 2);
+        assert_eq!(node.current_slot_age_ms, Some(2000));
+
+        health.update(
+            vec![Ok(observation(6, 5, ScpPhase::Externalize, 3))],
+            start + Duration::from_secs(4),
+        );
+        let node = &health.report().nodes[0];
+        assert_eq!(node.last_slot_duration_ms, Some(4000));
+        assert_eq!(node.current_slot_age_ms, None);
+
+        // A new slot resets the per-slot counters.
+        health.update(
+            vec![Ok(observation(6, 6, ScpPhase::Nominate, 0))],
+            start + Duration::from_secs(5),
+        );
+        let node = &health.report().nodes[0];
+        assert_eq!(node.nomination_rounds, 1);
+        assert_eq!(node.ballot_counter_growth, 0);
+        assert_eq!(node.last_slot_duration_ms, Some(4000));
+    }
+
+    #[test]
+    fn counts_distinct_nomination_payloads() {
+        let nominate = |voted: &[u32], accepted: &[u32]| {
+            let msg = Msg::new(
+                test_node_id(1),
+                QuorumSet::empty(),
+                5,
+                Topic::Nominate(NominatePayload {
+                    X: voted.iter().cloned().collect(),
+                    Y: accepted.iter().cloned().collect(),
+                }),
+            );
+            NodeObservation {
+                num_blocks: 5,
+                scp: Some(ScpSnapshot::from(&msg)),
+                sync_status: None,
+                peers: None,
+            }
+        };
+
+        let start = Instant::now();
+        let mut health = NetworkHealth::new(vec!["a".into()], Duration::from_secs(30), 2);
+
+        // Payloads of the same size with different values are distinct
+        // rounds, and seeing an earlier payload again is not a new round.
+        for (i, observation) in [
+            nominate(&[1], &[]),
+            nominate(&[2], &[]),
+            nominate(&[2], &[]),
+            nominate(&[1], &[]),
+            nominate(&[1, 2], &[1]),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            health.update(vec![Ok(observation)], start + Duration::from_secs(i as u64));
+        }
+
+        assert_eq!(health.report().nodes[0].nomination_rounds, 3);
+    }
+
+    #[test]
+    fn detects_stuck_lagging_and_unreachable_nodes() {
+        let start = Instant::now();
+        let mut health = NetworkHealth::new(
+            vec!["a".into(), "b".into(), "c".into(), "d".into()],
+            Duration::from_secs(30),
+            2,
+        );
+
+        health.update(
+            vec![
+                Ok(observation(10, 10, ScpPhase::Externalize, 1)),
+                Ok(observation(10, 10, ScpPhase::Prepare, 1)),
+                Ok(observation(8, 8, ScpPhase::Externalize, 1)),
+                Err("connection refused".into()),
+            ],
+            start,
+        );
+        health.update(
+            vec![
+                Ok(observation(10, 10, ScpPhase::Externalize, 1)),
+                Ok(observation(10, 10, ScpPhase::Prepare, 7)),
+                Ok(observation(8, 8, ScpPhase::Externalize, 1)),
+                Err("connection refused".into()),
+            ],
+            start + Duration::from_secs(31),
+        );
+
+        let report = health.report();
+        assert_eq!(report.highest_num_blocks, 10);
+        assert_eq!(report.healthy_nodes, 1);
+        let statuses: Vec<_> = report.nodes.iter().map(|node| node.status).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                NodeStatus::Healthy,
+                NodeStatus::Stuck,
+                NodeStatus::Lagging,
+                NodeStatus::Unreachable
+            ]
+        );
+        assert_eq!(report.nodes[1].ballot_counter_growth, 6);
+        assert_eq!(report.nodes[2].blocks_behind, 2);
+        assert_eq!(
+            report.nodes[3].last_error.as_deref(),
+            Some("connection refused")
+        );
+    }
+
+    #[test]
+    fn reports_peer_connection_status() {
+        let start = Instant::now();
+        let mut health = NetworkHealth::new(vec!["a".into()], Duration::from_secs(30), 2);
+        let peers = vec![
+            PeerConnectionStatus {
+                responder_id: "b".into(),
+                last_heard_secs: 1,
+                connected: true,
+            },
+            PeerConnectionStatus {
+                responder_id: "c".into(),
+                last_heard_secs: 120,
+                connected: false,
+            },
+        ];
+
+        health.update(
+            vec![Ok(NodeObservation {
+                peers: Some(peers.clone()),
+                ..observation(5, 5, ScpPhase::Nominate, 0)
+            })],
+            start,
+        );
+        let node = &health.report().nodes[0];
+        assert_eq!(node.peers, Some(peers));
+        assert_eq!(node.status, NodeStatus::Healthy);
+
+        // A failed poll keeps the last known peer status.
+        health.update(
+            vec![Err("timed out".into())],
+            start + Duration::from_secs(1),
+        );
+        let node = &health.report().nodes[0];
+        assert_eq!(node.status, NodeStatus::Unreachable);
+        assert_eq!(node.peers.as_ref().map(Vec::len), Some(2));
+    }
+}
@@ -0,0 +1,114 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! Polls a single consensus node for its state.
+
+use crate::{
+    config::NodeConfig,
+    error::Error,
+    network_health::{NodeObservation, ScpSnapshot},
+};
+use grpcio::{CallOption, ChannelBuilder, Environment};
+use mc_common::logger::Logger;
+use mc_consensus_api::{
+    consensus_common_grpc::BlockchainApiClient, consensus_peer_grpc::ConsensusPeerApiClient,
+};
+use mc_peers::ConsensusMsg;
+use mc_util_grpc::{admin_grpc::AdminApiClient, ConnectionUriGrpcioChannel, Empty};
+use mc_util_uri::ConnectionUri;
+use std::{sync::Arc, time::Duration};
+
+/// Polls a consensus node over its peer API, and optionally its admin API.
+pub struct NodePoller {
+    responder_id: String,
+    peer_api_client: ConsensusPeerApiClient,
+    blockchain_api_client: BlockchainApiClient,
+    admin_api_client: Option<AdminApiClient>,
+    timeout: Duration,
+}
+
+impl NodePoller {
+    /// Create a new poller for the given node.
+    ///
+    /// # Arguments
+    /// * `node_config` - The node to poll.
+    /// * `timeout` - Deadline for each gRPC call made while polling.
+    /// * `env` - gRPC environment.
+    /// * `logger` - Logger.
+    pub fn new(
+        node_config: &NodeConfig,
+        timeout: Duration,
+        env: Arc<Environment>,
+        logger: &Logger,
+    ) -> Self {
+        let responder_id = node_config
+            .peer_uri
+            .responder_id()
+            .map(|responder_id| responder_id.to_string())
+            .unwrap_or_else(|_| node_config.peer_uri.to_string());
+
+        let ch = ChannelBuilder::default_channel_builder(env.clone())
+            .connect_to_uri(&node_config.peer_uri, logger);
+        let peer_api_client = ConsensusPeerApiClient::new(ch.clone());
+        let blockchain_api_client = BlockchainApiClient::new(ch);
+
+        let admin_api_client = node_config.admin_uri.as_ref().map(|admin_uri| {
+            AdminApiClient::new(
+                ChannelBuilder::default_channel_builder(env).connect_to_uri(admin_uri, logger),
+            )
+        });
+
+        Self {
+            responder_id,
+            peer_api_client,
+            blockchain_api_client,
+            admin_api_client,
+            timeout,
+        }
+    }
+
+    /// The responder id of the polled node.
+    pub fn responder_id(&self) -> &str {
+        &self.responder_id
+    }
+
+    /// Poll the node.
+    pub fn poll(&self) -> Result<NodeObservation, Error> {
+        let last_block_info = self
+            .blockchain_api_client
+            .get_last_block_info_opt(&Empty::new(), self.call_option())?;
+
+        let latest_msg = self
+            .peer_api_client
+            .get_latest_msg_opt(&Empty::new(), self.call_option())?;
+        let scp = if latest_msg.get_payload().is_empty() {
+            None
+        } else {
+            let msg: ConsensusMsg = mc_util_serial::deserialize(latest_msg.get_payload())?;
+            Some(ScpSnapshot::from(&msg.scp_msg))
+        };
+
+        let (sync_status, peers) = match self.admin_api_client.as_ref() {
+            Some(admin_api_client) => {
+                let info = admin_api_client.get_info_opt(&Empty::new(), self.call_option())?;
+                let config: serde_json::Value = serde_json::from_str(info.get_config_json())?;
+                (
+                    config["status"]["sync_status"].as_str().map(String::from),
+                    // Nodes that predate per-peer status do not report it.
+                    serde_json::from_value(config["status"]["peers"].clone())?,
+                )
+            }
+            None => (None, None),
+        };
+
+        Ok(NodeObservation {
+            num_blocks: last_block_info.get_index() + 1,
+            scp,
+            sync_status,
+            peers,
+        })
+    }
+
+    fn call_option(&self) -> CallOption {
+        CallOption::default().timeout(self.timeout)
+    }
+}
@@ -10,7 +10,7 @@ use mc_consensus_api::{
     empty::Empty,
 };
 use mc_consensus_enclave_api::GovernorsSigner;
-use mc_consensus_mint_client::{printers, Commands, Config, TxFile};
+use mc_consensus_mint_client::{printers, Commands, Config, SigningCeremony, TxFile};
 use mc_crypto_keys::{Ed25519Pair, Signer};
 use mc_crypto_multisig::MultiSig;
 use mc_transaction_core::{
@@ -253,6 +253,106 @@ fn main() {
             }
         },
 
+        Commands::GetActiveMintConfigs { node, token_id } => {
+            let env = Arc::new(EnvBuilder::new().name_prefix("mint-client-grpc").build());
+            let ch = ChannelBuilder::default_channel_builder(env).connect_to_uri(&node, &logger);
+            let client_api = ConsensusClientApiClient::new(ch);
+
+            let node_config = client_api
+                .get_node_config(&Empty::new())
+                .expect("get node config");
+
+            let token_config_map = node_config.get_token_config_map();
+            let mut token_ids = token_config_map
+                .keys()
+                .filter(|id| token_id.map_or(true, |token_id| *token_id == **id))
+                .cloned()
+                .collect::<Vec<_>>();
+            token_ids.sort_unstable();
+
+            for id in token_ids {
+                let token_config = &token_config_map[&id];
+                println!("Token id {}:", id);
+                if token_config.has_active_mint_configs() {
+                    printers::print_active_mint_configs(token_config.get_active_mint_configs(), 1);
+                } else {
+                    println!("    No active mint configs");
+                }
+            }
+        }
+
+        Commands::StartSigningCeremony {
+            tx_file,
+            out,
+            params,
+        } => {
+            let signer_sets = params
+                .try_into_signer_sets(&tx_file)
+                .expect("failed loading signer sets");
+            let ceremony =
+                SigningCeremony::new(tx_file, signer_sets).expect("failed starting ceremony");
+
+            ceremony
+                .write_json(&out)
+                .expect("failed writing ceremony file");
+            printers::print_signing_ceremony_status(&ceremony, 0);
+        }
+
+        Commands::CollectSignatures {
+            ceremony: ceremony_path,
+            tx_filenames,
+        } => {
+            let mut ceremony =
+                SigningCeremony::from_json_file(&ceremony_path).expect("failed loading ceremony");
+
+            for tx_filename in &tx_filenames {
+                let tx_file = TxFile::from_json_file(tx_filename).expect("failed loading tx file");
+                let signers = ceremony
+                    .collect(&tx_file)
+                    .unwrap_or_else(|err| panic!("failed collecting {:?}: {}", tx_filename, err));
+                println!(
+                    "Collected {} new signature(s) from {:?}",
+                    signers.len(),
+                    tx_filename
+                );
+            }
+
+            ceremony
+                .write_json(&ceremony_path)
+                .expect("failed writing ceremony file");
+            printers::print_signing_ceremony_status(&ceremony, 0);
+        }
+
+        Commands::SigningCeremonyStatus { ceremony } => {
+            printers::print_signing_ceremony_status(&ceremony, 0);
+        }
+
+        Commands::SubmitSigningCeremony { node, ceremony } => {
+            let tx_file = match ceremony.signed_tx_file() {
+                Ok(tx_file) => tx_file,
+                Err(err) => {
+                    printers::print_signing_ceremony_status(&ceremony, 0);
+                    panic!("failed completing ceremony: {}", err);
+                }
+            };
+
+            let env = Arc::new(EnvBuilder::new().name_prefix("mint-client-grpc").build());
+            let ch = ChannelBuilder::default_channel_builder(env).connect_to_uri(&node, &logger);
+            let client_api = ConsensusClientApiClient::new(ch);
+
+            let resp = match tx_file {
+                TxFile::MintConfigTx(tx) => client_api.propose_mint_config_tx(&(&tx).into()),
+                TxFile::MintTx(tx) => client_api.propose_mint_tx(&(&tx).into()),
+            }
+            .expect("propose tx");
+            println!("response: {:?}", resp);
+
+            // Relying on the success result code being 0, we terminate ourselves in a way
+            // that allows whoever started this binary to easily determine if submitting the
+            // transaction succeeded.
+            exit(resp.get_result().get_code().value());
+        }
+
         Commands::Sign {
             tx_file: tx_file_path,
             signing_keys,
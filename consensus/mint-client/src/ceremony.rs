@@ -0,0 +1,363 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! A signing ceremony for a MintConfigTx or MintTx.
+//!
+//! The coordinator starts a ceremony from a transaction file and the signer
+//! sets that can authorize it, and distributes the transaction file to the
+//! signers. Each signer signs their copy offline, using the `sign` command and
+//! their keys on disk, and returns it. The coordinator collects the returned
+//! copies into the ceremony, which verifies every signature and keeps track of
+//! the signers that are still missing, and submits the transaction once the
+//! threshold of one of the signer sets is met.
+
+use crate::{tx_file::TxFileError, TxFile};
+use displaydoc::Display;
+use mc_crypto_keys::{Ed25519Public, Ed25519Signature, Verifier};
+use mc_crypto_multisig::{MultiSig, SignerSet};
+use serde::{Deserialize, Serialize};
+use std::{fs, path::Path};
+
+/// The state of a signing ceremony, which is stored in a JSON file between
+/// the steps of the ceremony.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct SigningCeremony {
+    /// The transaction being signed, holding the signatures collected so far.
+    tx_file: TxFile,
+
+    /// The signer sets, any one of which can authorize the transaction.
+    signer_sets: Vec<SignerSet<Ed25519Public>>,
+}
+
+/// Which signers of a signer set have signed the transaction.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SignerSetStatus {
+    /// The signer set.
+    pub signer_set: SignerSet<Ed25519Public>,
+
+    /// The signers that have signed, including those of nested signer sets.
+    pub signed: Vec<Ed25519Public>,
+
+    /// The signers that have not signed yet, including those of nested signer
+    /// sets.
+    pub missing: Vec<Ed25519Public>,
+}
+
+impl SignerSetStatus {
+    /// Whether enough signers have signed to meet the threshold.
+    pub fn is_complete(&self) -> bool {
+        self.signer_set.is_satisfied_by(&self.signed)
+    }
+}
+
+impl SigningCeremony {
+    /// Start a signing ceremony. Any signatures the transaction already holds
+    /// are verified and kept.
+    ///
+    /// # Arguments
+    /// * `tx_file` - The transaction to sign.
+    /// * `signer_sets` - The signer sets that can authorize the transaction.
+    pub fn new(
+        tx_file: TxFile,
+        signer_sets: Vec<SignerSet<Ed25519Public>>,
+    ) -> Result<Self, CeremonyError> {
+        if signer_sets.is_empty() {
+            return Err(CeremonyError::NoSignerSets);
+        }
+
+        let mut ceremony = Self {
+            tx_file: tx_file.clone(),
+            signer_sets,
+        };
+        ceremony.set_signatures(vec![]);
+        ceremony.collect(&tx_file)?;
+        Ok(ceremony)
+    }
+
+    /// The transaction being signed, holding the signatures collected so far.
+    pub fn tx_file(&self) -> &TxFile {
+        &self.tx_file
+    }
+
+    /// The signer sets that can authorize the transaction.
+    pub fn signer_sets(&self) -> &[SignerSet<Ed25519Public>] {
+        &self.signer_sets
+    }
+
+    /// The message the signers sign, i.e. the hash of the transaction prefix.
+    pub fn message(&self) -> [u8; 32] {
+        match &self.tx_file {
+            TxFile::MintConfigTx(tx) => tx.prefix.hash(),
+            TxFile::MintTx(tx) => tx.prefix.hash(),
+        }
+    }
+
+    /// Collect the signatures of a copy of the transaction returned by a
+    /// signer.
+    ///
+    /// The copy is rejected as a whole if its prefix differs from the
+    /// transaction being signed, or if any of its signatures is not a valid
+    /// signature by one of the signers.
+    ///
+    /// Returns the signers whose signatures were added.
+    pub fn collect(&mut self, tx_file: &TxFile) -> Result<Vec<Ed25519Public>, CeremonyError> {
+        match (&self.tx_file, tx_file) {
+            (TxFile::MintConfigTx(ours), TxFile::MintConfigTx(theirs))
+                if ours.prefix == theirs.prefix => {}
+            (TxFile::MintTx(ours), TxFile::MintTx(theirs)) if ours.prefix == theirs.prefix => {}
+            _ => return Err(CeremonyError::PrefixMismatch),
+        }
+
+        let mut signatures = self.signatures().to_vec();
+        let mut added_signers = Vec::new();
+        for signature in signatures_of(tx_file) {
+            if signatures.contains(signature) {
+                continue;
+            }
+            let signer = self
+                .signer_of(signature)
+                .ok_or_else(|| CeremonyError::UnknownSignature(hex::encode(signature)))?;
+            if self.signature_of(&signer).is_none() && !added_signers.contains(&signer) {
+                signatures.push(*signature);
+                added_signers.push(signer);
+            }
+        }
+
+        signatures.sort();
+        self.set_signatures(signatures);
+        Ok(added_signers)
+    }
+
+    /// Which signers of each signer set have signed the transaction.
+    pub fn status(&self) -> Vec<SignerSetStatus> {
+        self.signer_sets
+            .iter()
+            .map(|signer_set| {
+                let (signed, missing) = signer_set
+                    .all_signers()
+                    .into_iter()
+                    .partition(|signer| self.signature_of(signer).is_some());
+                SignerSetStatus {
+                    signer_set: signer_set.clone(),
+                    signed,
+                    missing,
+                }
+            })
+            .collect()
+    }
+
+    /// Whether the threshold of one of the signer sets is met.
+    pub fn is_complete(&self) -> bool {
+        self.status().iter().any(SignerSetStatus::is_complete)
+    }
+
+    /// The signed transaction, ready to be submitted.
+    ///
+    /// The transaction holds the signatures of the first signer set whose
+    /// threshold is met, limited to the signatures it requires.
+    pub fn signed_tx_file(&self) -> Result<TxFile, CeremonyError> {
+        let message = self.message();
+        let status = self
+            .status()
+            .into_iter()
+            .find(SignerSetStatus::is_complete)
+            .ok_or(CeremonyError::ThresholdNotMet)?;
+
+        // Drop the signers the threshold can be met without.
+        let mut signers = status.signed;
+        let mut index = 0;
+        while index < signers.len() {
+            let mut fewer_signers = signers.clone();
+            fewer_signers.remove(index);
+            if status.signer_set.is_satisfied_by(&fewer_signers) {
+                signers = fewer_signers;
+            } else {
+                index += 1;
+            }
+        }
+
+        let mut signatures = signers
+            .iter()
+            .filter_map(|signer| self.signature_of(signer))
+            .collect::<Vec<_>>();
+        signatures.sort();
+        let signature = MultiSig::new(signatures);
+        status
+            .signer_set
+            .verify(message.as_ref(), &signature)
+            .map_err(|_| CeremonyError::ThresholdNotMet)?;
+
+        let mut tx_file = self.tx_file.clone();
+        match &mut tx_file {
+            TxFile::MintConfigTx(ref mut tx) => tx.signature = signature,
+            TxFile::MintTx(ref mut tx) => tx.signature = signature,
+        }
+        Ok(tx_file)
+    }
+
+    /// Write the ceremony to the given file.
+    pub fn write_json(&self, path: &impl AsRef<Path>) -> Result<(), CeremonyError> {
+        let json = serde_json::to_string_pretty(&self).map_err(TxFileError::from)?;
+        fs::write(path, json).map_err(TxFileError::from)?;
+        Ok(())
+    }
+
+    /// Load a [SigningCeremony] from a JSON file.
+    pub fn from_json_file<P: AsRef<Path>>(path: P) -> Result<Self, CeremonyError> {
+        let json = fs::read_to_string(path).map_err(TxFileError::from)?;
+        let ceremony = serde_json::from_str(&json).map_err(TxFileError::from)?;
+        Ok(ceremony)
+    }
+
+    fn signatures(&self) -> &[Ed25519Signature] {
+        signatures_of(&self.tx_file)
+    }
+
+    fn set_signatures(&mut self, signatures: Vec<Ed25519Signature>) {
+        let signature = MultiSig::new(signatures);
+        match &mut self.tx_file {
+            TxFile::MintConfigTx(ref mut tx) => tx.signature = signature,
+            TxFile::MintTx(ref mut tx) => tx.signature = signature,
+        }
+    }
+
+    /// The signer, in any of the signer sets, that made the given signature.
+    fn signer_of(&self, signature: &Ed25519Signature) -> Option<Ed25519Public> {
+        let message = self.message();
+        self.signer_sets
+            .iter()
+            .flat_map(|signer_set| signer_set.all_signers())
+            .find(|signer| signer.verify(message.as_ref(), signature).is_ok())
+    }
+
+    /// The collected signature of the given signer, if they have signed.
+    fn signature_of(&self, signer: &Ed25519Public) -> Option<Ed25519Signature> {
+        let message = self.message();
+        self.signatures()
+            .iter()
+            .find(|signature| signer.verify(message.as_ref(), signature).is_ok())
+            .copied()
+    }
+}
+
+fn signatures_of(tx_file: &TxFile) -> &[Ed25519Signature] {
+    match tx_file {
+        TxFile::MintConfigTx(tx) => tx.signature.signatures(),
+        TxFile::MintTx(tx) => tx.signature.signatures(),
+    }
+}
+
+/// Error type for signing ceremony operations.
+#[derive(Debug, Display)]
+pub enum CeremonyError {
+    /// A signing ceremony requires at least one signer set
+    NoSignerSets,
+
+    /// The transaction does not match the transaction being signed
+    PrefixMismatch,
+
+    /// Signature {0} was not made by any of the signers
+    UnknownSignature(String),
+
+    /// No signer set has enough signatures to meet its threshold
+    ThresholdNotMet,
+
+    /// Tx file: {0}
+    TxFile(TxFileError),
+}
+
+impl From<TxFileError> for CeremonyError {
+    fn from(err: TxFileError) -> Self {
+        Self::TxFile(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mc_crypto_keys::{Ed25519Pair, Signer};
+    use mc_transaction_core::mint::{MintTx, MintTxPrefix};
+    use mc_util_from_random::FromRandom;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn collect_signatures_until_threshold_is_met() {
+        let mut rng: StdRng = SeedableRng::from_seed([1u8; 32]);
+        let signers = (0..3)
+            .map(|_| Ed25519Pair::from_random(&mut rng))
+            .collect::<Vec<_>>();
+        let outsider = Ed25519Pair::from_random(&mut rng);
+        let signer_set = SignerSet::new(
+            signers.iter().map(|signer| signer.public_key()).collect(),
+            2,
+        );
+
+        let tx = MintTx {
+            prefix: MintTxPrefix {
+                token_id: 1,
+                amount: 1000,
+                view_public_key: Default::default(),
+                spend_public_key: Default::default(),
+                nonce: vec![5u8; 64],
+                tombstone_block: 10,
+            },
+            signature: MultiSig::new(vec![]),
+        };
+        let message = tx.prefix.hash();
+        let signed_by = |signer: &Ed25519Pair| {
+            let mut tx = tx.clone();
+            tx.signature = MultiSig::new(vec![signer.try_sign(message.as_ref()).unwrap()]);
+            TxFile::from(tx)
+        };
+
+        let mut ceremony =
+            SigningCeremony::new(TxFile::from(tx.clone()), vec![signer_set.clone()]).unwrap();
+        assert!(!ceremony.is_complete());
+        assert!(matches!(
+            ceremony.signed_tx_file(),
+            Err(CeremonyError::ThresholdNotMet)
+        ));
+
+        // Signatures by someone outside the signer set are rejected.
+        assert!(matches!(
+            ceremony.collect(&signed_by(&outsider)),
+            Err(CeremonyError::UnknownSignature(_))
+        ));
+
+        // A different transaction is rejected.
+        let mut other_tx = tx.clone();
+        other_tx.prefix.amount += 1;
+        assert!(matches!(
+            ceremony.collect(&TxFile::from(other_tx)),
+            Err(CeremonyError::PrefixMismatch)
+        ));
+
+        assert_eq!(
+            ceremony.collect(&signed_by(&signers[0])).unwrap(),
+            vec![signers[0].public_key()]
+        );
+        // Collecting the same signature again adds nothing.
+        assert_eq!(ceremony.collect(&signed_by(&signers[0])).unwrap(), vec![]);
+
+        let status = ceremony.status();
+        assert_eq!(status.len(), 1);
+        assert_eq!(status[0].signed, vec![signers[0].public_key()]);
+        let mut missing = vec![signers[1].public_key(), signers[2].public_key()];
+        missing.sort();
+        assert_eq!(status[0].missing, missing);
+        assert!(!ceremony.is_complete());
+
+        ceremony.collect(&signed_by(&signers[2])).unwrap();
+        assert!(ceremony.is_complete());
+        assert_eq!(ceremony.status()[0].missing, vec![signers[1].public_key()]);
+
+        let signed_tx = MintTx::try_from(ceremony.signed_tx_file().unwrap()).unwrap();
+        assert_eq!(signed_tx.prefix, tx.prefix);
+        assert_eq!(
+            signer_set
+                .verify(message.as_ref(), &signed_tx.signature)
+                .unwrap()
+                .len(),
+            2
+        );
+    }
+}
@@ -2,7 +2,7 @@
 
 //! Command line configuration for the consensus mint client.
 
-use crate::TxFile;
+use crate::{SigningCeremony, TxFile};
 use clap::{Args, Parser, Subcommand};
 use hex::FromHex;
 use mc_account_keys::PublicAddress;
@@ -14,7 +14,8 @@ use mc_crypto_keys::{
 use mc_crypto_multisig::{MultiSig, SignerSet};
 use mc_transaction_core::{
     mint::{
-        constants::NONCE_LENGTH, MintConfig, MintConfigTx, MintConfigTxPrefix, MintTx, MintTxPrefix,
+        constants::NONCE_LENGTH, MintConfig, MintConfigTx, MintConfigTxPrefix, MintLimitWindow,
+        MintTx, MintTxPrefix,
     },
     TokenId,
 };
@@ -50,6 +51,14 @@ pub struct MintConfigTxPrefixParams {
     // Tuple of (mint limit, SignerSet)
     pub configs: Vec<(u64, SignerSet<Ed25519Public>)>,
 
+    /// Mint limit windows. Each window must be of the format: <config
+    /// index>:<number of blocks>:<limit>. For example: 0:720:5000 limits the
+    /// first configuration to minting 5000 tokens within any 720 consecutive
+    /// blocks.
+    #[clap(long = "mint-limit-window", parse(try_from_str = parse_mint_limit_window), use_value_delimiter = true, env = "MC_MINTING_LIMIT_WINDOWS")]
+    // Tuple of (config index, MintLimitWindow)
+    pub mint_limit_windows: Vec<(usize, MintLimitWindow)>,
+
     /// Total mint limit, shared amongst all configs.
     #[clap(long, env = "MC_MINTING_TOTAL_LIMIT")]
     pub total_mint_limit: u64,
@@ -63,17 +72,34 @@ impl MintConfigTxPrefixParams {
         let tombstone_block = self.tombstone.unwrap_or_else(fallback_tombstone_block);
         let nonce = get_or_generate_nonce(self.nonce);
         let token_id = self.token_id;
+
+        let mut configs = self
+            .configs
+            .into_iter()
+            .map(|(mint_limit, signer_set)| MintConfig {
+                token_id: *token_id,
+                mint_limit,
+                signer_set,
+                mint_limit_windows: vec![],
+            })
+            .collect::<Vec<_>>();
+        for (config_index, window) in self.mint_limit_windows {
+            configs
+                .get_mut(config_index)
+                .ok_or_else(|| {
+                    format!(
+                        "mint limit window refers to config {}, but only {} config(s) were provided",
+                        config_index,
+                        configs.len()
+                    )
+                })?
+                .mint_limit_windows
+                .push(window);
+        }
+
         Ok(MintConfigTxPrefix {
             token_id: *token_id,
-            configs: self
-                .configs
-                .into_iter()
-                .map(|(mint_limit, signer_set)| MintConfig {
-                    token_id: *token_id,
-                    mint_limit,
-                    signer_set,
-                })
-                .collect(),
+            configs,
             nonce,
             tombstone_block,
             total_mint_limit: self.total_mint_limit,
@@ -226,6 +252,82 @@ impl MintTxParams {
         Ok(MintTx { prefix, signature })
     }
 }
+
+#[derive(Args)]
+pub struct SignerSetParams {
+    /// Signer sets that can authorize the transaction. Each signer set must be
+    /// of the format: <signing threshold>:<signer 1 public keyfile>[:<signer
+    /// 2 public keyfile....>]. For example:
+    /// 2:signer1.pem:signer2.pem:signer3.pem defines a signer set requiring
+    /// 2 out of 3 signers.
+    #[clap(long = "signer-set", parse(try_from_str = parse_signer_set), use_value_delimiter = true, env = "MC_MINTING_SIGNER_SETS")]
+    signer_sets: Vec<SignerSet<Ed25519Public>>,
+
+    /// A tokens configuration file (in JSON or TOML format). The governors of
+    /// the token are used as the signer set of a MintConfigTx.
+    #[clap(long, parse(try_from_str = TokensConfig::load_from_path), env = "MC_MINTING_TOKENS_CONFIG")]
+    tokens: Option<TokensConfig>,
+
+    /// A JSON-formatted mint configuration tx file. The signer sets of its
+    /// mint configs are used as the signer sets of a MintTx.
+    #[clap(long, parse(try_from_str = load_tx_file_from_path), env = "MC_MINTING_CONFIG_TX_FILE")]
+    mint_config_tx: Option<TxFile>,
+}
+
+impl SignerSetParams {
+    /// The signer sets that can authorize the given transaction.
+    pub fn try_into_signer_sets(
+        self,
+        tx_file: &TxFile,
+    ) -> Result<Vec<SignerSet<Ed25519Public>>, String> {
+        let mut signer_sets = self.signer_sets;
+
+        if let Some(tokens) = self.tokens {
+            let tx = match tx_file {
+                TxFile::MintConfigTx(tx) => tx,
+                TxFile::MintTx(_) => {
+                    return Err(
+                        "a tokens configuration only provides the signer set of a MintConfigTx"
+                            .to_string(),
+                    )
+                }
+            };
+            let token_id = TokenId::from(tx.prefix.token_id);
+            let governors = tokens
+                .get_token_config(&token_id)
+                .and_then(|token_config| token_config.governors())
+                .ok_or_else(|| format!("token {} has no governors", token_id))?;
+            signer_sets.push(governors.clone());
+        }
+
+        if let Some(mint_config_tx) = self.mint_config_tx {
+            let tx = match tx_file {
+                TxFile::MintTx(tx) => tx,
+                TxFile::MintConfigTx(_) => {
+                    return Err(
+                        "a mint configuration tx only provides the signer sets of a MintTx"
+                            .to_string(),
+                    )
+                }
+            };
+            let mint_config_tx = MintConfigTx::try_from(mint_config_tx)
+                .map_err(|err| format!("failed loading mint configuration tx: {}", err))?;
+            signer_sets.extend(
+                mint_config_tx
+                    .prefix
+                    .configs
+                    .into_iter()
+                    .filter(|config| config.token_id == tx.prefix.token_id)
+                    .map(|config| config.signer_set),
+            );
+        }
+
+        if signer_sets.is_empty() {
+            return Err("no signer sets provided".to_string());
+        }
+        Ok(signer_sets)
+    }
+}
 #[derive(Subcommand)]
 pub enum Commands {
     /// Generate and submit a MintConfigTx transaction.
@@ -347,6 +449,72 @@ pub enum Commands {
         tx_file: TxFile,
     },
 
+    /// Query a consensus node for its active mint configurations, and print how
+    /// much can still be minted within each of their mint limit windows.
+    GetActiveMintConfigs {
+        /// URI of consensus node to connect to.
+        #[clap(long, env = "MC_CONSENSUS_URI")]
+        node: ConsensusClientUri,
+
+        /// Only print the active mint configurations of this token id.
+        #[clap(long, env = "MC_MINTING_TOKEN_ID")]
+        token_id: Option<TokenId>,
+    },
+
+    /// Start a signing ceremony for a transaction file produced by this tool.
+    /// The transaction file is then distributed to the signers, who sign it
+    /// with the `sign` command and return it to be collected.
+    StartSigningCeremony {
+        /// The transaction file to sign.
+        #[clap(long, parse(try_from_str = load_tx_file_from_path), env = "MC_MINTING_TX_FILE")]
+        tx_file: TxFile,
+
+        /// Filename to write the signing ceremony to.
+        #[clap(long, env = "MC_MINTING_CEREMONY_FILE")]
+        out: PathBuf,
+
+        #[clap(flatten)]
+        params: SignerSetParams,
+    },
+
+    /// Collect the signatures of transaction files returned by signers into a
+    /// signing ceremony, rewriting the ceremony file, and report which signers
+    /// are missing.
+    CollectSignatures {
+        /// The signing ceremony file.
+        #[clap(long, env = "MC_MINTING_CEREMONY_FILE")]
+        ceremony: PathBuf,
+
+        /// Paths for the signed JSON-formatted tx files.
+        #[clap(
+            long = "tx-file",
+            required = true,
+            use_value_delimiter = true,
+            env = "MC_MINTING_TX_FILES"
+        )]
+        tx_filenames: Vec<PathBuf>,
+    },
+
+    /// Report which signers of a signing ceremony have signed and which are
+    /// missing.
+    SigningCeremonyStatus {
+        /// The signing ceremony file.
+        #[clap(long, parse(try_from_str = load_signing_ceremony_from_path), env = "MC_MINTING_CEREMONY_FILE")]
+        ceremony: SigningCeremony,
+    },
+
+    /// Submit the transaction of a signing ceremony, once the threshold of one
+    /// of its signer sets is met.
+    SubmitSigningCeremony {
+        /// URI of consensus node to connect to.
+        #[clap(long, env = "MC_CONSENSUS_URI")]
+        node: ConsensusClientUri,
+
+        /// The signing ceremony file.
+        #[clap(long, parse(try_from_str = load_signing_ceremony_from_path), env = "MC_MINTING_CEREMONY_FILE")]
+        ceremony: SigningCeremony,
+    },
+
     /// Sign a transaction file produced by this tool, rewriting the file with
     /// the appended signature(s).
     Sign {
@@ -451,27 +619,72 @@ fn parse_public_address(b58: &str) -> Result<PublicAddress, String> {
 /// Parses a minting limit and signer set from a string in the format:
 /// mint limit:threshold:keyfile1.pem[:keyfile2.pem...]
 fn parse_mint_config(src: &str) -> Result<(u64, SignerSet<Ed25519Public>), String> {
-    let parts = src.split(':').collect::<Vec<_>>();
-
     // At the minimum we should have 3 parts: mint limit, signing threshold, one
     // public key file
-    if parts.len() < 3 {
+    let (mint_limit, signer_set) = match src.split_once(':') {
+        Some((mint_limit, signer_set)) if signer_set.contains(':') => (mint_limit, signer_set),
+        _ => {
+            return Err(format!(
+                "mint config '{}' is not in the correct format. Expected format is '<mint_limit>:<signing_threshold>:keyfile1.pem[:keyfile2.pem:...]'",
+                src
+            ))
+        }
+    };
+
+    // Parse the mint limit
+    let mint_limit = mint_limit
+        .parse::<u64>()
+        .map_err(|err| format!("failed parsing mint limit '{}': {}", mint_limit, err))?;
+
+    // Success.
+    Ok((mint_limit, parse_signer_set(signer_set)?))
+}
+
+/// Parses a mint limit window from a string in the format:
+/// config_index:num_blocks:limit
+fn parse_mint_limit_window(src: &str) -> Result<(usize, MintLimitWindow), String> {
+    let parts = src.split(':').collect::<Vec<_>>();
+    if parts.len() != 3 {
         return Err(format!(
-            "mint config '{}' is not in the correct format. Expected format is '<mint_limit>:<signing_threshold>:keyfile1.pem[:keyfile2.pem:...]'",
+            "mint limit window '{}' is not in the correct format. Expected format is '<config_index>:<num_blocks>:<limit>'",
             src
         ));
     }
 
-    // Parse the mint limit and signing theshold
-    let mint_limit = parts[0]
+    let config_index = parts[0]
+        .parse::<usize>()
+        .map_err(|err| format!("failed parsing config index '{}': {}", parts[0], err))?;
+    let num_blocks = parts[1]
         .parse::<u64>()
-        .map_err(|err| format!("failed parsing mint limit '{}': {}", parts[0], err))?;
-    let threshold = parts[1]
+        .map_err(|err| format!("failed parsing number of blocks '{}': {}", parts[1], err))?;
+    let limit = parts[2]
+        .parse::<u64>()
+        .map_err(|err| format!("failed parsing limit '{}': {}", parts[2], err))?;
+
+    Ok((config_index, MintLimitWindow { num_blocks, limit }))
+}
+
+/// Parses a signer set from a string in the format:
+/// threshold:keyfile1.pem[:keyfile2.pem...]
+fn parse_signer_set(src: &str) -> Result<SignerSet<Ed25519Public>, String> {
+    let parts = src.split(':').collect::<Vec<_>>();
+
+    // At the minimum we should have 2 parts: signing threshold, one public key
+    // file
+    if parts.len() < 2 {
+        return Err(format!(
+            "signer set '{}' is not in the correct format. Expected format is '<signing_threshold>:keyfile1.pem[:keyfile2.pem:...]'",
+            src
+        ));
+    }
+
+    // Parse the signing theshold
+    let threshold = parts[0]
         .parse::<u32>()
-        .map_err(|err| format!("failed parsing signing threshold '{}': {}", parts[1], err))?;
+        .map_err(|err| format!("failed parsing signing threshold '{}': {}", parts[0], err))?;
 
     // Load public keys
-    let public_keys = parts[2..]
+    let public_keys = parts[1..]
         .iter()
         .map(|filename| {
             let bytes = fs::read(filename)
@@ -495,7 +708,7 @@ fn parse_mint_config(src: &str) -> Result<(u64, SignerSet<Ed25519Public>), Strin
     }
 
     // Success.
-    Ok((mint_limit, SignerSet::new(public_keys, threshold)))
+    Ok(SignerSet::new(public_keys, threshold))
 }
 
 fn get_or_generate_nonce(nonce: Option<[u8; NONCE_LENGTH]>) -> Vec<u8> {
@@ -510,3 +723,8 @@ fn get_or_generate_nonce(nonce: Option<[u8; NONCE_LENGTH]>) -> Vec<u8> {
 fn load_tx_file_from_path(path: &str) -> Result<TxFile, String> {
     TxFile::from_json_file(path).map_err(|e| format!("failed loading file {:?}: {}", path, e))
 }
+
+fn load_signing_ceremony_from_path(path: &str) -> Result<SigningCeremony, String> {
+    SigningCeremony::from_json_file(path)
+        .map_err(|e| format!("failed loading signing ceremony {:?}: {}", path, e))
+}
@@ -1,9 +1,11 @@
 // Copyright (c) 2018-2022 The MobileCoin Foundation
 
+mod ceremony;
 mod config;
 mod tx_file;
 
 pub mod printers;
 
+pub use ceremony::{CeremonyError, SignerSetStatus, SigningCeremony};
 pub use config::{Commands, Config};
 pub use tx_file::TxFile;
@@ -2,14 +2,17 @@
 
 //! Utility functions for printing objects in a human-friendly way.
 
+use crate::SigningCeremony;
 use mc_account_keys::PublicAddress;
 use mc_api::printable::PrintableWrapper;
+use mc_consensus_api::consensus_config::ActiveMintConfigs;
 use mc_crypto_keys::{DistinguishedEncoding, Ed25519Public, Ed25519Signature};
 use mc_crypto_multisig::{MultiSig, SignerSet};
 use mc_transaction_core::mint::{
-    MintConfig, MintConfigTx, MintConfigTxPrefix, MintTx, MintTxPrefix,
+    MintConfig, MintConfigTx, MintConfigTxPrefix, MintLimitWindow, MintTx, MintTxPrefix,
 };
 use pem::Pem;
+use std::convert::TryFrom;
 
 const INDENT_STR: &str = "    ";
 const PEM_TAG_SIGNATURE: &str = "SIGNATURE";
@@ -50,9 +53,53 @@ pub fn print_mint_config(mint_config: &MintConfig, indent: usize) {
     indent_str.push_str(INDENT_STR);
     println!("{}Token id: {}", indent_str, mint_config.token_id);
     println!("{}Mint limit: {}", indent_str, mint_config.mint_limit);
+    for window in &mint_config.mint_limit_windows {
+        print_mint_limit_window(window, indent + 1);
+    }
     print_signer_set(&mint_config.signer_set, indent + 1);
 }
 
+pub fn print_mint_limit_window(window: &MintLimitWindow, indent: usize) {
+    let indent_str = INDENT_STR.repeat(indent);
+    println!(
+        "{}Mint limit window: {} per {} block(s)",
+        indent_str, window.limit, window.num_blocks
+    );
+}
+
+pub fn print_active_mint_configs(active_mint_configs: &ActiveMintConfigs, indent: usize) {
+    let mut indent_str = INDENT_STR.repeat(indent);
+    println!(
+        "{}Active mint configs ({} config(s)):",
+        indent_str,
+        active_mint_configs.get_configs().len()
+    );
+
+    indent_str.push_str(INDENT_STR);
+    for active_mint_config in active_mint_configs.get_configs() {
+        let mint_config = MintConfig::try_from(active_mint_config.get_mint_config())
+            .expect("failed converting mint config");
+        print_mint_config(&mint_config, indent + 1);
+
+        let inner_indent_str = INDENT_STR.repeat(indent + 2);
+        println!(
+            "{}Total minted: {}",
+            inner_indent_str,
+            active_mint_config.get_total_minted()
+        );
+        for capacity in active_mint_config.get_mint_limit_window_capacities() {
+            println!(
+                "{}Window of {} block(s): {} minted, {} of {} remaining",
+                inner_indent_str,
+                capacity.get_window().get_num_blocks(),
+                capacity.get_minted(),
+                capacity.get_remaining(),
+                capacity.get_window().get_limit()
+            );
+        }
+    }
+}
+
 pub fn print_mint_tx(tx: &MintTx, indent: usize) {
     let indent_str = INDENT_STR.repeat(indent);
     println!("{}MintTx:", indent_str);
@@ -92,6 +139,16 @@ pub fn print_signer_set(signer_set: &SignerSet<Ed25519Public>, indent: usize) {
     for signer in signer_set.signers() {
         print_pem(signer, PEM_TAG_PUBLIC_KEY, indent + 2);
     }
+    if !signer_set.multi_signers().is_empty() {
+        println!(
+            "{}Nested signer sets ({} set(s)):",
+            indent_str,
+            signer_set.multi_signers().len()
+        );
+        for multi_signer in signer_set.multi_signers() {
+            print_signer_set(multi_signer, indent + 2);
+        }
+    }
     println!("{}Threshold: {}", indent_str, signer_set.threshold());
 }
 
@@ -107,6 +164,44 @@ pub fn print_multi_sig(multi_sig: &MultiSig<Ed25519Signature>, indent: usize) {
     }
 }
 
+pub fn print_signing_ceremony_status(ceremony: &SigningCeremony, indent: usize) {
+    let mut indent_str = INDENT_STR.repeat(indent);
+    println!(
+        "{}Signing ceremony ({}):",
+        indent_str,
+        if ceremony.is_complete() {
+            "threshold met"
+        } else {
+            "threshold not met"
+        }
+    );
+    println!("{}Message: {}", indent_str, hex::encode(ceremony.message()));
+
+    indent_str.push_str(INDENT_STR);
+    for status in ceremony.status() {
+        println!(
+            "{}Signer set ({} of {} signer(s) signed, threshold {}):",
+            indent_str,
+            status.signed.len(),
+            status.signed.len() + status.missing.len(),
+            if status.is_complete() {
+                "met"
+            } else {
+                "not met"
+            }
+        );
+        let inner_indent_str = INDENT_STR.repeat(indent + 2);
+        println!("{}Signed:", inner_indent_str);
+        for signer in &status.signed {
+            print_pem(signer, PEM_TAG_PUBLIC_KEY, indent + 3);
+        }
+        println!("{}Missing:", inner_indent_str);
+        for signer in &status.missing {
+            print_pem(signer, PEM_TAG_PUBLIC_KEY, indent + 3);
+        }
+    }
+}
+
 pub fn print_pem(obj: &impl DistinguishedEncoding, tag: &str, indent: usize) {
     let indent_str = INDENT_STR.repeat(indent);
     let pem_str = pem::encode(&Pem {
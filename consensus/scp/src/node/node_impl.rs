@@ -160,6 +160,21 @@ impl<V: Value, ValidationError: Clone + Display + 'static> ScpNode<V> for Node<V
         self.Q.clone()
     }
 
+    /// Replace the local node quorum set, starting with the next slot.
+    fn set_quorum_set(&mut self, quorum_set: QuorumSet) {
+        debug_assert!(quorum_set.is_valid());
+        if quorum_set != self.Q {
+            log::info!(
+                self.logger,
+                "Quorum set will change from {:?} to {:?} starting with slot {}",
+                self.Q,
+                quorum_set,
+                self.current_slot_index() + 1,
+            );
+        }
+        self.Q = quorum_set;
+    }
+
     /// Propose values for this node to nominate.
     fn propose_values(&mut self, values: BTreeSet<V>) -> Result<Option<Msg<V>>, String> {
         if values.is_empty() {
@@ -732,6 +747,27 @@ mod tests {
         assert_eq!(node.externalized_slots.len(), 0);
     }
 
+    #[test_with_logger]
+    // A new quorum set should be used for slots created after it was set.
+    fn test_set_quorum_set(logger: Logger) {
+        let slot_index = 3;
+        let mut node = get_node(slot_index, logger);
+        let old_quorum_set = node.quorum_set();
+
+        let new_quorum_set =
+            QuorumSet::new_with_node_ids(2, vec![test_node_id(2), test_node_id(3)]);
+        node.set_quorum_set(new_quorum_set.clone());
+        assert_eq!(node.quorum_set(), new_quorum_set);
+        assert_ne!(node.quorum_set(), old_quorum_set);
+
+        // The current slot is left untouched.
+        assert_eq!(node.current_slot_index(), slot_index);
+
+        node.reset_slot_index(slot_index + 1);
+        assert_eq!(node.current_slot_index(), slot_index + 1);
+        assert_eq!(node.quorum_set(), new_quorum_set);
+    }
+
     #[test_with_logger]
     /// Steps through a sequence of messages that allow a two-node network to
     /// reach consensus.
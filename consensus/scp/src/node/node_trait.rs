@@ -14,6 +14,11 @@ pub trait ScpNode<V: Value>: Send {
     /// Get local node quorum set.
     fn quorum_set(&self) -> QuorumSet;
 
+    /// Replace the local node quorum set. The current slot keeps using the
+    /// quorum set it was created with; the new quorum set takes effect starting
+    /// with the next slot.
+    fn set_quorum_set(&mut self, quorum_set: QuorumSet);
+
     /// Propose values for this node to nominate.
     fn propose_values(&mut self, values: BTreeSet<V>) -> Result<Option<Msg<V>>, String>;
 
@@ -80,6 +80,15 @@ pub enum Error {
     /// Missing tx_source_urls
     MissingTxSourceUrls,
 
+    /// Invalid quorum set: {0}
+    InvalidQuorumSet(String),
+
+    /// Quorum set member {0} does not appear in broadcast_peers or known_peers
+    UnknownQuorumSetMember(ResponderId),
+
+    /// Node id mismatch for {0}
+    NodeIdMismatch(ResponderId),
+
     /// Missing governors_signature configuration key
     MissingGovernorsSignature,
 
@@ -50,6 +50,12 @@ pub struct Config {
     #[clap(long = "network", parse(from_os_str), env = "MC_NETWORK")]
     pub network_path: PathBuf,
 
+    /// How often to check the network configuration file for changes, in
+    /// seconds. Changes to the quorum set and peers are applied without
+    /// restarting the node. Set to 0 to disable.
+    #[clap(long, default_value = "10", parse(try_from_str = parse_duration_in_seconds), env = "MC_NETWORK_RELOAD_INTERVAL")]
+    pub network_reload_interval: Duration,
+
     /// Your Intel IAS API key.
     #[clap(long, env = "MC_IAS_API_KEY")]
     pub ias_api_key: String,
@@ -92,6 +98,17 @@ pub struct Config {
     #[clap(long, parse(from_os_str), env = "MC_SCP_DEBUG_DUMP")]
     pub scp_debug_dump: Option<PathBuf>,
 
+    /// Path to the file pending transactions are persisted to, so that they
+    /// survive restarts. Transactions are sealed by the enclave before being
+    /// written. If not provided, pending transactions are lost on restart.
+    #[clap(long, parse(from_os_str), env = "MC_TX_POOL_PATH")]
+    pub tx_pool_path: Option<PathBuf>,
+
+    /// How often to persist pending transactions, in seconds (only relevant
+    /// when --tx-pool-path is used).
+    #[clap(long, default_value = "10", parse(try_from_str = parse_duration_in_seconds), env = "MC_TX_POOL_PERSIST_INTERVAL")]
+    pub tx_pool_persist_interval: Duration,
+
     /// Path to the sealed block signing key
     #[clap(long, parse(from_os_str), env = "MC_SEALED_BLOCK_SIGNING_KEY")]
     pub sealed_block_signing_key: PathBuf,
@@ -187,6 +204,7 @@ mod tests {
             )
             .unwrap(),
             network_path: PathBuf::from("network.toml"),
+            network_reload_interval: Duration::from_secs(10),
             ias_api_key: "".to_string(),
             ias_spid: ProviderId::from_str("22222222222222222222222222222222").unwrap(),
             peer_listen_uri: PeerUri::from_str("insecure-mcp://0.0.0.0:8081/").unwrap(),
@@ -194,6 +212,8 @@ mod tests {
             admin_listen_uri: Some(AdminUri::from_str("insecure-mca://0.0.0.0:9090/").unwrap()),
             ledger_path: PathBuf::default(),
             scp_debug_dump: None,
+            tx_pool_path: None,
+            tx_pool_persist_interval: Duration::from_secs(10),
             origin_block_path: None,
             sealed_block_signing_key: PathBuf::default(),
             client_auth_token_secret: None,
@@ -254,6 +274,7 @@ mod tests {
                 "MC4CAQAwBQYDK2VwBCIEIC50QXQll2Y9qxztvmsUgcBBIxkmk7EQjxzQTa926bKo",
             ) .unwrap(),
             network_path: PathBuf::from("network.toml"),
+            network_reload_interval: Duration::from_secs(10),
             ias_api_key: "".to_string(),
             ias_spid: ProviderId::from_str("22222222222222222222222222222222").unwrap(),
             peer_listen_uri: PeerUri::from_str("mcp://0.0.0.0:8443/?tls-chain=./public/attest/test_certs/selfsigned_mobilecoin.crt&tls-key=./public/attest/test_certs/selfsigned_mobilecoin.key").unwrap(),
@@ -261,6 +282,8 @@ mod tests {
             admin_listen_uri: Some(AdminUri::from_str("insecure-mca://0.0.0.0:9090/").unwrap()),
             ledger_path: PathBuf::default(),
             scp_debug_dump: None,
+            tx_pool_path: None,
+            tx_pool_persist_interval: Duration::from_secs(10),
             origin_block_path: None,
             sealed_block_signing_key: PathBuf::default(),
             client_auth_token_secret: None,
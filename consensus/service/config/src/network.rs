@@ -79,39 +79,34 @@ impl NetworkConfig {
 
     /// Construct a quorum set from the configuration.
     pub fn quorum_set(&self) -> QuorumSet {
+        self.try_quorum_set()
+            .unwrap_or_else(|err| panic!("invalid quorum set: {}", err))
+    }
+
+    /// Construct a quorum set from the configuration, returning an error if
+    /// the configured quorum set is invalid or cannot be resolved into node
+    /// ids using the configured peers.
+    pub fn try_quorum_set(&self) -> Result<QuorumSet, Error> {
         if !self.quorum_set.is_valid() {
-            panic!("invalid quorum set: {:?}", self.quorum_set);
+            return Err(Error::InvalidQuorumSet(format!("{:?}", self.quorum_set)));
         }
 
-        let mut peer_map: HashMap<ResponderId, NodeID> = self
+        let mut peer_map: HashMap<ResponderId, NodeID> = HashMap::default();
+        for uri in self
             .broadcast_peers
             .iter()
-            .cloned()
-            .map(|uri| {
-                (
-                    uri.responder_id().unwrap_or_else(|e| {
-                        panic!("unable to get responder_id for {}: {:?}", uri, e)
-                    }),
-                    uri.node_id()
-                        .unwrap_or_else(|e| panic!("unable to get node_id for {}: {:?}", uri, e)),
-                )
-            })
-            .collect();
-
-        if let Some(known_peers) = self.known_peers.as_ref() {
-            for uri in known_peers.iter() {
-                let responder_id = uri
-                    .responder_id()
-                    .unwrap_or_else(|e| panic!("unable to get responder_id for {}: {:?}", uri, e));
-                let node_id = uri
-                    .node_id()
-                    .unwrap_or_else(|e| panic!("unable to get node_id for {}: {:?}", uri, e));
-                if peer_map.get(&responder_id).unwrap_or(&node_id) != &node_id {
-                    panic!("node id mismatch for {}", responder_id);
-                } else {
-                    peer_map.insert(responder_id, node_id);
-                }
+            .chain(self.known_peers.iter().flatten())
+        {
+            let responder_id = uri
+                .responder_id()
+                .map_err(|err| Error::UriConversion(uri.to_string(), err))?;
+            let node_id = uri
+                .node_id()
+                .map_err(|err| Error::UriConversion(uri.to_string(), err))?;
+            if peer_map.get(&responder_id).unwrap_or(&node_id) != &node_id {
+                return Err(Error::NodeIdMismatch(responder_id));
             }
+            peer_map.insert(responder_id, node_id);
         }
 
         Self::resolve_quorum_set(&self.quorum_set, &peer_map)
@@ -127,25 +122,23 @@ impl NetworkConfig {
     fn resolve_quorum_set(
         src: &QuorumSet<ResponderId>,
         peer_map: &HashMap<ResponderId, NodeID>,
-    ) -> QuorumSet<NodeID> {
+    ) -> Result<QuorumSet<NodeID>, Error> {
         let mut new_members = Vec::with_capacity(src.members.len());
         for member in src.members.iter() {
             let new_member = match member {
                 QuorumSetMember::Node(responder_id) => QuorumSetMember::Node(
                     peer_map
                         .get(responder_id)
-                        .unwrap_or_else(|| {
-                            panic!("Unknown responder_id {} in quorum set", responder_id)
-                        })
+                        .ok_or_else(|| Error::UnknownQuorumSetMember(responder_id.clone()))?
                         .clone(),
                 ),
                 QuorumSetMember::InnerSet(qs_config) => {
-                    QuorumSetMember::InnerSet(Self::resolve_quorum_set(qs_config, peer_map))
+                    QuorumSetMember::InnerSet(Self::resolve_quorum_set(qs_config, peer_map)?)
                 }
             };
             new_members.push(new_member);
         }
-        QuorumSet::new(src.threshold, new_members)
+        Ok(QuorumSet::new(src.threshold, new_members))
     }
 }
 
@@ -288,4 +281,38 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_try_quorum_set_errors() {
+        // Invalid quorum set (threshold larger than number of members).
+        let input_toml: &str = r#"
+            broadcast_peers = []
+            tx_source_urls = []
+            quorum_set = { threshold = 2, members = [] }
+        "#;
+        let network: NetworkConfig = toml::from_str(input_toml).expect("failed parsing toml");
+        assert!(matches!(
+            network.try_quorum_set(),
+            Err(Error::InvalidQuorumSet(_))
+        ));
+
+        // Quorum set referencing a node that is not a known peer.
+        let input_toml: &str = r#"
+            broadcast_peers = [
+                "insecure-mcp://0.0.0.0:8082?consensus-msg-key=MCowBQYDK2VwAyEA_ii3rCch5qhMbLZ2vVgpQr1iTrq1BBN2-i0mMPuAJhQ=",
+            ]
+            tx_source_urls = ["file:///tmp/dump"]
+            quorum_set = { threshold = 2, members = [
+                { type = "Node", args = "0.0.0.0:8082" },
+                { type = "Node", args = "0.0.0.0:8083" },
+            ] }
+        "#;
+        let network: NetworkConfig = toml::from_str(input_toml).expect("failed parsing toml");
+        match network.try_quorum_set() {
+            Err(Error::UnknownQuorumSetMember(responder_id)) => {
+                assert_eq!(responder_id, ResponderId::from_str("0.0.0.0:8083").unwrap())
+            }
+            other => panic!("Unexpected result: {:?}", other),
+        }
+    }
 }
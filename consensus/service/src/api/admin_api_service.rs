@@ -0,0 +1,148 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! Serves consensus-specific admin API requests.
+
+use crate::network_config_reloader::{
+    NetworkConfigChanges, NetworkConfigReloadError, ReloadNetworkConfigFn,
+};
+use grpcio::{RpcContext, RpcStatus, UnarySink};
+use mc_common::logger::{log, Logger};
+use mc_consensus_api::{
+    consensus_admin::ReloadNetworkConfigResponse, consensus_admin_grpc::ConsensusAdminApi,
+    empty::Empty,
+};
+use mc_util_grpc::{rpc_invalid_arg_error, rpc_logger, rpc_unavailable_error, send_result};
+use mc_util_metrics::SVC_COUNTERS;
+use protobuf::RepeatedField;
+
+#[derive(Clone)]
+pub struct AdminApiService {
+    /// Reloads the network configuration.
+    reload_network_config: ReloadNetworkConfigFn,
+
+    /// Logger.
+    logger: Logger,
+}
+
+impl AdminApiService {
+    pub fn new(reload_network_config: ReloadNetworkConfigFn, logger: Logger) -> Self {
+        Self {
+            reload_network_config,
+            logger,
+        }
+    }
+
+    fn handle_reload_network_config(
+        &self,
+        logger: &Logger,
+    ) -> Result<ReloadNetworkConfigResponse, RpcStatus> {
+        match (self.reload_network_config)() {
+            Ok(changes) => {
+                log::info!(logger, "Network configuration reloaded: {:?}", changes);
+                Ok(reload_network_config_response(changes))
+            }
+            Err(err @ NetworkConfigReloadError::ShuttingDown) => {
+                Err(rpc_unavailable_error("reload_network_config", err, logger))
+            }
+            Err(err) => Err(rpc_invalid_arg_error("reload_network_config", err, logger)),
+        }
+    }
+}
+
+impl ConsensusAdminApi for AdminApiService {
+    fn reload_network_config(
+        &mut self,
+        ctx: RpcContext,
+        _request: Empty,
+        sink: UnarySink<ReloadNetworkConfigResponse>,
+    ) {
+        let _timer = SVC_COUNTERS.req(&ctx);
+        mc_common::logger::scoped_global_logger(&rpc_logger(&ctx, &self.logger), |logger| {
+            let result = self.handle_reload_network_config(logger);
+            send_result(ctx, sink, result, logger)
+        });
+    }
+}
+
+fn reload_network_config_response(changes: NetworkConfigChanges) -> ReloadNetworkConfigResponse {
+    let mut response = ReloadNetworkConfigResponse::new();
+    response.set_changed(!changes.is_empty());
+    response.set_added_peers(RepeatedField::from_vec(
+        changes
+            .added_peers
+            .iter()
+            .map(|responder_id| responder_id.to_string())
+            .collect(),
+    ));
+    response.set_removed_peers(RepeatedField::from_vec(
+        changes
+            .removed_peers
+            .iter()
+            .map(|responder_id| responder_id.to_string())
+            .collect(),
+    ));
+    response.set_quorum_set_changed(changes.quorum_set_changed);
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use grpcio::{ChannelBuilder, Environment, RpcStatusCode, Server, ServerBuilder};
+    use mc_common::{
+        logger::{test_with_logger, Logger},
+        ResponderId,
+    };
+    use mc_consensus_api::consensus_admin_grpc::{
+        create_consensus_admin_api, ConsensusAdminApiClient,
+    };
+    use mc_consensus_service_config::Error as ConfigError;
+    use std::{str::FromStr, sync::Arc};
+
+    fn get_client_server(instance: AdminApiService) -> (ConsensusAdminApiClient, Server) {
+        let service = create_consensus_admin_api(instance);
+        let env = Arc::new(Environment::new(1));
+        let mut server = ServerBuilder::new(env.clone())
+            .register_service(service)
+            .bind("127.0.0.1", 0)
+            .build()
+            .unwrap();
+        server.start();
+        let (_, port) = server.bind_addrs().next().unwrap();
+        let ch = ChannelBuilder::new(env).connect(&format!("127.0.0.1:{}", port));
+        let client = ConsensusAdminApiClient::new(ch);
+        (client, server)
+    }
+
+    #[test_with_logger]
+    fn test_reload_network_config_reports_changes(logger: Logger) {
+        let reload_fn: ReloadNetworkConfigFn = Arc::new(|| {
+            Ok(NetworkConfigChanges {
+                added_peers: vec![ResponderId::from_str("peer1:443").unwrap()],
+                removed_peers: vec![],
+                quorum_set_changed: true,
+            })
+        });
+        let (client, _server) = get_client_server(AdminApiService::new(reload_fn, logger));
+
+        let response = client.reload_network_config(&Empty::new()).unwrap();
+        assert!(response.get_changed());
+        assert_eq!(response.get_added_peers(), &["peer1:443".to_string()]);
+        assert!(response.get_removed_peers().is_empty());
+        assert!(response.get_quorum_set_changed());
+    }
+
+    #[test_with_logger]
+    fn test_reload_network_config_invalid_config(logger: Logger) {
+        let reload_fn: ReloadNetworkConfigFn =
+            Arc::new(|| Err(NetworkConfigReloadError::Config(ConfigError::PathExtension)));
+        let (client, _server) = get_client_server(AdminApiService::new(reload_fn, logger));
+
+        match client.reload_network_config(&Empty::new()) {
+            Err(grpcio::Error::RpcFailure(rpc_status)) => {
+                assert_eq!(rpc_status.code(), RpcStatusCode::INVALID_ARGUMENT);
+            }
+            result => panic!("Unexpected result {:?}", result),
+        }
+    }
+}
@@ -8,22 +8,34 @@ use crate::{
     counters,
     mint_tx_manager::MintTxManager,
     tx_manager::{TxManager, TxManagerError},
+    tx_status_tracker::{TxStatusEvent, TxStatusTracker},
 };
-use grpcio::{RpcContext, RpcStatus, UnarySink};
+use futures::{
+    stream::{self, BoxStream},
+    FutureExt, SinkExt, StreamExt, TryFutureExt,
+};
+use grpcio::{RpcContext, RpcStatus, ServerStreamingSink, UnarySink, WriteFlags};
 use mc_attest_api::attest::Message;
-use mc_common::logger::Logger;
+use mc_common::logger::{log, Logger};
 use mc_consensus_api::{
-    consensus_client::{ProposeMintConfigTxResponse, ProposeMintTxResponse},
+    consensus_client::{
+        ProposeMintConfigTxResponse, ProposeMintTxResponse, SubscribeTxStatusRequest, TxStatus,
+        TxStatusUpdate,
+    },
     consensus_client_grpc::ConsensusClientApi,
     consensus_common::{ProposeTxResponse, ProposeTxResult},
-    consensus_config::{ConsensusNodeConfig, TokenConfig},
+    consensus_config::{ActiveMintConfigs, ConsensusNodeConfig, TokenConfig},
     empty::Empty,
 };
 use mc_consensus_enclave::ConsensusEnclave;
 use mc_consensus_service_config::Config;
 use mc_ledger_db::Ledger;
 use mc_peers::ConsensusValue;
-use mc_transaction_core::mint::{MintConfigTx, MintTx};
+use mc_transaction_core::{
+    mint::{MintConfigTx, MintTx},
+    ring_signature::KeyImage,
+    tx::TxHash,
+};
 use mc_util_grpc::{rpc_logger, send_result, Authenticator};
 use mc_util_metrics::{self, SVC_COUNTERS};
 use std::{convert::TryFrom, sync::Arc};
@@ -44,6 +56,8 @@ pub struct ClientApiService {
     /// Returns true if this node is able to process proposed transactions.
     is_serving_fn: Arc<(dyn Fn() -> bool + Sync + Send)>,
     authenticator: Arc<dyn Authenticator + Send + Sync>,
+    /// Tracks the status of transactions submitted by clients.
+    tx_status_tracker: Arc<TxStatusTracker>,
     logger: Logger,
 }
 
@@ -57,6 +71,7 @@ impl ClientApiService {
         mint_tx_manager: Arc<dyn MintTxManager + Send + Sync>,
         is_serving_fn: Arc<(dyn Fn() -> bool + Sync + Send)>,
         authenticator: Arc<dyn Authenticator + Send + Sync>,
+        tx_status_tracker: Arc<TxStatusTracker>,
         logger: Logger,
     ) -> Self {
         Self {
@@ -68,6 +83,7 @@ impl ClientApiService {
             propose_tx_callback: scp_client_value_sender,
             is_serving_fn,
             authenticator,
+            tx_status_tracker,
             logger,
         }
     }
@@ -83,6 +99,7 @@ impl ClientApiService {
     ) -> Result<ProposeTxResponse, ConsensusGrpcError> {
         counters::ADD_TX_INITIATED.inc();
         let tx_context = self.enclave.client_tx_propose(msg.into())?;
+        let key_images = tx_context.key_images.clone();
         let mut response = ProposeTxResponse::new();
 
         // Cache the transaction. This performs the well-formedness checks.
@@ -100,6 +117,15 @@ impl ClientApiService {
         // transaction.
         self.tx_manager.validate(&tx_hash)?;
 
+        // Allow the client to subscribe to the status of this transaction.
+        if !self.tx_status_tracker.track(tx_hash, key_images) {
+            log::debug!(
+                self.logger,
+                "Tx status tracker is full, not tracking {}",
+                tx_hash
+            );
+        }
+
         // The transaction can be considered by the network.
         (*self.propose_tx_callback)(ConsensusValue::TxHash(tx_hash), None, None);
         counters::ADD_TX.inc();
@@ -157,9 +183,60 @@ impl ClientApiService {
         Ok(response)
     }
 
+    /// Handles a client's request to subscribe to the status of a previously
+    /// proposed transaction.
+    ///
+    /// # Arguments
+    /// `request` - Identifies the transaction, by hash or by key image.
+    fn handle_subscribe_tx_status(
+        &self,
+        request: SubscribeTxStatusRequest,
+    ) -> Result<BoxStream<'static, TxStatusUpdate>, ConsensusGrpcError> {
+        if request.has_key_image() {
+            let key_image = KeyImage::try_from(request.get_key_image())
+                .map_err(|err| ConsensusGrpcError::InvalidArgument(format!("{:?}", err)))?;
+
+            // Several transactions may spend the same key image, so report on all of
+            // them.
+            let subscriptions = self.tx_status_tracker.subscribe_by_key_image(&key_image);
+            if !subscriptions.is_empty() {
+                return Ok(stream::select_all(subscriptions.into_iter().map(
+                    |(tx_hash, events)| {
+                        events
+                            .map(move |event| tx_status_update(Some(&tx_hash), event))
+                            .boxed()
+                    },
+                ))
+                .boxed());
+            }
+
+            // The transaction is not tracked, but it might have already made it into the
+            // ledger.
+            return match self.ledger.check_key_image(&key_image)? {
+                Some(block_index) => Ok(stream::once(async move {
+                    tx_status_update(None, TxStatusEvent::Externalized(block_index))
+                })
+                .boxed()),
+                None => Err(ConsensusGrpcError::NotFound(
+                    "No transaction with this key image is known".into(),
+                )),
+            };
+        }
+
+        let tx_hash = TxHash::try_from(request.get_tx_hash())
+            .map_err(|err| ConsensusGrpcError::InvalidArgument(format!("{:?}", err)))?;
+        let events = self.tx_status_tracker.subscribe(&tx_hash).ok_or_else(|| {
+            ConsensusGrpcError::NotFound(format!("Tx {} is not tracked", tx_hash))
+        })?;
+        Ok(events
+            .map(move |event| tx_status_update(Some(&tx_hash), event))
+            .boxed())
+    }
+
     /// Get the node's configuration.
     fn get_node_config_impl(&self) -> Result<ConsensusNodeConfig, ConsensusGrpcError> {
         let tokens_config = self.config.tokens();
+        let num_blocks = self.ledger.num_blocks()?;
 
         let token_config_map = tokens_config
             .tokens()
@@ -177,7 +254,25 @@ impl ClientApiService {
                     .ledger
                     .get_active_mint_configs(token_config.token_id())?;
                 if let Some(active_mint_configs) = active_mint_configs.as_ref() {
-                    grpc_token_config.set_active_mint_configs(active_mint_configs.into());
+                    let mut grpc_active_mint_configs = ActiveMintConfigs::from(active_mint_configs);
+
+                    // Report how much can still be minted within each mint limit window, as
+                    // of the block that is being built.
+                    for (config_index, grpc_active_mint_config) in grpc_active_mint_configs
+                        .mut_configs()
+                        .iter_mut()
+                        .enumerate()
+                    {
+                        grpc_active_mint_config.set_mint_limit_window_capacities(
+                            active_mint_configs
+                                .mint_limit_window_capacities(config_index, num_blocks)
+                                .iter()
+                                .map(Into::into)
+                                .collect(),
+                        );
+                    }
+
+                    grpc_token_config.set_active_mint_configs(grpc_active_mint_configs);
                 }
 
                 Ok((*token_config.token_id(), grpc_token_config))
@@ -325,6 +420,63 @@ impl ConsensusClientApi for ClientApiService {
             send_result(ctx, sink, result, logger)
         });
     }
+
+    fn subscribe_tx_status(
+        &mut self,
+        ctx: RpcContext,
+        request: SubscribeTxStatusRequest,
+        mut sink: ServerStreamingSink<TxStatusUpdate>,
+    ) {
+        let _timer = SVC_COUNTERS.req(&ctx);
+        let logger = rpc_logger(&ctx, &self.logger);
+
+        let result: Result<BoxStream<'static, TxStatusUpdate>, RpcStatus> =
+            if let Err(err) = self.authenticator.authenticate_rpc(&ctx) {
+                err.into()
+            } else {
+                self.handle_subscribe_tx_status(request)
+                    .map_err(RpcStatus::from)
+            };
+
+        match result {
+            Ok(updates) => {
+                let mut updates = updates.map(|update| Ok((update, WriteFlags::default())));
+                ctx.spawn(
+                    async move {
+                        sink.send_all(&mut updates).await?;
+                        sink.close().await
+                    }
+                    .map_err(move |err: grpcio::Error| {
+                        log::debug!(logger, "failed streaming tx status: {}", err)
+                    })
+                    .map(|_| ()),
+                );
+            }
+            Err(err) => ctx.spawn(
+                sink.fail(err)
+                    .map_err(move |err| log::error!(logger, "failed to reply: {}", err))
+                    .map(|_| ()),
+            ),
+        }
+    }
+}
+
+/// Convert a tracked transaction status event into its protobuf
+/// representation.
+fn tx_status_update(tx_hash: Option<&TxHash>, event: TxStatusEvent) -> TxStatusUpdate {
+    let mut update = TxStatusUpdate::new();
+    if let Some(tx_hash) = tx_hash {
+        update.set_tx_hash(tx_hash.to_vec());
+    }
+    let (status, block_index) = match event {
+        TxStatusEvent::AcceptedToPool => (TxStatus::AcceptedToPool, 0),
+        TxStatusEvent::Nominated(slot_index) => (TxStatus::Nominated, slot_index),
+        TxStatusEvent::Externalized(block_index) => (TxStatus::Externalized, block_index),
+        TxStatusEvent::Expired(block_index) => (TxStatus::Expired, block_index),
+    };
+    update.set_status(status);
+    update.set_block_index(block_index);
+    update
 }
 
 #[cfg(test)]
@@ -334,8 +486,10 @@ mod client_api_tests {
         counters,
         mint_tx_manager::{MintTxManagerError, MockMintTxManager},
         tx_manager::{MockTxManager, TxManagerError},
+        tx_status_tracker::{TxStatusEvent, TxStatusTracker},
     };
     use clap::Parser;
+    use futures::executor::block_on_stream;
     use grpcio::{
         ChannelBuilder, Environment, Error as GrpcError, RpcStatusCode, Server, ServerBuilder,
     };
@@ -346,8 +500,10 @@ mod client_api_tests {
         NodeID, ResponderId,
     };
     use mc_consensus_api::{
-        consensus_client::MintValidationResultCode, consensus_client_grpc,
-        consensus_client_grpc::ConsensusClientApiClient, consensus_common::ProposeTxResult,
+        consensus_client::{MintValidationResultCode, SubscribeTxStatusRequest, TxStatus},
+        consensus_client_grpc,
+        consensus_client_grpc::ConsensusClientApiClient,
+        consensus_common::ProposeTxResult,
     };
     use mc_consensus_enclave::TxContext;
     use mc_consensus_enclave_mock::MockConsensusEnclave;
@@ -464,6 +620,7 @@ mod client_api_tests {
             Arc::new(MockMintTxManager::new()),
             is_serving_fn,
             Arc::new(authenticator),
+            Arc::new(TxStatusTracker::default()),
             logger,
         );
 
@@ -538,6 +695,7 @@ mod client_api_tests {
             Arc::new(MockMintTxManager::new()),
             is_serving_fn,
             Arc::new(authenticator),
+            Arc::new(TxStatusTracker::default()),
             logger,
         );
 
@@ -606,6 +764,7 @@ mod client_api_tests {
             Arc::new(MockMintTxManager::new()),
             is_serving_fn,
             Arc::new(authenticator),
+            Arc::new(TxStatusTracker::default()),
             logger,
         );
 
@@ -654,6 +813,7 @@ mod client_api_tests {
             Arc::new(MockMintTxManager::new()),
             is_serving_fn,
             Arc::new(authenticator),
+            Arc::new(TxStatusTracker::default()),
             logger,
         );
 
@@ -704,6 +864,7 @@ mod client_api_tests {
             Arc::new(MockMintTxManager::new()),
             is_serving_fn,
             Arc::new(authenticator),
+            Arc::new(TxStatusTracker::default()),
             logger,
         );
 
@@ -758,6 +919,7 @@ mod client_api_tests {
             Arc::new(MockMintTxManager::new()),
             is_serving_fn,
             Arc::new(authenticator),
+            Arc::new(TxStatusTracker::default()),
             logger,
         );
 
@@ -820,6 +982,7 @@ mod client_api_tests {
             Arc::new(mint_tx_manager),
             is_serving_fn,
             Arc::new(authenticator),
+            Arc::new(TxStatusTracker::default()),
             logger,
         );
 
@@ -890,6 +1053,7 @@ mod client_api_tests {
             Arc::new(mint_tx_manager),
             is_serving_fn,
             Arc::new(authenticator),
+            Arc::new(TxStatusTracker::default()),
             logger,
         );
 
@@ -941,6 +1105,7 @@ mod client_api_tests {
             Arc::new(mint_tx_manager),
             is_serving_fn,
             Arc::new(authenticator),
+            Arc::new(TxStatusTracker::default()),
             logger,
         );
 
@@ -994,6 +1159,7 @@ mod client_api_tests {
             Arc::new(mint_tx_manager),
             is_serving_fn,
             Arc::new(authenticator),
+            Arc::new(TxStatusTracker::default()),
             logger,
         );
 
@@ -1056,6 +1222,7 @@ mod client_api_tests {
             Arc::new(mint_tx_manager),
             is_serving_fn,
             Arc::new(authenticator),
+            Arc::new(TxStatusTracker::default()),
             logger,
         );
 
@@ -1116,6 +1283,7 @@ mod client_api_tests {
             Arc::new(mint_tx_manager),
             is_serving_fn,
             Arc::new(authenticator),
+            Arc::new(TxStatusTracker::default()),
             logger,
         );
 
@@ -1196,6 +1364,7 @@ mod client_api_tests {
             Arc::new(mint_tx_manager),
             is_serving_fn,
             Arc::new(authenticator),
+            Arc::new(TxStatusTracker::default()),
             logger,
         );
 
@@ -1252,6 +1421,7 @@ mod client_api_tests {
             Arc::new(mint_tx_manager),
             is_serving_fn,
             Arc::new(authenticator),
+            Arc::new(TxStatusTracker::default()),
             logger,
         );
 
@@ -1310,6 +1480,7 @@ mod client_api_tests {
             Arc::new(mint_tx_manager),
             is_serving_fn,
             Arc::new(authenticator),
+            Arc::new(TxStatusTracker::default()),
             logger,
         );
 
@@ -1377,6 +1548,7 @@ mod client_api_tests {
             Arc::new(mint_tx_manager),
             is_serving_fn,
             Arc::new(authenticator),
+            Arc::new(TxStatusTracker::default()),
             logger,
         );
 
@@ -1394,4 +1566,194 @@ mod client_api_tests {
 
         assert!(submitted_values.lock().unwrap().is_empty());
     }
+
+    #[test_with_logger]
+    #[serial(counters)]
+    // A client should be able to follow a proposed transaction until it is
+    // externalized.
+    fn test_subscribe_tx_status_after_propose(logger: Logger) {
+        let tx_hash = TxHash([7u8; 32]);
+        let key_image = KeyImage::from(7);
+
+        let mut consensus_enclave = MockConsensusEnclave::new();
+        consensus_enclave
+            .expect_client_tx_propose()
+            .times(1)
+            .return_const(Ok(TxContext {
+                tx_hash,
+                key_images: vec![key_image],
+                ..Default::default()
+            }));
+
+        let scp_client_value_sender = Arc::new(
+            |_value: ConsensusValue,
+             _node_id: Option<&NodeID>,
+             _responder_id: Option<&ResponderId>| {},
+        );
+
+        let mut ledger = MockLedger::new();
+        ledger.expect_num_blocks().times(1).return_const(Ok(3));
+
+        let mut tx_manager = MockTxManager::new();
+        tx_manager
+            .expect_insert()
+            .times(1)
+            .return_const(Ok(tx_hash));
+        tx_manager.expect_validate().times(1).return_const(Ok(()));
+
+        let tx_status_tracker = Arc::new(TxStatusTracker::default());
+
+        let instance = ClientApiService::new(
+            get_config(),
+            Arc::new(consensus_enclave),
+            scp_client_value_sender,
+            Arc::new(ledger),
+            Arc::new(tx_manager),
+            Arc::new(MockMintTxManager::new()),
+            Arc::new(|| -> bool { true }),
+            Arc::new(AnonymousAuthenticator::default()),
+            tx_status_tracker.clone(),
+            logger,
+        );
+
+        // gRPC client and server.
+        let (client, _server) = get_client_server(instance);
+        client
+            .client_tx_propose(&Message::default())
+            .expect("client_tx_propose failed");
+
+        let mut request = SubscribeTxStatusRequest::new();
+        request.set_key_image((&key_image).into());
+        let updates = client
+            .subscribe_tx_status(&request)
+            .expect("subscribe_tx_status failed");
+
+        tx_status_tracker.notify(&[tx_hash], TxStatusEvent::Nominated(3));
+        tx_status_tracker.notify(&[tx_hash], TxStatusEvent::Externalized(3));
+
+        let updates = block_on_stream(updates)
+            .collect::<Result<Vec<_>, _>>()
+            .expect("stream failed");
+        let statuses: Vec<_> = updates
+            .iter()
+            .map(|update| (update.get_status(), update.get_block_index()))
+            .collect();
+        assert_eq!(
+            statuses,
+            vec![
+                (TxStatus::AcceptedToPool, 0),
+                (TxStatus::Nominated, 3),
+                (TxStatus::Externalized, 3),
+            ]
+        );
+        assert!(updates
+            .iter()
+            .all(|update| update.get_tx_hash() == tx_hash.to_vec()));
+
+        // Subscribing by hash after the transaction was externalized reports its
+        // final status.
+        let mut request = SubscribeTxStatusRequest::new();
+        request.set_tx_hash(tx_hash.to_vec());
+        let updates = block_on_stream(
+            client
+                .subscribe_tx_status(&request)
+                .expect("subscribe_tx_status failed"),
+        )
+        .collect::<Result<Vec<_>, _>>()
+        .expect("stream failed");
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].get_status(), TxStatus::Externalized);
+        assert_eq!(updates[0].get_block_index(), 3);
+        assert_eq!(updates[0].get_tx_hash(), tx_hash.to_vec());
+    }
+
+    #[test_with_logger]
+    // Should return NOT_FOUND for transactions that are not tracked and whose key
+    // images are not in the ledger.
+    fn test_subscribe_tx_status_not_found(logger: Logger) {
+        let mut ledger = MockLedger::new();
+        ledger.expect_check_key_image().return_const(Ok(None));
+
+        let instance = ClientApiService::new(
+            get_config(),
+            Arc::new(MockConsensusEnclave::new()),
+            Arc::new(
+                |_value: ConsensusValue,
+                 _node_id: Option<&NodeID>,
+                 _responder_id: Option<&ResponderId>| {},
+            ),
+            Arc::new(ledger),
+            Arc::new(MockTxManager::new()),
+            Arc::new(MockMintTxManager::new()),
+            Arc::new(|| -> bool { true }),
+            Arc::new(AnonymousAuthenticator::default()),
+            Arc::new(TxStatusTracker::default()),
+            logger,
+        );
+
+        // gRPC client and server.
+        let (client, _server) = get_client_server(instance);
+
+        let mut by_hash = SubscribeTxStatusRequest::new();
+        by_hash.set_tx_hash(vec![1u8; 32]);
+        let mut by_key_image = SubscribeTxStatusRequest::new();
+        by_key_image.set_key_image((&KeyImage::from(1)).into());
+
+        for request in [by_hash, by_key_image] {
+            let mut updates = block_on_stream(
+                client
+                    .subscribe_tx_status(&request)
+                    .expect("subscribe_tx_status failed"),
+            );
+            match updates.next() {
+                Some(Err(GrpcError::RpcFailure(rpc_status))) => {
+                    assert_eq!(rpc_status.code(), RpcStatusCode::NOT_FOUND);
+                }
+                other => panic!("Unexpected result: {:?}", other),
+            }
+        }
+    }
+
+    #[test_with_logger]
+    // Key images that are already in the ledger resolve to a single Externalized
+    // update.
+    fn test_subscribe_tx_status_key_image_in_ledger(logger: Logger) {
+        let mut ledger = MockLedger::new();
+        ledger.expect_check_key_image().return_const(Ok(Some(12)));
+
+        let instance = ClientApiService::new(
+            get_config(),
+            Arc::new(MockConsensusEnclave::new()),
+            Arc::new(
+                |_value: ConsensusValue,
+                 _node_id: Option<&NodeID>,
+                 _responder_id: Option<&ResponderId>| {},
+            ),
+            Arc::new(ledger),
+            Arc::new(MockTxManager::new()),
+            Arc::new(MockMintTxManager::new()),
+            Arc::new(|| -> bool { true }),
+            Arc::new(AnonymousAuthenticator::default()),
+            Arc::new(TxStatusTracker::default()),
+            logger,
+        );
+
+        // gRPC client and server.
+        let (client, _server) = get_client_server(instance);
+
+        let mut request = SubscribeTxStatusRequest::new();
+        request.set_key_image((&KeyImage::from(1)).into());
+        let updates = block_on_stream(
+            client
+                .subscribe_tx_status(&request)
+                .expect("subscribe_tx_status failed"),
+        )
+        .collect::<Result<Vec<_>, _>>()
+        .expect("stream failed");
+
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].get_status(), TxStatus::Externalized);
+        assert_eq!(updates[0].get_block_index(), 12);
+        assert!(updates[0].get_tx_hash().is_empty());
+    }
 }
@@ -38,6 +38,9 @@ pub enum ConsensusGrpcError {
     /// Invalid argument `{0}`
     InvalidArgument(String),
 
+    /// Not found `{0}`
+    NotFound(String),
+
     /// Other error `{0}`
     Other(String),
 }
@@ -117,6 +120,9 @@ impl From<ConsensusGrpcError> for RpcStatus {
                     "Permission Denied (attestation)".into(),
                 )
             }
+            ConsensusGrpcError::NotFound(err) => {
+                RpcStatus::with_message(RpcStatusCode::NOT_FOUND, err)
+            }
             ConsensusGrpcError::Other(err) => RpcStatus::with_message(RpcStatusCode::INTERNAL, err),
             ConsensusGrpcError::TransactionValidation(err) => {
                 global_log::error!("Attempting to convert a ConsensusGrpcError::TransactionValidation into RpcStatus, this should not happen! Error is: {}", err);
@@ -2,6 +2,7 @@
 
 //! gRPC APIs
 
+mod admin_api_service;
 mod attested_api_service;
 mod blockchain_api_service;
 mod client_api_service;
@@ -9,6 +10,7 @@ mod grpc_error;
 mod peer_api_service;
 mod peer_service_error;
 
+pub use admin_api_service::AdminApiService;
 pub use attested_api_service::AttestedApiService;
 pub use blockchain_api_service::BlockchainApiService;
 pub use client_api_service::ClientApiService;
@@ -17,7 +17,8 @@ use mc_common::{
     ResponderId,
 };
 use mc_consensus_api::{
-    consensus_common::ProposeTxResponse,
+    blockchain::ArchiveBlocks,
+    consensus_common::{BlocksRequest, ProposeTxResponse},
     consensus_peer::{
         ConsensusMsg as GrpcConsensusMsg, ConsensusMsgResponse, ConsensusMsgResult,
         GetLatestMsgResponse, GetTxsRequest, GetTxsResponse, TxHashesNotInCache,
@@ -35,15 +36,24 @@ use mc_util_grpc::{
 use mc_util_metrics::SVC_COUNTERS;
 use mc_util_serial::deserialize;
 use std::{
+    cmp::min,
     convert::{TryFrom, TryInto},
     str::FromStr,
     sync::Arc,
 };
 
+/// Maximum number of blocks returned by a single `get_block_data` call.
+const MAX_BLOCK_DATA_PER_REQUEST: u64 = 100;
+
 // Callback method for returning the latest SCP message issued by the local
 // node, used to implement the `fetch_latest_msg` RPC call.
 type FetchLatestMsgFn = Arc<dyn Fn() -> Option<mc_peers::ConsensusMsg> + Sync + Send>;
 
+// Callback method for returning the responder ids of the peers we are currently
+// connected to. This can change at runtime when the network configuration is
+// reloaded.
+type KnownResponderIdsFn = Arc<dyn Fn() -> Vec<ResponderId> + Sync + Send>;
+
 #[derive(Clone)]
 pub struct PeerApiService {
     /// Enclave instance.
@@ -70,7 +80,7 @@ pub struct PeerApiService {
     /// requests to. That is necessary for resolving TxHashes into Txs. If
     /// we received a consensus message from a peer not on this list, we
     /// won't be able to reach out to it to ask for the transaction contents.
-    known_responder_ids: Vec<ResponderId>,
+    known_responder_ids: KnownResponderIdsFn,
 
     /// Logger.
     logger: Logger,
@@ -97,7 +107,7 @@ impl PeerApiService {
         incoming_consensus_msgs_sender: BackgroundWorkQueueSenderFn<IncomingConsensusMsg>,
         scp_client_value_sender: ProposeTxCallback,
         fetch_latest_msg_fn: FetchLatestMsgFn,
-        known_responder_ids: Vec<ResponderId>,
+        known_responder_ids: KnownResponderIdsFn,
         logger: Logger,
     ) -> Self {
         Self {
@@ -192,7 +202,7 @@ impl PeerApiService {
         from_responder_id: ResponderId,
     ) -> Result<(), PeerServiceError> {
         // Ignore a consensus message from an unknown peer.
-        if !self.known_responder_ids.contains(&from_responder_id) {
+        if !(self.known_responder_ids)().contains(&from_responder_id) {
             return Err(PeerServiceError::UnknownPeer(from_responder_id.to_string()));
         }
 
@@ -228,6 +238,32 @@ impl PeerApiService {
                 }
             })
     }
+
+    /// Returns the blocks (with contents and signatures) in the requested
+    /// range that are present in the local ledger.
+    ///
+    /// # Arguments
+    /// * `offset` - Index of the first requested block.
+    /// * `limit` - Number of requested blocks. Capped at
+    ///   `MAX_BLOCK_DATA_PER_REQUEST`.
+    fn handle_get_block_data(
+        &mut self,
+        offset: u64,
+        limit: u32,
+    ) -> Result<ArchiveBlocks, PeerServiceError> {
+        let num_blocks = self.ledger.num_blocks().map_err(PeerServiceError::Ledger)?;
+        let end = min(
+            offset.saturating_add(min(limit as u64, MAX_BLOCK_DATA_PER_REQUEST)),
+            num_blocks,
+        );
+
+        let block_data = (offset..end)
+            .map(|block_index| self.ledger.get_block_data(block_index))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(PeerServiceError::Ledger)?;
+
+        Ok(ArchiveBlocks::from(&block_data[..]))
+    }
 }
 
 impl ConsensusPeerApi for PeerApiService {
@@ -393,6 +429,22 @@ impl ConsensusPeerApi for PeerApiService {
             send_result(ctx, sink, result, logger)
         });
     }
+
+    /// Returns a range of blocks, with their contents and signatures.
+    fn get_block_data(
+        &mut self,
+        ctx: RpcContext,
+        request: BlocksRequest,
+        sink: UnarySink<ArchiveBlocks>,
+    ) {
+        let _timer = SVC_COUNTERS.req(&ctx);
+        mc_common::logger::scoped_global_logger(&rpc_logger(&ctx, &self.logger), |logger| {
+            let result = self
+                .handle_get_block_data(request.get_offset(), request.get_limit())
+                .map_err(|err| rpc_internal_error("get_block_data", err, logger));
+            send_result(ctx, sink, result, logger)
+        });
+    }
 }
 
 #[cfg(test)]
@@ -400,7 +452,7 @@ mod tests {
     use super::*;
     use crate::{background_work_queue::BackgroundWorkQueueError, tx_manager::MockTxManager};
     use grpcio::{ChannelBuilder, Environment, Error::RpcFailure, Server, ServerBuilder};
-    use mc_blockchain_types::Block;
+    use mc_blockchain_types::{Block, BlockContents, BlockData};
     use mc_common::{logger::test_with_logger, NodeID};
     use mc_consensus_api::{
         consensus_peer::{ConsensusMsg, ConsensusMsgResult},
@@ -453,6 +505,11 @@ mod tests {
         Arc::new(|| None)
     }
 
+    // Always returns the given responder ids.
+    fn get_known_responder_ids_fn(known_responder_ids: Vec<ResponderId>) -> KnownResponderIdsFn {
+        Arc::new(move || known_responder_ids.clone())
+    }
+
     fn get_client_server(instance: PeerApiService) -> (ConsensusPeerApiClient, Server) {
         let service = create_consensus_peer_api(instance);
         let env = Arc::new(Environment::new(1));
@@ -487,7 +544,7 @@ mod tests {
             get_incoming_consensus_msgs_sender_ok(),
             get_scp_client_value_sender(),
             get_fetch_latest_msg_fn(),
-            known_responder_ids,
+            get_known_responder_ids_fn(known_responder_ids),
             logger,
         );
 
@@ -561,7 +618,7 @@ mod tests {
             get_incoming_consensus_msgs_sender_ok(),
             get_scp_client_value_sender(),
             get_fetch_latest_msg_fn(),
-            known_responder_ids.clone(),
+            get_known_responder_ids_fn(known_responder_ids.clone()),
             logger,
         );
 
@@ -627,7 +684,7 @@ mod tests {
             get_incoming_consensus_msgs_sender_ok(),
             get_scp_client_value_sender(),
             get_fetch_latest_msg_fn(),
-            known_responder_ids.clone(),
+            get_known_responder_ids_fn(known_responder_ids.clone()),
             logger,
         );
 
@@ -675,7 +732,7 @@ mod tests {
             get_incoming_consensus_msgs_sender_ok(),
             get_scp_client_value_sender(),
             get_fetch_latest_msg_fn(),
-            known_responder_ids.clone(),
+            get_known_responder_ids_fn(known_responder_ids.clone()),
             logger,
         );
 
@@ -729,6 +786,56 @@ mod tests {
 
     // TODO: fetch_latest_msg
 
+    #[test_with_logger]
+    // Should return the requested blocks that are present in the ledger, capped
+    // at MAX_BLOCK_DATA_PER_REQUEST.
+    fn test_get_block_data(logger: Logger) {
+        let (consensus_enclave, mut ledger, tx_manager) = get_mocks();
+        ledger.expect_num_blocks().return_const(Ok(500));
+        ledger.expect_get_block_data().returning(|_| {
+            Ok(BlockData::new(
+                Block::new_origin_block(&[]),
+                BlockContents::default(),
+                None,
+            ))
+        });
+
+        let instance = PeerApiService::new(
+            Arc::new(consensus_enclave),
+            Arc::new(ledger),
+            Arc::new(tx_manager),
+            get_incoming_consensus_msgs_sender_ok(),
+            get_scp_client_value_sender(),
+            get_fetch_latest_msg_fn(),
+            get_known_responder_ids_fn(vec![]),
+            logger,
+        );
+
+        let (client, _server) = get_client_server(instance);
+
+        // A range within the ledger.
+        let mut request = BlocksRequest::new();
+        request.set_offset(10);
+        request.set_limit(5);
+        let response = client.get_block_data(&request).unwrap();
+        assert_eq!(response.get_blocks().len(), 5);
+
+        // A range past the end of the ledger.
+        request.set_offset(498);
+        request.set_limit(5);
+        let response = client.get_block_data(&request).unwrap();
+        assert_eq!(response.get_blocks().len(), 2);
+
+        // A range larger than the per-request maximum.
+        request.set_offset(0);
+        request.set_limit(1000);
+        let response = client.get_block_data(&request).unwrap();
+        assert_eq!(
+            response.get_blocks().len() as u64,
+            MAX_BLOCK_DATA_PER_REQUEST
+        );
+    }
+
     // TODO: fetch_txs
 
     // TODO: peer_tx_propose
@@ -16,6 +16,9 @@ pub enum PeerServiceError {
     /// Enclave-related error `{0}`.
     Enclave(mc_consensus_enclave::Error),
 
+    /// Ledger error `{0}`.
+    Ledger(mc_ledger_db::Error),
+
     /// Something went wrong...
     InternalError,
 }
@@ -6,6 +6,7 @@
 //! peers.
 
 mod ledger_sync_state;
+mod peer_catchup;
 mod pending_values;
 mod task_message;
 mod worker;
@@ -15,6 +16,7 @@ use crate::{
     counters,
     mint_tx_manager::{MintTxManager, MintTxManagerError},
     tx_manager::{TxManager, TxManagerError},
+    tx_status_tracker::TxStatusTracker,
 };
 use displaydoc::Display;
 use mc_common::{logger::Logger, NodeID, ResponderId};
@@ -105,6 +107,8 @@ impl ByzantineLedger {
     /// * `tx_source_urls` - Source URLs for fetching block contents.
     /// * `scp_debug_dir` - If Some, debugging info will be written in this
     ///   directory.
+    /// * `tx_status_tracker` - Notified as transactions progress through
+    ///   consensus.
     /// * `logger` - Logger.
     pub fn new<
         PC: BlockchainConnection + ConsensusConnection + 'static,
@@ -124,6 +128,7 @@ impl ByzantineLedger {
         msg_signer_key: Arc<Ed25519Pair>,
         tx_source_urls: Vec<String>,
         scp_debug_dir: Option<PathBuf>,
+        tx_status_tracker: Arc<TxStatusTracker>,
         logger: Logger,
     ) -> Self {
         // TODO: this should be passed in as an argument.
@@ -232,6 +237,7 @@ impl ByzantineLedger {
                 is_behind.clone(),
                 highest_peer_block.clone(),
                 highest_issued_msg.clone(),
+                tx_status_tracker,
                 logger,
             );
 
@@ -264,6 +270,14 @@ impl ByzantineLedger {
             .expect("Could not send values");
     }
 
+    /// Replace the local node's quorum set. The change takes effect starting
+    /// with the next slot.
+    pub fn update_quorum_set(&self, quorum_set: QuorumSet) {
+        self.task_sender
+            .send(TaskMessage::UpdateQuorumSet(quorum_set))
+            .expect("Could not send quorum set");
+    }
+
     /// Handle consensus messages received from the network.
     pub fn handle_consensus_msg(
         &self,
@@ -468,6 +482,7 @@ mod tests {
             msg_signer_key,
             Vec::new(),
             None,
+            Arc::new(TxStatusTracker::default()),
             logger.clone(),
         );
 
@@ -560,6 +575,7 @@ mod tests {
             local_signer_key.clone(),
             Vec::new(),
             None,
+            Arc::new(TxStatusTracker::default()),
             logger.clone(),
         );
 
@@ -939,6 +955,7 @@ mod tests {
             local_signer_key.clone(),
             Vec::new(),
             None,
+            Arc::new(TxStatusTracker::default()),
             logger.clone(),
         );
 
@@ -0,0 +1,375 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! Catch-up of a lagging node by fetching signed blocks directly from its
+//! peers.
+//!
+//! Unlike ledger sync, which downloads block contents from the network's
+//! archive, this fetches blocks together with their contents and signatures
+//! over the peer API. Blocks are only appended once they have been verified,
+//! are signed by the attested enclave of one of the peers, and a blocking set
+//! that forms a quorum agrees on them.
+
+use displaydoc::Display;
+use mc_blockchain_types::{Block, BlockData, BlockID, BlockIndex};
+use mc_common::{
+    logger::{log, Logger},
+    ResponderId,
+};
+use mc_connection::{Connection, ConnectionManager, _retry::delay::Fibonacci};
+use mc_crypto_keys::Ed25519Public;
+use mc_ledger_db::{Error as LedgerError, Ledger};
+use mc_ledger_sync::{NetworkState, SCPNetworkState};
+use mc_peers::{ConsensusConnection, RetryableConsensusConnection};
+use mc_util_uri::ConnectionUri;
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Peer catch-up error.
+#[derive(Debug, Display)]
+pub enum PeerCatchupError {
+    /// The network is not ahead of the local ledger
+    NothingToSync,
+
+    /// Ledger error: {0}
+    Ledger(LedgerError),
+
+    /// No blocks were agreed upon by a blocking set that forms a quorum
+    NoQuorumAgreement,
+}
+
+impl From<LedgerError> for PeerCatchupError {
+    fn from(src: LedgerError) -> Self {
+        Self::Ledger(src)
+    }
+}
+
+/// Fetch up to `limit` blocks from peers, and append the ones that a blocking
+/// set forming a quorum agrees on to the local ledger.
+///
+/// Returns the number of appended blocks.
+///
+/// # Arguments
+/// * `ledger` - The local ledger.
+/// * `connection_manager` - Connections to peers.
+/// * `network_state` - Used to determine how far behind the local node is, and
+///   whether a set of peers can be trusted.
+/// * `limit` - Maximum number of blocks to append.
+/// * `logger` - Logger.
+pub fn catch_up_from_peers<L: Ledger, PC: ConsensusConnection + 'static>(
+    ledger: &mut L,
+    connection_manager: &ConnectionManager<PC>,
+    network_state: &SCPNetworkState,
+    limit: u32,
+    logger: &Logger,
+) -> Result<usize, PeerCatchupError> {
+    let num_blocks = ledger.num_blocks()?;
+    let end = match network_state.highest_block_index_on_network() {
+        Some(highest_block_index) if highest_block_index >= num_blocks => {
+            (highest_block_index + 1).min(num_blocks + BlockIndex::from(limit))
+        }
+        _ => return Err(PeerCatchupError::NothingToSync),
+    };
+
+    let last_block = ledger.get_block(num_blocks - 1)?;
+
+    // Query peers concurrently.
+    let node_to_fetched_blocks: HashMap<ResponderId, Vec<BlockData>> = connection_manager
+        .conns()
+        .into_par_iter()
+        .filter_map(|conn| {
+            let responder_id = conn.uri().responder_id().ok()?;
+            match conn.fetch_block_data(num_blocks..end, Fibonacci::from_millis(10).take(5)) {
+                Ok(blocks_data) => Some((responder_id, blocks_data)),
+                Err(err) => {
+                    log::debug!(logger, "Failed fetching blocks from {}: {:?}", conn, err);
+                    None
+                }
+            }
+        })
+        .collect();
+
+    // Blocks must be signed by the enclave of one of our peers. These keys come
+    // from attestation reports, so a peer cannot mint its own.
+    let trusted_signers = connection_manager
+        .conns()
+        .iter()
+        .filter_map(|conn| conn.remote_block_signer())
+        .collect::<Vec<_>>();
+
+    // Keep only the verified prefix of each response.
+    let node_to_blocks: HashMap<ResponderId, Vec<BlockData>> = node_to_fetched_blocks
+        .into_iter()
+        .map(|(responder_id, blocks_data)| {
+            let blocks_data = verified_prefix(&last_block, blocks_data, &trusted_signers);
+            log::debug!(
+                logger,
+                "Received {} verified blocks from {}",
+                blocks_data.len(),
+                responder_id
+            );
+            (responder_id, blocks_data)
+        })
+        .collect();
+
+    let (target_index, responder_id) = select_sync_target(&node_to_blocks, network_state)
+        .ok_or(PeerCatchupError::NoQuorumAgreement)?;
+
+    // Blocks chain back to the local last block, so nodes that agree on the
+    // block at `target_index` also agree on every block before it.
+    let blocks_data = &node_to_blocks[&responder_id][..=(target_index - num_blocks) as usize];
+    for block_data in blocks_data {
+        ledger.append_block(
+            block_data.block(),
+            block_data.contents(),
+            block_data.signature().clone(),
+        )?;
+    }
+
+    log::info!(
+        logger,
+        "Caught up {} blocks from peers, local ledger now has {} blocks",
+        blocks_data.len(),
+        num_blocks + blocks_data.len() as u64,
+    );
+
+    Ok(blocks_data.len())
+}
+
+/// Returns the longest prefix of `blocks_data` that extends `last_block` and
+/// consists of well-formed blocks signed by one of `trusted_signers`.
+fn verified_prefix(
+    last_block: &Block,
+    blocks_data: Vec<BlockData>,
+    trusted_signers: &[Ed25519Public],
+) -> Vec<BlockData> {
+    let mut prev_block = last_block;
+    let mut num_valid = 0;
+
+    for block_data in blocks_data.iter() {
+        let block = block_data.block();
+        let is_valid = block.index == prev_block.index + 1
+            && block.parent_id == prev_block.id
+            && block.is_block_id_valid()
+            && block.contents_hash == block_data.contents().hash()
+            && block_data.signature().as_ref().map_or(false, |signature| {
+                trusted_signers.contains(signature.signer()) && signature.verify(block).is_ok()
+            });
+        if !is_valid {
+            break;
+        }
+
+        prev_block = block;
+        num_valid += 1;
+    }
+
+    let mut blocks_data = blocks_data;
+    blocks_data.truncate(num_valid);
+    blocks_data
+}
+
+/// Returns the highest block index that a blocking set forming a quorum
+/// agrees on, together with a node from that set.
+fn select_sync_target(
+    node_to_blocks: &HashMap<ResponderId, Vec<BlockData>>,
+    network_state: &impl NetworkState,
+) -> Option<(BlockIndex, ResponderId)> {
+    let mut grouping: BTreeMap<BlockIndex, HashMap<BlockID, HashSet<ResponderId>>> =
+        BTreeMap::new();
+    for (responder_id, blocks_data) in node_to_blocks {
+        for block_data in blocks_data {
+            grouping
+                .entry(block_data.block().index)
+                .or_default()
+                .entry(block_data.block().id.clone())
+                .or_default()
+                .insert(responder_id.clone());
+        }
+    }
+
+    // Greedily start with the highest block index, as most of the time all nodes
+    // agree.
+    grouping
+        .iter()
+        .rev()
+        .find_map(|(block_index, block_id_to_nodes)| {
+            block_id_to_nodes.values().find_map(|responder_ids| {
+                if network_state.is_blocking_and_quorum(responder_ids) {
+                    responder_ids
+                        .iter()
+                        .next()
+                        .map(|responder_id| (*block_index, responder_id.clone()))
+                } else {
+                    None
+                }
+            })
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mc_blockchain_types::{BlockContents, BlockSignature};
+    use mc_crypto_keys::Ed25519Pair;
+    use mc_ledger_db::test_utils::mock_ledger::get_test_ledger_blocks;
+    use mc_util_from_random::FromRandom;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    // The key the test blocks are signed with.
+    fn test_signer() -> Ed25519Pair {
+        let mut rng: StdRng = SeedableRng::from_seed([1u8; 32]);
+        Ed25519Pair::from_random(&mut rng)
+    }
+
+    // Signs the given blocks.
+    fn sign_blocks(blocks: Vec<(Block, BlockContents)>) -> Vec<BlockData> {
+        sign_blocks_with(blocks, &test_signer())
+    }
+
+    // Signs the given blocks with the given key.
+    fn sign_blocks_with(
+        blocks: Vec<(Block, BlockContents)>,
+        signer: &Ed25519Pair,
+    ) -> Vec<BlockData> {
+        blocks
+            .into_iter()
+            .map(|(block, contents)| {
+                let signature = BlockSignature::from_block_and_keypair(&block, signer).unwrap();
+                BlockData::new(block, contents, Some(signature))
+            })
+            .collect()
+    }
+
+    // Always agrees with a given set of nodes.
+    struct FixedNetworkState(HashSet<ResponderId>);
+
+    impl NetworkState for FixedNetworkState {
+        fn is_blocking_and_quorum(&self, responder_ids: &HashSet<ResponderId>) -> bool {
+            self.0.is_subset(responder_ids)
+        }
+
+        fn is_behind(&self, _local_block_index: BlockIndex) -> bool {
+            false
+        }
+
+        fn highest_block_index_on_network(&self) -> Option<BlockIndex> {
+            None
+        }
+    }
+
+    #[test]
+    // Should accept a correctly chained sequence of signed blocks.
+    fn test_verified_prefix_valid() {
+        let mut blocks_data = sign_blocks(get_test_ledger_blocks(5));
+        let last_block = blocks_data.remove(0).block().clone();
+
+        let verified = verified_prefix(
+            &last_block,
+            blocks_data.clone(),
+            &[test_signer().public_key()],
+        );
+        assert_eq!(verified, blocks_data);
+    }
+
+    #[test]
+    // Should reject blocks signed by a key that is not a trusted block signer,
+    // even if the signature itself is valid.
+    fn test_verified_prefix_foreign_signer() {
+        let mut rng: StdRng = SeedableRng::from_seed([2u8; 32]);
+        let foreign_signer = Ed25519Pair::from_random(&mut rng);
+        let trusted_signers = [test_signer().public_key()];
+
+        let mut blocks_data = sign_blocks(get_test_ledger_blocks(5));
+        let last_block = blocks_data.remove(0).block().clone();
+
+        // A chain entirely signed by a foreign key.
+        let mut foreign_blocks_data = sign_blocks_with(get_test_ledger_blocks(5), &foreign_signer);
+        foreign_blocks_data.remove(0);
+        assert!(
+            verified_prefix(&last_block, foreign_blocks_data.clone(), &trusted_signers).is_empty()
+        );
+
+        // A foreign signature in the middle of the chain.
+        let mut mixed = blocks_data.clone();
+        mixed[2] = foreign_blocks_data[2].clone();
+        assert_eq!(
+            verified_prefix(&last_block, mixed, &trusted_signers),
+            blocks_data[..2]
+        );
+
+        // Nothing is trusted without any attested peers.
+        assert!(verified_prefix(&last_block, blocks_data, &[]).is_empty());
+    }
+
+    #[test]
+    // Should stop at the first block that is unsigned, tampered with, or does not
+    // chain.
+    fn test_verified_prefix_invalid() {
+        let trusted_signers = [test_signer().public_key()];
+        let mut blocks_data = sign_blocks(get_test_ledger_blocks(5));
+        let last_block = blocks_data.remove(0).block().clone();
+
+        // Missing signature.
+        let mut unsigned = blocks_data.clone();
+        unsigned[2] = BlockData::new(
+            unsigned[2].block().clone(),
+            unsigned[2].contents().clone(),
+            None,
+        );
+        assert_eq!(
+            verified_prefix(&last_block, unsigned, &trusted_signers),
+            blocks_data[..2]
+        );
+
+        // Contents that do not match the block.
+        let mut tampered = blocks_data.clone();
+        tampered[1] = BlockData::new(
+            tampered[1].block().clone(),
+            tampered[3].contents().clone(),
+            tampered[1].signature().clone(),
+        );
+        assert_eq!(
+            verified_prefix(&last_block, tampered, &trusted_signers),
+            blocks_data[..1]
+        );
+
+        // Blocks that do not extend the local ledger.
+        assert!(verified_prefix(
+            blocks_data[0].block(),
+            blocks_data.clone(),
+            &trusted_signers
+        )
+        .is_empty());
+    }
+
+    #[test]
+    // Should pick the highest block agreed upon by a blocking set that forms a
+    // quorum.
+    fn test_select_sync_target() {
+        let blocks_data = sign_blocks(get_test_ledger_blocks(5));
+        let a = ResponderId("a:8443".to_owned());
+        let b = ResponderId("b:8443".to_owned());
+        let c = ResponderId("c:8443".to_owned());
+
+        let mut node_to_blocks = HashMap::new();
+        node_to_blocks.insert(a.clone(), blocks_data[1..5].to_vec());
+        node_to_blocks.insert(b.clone(), blocks_data[1..3].to_vec());
+        node_to_blocks.insert(c.clone(), blocks_data[1..4].to_vec());
+
+        // Nodes a and c agree up to block 3.
+        let network_state = FixedNetworkState(HashSet::from_iter([a.clone(), c.clone()]));
+        let (block_index, responder_id) =
+            select_sync_target(&node_to_blocks, &network_state).unwrap();
+        assert_eq!(block_index, 3);
+        assert!(responder_id == a || responder_id == c);
+
+        // Nodes b and c agree up to block 2.
+        let network_state = FixedNetworkState(HashSet::from_iter([b.clone(), c.clone()]));
+        let (block_index, _) = select_sync_target(&node_to_blocks, &network_state).unwrap();
+        assert_eq!(block_index, 2);
+
+        // No agreement with an unknown node.
+        let network_state =
+            FixedNetworkState(HashSet::from_iter([ResponderId("d:8443".to_owned())]));
+        assert!(select_sync_target(&node_to_blocks, &network_state).is_none());
+    }
+}
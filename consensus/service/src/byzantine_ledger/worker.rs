@@ -2,12 +2,14 @@
 
 use crate::{
     byzantine_ledger::{
-        ledger_sync_state::LedgerSyncState, pending_values::PendingValues,
-        task_message::TaskMessage, IS_BEHIND_GRACE_PERIOD, MAX_PENDING_VALUES_TO_NOMINATE,
+        ledger_sync_state::LedgerSyncState, peer_catchup::catch_up_from_peers,
+        pending_values::PendingValues, task_message::TaskMessage, IS_BEHIND_GRACE_PERIOD,
+        MAX_PENDING_VALUES_TO_NOMINATE,
     },
     counters,
     mint_tx_manager::MintTxManager,
     tx_manager::TxManager,
+    tx_status_tracker::{TxStatusEvent, TxStatusTracker},
 };
 use mc_blockchain_types::BlockData;
 use mc_common::{
@@ -74,6 +76,9 @@ pub struct ByzantineLedgerWorker<
     // Mint tx manager.
     mint_tx_manager: Arc<MTXM>,
 
+    // Tracks the status of client-submitted transactions.
+    tx_status_tracker: Arc<TxStatusTracker>,
+
     // A map of responder id to a list of tx hashes that it is unable to provide. This allows us to
     // skip attempting to fetch txs that are bound to fail. A BTreeSet is used to speed up lookups
     // as expect to be doing more lookups than inserts.
@@ -142,6 +147,8 @@ impl<
     ///   network agrees on.
     /// * `highest_issued_msg` - Worker sets to highest consensus message issued
     ///   by this node.
+    /// * `tx_status_tracker` - Notified as transactions are nominated,
+    ///   externalized or expired.
     /// * `logger` - Logger instance.
     #[allow(clippy::too_many_arguments)]
     pub fn new(
@@ -158,6 +165,7 @@ impl<
         is_behind: Arc<AtomicBool>,
         highest_peer_block: Arc<AtomicU64>,
         highest_issued_msg: Arc<Mutex<Option<ConsensusMsg>>>,
+        tx_status_tracker: Arc<TxStatusTracker>,
         logger: Logger,
     ) -> Self {
         let current_slot_index = ledger.num_blocks().unwrap();
@@ -175,6 +183,7 @@ impl<
             ledger,
             tx_manager: tx_manager.clone(),
             mint_tx_manager: mint_tx_manager.clone(),
+            tx_status_tracker,
             broadcaster,
             connection_manager,
             logger,
@@ -365,6 +374,29 @@ impl<
             panic!("Attempted to sync when not behind?");
         };
 
+        // Prefer fetching signed blocks directly from peers, falling back to ledger
+        // sync if that does not make progress.
+        match catch_up_from_peers(
+            &mut self.ledger,
+            &self.connection_manager,
+            &self.network_state,
+            num_blocks,
+            &self.logger,
+        ) {
+            Ok(num_appended) if num_appended > 0 => {
+                counters::PEER_CATCHUP_BLOCKS.inc_by(num_appended as u64);
+                self.ledger_sync_state = LedgerSyncState::IsBehind {
+                    attempt_sync_at: Instant::now(),
+                    num_sync_attempts: 0,
+                };
+                return;
+            }
+            Ok(_) => {}
+            Err(err) => {
+                log::debug!(self.logger, "Could not catch up from peers: {}", err);
+            }
+        }
+
         self.ledger_sync_state = match self
             .ledger_sync_service
             .attempt_ledger_sync(&self.network_state, num_blocks)
@@ -413,6 +445,15 @@ impl<
                         .push((consensus_msg, from_responder_id));
                 }
 
+                // Quorum set changed (e.g. the network configuration was reloaded).
+                TaskMessage::UpdateQuorumSet(quorum_set) => {
+                    log::info!(self.logger, "Updating quorum set: {:?}", quorum_set);
+                    self.scp_node.set_quorum_set(quorum_set.clone());
+                    // Network state is evaluated against the local quorum set, so it needs to
+                    // be rebuilt. It will repopulate as messages arrive.
+                    self.network_state = SCPNetworkState::new(self.scp_node.node_id(), quorum_set);
+                }
+
                 // Request to stop thread
                 TaskMessage::StopTrigger => {
                     return false;
@@ -435,13 +476,21 @@ impl<
         // Fairness heuristics:
         // * Values are proposed in the order that they were received.
         // * Each node limits the total number of values it proposes per slot.
-        let values = self
+        let values: BTreeSet<ConsensusValue> = self
             .pending_values
             .iter()
             .take(MAX_PENDING_VALUES_TO_NOMINATE)
             .cloned()
             .collect();
 
+        self.tx_status_tracker.notify(
+            values.iter().filter_map(|value| match value {
+                ConsensusValue::TxHash(tx_hash) => Some(tx_hash),
+                _ => None,
+            }),
+            TxStatusEvent::Nominated(self.current_slot_index),
+        );
+
         let msg_opt = self
             .scp_node
             .propose_values(values)
@@ -586,6 +635,14 @@ impl<
 
         counters::TX_EXTERNALIZED_COUNT.inc_by(externalized.len() as u64);
 
+        self.tx_status_tracker.notify(
+            externalized.iter().filter_map(|value| match value {
+                ConsensusValue::TxHash(tx_hash) => Some(tx_hash),
+                _ => None,
+            }),
+            TxStatusEvent::Externalized(block_data.block().index),
+        );
+
         // Update current slot index.
         self.current_slot_index = {
             let current_slot_index: SlotIndex = self.ledger.num_blocks().unwrap();
@@ -600,6 +657,8 @@ impl<
             .current_slot_index
             .saturating_sub(max_externalized_slots);
         let purged_hashes = self.tx_manager.remove_expired(expired_block_index);
+        self.tx_status_tracker
+            .notify(&purged_hashes, TxStatusEvent::Expired(expired_block_index));
         let pending_values_len_before_purge = self.pending_values.len();
 
         self.pending_values.retain(|value| match value {
@@ -890,6 +949,7 @@ mod tests {
         },
         mint_tx_manager::{MintTxManagerImpl, MockMintTxManager},
         tx_manager::{MockTxManager, TxManager, TxManagerError, TxManagerImpl},
+        tx_status_tracker::TxStatusTracker,
         validators::DefaultTxManagerUntrustedInterfaces,
     };
     use mc_account_keys::AccountKey;
@@ -1028,6 +1088,7 @@ mod tests {
             Arc::new(AtomicBool::new(false)),
             Arc::new(AtomicU64::new(0)),
             Arc::new(Mutex::new(Option::<ConsensusMsg>::None)),
+            Arc::new(TxStatusTracker::default()),
             logger,
         );
 
@@ -1080,6 +1141,7 @@ mod tests {
             Arc::new(AtomicBool::new(false)),
             Arc::new(AtomicU64::new(0)),
             Arc::new(Mutex::new(Option::<ConsensusMsg>::None)),
+            Arc::new(TxStatusTracker::default()),
             logger,
         );
 
@@ -1199,6 +1261,7 @@ mod tests {
             Arc::new(AtomicBool::new(false)),
             Arc::new(AtomicU64::new(0)),
             Arc::new(Mutex::new(Option::<ConsensusMsg>::None)),
+            Arc::new(TxStatusTracker::default()),
             logger,
         );
 
@@ -1260,6 +1323,7 @@ mod tests {
             Arc::new(AtomicBool::new(false)),
             Arc::new(AtomicU64::new(0)),
             Arc::new(Mutex::new(Option::<ConsensusMsg>::None)),
+            Arc::new(TxStatusTracker::default()),
             logger,
         );
 
@@ -1361,6 +1425,7 @@ mod tests {
             Arc::new(AtomicBool::new(false)),
             Arc::new(AtomicU64::new(0)),
             Arc::new(Mutex::new(Option::<ConsensusMsg>::None)),
+            Arc::new(TxStatusTracker::default()),
             logger,
         );
 
@@ -1447,6 +1512,7 @@ mod tests {
             Arc::new(AtomicBool::new(false)),
             Arc::new(AtomicU64::new(0)),
             Arc::new(Mutex::new(Option::<ConsensusMsg>::None)),
+            Arc::new(TxStatusTracker::default()),
             logger,
         );
 
@@ -1544,6 +1610,7 @@ mod tests {
             Arc::new(AtomicBool::new(false)),
             Arc::new(AtomicU64::new(0)),
             Arc::new(Mutex::new(Option::<ConsensusMsg>::None)),
+            Arc::new(TxStatusTracker::default()),
             logger,
         );
 
@@ -1699,6 +1766,7 @@ mod tests {
             Arc::new(AtomicBool::new(false)),
             Arc::new(AtomicU64::new(0)),
             Arc::new(Mutex::new(Option::<ConsensusMsg>::None)),
+            Arc::new(TxStatusTracker::default()),
             logger,
         );
 
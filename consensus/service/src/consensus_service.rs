@@ -3,13 +3,21 @@
 //! The MobileCoin consensus service.
 
 use crate::{
-    api::{AttestedApiService, BlockchainApiService, ClientApiService, PeerApiService},
+    api::{
+        AdminApiService, AttestedApiService, BlockchainApiService, ClientApiService, PeerApiService,
+    },
     background_work_queue::BackgroundWorkQueue,
     byzantine_ledger::ByzantineLedger,
     counters,
     mint_tx_manager::MintTxManager,
+    network_config_reloader::{
+        NetworkConfigReloadError, NetworkConfigReloader, NetworkConfigUpdater,
+        ReloadNetworkConfigFn,
+    },
     peer_keepalive::PeerKeepalive,
-    tx_manager::TxManager,
+    tx_manager::{TxManager, TxPoolStore},
+    tx_pool_persister::TxPoolPersister,
+    tx_status_tracker::TxStatusTracker,
 };
 use base64::{encode_config, URL_SAFE};
 use displaydoc::Display;
@@ -24,7 +32,9 @@ use mc_common::{
     NodeID, ResponderId,
 };
 use mc_connection::{Connection, ConnectionManager};
-use mc_consensus_api::{consensus_client_grpc, consensus_common_grpc, consensus_peer_grpc};
+use mc_consensus_api::{
+    consensus_admin_grpc, consensus_client_grpc, consensus_common_grpc, consensus_peer_grpc,
+};
 use mc_consensus_enclave::{ConsensusEnclave, Error as ConsensusEnclaveError};
 use mc_consensus_service_config::{Config, Error as ConfigError};
 use mc_crypto_keys::DistinguishedEncoding;
@@ -129,12 +139,23 @@ pub struct ConsensusService<
     broadcaster: Arc<Mutex<ThreadedBroadcaster>>,
     tx_manager: Arc<TXM>,
     mint_tx_manager: Arc<MTXM>,
+    // Tracks the status of client-submitted transactions, shared between the client API
+    // service and the ByzantineLedger.
+    tx_status_tracker: Arc<TxStatusTracker>,
     // Option is only here because we need a way to drop the PeerKeepalive without mutex,
     // if we want to implement Stop as currently concieved
     peer_keepalive: Option<Arc<PeerKeepalive>>,
     // GRPC client requests authenticator
     client_authenticator: Arc<dyn Authenticator + Send + Sync>,
 
+    // Periodically reloads the network configuration, if enabled.
+    network_config_reloader: Option<NetworkConfigReloader>,
+    // Applies network configuration changes, shared by the reloader and the admin API.
+    network_config_updater: Option<Arc<NetworkConfigUpdater<E>>>,
+
+    // Periodically persists pending transactions, if enabled.
+    tx_pool_persister: Option<TxPoolPersister>,
+
     admin_rpc_server: Option<AdminServer>,
     consensus_rpc_server: Option<Server>,
     user_rpc_server: Option<Server>,
@@ -235,9 +256,15 @@ impl<
             broadcaster,
             tx_manager,
             mint_tx_manager,
+            tx_status_tracker: Arc::new(TxStatusTracker::default()),
             peer_keepalive,
             client_authenticator,
 
+            network_config_reloader: None,
+            network_config_updater: None,
+
+            tx_pool_persister: None,
+
             admin_rpc_server: None,
             consensus_rpc_server: None,
             user_rpc_server: None,
@@ -262,6 +289,9 @@ impl<
             self.start_consensus_rpc_server()?;
             self.start_user_rpc_server()?;
             self.start_byzantine_ledger_service()?;
+            self.load_tx_pool();
+            self.start_tx_pool_persister();
+            self.start_network_config_reloader();
 
             // Success.
             Ok(())
@@ -275,6 +305,14 @@ impl<
     pub fn stop(&mut self) -> Result<(), ConsensusServiceError> {
         log::debug!(self.logger, "Attempting to stop node...");
 
+        // This will join the persister thread, after persisting pending transactions
+        // one last time.
+        self.tx_pool_persister = None;
+
+        // This will join the reloader thread.
+        self.network_config_reloader = None;
+        self.network_config_updater = None;
+
         // This will join the peer_keepalive in drop if we are the last thread holding
         // it
         self.peer_keepalive = None;
@@ -344,6 +382,7 @@ impl<
                 self.mint_tx_manager.clone(),
                 self.create_is_serving_user_requests_fn(),
                 self.client_authenticator.clone(),
+                self.tx_status_tracker.clone(),
                 self.logger.clone(),
             ));
 
@@ -406,13 +445,18 @@ impl<
 
     fn start_admin_rpc_server(&mut self) -> Result<(), ConsensusServiceError> {
         if let Some(admin_listen_uri) = self.config.admin_listen_uri.as_ref() {
+            let consensus_admin_service = consensus_admin_grpc::create_consensus_admin_api(
+                AdminApiService::new(self.create_reload_network_config_fn(), self.logger.clone()),
+            );
+
             self.admin_rpc_server = Some(
-                AdminServer::start(
+                AdminServer::start_with_additional_services(
                     Some(self.env.clone()),
                     admin_listen_uri,
                     "Consensus Service".to_owned(),
                     self.config.peer_responder_id.to_string(),
                     Some(self.create_get_config_json_fn()),
+                    vec![consensus_admin_service],
                     self.logger.clone(),
                 )
                 .expect("Failed starting admin grpc server"),
@@ -464,7 +508,10 @@ impl<
             self.consensus_msgs_from_network.get_sender_fn(),
             self.create_scp_client_value_sender_fn(),
             get_highest_scp_message_fn,
-            self.peer_manager.responder_ids(),
+            {
+                let peer_manager = self.peer_manager.clone();
+                Arc::new(move || peer_manager.responder_ids())
+            },
             self.logger.clone(),
         ));
 
@@ -518,6 +565,7 @@ impl<
                 self.config.msg_signer_key.clone(),
                 self.config.network().tx_source_urls,
                 self.config.scp_debug_dump.clone(),
+                self.tx_status_tracker.clone(),
                 self.logger.clone(),
             ))
             .is_err()
@@ -674,6 +722,116 @@ impl<
         })
     }
 
+    /// Reloads the transactions persisted by a previous run of the service, and
+    /// proposes the ones that are still valid.
+    fn load_tx_pool(&mut self) {
+        let tx_pool_path = match self.config.tx_pool_path.as_ref() {
+            Some(tx_pool_path) => tx_pool_path,
+            None => return,
+        };
+
+        let persisted_txs = match TxPoolStore::new(tx_pool_path).load() {
+            Ok(persisted_txs) => persisted_txs,
+            Err(err) => {
+                log::error!(
+                    self.logger,
+                    "Failed loading persisted transactions from {:?}: {}",
+                    tx_pool_path,
+                    err
+                );
+                return;
+            }
+        };
+
+        let block_index = self
+            .ledger_db
+            .num_blocks()
+            .expect("Failed getting the number of blocks in the ledger");
+        let propose_tx_callback = self.create_scp_client_value_sender_fn();
+
+        let num_persisted = persisted_txs.len();
+        let mut num_reloaded = 0;
+        for persisted_tx in persisted_txs {
+            match self.tx_manager.insert_persisted(persisted_tx, block_index) {
+                Ok(tx_hash) => {
+                    num_reloaded += 1;
+                    propose_tx_callback(ConsensusValue::TxHash(tx_hash), None, None);
+                }
+                Err(err) => {
+                    log::debug!(self.logger, "Dropping persisted transaction: {}", err);
+                }
+            }
+        }
+
+        log::info!(
+            self.logger,
+            "Reloaded {} of {} persisted transactions",
+            num_reloaded,
+            num_persisted
+        );
+    }
+
+    fn start_tx_pool_persister(&mut self) {
+        if let Some(tx_pool_path) = self.config.tx_pool_path.as_ref() {
+            self.tx_pool_persister = Some(TxPoolPersister::start(
+                self.tx_manager.clone(),
+                TxPoolStore::new(tx_pool_path),
+                self.config.tx_pool_persist_interval,
+                self.logger.clone(),
+            ));
+        }
+    }
+
+    fn start_network_config_reloader(&mut self) {
+        if self.config.network_reload_interval.is_zero() {
+            log::info!(self.logger, "Network configuration reloading is disabled.");
+            return;
+        }
+
+        self.network_config_reloader = Some(NetworkConfigReloader::start(
+            self.create_reload_network_config_fn(),
+            self.config.network_reload_interval,
+            self.logger.clone(),
+        ));
+    }
+
+    /// Helper method for creating the function that reloads the network
+    /// configuration. Shared between the reloader thread and the admin API, so
+    /// that reloads are serialized and applied against the same state.
+    fn create_reload_network_config_fn(&mut self) -> ReloadNetworkConfigFn {
+        if self.network_config_updater.is_none() {
+            self.network_config_updater = Some(Arc::new(NetworkConfigUpdater::new(
+                self.config.clone(),
+                self.local_node_id.clone(),
+                self.enclave.clone(),
+                self.env.clone(),
+                self.peer_manager.clone(),
+                self.broadcaster.clone(),
+                self.peer_keepalive
+                    .as_ref()
+                    .map(Arc::downgrade)
+                    .expect("Server was not initialized"),
+                self.byzantine_ledger
+                    .as_ref()
+                    .map(Arc::downgrade)
+                    .expect("Server was not initialized"),
+                self.logger.clone(),
+            )));
+        }
+
+        let updater = Arc::downgrade(
+            self.network_config_updater
+                .as_ref()
+                .expect("Server was not initialized"),
+        );
+        Arc::new(move || {
+            updater
+                .upgrade()
+                .ok_or(NetworkConfigReloadError::ShuttingDown)?
+                .reload()
+        })
+    }
+
     /// Helper method for creating the get config json function needed by the
     /// GRPC admin service.
     fn create_get_config_json_fn(&self) -> GetConfigJsonFn {
@@ -683,6 +841,11 @@ impl<
             .as_ref()
             .map(Arc::downgrade)
             .expect("Server was not initialized");
+        let peer_keepalive = self
+            .peer_keepalive
+            .as_ref()
+            .map(Arc::downgrade)
+            .expect("Server was not initialized");
         let config = self.config.clone();
         let logger = self.logger.clone();
         Arc::new(move || {
@@ -764,6 +927,7 @@ impl<
                     "broadcast_peer_count": config.network().broadcast_peers.len(),
                     "known_peer_count": config.network().known_peers.map_or(0, |x| x.len()),
                     "sync_status": sync_status,
+                    "peers": peer_keepalive.upgrade().map(|peer_keepalive| peer_keepalive.peer_statuses()),
                     "blocks_behind": blocks_behind,
                     "latest_block_hash": latest_block_hash,
                     "latest_block_timestamp": latest_block_timestamp.map_or("".to_string(), |u| u.to_string()),
@@ -84,6 +84,9 @@ lazy_static::lazy_static! {
     // Number of times catchup is initiated
     pub static ref CATCHUP_INITIATED: IntCounter = OP_COUNTERS.counter("catchup_initiated");
 
+    // Number of blocks appended by fetching signed blocks directly from peers
+    pub static ref PEER_CATCHUP_BLOCKS: IntCounter = OP_COUNTERS.counter("peer_catchup_blocks");
+
     // Number of times attestation is initiated
     pub static ref ATTESTATION_INITIATED: IntCounter = OP_COUNTERS.counter("attestation_initiated");
 
@@ -113,6 +116,12 @@ lazy_static::lazy_static! {
 
     // Number of times a ProposeMintTx call has returned a response.
     pub static ref PROPOSE_MINT_TX: IntCounter = OP_COUNTERS.counter("propose_mint_tx");
+
+    // Number of transactions whose status is currently being tracked for subscribers.
+    pub static ref TX_STATUS_TRACKED_TXS: IntGauge = OP_COUNTERS.gauge("tx_status_tracked_txs");
+
+    // Number of times a client subscribed to a transaction's status.
+    pub static ref TX_STATUS_SUBSCRIPTIONS: IntCounter = OP_COUNTERS.counter("tx_status_subscriptions");
 }
 
 /// TxValidationErrorMetrics keeps track of tx validation errors upon ingress
@@ -16,4 +16,7 @@ mod api;
 mod background_work_queue;
 mod byzantine_ledger;
 mod counters;
+mod network_config_reloader;
 mod peer_keepalive;
+mod tx_pool_persister;
+mod tx_status_tracker;
@@ -144,6 +144,9 @@ impl<L: Ledger> MintTxManager for MintTxManagerImpl<L> {
                 LedgerError::MintLimitExceeded(_, _, _) => {
                     MintTxManagerError::MintValidation(MintValidationError::AmountExceedsMintLimit)
                 }
+                LedgerError::MintLimitWindowExceeded(_) => MintTxManagerError::MintValidation(
+                    MintValidationError::AmountExceedsMintLimitWindow,
+                ),
                 err => err.into(),
             })?;
 
@@ -209,6 +212,9 @@ impl<L: Ledger> MintTxManager for MintTxManagerImpl<L> {
         &self,
         txs: &[MintTx],
     ) -> MintTxManagerResult<Vec<(MintTx, MintConfigTx, MintConfig)>> {
+        // Get the index of the block currently being built.
+        let current_block_index = self.ledger_db.num_blocks()?;
+
         txs.iter()
             .map(|mint_tx| {
                 let active_mint_configs = self
@@ -218,8 +224,8 @@ impl<L: Ledger> MintTxManager for MintTxManagerImpl<L> {
                         MintValidationError::NoMatchingMintConfig,
                     ))?;
 
-                let active_mint_config =
-                    active_mint_configs.get_active_mint_config_for_mint_tx(mint_tx)?;
+                let active_mint_config = active_mint_configs
+                    .get_active_mint_config_for_mint_tx(mint_tx, current_block_index)?;
 
                 Ok((
                     mint_tx.clone(),
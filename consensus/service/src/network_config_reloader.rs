@@ -0,0 +1,538 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! Reloads the network configuration (quorum set and broadcast peers) of a
+//! running consensus service, without requiring a restart.
+//!
+//! The new configuration is fully validated before anything is applied. Peer
+//! connections are then added/removed from the shared `ConnectionManager`, the
+//! broadcaster and keepalive are updated to match, and the new quorum set is
+//! handed to the `ByzantineLedger`, which starts using it from the next slot.
+//!
+//! Only `quorum_set`, `broadcast_peers` and `known_peers` are hot-reloadable;
+//! changes to `tx_source_urls` still require a restart.
+
+use crate::{byzantine_ledger::ByzantineLedger, peer_keepalive::PeerKeepalive};
+use displaydoc::Display;
+use grpcio::Environment;
+use mc_common::{
+    logger::{log, Logger},
+    NodeID, ResponderId,
+};
+use mc_connection::ConnectionManager;
+use mc_consensus_enclave::ConsensusEnclave;
+use mc_consensus_service_config::{Config, Error as ConfigError, NetworkConfig};
+use mc_peers::{PeerConnection, ThreadedBroadcaster};
+use mc_util_uri::{ConnectionUri, ConsensusPeerUri as PeerUri, UriConversionError};
+use once_cell::sync::OnceCell;
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, Weak,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+/// How often the reloader thread checks whether it was asked to stop.
+const STOP_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Errors that can occur while reloading the network configuration.
+#[derive(Debug, Display)]
+pub enum NetworkConfigReloadError {
+    /// Invalid network configuration: `{0}`
+    Config(ConfigError),
+
+    /// Invalid peer URI `{0}`: `{1}`
+    PeerUri(String, UriConversionError),
+
+    /// The consensus service is shutting down
+    ShuttingDown,
+}
+
+impl From<ConfigError> for NetworkConfigReloadError {
+    fn from(src: ConfigError) -> Self {
+        Self::Config(src)
+    }
+}
+
+/// The changes that were applied by a reload.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct NetworkConfigChanges {
+    /// Peers we started connecting to.
+    pub added_peers: Vec<ResponderId>,
+
+    /// Peers we disconnected from.
+    pub removed_peers: Vec<ResponderId>,
+
+    /// Whether the quorum set was replaced.
+    pub quorum_set_changed: bool,
+}
+
+impl NetworkConfigChanges {
+    /// Returns true if the reload did not change anything.
+    pub fn is_empty(&self) -> bool {
+        self.added_peers.is_empty() && self.removed_peers.is_empty() && !self.quorum_set_changed
+    }
+}
+
+/// A callback for reloading the network configuration.
+pub type ReloadNetworkConfigFn =
+    Arc<dyn Fn() -> Result<NetworkConfigChanges, NetworkConfigReloadError> + Sync + Send>;
+
+/// Applies network configuration changes to the running service components.
+pub struct NetworkConfigUpdater<E: ConsensusEnclave + Clone + Send + Sync + 'static> {
+    config: Config,
+    local_node_id: NodeID,
+    enclave: E,
+    env: Arc<Environment>,
+    peer_manager: ConnectionManager<PeerConnection<E>>,
+    broadcaster: Arc<Mutex<ThreadedBroadcaster>>,
+    peer_keepalive: Weak<PeerKeepalive>,
+    byzantine_ledger: Weak<OnceCell<ByzantineLedger>>,
+
+    /// The currently applied network configuration. The mutex also ensures
+    /// reloads never run concurrently.
+    current: Mutex<NetworkConfig>,
+
+    logger: Logger,
+}
+
+impl<E: ConsensusEnclave + Clone + Send + Sync + 'static> NetworkConfigUpdater<E> {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        config: Config,
+        local_node_id: NodeID,
+        enclave: E,
+        env: Arc<Environment>,
+        peer_manager: ConnectionManager<PeerConnection<E>>,
+        broadcaster: Arc<Mutex<ThreadedBroadcaster>>,
+        peer_keepalive: Weak<PeerKeepalive>,
+        byzantine_ledger: Weak<OnceCell<ByzantineLedger>>,
+        logger: Logger,
+    ) -> Self {
+        let current = Mutex::new(config.network());
+        Self {
+            config,
+            local_node_id,
+            enclave,
+            env,
+            peer_manager,
+            broadcaster,
+            peer_keepalive,
+            byzantine_ledger,
+            current,
+            logger,
+        }
+    }
+
+    /// Re-read the network configuration file and apply any changes.
+    ///
+    /// Nothing is applied if the new configuration fails to validate.
+    pub fn reload(&self) -> Result<NetworkConfigChanges, NetworkConfigReloadError> {
+        let mut current = self.current.lock().expect("mutex poisoned");
+
+        let new_network = NetworkConfig::load_from_path(
+            &self.config.network_path,
+            &self.config.peer_responder_id,
+        )?;
+        if *current == new_network {
+            return Ok(NetworkConfigChanges::default());
+        }
+
+        // Validate everything before touching any state.
+        let new_quorum_set = new_network.try_quorum_set()?;
+        let quorum_set_changed = current.try_quorum_set().ok().as_ref() != Some(&new_quorum_set);
+
+        let removed_uris: Vec<&PeerUri> = current
+            .broadcast_peers
+            .iter()
+            .filter(|uri| !new_network.broadcast_peers.contains(uri))
+            .collect();
+        let added_uris: Vec<&PeerUri> = new_network
+            .broadcast_peers
+            .iter()
+            .filter(|uri| !current.broadcast_peers.contains(uri))
+            .collect();
+        let removed_ids = removed_uris
+            .iter()
+            .map(|uri| Self::connection_id(uri))
+            .collect::<Result<Vec<_>, _>>()?;
+        for uri in added_uris.iter() {
+            Self::connection_id(uri)?;
+        }
+
+        let byzantine_ledger = self
+            .byzantine_ledger
+            .upgrade()
+            .ok_or(NetworkConfigReloadError::ShuttingDown)?;
+        let peer_keepalive = self.peer_keepalive.upgrade();
+
+        // Apply peer changes.
+        let mut changes = NetworkConfigChanges {
+            quorum_set_changed,
+            ..Default::default()
+        };
+        for responder_id in removed_ids {
+            if self.peer_manager.remove_conn(&responder_id).is_some() {
+                log::info!(self.logger, "Removed peer {}", responder_id);
+                changes.removed_peers.push(responder_id.clone());
+            }
+            if let Some(peer_keepalive) = peer_keepalive.as_ref() {
+                peer_keepalive.forget_peer(&responder_id);
+            }
+        }
+        for uri in added_uris {
+            let responder_id = self.peer_manager.add_conn(PeerConnection::new(
+                self.enclave.clone(),
+                self.local_node_id.clone(),
+                uri.clone(),
+                self.env.clone(),
+                self.logger.clone(),
+            ));
+            log::info!(self.logger, "Added peer {}", responder_id);
+            if let Some(peer_keepalive) = peer_keepalive.as_ref() {
+                peer_keepalive.heard_from_peer(responder_id.clone());
+            }
+            changes.added_peers.push(responder_id);
+        }
+        self.broadcaster
+            .lock()
+            .expect("mutex poisoned")
+            .update_peers(&self.peer_manager);
+
+        // Apply quorum set changes.
+        if quorum_set_changed {
+            log::info!(self.logger, "Updating quorum set to {:?}", new_quorum_set);
+            if let Some(byzantine_ledger) = byzantine_ledger.get() {
+                byzantine_ledger.update_quorum_set(new_quorum_set);
+            }
+        }
+
+        *current = new_network;
+        Ok(changes)
+    }
+
+    /// The responder id the connection manager uses to key a peer connection.
+    fn connection_id(uri: &PeerUri) -> Result<ResponderId, NetworkConfigReloadError> {
+        uri.host_and_port_responder_id()
+            .map_err(|err| NetworkConfigReloadError::PeerUri(uri.to_string(), err))
+    }
+}
+
+/// Periodically reloads the network configuration file.
+pub struct NetworkConfigReloader {
+    join_handle: Option<thread::JoinHandle<()>>,
+    stop_requested: Arc<AtomicBool>,
+}
+
+impl NetworkConfigReloader {
+    pub fn start(reload_fn: ReloadNetworkConfigFn, interval: Duration, logger: Logger) -> Self {
+        let stop_requested = Arc::new(AtomicBool::new(false));
+        let thread_stop_requested = stop_requested.clone();
+        let join_handle = Some(
+            thread::Builder::new()
+                .name("NetworkConfigReloader".into())
+                .spawn(move || {
+                    Self::thread_entrypoint(reload_fn, interval, thread_stop_requested, logger)
+                })
+                .expect("Failed spawning NetworkConfigReloader thread"),
+        );
+
+        Self {
+            join_handle,
+            stop_requested,
+        }
+    }
+
+    pub fn stop(&mut self) {
+        self.stop_requested.store(true, Ordering::SeqCst);
+        if let Some(thread) = self.join_handle.take() {
+            thread
+                .join()
+                .expect("NetworkConfigReloader thread join failed");
+        }
+    }
+
+    fn thread_entrypoint(
+        reload_fn: ReloadNetworkConfigFn,
+        interval: Duration,
+        stop_requested: Arc<AtomicBool>,
+        logger: Logger,
+    ) {
+        log::debug!(logger, "NetworkConfigReloader thread has started.");
+
+        let mut last_reload_at = Instant::now();
+        let mut last_error = None;
+
+        loop {
+            if stop_requested.load(Ordering::SeqCst) {
+                log::debug!(logger, "NetworkConfigReloader stop requested.");
+                break;
+            }
+
+            if last_reload_at.elapsed() >= interval {
+                last_reload_at = Instant::now();
+                match reload_fn() {
+                    Ok(changes) => {
+                        if !changes.is_empty() {
+                            log::info!(logger, "Network configuration reloaded: {:?}", changes);
+                        }
+                        last_error = None;
+                    }
+                    Err(err) => {
+                        // Avoid logging the same error on every poll.
+                        let err = err.to_string();
+                        if last_error.as_ref() != Some(&err) {
+                            log::error!(logger, "Failed reloading network configuration: {}", err);
+                            last_error = Some(err);
+                        }
+                    }
+                }
+            }
+
+            thread::sleep(STOP_CHECK_INTERVAL);
+        }
+    }
+}
+
+impl Drop for NetworkConfigReloader {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        background_work_queue::BackgroundWorkQueueSenderFn, consensus_service::IncomingConsensusMsg,
+    };
+    use clap::Parser;
+    use mc_common::logger::test_with_logger;
+    use mc_connection::Connection;
+    use mc_consensus_enclave_mock::ConsensusServiceMockEnclave;
+    use mc_consensus_scp::QuorumSet;
+    use mc_peers::ThreadedBroadcasterFibonacciRetryPolicy;
+    use mc_peers_test_utils::{
+        test_node_id, test_node_id_and_signer, test_peer_uri, test_peer_uri_with_key,
+    };
+    use std::{
+        fs,
+        path::{Path, PathBuf},
+    };
+    use tempdir::TempDir;
+
+    /// A network config whose quorum set requires all of `peers`.
+    fn network_config(peers: Vec<PeerUri>) -> NetworkConfig {
+        let responder_ids = peers
+            .iter()
+            .map(|uri| uri.responder_id().unwrap())
+            .collect::<Vec<_>>();
+        NetworkConfig {
+            quorum_set: QuorumSet::new_with_node_ids(responder_ids.len() as u32, responder_ids),
+            broadcast_peers: peers,
+            tx_source_urls: vec![],
+            known_peers: None,
+        }
+    }
+
+    fn write_network_config(path: &Path, network: &NetworkConfig) {
+        fs::write(path, serde_json::to_string(network).unwrap()).unwrap();
+    }
+
+    /// An updater together with the service components it updates.
+    struct TestContext {
+        updater: NetworkConfigUpdater<ConsensusServiceMockEnclave>,
+        peer_manager: ConnectionManager<PeerConnection<ConsensusServiceMockEnclave>>,
+        network_path: PathBuf,
+        _peer_keepalive: Arc<PeerKeepalive>,
+        _byzantine_ledger: Arc<OnceCell<ByzantineLedger>>,
+        _temp_dir: TempDir,
+    }
+
+    fn setup(peers: Vec<PeerUri>, logger: Logger) -> TestContext {
+        let temp_dir = TempDir::new("network_config_reloader").unwrap();
+        let network_path = temp_dir.path().join("network.json");
+        write_network_config(&network_path, &network_config(peers.clone()));
+
+        let config = Config::try_parse_from(&[
+            "foo".to_string(),
+            "--peer-responder-id=localhost:8081".to_string(),
+            "--client-responder-id=localhost:3223".to_string(),
+            "--msg-signer-key=MC4CAQAwBQYDK2VwBCIEIC50QXQll2Y9qxztvmsUgcBBIxkmk7EQjxzQTa926bKo"
+                .to_string(),
+            format!("--network={}", network_path.display()),
+            "--peer-listen-uri=insecure-mcp://0.0.0.0:8081/".to_string(),
+            "--client-listen-uri=insecure-mc://0.0.0.0:3223/".to_string(),
+            "--admin-listen-uri=insecure-mca://0.0.0.0:9090/".to_string(),
+            "--sealed-block-signing-key=/tmp/key".to_string(),
+            "--ledger-path=/tmp/ledger".to_string(),
+            "--ias-spid=22222222222222222222222222222222".to_string(),
+            "--ias-api-key=asdf".to_string(),
+        ])
+        .unwrap();
+
+        let local_node_id = test_node_id(1);
+        let enclave = ConsensusServiceMockEnclave::default();
+        let env = Arc::new(Environment::new(1));
+        let peer_manager = ConnectionManager::new(
+            peers
+                .into_iter()
+                .map(|uri| {
+                    PeerConnection::new(
+                        enclave.clone(),
+                        local_node_id.clone(),
+                        uri,
+                        env.clone(),
+                        logger.clone(),
+                    )
+                })
+                .collect(),
+            logger.clone(),
+        );
+        let broadcaster = Arc::new(Mutex::new(ThreadedBroadcaster::new(
+            &peer_manager,
+            &ThreadedBroadcasterFibonacciRetryPolicy::default(),
+            logger.clone(),
+        )));
+        let incoming_consensus_msgs_sender: BackgroundWorkQueueSenderFn<IncomingConsensusMsg> =
+            Arc::new(|_msg: IncomingConsensusMsg| Ok(()));
+        let peer_keepalive = Arc::new(PeerKeepalive::start(
+            peer_manager.clone(),
+            incoming_consensus_msgs_sender,
+            logger.clone(),
+        ));
+        let byzantine_ledger = Arc::new(OnceCell::new());
+
+        let updater = NetworkConfigUpdater::new(
+            config,
+            local_node_id,
+            enclave,
+            env,
+            peer_manager.clone(),
+            broadcaster,
+            Arc::downgrade(&peer_keepalive),
+            Arc::downgrade(&byzantine_ledger),
+            logger,
+        );
+
+        TestContext {
+            updater,
+            peer_manager,
+            network_path,
+            _peer_keepalive: peer_keepalive,
+            _byzantine_ledger: byzantine_ledger,
+            _temp_dir: temp_dir,
+        }
+    }
+
+    fn sorted_responder_ids(
+        peer_manager: &ConnectionManager<PeerConnection<ConsensusServiceMockEnclave>>,
+    ) -> Vec<ResponderId> {
+        let mut responder_ids = peer_manager.responder_ids();
+        responder_ids.sort();
+        responder_ids
+    }
+
+    #[test_with_logger]
+    // Reloading an unchanged configuration should not change anything.
+    fn test_reload_unchanged(logger: Logger) {
+        let context = setup(vec![test_peer_uri(2), test_peer_uri(3)], logger);
+
+        let changes = context.updater.reload().unwrap();
+        assert!(changes.is_empty());
+        assert_eq!(context.peer_manager.len(), 2);
+    }
+
+    #[test_with_logger]
+    // Peers added to or removed from the configuration should be connected to or
+    // disconnected from.
+    fn test_reload_adds_and_removes_peers(logger: Logger) {
+        let peer2 = test_peer_uri(2);
+        let peer3 = test_peer_uri(3);
+        let peer4 = test_peer_uri(4);
+        let context = setup(vec![peer2.clone(), peer3.clone()], logger);
+
+        write_network_config(
+            &context.network_path,
+            &network_config(vec![peer3.clone(), peer4.clone()]),
+        );
+        let changes = context.updater.reload().unwrap();
+        assert_eq!(
+            changes,
+            NetworkConfigChanges {
+                added_peers: vec![peer4.responder_id().unwrap()],
+                removed_peers: vec![peer2.responder_id().unwrap()],
+                quorum_set_changed: true,
+            }
+        );
+        assert_eq!(
+            sorted_responder_ids(&context.peer_manager),
+            vec![peer3.responder_id().unwrap(), peer4.responder_id().unwrap()]
+        );
+
+        // Reloading again is a no-op.
+        assert!(context.updater.reload().unwrap().is_empty());
+    }
+
+    #[test_with_logger]
+    // A peer whose URI changed, e.g. because its message signing key was
+    // rotated, should be reconnected to using the new URI.
+    fn test_reload_changed_peer_uri(logger: Logger) {
+        let peer2 = test_peer_uri(2);
+        let peer3 = test_peer_uri(3);
+        let context = setup(vec![peer2.clone(), peer3.clone()], logger);
+
+        let (_, new_signer) = test_node_id_and_signer(22);
+        let new_peer2 = test_peer_uri_with_key(2, &new_signer.public_key());
+        write_network_config(
+            &context.network_path,
+            &network_config(vec![new_peer2.clone(), peer3]),
+        );
+        let changes = context.updater.reload().unwrap();
+        let responder_id2 = peer2.responder_id().unwrap();
+        assert_eq!(changes.added_peers, vec![responder_id2.clone()]);
+        assert_eq!(changes.removed_peers, vec![responder_id2.clone()]);
+        // The node id of peer2 changed along with its key.
+        assert!(changes.quorum_set_changed);
+
+        assert_eq!(context.peer_manager.len(), 2);
+        assert_eq!(
+            context.peer_manager.conn(&responder_id2).unwrap().uri(),
+            new_peer2
+        );
+    }
+
+    #[test_with_logger]
+    // An invalid configuration should be rejected without applying anything.
+    fn test_reload_invalid_config(logger: Logger) {
+        let peer2 = test_peer_uri(2);
+        let peer3 = test_peer_uri(3);
+        let context = setup(vec![peer2.clone(), peer3.clone()], logger);
+        let responder_ids = sorted_responder_ids(&context.peer_manager);
+
+        // A configuration that cannot be parsed.
+        fs::write(&context.network_path, "not a network config").unwrap();
+        assert!(matches!(
+            context.updater.reload(),
+            Err(NetworkConfigReloadError::Config(_))
+        ));
+        assert_eq!(sorted_responder_ids(&context.peer_manager), responder_ids);
+
+        // A quorum set that cannot be satisfied.
+        let mut network = network_config(vec![peer3, test_peer_uri(4)]);
+        network.quorum_set.threshold = 3;
+        write_network_config(&context.network_path, &network);
+        assert!(matches!(
+            context.updater.reload(),
+            Err(NetworkConfigReloadError::Config(_))
+        ));
+        assert_eq!(sorted_responder_ids(&context.peer_manager), responder_ids);
+
+        // Fixing the configuration allows it to be applied.
+        network.quorum_set.threshold = 2;
+        write_network_config(&context.network_path, &network);
+        let changes = context.updater.reload().unwrap();
+        assert_eq!(changes.removed_peers, vec![peer2.responder_id().unwrap()]);
+    }
+}
@@ -15,6 +15,7 @@ use mc_common::{
 use mc_connection::ConnectionManager;
 use mc_peers::{ConsensusConnection, RetryableConsensusConnection};
 use retry::Error as RetryError;
+use serde::Serialize;
 use std::{
     convert::TryInto,
     sync::{
@@ -32,6 +33,24 @@ const START_KEEPALIVE_CHECKS_AFTER: Duration = Duration::from_secs(30);
 /// Time between keepalive checks for silent nodes.
 const KEEPALIVE_INTERVAL: Duration = Duration::from_secs(10);
 
+/// A peer we have not heard from in this long did not answer our keepalive
+/// pings either, and is considered disconnected.
+const DISCONNECTED_AFTER: Duration =
+    Duration::from_secs(START_KEEPALIVE_CHECKS_AFTER.as_secs() + 2 * KEEPALIVE_INTERVAL.as_secs());
+
+/// The connection status of a peer, as reported by the admin API.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct PeerStatus {
+    /// The peer's responder id.
+    pub responder_id: ResponderId,
+
+    /// Seconds since we last heard from the peer.
+    pub last_heard_secs: u64,
+
+    /// Whether we heard from the peer recently.
+    pub connected: bool,
+}
+
 pub struct PeerKeepalive {
     join_handle: Option<thread::JoinHandle<()>>,
     stop_requested: Arc<AtomicBool>,
@@ -94,6 +113,40 @@ impl PeerKeepalive {
         responder_id_to_last_heard.insert(responder_id, Instant::now());
     }
 
+    /// Stop pinging a peer, e.g. because it was removed from the network
+    /// configuration.
+    pub fn forget_peer(&self, responder_id: &ResponderId) {
+        let mut responder_id_to_last_heard = self
+            .responder_id_to_last_heard
+            .lock()
+            .expect("mutex poisoned");
+        responder_id_to_last_heard.remove(responder_id);
+    }
+
+    /// The connection status of each peer, sorted by responder id.
+    pub fn peer_statuses(&self) -> Vec<PeerStatus> {
+        self.peer_statuses_at(Instant::now())
+    }
+
+    fn peer_statuses_at(&self, now: Instant) -> Vec<PeerStatus> {
+        let mut peer_statuses: Vec<PeerStatus> = self
+            .responder_id_to_last_heard
+            .lock()
+            .expect("mutex poisoned")
+            .iter()
+            .map(|(responder_id, last_heard)| {
+                let since_last_heard = now.saturating_duration_since(*last_heard);
+                PeerStatus {
+                    responder_id: responder_id.clone(),
+                    last_heard_secs: since_last_heard.as_secs(),
+                    connected: since_last_heard <= DISCONNECTED_AFTER,
+                }
+            })
+            .collect();
+        peer_statuses.sort_by(|a, b| a.responder_id.cmp(&b.responder_id));
+        peer_statuses
+    }
+
     fn thread_entrypoint<CC: ConsensusConnection>(
         conn_manager: ConnectionManager<CC>,
         stop_requested: Arc<AtomicBool>,
@@ -210,3 +263,95 @@ impl Drop for PeerKeepalive {
         self.stop();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mc_common::logger::test_with_logger;
+    use mc_ledger_db::test_utils::get_mock_ledger;
+    use mc_peers_test_utils::{test_node_id, test_peer_uri, MockPeerConnection};
+    use mc_util_uri::ConnectionUri;
+
+    fn tracked_responder_ids(peer_keepalive: &PeerKeepalive) -> Vec<ResponderId> {
+        let mut responder_ids: Vec<ResponderId> = peer_keepalive
+            .responder_id_to_last_heard
+            .lock()
+            .expect("mutex poisoned")
+            .keys()
+            .cloned()
+            .collect();
+        responder_ids.sort();
+        responder_ids
+    }
+
+    #[test_with_logger]
+    // Peers should be tracked from the start, heard_from_peer should start
+    // tracking a new peer, and forget_peer should stop tracking a peer.
+    fn test_heard_from_peer_and_forget_peer(logger: Logger) {
+        let peer2 =
+            MockPeerConnection::new(test_peer_uri(2), test_node_id(1), get_mock_ledger(1), 0);
+        let responder_id2 = peer2.uri.responder_id().unwrap();
+        let responder_id3 = test_peer_uri(3).responder_id().unwrap();
+
+        let peer_keepalive = PeerKeepalive::start(
+            ConnectionManager::new(vec![peer2], logger.clone()),
+            Arc::new(|_msg: IncomingConsensusMsg| Ok(())),
+            logger,
+        );
+        assert_eq!(
+            tracked_responder_ids(&peer_keepalive),
+            vec![responder_id2.clone()]
+        );
+
+        peer_keepalive.heard_from_peer(responder_id3.clone());
+        assert_eq!(
+            tracked_responder_ids(&peer_keepalive),
+            vec![responder_id2.clone(), responder_id3.clone()]
+        );
+
+        peer_keepalive.forget_peer(&responder_id2);
+        assert_eq!(
+            tracked_responder_ids(&peer_keepalive),
+            vec![responder_id3.clone()]
+        );
+
+        // Forgetting a peer that is not tracked is a no-op.
+        peer_keepalive.forget_peer(&responder_id2);
+        assert_eq!(tracked_responder_ids(&peer_keepalive), vec![responder_id3]);
+    }
+
+    #[test_with_logger]
+    // Peers we heard from recently should be reported as connected, and peers
+    // we have not heard from in a while as disconnected.
+    fn test_peer_statuses(logger: Logger) {
+        let peer2 =
+            MockPeerConnection::new(test_peer_uri(2), test_node_id(1), get_mock_ledger(1), 0);
+        let peer3 =
+            MockPeerConnection::new(test_peer_uri(3), test_node_id(1), get_mock_ledger(1), 0);
+        let responder_id2 = peer2.uri.responder_id().unwrap();
+        let responder_id3 = peer3.uri.responder_id().unwrap();
+
+        let peer_keepalive = PeerKeepalive::start(
+            ConnectionManager::new(vec![peer2, peer3], logger.clone()),
+            Arc::new(|_msg: IncomingConsensusMsg| Ok(())),
+            logger,
+        );
+        // Look at the statuses from a point in time at which we just heard from
+        // peer2, but have not heard from peer3 since the keepalive started.
+        let later = Instant::now() + DISCONNECTED_AFTER * 2;
+        peer_keepalive
+            .responder_id_to_last_heard
+            .lock()
+            .expect("mutex poisoned")
+            .insert(responder_id2.clone(), later);
+
+        let peer_statuses = peer_keepalive.peer_statuses_at(later);
+        assert_eq!(peer_statuses.len(), 2);
+        assert_eq!(peer_statuses[0].responder_id, responder_id2);
+        assert!(peer_statuses[0].connected);
+        assert_eq!(peer_statuses[0].last_heard_secs, 0);
+        assert_eq!(peer_statuses[1].responder_id, responder_id3);
+        assert!(!peer_statuses[1].connected);
+        assert!(peer_statuses[1].last_heard_secs >= DISCONNECTED_AFTER.as_secs() * 2);
+    }
+}
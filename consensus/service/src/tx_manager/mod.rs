@@ -19,14 +19,17 @@ use mc_consensus_enclave::{
 use mc_transaction_core::{
     constants::MAX_TRANSACTIONS_PER_BLOCK,
     tx::{TxHash, TxOutMembershipProof},
+    validation::TransactionValidationError,
 };
 use std::sync::{Arc, Mutex, MutexGuard};
 
 mod error;
+mod persistence;
 mod tx_manager_trait;
 mod untrusted_interfaces;
 
 pub use error::{TxManagerError, TxManagerResult};
+pub use persistence::{PersistedTx, TxPoolStore, TxPoolStoreError};
 pub use tx_manager_trait::TxManager;
 pub use untrusted_interfaces::UntrustedInterfaces;
 
@@ -329,6 +332,68 @@ impl<E: ConsensusEnclave + Send, UI: UntrustedInterfaces + Send> TxManager
             .get(tx_hash)
             .map(|entry| entry.encrypted_tx().clone())
     }
+
+    /// Seal all cached transactions so that they can be persisted.
+    fn seal_all(&self) -> Vec<PersistedTx> {
+        let entries: Vec<_> = self
+            .lock_cache()
+            .iter()
+            .map(|(tx_hash, entry)| {
+                (
+                    *tx_hash,
+                    entry.encrypted_tx().clone(),
+                    entry.context().tombstone_block(),
+                )
+            })
+            .collect();
+
+        entries
+            .into_iter()
+            .filter_map(|(tx_hash, encrypted_tx, tombstone_block)| {
+                match self.enclave.seal_well_formed_tx(&encrypted_tx) {
+                    Ok(sealed_tx) => Some(PersistedTx {
+                        sealed_tx,
+                        tombstone_block,
+                    }),
+                    Err(err) => {
+                        log::warn!(self.logger, "Failed sealing tx {}: {}", tx_hash, err);
+                        None
+                    }
+                }
+            })
+            .collect()
+    }
+
+    /// Insert a persisted transaction into the cache. The transaction must not
+    /// be expired, and must be well-formed and valid w.r.t. the current
+    /// ledger.
+    ///
+    /// # Arguments
+    /// * `persisted_tx` - A transaction sealed by `seal_all`.
+    /// * `block_index` - Current block index.
+    fn insert_persisted(
+        &self,
+        persisted_tx: PersistedTx,
+        block_index: u64,
+    ) -> TxManagerResult<TxHash> {
+        if persisted_tx.tombstone_block <= block_index {
+            return Err(TransactionValidationError::TombstoneBlockExceeded.into());
+        }
+
+        let tx_context = self.enclave.unseal_tx(persisted_tx.sealed_tx)?;
+        let tx_hash = self.insert(tx_context)?;
+
+        // The ledger may have changed while the transaction was persisted, e.g. its
+        // key images may have been spent.
+        if let Err(err) = self.validate(&tx_hash) {
+            let mut cache = self.lock_cache();
+            cache.remove(&tx_hash);
+            counters::TX_CACHE_NUM_ENTRIES.set(cache.len() as i64);
+            return Err(err);
+        }
+
+        Ok(tx_hash)
+    }
 }
 
 #[cfg(test)]
@@ -974,4 +1039,165 @@ mod tests {
         }
         assert_eq!(tx_manager.num_entries(), tx_hashes.len());
     }
+
+    #[test_with_logger]
+    // Should seal every cached transaction, along with its tombstone block.
+    fn test_seal_all(logger: Logger) {
+        let mock_untrusted = MockUntrustedInterfaces::new();
+        let mut mock_enclave = MockConsensusEnclave::new();
+        mock_enclave
+            .expect_seal_well_formed_tx()
+            .times(3)
+            .returning(|encrypted_tx| Ok(encrypted_tx.0.clone()));
+        let tx_manager = TxManagerImpl::new(mock_enclave, mock_untrusted, logger.clone());
+
+        for i in 0..3u8 {
+            let context = WellFormedTxContext::new(
+                Default::default(),
+                TxHash([i; 32]),
+                100 + i as u64,
+                Default::default(),
+                Default::default(),
+                Default::default(),
+            );
+
+            let cache_entry = CacheEntry {
+                encrypted_tx: WellFormedEncryptedTx(vec![i]),
+                context: Arc::new(context.clone()),
+            };
+
+            tx_manager
+                .cache
+                .lock()
+                .unwrap()
+                .insert(*context.tx_hash(), cache_entry);
+        }
+
+        let mut persisted_txs = tx_manager.seal_all();
+        persisted_txs.sort_by_key(|persisted_tx| persisted_tx.tombstone_block);
+        assert_eq!(
+            persisted_txs,
+            (0..3u8)
+                .map(|i| PersistedTx {
+                    sealed_tx: vec![i],
+                    tombstone_block: 100 + i as u64,
+                })
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test_with_logger]
+    // Should insert a persisted transaction that is well-formed and valid.
+    fn test_insert_persisted_ok(logger: Logger) {
+        let tx_context = TxContext::default();
+        let tx_hash = tx_context.tx_hash;
+
+        let mut mock_untrusted = MockUntrustedInterfaces::new();
+        mock_untrusted
+            .expect_well_formed_check()
+            .times(1)
+            .return_const(Ok((0, vec![])));
+        mock_untrusted
+            .expect_is_valid()
+            .times(1)
+            .return_const(Ok(()));
+
+        let mut mock_enclave = MockConsensusEnclave::new();
+        mock_enclave
+            .expect_unseal_tx()
+            .times(1)
+            .return_const(Ok(tx_context));
+        mock_enclave
+            .expect_tx_is_well_formed()
+            .times(1)
+            .return_const(Ok((
+                WellFormedEncryptedTx::default(),
+                WellFormedTxContext::new(
+                    0,
+                    tx_hash,
+                    Default::default(),
+                    Default::default(),
+                    Default::default(),
+                    Default::default(),
+                ),
+            )));
+
+        let tx_manager = TxManagerImpl::new(mock_enclave, mock_untrusted, logger.clone());
+
+        let persisted_tx = PersistedTx {
+            sealed_tx: vec![1, 2, 3],
+            tombstone_block: 10,
+        };
+        assert_eq!(
+            tx_manager.insert_persisted(persisted_tx, 5).unwrap(),
+            tx_hash
+        );
+        assert!(tx_manager.contains(&tx_hash));
+    }
+
+    #[test_with_logger]
+    // Should reject an expired persisted transaction without unsealing it.
+    fn test_insert_persisted_expired(logger: Logger) {
+        let mock_untrusted = MockUntrustedInterfaces::new();
+        let mock_enclave = MockConsensusEnclave::new();
+        let tx_manager = TxManagerImpl::new(mock_enclave, mock_untrusted, logger.clone());
+
+        let persisted_tx = PersistedTx {
+            sealed_tx: vec![1, 2, 3],
+            tombstone_block: 10,
+        };
+        match tx_manager.insert_persisted(persisted_tx, 10) {
+            Err(TxManagerError::TransactionValidation(
+                TransactionValidationError::TombstoneBlockExceeded,
+            )) => {} // This is expected.
+            result => panic!("Unexpected result: {:?}", result),
+        }
+        assert_eq!(tx_manager.num_entries(), 0);
+    }
+
+    #[test_with_logger]
+    // Should not cache a persisted transaction that is no longer valid.
+    fn test_insert_persisted_invalid(logger: Logger) {
+        let tx_context = TxContext::default();
+        let tx_hash = tx_context.tx_hash;
+
+        let mut mock_untrusted = MockUntrustedInterfaces::new();
+        mock_untrusted
+            .expect_well_formed_check()
+            .times(1)
+            .return_const(Ok((0, vec![])));
+        mock_untrusted
+            .expect_is_valid()
+            .times(1)
+            .return_const(Err(TransactionValidationError::ContainsSpentKeyImage));
+
+        let mut mock_enclave = MockConsensusEnclave::new();
+        mock_enclave
+            .expect_unseal_tx()
+            .times(1)
+            .return_const(Ok(tx_context));
+        mock_enclave
+            .expect_tx_is_well_formed()
+            .times(1)
+            .return_const(Ok((
+                WellFormedEncryptedTx::default(),
+                WellFormedTxContext::new(
+                    0,
+                    tx_hash,
+                    Default::default(),
+                    Default::default(),
+                    Default::default(),
+                    Default::default(),
+                ),
+            )));
+
+        let tx_manager = TxManagerImpl::new(mock_enclave, mock_untrusted, logger.clone());
+
+        let persisted_tx = PersistedTx {
+            sealed_tx: vec![1, 2, 3],
+            tombstone_block: 10,
+        };
+        assert!(tx_manager.insert_persisted(persisted_tx, 5).is_err());
+        assert!(!tx_manager.contains(&tx_hash));
+    }
 }
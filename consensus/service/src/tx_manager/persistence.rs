@@ -0,0 +1,128 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! Persistence of the transaction cache across restarts.
+//!
+//! Transactions are sealed by the enclave before being written to disk, so
+//! their contents are only readable by the enclave. Reloaded transactions are
+//! unsealed and go through the well-formed and validity checks again before
+//! re-entering the cache.
+
+use displaydoc::Display;
+use mc_consensus_enclave::SealedTx;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// A sealed transaction, as persisted to disk.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct PersistedTx {
+    /// The transaction, sealed by the enclave.
+    pub sealed_tx: SealedTx,
+
+    /// The tombstone block of the transaction. Allows dropping expired
+    /// transactions without unsealing them.
+    pub tombstone_block: u64,
+}
+
+#[derive(Debug, Display)]
+pub enum TxPoolStoreError {
+    /// IO error: {0}
+    Io(io::Error),
+
+    /// Serialization error: {0}
+    Serialization(mc_util_serial::encode::Error),
+
+    /// Deserialization error: {0}
+    Deserialization(mc_util_serial::decode::Error),
+}
+
+impl From<io::Error> for TxPoolStoreError {
+    fn from(src: io::Error) -> Self {
+        Self::Io(src)
+    }
+}
+
+impl From<mc_util_serial::encode::Error> for TxPoolStoreError {
+    fn from(src: mc_util_serial::encode::Error) -> Self {
+        Self::Serialization(src)
+    }
+}
+
+impl From<mc_util_serial::decode::Error> for TxPoolStoreError {
+    fn from(src: mc_util_serial::decode::Error) -> Self {
+        Self::Deserialization(src)
+    }
+}
+
+/// Stores persisted transactions in a single file.
+#[derive(Clone, Debug)]
+pub struct TxPoolStore {
+    path: PathBuf,
+}
+
+impl TxPoolStore {
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Replace the stored transactions.
+    ///
+    /// The file is written atomically, so a crash while saving leaves the
+    /// previously saved transactions in place.
+    pub fn save(&self, persisted_txs: &[PersistedTx]) -> Result<(), TxPoolStoreError> {
+        let bytes = mc_util_serial::serialize(&persisted_txs)?;
+
+        let tmp_path = self.path.with_extension("tmp");
+        fs::write(&tmp_path, bytes)?;
+        fs::rename(&tmp_path, &self.path)?;
+
+        Ok(())
+    }
+
+    /// Load the stored transactions. Returns an empty list if nothing was
+    /// stored yet.
+    pub fn load(&self) -> Result<Vec<PersistedTx>, TxPoolStoreError> {
+        match fs::read(&self.path) {
+            Ok(bytes) => Ok(mc_util_serial::deserialize(&bytes)?),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+            Err(err) => Err(err.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempdir::TempDir;
+
+    #[test]
+    // Should load the transactions that were last saved.
+    fn test_save_and_load() {
+        let temp_dir = TempDir::new("tx_pool").unwrap();
+        let store = TxPoolStore::new(temp_dir.path().join("tx_pool"));
+
+        // Nothing was saved yet.
+        assert_eq!(store.load().unwrap(), vec![]);
+
+        let persisted_txs = vec![
+            PersistedTx {
+                sealed_tx: vec![1, 2, 3],
+                tombstone_block: 10,
+            },
+            PersistedTx {
+                sealed_tx: vec![4, 5, 6],
+                tombstone_block: 20,
+            },
+        ];
+        store.save(&persisted_txs).unwrap();
+        assert_eq!(store.load().unwrap(), persisted_txs);
+
+        // Saving replaces previously saved transactions.
+        store.save(&persisted_txs[1..]).unwrap();
+        assert_eq!(store.load().unwrap(), persisted_txs[1..]);
+    }
+}
@@ -1,6 +1,6 @@
 // Copyright (c) 2018-2022 The MobileCoin Foundation
 
-use crate::tx_manager::TxManagerResult;
+use crate::tx_manager::{PersistedTx, TxManagerResult};
 use mc_attest_enclave_api::{EnclaveMessage, PeerSession};
 use mc_common::HashSet;
 use mc_consensus_enclave::{TxContext, WellFormedEncryptedTx};
@@ -61,4 +61,20 @@ pub trait TxManager: Send {
 
     /// Get the encrypted transaction corresponding to the given hash.
     fn get_encrypted_tx(&self, tx_hash: &TxHash) -> Option<WellFormedEncryptedTx>;
+
+    /// Seal all cached transactions so that they can be persisted.
+    fn seal_all(&self) -> Vec<PersistedTx>;
+
+    /// Insert a persisted transaction into the cache. The transaction must not
+    /// be expired, and must be well-formed and valid w.r.t. the current
+    /// ledger.
+    ///
+    /// # Arguments
+    /// * `persisted_tx` - A transaction sealed by `seal_all`.
+    /// * `block_index` - Current block index.
+    fn insert_persisted(
+        &self,
+        persisted_tx: PersistedTx,
+        block_index: u64,
+    ) -> TxManagerResult<TxHash>;
 }
@@ -0,0 +1,105 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! Periodically persists the transaction cache, so that pending transactions
+//! survive a restart of the consensus service.
+
+use crate::tx_manager::{TxManager, TxPoolStore};
+use mc_common::logger::{log, Logger};
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+/// How often the persister thread checks whether it was asked to stop.
+const STOP_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+pub struct TxPoolPersister {
+    join_handle: Option<thread::JoinHandle<()>>,
+    stop_requested: Arc<AtomicBool>,
+}
+
+impl TxPoolPersister {
+    /// Start persisting the cached transactions of `tx_manager` to `store`
+    /// every `interval`. The transactions are persisted one last time when
+    /// the persister is stopped.
+    pub fn start<TXM: TxManager + Sync + 'static>(
+        tx_manager: Arc<TXM>,
+        store: TxPoolStore,
+        interval: Duration,
+        logger: Logger,
+    ) -> Self {
+        let stop_requested = Arc::new(AtomicBool::new(false));
+        let thread_stop_requested = stop_requested.clone();
+        let join_handle = Some(
+            thread::Builder::new()
+                .name("TxPoolPersister".into())
+                .spawn(move || {
+                    Self::thread_entrypoint(
+                        tx_manager,
+                        store,
+                        interval,
+                        thread_stop_requested,
+                        logger,
+                    )
+                })
+                .expect("Failed spawning TxPoolPersister thread"),
+        );
+
+        Self {
+            join_handle,
+            stop_requested,
+        }
+    }
+
+    pub fn stop(&mut self) {
+        self.stop_requested.store(true, Ordering::SeqCst);
+        if let Some(thread) = self.join_handle.take() {
+            thread.join().expect("TxPoolPersister thread join failed");
+        }
+    }
+
+    fn thread_entrypoint<TXM: TxManager>(
+        tx_manager: Arc<TXM>,
+        store: TxPoolStore,
+        interval: Duration,
+        stop_requested: Arc<AtomicBool>,
+        logger: Logger,
+    ) {
+        log::debug!(logger, "TxPoolPersister thread has started.");
+
+        let mut last_persisted_at = Instant::now();
+
+        loop {
+            if stop_requested.load(Ordering::SeqCst) {
+                log::debug!(logger, "TxPoolPersister stop requested.");
+                Self::persist(&*tx_manager, &store, &logger);
+                break;
+            }
+
+            if last_persisted_at.elapsed() >= interval {
+                last_persisted_at = Instant::now();
+                Self::persist(&*tx_manager, &store, &logger);
+            }
+
+            thread::sleep(STOP_CHECK_INTERVAL);
+        }
+    }
+
+    fn persist(tx_manager: &impl TxManager, store: &TxPoolStore, logger: &Logger) {
+        let persisted_txs = tx_manager.seal_all();
+        match store.save(&persisted_txs) {
+            Ok(()) => log::trace!(logger, "Persisted {} transactions", persisted_txs.len()),
+            Err(err) => log::error!(logger, "Failed persisting transactions: {}", err),
+        }
+    }
+}
+
+impl Drop for TxPoolPersister {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
@@ -0,0 +1,418 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! Tracks the progress of client-submitted transactions through consensus and
+//! notifies anyone who subscribed to hear about it.
+//!
+//! Transactions are tracked from the moment they are accepted into the pool by
+//! the client API until they are either externalized in a block or purged from
+//! the pool because their tombstone block was reached. At that point any
+//! subscription streams are closed, but the final status is kept for a
+//! retention window, so that clients subscribing shortly after (by hash or by
+//! key image) still learn what happened to their transaction.
+
+use crate::counters;
+use futures::channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender};
+use mc_transaction_core::{ring_signature::KeyImage, tx::TxHash};
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+/// Maximum number of transactions that can be tracked at any given time.
+/// Transactions accepted while the tracker is full can still be processed by
+/// the network, but it is not possible to subscribe to their status.
+pub const MAX_TRACKED_TXS: usize = 10_000;
+
+/// Maximum number of finalized transactions whose final status is retained.
+/// The oldest ones are forgotten first.
+pub const MAX_FINALIZED_TXS: usize = 10_000;
+
+/// How long the final status of a transaction is retained.
+pub const FINALIZED_TX_RETENTION: Duration = Duration::from_secs(10 * 60);
+
+/// A transaction status event.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TxStatusEvent {
+    /// The transaction was validated and added to the pool of pending values.
+    AcceptedToPool,
+
+    /// The transaction was nominated by this node for the given slot index.
+    Nominated(u64),
+
+    /// The transaction was externalized in the block with the given index.
+    Externalized(u64),
+
+    /// The transaction was purged from the pool before being externalized.
+    /// Contains the block index at which it was considered expired.
+    Expired(u64),
+}
+
+impl TxStatusEvent {
+    /// Returns true if no further events can follow this one.
+    pub fn is_final(&self) -> bool {
+        matches!(self, Self::Externalized(_) | Self::Expired(_))
+    }
+}
+
+/// A single tracked transaction.
+struct TrackedTx {
+    /// Key images spent by the transaction.
+    key_images: Vec<KeyImage>,
+
+    /// The most recent event observed for this transaction.
+    latest: TxStatusEvent,
+
+    /// Subscribers waiting to hear about further events.
+    subscribers: Vec<UnboundedSender<TxStatusEvent>>,
+
+    /// When the transaction reached a final state, if it did.
+    finalized_at: Option<Instant>,
+}
+
+#[derive(Default)]
+struct TrackerState {
+    /// Tracked transactions, both pending and recently finalized.
+    txs: HashMap<TxHash, TrackedTx>,
+
+    /// Recently finalized transactions, oldest first.
+    finalized: VecDeque<TxHash>,
+
+    /// Maps key images to the tracked transactions that spend them. Several
+    /// pending transactions may spend the same key image, although at most
+    /// one of them can be externalized.
+    key_images: HashMap<KeyImage, Vec<TxHash>>,
+}
+
+impl TrackerState {
+    /// Number of transactions that have not reached a final state yet.
+    fn num_pending(&self) -> usize {
+        self.txs.len() - self.finalized.len()
+    }
+
+    fn subscribe(&mut self, tx_hash: &TxHash) -> Option<UnboundedReceiver<TxStatusEvent>> {
+        let tracked_tx = self.txs.get_mut(tx_hash)?;
+
+        let (sender, receiver) = unbounded();
+        // Finalized transactions only report their final event, after which the
+        // stream ends since the sender is dropped.
+        if sender.unbounded_send(tracked_tx.latest).is_ok() && tracked_tx.finalized_at.is_none() {
+            tracked_tx.subscribers.push(sender);
+        }
+        counters::TX_STATUS_SUBSCRIPTIONS.inc();
+        Some(receiver)
+    }
+
+    /// Forget finalized transactions that are past the retention window, or in
+    /// excess of [MAX_FINALIZED_TXS].
+    fn prune_finalized(&mut self, retention: Duration, now: Instant) {
+        while let Some(tx_hash) = self.finalized.front() {
+            let finalized_at = self
+                .txs
+                .get(tx_hash)
+                .and_then(|tracked_tx| tracked_tx.finalized_at)
+                .expect("finalized tx is tracked");
+            if self.finalized.len() <= MAX_FINALIZED_TXS
+                && now.saturating_duration_since(finalized_at) < retention
+            {
+                break;
+            }
+
+            let tx_hash = self.finalized.pop_front().expect("front exists");
+            if let Some(tracked_tx) = self.txs.remove(&tx_hash) {
+                for key_image in tracked_tx.key_images.iter() {
+                    if let Some(tx_hashes) = self.key_images.get_mut(key_image) {
+                        tx_hashes.retain(|other| *other != tx_hash);
+                        if tx_hashes.is_empty() {
+                            self.key_images.remove(key_image);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Keeps track of submitted transactions and their subscribers.
+pub struct TxStatusTracker {
+    state: Mutex<TrackerState>,
+
+    /// How long the final status of a transaction is retained.
+    finalized_tx_retention: Duration,
+}
+
+impl Default for TxStatusTracker {
+    fn default() -> Self {
+        Self::new(FINALIZED_TX_RETENTION)
+    }
+}
+
+impl TxStatusTracker {
+    /// Create a tracker that retains the final status of transactions for
+    /// `finalized_tx_retention`.
+    pub fn new(finalized_tx_retention: Duration) -> Self {
+        Self {
+            state: Mutex::new(TrackerState::default()),
+            finalized_tx_retention,
+        }
+    }
+
+    /// Start tracking a transaction that was accepted to the pool.
+    ///
+    /// # Arguments
+    /// * `tx_hash` - Hash of the accepted transaction.
+    /// * `key_images` - Key images spent by the transaction.
+    ///
+    /// Returns false if the tracker is full and the transaction was not
+    /// tracked.
+    pub fn track(&self, tx_hash: TxHash, key_images: Vec<KeyImage>) -> bool {
+        let mut state = self.state.lock().expect("mutex poisoned");
+
+        if state.txs.contains_key(&tx_hash) {
+            return true;
+        }
+        if state.num_pending() >= MAX_TRACKED_TXS {
+            return false;
+        }
+
+        for key_image in key_images.iter() {
+            state
+                .key_images
+                .entry(*key_image)
+                .or_default()
+                .push(tx_hash);
+        }
+        state.txs.insert(
+            tx_hash,
+            TrackedTx {
+                key_images,
+                latest: TxStatusEvent::AcceptedToPool,
+                subscribers: Vec::new(),
+                finalized_at: None,
+            },
+        );
+        counters::TX_STATUS_TRACKED_TXS.set(state.num_pending() as i64);
+        true
+    }
+
+    /// Subscribe to status events of a tracked transaction.
+    ///
+    /// The returned stream immediately yields the most recent known event, and
+    /// terminates after a final event (see [TxStatusEvent::is_final]).
+    /// Returns None if the transaction is not being tracked, and did not reach
+    /// a final state within the retention window either.
+    pub fn subscribe(&self, tx_hash: &TxHash) -> Option<UnboundedReceiver<TxStatusEvent>> {
+        let mut state = self.state.lock().expect("mutex poisoned");
+        state.prune_finalized(self.finalized_tx_retention, Instant::now());
+        state.subscribe(tx_hash)
+    }
+
+    /// Subscribe to status events of every tracked transaction spending the
+    /// given key image, including ones that reached a final state within the
+    /// retention window.
+    ///
+    /// Returns the hash of each matching transaction together with its event
+    /// stream. Empty if no tracked transaction spends this key image.
+    pub fn subscribe_by_key_image(
+        &self,
+        key_image: &KeyImage,
+    ) -> Vec<(TxHash, UnboundedReceiver<TxStatusEvent>)> {
+        let mut state = self.state.lock().expect("mutex poisoned");
+        state.prune_finalized(self.finalized_tx_retention, Instant::now());
+
+        let tx_hashes = state.key_images.get(key_image).cloned().unwrap_or_default();
+        tx_hashes
+            .into_iter()
+            .filter_map(|tx_hash| {
+                state
+                    .subscribe(&tx_hash)
+                    .map(|receiver| (tx_hash, receiver))
+            })
+            .collect()
+    }
+
+    /// Record a new event for a set of transactions. Transactions that are not
+    /// tracked, or already reached a final state, are ignored.
+    pub fn notify<'a>(
+        &self,
+        tx_hashes: impl IntoIterator<Item = &'a TxHash>,
+        event: TxStatusEvent,
+    ) {
+        let mut state = self.state.lock().expect("mutex poisoned");
+        let now = Instant::now();
+
+        for tx_hash in tx_hashes {
+            let tracked_tx = match state.txs.get_mut(tx_hash) {
+                Some(tracked_tx) if tracked_tx.finalized_at.is_none() => tracked_tx,
+                _ => continue,
+            };
+
+            tracked_tx.latest = event;
+            // Drop subscribers that went away.
+            tracked_tx
+                .subscribers
+                .retain(|sender| sender.unbounded_send(event).is_ok());
+
+            if event.is_final() {
+                // Dropping the senders terminates the subscriber streams.
+                tracked_tx.subscribers.clear();
+                tracked_tx.finalized_at = Some(now);
+                state.finalized.push_back(*tx_hash);
+            }
+        }
+
+        state.prune_finalized(self.finalized_tx_retention, now);
+        counters::TX_STATUS_TRACKED_TXS.set(state.num_pending() as i64);
+    }
+
+    /// Number of transactions currently being tracked that have not reached a
+    /// final state yet.
+    pub fn len(&self) -> usize {
+        self.state.lock().expect("mutex poisoned").num_pending()
+    }
+
+    /// Returns true if no transactions are currently pending.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::{executor::block_on, StreamExt};
+
+    #[test]
+    fn subscribe_receives_events_until_externalized() {
+        let tracker = TxStatusTracker::default();
+        let tx_hash = TxHash([1u8; 32]);
+        assert!(tracker.track(tx_hash, vec![KeyImage::from(1)]));
+
+        let receiver = tracker.subscribe(&tx_hash).unwrap();
+        tracker.notify(&[tx_hash], TxStatusEvent::Nominated(5));
+        tracker.notify(&[tx_hash], TxStatusEvent::Externalized(5));
+
+        let events: Vec<_> = block_on(receiver.collect());
+        assert_eq!(
+            events,
+            vec![
+                TxStatusEvent::AcceptedToPool,
+                TxStatusEvent::Nominated(5),
+                TxStatusEvent::Externalized(5),
+            ]
+        );
+
+        // The transaction is no longer pending once it reached a final state, but
+        // its final status can still be subscribed to.
+        assert!(tracker.is_empty());
+        let events: Vec<_> = block_on(tracker.subscribe(&tx_hash).unwrap().collect());
+        assert_eq!(events, vec![TxStatusEvent::Externalized(5)]);
+
+        let subscriptions = tracker.subscribe_by_key_image(&KeyImage::from(1));
+        assert_eq!(subscriptions.len(), 1);
+        let (found_hash, receiver) = subscriptions.into_iter().next().unwrap();
+        assert_eq!(found_hash, tx_hash);
+        let events: Vec<_> = block_on(receiver.collect());
+        assert_eq!(events, vec![TxStatusEvent::Externalized(5)]);
+
+        // Later events are ignored.
+        tracker.notify(&[tx_hash], TxStatusEvent::Expired(6));
+        let events: Vec<_> = block_on(tracker.subscribe(&tx_hash).unwrap().collect());
+        assert_eq!(events, vec![TxStatusEvent::Externalized(5)]);
+    }
+
+    #[test]
+    fn finalized_txs_are_forgotten_after_retention() {
+        let tracker = TxStatusTracker::new(Duration::ZERO);
+        let tx_hash = TxHash([1u8; 32]);
+        assert!(tracker.track(tx_hash, vec![KeyImage::from(1)]));
+
+        tracker.notify(&[tx_hash], TxStatusEvent::Externalized(5));
+
+        assert!(tracker.subscribe(&tx_hash).is_none());
+        assert!(tracker
+            .subscribe_by_key_image(&KeyImage::from(1))
+            .is_empty());
+    }
+
+    #[test]
+    fn subscribe_by_key_image_follows_all_txs_spending_it() {
+        let tracker = TxStatusTracker::default();
+        let tx_hash1 = TxHash([1u8; 32]);
+        let tx_hash2 = TxHash([2u8; 32]);
+        tracker.track(tx_hash1, vec![KeyImage::from(1), KeyImage::from(2)]);
+        tracker.track(tx_hash2, vec![KeyImage::from(1)]);
+
+        let subscriptions = tracker.subscribe_by_key_image(&KeyImage::from(1));
+        let hashes: Vec<_> = subscriptions.iter().map(|(tx_hash, _)| *tx_hash).collect();
+        assert_eq!(hashes, vec![tx_hash1, tx_hash2]);
+
+        // Finalizing one of the transactions does not affect the other.
+        tracker.notify(&[tx_hash1], TxStatusEvent::Expired(4));
+        tracker.notify(&[tx_hash2], TxStatusEvent::Nominated(5));
+        tracker.notify(&[tx_hash2], TxStatusEvent::Externalized(5));
+
+        let events: Vec<_> = subscriptions
+            .into_iter()
+            .map(|(tx_hash, receiver)| (tx_hash, block_on(receiver.collect::<Vec<_>>())))
+            .collect();
+        assert_eq!(
+            events,
+            vec![
+                (
+                    tx_hash1,
+                    vec![TxStatusEvent::AcceptedToPool, TxStatusEvent::Expired(4)]
+                ),
+                (
+                    tx_hash2,
+                    vec![
+                        TxStatusEvent::AcceptedToPool,
+                        TxStatusEvent::Nominated(5),
+                        TxStatusEvent::Externalized(5)
+                    ]
+                ),
+            ]
+        );
+        assert_eq!(tracker.subscribe_by_key_image(&KeyImage::from(1)).len(), 2);
+        assert_eq!(tracker.subscribe_by_key_image(&KeyImage::from(2)).len(), 1);
+    }
+
+    #[test]
+    fn subscribe_by_key_image_reports_expiry() {
+        let tracker = TxStatusTracker::default();
+        let tx_hash = TxHash([2u8; 32]);
+        tracker.track(tx_hash, vec![KeyImage::from(7), KeyImage::from(8)]);
+
+        let mut subscriptions = tracker.subscribe_by_key_image(&KeyImage::from(8));
+        assert_eq!(subscriptions.len(), 1);
+        let (found_hash, receiver) = subscriptions.remove(0);
+        assert_eq!(found_hash, tx_hash);
+
+        // Events for unrelated transactions are ignored.
+        tracker.notify(&[TxHash([3u8; 32])], TxStatusEvent::Externalized(10));
+        tracker.notify(&[tx_hash], TxStatusEvent::Expired(20));
+
+        let events: Vec<_> = block_on(receiver.collect());
+        assert_eq!(
+            events,
+            vec![TxStatusEvent::AcceptedToPool, TxStatusEvent::Expired(20)]
+        );
+    }
+
+    #[test]
+    fn track_refuses_when_full() {
+        let tracker = TxStatusTracker::default();
+        for i in 0..MAX_TRACKED_TXS {
+            let mut hash = [0u8; 32];
+            hash[..8].copy_from_slice(&(i as u64).to_le_bytes());
+            assert!(tracker.track(TxHash(hash), vec![]));
+        }
+        assert!(!tracker.track(TxHash([0xffu8; 32]), vec![]));
+        assert_eq!(tracker.len(), MAX_TRACKED_TXS);
+
+        // Finalized transactions do not count towards the limit.
+        tracker.notify(&[TxHash([0u8; 32])], TxStatusEvent::Externalized(1));
+        assert!(tracker.track(TxHash([0xffu8; 32]), vec![]));
+        assert_eq!(tracker.len(), MAX_TRACKED_TXS);
+    }
+}
@@ -70,6 +70,11 @@ impl<
 }
 
 /// A set of M-out-of-N public keys.
+///
+/// A signer set may also contain nested signer sets, each of which counts as a
+/// single signer towards the threshold once its own threshold is met. For
+/// example, "2 of (3 ops keys) AND 1 of (2 compliance keys)" is a signer set
+/// with a threshold of 2 and two nested signer sets.
 #[derive(
     Clone, Deserialize, Digestible, Eq, Hash, Message, Ord, PartialEq, PartialOrd, Serialize,
 )]
@@ -79,15 +84,36 @@ pub struct SignerSet<P: Default + PublicKey + Message> {
     #[prost(message, repeated, tag = "1")]
     signers: Vec<P>,
 
-    /// Minimum number of signers required.
+    /// Minimum number of signers and satisfied nested signer sets required.
     #[prost(uint32, tag = "2")]
     threshold: u32,
+
+    /// List of nested signer sets.
+    /// This is omitted from the digest when empty, so the hash of signer sets
+    /// without nested signer sets is unchanged.
+    #[prost(message, repeated, tag = "3")]
+    #[serde(default)]
+    multi_signers: Vec<SignerSet<P>>,
 }
 
 impl<P: Default + PublicKey + Message> SignerSet<P> {
     /// Construct a new `SignerSet` from a list of public keys and threshold.
     pub fn new(signers: Vec<P>, threshold: u32) -> Self {
-        Self { signers, threshold }
+        Self::new_with_multi(signers, Vec::new(), threshold)
+    }
+
+    /// Construct a new `SignerSet` from a list of public keys, a list of
+    /// nested signer sets and threshold.
+    pub fn new_with_multi(
+        signers: Vec<P>,
+        multi_signers: Vec<SignerSet<P>>,
+        threshold: u32,
+    ) -> Self {
+        Self {
+            signers,
+            threshold,
+            multi_signers,
+        }
     }
 
     /// Get the list of potential signers.
@@ -95,11 +121,59 @@ impl<P: Default + PublicKey + Message> SignerSet<P> {
         &self.signers
     }
 
+    /// Get the list of nested signer sets.
+    pub fn multi_signers(&self) -> &[SignerSet<P>] {
+        &self.multi_signers
+    }
+
     /// Get the threshold.
     pub fn threshold(&self) -> u32 {
         self.threshold
     }
 
+    /// Get the number of nested signer set levels below this one, i.e. 0 for
+    /// a signer set without nested signer sets.
+    pub fn depth(&self) -> usize {
+        self.multi_signers
+            .iter()
+            .map(|multi_signer| multi_signer.depth() + 1)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Get the sorted and deduped list of all potential signers, including
+    /// those of nested signer sets.
+    pub fn all_signers(&self) -> Vec<P> {
+        let mut all_signers = self.signers.clone();
+        for multi_signer in self.multi_signers.iter() {
+            all_signers.extend(multi_signer.all_signers());
+        }
+        all_signers.sort();
+        all_signers.dedup();
+        all_signers
+    }
+
+    /// Check whether the given signers meet the threshold of this signer set.
+    /// Each potential signer and each satisfied nested signer set counts once
+    /// towards the threshold.
+    pub fn is_satisfied_by(&self, signers: &[P]) -> bool {
+        let mut potential_signers = self.signers.clone();
+        potential_signers.sort();
+        potential_signers.dedup();
+
+        let num_matched_signers = potential_signers
+            .iter()
+            .filter(|signer| signers.contains(signer))
+            .count();
+        let num_matched_multi_signers = self
+            .multi_signers
+            .iter()
+            .filter(|multi_signer| multi_signer.is_satisfied_by(signers))
+            .count();
+
+        num_matched_signers + num_matched_multi_signers >= self.threshold as usize
+    }
+
     /// Verify a message against a multi-signature, returning the list of
     /// signers that signed it.
     pub fn verify<
@@ -122,10 +196,12 @@ impl<P: Default + PublicKey + Message> SignerSet<P> {
     where
         P: Verifier<S>,
     {
-        // If the signature contains less than the threshold number of signers or more
-        // than the hardcoded limit, there's no point in trying.
-        if multi_sig.signatures.len() < self.threshold as usize
-            || multi_sig.signatures.len() > MAX_SIGNATURES
+        // If the signature contains more than the hardcoded limit, there's no point in
+        // trying. Without nested signer sets, the same holds if it contains less than
+        // the threshold number of signers.
+        if multi_sig.signatures.len() > MAX_SIGNATURES
+            || (self.multi_signers.is_empty()
+                && multi_sig.signatures.len() < self.threshold as usize)
         {
             return Err(SignatureError::new());
         }
@@ -134,9 +210,7 @@ impl<P: Default + PublicKey + Message> SignerSet<P> {
         // While the verification code below should be immune to duplicate signers or
         // signatures, the overhead of deduping them is negligible and being
         // extra-safe is a good idea.
-        let mut potential_signers = self.signers.clone();
-        potential_signers.sort();
-        potential_signers.dedup();
+        let mut potential_signers = self.all_signers();
 
         let mut signatures = multi_sig.signatures.clone();
         signatures.sort_by(|a, b| a.as_ref().cmp(b.as_ref()));
@@ -158,7 +232,7 @@ impl<P: Default + PublicKey + Message> SignerSet<P> {
         }
 
         // Did we pass the threshold of verified signatures?
-        if matched_signers.len() < self.threshold as usize {
+        if !self.is_satisfied_by(&matched_signers) {
             return Err(SignatureError::new());
         }
 
@@ -170,6 +244,7 @@ impl<P: Default + PublicKey + Message> SignerSet<P> {
 mod test {
     use super::*;
     use alloc::vec;
+    use mc_crypto_digestible::MerlinTranscript;
     use mc_crypto_keys::{Ed25519Pair, Ed25519Public, Signer};
     use mc_util_from_random::FromRandom;
     use rand_core::SeedableRng;
@@ -433,6 +508,134 @@ mod test {
         );
     }
 
+    #[test]
+    fn ed25519_verify_nested_signer_sets() {
+        let mut rng = Hc128Rng::from_seed([1u8; 32]);
+        let ops = (0..3)
+            .map(|_| Ed25519Pair::from_random(&mut rng))
+            .collect::<Vec<_>>();
+        let compliance = (0..2)
+            .map(|_| Ed25519Pair::from_random(&mut rng))
+            .collect::<Vec<_>>();
+        let admin = Ed25519Pair::from_random(&mut rng);
+        let outsider = Ed25519Pair::from_random(&mut rng);
+
+        // 2 of (3 ops keys) AND 1 of (2 compliance keys)
+        let ops_set = SignerSet::new(ops.iter().map(|s| s.public_key()).collect(), 2);
+        let compliance_set = SignerSet::new(compliance.iter().map(|s| s.public_key()).collect(), 1);
+        let signer_set =
+            SignerSet::new_with_multi(vec![], vec![ops_set.clone(), compliance_set.clone()], 2);
+        assert_eq!(signer_set.depth(), 1);
+        assert_eq!(signer_set.all_signers().len(), 5);
+
+        let message = b"this is a test";
+        let sign = |signers: &[&Ed25519Pair]| {
+            MultiSig::new(
+                signers
+                    .iter()
+                    .map(|signer| signer.try_sign(message.as_ref()).unwrap())
+                    .collect(),
+            )
+        };
+
+        // Both nested sets are satisfied.
+        assert_eq_ignore_order(
+            signer_set
+                .verify(message.as_ref(), &sign(&[&ops[0], &ops[2], &compliance[1]]))
+                .unwrap(),
+            vec![
+                ops[0].public_key(),
+                ops[2].public_key(),
+                compliance[1].public_key(),
+            ],
+        );
+
+        // Only one nested set is satisfied.
+        assert!(signer_set
+            .verify(message.as_ref(), &sign(&[&ops[0], &ops[1], &ops[2]]))
+            .is_err());
+        assert!(signer_set
+            .verify(
+                message.as_ref(),
+                &sign(&[&ops[0], &compliance[0], &compliance[1]])
+            )
+            .is_err());
+        assert!(signer_set
+            .verify(
+                message.as_ref(),
+                &sign(&[&ops[0], &outsider, &compliance[0]])
+            )
+            .is_err());
+
+        // Signing twice with the same ops key does not satisfy the ops set.
+        assert!(signer_set
+            .verify(message.as_ref(), &sign(&[&ops[0], &ops[0], &compliance[0]]))
+            .is_err());
+
+        // An individual signer and a nested set together: admin AND 1 of
+        // (2 compliance keys).
+        let signer_set =
+            SignerSet::new_with_multi(vec![admin.public_key()], vec![compliance_set.clone()], 2);
+        assert!(signer_set
+            .verify(message.as_ref(), &sign(&[&admin, &compliance[0]]))
+            .is_ok());
+        assert!(signer_set
+            .verify(message.as_ref(), &sign(&[&admin, &ops[0]]))
+            .is_err());
+
+        // Two levels of nesting: 1 of (admin, (2 of (3 ops keys) AND 1 of (2
+        // compliance keys))).
+        let inner = SignerSet::new_with_multi(vec![], vec![ops_set, compliance_set], 2);
+        let signer_set = SignerSet::new_with_multi(vec![admin.public_key()], vec![inner], 1);
+        assert_eq!(signer_set.depth(), 2);
+        assert!(signer_set
+            .verify(message.as_ref(), &sign(&[&admin]))
+            .is_ok());
+        assert!(signer_set
+            .verify(message.as_ref(), &sign(&[&ops[1], &ops[2], &compliance[0]]))
+            .is_ok());
+        assert!(signer_set
+            .verify(message.as_ref(), &sign(&[&ops[1], &compliance[0]]))
+            .is_err());
+    }
+
+    #[test]
+    fn test_nested_signer_set_encoding() {
+        let mut rng = Hc128Rng::from_seed([1u8; 32]);
+        let signer1 = Ed25519Pair::from_random(&mut rng);
+        let signer2 = Ed25519Pair::from_random(&mut rng);
+        let signer3 = Ed25519Pair::from_random(&mut rng);
+
+        let nested = SignerSet::new(vec![signer2.public_key(), signer3.public_key()], 1);
+        let signer_set = SignerSet::new_with_multi(vec![signer1.public_key()], vec![nested], 2);
+
+        assert_eq!(
+            signer_set,
+            mc_util_serial::decode(&mc_util_serial::encode(&signer_set)).unwrap(),
+        );
+        assert_eq!(
+            signer_set,
+            mc_util_serial::deserialize(&mc_util_serial::serialize(&signer_set).unwrap()).unwrap(),
+        );
+
+        // A signer set without nested signer sets has the same digest as before
+        // nested signer sets were introduced.
+        #[derive(Digestible)]
+        #[digestible(name = "SignerSet")]
+        struct FlatSignerSet {
+            signers: Vec<Ed25519Public>,
+            threshold: u32,
+        }
+        let flat = FlatSignerSet {
+            signers: vec![signer1.public_key(), signer2.public_key()],
+            threshold: 1,
+        };
+        assert_eq!(
+            flat.digest32::<MerlinTranscript>(b"test"),
+            SignerSet::new(flat.signers.clone(), 1).digest32::<MerlinTranscript>(b"test"),
+        );
+    }
+
     #[test]
     fn test_serde_works() {
         let mut rng = Hc128Rng::from_seed([1u8; 32]);
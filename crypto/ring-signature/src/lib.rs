@@ -22,7 +22,7 @@ pub mod proptest_fixtures;
 pub use amount::{Commitment, CompressedCommitment};
 pub use ring_signature::{
     generators, CryptoRngCore, CurveScalar, Error, GeneratorCache, KeyImage, PedersenGens,
-    ReducedTxOut, RingMLSAG, Scalar,
+    ReducedTxOut, RingMLSAG, RingMemberCache, Scalar,
 };
 
 /// Get the shared secret for a transaction output.
@@ -2,10 +2,10 @@
 
 extern crate alloc;
 
-use alloc::{vec, vec::Vec};
+use alloc::{collections::BTreeMap, vec, vec::Vec};
 use core::convert::TryFrom;
 
-use curve25519_dalek::ristretto::RistrettoPoint;
+use curve25519_dalek::{ristretto::RistrettoPoint, traits::VartimeMultiscalarMul};
 use mc_crypto_digestible::Digestible;
 use mc_crypto_hashes::{Blake2b512, Digest};
 use mc_crypto_keys::{CompressedRistrettoPublic, RistrettoPrivate, RistrettoPublic};
@@ -258,6 +258,29 @@ impl RingMLSAG {
         message: &[u8],
         ring: &[ReducedTxOut],
         output_commitment: &CompressedCommitment,
+    ) -> Result<(), Error> {
+        self.verify_with_cache(
+            message,
+            ring,
+            output_commitment,
+            &mut RingMemberCache::default(),
+        )
+    }
+
+    /// Verify MLSAG signature, reusing the decompressed ring members of
+    /// previously verified signatures.
+    ///
+    /// # Arguments
+    /// * `message` - Message to be signed.
+    /// * `ring` - A ring of input onetime addresses and amount commitments.
+    /// * `output_commitment` - Output amount commitment.
+    /// * `cache` - Ring members decompressed by earlier verifications.
+    pub fn verify_with_cache(
+        &self,
+        message: &[u8],
+        ring: &[ReducedTxOut],
+        output_commitment: &CompressedCommitment,
+        cache: &mut RingMemberCache,
     ) -> Result<(), Error> {
         let ring_size = ring.len();
         // `responses` must contain `2 * ring_size` elements.
@@ -265,8 +288,6 @@ impl RingMLSAG {
             return Err(Error::LengthMismatch(2 * ring_size, self.responses.len()));
         }
 
-        let G = B_BLINDING;
-
         // The key image must decompress.
         // This ensures that the key image encodes a valid Ristretto point.
         let I: RistrettoPoint = self
@@ -287,7 +308,7 @@ impl RingMLSAG {
         // Ring must decompress.
         // This ensures that each address and commitment encodes a valid Ristretto
         // point.
-        let decompressed_ring = decompress_ring(ring)?;
+        let decompressed_ring = cache.decompress_ring(ring)?;
 
         // Scalars must be canonical.
         if !self.c_zero.scalar.is_canonical() {
@@ -304,7 +325,7 @@ impl RingMLSAG {
         // Recompute challenges.
         let mut recomputed_c = vec![Scalar::zero(); ring.len()];
 
-        for (i, (P_i, input_commitment)) in decompressed_ring.iter().enumerate() {
+        for (i, (P_i, hash_of_P_i, input_commitment)) in decompressed_ring.iter().enumerate() {
             let c_i = if i == 0 {
                 // Initialize loop using the signature's c_0 term.
                 self.c_zero.scalar
@@ -322,9 +343,18 @@ impl RingMLSAG {
             // * Z_i is the i^th "commitment to zero" = output_commitment - i^th
             //   input_commitment.
 
-            let L0 = r[2 * i] * G + c_i * P_i.as_ref();
-            let R0 = r[2 * i] * hash_to_point(P_i) + c_i * I;
-            let L1 = r[2 * i + 1] * G + c_i * (output_commitment.point - input_commitment.point);
+            //
+            // All of these are public, so variable-time arithmetic is used. G is the
+            // Ristretto basepoint, which allows using its precomputed tables.
+
+            let L0 =
+                RistrettoPoint::vartime_double_scalar_mul_basepoint(&c_i, P_i.as_ref(), &r[2 * i]);
+            let R0 = RistrettoPoint::vartime_multiscalar_mul(&[r[2 * i], c_i], &[*hash_of_P_i, I]);
+            let L1 = RistrettoPoint::vartime_double_scalar_mul_basepoint(
+                &c_i,
+                &(output_commitment.point - input_commitment.point),
+                &r[2 * i + 1],
+            );
 
             recomputed_c[(i + 1) % ring_size] = challenge(message, &self.key_image, &L0, &R0, &L1);
         }
@@ -367,6 +397,59 @@ fn decompress_ring(ring: &[ReducedTxOut]) -> Result<Vec<(RistrettoPublic, Commit
     Ok(decompressed_ring)
 }
 
+/// Decompressed ring members, shared between MLSAG verifications.
+///
+/// This is only a cache: each MLSAG is still verified on its own. The
+/// transactions of a block often use the same TxOuts as mixins, so
+/// decompressing each ring member and hashing it to a point once saves work
+/// when verifying the whole block.
+#[derive(Default)]
+pub struct RingMemberCache {
+    /// Target key -> (decompressed target key, hash_to_point(target key))
+    target_keys: BTreeMap<[u8; 32], (RistrettoPublic, RistrettoPoint)>,
+
+    /// Compressed commitment -> decompressed commitment
+    commitments: BTreeMap<[u8; 32], Commitment>,
+}
+
+impl RingMemberCache {
+    /// Decompress a ring, returning the target key, its hash to point and the
+    /// amount commitment of each ring member.
+    fn decompress_ring(
+        &mut self,
+        ring: &[ReducedTxOut],
+    ) -> Result<Vec<(RistrettoPublic, RistrettoPoint, Commitment)>, Error> {
+        ring.iter()
+            .map(|tx_out| {
+                let target_key_bytes: &[u8; 32] = tx_out.target_key.as_ref();
+                let (target_key, hash_of_target_key) = match self.target_keys.get(target_key_bytes)
+                {
+                    Some(decompressed) => *decompressed,
+                    None => {
+                        let target_key = RistrettoPublic::try_from(&tx_out.target_key)
+                            .map_err(|_e| Error::InvalidCurvePoint)?;
+                        let decompressed = (target_key, hash_to_point(&target_key));
+                        self.target_keys.insert(*target_key_bytes, decompressed);
+                        decompressed
+                    }
+                };
+
+                let commitment = match self.commitments.get(tx_out.commitment.point.as_bytes()) {
+                    Some(commitment) => *commitment,
+                    None => {
+                        let commitment = Commitment::try_from(&tx_out.commitment)?;
+                        self.commitments
+                            .insert(tx_out.commitment.point.to_bytes(), commitment);
+                        commitment
+                    }
+                };
+
+                Ok((target_key, hash_of_target_key, commitment))
+            })
+            .collect()
+    }
+}
+
 #[cfg(test)]
 mod mlsag_tests {
     use super::*;
@@ -586,6 +669,36 @@ mod mlsag_tests {
                 .is_ok());
         }
 
+        #[test]
+        // `verify_with_cache` should accept valid signatures sharing ring members, and
+        // reject a modified one.
+        fn test_verify_with_cache_shares_ring_members(
+            num_mixins in 1..17usize,
+            seed in any::<[u8; 32]>(),
+        ) {
+            let mut rng: RngType = SeedableRng::from_seed(seed);
+            let pseudo_output_blinding = Scalar::random(&mut rng);
+            let params = RingMLSAGParameters::random(num_mixins, pseudo_output_blinding, &mut rng);
+            let output_commitment = CompressedCommitment::new(params.value, params.pseudo_output_blinding, &params.generator);
+
+            let mut cache = RingMemberCache::default();
+            for _ in 0..3 {
+                // Signatures over the same ring, as when mixins are reused.
+                let signature = params.sign(&mut rng).unwrap();
+                assert!(signature
+                    .verify_with_cache(&params.message, &params.ring, &output_commitment, &mut cache)
+                    .is_ok());
+            }
+
+            let mut wrong_message = params.message;
+            wrong_message[0] = !wrong_message[0];
+            let signature = params.sign(&mut rng).unwrap();
+            assert_eq!(
+                signature.verify_with_cache(&wrong_message, &params.ring, &output_commitment, &mut cache),
+                Err(Error::InvalidSignature)
+            );
+        }
+
         #[test]
         // `verify` should reject a signature signed with wrong onetime_private_key.
         fn test_verify_rejects_signature_signed_with_wrong_onetime_private_key(
@@ -75,6 +75,7 @@ fn fog_view_query_request_aad_round_trip() {
         let test_val = mc_fog_types::view::QueryRequestAAD {
             start_from_user_event_id: rng.next_u64() as i64,
             start_from_block_index: rng.next_u64(),
+            account_start_block: rng.next_u64(),
         };
         round_trip_message::<mc_fog_types::view::QueryRequestAAD, mc_fog_api::view::QueryRequestAAD>(
             &test_val,
@@ -90,6 +91,7 @@ fn fog_view_query_request_aad_protobuf_round_trip() {
         let mut test_val = mc_fog_api::view::QueryRequestAAD::new();
         test_val.start_from_user_event_id = rng.next_u64() as i64;
         test_val.start_from_block_index = rng.next_u64();
+        test_val.account_start_block = rng.next_u64();
 
         round_trip_protobuf_object::<
             mc_fog_api::view::QueryRequestAAD,
@@ -101,6 +103,7 @@ fn fog_view_query_request_aad_protobuf_round_trip() {
         let mut test_val = mc_fog_api::view::QueryRequestAAD::new();
         test_val.start_from_user_event_id = rng.next_u64() as i64;
         test_val.start_from_block_index = rng.next_u64();
+        test_val.account_start_block = rng.next_u64();
         round_trip_protobuf_object::<
             mc_fog_api::view::QueryRequestAAD,
             mc_fog_types::view::QueryRequestAAD,
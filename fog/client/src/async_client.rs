@@ -0,0 +1,140 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! Async Client Object
+
+use crate::{
+    client::Client,
+    error::{Error, Result},
+    TransactionHistoryEntry, TransactionStatus,
+};
+use futures::channel::oneshot;
+use mc_account_keys::PublicAddress;
+use mc_crypto_rand::McRng;
+use mc_fog_types::BlockCount;
+use mc_transaction_core::{tx::Tx, Amount, SignedContingentInput, TokenId};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    thread,
+};
+
+/// An async handle to a fog client, which can be cloned and shared between
+/// tasks.
+///
+/// The calls of the underlying Client are blocking, so each of them is made on
+/// a thread of its own, and the async call completes when it returns. The
+/// Client is locked for the duration of a call, so concurrent calls are
+/// served one at a time, in no particular order.
+#[derive(Clone)]
+pub struct AsyncClient {
+    client: Arc<Mutex<Client>>,
+}
+
+impl AsyncClient {
+    /// Wrap a fog client
+    pub fn new(client: Client) -> Self {
+        Self {
+            client: Arc::new(Mutex::new(client)),
+        }
+    }
+
+    /// Poll fog, then get the balances of all tokens, and the number of blocks
+    /// in the chain at the time that they were correct.
+    pub async fn check_balance(&self) -> Result<(HashMap<TokenId, u64>, BlockCount)> {
+        self.call(|client| client.check_balance()).await
+    }
+
+    /// Poll fog, then get the balance of one token, and the number of blocks in
+    /// the chain at the time that it was correct.
+    pub async fn get_balance(&self, token_id: TokenId) -> Result<(u64, BlockCount)> {
+        let (balances, block_count) = self.check_balance().await?;
+        Ok((
+            balances.get(&token_id).cloned().unwrap_or_default(),
+            block_count,
+        ))
+    }
+
+    /// Poll fog, then get the transaction history of the account, with the
+    /// memo of each TxOut.
+    pub async fn get_transaction_history(&self) -> Result<Vec<TransactionHistoryEntry>> {
+        self.call(|client| {
+            client.check_balance()?;
+            Ok(client.get_transaction_history())
+        })
+        .await
+    }
+
+    /// Get the minimum fee for a token id, if it is configured
+    pub async fn get_minimum_fee(&self, token_id: TokenId) -> Result<Option<u64>> {
+        self.call(move |client| client.get_minimum_fee(token_id))
+            .await
+    }
+
+    /// Build a transaction sending `amount` to `recipient`, with RTH memos,
+    /// and submit it to consensus.
+    ///
+    /// Returns the transaction, which can be passed to is_transaction_present
+    /// to learn if it landed.
+    pub async fn send(&self, amount: Amount, recipient: PublicAddress, fee: u64) -> Result<Tx> {
+        self.call(move |client| {
+            let tx = client.build_transaction(amount, &recipient, &mut McRng::default(), fee)?;
+            client.send_transaction(&tx)?;
+            Ok(tx)
+        })
+        .await
+    }
+
+    /// Build a signed contingent input offering `offered` in exchange for
+    /// `requested`.
+    pub async fn build_swap_proposal(
+        &self,
+        offered: Amount,
+        requested: Amount,
+    ) -> Result<SignedContingentInput> {
+        self.call(move |client| {
+            client.build_swap_proposal(offered, requested, &mut McRng::default())
+        })
+        .await
+    }
+
+    /// Build a transaction fulfilling a signed contingent input, paying `fee`,
+    /// and submit it to consensus.
+    pub async fn fulfill_swap(&self, sci: SignedContingentInput, fee: Amount) -> Result<Tx> {
+        self.call(move |client| {
+            let tx = client.build_swap_transaction(sci, fee, &mut McRng::default())?;
+            client.send_transaction(&tx)?;
+            Ok(tx)
+        })
+        .await
+    }
+
+    /// Check if a submitted transaction appeared in the ledger or expired
+    pub async fn is_transaction_present(&self, tx: Tx) -> Result<TransactionStatus> {
+        self.call(move |client| client.is_transaction_present(&tx))
+            .await
+    }
+
+    /// Get the public b58 address of the account
+    pub async fn get_b58_address(&self) -> Result<String> {
+        self.call(|client| Ok(client.get_b58_address())).await
+    }
+
+    // Make a call of the client on a thread of its own
+    async fn call<T, F>(&self, func: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut Client) -> Result<T> + Send + 'static,
+    {
+        let client = self.client.clone();
+        let (sender, receiver) = oneshot::channel();
+        thread::spawn(move || {
+            let result = match client.lock() {
+                Ok(mut client) => func(&mut client),
+                Err(_) => Err(Error::ClientPoisoned),
+            };
+            // The receiver is gone if the caller stopped waiting
+            let _ = sender.send(result);
+        });
+        receiver.await.map_err(|_| Error::Interrupted)?
+    }
+}
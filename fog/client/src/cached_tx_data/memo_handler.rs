@@ -1,6 +1,6 @@
 // Copyright (c) 2018-2022 The MobileCoin Foundation
 
-//! A memo handler object which processes memos, for use in integration tests
+//! A memo handler object which processes memos
 
 use displaydoc::Display;
 use mc_account_keys::{AccountKey, PublicAddress, ShortAddressHash, CHANGE_SUBADDRESS_INDEX};
@@ -41,9 +41,15 @@ impl MemoHandler {
         &self.last_memo
     }
 
-    /// Handle a memo
-    pub fn handle_memo(&mut self, tx_out: &TxOut, account_key: &AccountKey) {
+    /// Handle a memo, returning the memo or memo handler error that it
+    /// produced
+    pub fn handle_memo(
+        &mut self,
+        tx_out: &TxOut,
+        account_key: &AccountKey,
+    ) -> Result<Option<MemoType>, MemoHandlerError> {
         self.last_memo = self.handle_memo_helper(tx_out, account_key);
+        self.last_memo.clone()
     }
 
     // Helper for handle_memo function. The result of this gets assigned to
@@ -67,7 +73,7 @@ impl MemoHandler {
                 // TODO: For now we are not validating anything with burn redemption memos.
                 // Right now the memo data is unstructured, so there's nothing
                 // to verify there. In theory we should only find this type of
-                // memo on a the burn account, which cannot be used with the sample paykit since
+                // memo on a the burn account, which cannot be used with the fog client since
                 // the spend key is unknown.
                 Ok(Some(memo_type))
             }
@@ -1,6 +1,9 @@
 // Copyright (c) 2018-2022 The MobileCoin Foundation
 
-use crate::error::{Error, Result, TxOutMatchingError};
+use crate::{
+    error::{Error, Result, TxOutMatchingError},
+    transaction_history::TransactionHistoryEntry,
+};
 use core::{
     cmp::{max, min},
     convert::TryFrom,
@@ -25,7 +28,6 @@ use mc_fog_types::{
     view::{FogTxOut, FogTxOutMetadata, TxOutRecord},
     BlockCount,
 };
-use mc_fog_view_connection::FogViewGrpcClient;
 use mc_fog_view_protocol::{FogViewConnection, UserPrivate, UserRngSet};
 use mc_transaction_core::{
     get_tx_out_shared_secret,
@@ -41,6 +43,9 @@ use std::collections::{BTreeMap, HashMap, HashSet};
 mod memo_handler;
 pub use memo_handler::{MemoHandler, MemoHandlerError};
 
+mod snapshot;
+pub use snapshot::CachedTxDataSnapshot;
+
 /// Maximum number of inputs in a transaction
 const MAX_INPUTS: usize = mc_transaction_core::constants::MAX_INPUTS as usize;
 
@@ -369,7 +374,11 @@ impl CachedTxData {
 
         for record in records {
             match OwnedTxOut::new(record, &self.account_key, &self.spsk_to_index) {
-                Ok(otxo) => {
+                Ok(mut otxo) => {
+                    // Handle memo
+                    otxo.memo = self
+                        .memo_handler
+                        .handle_memo(&otxo.tx_out, &self.account_key);
                     // Insert into owned_tx_outs
                     log::trace!(
                         self.logger,
@@ -399,9 +408,6 @@ impl CachedTxData {
                                 min(self.key_image_data_completeness, as_of);
                         }
                     }
-                    // Handle memo
-                    self.memo_handler
-                        .handle_memo(&otxo.tx_out, &self.account_key);
                 }
                 Err(err) => {
                     errors.push(err);
@@ -412,19 +418,17 @@ impl CachedTxData {
         errors
     }
 
-    /// Poll for new txo data, given fog view connection object
+    /// Do the fog view protocol, log any errors, and record any new missed
+    /// block ranges, given fog view connection object
     ///
-    /// This is called when doing a balance check. Returns the number of txos
-    /// discovered.
-    pub fn poll_fog_for_txos(
+    /// Returns the new txo records, which are not consumed yet.
+    pub fn poll_fog_view(
         &mut self,
-        fog_view_client: &mut FogViewGrpcClient,
-        fog_block_client: &mut FogBlockGrpcClient,
-    ) -> Result<usize> {
+        fog_view_client: &mut impl FogViewConnection,
+    ) -> Vec<TxOutRecord> {
         let old_rng_num_blocks = self.rng_set.get_highest_processed_block_count();
-        // Do the fog view protocol, log any errors, and consume any new transactions
 
-        let (mut txo_records, new_missed_block_ranges, errors) =
+        let (txo_records, new_missed_block_ranges, errors) =
             fog_view_client.poll(&mut self.rng_set, &UserPrivate::from(&self.account_key));
 
         log::trace!(
@@ -460,6 +464,21 @@ impl CachedTxData {
         }
 
         self.missed_block_ranges.extend(new_missed_block_ranges);
+        txo_records
+    }
+
+    /// Poll for new txo data, given fog view connection object
+    ///
+    /// This is called when doing a balance check. Returns the number of txos
+    /// discovered.
+    pub fn poll_fog_for_txos(
+        &mut self,
+        fog_view_client: &mut impl FogViewConnection,
+        fog_block_client: &mut FogBlockGrpcClient,
+    ) -> Result<usize> {
+        let old_rng_num_blocks = self.rng_set.get_highest_processed_block_count();
+        let mut txo_records = self.poll_fog_view(fog_view_client);
+
         let fog_common_block_ranges: Vec<fog_common::BlockRange> = self
             .missed_block_ranges
             .iter()
@@ -791,7 +810,7 @@ impl CachedTxData {
     /// Poll for txos and then key images, with some appropriate debug logging
     pub fn poll_fog(
         &mut self,
-        fog_view_client: &mut FogViewGrpcClient,
+        fog_view_client: &mut impl FogViewConnection,
         key_image_client: &mut FogKeyImageGrpcClient,
         fog_block_client: &mut FogBlockGrpcClient,
     ) -> Result<()> {
@@ -823,6 +842,16 @@ impl CachedTxData {
         Ok(())
     }
 
+    /// Get the transaction history of the account: every TxOut we received,
+    /// in the order in which they appeared in the blockchain, with its memo and
+    /// the block in which it was spent, if we know about it.
+    pub fn get_transaction_history(&self) -> Vec<TransactionHistoryEntry> {
+        self.owned_tx_outs
+            .values()
+            .map(TransactionHistoryEntry::from)
+            .collect()
+    }
+
     /// Get debug balance information (to help debug a wrong balance
     /// computation)
     pub fn debug_balance(&mut self) -> String {
@@ -885,6 +914,9 @@ pub struct OwnedTxOut {
     /// server. This is either `spent_at(index)` or
     /// `not_spent_as_of(count)`.
     pub status: KeyImageStatus,
+    /// The memo of the TxOut, or the error we got when trying to decrypt and
+    /// validate it.
+    pub memo: StdResult<Option<MemoType>, MemoHandlerError>,
 }
 
 impl OwnedTxOut {
@@ -901,6 +933,23 @@ impl OwnedTxOut {
         // Reconstute TxOut from FogTxOut and our view private key
         let tx_out = fog_tx_out.try_recover_tx_out(account_key.view_private_key())?;
 
+        Self::from_tx_out(
+            rec.tx_out_global_index,
+            rec.block_index,
+            tx_out,
+            account_key,
+            spsk_to_index,
+        )
+    }
+
+    /// Try to view-key match a TxOut, producing an OwnedTxOut or an error
+    pub fn from_tx_out(
+        global_index: u64,
+        block_index: BlockIndex,
+        tx_out: TxOut,
+        account_key: &AccountKey,
+        spsk_to_index: &HashMap<RistrettoPublic, u64>,
+    ) -> StdResult<Self, TxOutMatchingError> {
         // This is view key scanning part, getting the value fails if view-key scanning
         // fails
         let decompressed_tx_pub = RistrettoPublic::try_from(&tx_out.public_key)?;
@@ -931,16 +980,17 @@ impl OwnedTxOut {
 
         // The default status of a key image is that it isn't spent as of the block
         // in which it appeared
-        let status = KeyImageStatus::NotSpent(BlockCount::from(rec.block_index + 1));
+        let status = KeyImageStatus::NotSpent(BlockCount::from(block_index + 1));
 
         Ok(Self {
-            global_index: rec.tx_out_global_index,
-            block_index: rec.block_index,
+            global_index,
+            block_index,
             tx_out,
             key_image,
             amount,
             subaddress_index: *subaddress_index,
             status,
+            memo: Ok(None),
         })
     }
 }
@@ -1102,9 +1152,9 @@ fn input_selection_heuristic(
 }
 
 /// InputSelection error is an error that can go wrong when selecting inputs.
-/// This error implements Eq and PartialEq unlike fog_sample_paykit::Error,
+/// This error implements Eq and PartialEq unlike mc_fog_client::Error,
 /// which makes it work with assert_eq! for tests.
-/// fog_sample_paykit::Error cannot do this because grpcio and
+/// mc_fog_client::Error cannot do this because grpcio and
 /// mc_transaction_std Error's don't implement Eq.
 #[derive(Debug, Display, Eq, PartialEq)]
 enum InputSelectionError {
@@ -1126,6 +1176,295 @@ impl From<InputSelectionError> for Error {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use mc_account_keys::ShortAddressHash;
+    use mc_blockchain_types::{Block, BlockID, BlockVersion};
+    use mc_common::logger::{test_with_logger, Logger};
+    use mc_crypto_box::{CryptoBox, VersionedCryptoBox};
+    use mc_crypto_keys::{CompressedRistrettoPublic, RistrettoPrivate};
+    use mc_fog_in_memory_recovery_db::InMemoryRecoveryDb;
+    use mc_fog_kex_rng::{BufferedRng, NewFromKex, VersionedKexRng};
+    use mc_fog_recovery_db_iface::RecoveryDb;
+    use mc_fog_test_infra::{db_tests::random_tx_row, mock_client::PassThroughViewClient};
+    use mc_fog_types::ETxOutRecord;
+    use mc_transaction_core::encrypted_fog_hint::EncryptedFogHint;
+    use mc_transaction_std::{AuthenticatedSenderMemo, SenderMemoCredential};
+    use mc_util_from_random::FromRandom;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    // Do what fog ingest does for a TxOut of the user: encrypt the TxOutRecord
+    // for the user's view key, under the next search key of the user's rng.
+    fn make_e_tx_out_record(
+        tx_out: &TxOut,
+        global_index: u64,
+        block_index: u64,
+        account_key: &AccountKey,
+        user_rng: &mut VersionedKexRng,
+        rng: &mut StdRng,
+    ) -> ETxOutRecord {
+        let txo_record = TxOutRecord::new(
+            FogTxOut::from(tx_out),
+            FogTxOutMetadata {
+                global_index,
+                block_index,
+                timestamp: 0,
+            },
+        );
+        let payload = VersionedCryptoBox::default()
+            .encrypt(
+                rng,
+                account_key.default_subaddress().view_public_key(),
+                &mc_util_serial::encode(&txo_record),
+            )
+            .unwrap();
+        let search_key = user_rng.peek().to_vec();
+        user_rng.advance();
+        ETxOutRecord {
+            search_key,
+            payload,
+        }
+    }
+
+    // Polling a view server backed by the recovery db finds the TxOuts of the
+    // account, and keeps their memos in the transaction history.
+    #[test_with_logger]
+    fn poll_fog_view_finds_tx_outs_and_memos(logger: Logger) {
+        let mut rng: StdRng = SeedableRng::from_seed([7u8; 32]);
+        let account_key = AccountKey::random(&mut rng);
+        let sender = AccountKey::random(&mut rng);
+        let stranger = AccountKey::random(&mut rng);
+        let recipient = account_key.default_subaddress();
+
+        // Only the sender is in the address book.
+        let address_book = vec![sender.default_subaddress()];
+        let mut tx_data =
+            CachedTxData::new(account_key.clone(), address_book.clone(), logger.clone());
+
+        let db = InMemoryRecoveryDb::new();
+        let mut view_client = PassThroughViewClient::new(db.clone());
+
+        let ingress_key = CompressedRistrettoPublic::from(RistrettoPublic::from_random(&mut rng));
+        db.new_ingress_key(&ingress_key, 0).unwrap();
+        let egress_key = RistrettoPrivate::from_random(&mut rng);
+        let (kex_rng_pubkey, mut user_rng) =
+            VersionedKexRng::new_from_static_static(&egress_key, recipient.view_public_key());
+        let invoc_id = db
+            .new_ingest_invocation(None, &ingress_key, &kex_rng_pubkey, 0)
+            .unwrap();
+
+        let sender_memo_tx_out = |amount: Amount, sender: &AccountKey, rng: &mut StdRng| {
+            TxOut::new_with_memo(
+                BlockVersion::MAX,
+                amount,
+                &recipient,
+                &RistrettoPrivate::from_random(rng),
+                EncryptedFogHint::fake_onetime_hint(rng),
+                |memo_ctxt| {
+                    Ok(AuthenticatedSenderMemo::new(
+                        &SenderMemoCredential::from(sender),
+                        recipient.view_public_key(),
+                        &CompressedRistrettoPublic::from(memo_ctxt.tx_public_key),
+                    )
+                    .into())
+                },
+            )
+            .unwrap()
+        };
+        let from_sender = sender_memo_tx_out(Amount::new(10, TokenId::from(0)), &sender, &mut rng);
+        let from_stranger =
+            sender_memo_tx_out(Amount::new(20, TokenId::from(1)), &stranger, &mut rng);
+
+        // Block 0 has a TxOut of another user, and our two TxOuts.
+        let block = Block::new(
+            BlockVersion::MAX,
+            &BlockID::default(),
+            0,
+            3,
+            &Default::default(),
+            &Default::default(),
+        );
+        let records = vec![
+            random_tx_row(&mut rng),
+            make_e_tx_out_record(&from_sender, 1, 0, &account_key, &mut user_rng, &mut rng),
+            make_e_tx_out_record(&from_stranger, 2, 0, &account_key, &mut user_rng, &mut rng),
+        ];
+        db.add_block_data(&invoc_id, &block, 0, &records).unwrap();
+
+        let txo_records = tx_data.poll_fog_view(&mut view_client);
+        assert_eq!(txo_records.len(), 2);
+        assert!(tx_data
+            .consume_new_txo_records(txo_records.into_iter())
+            .is_empty());
+
+        let (balances, block_count) = tx_data.get_balance();
+        assert_eq!(block_count, BlockCount::from(1));
+        assert_eq!(balances[&TokenId::from(0)], 10);
+        assert_eq!(balances[&TokenId::from(1)], 20);
+
+        let history = tx_data.get_transaction_history();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].global_index, 1);
+        assert_eq!(history[0].amount, Amount::new(10, TokenId::from(0)));
+        assert_eq!(history[0].spent_at, None);
+        match &history[0].memo {
+            Ok(Some(MemoType::AuthenticatedSender(memo))) => assert_eq!(
+                memo.sender_address_hash(),
+                ShortAddressHash::from(&sender.default_subaddress())
+            ),
+            other => panic!("Unexpected memo: {:?}", other),
+        }
+        assert_eq!(history[1].global_index, 2);
+        assert_eq!(history[1].amount, Amount::new(20, TokenId::from(1)));
+        assert!(matches!(
+            history[1].memo,
+            Err(MemoHandlerError::UnknownSender)
+        ));
+
+        // Polling again doesn't find the same TxOuts twice.
+        assert!(tx_data.poll_fog_view(&mut view_client).is_empty());
+
+        // A client restored from a snapshot has the same TxOuts and memos, and
+        // polls fog view from where the snapshot was taken.
+        let snapshot: CachedTxDataSnapshot =
+            mc_util_serial::decode(&mc_util_serial::encode(&tx_data.snapshot())).unwrap();
+        let mut restored = CachedTxData::new(account_key.clone(), address_book, logger);
+        restored.restore(snapshot).unwrap();
+        assert_eq!(restored.get_balance(), tx_data.get_balance());
+        let restored_history = restored.get_transaction_history();
+        assert_eq!(restored_history.len(), 2);
+        assert!(matches!(
+            restored_history[0].memo,
+            Ok(Some(MemoType::AuthenticatedSender(_)))
+        ));
+        assert!(matches!(
+            restored_history[1].memo,
+            Err(MemoHandlerError::UnknownSender)
+        ));
+
+        let from_sender = sender_memo_tx_out(Amount::new(5, TokenId::from(0)), &sender, &mut rng);
+        let block = Block::new(
+            BlockVersion::MAX,
+            &BlockID::default(),
+            1,
+            4,
+            &Default::default(),
+            &Default::default(),
+        );
+        let records = vec![make_e_tx_out_record(
+            &from_sender,
+            3,
+            1,
+            &account_key,
+            &mut user_rng,
+            &mut rng,
+        )];
+        db.add_block_data(&invoc_id, &block, 0, &records).unwrap();
+
+        let txo_records = restored.poll_fog_view(&mut view_client);
+        assert_eq!(txo_records.len(), 1);
+        assert_eq!(txo_records[0].tx_out_global_index, 3);
+    }
+
+    // An encrypted snapshot can only be restored by the same account, and a
+    // corrupted or inconsistent snapshot is rejected without changing the state.
+    #[test_with_logger]
+    fn restore_encrypted_snapshot(logger: Logger) {
+        let mut rng: StdRng = SeedableRng::from_seed([9u8; 32]);
+        let account_key = AccountKey::random(&mut rng);
+        let recipient = account_key.default_subaddress();
+
+        let mut tx_data = CachedTxData::new(account_key.clone(), vec![], logger.clone());
+        let tx_out = TxOut::new(
+            BlockVersion::MAX,
+            Amount::new(10, TokenId::from(0)),
+            &recipient,
+            &RistrettoPrivate::from_random(&mut rng),
+            EncryptedFogHint::fake_onetime_hint(&mut rng),
+        )
+        .unwrap();
+        let txo_record = TxOutRecord::new(
+            FogTxOut::from(&tx_out),
+            FogTxOutMetadata {
+                global_index: 4,
+                block_index: 2,
+                timestamp: 0,
+            },
+        );
+        assert!(tx_data
+            .consume_new_txo_records(vec![txo_record].into_iter())
+            .is_empty());
+        tx_data.latest_global_txo_count = 5;
+
+        let encrypted = tx_data.encrypted_snapshot(&mut rng);
+
+        let mut restored = CachedTxData::new(account_key.clone(), vec![], logger.clone());
+        restored.restore_encrypted(&encrypted).unwrap();
+        assert_eq!(restored.get_balance(), tx_data.get_balance());
+        assert_eq!(
+            mc_util_serial::encode(&restored.snapshot()),
+            mc_util_serial::encode(&tx_data.snapshot())
+        );
+
+        // A flipped byte fails the MAC check
+        let mut corrupted = encrypted.clone();
+        let last = corrupted.len() - 1;
+        corrupted[last] ^= 1;
+        let mut restored = CachedTxData::new(account_key.clone(), vec![], logger.clone());
+        assert!(matches!(
+            restored.restore_encrypted(&corrupted),
+            Err(Error::CacheMacCheckFailed)
+        ));
+        assert!(restored.owned_tx_outs.is_empty());
+
+        // Another account can't decrypt the snapshot
+        let mut other = CachedTxData::new(AccountKey::random(&mut rng), vec![], logger.clone());
+        assert!(matches!(
+            other.restore_encrypted(&encrypted),
+            Err(Error::CacheMacCheckFailed)
+        ));
+
+        // A snapshot of another account doesn't match our TxOuts
+        let snapshot = tx_data.snapshot();
+        let mut other = CachedTxData::new(AccountKey::random(&mut rng), vec![], logger.clone());
+        assert!(matches!(
+            other.restore(snapshot.clone()),
+            Err(Error::CacheInconsistent(_))
+        ));
+        assert!(other.owned_tx_outs.is_empty());
+
+        // A TxOut can't be spent in the block in which it appeared
+        let mut bad_snapshot = snapshot.clone();
+        bad_snapshot.owned_tx_outs[0].spent_at = Some(2);
+        let mut restored = CachedTxData::new(account_key.clone(), vec![], logger.clone());
+        assert!(matches!(
+            restored.restore(bad_snapshot),
+            Err(Error::CacheInconsistent(_))
+        ));
+
+        // The amount must match the TxOut
+        let mut bad_snapshot = snapshot.clone();
+        bad_snapshot.owned_tx_outs[0].value = 11;
+        assert!(matches!(
+            restored.restore(bad_snapshot),
+            Err(Error::CacheInconsistent(_))
+        ));
+
+        // A TxOut can't appear twice
+        let mut bad_snapshot = snapshot;
+        bad_snapshot
+            .owned_tx_outs
+            .push(bad_snapshot.owned_tx_outs[0].clone());
+        assert!(matches!(
+            restored.restore(bad_snapshot),
+            Err(Error::CacheInconsistent(_))
+        ));
+        assert!(restored.owned_tx_outs.is_empty());
+        assert_eq!(restored.latest_global_txo_count, 0);
+
+        // Resetting forgets everything learned from fog
+        tx_data.reset();
+        assert!(tx_data.owned_tx_outs.is_empty());
+        assert_eq!(tx_data.latest_global_txo_count, 0);
+    }
 
     #[test]
     fn input_selection_heuristic_3_inputs() {
@@ -0,0 +1,290 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! Snapshots of CachedTxData, which let a client resume polling fog where it
+//! stopped, instead of from the first block, when it restarts.
+
+use super::{CachedTxData, KeyImageStatus, OwnedTxOut};
+use crate::error::{Error, Result};
+use core::convert::TryFrom;
+use mc_crypto_box::{CryptoBox, VersionedCryptoBox};
+use mc_crypto_keys::RistrettoPublic;
+use mc_crypto_rand::{CryptoRng, RngCore};
+use mc_fog_kex_rng::{StoredRng, VersionedKexRng};
+use mc_fog_types::{common::BlockRange, BlockCount};
+use mc_fog_view_protocol::UserRngSet;
+use mc_transaction_core::{ring_signature::KeyImage, tx::TxOut, Amount};
+use mc_util_serial::Message;
+use std::collections::BTreeMap;
+
+/// The persisted state of a CachedTxData.
+///
+/// The account key and the address book are not part of it, they are those of
+/// the CachedTxData which restores it.
+#[derive(Clone, Message)]
+pub struct CachedTxDataSnapshot {
+    /// The rngs of the UserRngSet
+    #[prost(message, repeated, tag = "1")]
+    pub rngs: Vec<RngSnapshot>,
+
+    /// The highest processed block count of the UserRngSet
+    #[prost(uint64, tag = "2")]
+    pub highest_processed_block_count: u64,
+
+    /// The next user event id to request from fog view
+    #[prost(int64, tag = "3")]
+    pub next_start_from_user_event_id: i64,
+
+    /// The account start block of the UserRngSet
+    #[prost(uint64, tag = "4")]
+    pub account_start_block: u64,
+
+    /// The TxOuts we own
+    #[prost(message, repeated, tag = "5")]
+    pub owned_tx_outs: Vec<OwnedTxOutSnapshot>,
+
+    /// How fresh our information about unspent key images is
+    #[prost(uint64, tag = "6")]
+    pub key_image_data_completeness: u64,
+
+    /// The latest global txo count that we heard about
+    #[prost(uint64, tag = "7")]
+    pub latest_global_txo_count: u64,
+
+    /// The latest block version that we heard about
+    #[prost(uint32, tag = "8")]
+    pub latest_block_version: u32,
+
+    /// The missed block ranges that we did not download yet
+    #[prost(message, repeated, tag = "9")]
+    pub missed_block_ranges: Vec<BlockRange>,
+}
+
+/// The persisted state of one rng of a UserRngSet
+#[derive(Clone, Message)]
+pub struct RngSnapshot {
+    /// The nonce of the rng, which is the public key of its KexRngPubkey
+    #[prost(bytes, tag = "1")]
+    pub nonce: Vec<u8>,
+
+    /// The state of the rng
+    #[prost(message, required, tag = "2")]
+    pub rng: StoredRng,
+}
+
+/// The persisted state of an OwnedTxOut.
+///
+/// The memo is not part of it, it is decrypted again when restoring.
+/// The amount, subaddress index and key image are checked against the TxOut
+/// when restoring.
+#[derive(Clone, Message)]
+pub struct OwnedTxOutSnapshot {
+    /// The global index of the TxOut
+    #[prost(uint64, tag = "1")]
+    pub global_index: u64,
+
+    /// The block in which the TxOut appeared
+    #[prost(uint64, tag = "2")]
+    pub block_index: u64,
+
+    /// The TxOut
+    #[prost(message, required, tag = "3")]
+    pub tx_out: TxOut,
+
+    /// The value of the TxOut
+    #[prost(uint64, tag = "4")]
+    pub value: u64,
+
+    /// The token id of the TxOut
+    #[prost(uint64, tag = "5")]
+    pub token_id: u64,
+
+    /// The subaddress the TxOut was sent to
+    #[prost(uint64, tag = "6")]
+    pub subaddress_index: u64,
+
+    /// The key image of the TxOut
+    #[prost(message, required, tag = "7")]
+    pub key_image: KeyImage,
+
+    /// The block in which the TxOut was spent, if it is known to be spent
+    #[prost(uint64, optional, tag = "8")]
+    pub spent_at: Option<u64>,
+
+    /// The block count as of which the TxOut is known not to be spent, if it
+    /// is not known to be spent
+    #[prost(uint64, tag = "9")]
+    pub not_spent_as_of: u64,
+}
+
+impl From<&OwnedTxOut> for OwnedTxOutSnapshot {
+    fn from(src: &OwnedTxOut) -> Self {
+        let (spent_at, not_spent_as_of) = match src.status {
+            KeyImageStatus::SpentAt(block_index) => (Some(block_index), 0),
+            KeyImageStatus::NotSpent(block_count) => (None, u64::from(block_count)),
+        };
+        Self {
+            global_index: src.global_index,
+            block_index: src.block_index,
+            tx_out: src.tx_out.clone(),
+            value: src.amount.value,
+            token_id: *src.amount.token_id,
+            subaddress_index: src.subaddress_index,
+            key_image: src.key_image,
+            spent_at,
+            not_spent_as_of,
+        }
+    }
+}
+
+impl CachedTxData {
+    /// Take a snapshot of the state of this object
+    pub fn snapshot(&self) -> CachedTxDataSnapshot {
+        CachedTxDataSnapshot {
+            rngs: self
+                .rng_set
+                .get_rngs()
+                .iter()
+                .map(|(nonce, rng)| RngSnapshot {
+                    nonce: nonce.clone(),
+                    rng: StoredRng::from(rng.clone()),
+                })
+                .collect(),
+            highest_processed_block_count: u64::from(
+                self.rng_set.get_highest_processed_block_count(),
+            ),
+            next_start_from_user_event_id: self.rng_set.get_next_start_from_user_event_id(),
+            account_start_block: self.rng_set.get_account_start_block(),
+            owned_tx_outs: self
+                .owned_tx_outs
+                .values()
+                .map(OwnedTxOutSnapshot::from)
+                .collect(),
+            key_image_data_completeness: u64::from(self.key_image_data_completeness),
+            latest_global_txo_count: self.latest_global_txo_count,
+            latest_block_version: self.latest_block_version,
+            missed_block_ranges: self.missed_block_ranges.clone(),
+        }
+    }
+
+    /// Take a snapshot of the state of this object, and encrypt it for the
+    /// view key of our account.
+    ///
+    /// Everything in the snapshot can be recovered from fog with the view key,
+    /// so encrypting it for the view key doesn't give its holder anything new.
+    pub fn encrypted_snapshot<T: RngCore + CryptoRng>(&self, rng: &mut T) -> Vec<u8> {
+        let view_public_key = RistrettoPublic::from(self.account_key.view_private_key());
+        VersionedCryptoBox::default()
+            .encrypt(
+                rng,
+                &view_public_key,
+                &mc_util_serial::encode(&self.snapshot()),
+            )
+            .expect("CryptoBox encryption should not fail")
+    }
+
+    /// Decrypt an encrypted snapshot, and restore the state of this object
+    /// from it. The authentication of the ciphertext detects a corrupted
+    /// snapshot.
+    pub fn restore_encrypted(&mut self, encrypted_snapshot: &[u8]) -> Result<()> {
+        let (success, plaintext) = VersionedCryptoBox::default()
+            .decrypt(self.account_key.view_private_key(), encrypted_snapshot)?;
+        if !bool::from(success) {
+            return Err(Error::CacheMacCheckFailed);
+        }
+        self.restore(mc_util_serial::decode(&plaintext)?)
+    }
+
+    /// Restore the state of this object from a snapshot, replacing its
+    /// current state. On error, the state is left unchanged.
+    ///
+    /// The TxOuts of the snapshot are matched against our account key again,
+    /// and the invariants of the key image statuses are checked, so that a
+    /// corrupted snapshot, or the snapshot of another account, is rejected.
+    pub fn restore(&mut self, snapshot: CachedTxDataSnapshot) -> Result<()> {
+        let mut rng_set = UserRngSet::new_from_account_start_block(snapshot.account_start_block);
+        for rng_snapshot in snapshot.rngs {
+            rng_set.insert_rng(
+                rng_snapshot.nonce,
+                VersionedKexRng::try_from(rng_snapshot.rng)?,
+            );
+        }
+        rng_set.set_highest_processed_block_count(snapshot.highest_processed_block_count);
+        rng_set.set_next_start_from_user_event_id(snapshot.next_start_from_user_event_id);
+
+        let key_image_data_completeness = BlockCount::from(snapshot.key_image_data_completeness);
+        let mut owned_tx_outs = BTreeMap::new();
+        for otxo_snapshot in snapshot.owned_tx_outs {
+            let global_index = otxo_snapshot.global_index;
+            let block_index = otxo_snapshot.block_index;
+            let mut otxo = OwnedTxOut::from_tx_out(
+                global_index,
+                block_index,
+                otxo_snapshot.tx_out,
+                &self.account_key,
+                &self.spsk_to_index,
+            )
+            .map_err(|err| {
+                Error::CacheInconsistent(format!("TxOut {} is not ours: {}", global_index, err))
+            })?;
+            if otxo.amount != Amount::new(otxo_snapshot.value, otxo_snapshot.token_id.into())
+                || otxo.subaddress_index != otxo_snapshot.subaddress_index
+                || otxo.key_image != otxo_snapshot.key_image
+            {
+                return Err(Error::CacheInconsistent(format!(
+                    "TxOut {} has the wrong amount, subaddress or key image",
+                    global_index
+                )));
+            }
+
+            // A TxOut can't be spent in the block in which it appeared
+            otxo.status = match otxo_snapshot.spent_at {
+                Some(spent_at) if spent_at > block_index => KeyImageStatus::SpentAt(spent_at),
+                None if otxo_snapshot.not_spent_as_of > block_index
+                    && BlockCount::from(otxo_snapshot.not_spent_as_of)
+                        >= key_image_data_completeness =>
+                {
+                    KeyImageStatus::NotSpent(BlockCount::from(otxo_snapshot.not_spent_as_of))
+                }
+                _ => {
+                    return Err(Error::CacheInconsistent(format!(
+                        "TxOut {} has an invalid key image status",
+                        global_index
+                    )))
+                }
+            };
+
+            if owned_tx_outs.insert(global_index, otxo).is_some() {
+                return Err(Error::CacheInconsistent(format!(
+                    "TxOut {} appears twice",
+                    global_index
+                )));
+            }
+        }
+
+        for otxo in owned_tx_outs.values_mut() {
+            otxo.memo = self
+                .memo_handler
+                .handle_memo(&otxo.tx_out, &self.account_key);
+        }
+
+        self.rng_set = rng_set;
+        self.owned_tx_outs = owned_tx_outs;
+        self.key_image_data_completeness = key_image_data_completeness;
+        self.latest_global_txo_count = snapshot.latest_global_txo_count;
+        self.latest_block_version = snapshot.latest_block_version;
+        self.missed_block_ranges = snapshot.missed_block_ranges;
+        Ok(())
+    }
+
+    /// Forget everything that we learned from fog, so that the next poll
+    /// starts over from the first block
+    pub fn reset(&mut self) {
+        self.rng_set =
+            UserRngSet::new_from_account_start_block(self.rng_set.get_account_start_block());
+        self.owned_tx_outs.clear();
+        self.key_image_data_completeness = BlockCount::MAX;
+        self.latest_global_txo_count = 0;
+        self.latest_block_version = 0;
+        self.missed_block_ranges.clear();
+    }
+}
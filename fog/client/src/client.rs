@@ -5,7 +5,7 @@
 use crate::{
     cached_tx_data::{CachedTxData, OwnedTxOut},
     error::{Error, Result},
-    BlockInfo, MemoHandlerError, TransactionStatus,
+    BlockInfo, MemoHandlerError, TransactionHistoryEntry, TransactionStatus,
 };
 use core::{convert::TryFrom, result::Result as StdResult, str::FromStr};
 use mc_account_keys::{AccountKey, PublicAddress};
@@ -16,7 +16,7 @@ use mc_connection::{
     BlockchainConnection, Connection, HardcodedCredentialsProvider, ThickClient, UserTxConnection,
 };
 use mc_crypto_keys::CompressedRistrettoPublic;
-use mc_crypto_rand::{CryptoRng, RngCore};
+use mc_crypto_rand::{CryptoRng, McRng, RngCore};
 use mc_crypto_ring_signature_signer::{LocalRingSigner, OneTimeKeyDeriveData, RingSigner};
 use mc_fog_api::ledger::TxOutResultCode;
 use mc_fog_ledger_connection::{
@@ -38,7 +38,11 @@ use mc_transaction_std::{
 use mc_util_telemetry::{block_span_builder, telemetry_static_key, tracer, Key, Span};
 use mc_util_uri::{ConnectionUri, FogUri};
 use rand::Rng;
-use std::collections::{HashMap, HashSet};
+use std::{
+    collections::{HashMap, HashSet},
+    fs, io,
+    path::PathBuf,
+};
 
 /// Default number of blocks used for calculating transaction tombstone block
 /// number. See `new_tx_block_attempts` below.
@@ -47,7 +51,7 @@ const DEFAULT_NEW_TX_BLOCK_ATTEMPTS: u16 = 50;
 /// Telemetry: block index the transaction is expected to land at.
 const TELEMETRY_BLOCK_INDEX_KEY: Key = telemetry_static_key!("block-index");
 
-/// Represents the entire sample paykit object, capable of balance checks and
+/// Represents the entire fog client object, capable of balance checks and
 /// sending transactions
 pub struct Client {
     consensus_service_conn: ThickClient<HardcodedCredentialsProvider>,
@@ -62,6 +66,9 @@ pub struct Client {
     account_key: AccountKey,
     tx_data: CachedTxData,
 
+    /// A file in which tx_data is persisted, if any.
+    cache_path: Option<PathBuf>,
+
     /// Number of blocks for which to try and get the new transaction to be
     /// included in the ledger. This value is used to calculate the
     /// tombstone block when generating a new transaction.
@@ -71,7 +78,7 @@ pub struct Client {
 }
 
 impl Client {
-    /// Create a new sample paykit object
+    /// Create a new fog client object
     pub fn new(
         consensus_service_conn: ThickClient<HardcodedCredentialsProvider>,
         fog_view: FogViewGrpcClient,
@@ -100,12 +107,13 @@ impl Client {
             ring_size,
             account_key,
             tx_data,
+            cache_path: None,
             new_tx_block_attempts: DEFAULT_NEW_TX_BLOCK_ATTEMPTS,
             logger,
         }
     }
 
-    /// Get the account key associated to this paykit
+    /// Get the account key associated to this client
     pub fn get_account_key(&self) -> &AccountKey {
         &self.account_key
     }
@@ -128,6 +136,64 @@ impl Client {
         self.new_tx_block_attempts = new_tx_block_attempts;
     }
 
+    /// Persist the cached transaction data of this client in a file, so that
+    /// a restarted client doesn't poll fog from the first block again. The
+    /// file is encrypted for the view key of the account.
+    ///
+    /// If the file exists, the cached transaction data is restored from it,
+    /// otherwise it is written at the next balance check. It is saved again
+    /// after each balance check.
+    ///
+    /// If the file can't be decrypted, or its contents are inconsistent, it is
+    /// moved aside with a `.corrupt` extension, and the client resyncs from
+    /// fog as if there was no file.
+    pub fn set_cache_path(&mut self, cache_path: impl Into<PathBuf>) -> Result<()> {
+        let cache_path = cache_path.into();
+        match fs::read(&cache_path) {
+            Ok(bytes) => match self.tx_data.restore_encrypted(&bytes) {
+                Ok(()) => {
+                    log::info!(
+                        self.logger,
+                        "Restored cached tx data from {:?}, num_blocks = {}",
+                        cache_path,
+                        self.tx_data.get_num_blocks()
+                    );
+                }
+                Err(err) => {
+                    let corrupt_path = cache_path.with_extension("corrupt");
+                    log::warn!(
+                        self.logger,
+                        "Could not restore cached tx data from {:?}, moving it to {:?} and resyncing from fog: {}",
+                        cache_path,
+                        corrupt_path,
+                        err
+                    );
+                    fs::rename(&cache_path, &corrupt_path)?;
+                    self.tx_data.reset();
+                }
+            },
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+            Err(err) => return Err(err.into()),
+        }
+        self.cache_path = Some(cache_path);
+        Ok(())
+    }
+
+    /// Save the cached transaction data to the cache file, if there is one.
+    pub fn save_cache(&self) -> Result<()> {
+        if let Some(cache_path) = self.cache_path.as_ref() {
+            // Write a new file and then rename it, so that a crash doesn't leave a
+            // partially written cache file behind
+            let tmp_path = cache_path.with_extension("tmp");
+            fs::write(
+                &tmp_path,
+                self.tx_data.encrypted_snapshot(&mut McRng::default()),
+            )?;
+            fs::rename(&tmp_path, cache_path)?;
+        }
+        Ok(())
+    }
+
     /// Check this user's current available balance.
     ///
     /// Returns:
@@ -141,6 +207,7 @@ impl Client {
             &mut self.fog_key_image,
             &mut self.fog_block,
         )?;
+        self.save_cache()?;
         Ok(self.compute_balance())
     }
 
@@ -161,6 +228,13 @@ impl Client {
         self.tx_data.debug_balance()
     }
 
+    /// Get the transaction history of this account, based on locally
+    /// available data. Does NOT make any new network calls, so this should
+    /// usually follow a call to check_balance.
+    pub fn get_transaction_history(&self) -> Vec<TransactionHistoryEntry> {
+        self.tx_data.get_transaction_history()
+    }
+
     /// Get the last memo (or validation error) that we recieved from a TxOut
     pub fn get_last_memo(&self) -> &StdResult<Option<MemoType>, MemoHandlerError> {
         self.tx_data.get_last_memo()
@@ -21,7 +21,7 @@ use mc_util_grpc::GrpcRetryConfig;
 use mc_util_uri::{ConnectionUri, ConsensusClientUri};
 use std::sync::Arc;
 
-/// Builder object which helps to initialize the sample paykit
+/// Builder object which helps to initialize the fog client
 pub struct ClientBuilder {
     // Required
     uri: ConsensusClientUri,
@@ -5,8 +5,10 @@
 use displaydoc::Display;
 use mc_connection::Error as ConnectionError;
 use mc_consensus_api::ConversionError;
+use mc_crypto_box::Error as CryptoBoxError;
 use mc_crypto_keys::KeyError;
 use mc_fog_enclave_connection::Error as EnclaveConnectionError;
+use mc_fog_kex_rng::Error as KexRngError;
 use mc_fog_ledger_connection::{Error as LedgerConnectionError, KeyImageQueryError};
 use mc_fog_report_connection::Error as FogResolutionError;
 use mc_fog_types::view::FogTxOutError;
@@ -16,10 +18,11 @@ use mc_transaction_core::{
     SignedContingentInputError,
 };
 use mc_transaction_std::{SignedContingentInputBuilderError, TxBuilderError};
+use mc_util_serial::DecodeError;
 use mc_util_uri::UriParseError;
-use std::result::Result as StdResult;
+use std::{io::Error as IoError, result::Result as StdResult};
 
-/// A result type alias for the sample paykit
+/// A result type alias for the fog client
 pub type Result<T> = StdResult<T, Error>;
 
 type FogViewError = TxOutPollingError<EnclaveConnectionError>;
@@ -58,7 +61,7 @@ impl From<FogTxOutError> for TxOutMatchingError {
     }
 }
 
-/// An error that can be returned by the sample paykit
+/// An error that can be returned by the fog client
 #[derive(Debug, Display)]
 pub enum Error {
     /// Error in connection to consensus: {0}
@@ -135,6 +138,30 @@ pub enum Error {
 
     /// Fog merkle proof: {0}
     FogMerkleProof(String),
+
+    /// Could not restore the rngs of the cached tx data: {0}
+    KexRng(KexRngError),
+
+    /// Could not decode the cache file: {0}
+    CacheDecode(DecodeError),
+
+    /// Could not read or write the cache file: {0}
+    CacheIo(IoError),
+
+    /// Could not decrypt the cache file: {0}
+    CacheDecrypt(CryptoBoxError),
+
+    /// Could not decrypt the cache file: Mac check failed
+    CacheMacCheckFailed,
+
+    /// The cache is inconsistent: {0}
+    CacheInconsistent(String),
+
+    /// The client is unusable because an earlier call panicked
+    ClientPoisoned,
+
+    /// The client call was interrupted before it completed
+    Interrupted,
 }
 
 impl From<ConnectionError> for Error {
@@ -217,3 +244,27 @@ impl From<BlockVersionError> for Error {
         Self::BlockVersion(src)
     }
 }
+
+impl From<KexRngError> for Error {
+    fn from(x: KexRngError) -> Error {
+        Error::KexRng(x)
+    }
+}
+
+impl From<DecodeError> for Error {
+    fn from(x: DecodeError) -> Error {
+        Error::CacheDecode(x)
+    }
+}
+
+impl From<IoError> for Error {
+    fn from(x: IoError) -> Error {
+        Error::CacheIo(x)
+    }
+}
+
+impl From<CryptoBoxError> for Error {
+    fn from(x: CryptoBoxError) -> Error {
+        Error::CacheDecrypt(x)
+    }
+}
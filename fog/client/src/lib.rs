@@ -0,0 +1,51 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! MobileCoin Fog client library
+//!
+//! The Client connects to consensus and to the fog view and fog ledger
+//! services on behalf of one account. It checks the balance of the account
+//! per token, keeps its transaction history with the memos it received,
+//! builds and submits transactions with RTH memos, and builds and fulfills
+//! swaps using signed contingent inputs.
+//!
+//! The calls of Client are blocking. AsyncClient wraps a Client for use from
+//! async code.
+#![deny(missing_docs)]
+
+mod async_client;
+mod cached_tx_data;
+mod client;
+mod client_builder;
+mod error;
+mod transaction_history;
+
+pub use crate::{
+    async_client::AsyncClient,
+    client::Client,
+    client_builder::ClientBuilder,
+    error::{Error, Result, TxOutMatchingError},
+    transaction_history::TransactionHistoryEntry,
+};
+pub use cached_tx_data::MemoHandlerError;
+pub use mc_account_keys::{AccountKey, PublicAddress};
+pub use mc_blockchain_types::BlockIndex;
+pub use mc_connection::BlockInfo;
+pub use mc_crypto_keys::{RistrettoPrivate, RistrettoPublic};
+pub use mc_fog_types::BlockCount;
+pub use mc_transaction_core::{
+    onetime_keys::recover_onetime_private_key,
+    ring_signature::KeyImage,
+    tx::{Tx, TxOutMembershipProof},
+    Amount, SignedContingentInput, TokenId,
+};
+pub use mc_transaction_std::MemoType;
+
+/// A status that a submitted transaction can have
+pub enum TransactionStatus {
+    /// The transaction has appeared at a particular block index
+    Appeared(BlockIndex),
+    /// The transaction has expired (tombstone block passed)
+    Expired,
+    /// It isn't known if the transaction appeared or expired yet
+    Unknown,
+}
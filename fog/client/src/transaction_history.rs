@@ -0,0 +1,47 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! Transaction history of an account
+
+use crate::cached_tx_data::{KeyImageStatus, MemoHandlerError, OwnedTxOut};
+use mc_blockchain_types::BlockIndex;
+use mc_transaction_core::{ring_signature::KeyImage, Amount};
+use mc_transaction_std::MemoType;
+
+/// A TxOut which the account received, as it appears in the transaction
+/// history.
+#[derive(Clone, Debug)]
+pub struct TransactionHistoryEntry {
+    /// The global index of the TxOut
+    pub global_index: u64,
+    /// The block in which the TxOut appeared
+    pub block_index: BlockIndex,
+    /// The amount and token id of the TxOut
+    pub amount: Amount,
+    /// The subaddress the TxOut was sent to
+    pub subaddress_index: u64,
+    /// The key image of the TxOut
+    pub key_image: KeyImage,
+    /// The block in which the TxOut was spent, if we know that it was spent
+    pub spent_at: Option<BlockIndex>,
+    /// The memo of the TxOut, or the error we got when trying to decrypt and
+    /// validate it. Validating the sender of an authenticated sender memo
+    /// requires the sender to be in the address book of the client.
+    pub memo: Result<Option<MemoType>, MemoHandlerError>,
+}
+
+impl From<&OwnedTxOut> for TransactionHistoryEntry {
+    fn from(src: &OwnedTxOut) -> Self {
+        Self {
+            global_index: src.global_index,
+            block_index: src.block_index,
+            amount: src.amount,
+            subaddress_index: src.subaddress_index,
+            key_image: src.key_image,
+            spent_at: match src.status {
+                KeyImageStatus::SpentAt(block_index) => Some(block_index),
+                KeyImageStatus::NotSpent(_) => None,
+            },
+            memo: src.memo.clone(),
+        }
+    }
+}
@@ -0,0 +1,38 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+use displaydoc::Display;
+use mc_crypto_keys::CompressedRistrettoPublic;
+use mc_fog_recovery_db_iface::{IngestInvocationId, RecoveryDbError};
+use mc_fog_types::common::BlockRange;
+
+/// Enum for error types.
+#[derive(Display, Debug, Eq, PartialEq)]
+pub enum Error {
+    /// The following ingress key was not found: {0:?}
+    MissingIngressKey(CompressedRistrettoPublic),
+
+    /// The following ingress key already exists: {0:?}
+    IngressKeyAlreadyExists(CompressedRistrettoPublic),
+
+    /// The report {0:?} for the following ingress key was not found: {1:?}
+    MissingReport(String, CompressedRistrettoPublic),
+
+    /// The following ingest invocation was not found: {0}
+    MissingIngestInvocation(IngestInvocationId),
+
+    /// The egress public key is already used by another ingest invocation
+    EgressPublicKeyAlreadyExists,
+
+    /// The following ingest invocation was already decommissioned: {0}
+    IngestInvocationAlreadyDecommissioned(IngestInvocationId),
+
+    /// The following missed blocks range was already reported: {0:?}
+    MissedBlocksRangeAlreadyExists(BlockRange),
+}
+
+impl RecoveryDbError for Error {
+    /// Nothing can go wrong with an in-memory database that a retry would fix.
+    fn should_retry(&self) -> bool {
+        false
+    }
+}
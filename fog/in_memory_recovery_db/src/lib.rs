@@ -0,0 +1,742 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+#![deny(missing_docs)]
+
+//! Recovery db implementation keeping all of its data in memory.
+//!
+//! This is meant for tests and small development deployments, which would
+//! otherwise need a running PostgreSQL server. The data is lost when the
+//! process exits, and it is not shared with other processes, so every server
+//! using it has a database of its own.
+
+mod error;
+
+use mc_blockchain_types::Block;
+use mc_crypto_keys::CompressedRistrettoPublic;
+use mc_fog_kex_rng::KexRngPubkey;
+use mc_fog_recovery_db_iface::{
+    select_served_reports, AddBlockDataStatus, ArchivedBlockRange, FogUserEvent,
+    IngestInvocationId, IngestableRange, IngressPublicKeyRecord, IngressPublicKeyRecordFilters,
+    IngressPublicKeyStatus, RecoveryDb, ReportData, ReportDb, ReportKeyState, ReportRecord,
+};
+use mc_fog_types::{
+    common::BlockRange,
+    view::{DecommissionedIngestInvocation, RngRecord, TxOutSearchResult, TxOutSearchResultCode},
+    ETxOutRecord,
+};
+use std::{
+    cmp::max,
+    collections::{BTreeMap, HashMap},
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+pub use error::Error;
+
+/// The URL scheme selecting the in-memory recovery db, e.g. `memory://`.
+pub const IN_MEMORY_URL_SCHEME: &str = "memory";
+
+/// Check whether a database URL selects the in-memory recovery db.
+pub fn is_in_memory_url(database_url: &str) -> bool {
+    database_url
+        .split_once(':')
+        .map(|(scheme, _)| scheme == IN_MEMORY_URL_SCHEME)
+        .unwrap_or(false)
+}
+
+/// In-memory recovery database.
+///
+/// Clones share the same data, like clones of a connection pool would.
+#[derive(Clone, Default)]
+pub struct InMemoryRecoveryDb {
+    state: Arc<Mutex<State>>,
+}
+
+impl InMemoryRecoveryDb {
+    /// Create a new, empty, database.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> MutexGuard<State> {
+        self.state.lock().expect("mutex poisoned")
+    }
+}
+
+/// The tables of the database. Rows which have an id in the SQL schema are
+/// stored in a Vec, and their id is their position in it plus one. Removed user
+/// events are kept as None, so that the ids of the others don't change.
+#[derive(Default)]
+struct State {
+    ingress_keys: BTreeMap<CompressedRistrettoPublic, IngressPublicKeyStatus>,
+    ingest_invocations: Vec<IngestInvocation>,
+    ingested_blocks: Vec<IngestedBlock>,
+    user_events: Vec<Option<UserEvent>>,
+    reports: Vec<Report>,
+}
+
+struct IngestInvocation {
+    ingress_public_key: CompressedRistrettoPublic,
+    egress_public_key: KexRngPubkey,
+    start_block: u64,
+    decommissioned: bool,
+}
+
+struct IngestedBlock {
+    ingest_invocation_id: IngestInvocationId,
+    ingress_public_key: CompressedRistrettoPublic,
+    block_index: u64,
+    cumulative_txo_count: u64,
+    block_signature_timestamp: u64,
+    e_tx_out_records: Vec<ETxOutRecord>,
+}
+
+enum UserEvent {
+    NewIngestInvocation(IngestInvocationId),
+    DecommissionIngestInvocation(IngestInvocationId),
+    MissingBlocks(BlockRange),
+}
+
+struct Report {
+    report_id: String,
+    ingress_public_key: CompressedRistrettoPublic,
+    data: ReportData,
+    pinned: bool,
+    revoked: bool,
+}
+
+impl Report {
+    fn to_record(&self) -> ReportRecord {
+        ReportRecord {
+            report_id: self.report_id.clone(),
+            ingress_public_key: self.ingress_public_key,
+            data: self.data.clone(),
+            pinned: self.pinned,
+            revoked: self.revoked,
+        }
+    }
+}
+
+impl State {
+    fn ingest_invocation(
+        &self,
+        ingest_invocation_id: &IngestInvocationId,
+    ) -> Result<&IngestInvocation, Error> {
+        usize::try_from(**ingest_invocation_id - 1)
+            .ok()
+            .and_then(|index| self.ingest_invocations.get(index))
+            .ok_or(Error::MissingIngestInvocation(*ingest_invocation_id))
+    }
+
+    fn ingest_invocation_mut(
+        &mut self,
+        ingest_invocation_id: &IngestInvocationId,
+    ) -> Result<&mut IngestInvocation, Error> {
+        usize::try_from(**ingest_invocation_id - 1)
+            .ok()
+            .and_then(|index| self.ingest_invocations.get_mut(index))
+            .ok_or(Error::MissingIngestInvocation(*ingest_invocation_id))
+    }
+
+    fn decommission_ingest_invocation(
+        &mut self,
+        ingest_invocation_id: &IngestInvocationId,
+    ) -> Result<(), Error> {
+        let ingest_invocation = self.ingest_invocation_mut(ingest_invocation_id)?;
+        if ingest_invocation.decommissioned {
+            return Err(Error::IngestInvocationAlreadyDecommissioned(
+                *ingest_invocation_id,
+            ));
+        }
+        ingest_invocation.decommissioned = true;
+
+        self.user_events
+            .push(Some(UserEvent::DecommissionIngestInvocation(
+                *ingest_invocation_id,
+            )));
+        Ok(())
+    }
+
+    fn report_mut(
+        &mut self,
+        ingress_key: &CompressedRistrettoPublic,
+        report_id: &str,
+    ) -> Result<&mut Report, Error> {
+        self.reports
+            .iter_mut()
+            .find(|report| {
+                report.report_id == report_id && report.ingress_public_key == *ingress_key
+            })
+            .ok_or_else(|| Error::MissingReport(report_id.to_owned(), *ingress_key))
+    }
+
+    /// The reports ordered by report id, then by decreasing pubkey expiry, like
+    /// in the SQL database.
+    fn reports_in_order(&self) -> Vec<&Report> {
+        let mut reports: Vec<&Report> = self.reports.iter().collect();
+        reports.sort_by(|a, b| {
+            a.report_id
+                .cmp(&b.report_id)
+                .then(b.data.pubkey_expiry.cmp(&a.data.pubkey_expiry))
+        });
+        reports
+    }
+
+    fn last_scanned_block_index(&self, key: &CompressedRistrettoPublic) -> Option<u64> {
+        self.ingested_blocks
+            .iter()
+            .filter(|block| block.ingress_public_key == *key)
+            .map(|block| block.block_index)
+            .max()
+    }
+
+    fn last_ingested_block_index(&self, ingest_invocation_id: &IngestInvocationId) -> Option<u64> {
+        self.ingested_blocks
+            .iter()
+            .filter(|block| block.ingest_invocation_id == *ingest_invocation_id)
+            .map(|block| block.block_index)
+            .max()
+    }
+
+    fn highest_known_block_index(&self) -> Option<u64> {
+        self.ingested_blocks
+            .iter()
+            .map(|block| block.block_index)
+            .max()
+    }
+
+    fn ingested_blocks_at(&self, block_index: u64) -> impl Iterator<Item = &IngestedBlock> {
+        self.ingested_blocks
+            .iter()
+            .filter(move |block| block.block_index == block_index)
+    }
+
+    fn ingested_block_by_key(
+        &self,
+        ingress_key: &CompressedRistrettoPublic,
+        block_index: u64,
+    ) -> Option<&IngestedBlock> {
+        self.ingested_blocks_at(block_index)
+            .find(|block| block.ingress_public_key == *ingress_key)
+    }
+
+    fn user_event(&self, event: &UserEvent) -> Result<FogUserEvent, Error> {
+        Ok(match event {
+            UserEvent::NewIngestInvocation(ingest_invocation_id) => {
+                let ingest_invocation = self.ingest_invocation(ingest_invocation_id)?;
+                FogUserEvent::NewRngRecord(RngRecord {
+                    ingest_invocation_id: **ingest_invocation_id,
+                    pubkey: ingest_invocation.egress_public_key.clone(),
+                    start_block: ingest_invocation.start_block,
+                })
+            }
+            UserEvent::DecommissionIngestInvocation(ingest_invocation_id) => {
+                FogUserEvent::DecommissionIngestInvocation(DecommissionedIngestInvocation {
+                    ingest_invocation_id: **ingest_invocation_id,
+                    last_ingested_block: self
+                        .last_ingested_block_index(ingest_invocation_id)
+                        .unwrap_or(0),
+                })
+            }
+            UserEvent::MissingBlocks(range) => FogUserEvent::MissingBlocks(range.clone()),
+        })
+    }
+}
+
+/// See trait `fog_recovery_db_iface::RecoveryDb` for documentation.
+impl RecoveryDb for InMemoryRecoveryDb {
+    type Error = Error;
+
+    fn get_ingress_key_status(
+        &self,
+        key: &CompressedRistrettoPublic,
+    ) -> Result<Option<IngressPublicKeyStatus>, Self::Error> {
+        Ok(self.lock().ingress_keys.get(key).cloned())
+    }
+
+    fn new_ingress_key(
+        &self,
+        key: &CompressedRistrettoPublic,
+        start_block_count: u64,
+    ) -> Result<u64, Self::Error> {
+        let mut state = self.lock();
+        if state.ingress_keys.contains_key(key) {
+            return Err(Error::IngressKeyAlreadyExists(*key));
+        }
+
+        let highest_known_block_count = state
+            .highest_known_block_index()
+            .map(|index| index + 1)
+            .unwrap_or(0);
+        let accepted_start_block_count = max(start_block_count, highest_known_block_count);
+
+        state.ingress_keys.insert(
+            *key,
+            IngressPublicKeyStatus {
+                start_block: accepted_start_block_count,
+                pubkey_expiry: 0,
+                retired: false,
+                lost: false,
+            },
+        );
+        Ok(accepted_start_block_count)
+    }
+
+    fn retire_ingress_key(
+        &self,
+        key: &CompressedRistrettoPublic,
+        set_retired: bool,
+    ) -> Result<(), Self::Error> {
+        if let Some(status) = self.lock().ingress_keys.get_mut(key) {
+            status.retired = set_retired;
+        }
+        Ok(())
+    }
+
+    fn get_last_scanned_block_index(
+        &self,
+        key: &CompressedRistrettoPublic,
+    ) -> Result<Option<u64>, Self::Error> {
+        Ok(self.lock().last_scanned_block_index(key))
+    }
+
+    fn get_ingress_key_records(
+        &self,
+        start_block_at_least: u64,
+        ingress_public_key_record_filters: &IngressPublicKeyRecordFilters,
+    ) -> Result<Vec<IngressPublicKeyRecord>, Self::Error> {
+        let state = self.lock();
+        Ok(state
+            .ingress_keys
+            .iter()
+            .filter(|(_key, status)| status.start_block >= start_block_at_least)
+            .filter(|(_key, status)| {
+                ingress_public_key_record_filters.should_include_lost_keys || !status.lost
+            })
+            .filter(|(_key, status)| {
+                ingress_public_key_record_filters.should_include_retired_keys || !status.retired
+            })
+            .map(|(key, status)| IngressPublicKeyRecord {
+                key: *key,
+                status: status.clone(),
+                last_scanned_block: state.last_scanned_block_index(key),
+            })
+            .filter(|record| {
+                !ingress_public_key_record_filters.should_only_include_unexpired_keys
+                    || record
+                        .last_scanned_block
+                        .map(|last_scanned_block| record.status.pubkey_expiry > last_scanned_block)
+                        .unwrap_or(false)
+            })
+            .collect())
+    }
+
+    fn new_ingest_invocation(
+        &self,
+        prev_ingest_invocation_id: Option<IngestInvocationId>,
+        ingress_public_key: &CompressedRistrettoPublic,
+        egress_public_key: &KexRngPubkey,
+        start_block: u64,
+    ) -> Result<IngestInvocationId, Self::Error> {
+        let mut state = self.lock();
+
+        // Check all the constraints before changing anything, so that a failure
+        // leaves the database untouched.
+        if let Some(prev_ingest_invocation_id) = prev_ingest_invocation_id.as_ref() {
+            if state
+                .ingest_invocation(prev_ingest_invocation_id)?
+                .decommissioned
+            {
+                return Err(Error::IngestInvocationAlreadyDecommissioned(
+                    *prev_ingest_invocation_id,
+                ));
+            }
+        }
+        if !state.ingress_keys.contains_key(ingress_public_key) {
+            return Err(Error::MissingIngressKey(*ingress_public_key));
+        }
+        if state.ingest_invocations.iter().any(|invocation| {
+            invocation.egress_public_key.public_key == egress_public_key.public_key
+        }) {
+            return Err(Error::EgressPublicKeyAlreadyExists);
+        }
+
+        // Optionally decommission old invocation.
+        if let Some(prev_ingest_invocation_id) = prev_ingest_invocation_id {
+            state.decommission_ingest_invocation(&prev_ingest_invocation_id)?;
+        }
+
+        // Write new invocation.
+        state.ingest_invocations.push(IngestInvocation {
+            ingress_public_key: *ingress_public_key,
+            egress_public_key: egress_public_key.clone(),
+            start_block,
+            decommissioned: false,
+        });
+        let ingest_invocation_id = IngestInvocationId::from(state.ingest_invocations.len() as i64);
+
+        // Write a user event.
+        state
+            .user_events
+            .push(Some(UserEvent::NewIngestInvocation(ingest_invocation_id)));
+
+        Ok(ingest_invocation_id)
+    }
+
+    fn get_ingestable_ranges(&self) -> Result<Vec<IngestableRange>, Self::Error> {
+        let state = self.lock();
+        Ok(state
+            .ingest_invocations
+            .iter()
+            .enumerate()
+            .map(|(index, ingest_invocation)| {
+                let id = IngestInvocationId::from(index as i64 + 1);
+                IngestableRange {
+                    id,
+                    start_block: ingest_invocation.start_block,
+                    decommissioned: ingest_invocation.decommissioned,
+                    last_ingested_block: state.last_ingested_block_index(&id),
+                }
+            })
+            .collect())
+    }
+
+    fn decommission_ingest_invocation(
+        &self,
+        ingest_invocation_id: &IngestInvocationId,
+    ) -> Result<(), Self::Error> {
+        self.lock()
+            .decommission_ingest_invocation(ingest_invocation_id)
+    }
+
+    fn add_block_data(
+        &self,
+        ingest_invocation_id: &IngestInvocationId,
+        block: &Block,
+        block_signature_timestamp: u64,
+        txs: &[ETxOutRecord],
+    ) -> Result<AddBlockDataStatus, Self::Error> {
+        let mut state = self.lock();
+        let ingress_public_key = state
+            .ingest_invocation(ingest_invocation_id)?
+            .ingress_public_key;
+
+        // A block can only be added once per ingest invocation and per ingress key.
+        if state.ingested_blocks_at(block.index).any(|ingested_block| {
+            ingested_block.ingest_invocation_id == *ingest_invocation_id
+                || ingested_block.ingress_public_key == ingress_public_key
+        }) {
+            return Ok(AddBlockDataStatus {
+                block_already_scanned_with_this_key: true,
+            });
+        }
+
+        state.ingested_blocks.push(IngestedBlock {
+            ingest_invocation_id: *ingest_invocation_id,
+            ingress_public_key,
+            block_index: block.index,
+            cumulative_txo_count: block.cumulative_txo_count,
+            block_signature_timestamp,
+            e_tx_out_records: txs.to_vec(),
+        });
+
+        Ok(AddBlockDataStatus {
+            block_already_scanned_with_this_key: false,
+        })
+    }
+
+    fn report_lost_ingress_key(
+        &self,
+        lost_ingress_key: CompressedRistrettoPublic,
+    ) -> Result<(), Self::Error> {
+        let mut state = self.lock();
+        let status = state
+            .ingress_keys
+            .get(&lost_ingress_key)
+            .ok_or(Error::MissingIngressKey(lost_ingress_key))?;
+
+        // Compute a missed block range based on looking at the key status,
+        // which is correct if no blocks have actually been scanned using the key.
+        let mut missed_block_range = BlockRange::new(status.start_block, status.pubkey_expiry);
+
+        // Find the last scanned block index (if any block has been scanned with this
+        // key)
+        if let Some(block_index) = state.last_scanned_block_index(&lost_ingress_key) {
+            // If we did actually scan some blocks, then report a smaller range
+            if block_index + 1 > missed_block_range.start_block {
+                missed_block_range.start_block = block_index + 1;
+            }
+        }
+
+        // If the missed block range is invalid (empty), we don't have to add it.
+        // This covers the case where the key was scanned up to its pubkey expiry,
+        // and the case where the ingress key was never actually published to the
+        // report server, and then pubkey_expiry is zero.
+        let new_event = if missed_block_range.is_valid() {
+            if state.user_events.iter().flatten().any(|event| {
+                matches!(event, UserEvent::MissingBlocks(range) if *range == missed_block_range)
+            }) {
+                return Err(Error::MissedBlocksRangeAlreadyExists(missed_block_range));
+            }
+            Some(UserEvent::MissingBlocks(missed_block_range))
+        } else {
+            None
+        };
+
+        // Mark the key lost, and add the new range.
+        if let Some(status) = state.ingress_keys.get_mut(&lost_ingress_key) {
+            status.lost = true;
+        }
+        state.user_events.extend(new_event.map(Some));
+
+        Ok(())
+    }
+
+    fn get_missed_block_ranges(&self) -> Result<Vec<BlockRange>, Self::Error> {
+        Ok(self
+            .lock()
+            .user_events
+            .iter()
+            .flatten()
+            .filter_map(|event| match event {
+                UserEvent::MissingBlocks(range) => Some(range.clone()),
+                _ => None,
+            })
+            .collect())
+    }
+
+    fn remove_missed_block_range(&self, block_range: &BlockRange) -> Result<bool, Self::Error> {
+        let mut state = self.lock();
+        let event = state.user_events.iter_mut().find(
+            |event| matches!(event, Some(UserEvent::MissingBlocks(range)) if range == block_range),
+        );
+        Ok(event.and_then(Option::take).is_some())
+    }
+
+    fn search_user_events(
+        &self,
+        start_from_user_event_id: i64,
+    ) -> Result<(Vec<FogUserEvent>, i64), Self::Error> {
+        let state = self.lock();
+
+        // User event ids start from 1, so "start_from_user_event_id = 0" will
+        // capture everything
+        let start_index = usize::try_from(start_from_user_event_id)
+            .unwrap_or(0)
+            .min(state.user_events.len());
+        let events = state.user_events[start_index..]
+            .iter()
+            .flatten()
+            .map(|event| state.user_event(event))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // If no events are found, return start_from_user_event_id and not 0
+        let next_start_from_user_event_id =
+            max(start_from_user_event_id, state.user_events.len() as i64);
+
+        Ok((events, next_start_from_user_event_id))
+    }
+
+    fn get_tx_outs(
+        &self,
+        start_block: u64,
+        search_keys: &[Vec<u8>],
+    ) -> Result<Vec<TxOutSearchResult>, Self::Error> {
+        let state = self.lock();
+
+        let search_key_to_payload: HashMap<&[u8], &[u8]> = state
+            .ingested_blocks
+            .iter()
+            .filter(|block| block.block_index >= start_block)
+            .flat_map(|block| block.e_tx_out_records.iter())
+            .map(|record| (record.search_key.as_slice(), record.payload.as_slice()))
+            .collect();
+
+        Ok(search_keys
+            .iter()
+            .map(
+                |search_key| match search_key_to_payload.get(search_key.as_slice()) {
+                    Some(payload) => TxOutSearchResult {
+                        search_key: search_key.clone(),
+                        result_code: TxOutSearchResultCode::Found as u32,
+                        ciphertext: payload.to_vec(),
+                    },
+                    None => TxOutSearchResult {
+                        search_key: search_key.clone(),
+                        result_code: TxOutSearchResultCode::NotFound as u32,
+                        ciphertext: Default::default(),
+                    },
+                },
+            )
+            .collect())
+    }
+
+    /// Liveness of ingest invocations is not tracked, since nothing reads it
+    /// back through this interface.
+    fn update_last_active_at(
+        &self,
+        ingest_invocation_id: &IngestInvocationId,
+    ) -> Result<(), Self::Error> {
+        self.lock().ingest_invocation(ingest_invocation_id)?;
+        Ok(())
+    }
+
+    fn get_tx_outs_by_block_and_key(
+        &self,
+        ingress_key: CompressedRistrettoPublic,
+        block_index: u64,
+    ) -> Result<Option<Vec<ETxOutRecord>>, Self::Error> {
+        Ok(self
+            .lock()
+            .ingested_block_by_key(&ingress_key, block_index)
+            .map(|block| block.e_tx_out_records.clone()))
+    }
+
+    fn get_invocation_id_by_block_and_key(
+        &self,
+        ingress_key: CompressedRistrettoPublic,
+        block_index: u64,
+    ) -> Result<Option<IngestInvocationId>, Self::Error> {
+        Ok(self
+            .lock()
+            .ingested_block_by_key(&ingress_key, block_index)
+            .map(|block| block.ingest_invocation_id))
+    }
+
+    fn get_cumulative_txo_count_for_block(
+        &self,
+        block_index: u64,
+    ) -> Result<Option<u64>, Self::Error> {
+        Ok(self
+            .lock()
+            .ingested_blocks_at(block_index)
+            .map(|block| block.cumulative_txo_count)
+            .next())
+    }
+
+    fn get_block_signature_timestamp_for_block(
+        &self,
+        block_index: u64,
+    ) -> Result<Option<u64>, Self::Error> {
+        Ok(self
+            .lock()
+            .ingested_blocks_at(block_index)
+            .map(|block| block.block_signature_timestamp)
+            .next())
+    }
+
+    fn get_highest_known_block_index(&self) -> Result<Option<u64>, Self::Error> {
+        Ok(self.lock().highest_known_block_index())
+    }
+
+    /// Blocks are never archived out of memory.
+    fn get_archived_block_ranges(&self) -> Result<Vec<ArchivedBlockRange>, Self::Error> {
+        Ok(Vec::new())
+    }
+}
+
+/// See trait `fog_recovery_db_iface::ReportDb` for documentation.
+impl ReportDb for InMemoryRecoveryDb {
+    type Error = Error;
+
+    fn get_all_reports(&self) -> Result<Vec<(String, ReportData)>, Self::Error> {
+        let state = self.lock();
+        let reports = state
+            .reports_in_order()
+            .into_iter()
+            .map(|report| {
+                let key_state = match state.ingress_keys.get(&report.ingress_public_key) {
+                    None => ReportKeyState::Lost,
+                    Some(status) if status.lost => ReportKeyState::Lost,
+                    Some(status) if status.retired => ReportKeyState::Retired,
+                    Some(_)
+                        if state.ingest_invocations.iter().any(|invocation| {
+                            !invocation.decommissioned
+                                && invocation.ingress_public_key == report.ingress_public_key
+                        }) =>
+                    {
+                        ReportKeyState::Active
+                    }
+                    Some(_) => ReportKeyState::Pending,
+                };
+                (report.to_record(), key_state)
+            })
+            .collect();
+
+        Ok(select_served_reports(reports))
+    }
+
+    fn get_report_records(&self) -> Result<Vec<ReportRecord>, Self::Error> {
+        Ok(self
+            .lock()
+            .reports_in_order()
+            .into_iter()
+            .map(Report::to_record)
+            .collect())
+    }
+
+    fn set_report(
+        &self,
+        ingress_key: &CompressedRistrettoPublic,
+        report_id: &str,
+        data: &ReportData,
+    ) -> Result<IngressPublicKeyStatus, Self::Error> {
+        let mut state = self.lock();
+        if let Some(ingest_invocation_id) = data.ingest_invocation_id.as_ref() {
+            state.ingest_invocation(ingest_invocation_id)?;
+        }
+
+        // First, update the pubkey_expiry value on this ingress key, only
+        // allowing it to increase, and only if it is not retired
+        let status = state
+            .ingress_keys
+            .get_mut(ingress_key)
+            .ok_or(Error::MissingIngressKey(*ingress_key))?;
+        if status.retired {
+            // Cannot publish key because it is retired
+            return Ok(status.clone());
+        }
+        status.pubkey_expiry = max(status.pubkey_expiry, data.pubkey_expiry);
+        let status = status.clone();
+
+        // Then insert the report, or replace the data of the one with the same
+        // report id and key.
+        match state.report_mut(ingress_key, report_id) {
+            Ok(existing) => existing.data = data.clone(),
+            Err(_) => state.reports.push(Report {
+                report_id: report_id.to_owned(),
+                ingress_public_key: *ingress_key,
+                data: data.clone(),
+                pinned: false,
+                revoked: false,
+            }),
+        }
+
+        Ok(status)
+    }
+
+    fn remove_report(&self, report_id: &str) -> Result<(), Self::Error> {
+        self.lock()
+            .reports
+            .retain(|report| report.report_id != report_id);
+        Ok(())
+    }
+
+    fn set_report_pinned(
+        &self,
+        ingress_key: &CompressedRistrettoPublic,
+        report_id: &str,
+        pinned: bool,
+    ) -> Result<(), Self::Error> {
+        self.lock().report_mut(ingress_key, report_id)?.pinned = pinned;
+        Ok(())
+    }
+
+    fn set_report_revoked(
+        &self,
+        ingress_key: &CompressedRistrettoPublic,
+        report_id: &str,
+        revoked: bool,
+    ) -> Result<(), Self::Error> {
+        self.lock().report_mut(ingress_key, report_id)?.revoked = revoked;
+        Ok(())
+    }
+}
@@ -0,0 +1,40 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+use mc_fog_in_memory_recovery_db::InMemoryRecoveryDb;
+use mc_fog_test_infra::db_tests::*;
+
+#[test]
+fn in_memory_recovery_db_smoke_tests_new_apis() {
+    mc_util_test_helper::run_with_several_seeds(|mut rng| {
+        let db = InMemoryRecoveryDb::new();
+
+        recovery_db_smoke_tests_new_apis(&mut rng, &db);
+    })
+}
+
+#[test]
+fn in_memory_recovery_db_missed_blocks_reporting() {
+    mc_util_test_helper::run_with_several_seeds(|mut rng| {
+        let db = InMemoryRecoveryDb::new();
+
+        recovery_db_missed_blocks_reporting(&mut rng, &db);
+    })
+}
+
+#[test]
+fn in_memory_recovery_db_rng_records_decommissioning() {
+    mc_util_test_helper::run_with_several_seeds(|mut rng| {
+        let db = InMemoryRecoveryDb::new();
+
+        recovery_db_rng_records_decommissioning(&mut rng, &db);
+    })
+}
+
+#[test]
+fn in_memory_recovery_db_ingress_keys() {
+    mc_util_test_helper::run_with_several_seeds(|mut rng| {
+        let db = InMemoryRecoveryDb::new();
+
+        test_recovery_db_ingress_keys(&mut rng, &db);
+    })
+}
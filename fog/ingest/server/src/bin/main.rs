@@ -11,7 +11,7 @@ use mc_fog_ingest_server::{
     server::{IngestServer, IngestServerConfig},
     state_file::StateFile,
 };
-use mc_fog_sql_recovery_db::SqlRecoveryDb;
+use mc_fog_sql_recovery_db::AnyRecoveryDb;
 use mc_ledger_db::LedgerDB;
 use mc_util_cli::ParserWithBuildInfo;
 use mc_util_grpc::AdminServer;
@@ -41,6 +41,15 @@ fn main() {
 
     log::info!(logger, "State file is {:?}", state_file_path);
 
+    // In backfill mode, the enclave is initialized with the lost ingress key
+    // sealed in the given state file.
+    let backfill_sealed_key = config.backfill_state_file.as_ref().map(|path| {
+        StateFile::new(path.clone())
+            .read()
+            .unwrap_or_else(|err| panic!("Could not read backfill state file {:?}: {}", path, err))
+            .sealed_ingress_key
+    });
+
     // Create IAS client.
     let ias_client = Client::new(&config.ias_api_key).expect("Could not create IAS client");
 
@@ -53,7 +62,7 @@ fn main() {
     // Open databases.
     let database_url =
         std::env::var("DATABASE_URL").expect("DATABASE_URL environment variable missing");
-    let recovery_db = SqlRecoveryDb::new_from_url(
+    let recovery_db = AnyRecoveryDb::new_shared_from_url(
         &database_url,
         config.postgres_config.clone(),
         logger.clone(),
@@ -83,7 +92,11 @@ fn main() {
         peer_checkup_period: Some(config.peer_checkup_period),
         watcher_timeout: config.watcher_timeout,
         fog_report_id: config.fog_report_id.clone(),
-        state_file: Some(StateFile::new(state_file_path)),
+        // The restored ingress key must not replace the one in our state file.
+        state_file: backfill_sealed_key
+            .is_none()
+            .then(|| StateFile::new(state_file_path)),
+        sealed_ingress_key: backfill_sealed_key.clone(),
         enclave_path,
     };
 
@@ -96,6 +109,14 @@ fn main() {
         logger.clone(),
     );
 
+    if backfill_sealed_key.is_some() {
+        let block_range = server
+            .backfill()
+            .unwrap_or_else(|err| panic!("Failed backfilling missed blocks: {}", err));
+        log::info!(logger, "Backfilled missed blocks {}, exiting", block_range);
+        return;
+    }
+
     server.start().expect("Failed starting Ingest Service");
 
     // Start admin server.
@@ -102,6 +102,17 @@ pub struct IngestConfig {
     #[clap(long, env = "MC_STATE_FILE")]
     pub state_file: Option<PathBuf>,
 
+    /// State file of an ingest server which held an ingress key that was
+    /// since reported lost.
+    ///
+    /// If set, the server does not serve, but restores the lost ingress key
+    /// from this state file, scans the blocks that the key missed, publishes
+    /// the results, and exits. The key is sealed to the machine which wrote
+    /// the state file, so this must run on that machine. Our own state file
+    /// is not used in this mode.
+    #[clap(long, env = "MC_BACKFILL_STATE_FILE")]
+    pub backfill_state_file: Option<PathBuf>,
+
     /// Postgres config
     #[clap(flatten)]
     pub postgres_config: SqlRecoveryDbConnectionConfig,
@@ -125,5 +136,39 @@ mod tests {
       "--admin-listen-uri", "insecure-mca://127.0.0.1:8003/",
       "--pubkey-expiry-window", "100"]).expect("Could not parse command line arguments");
         assert_eq!(config.peers.len(), 2);
+        assert_eq!(config.backfill_state_file, None);
+    }
+
+    #[test]
+    fn ingest_server_config_backfill() {
+        let args = [
+            "/usr/bin/fog_ingest_server",
+            "--ledger-db",
+            "/fog-data/ledger",
+            "--watcher-db",
+            "/fog-data/watcher",
+            "--ias-spid",
+            "00000000000000000000000000000000",
+            "--ias-api-key",
+            "00000000000000000000000000000000",
+            "--client-listen-uri",
+            "insecure-fog-ingest://0.0.0.0:3226/",
+            "--peer-listen-uri",
+            "insecure-igp://0.0.0.0:8090/",
+            "--local-node-id",
+            "fogingest2.buildtest.svc.cluster.local:443",
+            "--peers",
+            "insecure-igp://fogingest2.buildtest.svc.cluster.local:443",
+        ];
+
+        let config = IngestConfig::try_parse_from(
+            args.iter()
+                .chain(&["--backfill-state-file", "/fog-data/lost-key-state"]),
+        )
+        .expect("Could not parse command line arguments");
+        assert_eq!(
+            config.backfill_state_file,
+            Some(PathBuf::from("/fog-data/lost-key-state"))
+        );
     }
 }
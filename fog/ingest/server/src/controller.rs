@@ -140,9 +140,12 @@ where
                 }
             });
 
-        let cached_key: Option<Vec<u8>> = state_file_data
-            .as_ref()
-            .map(|x| x.sealed_ingress_key.clone());
+        // A sealed key from the config takes precedence over the state file.
+        let cached_key: Option<Vec<u8>> = config.sealed_ingress_key.clone().or_else(|| {
+            state_file_data
+                .as_ref()
+                .map(|x| x.sealed_ingress_key.clone())
+        });
 
         // Initialize the enclave
         let enclave = match IngestSgxEnclave::new(
@@ -1154,6 +1157,86 @@ where
         self.recovery_db.get_missed_block_ranges()
     }
 
+    /// Prepare to backfill the blocks that our ingress key missed.
+    ///
+    /// Our ingress key must have been reported lost, and restored into our
+    /// enclave from a sealed backup. The egress key is rotated, so that the
+    /// first block we process publishes a fresh set of RNGs, starting at the
+    /// first block that the key has not scanned.
+    ///
+    /// Returns the missed block range that was recorded when the key was
+    /// lost, which ends at its pubkey expiry. Blocks scanned by an earlier,
+    /// interrupted backfill are not scanned again.
+    ///
+    /// This is only possible if the server is idling
+    pub fn start_backfill(&self) -> Result<BlockRange, Error> {
+        let mut state = self.get_state();
+        if !state.is_idle() {
+            return Err(Error::ServerNotIdle);
+        }
+
+        let ingress_pubkey: CompressedRistrettoPublic = self.enclave.get_ingress_pubkey()?.into();
+        let status = match self.recovery_db.get_ingress_key_status(&ingress_pubkey)? {
+            Some(status) if status.lost => status,
+            _ => return Err(Error::BackfillKeyNotLost(ingress_pubkey)),
+        };
+
+        let next_block_index = self
+            .recovery_db
+            .get_last_scanned_block_index(&ingress_pubkey)?
+            .map(|block_index| block_index + 1)
+            .unwrap_or(status.start_block)
+            .max(status.start_block);
+
+        // Missed block ranges don't record which key missed them, so we look for
+        // the one our key left when it was lost.
+        let missed_block_range = self
+            .recovery_db
+            .get_missed_block_ranges()?
+            .into_iter()
+            .find(|range| {
+                range.end_block == status.pubkey_expiry
+                    && status.start_block <= range.start_block
+                    && range.start_block <= next_block_index
+            })
+            .ok_or(Error::BackfillNoMissedBlocks(ingress_pubkey))?;
+
+        log::info!(
+            self.logger,
+            "Backfilling blocks {} with lost ingress key {}, starting at block {}",
+            missed_block_range,
+            ingress_pubkey,
+            next_block_index
+        );
+        self.new_egress_key(&mut state)?;
+        state
+            .set_next_block_index(next_block_index)
+            .expect("Modification should have been allowed, this is a logic error");
+
+        Ok(missed_block_range)
+    }
+
+    /// Finish backfilling with our ingress key, successfully or not.
+    ///
+    /// This decommissions our ingest invocation. If the missed block range was
+    /// scanned completely, it is removed, so that clients stop downloading its
+    /// blocks and get their TxOuts from fog view instead.
+    pub fn finish_backfill(&self, scanned_block_range: Option<&BlockRange>) -> Result<(), Error> {
+        let mut state = self.get_state();
+        self.decommission_ingest_invocation_id(&mut state);
+
+        if let Some(block_range) = scanned_block_range {
+            if !self.recovery_db.remove_missed_block_range(block_range)? {
+                log::warn!(
+                    self.logger,
+                    "Missed block range {} was already removed",
+                    block_range
+                );
+            }
+        }
+        Ok(())
+    }
+
     /// Get the public key of the enclave
     ///
     /// This thin pass-through exists to reduce the need for other components
@@ -13,6 +13,7 @@ use mc_fog_api::report_parse::ReportParseError;
 use mc_fog_ingest_enclave::Error as EnclaveError;
 use mc_fog_recovery_db_iface::RecoveryDbError;
 use mc_fog_sql_recovery_db::Error as SqlRecoveryDbError;
+use mc_fog_types::common::BlockRange;
 use mc_fog_uri::IngestPeerUri;
 use mc_ledger_db::Error as LedgerDbError;
 use mc_sgx_report_cache_api::Error as ReportableEnclaveError;
@@ -56,6 +57,14 @@ pub enum IngestServiceError {
     Grpc(GrpcError),
     /// Report Parse: {0}
     ReportParse(ReportParseError),
+    /// Ingress key {0} was not reported lost, there are no blocks to backfill
+    BackfillKeyNotLost(CompressedRistrettoPublic),
+    /// No missed block range was found for lost ingress key {0}
+    BackfillNoMissedBlocks(CompressedRistrettoPublic),
+    /// Blocks {0} are not all in the ledger yet
+    BackfillBlocksNotInLedger(BlockRange),
+    /// Block {0} could not be backfilled
+    BackfillBlock(u64),
 }
 
 impl From<EnclaveError> for IngestServiceError {
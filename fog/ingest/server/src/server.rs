@@ -24,6 +24,7 @@ use mc_fog_api::{
     ingest_grpc, ingest_peer_grpc,
 };
 use mc_fog_recovery_db_iface::{RecoveryDb, ReportDb};
+use mc_fog_types::common::BlockRange;
 use mc_fog_uri::{FogIngestUri, IngestPeerUri};
 use mc_ledger_db::{Ledger, LedgerDB};
 use mc_util_grpc::ConnectionUriGrpcioServer;
@@ -90,6 +91,11 @@ pub struct IngestServerConfig {
     /// Optional state file path.
     pub state_file: Option<StateFile>,
 
+    /// Optional sealed ingress private key to initialize the enclave with,
+    /// instead of the one in the state file. This restores a lost ingress key
+    /// to backfill the blocks it missed.
+    pub sealed_ingress_key: Option<Vec<u8>>,
+
     /// Enclave path
     /// This should generally be, next to current exe, in production.
     /// During cargo tests we use a helper that searches the target/ dir for the
@@ -393,6 +399,57 @@ where
     ) -> Result<IngestSummary, IngestServiceError> {
         self.controller.sync_keys_from_remote(remote_peer_uri)
     }
+
+    /// Scan the blocks that our ingress key missed when it was lost, and then
+    /// remove its missed block range, so that fog view serves the resulting
+    /// ETxOutRecords to clients instead.
+    ///
+    /// This is meant to be used on a server which was not started, whose
+    /// enclave was initialized with the sealed private key of the lost ingress
+    /// key (see IngestServerConfig::sealed_ingress_key).
+    ///
+    /// Returns the missed block range that was backfilled.
+    pub fn backfill(&self) -> Result<BlockRange, IngestServiceError> {
+        let missed_block_range = self.controller.start_backfill()?;
+        let result = self.backfill_blocks(&missed_block_range);
+        // Even if we failed, the blocks we did scan should be served, and another
+        // backfill will resume after them.
+        self.controller
+            .finish_backfill(result.is_ok().then(|| &missed_block_range))?;
+        result?;
+
+        log::info!(self.logger, "Backfilled blocks {}", missed_block_range);
+        Ok(missed_block_range)
+    }
+
+    /// Helper which feeds the blocks of a backfill to the controller
+    fn backfill_blocks(&self, block_range: &BlockRange) -> Result<(), IngestServiceError> {
+        if self.ledger_db.num_blocks()? < block_range.end_block {
+            return Err(IngestServiceError::BackfillBlocksNotInLedger(
+                block_range.clone(),
+            ));
+        }
+
+        let next_block_index = self.controller.get_next_block_index().0;
+        for block_index in next_block_index..block_range.end_block {
+            let block_data = self.ledger_db.get_block_data(block_index)?;
+            let timestamp = self
+                .watcher
+                .poll_block_timestamp(block_index, self.config.watcher_timeout);
+            self.controller.process_next_block(
+                block_data.block(),
+                block_data.contents(),
+                timestamp,
+            );
+
+            // process_next_block only moves on to the next block if the block data
+            // made it to the database.
+            if self.controller.get_next_block_index().0 != block_index + 1 {
+                return Err(IngestServiceError::BackfillBlock(block_index));
+            }
+        }
+        Ok(())
+    }
 }
 
 impl<
@@ -209,6 +209,36 @@ impl IngestServerTestHelper {
         peer_idxs: impl Iterator<Item = u8>,
         state_file_path: PathBuf,
     ) -> TestIngestNode {
+        let (client_listen_uri, peer_listen_uri) = make_uris(self.base_port, idx);
+        let mut server = self.make_server(
+            idx,
+            peer_idxs,
+            Some(StateFile::new(state_file_path.clone())),
+            None,
+        );
+        server.start().expect("Failed to start IngestServer");
+        assert!(
+            !server.is_active(),
+            "Newly created IngestServer should never be active"
+        );
+
+        TestIngestNode {
+            server,
+            state_file_path,
+            client_listen_uri,
+            peer_listen_uri,
+        }
+    }
+
+    /// Set up the i'th server without starting it, e.g. to backfill blocks
+    /// with a restored ingress key.
+    pub fn make_server(
+        &self,
+        idx: u8,
+        peer_idxs: impl Iterator<Item = u8>,
+        state_file: Option<StateFile>,
+        sealed_ingress_key: Option<Vec<u8>>,
+    ) -> IngestServer<AttestClient, SqlRecoveryDb> {
         let logger = self.logger.new(o!("mc.node_id" => idx.to_string()));
 
         let (client_listen_uri, peer_listen_uri) = make_uris(self.base_port, idx);
@@ -223,41 +253,30 @@ impl IngestServerTestHelper {
         let config = IngestServerConfig {
             ias_spid: Default::default(),
             local_node_id,
-            peer_listen_uri: peer_listen_uri.clone(),
+            peer_listen_uri,
             peers,
             fog_report_id: Default::default(),
-            client_listen_uri: client_listen_uri.clone(),
+            client_listen_uri,
             max_transactions: 10_000,
             pubkey_expiry_window: 10,
             peer_checkup_period: Some(Duration::from_secs(5)),
             watcher_timeout: Duration::from_secs(5),
-            state_file: Some(StateFile::new(state_file_path.clone())),
+            state_file,
+            sealed_ingress_key,
             enclave_path: get_enclave_path(mc_fog_ingest_enclave::ENCLAVE_FILE),
             omap_capacity: OMAP_CAPACITY,
         };
 
         let ra_client = AttestClient::new("").expect("Failed to create IAS client");
 
-        let mut server = IngestServer::new(
+        IngestServer::new(
             config,
             ra_client,
             self.recovery_db.clone(),
             self.watcher.clone(),
             self.ledger.clone(),
             logger,
-        );
-        server.start().expect("Failed to start IngestServer");
-        assert!(
-            !server.is_active(),
-            "Newly created IngestServer should never be active"
-        );
-
-        TestIngestNode {
-            server,
-            state_file_path,
-            client_listen_uri,
-            peer_listen_uri,
-        }
+        )
     }
 
     /// Generate a random [TxOut].
@@ -0,0 +1,93 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! Tests backfilling the blocks missed by a lost ingress key, once the key is
+//! restored from a sealed backup
+
+use mc_common::logger::{test_with_logger, Logger};
+use mc_fog_ingest_server::{error::IngestServiceError, state_file::StateFile};
+use mc_fog_ingest_server_test_utils::IngestServerTestHelper;
+use mc_fog_recovery_db_iface::{RecoveryDb, ReportData, ReportDb};
+use mc_fog_types::common::BlockRange;
+
+const BASE_PORT: u16 = 3620;
+
+// Lose a key which was published but never used to scan blocks, then restore it
+// from the state file of the node which held it, and check that the backfill
+// scanned exactly the missed blocks and removed the missed block range.
+#[test_with_logger]
+fn backfill_missed_block_range(logger: Logger) {
+    let mut helper = IngestServerTestHelper::new(BASE_PORT, logger);
+    helper.add_origin_block();
+    helper.add_test_blocks(10);
+
+    let node = helper.make_node(0, 0..1);
+    let lost_key = node.get_ingress_key();
+    let state_file_path = node.state_file_path.clone();
+    drop(node);
+
+    helper.recovery_db.new_ingress_key(&lost_key, 1).unwrap();
+    helper
+        .recovery_db
+        .set_report(
+            &lost_key,
+            "",
+            &ReportData {
+                pubkey_expiry: 8,
+                ingest_invocation_id: None,
+                report: Default::default(),
+            },
+        )
+        .unwrap();
+    helper
+        .recovery_db
+        .report_lost_ingress_key(lost_key)
+        .unwrap();
+    assert_eq!(
+        helper.recovery_db.get_missed_block_ranges().unwrap(),
+        vec![BlockRange::new(1, 8)]
+    );
+
+    // A server with a fresh key has nothing to backfill.
+    let server = helper.make_server(1, 1..2, None, None);
+    match server.backfill() {
+        Err(IngestServiceError::BackfillKeyNotLost(_)) => {}
+        result => panic!("Unexpected backfill result: {:?}", result),
+    }
+    drop(server);
+
+    let sealed_ingress_key = StateFile::new(state_file_path)
+        .read()
+        .expect("Could not read state file")
+        .sealed_ingress_key;
+    let server = helper.make_server(0, 0..1, None, Some(sealed_ingress_key));
+    assert_eq!(
+        server.backfill().expect("Failed to backfill"),
+        BlockRange::new(1, 8)
+    );
+    helper.check_ingress_key(&lost_key, false, true);
+
+    for block_index in 0..11 {
+        let tx_outs = helper
+            .recovery_db
+            .get_tx_outs_by_block_and_key(lost_key, block_index)
+            .unwrap();
+        if (1..8).contains(&block_index) {
+            assert_eq!(tx_outs.map(|tx_outs| tx_outs.len()), Some(1));
+        } else {
+            assert!(tx_outs.is_none());
+        }
+    }
+
+    // Clients don't have to download the missed blocks anymore.
+    assert!(helper
+        .recovery_db
+        .get_missed_block_ranges()
+        .unwrap()
+        .is_empty());
+
+    // There is nothing left to backfill.
+    match server.backfill() {
+        Err(IngestServiceError::BackfillNoMissedBlocks(key)) => assert_eq!(key, lost_key),
+        result => panic!("Unexpected backfill result: {:?}", result),
+    }
+}
@@ -77,6 +77,9 @@ fn test_ingest_polling_integration(base_port: u16, mut rng: RngType, logger: Log
                 logger.clone(),
             );
 
+            // The first block of this phase, from which a user could be restored
+            let phase_start_block = block_index;
+
             // Do a series of random blocks
             for block_count in 0..NUM_BLOCKS_PER_PHASE {
                 log::info!(logger, "Block {}/{}", block_count + 1, NUM_BLOCKS_PER_PHASE);
@@ -96,6 +99,11 @@ fn test_ingest_polling_integration(base_port: u16, mut rng: RngType, logger: Log
                 );
                 block_index += 1;
                 mc_fog_test_infra::test_polling_recovery(&mut users, &mut view_client);
+                mc_fog_test_infra::test_polling_recovery_from_block(
+                    &mut users,
+                    &mut view_client,
+                    phase_start_block,
+                );
             }
         }
 
@@ -40,6 +40,9 @@ pub enum Error {
 
     /// Prost decode error
     ProstDecode,
+
+    /// Key image store responses do not match the query
+    KeyImageStoreResponseMismatch,
 }
 
 /// An error when something goes wrong with adding a record
@@ -15,7 +15,10 @@ pub use crate::{
 };
 use alloc::vec::Vec;
 use core::result::Result as StdResult;
-use mc_attest_enclave_api::{ClientAuthRequest, ClientAuthResponse, ClientSession, EnclaveMessage};
+use mc_attest_enclave_api::{
+    ClientAuthRequest, ClientAuthResponse, ClientSession, EnclaveMessage, PeerAuthRequest,
+    PeerAuthResponse, PeerSession,
+};
 use mc_common::ResponderId;
 use mc_crypto_keys::X25519Public;
 pub use mc_fog_types::ledger::{
@@ -98,6 +101,54 @@ pub trait LedgerEnclave: ReportableEnclave {
 
     /// Add a key image data to the oram Using thrm -rf targete key image
     fn add_key_image_data(&self, records: Vec<KeyImageData>) -> Result<()>;
+
+    // SHARDING
+    //
+    // A router enclave holds no key images. It decrypts client key image
+    // checks, forwards them to key image store enclaves over attested peer
+    // sessions, and merges the KeyImageResult's they return.
+
+    /// Begin an attested connection to the key image store with the given
+    /// responder id
+    fn key_image_store_init(&self, key_image_store_id: ResponderId) -> Result<PeerAuthRequest>;
+
+    /// Complete an attested connection to the key image store with the given
+    /// responder id
+    fn key_image_store_connect(
+        &self,
+        key_image_store_id: ResponderId,
+        key_image_store_auth_response: PeerAuthResponse,
+    ) -> Result<PeerSession>;
+
+    /// Accept an attested connection from a router
+    fn router_accept(&self, req: PeerAuthRequest) -> Result<(PeerAuthResponse, PeerSession)>;
+
+    /// Decrypt a client's encrypted CheckKeyImagesRequest and re-encrypt it
+    /// for each of the given key image store sessions. The results are in the
+    /// same order as the sessions.
+    fn create_key_image_store_queries(
+        &self,
+        client_query: EnclaveMessage<ClientSession>,
+        key_image_store_sessions: Vec<PeerSession>,
+    ) -> Result<Vec<EnclaveMessage<PeerSession>>>;
+
+    /// Service a router's encrypted CheckKeyImagesRequest, returning an
+    /// encrypted CheckKeyImagesResponse which only holds this store's
+    /// KeyImageResult's
+    fn check_key_image_store(
+        &self,
+        msg: EnclaveMessage<PeerSession>,
+    ) -> Result<EnclaveMessage<PeerSession>>;
+
+    /// Merge the encrypted responses of the key image stores to a query
+    /// created by create_key_image_store_queries into a
+    /// CheckKeyImagesResponse encrypted for the client
+    fn collate_key_image_store_responses(
+        &self,
+        client_session: ClientSession,
+        untrusted_key_image_query_response: UntrustedKeyImageQueryResponse,
+        key_image_store_responses: Vec<EnclaveMessage<PeerSession>>,
+    ) -> Result<Vec<u8>>;
 }
 
 /// Helper trait which reduces boiler-plate in untrusted side
@@ -4,7 +4,10 @@
 use crate::UntrustedKeyImageQueryResponse;
 use alloc::vec::Vec;
 use mc_attest_core::{Quote, Report, TargetInfo, VerificationReport};
-use mc_attest_enclave_api::{ClientAuthRequest, ClientSession, EnclaveMessage};
+use mc_attest_enclave_api::{
+    ClientAuthRequest, ClientSession, EnclaveMessage, PeerAuthRequest, PeerAuthResponse,
+    PeerSession,
+};
 use mc_common::ResponderId;
 use mc_fog_types::ledger::GetOutputsResponse;
 use mc_transaction_core::ring_signature::KeyImage;
@@ -101,4 +104,40 @@ pub enum EnclaveCall {
     ///
     ///  Add key image data to the ORAM.
     AddKeyImageData(Vec<KeyImageData>),
+
+    /// The [LedgerEnclave::key_image_store_init()] method.
+    ///
+    /// Begin an attested connection to a key image store, as a router.
+    KeyImageStoreInit(ResponderId),
+
+    /// The [LedgerEnclave::key_image_store_connect()] method.
+    ///
+    /// Complete an attested connection to a key image store, as a router.
+    KeyImageStoreConnect(ResponderId, PeerAuthResponse),
+
+    /// The [LedgerEnclave::router_accept()] method.
+    ///
+    /// Accept an attested connection from a router, as a key image store.
+    RouterAccept(PeerAuthRequest),
+
+    /// The [LedgerEnclave::create_key_image_store_queries()] method.
+    ///
+    /// Decrypt a client's key image check and encrypt it for each of the given
+    /// key image store sessions, as a router.
+    CreateKeyImageStoreQueries(EnclaveMessage<ClientSession>, Vec<PeerSession>),
+
+    /// The [LedgerEnclave::check_key_image_store()] method.
+    ///
+    /// Check key images from a router against this store's ORAM.
+    CheckKeyImageStore(EnclaveMessage<PeerSession>),
+
+    /// The [LedgerEnclave::collate_key_image_store_responses()] method.
+    ///
+    /// Merge the responses of the key image stores into a client's
+    /// CheckKeyImagesResponse, as a router.
+    CollateKeyImageStoreResponses(
+        ClientSession,
+        UntrustedKeyImageQueryResponse,
+        Vec<EnclaveMessage<PeerSession>>,
+    ),
 }
@@ -0,0 +1,114 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! Merging of the KeyImageResult's returned by the key image stores of a
+//! router.
+//!
+//! Each key image store holds the key images of a distinct block range, so at
+//! most one of them finds a given key image. The merge is done without
+//! branching on the result codes, so that the host does not learn which store
+//! holds the key image.
+
+use aligned_cmov::{subtle::ConstantTimeEq, CMov};
+use alloc::vec::Vec;
+use mc_fog_ledger_enclave_api::Error;
+use mc_fog_types::ledger::{KeyImageResult, KeyImageResultCode};
+
+/// Merge the KeyImageResult's of several key image stores, which were queried
+/// for the same key images in the same order.
+pub fn collate_key_image_results(
+    key_image_store_results: Vec<Vec<KeyImageResult>>,
+) -> Result<Vec<KeyImageResult>, Error> {
+    let mut key_image_store_results = key_image_store_results.into_iter();
+    let mut collated = match key_image_store_results.next() {
+        Some(results) => results,
+        None => return Ok(Vec::new()),
+    };
+
+    for results in key_image_store_results {
+        if results.len() != collated.len() {
+            return Err(Error::KeyImageStoreResponseMismatch);
+        }
+
+        for (collated_result, result) in collated.iter_mut().zip(results) {
+            if collated_result.key_image != result.key_image {
+                return Err(Error::KeyImageStoreResponseMismatch);
+            }
+            merge_result(collated_result, &result);
+        }
+    }
+
+    Ok(collated)
+}
+
+/// Replace `collated_result` with `result` if the key image was spent in the
+/// block range of `result`'s store.
+fn merge_result(collated_result: &mut KeyImageResult, result: &KeyImageResult) {
+    let spent = result
+        .key_image_result_code
+        .ct_eq(&(KeyImageResultCode::Spent as u32));
+
+    collated_result.spent_at.cmov(spent, &result.spent_at);
+    collated_result.timestamp.cmov(spent, &result.timestamp);
+    collated_result
+        .timestamp_result_code
+        .cmov(spent, &result.timestamp_result_code);
+    collated_result
+        .key_image_result_code
+        .cmov(spent, &result.key_image_result_code);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+    use mc_transaction_core::ring_signature::KeyImage;
+
+    fn key_image_result(key_image: u64, code: KeyImageResultCode, spent_at: u64) -> KeyImageResult {
+        KeyImageResult {
+            key_image: KeyImage::from(key_image),
+            spent_at,
+            timestamp: spent_at * 10,
+            timestamp_result_code: 1,
+            key_image_result_code: code as u32,
+        }
+    }
+
+    #[test]
+    fn collate_picks_spent_results() {
+        let store_a = vec![
+            key_image_result(1, KeyImageResultCode::Spent, 5),
+            key_image_result(2, KeyImageResultCode::NotSpent, u64::MAX),
+            key_image_result(3, KeyImageResultCode::NotSpent, u64::MAX),
+        ];
+        let store_b = vec![
+            key_image_result(1, KeyImageResultCode::NotSpent, u64::MAX),
+            key_image_result(2, KeyImageResultCode::NotSpent, u64::MAX),
+            key_image_result(3, KeyImageResultCode::Spent, 150),
+        ];
+
+        let collated = collate_key_image_results(vec![store_a.clone(), store_b]).unwrap();
+        assert_eq!(
+            collated,
+            vec![
+                store_a[0].clone(),
+                key_image_result(2, KeyImageResultCode::NotSpent, u64::MAX),
+                key_image_result(3, KeyImageResultCode::Spent, 150),
+            ]
+        );
+    }
+
+    #[test]
+    fn collate_rejects_mismatched_results() {
+        let store_a = vec![key_image_result(1, KeyImageResultCode::NotSpent, u64::MAX)];
+        let store_b = vec![key_image_result(2, KeyImageResultCode::NotSpent, u64::MAX)];
+
+        assert!(matches!(
+            collate_key_image_results(vec![store_a.clone(), store_b]),
+            Err(Error::KeyImageStoreResponseMismatch)
+        ));
+        assert!(matches!(
+            collate_key_image_results(vec![store_a, vec![]]),
+            Err(Error::KeyImageStoreResponseMismatch)
+        ));
+    }
+}
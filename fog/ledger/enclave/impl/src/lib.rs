@@ -12,11 +12,16 @@
 #![deny(missing_docs)]
 extern crate alloc;
 
+mod collation;
 mod key_image_store;
 use alloc::vec::Vec;
+use collation::collate_key_image_results;
 use key_image_store::{KeyImageStore, StorageDataSize, StorageMetaSize};
 use mc_attest_core::{IasNonce, Quote, QuoteNonce, Report, TargetInfo, VerificationReport};
-use mc_attest_enclave_api::{ClientAuthRequest, ClientAuthResponse, ClientSession, EnclaveMessage};
+use mc_attest_enclave_api::{
+    ClientAuthRequest, ClientAuthResponse, ClientSession, EnclaveMessage, PeerAuthRequest,
+    PeerAuthResponse, PeerSession,
+};
 use mc_common::{
     logger::{log, Logger},
     ResponderId,
@@ -28,6 +33,7 @@ use mc_fog_ledger_enclave_api::{
 };
 use mc_fog_types::ledger::{
     CheckKeyImagesRequest, CheckKeyImagesResponse, GetOutputsRequest, GetOutputsResponse,
+    KeyImageQuery, KeyImageResult,
 };
 use mc_oblivious_traits::ORAMStorageCreator;
 use mc_sgx_compat::sync::Mutex;
@@ -61,6 +67,41 @@ where
             logger,
         }
     }
+
+    /// Decode an encrypted CheckKeyImagesRequest
+    fn decode_check_key_images_request(&self, plaintext: &[u8]) -> Result<CheckKeyImagesRequest> {
+        mc_util_serial::decode(plaintext).map_err(|e| {
+            log::error!(self.logger, "Could not decode user request: {}", e);
+            Error::ProstDecode
+        })
+    }
+
+    /// Look up the given key images in the ORAM
+    fn find_key_images(&self, queries: &[KeyImageQuery]) -> Result<Vec<KeyImageResult>> {
+        let mut lk = self.key_image_store.lock()?;
+        let store = lk.as_mut().ok_or(Error::EnclaveNotInitialized)?;
+
+        Ok(queries
+            .iter() //  get the key images used to find the key image data using the oram
+            .map(|key| store.find_record(&key.key_image))
+            .collect())
+    }
+
+    /// Build the response to a client key image check, out of the untrusted
+    /// part of the response and the KeyImageResult's
+    fn build_check_key_images_response(
+        untrusted_key_image_query_response: UntrustedKeyImageQueryResponse,
+        results: Vec<KeyImageResult>,
+    ) -> CheckKeyImagesResponse {
+        CheckKeyImagesResponse {
+            num_blocks: untrusted_key_image_query_response.highest_processed_block_count,
+            results,
+            global_txo_count: untrusted_key_image_query_response
+                .last_known_block_cumulative_txo_count,
+            latest_block_version: untrusted_key_image_query_response.latest_block_version,
+            max_block_version: untrusted_key_image_query_response.max_block_version,
+        }
+    }
 }
 
 /// Implementation of the reportable enclave for sgxledger enclave
@@ -92,7 +133,9 @@ where
     OSC: ORAMStorageCreator<StorageDataSize, StorageMetaSize>,
 {
     fn enclave_init(&self, self_id: &ResponderId, desired_capacity: u64) -> Result<()> {
-        self.ake.init(Default::default(), self_id.clone())?;
+        // The client responder id doubles as the peer responder id, which routers
+        // use when connecting to this enclave as a key image store.
+        self.ake.init(self_id.clone(), self_id.clone())?;
         let mut lk = self.key_image_store.lock()?;
 
         *lk = Some(KeyImageStore::new(desired_capacity, self.logger.clone()));
@@ -145,31 +188,11 @@ where
         let channel_id = msg.channel_id.clone(); //client session does not implement copy trait so clone
         let user_plaintext = self.ake.client_decrypt(msg)?;
 
-        let req: CheckKeyImagesRequest = mc_util_serial::decode(&user_plaintext).map_err(|e| {
-            log::error!(self.logger, "Could not decode user request: {}", e);
-            Error::ProstDecode
-        })?;
-
-        let mut resp = CheckKeyImagesResponse {
-            num_blocks: untrusted_key_image_query_response.highest_processed_block_count,
-            results: Default::default(),
-            global_txo_count: untrusted_key_image_query_response
-                .last_known_block_cumulative_txo_count,
-            latest_block_version: untrusted_key_image_query_response.latest_block_version,
-            max_block_version: untrusted_key_image_query_response.max_block_version,
-        };
-
-        // Do the scope lock of keyimagetore
-        {
-            let mut lk = self.key_image_store.lock()?;
-            let store = lk.as_mut().ok_or(Error::EnclaveNotInitialized)?;
+        let req = self.decode_check_key_images_request(&user_plaintext)?;
 
-            resp.results = req
-                .queries
-                .iter() //  get the key images used to find the key image data using the oram
-                .map(|key| store.find_record(&key.key_image))
-                .collect();
-        }
+        let results = self.find_key_images(&req.queries)?;
+        let resp =
+            Self::build_check_key_images_response(untrusted_key_image_query_response, results);
 
         let response_plaintext_bytes = mc_util_serial::encode(&resp);
 
@@ -191,6 +214,100 @@ where
 
         Ok(())
     }
+
+    // Sharding
+
+    fn key_image_store_init(&self, key_image_store_id: ResponderId) -> Result<PeerAuthRequest> {
+        Ok(self.ake.peer_init(&key_image_store_id)?)
+    }
+
+    fn key_image_store_connect(
+        &self,
+        key_image_store_id: ResponderId,
+        key_image_store_auth_response: PeerAuthResponse,
+    ) -> Result<PeerSession> {
+        let (peer_session, _verification_report) = self
+            .ake
+            .peer_connect(&key_image_store_id, key_image_store_auth_response)?;
+        Ok(peer_session)
+    }
+
+    fn router_accept(&self, req: PeerAuthRequest) -> Result<(PeerAuthResponse, PeerSession)> {
+        Ok(self.ake.peer_accept(req)?)
+    }
+
+    fn create_key_image_store_queries(
+        &self,
+        client_query: EnclaveMessage<ClientSession>,
+        key_image_store_sessions: Vec<PeerSession>,
+    ) -> Result<Vec<EnclaveMessage<PeerSession>>> {
+        // The client session is used as aad, and echoed back by the key image
+        // stores. This prevents the host from collating key image store responses
+        // into a response for another client.
+        let aad: Vec<u8> = client_query.channel_id.clone().into();
+        let user_plaintext = self.ake.client_decrypt(client_query)?;
+
+        // Make sure the request is valid before forwarding it.
+        self.decode_check_key_images_request(&user_plaintext)?;
+
+        key_image_store_sessions
+            .iter()
+            .map(|session| Ok(self.ake.peer_encrypt(session, &aad, &user_plaintext)?))
+            .collect()
+    }
+
+    fn check_key_image_store(
+        &self,
+        msg: EnclaveMessage<PeerSession>,
+    ) -> Result<EnclaveMessage<PeerSession>> {
+        let peer_session = msg.channel_id.clone();
+        let aad = msg.aad.clone();
+        let router_plaintext = self.ake.peer_decrypt(msg)?;
+
+        let req = self.decode_check_key_images_request(&router_plaintext)?;
+
+        let resp = CheckKeyImagesResponse {
+            results: self.find_key_images(&req.queries)?,
+            ..Default::default()
+        };
+
+        let response_plaintext_bytes = mc_util_serial::encode(&resp);
+        Ok(self
+            .ake
+            .peer_encrypt(&peer_session, &aad, &response_plaintext_bytes)?)
+    }
+
+    fn collate_key_image_store_responses(
+        &self,
+        client_session: ClientSession,
+        untrusted_key_image_query_response: UntrustedKeyImageQueryResponse,
+        key_image_store_responses: Vec<EnclaveMessage<PeerSession>>,
+    ) -> Result<Vec<u8>> {
+        let key_image_store_results = key_image_store_responses
+            .into_iter()
+            .map(|msg| {
+                if msg.aad != client_session.as_ref() {
+                    return Err(Error::KeyImageStoreResponseMismatch);
+                }
+                let plaintext = self.ake.peer_decrypt(msg)?;
+                let resp: CheckKeyImagesResponse =
+                    mc_util_serial::decode(&plaintext).map_err(|_| Error::ProstDecode)?;
+                Ok(resp.results)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let results = collate_key_image_results(key_image_store_results)?;
+        let resp =
+            Self::build_check_key_images_response(untrusted_key_image_query_response, results);
+
+        let response_plaintext_bytes = mc_util_serial::encode(&resp);
+
+        let response = self
+            .ake
+            .client_encrypt(&client_session, &[], &response_plaintext_bytes)?;
+
+        Ok(response.data)
+    }
 }
 
 #[cfg(test)]
@@ -14,7 +14,10 @@ pub use mc_fog_ledger_enclave_api::{
 use mc_attest_core::{
     IasNonce, Quote, QuoteNonce, Report, SgxError, TargetInfo, VerificationReport,
 };
-use mc_attest_enclave_api::{ClientAuthRequest, ClientAuthResponse, ClientSession, EnclaveMessage};
+use mc_attest_enclave_api::{
+    ClientAuthRequest, ClientAuthResponse, ClientSession, EnclaveMessage, PeerAuthRequest,
+    PeerAuthResponse, PeerSession,
+};
 use mc_attest_verifier::DEBUG_ENCLAVE;
 use mc_common::{logger::Logger, ResponderId};
 use mc_crypto_keys::X25519Public;
@@ -187,6 +190,68 @@ impl LedgerEnclave for LedgerSgxEnclave {
         let outbuf = self.enclave_call(&inbuf)?;
         mc_util_serial::deserialize(&outbuf[..])?
     }
+
+    fn key_image_store_init(&self, key_image_store_id: ResponderId) -> Result<PeerAuthRequest> {
+        let inbuf = mc_util_serial::serialize(&EnclaveCall::KeyImageStoreInit(key_image_store_id))?;
+        let outbuf = self.enclave_call(&inbuf)?;
+        mc_util_serial::deserialize(&outbuf[..])?
+    }
+
+    fn key_image_store_connect(
+        &self,
+        key_image_store_id: ResponderId,
+        key_image_store_auth_response: PeerAuthResponse,
+    ) -> Result<PeerSession> {
+        let inbuf = mc_util_serial::serialize(&EnclaveCall::KeyImageStoreConnect(
+            key_image_store_id,
+            key_image_store_auth_response,
+        ))?;
+        let outbuf = self.enclave_call(&inbuf)?;
+        mc_util_serial::deserialize(&outbuf[..])?
+    }
+
+    fn router_accept(&self, req: PeerAuthRequest) -> Result<(PeerAuthResponse, PeerSession)> {
+        let inbuf = mc_util_serial::serialize(&EnclaveCall::RouterAccept(req))?;
+        let outbuf = self.enclave_call(&inbuf)?;
+        mc_util_serial::deserialize(&outbuf[..])?
+    }
+
+    fn create_key_image_store_queries(
+        &self,
+        client_query: EnclaveMessage<ClientSession>,
+        key_image_store_sessions: Vec<PeerSession>,
+    ) -> Result<Vec<EnclaveMessage<PeerSession>>> {
+        let inbuf = mc_util_serial::serialize(&EnclaveCall::CreateKeyImageStoreQueries(
+            client_query,
+            key_image_store_sessions,
+        ))?;
+        let outbuf = self.enclave_call(&inbuf)?;
+        mc_util_serial::deserialize(&outbuf[..])?
+    }
+
+    fn check_key_image_store(
+        &self,
+        msg: EnclaveMessage<PeerSession>,
+    ) -> Result<EnclaveMessage<PeerSession>> {
+        let inbuf = mc_util_serial::serialize(&EnclaveCall::CheckKeyImageStore(msg))?;
+        let outbuf = self.enclave_call(&inbuf)?;
+        mc_util_serial::deserialize(&outbuf[..])?
+    }
+
+    fn collate_key_image_store_responses(
+        &self,
+        client_session: ClientSession,
+        untrusted_key_image_query_response: UntrustedKeyImageQueryResponse,
+        key_image_store_responses: Vec<EnclaveMessage<PeerSession>>,
+    ) -> Result<Vec<u8>> {
+        let inbuf = mc_util_serial::serialize(&EnclaveCall::CollateKeyImageStoreResponses(
+            client_session,
+            untrusted_key_image_query_response,
+            key_image_store_responses,
+        ))?;
+        let outbuf = self.enclave_call(&inbuf)?;
+        mc_util_serial::deserialize(&outbuf[..])?
+    }
 }
 
 extern "C" {
@@ -61,6 +61,29 @@ pub fn ecall_dispatcher(inbuf: &[u8]) -> Result<Vec<u8>, sgx_status_t> {
         }
         // Add Key Image Data
         EnclaveCall::AddKeyImageData(records) => serialize(&ENCLAVE.add_key_image_data(records)),
+        // Sharding
+        EnclaveCall::KeyImageStoreInit(key_image_store_id) => {
+            serialize(&ENCLAVE.key_image_store_init(key_image_store_id))
+        }
+        EnclaveCall::KeyImageStoreConnect(key_image_store_id, auth_response) => {
+            serialize(&ENCLAVE.key_image_store_connect(key_image_store_id, auth_response))
+        }
+        EnclaveCall::RouterAccept(auth_request) => serialize(&ENCLAVE.router_accept(auth_request)),
+        EnclaveCall::CreateKeyImageStoreQueries(client_query, key_image_store_sessions) => {
+            serialize(
+                &ENCLAVE.create_key_image_store_queries(client_query, key_image_store_sessions),
+            )
+        }
+        EnclaveCall::CheckKeyImageStore(msg) => serialize(&ENCLAVE.check_key_image_store(msg)),
+        EnclaveCall::CollateKeyImageStoreResponses(
+            client_session,
+            untrusted_keyimagequery_response,
+            key_image_store_responses,
+        ) => serialize(&ENCLAVE.collate_key_image_store_responses(
+            client_session,
+            untrusted_keyimagequery_response,
+            key_image_store_responses,
+        )),
     }
     .or(Err(sgx_status_t::SGX_ERROR_UNEXPECTED))
 }
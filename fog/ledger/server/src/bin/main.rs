@@ -23,6 +23,11 @@ fn main() {
 
     let (logger, _global_logger_guard) = create_app_logger(o!());
     let config = LedgerServerConfig::parse();
+    assert!(
+        config.block_range().is_valid(),
+        "Invalid block range {}",
+        config.block_range()
+    );
 
     let _tracer = mc_util_telemetry::setup_default_tracer_with_tags(
         env!("CARGO_PKG_NAME"),
@@ -0,0 +1,85 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+#![deny(missing_docs)]
+
+//! Ledger Router target
+
+use grpcio::{RpcStatus, RpcStatusCode};
+use mc_attest_net::{Client, RaClient};
+use mc_common::{
+    logger::{create_app_logger, log, o},
+    time::SystemTimeProvider,
+};
+use mc_fog_ledger_enclave::{LedgerSgxEnclave, ENCLAVE_FILE};
+use mc_fog_ledger_server::{LedgerRouter, LedgerRouterConfig};
+use mc_util_cli::ParserWithBuildInfo;
+use mc_util_grpc::AdminServer;
+use std::{env, sync::Arc};
+
+/// The router does not load any key images, so its enclave only needs a
+/// token ORAM.
+const ROUTER_OMAP_CAPACITY: u64 = 1024;
+
+fn main() {
+    mc_common::setup_panic_handler();
+    let _sentry_guard = mc_common::sentry::init();
+
+    let (logger, _global_logger_guard) = create_app_logger(o!());
+    let config = LedgerRouterConfig::parse();
+
+    let _tracer = mc_util_telemetry::setup_default_tracer_with_tags(
+        env!("CARGO_PKG_NAME"),
+        &[(
+            "client_responser_id",
+            config.client_responder_id.to_string(),
+        )],
+    )
+    .expect("Failed setting telemetry tracer");
+
+    let enclave_path = env::current_exe()
+        .expect("Could not get the path of our executable")
+        .with_file_name(ENCLAVE_FILE);
+    log::info!(
+        logger,
+        "enclave path {}, responder ID {}",
+        enclave_path.to_str().expect("Could not get enclave path"),
+        &config.client_responder_id
+    );
+    let enclave = LedgerSgxEnclave::new(
+        enclave_path,
+        &config.client_responder_id,
+        ROUTER_OMAP_CAPACITY,
+        logger.clone(),
+    );
+
+    let ias_client = Client::new(&config.ias_api_key).expect("Could not create IAS client");
+    let mut router = LedgerRouter::new(
+        config.clone(),
+        enclave,
+        ias_client,
+        SystemTimeProvider::default(),
+        logger.clone(),
+    );
+
+    router.start().expect("Router failed to start");
+
+    let config2 = config.clone();
+    let get_config_json = Arc::new(move || {
+        serde_json::to_string(&config2)
+            .map_err(|err| RpcStatus::with_message(RpcStatusCode::INTERNAL, format!("{:?}", err)))
+    });
+    let _admin_server = config.admin_listen_uri.as_ref().map(|admin_listen_uri| {
+        AdminServer::start(
+            None,
+            admin_listen_uri,
+            "Fog Ledger Router".to_owned(),
+            config.client_responder_id.to_string(),
+            Some(get_config_json),
+            logger,
+        )
+        .expect("Failed starting admin server")
+    });
+
+    loop {
+        std::thread::sleep(std::time::Duration::from_millis(1000));
+    }
+}
@@ -1,13 +1,14 @@
 // Copyright (c) 2018-2022 The MobileCoin Foundation
 
-//! Configuration parameters for the ledger server
+//! Configuration parameters for the ledger server and router
 
 #![deny(missing_docs)]
 
 use clap::Parser;
 use mc_attest_core::ProviderId;
 use mc_common::ResponderId;
-use mc_fog_uri::FogLedgerUri;
+use mc_fog_types::common::BlockRange;
+use mc_fog_uri::{FogLedgerUri, KeyImageStoreUri};
 use mc_util_parse::parse_duration_in_seconds;
 use mc_util_uri::AdminUri;
 use serde::Serialize;
@@ -71,4 +72,89 @@ pub struct LedgerServerConfig {
     /// to disk by linux kernel.
     #[clap(long, default_value = "1048576", env = "MC_OMAP_CAPACITY")]
     pub omap_capacity: u64,
+
+    /// Optional gRPC listening URI for fog ledger routers. When set, this node
+    /// serves as a key image store shard for the routers, and stops serving
+    /// key image checks to clients. Merkle proofs, blocks and untrusted tx
+    /// outs, which do not depend on the block range, are still served on
+    /// the client listening URI.
+    ///
+    /// The responder id routers use for this store must be this node's
+    /// client responder id.
+    #[clap(long, env = "MC_STORE_LISTEN_URI")]
+    pub store_listen_uri: Option<KeyImageStoreUri>,
+
+    /// The first block of the range of blocks this node loads key images for.
+    #[clap(long, default_value = "0", env = "MC_BLOCK_RANGE_START")]
+    pub block_range_start: u64,
+
+    /// One past the last block of the range of blocks this node loads key
+    /// images for. Defaults to an unbounded range.
+    #[clap(long, env = "MC_BLOCK_RANGE_END")]
+    pub block_range_end: Option<u64>,
+}
+
+impl LedgerServerConfig {
+    /// The range of blocks this node loads key images for.
+    pub fn block_range(&self) -> BlockRange {
+        BlockRange::new(
+            self.block_range_start,
+            self.block_range_end.unwrap_or(u64::MAX),
+        )
+    }
+}
+
+/// Configuration parameters for the ledger router, which serves key image
+/// checks out of key image store shards
+#[derive(Clone, Parser, Serialize)]
+#[clap(version)]
+pub struct LedgerRouterConfig {
+    /// gRPC listening URI for client requests.
+    #[clap(long, env = "MC_CLIENT_LISTEN_URI")]
+    pub client_listen_uri: FogLedgerUri,
+
+    /// Client Responder id.
+    ///
+    /// This ID needs to match the host:port clients use in their URI when
+    /// referencing this node.
+    #[clap(long, env = "MC_CLIENT_RESPONDER_ID")]
+    pub client_responder_id: ResponderId,
+
+    /// IAS Api Key.
+    #[clap(long, env = "MC_IAS_API_KEY")]
+    pub ias_api_key: String,
+
+    /// IAS Service Provider ID.
+    #[clap(long, env = "MC_IAS_SPID")]
+    pub ias_spid: ProviderId,
+
+    /// Optional admin listening URI.
+    #[clap(long, env = "MC_ADMIN_LISTEN_URI")]
+    pub admin_listen_uri: Option<AdminUri>,
+
+    /// Enables authenticating client requests using Authorization tokens using
+    /// the provided hex-encoded 32 bytes shared secret.
+    #[clap(long, parse(try_from_str = hex::FromHex::from_hex), env = "MC_CLIENT_AUTH_TOKEN_SECRET")]
+    pub client_auth_token_secret: Option<[u8; 32]>,
+
+    /// Maximal client authentication token lifetime, in seconds (only relevant
+    /// when --client-auth-token-secret is used. Defaults to 86400 - 24
+    /// hours).
+    #[clap(long, default_value = "86400", parse(try_from_str = parse_duration_in_seconds), env = "MC_CLIENT_AUTH_TOKEN_MAX_LIFETIME")]
+    pub client_auth_token_max_lifetime: Duration,
+
+    /// The key image store shards to fan key image checks out to. Together,
+    /// their block ranges should cover the whole chain without gaps.
+    ///
+    /// The responder id of each uri must be the client responder id of the
+    /// shard, e.g.
+    /// insecure-key-image-store://10.0.0.1:3228/?responder-id=shard1.fog.com:
+    /// 443
+    #[clap(
+        long = "shard-uri",
+        required = true,
+        use_value_delimiter = true,
+        env = "MC_SHARD_URIS"
+    )]
+    pub shard_uris: Vec<KeyImageStoreUri>,
 }
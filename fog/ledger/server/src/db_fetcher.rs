@@ -2,7 +2,8 @@
 
 //! A background thread, in the server side, that continuously checks the
 //! LedgerDB for new blocks, then gets all the key images associated to those
-//! blocks and adds them to the enclave.
+//! blocks and adds them to the enclave. Only the blocks of the configured
+//! block range are loaded.
 use crate::{counters, server::DbPollSharedState};
 use mc_common::{
     logger::{log, Logger},
@@ -10,6 +11,7 @@ use mc_common::{
 };
 use mc_fog_ledger_enclave::LedgerEnclaveProxy;
 use mc_fog_ledger_enclave_api::KeyImageData;
+use mc_fog_types::common::BlockRange;
 use mc_ledger_db::{self, Error as LedgerError, Ledger};
 use mc_util_grpc::ReadinessIndicator;
 use mc_util_telemetry::{
@@ -44,6 +46,7 @@ impl DbFetcher {
         enclave: E,
         watcher: WatcherDB,
         db_poll_shared_state: Arc<Mutex<DbPollSharedState>>,
+        block_range: BlockRange,
         readiness_indicator: ReadinessIndicator,
         logger: Logger,
     ) -> Self {
@@ -57,7 +60,7 @@ impl DbFetcher {
                     DbFetcherThread::start(
                         db,
                         thread_stop_requested,
-                        0,
+                        block_range,
                         enclave,
                         watcher,
                         thread_shared_state,
@@ -94,6 +97,7 @@ impl Drop for DbFetcher {
 struct DbFetcherThread<DB: Ledger, E: LedgerEnclaveProxy + Clone + Send + Sync + 'static> {
     db: DB,
     stop_requested: Arc<AtomicBool>,
+    block_range: BlockRange,
     next_block_index: u64,
     enclave: E,
     watcher: WatcherDB,
@@ -111,16 +115,18 @@ impl<DB: Ledger, E: LedgerEnclaveProxy + Clone + Send + Sync + 'static> DbFetche
     pub fn start(
         db: DB,
         stop_requested: Arc<AtomicBool>,
-        next_block_index: u64,
+        block_range: BlockRange,
         enclave: E,
         watcher: WatcherDB,
         db_poll_shared_state: Arc<Mutex<DbPollSharedState>>,
         readiness_indicator: ReadinessIndicator,
         logger: Logger,
     ) {
+        let next_block_index = block_range.start_block;
         let thread = Self {
             db,
             stop_requested,
+            block_range,
             next_block_index,
             enclave,
             watcher,
@@ -132,8 +138,11 @@ impl<DB: Ledger, E: LedgerEnclaveProxy + Clone + Send + Sync + 'static> DbFetche
     }
 
     fn run(mut self) {
-        log::info!(self.logger, "Db fetcher thread started.");
-        self.next_block_index = 0;
+        log::info!(
+            self.logger,
+            "Db fetcher thread started, loading blocks {}.",
+            self.block_range
+        );
         loop {
             if self.stop_requested.load(Ordering::SeqCst) {
                 log::info!(self.logger, "Db fetcher thread stop requested.");
@@ -176,6 +185,11 @@ impl<DB: Ledger, E: LedgerEnclaveProxy + Clone + Send + Sync + 'static> DbFetche
     /// are aware of and tracking.
     /// Returns true if we might have more block data to load.
     fn load_block_data(&mut self) -> bool {
+        // Blocks past the end of our range are loaded by other key image stores.
+        if !self.block_range.contains(self.next_block_index) {
+            return false;
+        }
+
         // Default to true: if there is an error, we may have more work, we don't know
         let mut may_have_more_work = true;
         let watcher_timeout: Duration = Duration::from_millis(5000);
@@ -0,0 +1,301 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! Serves fog ledger key image checks by fanning them out to the key image
+//! store shards, and merging the results.
+
+use crate::{
+    key_image_service::enclave_err_to_rpc_status,
+    key_image_store_connection::KeyImageStoreConnection,
+};
+use futures::{executor::block_on, future::join_all};
+use grpcio::{RpcContext, RpcStatus, UnarySink};
+use mc_attest_api::attest::{self, AuthMessage, Message};
+use mc_attest_enclave_api::ClientSession;
+use mc_blockchain_types::MAX_BLOCK_VERSION;
+use mc_common::logger::{log, Logger};
+use mc_fog_api::{ledger::KeyImageStoreResponse, ledger_grpc::FogKeyImageApi};
+use mc_fog_ledger_enclave::LedgerEnclaveProxy;
+use mc_fog_ledger_enclave_api::UntrustedKeyImageQueryResponse;
+use mc_util_grpc::{
+    rpc_internal_error, rpc_logger, rpc_permissions_error, rpc_unavailable_error, send_result,
+    Authenticator,
+};
+use mc_util_metrics::SVC_COUNTERS;
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone)]
+pub struct KeyImageRouterService<E: LedgerEnclaveProxy> {
+    /// Router enclave
+    enclave: E,
+
+    /// Attested connections to the key image stores.
+    key_image_stores: Arc<Vec<Mutex<KeyImageStoreConnection<E>>>>,
+
+    /// GRPC request authenticator.
+    authenticator: Arc<dyn Authenticator + Send + Sync>,
+
+    /// Slog logger object
+    logger: Logger,
+}
+
+impl<E: LedgerEnclaveProxy> KeyImageRouterService<E> {
+    /// Creates a new key-image-router-service (but does not create sockets
+    /// and start it etc.)
+    pub fn new(
+        enclave: E,
+        key_image_stores: Arc<Vec<Mutex<KeyImageStoreConnection<E>>>>,
+        authenticator: Arc<dyn Authenticator + Send + Sync>,
+        logger: Logger,
+    ) -> Self {
+        Self {
+            enclave,
+            key_image_stores,
+            authenticator,
+            logger,
+        }
+    }
+
+    /// Fan the key image check out to the key image stores, and collate their
+    /// responses
+    fn check_key_images_auth(
+        &mut self,
+        request: attest::Message,
+    ) -> Result<attest::Message, RpcStatus> {
+        log::trace!(self.logger, "Getting encrypted request");
+
+        let client_session = ClientSession::from(request.get_channel_id());
+
+        let key_image_store_responses = self.query_key_image_stores(request)?;
+
+        let key_image_store_state = KeyImageStoreState::merge(&key_image_store_responses);
+        let untrusted_query_response = UntrustedKeyImageQueryResponse {
+            highest_processed_block_count: key_image_store_state.highest_processed_block_count,
+            last_known_block_cumulative_txo_count: key_image_store_state
+                .last_known_block_cumulative_txo_count,
+            latest_block_version: key_image_store_state.latest_block_version,
+            max_block_version: key_image_store_state
+                .latest_block_version
+                .max(*MAX_BLOCK_VERSION),
+        };
+
+        let result_blob = self
+            .enclave
+            .collate_key_image_store_responses(
+                client_session,
+                untrusted_query_response,
+                key_image_store_responses
+                    .into_iter()
+                    .map(|mut response| response.take_query_response().into())
+                    .collect(),
+            )
+            .map_err(|e| enclave_err_to_rpc_status("enclave collate", e, &self.logger))?;
+
+        let mut resp = attest::Message::new();
+        resp.set_data(result_blob);
+        Ok(resp)
+    }
+
+    /// Query all key image stores concurrently.
+    fn query_key_image_stores(
+        &self,
+        request: attest::Message,
+    ) -> Result<Vec<KeyImageStoreResponse>, RpcStatus> {
+        // Attest to any key image store we are not connected to yet. The locks are
+        // only held while attesting, so that queries can be served concurrently.
+        let mut key_image_store_sessions = Vec::with_capacity(self.key_image_stores.len());
+        let mut key_image_store_clients = Vec::with_capacity(self.key_image_stores.len());
+        for key_image_store in self.key_image_stores.iter() {
+            let mut key_image_store = key_image_store.lock().expect("mutex poisoned");
+            let session = key_image_store.attested_session().map_err(|err| {
+                rpc_unavailable_error(
+                    "key_image_store_attest",
+                    format!("{}: {}", key_image_store, err),
+                    &self.logger,
+                )
+            })?;
+            key_image_store_sessions.push(session);
+            key_image_store_clients.push(key_image_store.key_image_store_api_client());
+        }
+
+        let key_image_store_queries = self
+            .enclave
+            .create_key_image_store_queries(request.into(), key_image_store_sessions)
+            .map_err(|e| enclave_err_to_rpc_status("enclave request", e, &self.logger))?;
+
+        let receivers = key_image_store_clients
+            .iter()
+            .zip(key_image_store_queries)
+            .map(|(client, query)| client.check_key_images_async(&query.into()))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|err| rpc_internal_error("key_image_store_query", err, &self.logger))?;
+
+        let results = block_on(join_all(receivers));
+
+        let mut key_image_store_responses = Vec::with_capacity(results.len());
+        for (key_image_store, result) in self.key_image_stores.iter().zip(results) {
+            match result {
+                Ok(response) => key_image_store_responses.push(response),
+                Err(err) => {
+                    // The key image store may have lost our session, e.g. because it
+                    // restarted.
+                    let mut key_image_store = key_image_store.lock().expect("mutex poisoned");
+                    key_image_store.deattest();
+                    return Err(rpc_unavailable_error(
+                        "key_image_store_query",
+                        format!("{}: {}", key_image_store, err),
+                        &self.logger,
+                    ));
+                }
+            }
+        }
+
+        Ok(key_image_store_responses)
+    }
+}
+
+/// The untrusted state of the key image stores, merged into the state of a
+/// single fog ledger node.
+#[derive(Debug, Default, Eq, PartialEq)]
+struct KeyImageStoreState {
+    highest_processed_block_count: u64,
+    last_known_block_cumulative_txo_count: u64,
+    latest_block_version: u32,
+}
+
+impl KeyImageStoreState {
+    /// Merge the state reported by the key image stores.
+    ///
+    /// The highest processed block count is the end of the longest run of
+    /// blocks, starting at the origin block, that the stores have loaded
+    /// without gaps. A store that has processed up to some block count has
+    /// loaded all the blocks from the start of its range up to it.
+    fn merge(key_image_store_responses: &[KeyImageStoreResponse]) -> Self {
+        let mut state = Self::default();
+
+        while let Some(response) = key_image_store_responses
+            .iter()
+            .filter(|response| {
+                response.get_block_range().start_block <= state.highest_processed_block_count
+                    && response.highest_processed_block_count > state.highest_processed_block_count
+            })
+            .max_by_key(|response| response.highest_processed_block_count)
+        {
+            state.highest_processed_block_count = response.highest_processed_block_count;
+        }
+
+        state.last_known_block_cumulative_txo_count = key_image_store_responses
+            .iter()
+            .map(|response| response.last_known_block_cumulative_txo_count)
+            .max()
+            .unwrap_or_default();
+        state.latest_block_version = key_image_store_responses
+            .iter()
+            .map(|response| response.latest_block_version)
+            .max()
+            .unwrap_or_default();
+
+        state
+    }
+}
+
+impl<E: LedgerEnclaveProxy> FogKeyImageApi for KeyImageRouterService<E> {
+    fn check_key_images(&mut self, ctx: RpcContext, request: Message, sink: UnarySink<Message>) {
+        let _timer = SVC_COUNTERS.req(&ctx);
+        mc_common::logger::scoped_global_logger(&rpc_logger(&ctx, &self.logger), |logger| {
+            if let Err(err) = self.authenticator.authenticate_rpc(&ctx) {
+                return send_result(ctx, sink, err.into(), logger);
+            }
+
+            send_result(ctx, sink, self.check_key_images_auth(request), logger)
+        })
+    }
+
+    fn auth(&mut self, ctx: RpcContext, request: AuthMessage, sink: UnarySink<AuthMessage>) {
+        let _timer = SVC_COUNTERS.req(&ctx);
+        mc_common::logger::scoped_global_logger(&rpc_logger(&ctx, &self.logger), |logger| {
+            if let Err(err) = self.authenticator.authenticate_rpc(&ctx) {
+                return send_result(ctx, sink, err.into(), logger);
+            }
+
+            match self.enclave.client_accept(request.into()) {
+                Ok((response, _session_id)) => {
+                    send_result(ctx, sink, Ok(response.into()), logger);
+                }
+                Err(client_error) => {
+                    // This is debug because there's no requirement on the remote party to trigger
+                    // it.
+                    log::debug!(
+                        logger,
+                        "LedgerEnclave::client_accept failed: {}",
+                        client_error
+                    );
+                    send_result(
+                        ctx,
+                        sink,
+                        Err(rpc_permissions_error(
+                            "client_auth",
+                            "Permission denied",
+                            logger,
+                        )),
+                        logger,
+                    );
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mc_fog_types::common::BlockRange;
+
+    fn key_image_store_response(
+        block_range: BlockRange,
+        highest_processed_block_count: u64,
+        latest_block_version: u32,
+    ) -> KeyImageStoreResponse {
+        let mut response = KeyImageStoreResponse::new();
+        response.set_block_range((&block_range).into());
+        response.set_highest_processed_block_count(highest_processed_block_count);
+        response.set_last_known_block_cumulative_txo_count(highest_processed_block_count * 100);
+        response.set_latest_block_version(latest_block_version);
+        response
+    }
+
+    #[test]
+    fn merge_contiguous_key_image_stores() {
+        let responses = vec![
+            key_image_store_response(BlockRange::new(100, 200), 150, 2),
+            key_image_store_response(BlockRange::new(0, 100), 100, 1),
+        ];
+        assert_eq!(
+            KeyImageStoreState::merge(&responses),
+            KeyImageStoreState {
+                highest_processed_block_count: 150,
+                last_known_block_cumulative_txo_count: 15000,
+                latest_block_version: 2,
+            }
+        );
+    }
+
+    #[test]
+    fn merge_stops_at_gaps() {
+        // The first store is still loading its range, so the key images of the
+        // second store cannot be vouched for yet.
+        let responses = vec![
+            key_image_store_response(BlockRange::new(0, 100), 80, 1),
+            key_image_store_response(BlockRange::new(100, 200), 150, 2),
+        ];
+        let state = KeyImageStoreState::merge(&responses);
+        assert_eq!(state.highest_processed_block_count, 80);
+        assert_eq!(state.last_known_block_cumulative_txo_count, 15000);
+
+        // Nothing is processed if no store covers the origin block.
+        let responses = vec![key_image_store_response(BlockRange::new(100, 200), 150, 2)];
+        assert_eq!(
+            KeyImageStoreState::merge(&responses).highest_processed_block_count,
+            0
+        );
+    }
+}
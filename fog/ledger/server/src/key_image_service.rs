@@ -96,25 +96,27 @@ impl<L: Ledger + Clone, E: LedgerEnclaveProxy> KeyImageService<L, E> {
         let result_blob = self
             .enclave
             .check_key_images(request.into(), untrusted_query_response)
-            .map_err(|e| self.enclave_err_to_rpc_status("enclave request", e))?;
+            .map_err(|e| enclave_err_to_rpc_status("enclave request", e, &self.logger))?;
 
         let mut resp = attest::Message::new();
         resp.set_data(result_blob);
         Ok(resp)
     }
+}
 
-    // Helper function that is common
-    fn enclave_err_to_rpc_status(&self, context: &str, src: EnclaveError) -> RpcStatus {
-        // Treat prost-decode error as an invalid arg,
-        // treat attest error as permission denied,
-        // everything else is an internal error
-        match src {
-            EnclaveError::ProstDecode => {
-                rpc_invalid_arg_error(context, "Prost decode failed", &self.logger)
-            }
-            EnclaveError::Attest(err) => rpc_permissions_error(context, err, &self.logger),
-            other => rpc_internal_error(context, format!("{}", &other), &self.logger),
-        }
+/// Convert a ledger enclave error into an RpcStatus.
+pub(crate) fn enclave_err_to_rpc_status(
+    context: &str,
+    src: EnclaveError,
+    logger: &Logger,
+) -> RpcStatus {
+    // Treat prost-decode error as an invalid arg,
+    // treat attest error as permission denied,
+    // everything else is an internal error
+    match src {
+        EnclaveError::ProstDecode => rpc_invalid_arg_error(context, "Prost decode failed", logger),
+        EnclaveError::Attest(err) => rpc_permissions_error(context, err, logger),
+        other => rpc_internal_error(context, format!("{}", &other), logger),
     }
 }
 
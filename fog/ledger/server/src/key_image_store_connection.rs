@@ -0,0 +1,130 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! An attested connection from a fog ledger router to a key image store.
+
+use displaydoc::Display;
+use grpcio::{ChannelBuilder, Environment, Error as GrpcError};
+use mc_attest_api::attest_grpc::AttestedApiClient;
+use mc_attest_enclave_api::PeerSession;
+use mc_common::{
+    logger::{log, o, Logger},
+    ResponderId,
+};
+use mc_fog_api::ledger_grpc::KeyImageStoreApiClient;
+use mc_fog_ledger_enclave::{Error as EnclaveError, LedgerEnclaveProxy};
+use mc_fog_uri::{ConnectionUri, KeyImageStoreUri};
+use mc_util_grpc::ConnectionUriGrpcioChannel;
+use std::{
+    fmt::{Display as FmtDisplay, Formatter, Result as FmtResult},
+    sync::Arc,
+};
+
+/// An error on the attested connection from a router to a key image store
+#[derive(Debug, Display)]
+pub enum KeyImageStoreConnectionError {
+    /// Ledger enclave error: {0}
+    Enclave(EnclaveError),
+    /// GRPC error: {0}
+    Grpc(GrpcError),
+}
+
+impl From<EnclaveError> for KeyImageStoreConnectionError {
+    fn from(src: EnclaveError) -> Self {
+        Self::Enclave(src)
+    }
+}
+
+impl From<GrpcError> for KeyImageStoreConnectionError {
+    fn from(src: GrpcError) -> Self {
+        Self::Grpc(src)
+    }
+}
+
+/// A connection to a key image store, attested by the local (router) enclave.
+pub struct KeyImageStoreConnection<E: LedgerEnclaveProxy> {
+    /// The local enclave.
+    enclave: E,
+
+    /// The responder id of the key image store enclave.
+    key_image_store_id: ResponderId,
+
+    /// The session established with the key image store enclave, if attested.
+    peer_session: Option<PeerSession>,
+
+    /// The key image store's URI.
+    uri: KeyImageStoreUri,
+
+    /// The gRPC client used to attest to the key image store.
+    attested_api_client: AttestedApiClient,
+
+    /// The gRPC client used to query the key image store once attested.
+    key_image_store_api_client: KeyImageStoreApiClient,
+
+    /// Logger.
+    logger: Logger,
+}
+
+impl<E: LedgerEnclaveProxy> KeyImageStoreConnection<E> {
+    /// Create a new, unattested, connection to the key image store at `uri`.
+    pub fn new(enclave: E, uri: KeyImageStoreUri, env: Arc<Environment>, logger: Logger) -> Self {
+        let key_image_store_id = uri.responder_id().unwrap_or_else(|_| {
+            panic!("Could not get responder id from uri {:?}", uri.to_string())
+        });
+
+        let logger = logger.new(o!("mc.fog.key_image_store" => uri.to_string()));
+
+        let ch = ChannelBuilder::default_channel_builder(env).connect_to_uri(&uri, &logger);
+
+        let attested_api_client = AttestedApiClient::new(ch.clone());
+        let key_image_store_api_client = KeyImageStoreApiClient::new(ch);
+
+        Self {
+            enclave,
+            key_image_store_id,
+            peer_session: None,
+            uri,
+            attested_api_client,
+            key_image_store_api_client,
+            logger,
+        }
+    }
+
+    /// Get the session with the key image store enclave, attesting first if
+    /// needed.
+    pub fn attested_session(&mut self) -> Result<PeerSession, KeyImageStoreConnectionError> {
+        if let Some(peer_session) = self.peer_session.as_ref() {
+            return Ok(peer_session.clone());
+        }
+
+        log::debug!(self.logger, "Attesting to key image store");
+        let req = self
+            .enclave
+            .key_image_store_init(self.key_image_store_id.clone())?;
+        let res = self.attested_api_client.auth(&req.into())?;
+        let peer_session = self
+            .enclave
+            .key_image_store_connect(self.key_image_store_id.clone(), res.into())?;
+
+        self.peer_session = Some(peer_session.clone());
+        Ok(peer_session)
+    }
+
+    /// Tear down the session with the key image store enclave, so that the
+    /// next query attests again.
+    pub fn deattest(&mut self) {
+        if self.peer_session.take().is_some() {
+            log::trace!(self.logger, "Tearing down existing attested connection.");
+        }
+    }
+
+    /// The gRPC client used to query the key image store.
+    pub fn key_image_store_api_client(&self) -> KeyImageStoreApiClient {
+        self.key_image_store_api_client.clone()
+    }
+}
+
+impl<E: LedgerEnclaveProxy> FmtDisplay for KeyImageStoreConnection<E> {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "{}", self.uri)
+    }
+}
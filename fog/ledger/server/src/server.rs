@@ -1,12 +1,14 @@
 // Copyright (c) 2018-2022 The MobileCoin Foundation
 
 use crate::{
-    config::LedgerServerConfig, counters, db_fetcher::DbFetcher, BlockService, KeyImageService,
+    config::LedgerServerConfig, counters, db_fetcher::DbFetcher,
+    key_image_store_service::KeyImageStoreService, BlockService, KeyImageService,
     MerkleProofService, UntrustedTxOutService,
 };
 use displaydoc::Display;
 use futures::executor::block_on;
 use grpcio::Error as GrpcError;
+use mc_attest_api::attest_grpc;
 use mc_attest_net::RaClient;
 use mc_common::{
     logger::{log, Logger},
@@ -68,6 +70,7 @@ impl From<GrpcError> for LedgerServerError {
 pub struct LedgerServer<E: LedgerEnclaveProxy, R: RaClient + Send + Sync + 'static> {
     config: LedgerServerConfig,
     server: Option<grpcio::Server>,
+    store_server: Option<grpcio::Server>,
     key_image_service: KeyImageService<LedgerDB, E>,
     merkle_proof_service: MerkleProofService<LedgerDB, E>,
     block_service: BlockService<LedgerDB>,
@@ -132,6 +135,7 @@ impl<E: LedgerEnclaveProxy, R: RaClient + Send + Sync + 'static> LedgerServer<E,
         Self {
             config,
             server: None,
+            store_server: None,
             key_image_service,
             merkle_proof_service,
             block_service,
@@ -161,6 +165,7 @@ impl<E: LedgerEnclaveProxy, R: RaClient + Send + Sync + 'static> LedgerServer<E,
                 self.enclave.clone(),
                 self.key_image_service.get_watcher(),
                 self.key_image_service.get_db_poll_shared_state(),
+                self.config.block_range(),
                 readiness_indicator.clone(),
                 self.logger.clone(),
             ));
@@ -172,8 +177,6 @@ impl<E: LedgerEnclaveProxy, R: RaClient + Send + Sync + 'static> LedgerServer<E,
             );
 
             // Package endpoints into grpc service
-            let key_image_service =
-                ledger_grpc::create_fog_key_image_api(self.key_image_service.clone());
             let merkle_proof_service =
                 ledger_grpc::create_fog_merkle_proof_api(self.merkle_proof_service.clone());
             let block_service = ledger_grpc::create_fog_block_api(self.block_service.clone());
@@ -193,19 +196,58 @@ impl<E: LedgerEnclaveProxy, R: RaClient + Send + Sync + 'static> LedgerServer<E,
                 "Starting Ledger server on {}",
                 self.config.client_listen_uri.addr(),
             );
-            let server_builder = grpcio::ServerBuilder::new(env)
-                .register_service(key_image_service)
+            let mut server_builder = grpcio::ServerBuilder::new(env)
                 .register_service(merkle_proof_service)
                 .register_service(block_service)
                 .register_service(untrusted_tx_out_service)
-                .register_service(health_service)
-                .bind_using_uri(&self.config.client_listen_uri, self.logger.clone());
+                .register_service(health_service);
+            // A key image store shard only holds the key images of its block
+            // range, so clients must check key images through a router.
+            if self.config.store_listen_uri.is_none() {
+                server_builder = server_builder.register_service(
+                    ledger_grpc::create_fog_key_image_api(self.key_image_service.clone()),
+                );
+            }
+            let server_builder =
+                server_builder.bind_using_uri(&self.config.client_listen_uri, self.logger.clone());
 
             let mut server = server_builder.build()?;
             server.start();
 
             self.server = Some(server);
 
+            // When configured, also serve as a key image store for ledger routers.
+            if let Some(store_listen_uri) = self.config.store_listen_uri.as_ref() {
+                let store_env = Arc::new(
+                    grpcio::EnvBuilder::new()
+                        .name_prefix("KeyImageStore-RPC".to_string())
+                        .build(),
+                );
+
+                let key_image_store_service = KeyImageStoreService::new(
+                    self.enclave.clone(),
+                    self.config.block_range(),
+                    self.key_image_service.get_db_poll_shared_state(),
+                    self.logger.clone(),
+                );
+
+                log::info!(
+                    self.logger,
+                    "Starting Key Image Store on {}",
+                    store_listen_uri.addr(),
+                );
+                let mut store_server = grpcio::ServerBuilder::new(store_env)
+                    .register_service(ledger_grpc::create_key_image_store_api(
+                        key_image_store_service.clone(),
+                    ))
+                    .register_service(attest_grpc::create_attested_api(key_image_store_service))
+                    .bind_using_uri(store_listen_uri, self.logger.clone())
+                    .build()?;
+                store_server.start();
+
+                self.store_server = Some(store_server);
+            }
+
             // Success.
             Ok(())
         };
@@ -220,6 +262,10 @@ impl<E: LedgerEnclaveProxy, R: RaClient + Send + Sync + 'static> LedgerServer<E,
             block_on(server.shutdown()).expect("Could not stop grpc server");
         }
 
+        if let Some(ref mut store_server) = self.store_server.take() {
+            block_on(store_server.shutdown()).expect("Could not stop key image store grpc server");
+        }
+
         if let Some(ref mut report_cache_thread) = self.report_cache_thread.take() {
             report_cache_thread
                 .stop()
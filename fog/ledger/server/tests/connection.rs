@@ -128,6 +128,9 @@ fn fog_ledger_merkle_proofs_test(logger: Logger) {
                 client_auth_token_secret: None,
                 client_auth_token_max_lifetime: Default::default(),
                 omap_capacity: OMAP_CAPACITY,
+                store_listen_uri: None,
+                block_range_start: 0,
+                block_range_end: None,
             };
 
             let enclave = LedgerSgxEnclave::new(
@@ -320,6 +323,9 @@ fn fog_ledger_key_images_test(logger: Logger) {
                 client_auth_token_secret: None,
                 client_auth_token_max_lifetime: Default::default(),
                 omap_capacity: OMAP_CAPACITY,
+                store_listen_uri: None,
+                block_range_start: 0,
+                block_range_end: None,
             };
 
             let enclave = LedgerSgxEnclave::new(
@@ -517,6 +523,9 @@ fn fog_ledger_blocks_api_test(logger: Logger) {
             client_auth_token_secret: None,
             client_auth_token_max_lifetime: Default::default(),
             omap_capacity: OMAP_CAPACITY,
+            store_listen_uri: None,
+            block_range_start: 0,
+            block_range_end: None,
         };
 
         let enclave = LedgerSgxEnclave::new(
@@ -678,6 +687,9 @@ fn fog_ledger_untrusted_tx_out_api_test(logger: Logger) {
             client_auth_token_secret: None,
             client_auth_token_max_lifetime: Default::default(),
             omap_capacity: OMAP_CAPACITY,
+            store_listen_uri: None,
+            block_range_start: 0,
+            block_range_end: None,
         };
 
         let enclave = LedgerSgxEnclave::new(
@@ -0,0 +1,149 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! Audit log of the changes Fog Overseer observes and makes in the Fog Ingest
+//! cluster.
+//!
+//! Every event is logged, and the most recent ones are kept in memory so that
+//! operators can review them through the `/audit_events` endpoint.
+
+use mc_common::logger::{log, Logger};
+use mc_fog_types::common::BlockRange;
+use mc_fog_uri::{FogIngestUri, IngestPeerUri};
+use serde::Serialize;
+use std::{
+    collections::VecDeque,
+    sync::Mutex,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// The maximum number of audit records kept in memory.
+pub const MAX_AUDIT_RECORDS: usize = 1000;
+
+/// Something Fog Overseer observed or did in the Fog Ingest cluster.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(tag = "event")]
+pub enum AuditEvent {
+    /// A node stopped responding to status requests.
+    NodeUnresponsive {
+        /// The node.
+        node_uri: FogIngestUri,
+        /// Whether the node was active when it last responded.
+        was_active: bool,
+    },
+
+    /// A node responds to status requests again.
+    NodeResponsive {
+        /// The node.
+        node_uri: FogIngestUri,
+    },
+
+    /// No node is active anymore, and a failover started.
+    FailoverStarted,
+
+    /// An outstanding ingress key was reported lost, because no node has it.
+    IngressKeyReportedLost {
+        /// The hex-encoded ingress public key.
+        ingress_key: String,
+        /// The blocks that will not be scanned with the key, if any.
+        missed_block_range: Option<BlockRange>,
+    },
+
+    /// A node got a new ingress key.
+    NewKeysSet {
+        /// The node.
+        node_uri: FogIngestUri,
+        /// The hex-encoded ingress public key.
+        ingress_key: String,
+    },
+
+    /// A node synced the ingress key of a peer.
+    KeysSynced {
+        /// The node.
+        node_uri: FogIngestUri,
+        /// The peer the key was synced from.
+        peer_uri: IngestPeerUri,
+        /// The hex-encoded ingress public key.
+        ingress_key: String,
+    },
+
+    /// A node was activated.
+    NodeActivated {
+        /// The node.
+        node_uri: FogIngestUri,
+        /// The hex-encoded ingress public key.
+        ingress_key: String,
+    },
+
+    /// The peers of a node were set, so that it backs its key up to them.
+    PeersSet {
+        /// The node.
+        node_uri: FogIngestUri,
+        /// The new peers of the node.
+        peer_uris: Vec<IngestPeerUri>,
+    },
+
+    /// A failover completed.
+    FailoverCompleted {
+        /// The node which is now active.
+        node_uri: FogIngestUri,
+    },
+
+    /// A failover failed, and will be retried on the next poll.
+    FailoverFailed {
+        /// The reason of the failure.
+        error: String,
+    },
+}
+
+/// An audit event, together with when it happened.
+#[derive(Clone, Debug, Serialize)]
+pub struct AuditRecord {
+    /// Seconds since the unix epoch.
+    pub timestamp: u64,
+
+    /// The event.
+    pub event: AuditEvent,
+}
+
+/// Keeps the most recent audit records.
+pub struct AuditLog {
+    records: Mutex<VecDeque<AuditRecord>>,
+    logger: Logger,
+}
+
+impl AuditLog {
+    /// Create an empty audit log.
+    pub fn new(logger: Logger) -> Self {
+        Self {
+            records: Mutex::new(VecDeque::new()),
+            logger,
+        }
+    }
+
+    /// Log an event and keep it, dropping the oldest record if there are too
+    /// many.
+    pub fn record(&self, event: AuditEvent) {
+        log::info!(self.logger, "Audit event: {:?}", event);
+
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or_default();
+
+        let mut records = self.records.lock().expect("mutex poisoned");
+        if records.len() >= MAX_AUDIT_RECORDS {
+            records.pop_front();
+        }
+        records.push_back(AuditRecord { timestamp, event });
+    }
+
+    /// The records kept so far, oldest first.
+    pub fn records(&self) -> Vec<AuditRecord> {
+        self.records
+            .lock()
+            .expect("mutex poisoned")
+            .iter()
+            .cloned()
+            .collect()
+    }
+}
@@ -34,8 +34,21 @@ async fn main() -> Result<(), rocket::Error> {
         )
     });
 
-    let mut overseer_service =
-        OverseerService::new(config.ingest_cluster_uris, recovery_db, logger.clone());
+    let peer_uris = &config.failover_config.ingest_peer_uris;
+    if !peer_uris.is_empty() && peer_uris.len() != config.ingest_cluster_uris.len() {
+        panic!(
+            "fog-overseer needs one ingest peer uri per ingest cluster uri, got {} peer uris and {} cluster uris",
+            peer_uris.len(),
+            config.ingest_cluster_uris.len()
+        );
+    }
+
+    let mut overseer_service = OverseerService::new(
+        config.ingest_cluster_uris,
+        recovery_db,
+        config.failover_config,
+        logger.clone(),
+    );
     overseer_service
         .start()
         .expect("OverseerService failed to start");
@@ -5,8 +5,10 @@
 
 use clap::Parser;
 use mc_fog_sql_recovery_db::SqlRecoveryDbConnectionConfig;
-use mc_fog_uri::FogIngestUri;
+use mc_fog_uri::{FogIngestUri, IngestPeerUri};
+use mc_util_parse::parse_duration_in_seconds;
 use serde::Serialize;
+use std::time::Duration;
 
 /// Parser configuration options for an Overseer Server
 #[derive(Clone, Serialize, Parser)]
@@ -29,11 +31,53 @@ pub struct OverseerConfig {
     #[clap(long, use_value_delimiter = true, env = "MC_INGEST_CLUSTER_URIS")]
     pub ingest_cluster_uris: Vec<FogIngestUri>,
 
+    /// Failover config
+    #[clap(flatten)]
+    pub failover_config: FailoverConfig,
+
     /// Postgres config
     #[clap(flatten)]
     pub postgres_config: SqlRecoveryDbConnectionConfig,
 }
 
+/// Parameters of the automatic failover of Fog Ingest nodes.
+#[derive(Clone, Debug, Parser, Serialize)]
+pub struct FailoverConfig {
+    /// How often to poll the status of the Fog Ingest nodes, in seconds.
+    #[clap(long, default_value = "5", parse(try_from_str = parse_duration_in_seconds), env = "MC_POLL_INTERVAL")]
+    pub poll_interval: Duration,
+
+    /// How long, in seconds, a Fog Ingest node which may be active has to stop
+    /// responding before it is considered dead and another node is activated.
+    #[clap(long, default_value = "30", parse(try_from_str = parse_duration_in_seconds), env = "MC_ACTIVE_NODE_TIMEOUT")]
+    pub active_node_timeout: Duration,
+
+    /// How long, in seconds, to retry a failed request to a Fog Ingest node.
+    #[clap(long, default_value = "10", parse(try_from_str = parse_duration_in_seconds), env = "MC_INGEST_RETRY_DURATION")]
+    pub ingest_retry_duration: Duration,
+
+    /// Peer URIs (igp://) of the Fog Ingest nodes, in the same order as
+    /// --ingest-cluster-uris.
+    ///
+    /// When set, the peers of a newly activated node are set to the whole
+    /// cluster so that it backs its key up to every other node, and an
+    /// outstanding key can be synced from an idle node which fails to activate
+    /// to another idle node.
+    #[clap(long, use_value_delimiter = true, env = "MC_INGEST_PEER_URIS")]
+    pub ingest_peer_uris: Vec<IngestPeerUri>,
+}
+
+impl Default for FailoverConfig {
+    fn default() -> Self {
+        Self {
+            poll_interval: Duration::from_secs(5),
+            active_node_timeout: Duration::from_secs(30),
+            ingest_retry_duration: Duration::from_secs(10),
+            ingest_peer_uris: Vec::new(),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -55,5 +99,26 @@ mod tests {
 
         assert_eq!(config.ingest_cluster_uris[0].port(), 3226);
         assert_eq!(config.ingest_cluster_uris[1].port(), 3227);
+        assert!(config.failover_config.ingest_peer_uris.is_empty());
+    }
+
+    #[test]
+    fn failover_config_example() {
+        let config = OverseerConfig::try_parse_from(&[
+            "/usr/bin/fog_overseer_server",
+            "--ingest-cluster-uris",
+            "insecure-fog-ingest://0.0.0.0:3226/,insecure-fog-ingest://0.0.0.0:3227/",
+            "--ingest-peer-uris",
+            "insecure-igp://0.0.0.0:8090/,insecure-igp://0.0.0.0:8091/",
+            "--active-node-timeout",
+            "60",
+        ])
+        .expect("Could not parse command line arguments.");
+
+        let failover_config = config.failover_config;
+        assert_eq!(failover_config.poll_interval, Duration::from_secs(5));
+        assert_eq!(failover_config.active_node_timeout, Duration::from_secs(60));
+        assert_eq!(failover_config.ingest_peer_uris.len(), 2);
+        assert_eq!(failover_config.ingest_peer_uris[1].port(), 8091);
     }
 }
@@ -25,6 +25,12 @@ pub enum OverseerError {
     /// Activating an idle node failed: {0}
     ActivateNode(String),
 
+    /// Syncing an outstanding key to an idle node failed: {0}
+    SyncKeys(String),
+
+    /// Setting the peers of a node failed: {0}
+    SetPeers(String),
+
     /// Multiple inactive outstanding keys found: {0}
     MultipleInactiveOutstandingKeys(String),
 
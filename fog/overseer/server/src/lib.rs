@@ -5,6 +5,7 @@
 #![feature(proc_macro_hygiene, decl_macro)]
 #![deny(missing_docs)]
 
+pub mod audit;
 pub mod config;
 pub mod metrics;
 pub mod responses;
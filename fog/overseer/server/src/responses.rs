@@ -2,6 +2,7 @@
 
 //! Contains responses that are returned by Fog Overseer.
 
+use crate::audit::AuditRecord;
 use mc_fog_types::ingest_common::IngestSummary;
 use mc_fog_uri::FogIngestUri;
 use serde::Serialize;
@@ -15,3 +16,11 @@ pub struct GetIngestSummariesResponse {
     /// The ingest summaries.
     pub ingest_summaries: HashMap<FogIngestUri, Result<IngestSummary, String>>,
 }
+
+/// The most recent changes Fog Overseer observed and made in the Fog Ingest
+/// cluster, oldest first.
+#[derive(Serialize)]
+pub struct GetAuditEventsResponse {
+    /// The audit records.
+    pub events: Vec<AuditRecord>,
+}
@@ -6,7 +6,9 @@
 //! HTTP Client -> *Overseer Rocket Server* -> OverseerService -> OverseerWorker
 
 use crate::{
-    error::OverseerError, responses::GetIngestSummariesResponse, service::OverseerService,
+    error::OverseerError,
+    responses::{GetAuditEventsResponse, GetIngestSummariesResponse},
+    service::OverseerService,
 };
 use mc_fog_recovery_db_iface::RecoveryDb;
 use mc_fog_sql_recovery_db::SqlRecoveryDb;
@@ -34,6 +36,15 @@ fn get_ingest_summaries(
     state.overseer_service.get_ingest_summaries().map(Json)
 }
 
+/// Returns the most recent changes Overseer observed and made in the Fog
+/// Ingest cluster, oldest first.
+#[get("/audit_events")]
+fn get_audit_events(
+    state: &rocket::State<OverseerState<SqlRecoveryDb>>,
+) -> Result<Json<GetAuditEventsResponse>, String> {
+    state.overseer_service.get_audit_events().map(Json)
+}
+
 /// Produces metrics for Prometheus.
 ///
 /// Meant to be called only by the Prometheus pull mechanism.
@@ -66,7 +77,8 @@ pub fn initialize_rocket_server<T: rocket::figment::Provider>(
             disable,
             get_status,
             get_metrics,
-            get_ingest_summaries
+            get_ingest_summaries,
+            get_audit_events
         ],
     )
 }
@@ -7,7 +7,13 @@
 //!
 //! HTTP Client -> Overseer Rocket Server -> *OverseerService* -> OverseerWorker
 
-use crate::{error::OverseerError, responses::GetIngestSummariesResponse, worker::OverseerWorker};
+use crate::{
+    audit::AuditLog,
+    config::FailoverConfig,
+    error::OverseerError,
+    responses::{GetAuditEventsResponse, GetIngestSummariesResponse},
+    worker::OverseerWorker,
+};
 use mc_common::logger::{log, Logger};
 use mc_fog_ingest_client::FogIngestGrpcClient;
 use mc_fog_recovery_db_iface::RecoveryDb;
@@ -21,7 +27,6 @@ use std::{
         atomic::{AtomicBool, Ordering},
         Arc,
     },
-    time::Duration,
 };
 
 /// Implements core logic for the Fog Overseer HTTP server.
@@ -33,6 +38,8 @@ where
     logger: Logger,
     overseer_worker: Option<OverseerWorker>,
     recovery_db: DB,
+    failover_config: FailoverConfig,
+    audit_log: Arc<AuditLog>,
     is_enabled: Arc<AtomicBool>,
 }
 
@@ -40,28 +47,33 @@ impl<DB: RecoveryDb + Clone + Send + Sync + 'static> OverseerService<DB>
 where
     OverseerError: From<DB::Error>,
 {
-    /// Retry failed GRPC requests every 10 seconds.
-    const GRPC_RETRY_SECONDS: Duration = Duration::from_millis(10000);
-
-    /// Insantiate the service with the given URIs and DB.
-    pub fn new(ingest_cluster_uris: Vec<FogIngestUri>, recovery_db: DB, logger: Logger) -> Self {
+    /// Insantiate the service with the given URIs, DB and failover config.
+    pub fn new(
+        ingest_cluster_uris: Vec<FogIngestUri>,
+        recovery_db: DB,
+        failover_config: FailoverConfig,
+        logger: Logger,
+    ) -> Self {
         let grpcio_env = Arc::new(grpcio::EnvBuilder::new().build());
         let ingest_clients: Vec<FogIngestGrpcClient> = ingest_cluster_uris
             .iter()
             .map(|fog_ingest_uri| {
                 FogIngestGrpcClient::new(
                     fog_ingest_uri.clone(),
-                    Self::GRPC_RETRY_SECONDS,
+                    failover_config.ingest_retry_duration,
                     grpcio_env.clone(),
                     logger.clone(),
                 )
             })
             .collect();
+        let audit_log = Arc::new(AuditLog::new(logger.clone()));
         Self {
             ingest_clients: Arc::new(ingest_clients),
             logger,
             overseer_worker: None,
             recovery_db,
+            failover_config,
+            audit_log,
             is_enabled: Arc::new(AtomicBool::new(false)),
         }
     }
@@ -83,6 +95,8 @@ where
         self.overseer_worker = Some(OverseerWorker::new(
             self.ingest_clients.clone(),
             self.recovery_db.clone(),
+            self.failover_config.clone(),
+            self.audit_log.clone(),
             self.logger.clone(),
             self.is_enabled.clone(),
         ));
@@ -184,6 +198,14 @@ where
 
         Ok(GetIngestSummariesResponse { ingest_summaries })
     }
+
+    /// Get the most recent changes Overseer observed and made in the Fog
+    /// Ingest cluster.
+    pub fn get_audit_events(&self) -> Result<GetAuditEventsResponse, String> {
+        Ok(GetAuditEventsResponse {
+            events: self.audit_log.records(),
+        })
+    }
 }
 
 impl<DB: RecoveryDb + Clone + Send + Sync + 'static> Drop for OverseerService<DB>
@@ -7,17 +7,22 @@
 //!
 //! HTTP Client -> Overseer Rocket Server -> OverseerService -> *OverseerWorker*
 
-use crate::{error::OverseerError, metrics};
+use crate::{
+    audit::{AuditEvent, AuditLog},
+    config::FailoverConfig,
+    error::OverseerError,
+    metrics,
+};
 use mc_api::external;
 use mc_common::logger::{log, Logger};
 use mc_crypto_keys::CompressedRistrettoPublic;
 use mc_fog_api::ingest_common::{IngestControllerMode, IngestSummary};
 use mc_fog_ingest_client::FogIngestGrpcClient;
 use mc_fog_recovery_db_iface::{IngressPublicKeyRecord, IngressPublicKeyRecordFilters, RecoveryDb};
-use mc_fog_uri::FogIngestUri;
+use mc_fog_types::common::BlockRange;
 use retry::{delay::Fixed, retry_with_index, OperationResult};
 use std::{
-    collections::HashSet,
+    cmp::max,
     convert::TryFrom,
     iter::Iterator,
     sync::{
@@ -25,7 +30,7 @@ use std::{
         Arc,
     },
     thread::{sleep, Builder as ThreadBuilder, JoinHandle},
-    time::Duration,
+    time::Instant,
 };
 
 /// Wraps a thread that is responsible for overseeing the active Fog Ingest
@@ -47,6 +52,8 @@ impl OverseerWorker {
     pub fn new<DB: RecoveryDb + Clone + Send + Sync + 'static>(
         ingest_clients: Arc<Vec<FogIngestGrpcClient>>,
         recovery_db: DB,
+        failover_config: FailoverConfig,
+        audit_log: Arc<AuditLog>,
         logger: Logger,
         is_enabled: Arc<AtomicBool>,
     ) -> Self
@@ -63,9 +70,10 @@ impl OverseerWorker {
                     OverseerWorkerThread::start(
                         ingest_clients,
                         recovery_db,
+                        failover_config,
+                        audit_log,
                         thread_is_enabled,
                         thread_stop_requested,
-                        HashSet::new(),
                         logger,
                     )
                 })
@@ -105,20 +113,38 @@ struct OverseerWorkerThread<DB: RecoveryDb> {
     /// cluster's ingress keys.
     recovery_db: DB,
 
+    /// Timeouts and peer uris used when failing over.
+    failover_config: FailoverConfig,
+
+    /// Where the changes to the cluster are recorded.
+    audit_log: Arc<AuditLog>,
+
     /// If this is true, the worker will not perform it's monitoring logic.
     is_enabled: Arc<AtomicBool>,
 
     /// If this is true, the thread will stop.
     stop_requested: Arc<AtomicBool>,
 
-    /// If a node doesn't respond to a status request, it goes here.
+    /// What we last learned about each node, in the order of ingest_clients.
     ///
-    /// This helps us debug when a node starts responding again.
-    unresponsive_node_urls: HashSet<FogIngestUri>,
+    /// This lets us tell a node which died while active apart from one which
+    /// is briefly unreachable, and helps us debug when a node starts
+    /// responding again.
+    node_healths: Vec<NodeHealth>,
 
     logger: Logger,
 }
 
+/// What Overseer last learned about a Fog Ingest node.
+#[derive(Default)]
+struct NodeHealth {
+    /// The last ingest summary the node responded with.
+    last_ingest_summary: Option<IngestSummary>,
+
+    /// When the node stopped responding, if it currently doesn't respond.
+    unresponsive_since: Option<Instant>,
+}
+
 /// This associates an IngestSummary with an IngestClient. This makes it easy
 /// to query a given node based on its IngestSummary.
 struct IngestSummaryNodeMapping {
@@ -131,9 +157,6 @@ impl<DB: RecoveryDb> OverseerWorkerThread<DB>
 where
     OverseerError: From<DB::Error>,
 {
-    /// Poll the Fog Ingest cluster every 5 seconds.
-    const POLLING_FREQUENCY: Duration = Duration::from_secs(5);
-
     /// Try a request to Fog Ingest node this many times if you encounter an
     /// error.
     const NUMBER_OF_TRIES: usize = 3;
@@ -142,17 +165,24 @@ where
     pub fn start(
         ingest_clients: Arc<Vec<FogIngestGrpcClient>>,
         recovery_db: DB,
+        failover_config: FailoverConfig,
+        audit_log: Arc<AuditLog>,
         is_enabled: Arc<AtomicBool>,
         stop_requested: Arc<AtomicBool>,
-        unresponsive_node_urls: HashSet<FogIngestUri>,
         logger: Logger,
     ) {
+        let node_healths = ingest_clients
+            .iter()
+            .map(|_| NodeHealth::default())
+            .collect();
         let thread = Self {
             ingest_clients,
             recovery_db,
+            failover_config,
+            audit_log,
             is_enabled,
             stop_requested,
-            unresponsive_node_urls,
+            node_healths,
             logger,
         };
         thread.run();
@@ -161,7 +191,7 @@ where
     fn run(mut self) {
         loop {
             log::trace!(self.logger, "Overseer worker start of thread.");
-            sleep(Self::POLLING_FREQUENCY);
+            sleep(self.failover_config.poll_interval);
 
             if self.stop_requested.load(Ordering::SeqCst) {
                 log::info!(self.logger, "Overseer worker thread stopping.");
@@ -173,16 +203,9 @@ where
                 continue;
             }
 
-            let ingest_summary_node_mappings: Vec<IngestSummaryNodeMapping> = match self
-                .retrieve_ingest_summary_node_mappings()
-            {
-                Ok(ingest_summary_node_mappings) => ingest_summary_node_mappings,
-                Err(err) => {
-                    log::error!(self.logger, "Encountered an error while retrieving ingest summaries: {}. Returning to beginning of overseer logic.", err);
-                    metrics::increment_unresponsive_node_count(&self.logger);
-                    continue;
-                }
-            };
+            // Unresponsive nodes are left out, and can't be acted upon.
+            let ingest_summary_node_mappings: Vec<IngestSummaryNodeMapping> =
+                self.retrieve_ingest_summary_node_mappings();
 
             let ingest_summaries: Vec<IngestSummary> = ingest_summary_node_mappings
                 .iter()
@@ -202,16 +225,34 @@ where
             let active_node_count = active_ingest_summary_node_mappings.len();
             match active_node_count {
                 0 => {
+                    if let Some(node_index) = self.maybe_active_unresponsive_node() {
+                        log::warn!(
+                            self.logger,
+                            "There are currently no active nodes responding in the Fog Ingest cluster. Waiting for node {} to respond before failing over.",
+                            self.ingest_clients[node_index].get_uri(),
+                        );
+                        continue;
+                    }
+
                     log::warn!(
                         self.logger,
                         "There are currently no active nodes in the Fog Ingest cluster. Initiating automatic failover.",
                     );
+                    self.audit_log.record(AuditEvent::FailoverStarted);
                     match self.perform_automatic_failover(ingest_summary_node_mappings) {
-                        Ok(_) => {
-                            log::info!(self.logger, "Automatic failover completed successfully.")
+                        Ok(activated_node_index) => {
+                            log::info!(self.logger, "Automatic failover completed successfully.");
+                            self.audit_log.record(AuditEvent::FailoverCompleted {
+                                node_uri: self.ingest_clients[activated_node_index]
+                                    .get_uri()
+                                    .clone(),
+                            });
                         }
                         Err(err) => {
-                            log::error!(self.logger, "Automatic failover failed: {}", err)
+                            log::error!(self.logger, "Automatic failover failed: {}", err);
+                            self.audit_log.record(AuditEvent::FailoverFailed {
+                                error: err.to_string(),
+                            });
                         }
                     };
                 }
@@ -245,16 +286,16 @@ where
     }
 
     /// Returns the latest round of ingest summaries for each
-    /// FogIngestGrpcClient that communicates with a node that is online.
-    fn retrieve_ingest_summary_node_mappings(
-        &mut self,
-    ) -> Result<Vec<IngestSummaryNodeMapping>, OverseerError> {
+    /// FogIngestGrpcClient that communicates with a node that is online, and
+    /// updates the health of every node.
+    fn retrieve_ingest_summary_node_mappings(&mut self) -> Vec<IngestSummaryNodeMapping> {
         let logger = &self.logger;
-        let unresponsive_node_urls = &mut self.unresponsive_node_urls;
+        let audit_log = &self.audit_log;
         self.ingest_clients
             .iter()
+            .zip(self.node_healths.iter_mut())
             .enumerate()
-            .map(|(node_index, ingest_client)| {
+            .filter_map(|(node_index, (ingest_client, node_health))| {
                 let uri = ingest_client.get_uri();
                 match ingest_client.get_status() {
                     Ok(ingest_summary) => {
@@ -264,33 +305,70 @@ where
                             uri,
                             ingest_summary
                         );
-                        if unresponsive_node_urls.remove(uri) {
+                        if node_health.unresponsive_since.take().is_some() {
                             log::info!(
-                            logger,
-                            "Node {} was previously unresponsive, but just successfully responded!",
-                            uri,
-                        );
+                                logger,
+                                "Node {} was previously unresponsive, but just successfully responded!",
+                                uri,
+                            );
+                            audit_log.record(AuditEvent::NodeResponsive {
+                                node_uri: uri.clone(),
+                            });
                         }
-                        Ok(IngestSummaryNodeMapping {
+                        node_health.last_ingest_summary = Some(ingest_summary.clone());
+                        Some(IngestSummaryNodeMapping {
                             node_index,
                             ingest_summary,
                         })
                     }
 
                     Err(err) => {
-                        let error_message = format!(
+                        log::trace!(
+                            logger,
                             "Unable to retrieve ingest summary for node ({}): {}",
-                            uri, err
+                            uri,
+                            err
                         );
-                        log::trace!(logger, "{}", error_message);
-                        unresponsive_node_urls.insert(uri.clone());
-                        Err(OverseerError::UnresponsiveNodeError(error_message))
+                        metrics::increment_unresponsive_node_count(logger);
+                        if node_health.unresponsive_since.is_none() {
+                            let error = OverseerError::UnresponsiveNodeError(format!(
+                                "{}: {}",
+                                uri, err
+                            ));
+                            log::warn!(logger, "{}", error);
+                            node_health.unresponsive_since = Some(Instant::now());
+                            audit_log.record(AuditEvent::NodeUnresponsive {
+                                node_uri: uri.clone(),
+                                was_active: node_health.was_active(),
+                            });
+                        }
+                        None
                     }
                 }
             })
             .collect()
     }
 
+    /// Returns an unresponsive node which might still be active, because it was
+    /// active (or never responded) when we last heard from it, and it hasn't
+    /// been unresponsive for long enough to be considered dead. We don't fail
+    /// over while there is such a node, so that a brief network issue doesn't
+    /// lead to two active nodes.
+    fn maybe_active_unresponsive_node(&self) -> Option<usize> {
+        let now = Instant::now();
+        self.node_healths.iter().position(|node_health| {
+            let maybe_active =
+                node_health.last_ingest_summary.is_none() || node_health.was_active();
+            node_health
+                .unresponsive_since
+                .map_or(false, |unresponsive_since| {
+                    maybe_active
+                        && now.duration_since(unresponsive_since)
+                            < self.failover_config.active_node_timeout
+                })
+        })
+    }
+
     /// Performs automatic failover, which means that we try to activate nodes
     /// for an outstanding ingress key, if it exists.
     ///
@@ -306,51 +384,63 @@ where
     ///                 (ii) Activate that node.
     ///         b) 1 outsanding key:
     ///              Try to find an idle node that contains that key.
-    ///                 (i)  If you find one, great! Just activate that node. If
-    ///                      activation is unsuccessful, then return an error
-    ///                      and return to the overseer polling logic.
-    ///                 (ii) If you don't find an idle node with that key,
-    ///                      then you have to report that key as lost, set
-    ///                      new keys on an idle node, and activate that node.
+    ///                 (i)   If you find one, great! Just activate that node.
+    ///                 (ii)  If none of the nodes with that key can be
+    ///                       activated, sync the key from one of them to
+    ///                       another idle node and activate that one. If
+    ///                       that fails too, return an error and return to
+    ///                       the overseer polling logic.
+    ///                 (iii) If you don't find an idle node with that key,
+    ///                       then you have to report that key as lost, set
+    ///                       new keys on an idle node, and activate that node.
     ///        c) > 1 outstanding key:
     ///             (i) Disable
     ///             (ii) TODO: Send an alert.
+    ///   3. Set the peers of the activated node to the whole cluster, so that
+    ///      it backs its key up to every other node.
+    ///
+    /// Returns the index of the activated node.
     fn perform_automatic_failover(
         &self,
         ingest_summary_node_mappings: Vec<IngestSummaryNodeMapping>,
-    ) -> Result<(), OverseerError> {
-        let inactive_outstanding_keys: Vec<CompressedRistrettoPublic> =
+    ) -> Result<usize, OverseerError> {
+        let inactive_outstanding_keys: Vec<IngressPublicKeyRecord> =
             self.get_inactive_outstanding_keys()?;
 
-        match inactive_outstanding_keys.len() {
+        let activated_node_index = match inactive_outstanding_keys.len() {
             0 => {
                 log::info!(self.logger, "Found 0 outstanding keys.");
-                let activated_node_index = self.set_new_key_on_a_node()?;
+                let activated_node_index =
+                    self.set_new_key_on_a_node(&ingest_summary_node_mappings)?;
                 self.activate_a_node(activated_node_index)?;
-                Ok(())
+                activated_node_index
             }
             1 => {
                 log::info!(self.logger, "Found 1 outstanding key.");
-                let inactive_outstanding_key = inactive_outstanding_keys[0];
                 self.handle_one_inactive_outstanding_key(
-                    inactive_outstanding_key,
-                    ingest_summary_node_mappings,
-                )?;
-                Ok(())
+                    &inactive_outstanding_keys[0],
+                    &ingest_summary_node_mappings,
+                )?
             }
             _ => {
                 self.is_enabled.store(false, Ordering::SeqCst);
+                let inactive_outstanding_keys: Vec<CompressedRistrettoPublic> =
+                    inactive_outstanding_keys
+                        .iter()
+                        .map(|record| record.key)
+                        .collect();
                 let error_message = format!("This is unexpected and requires manual intervention. As such, we've disabled overseer. Take the appropriate action and then re-enable overseer by calling the /enable endpoint. Inactive oustanding keys: {:?}", inactive_outstanding_keys);
-                Err(OverseerError::MultipleInactiveOutstandingKeys(
+                return Err(OverseerError::MultipleInactiveOutstandingKeys(
                     error_message,
-                ))
+                ));
             }
-        }
+        };
+
+        self.set_peers_on_a_node(activated_node_index);
+        Ok(activated_node_index)
     }
 
-    fn get_inactive_outstanding_keys(
-        &self,
-    ) -> Result<Vec<CompressedRistrettoPublic>, OverseerError> {
+    fn get_inactive_outstanding_keys(&self) -> Result<Vec<IngressPublicKeyRecord>, OverseerError> {
         // An outanding key is one that Fog Ingest is still obligated to be
         // scanning blocks with on behalf of users.
         let outstanding_keys_filters = IngressPublicKeyRecordFilters {
@@ -372,67 +462,60 @@ where
         // keys that we've grabbed from the RecoveryDb.
         //
         // TODO: Add a config that allows us to set this start block.
-        let ingress_public_key_records: Vec<IngressPublicKeyRecord> =
-            self.recovery_db.get_ingress_key_records(
-                /* start_block_at_least= */ 0,
-                &outstanding_keys_filters,
-            )?;
-
-        Ok(ingress_public_key_records
-            .iter()
-            .map(|record| record.key)
-            .collect())
+        Ok(self.recovery_db.get_ingress_key_records(
+            /* start_block_at_least= */ 0,
+            &outstanding_keys_filters,
+        )?)
     }
 
     /// Performs the following logic when one inactive outstanding key is found:
     ///   1) Tries to find an idle node that contains that key.
     ///      (i)  If it's found, it activates the node that contains it. If
-    ///           activation is unsuccessful, then it returns an error.
+    ///           activation is unsuccessful for every such node, it syncs the
+    ///           key to another idle node and activates that one, or returns
+    ///           an error.
     ///      (ii) If no idle node is found that contains the key, then it
     ///           reports that key as lost, sets new keys on an idle node, and
     ///           activates that node.
+    ///
+    /// Returns the index of the activated node.
     fn handle_one_inactive_outstanding_key(
         &self,
-        inactive_outstanding_key: CompressedRistrettoPublic,
-        ingest_summary_node_mappings: Vec<IngestSummaryNodeMapping>,
-    ) -> Result<(), OverseerError> {
+        inactive_outstanding_key_record: &IngressPublicKeyRecord,
+        ingest_summary_node_mappings: &[IngestSummaryNodeMapping],
+    ) -> Result<usize, OverseerError> {
+        let inactive_outstanding_key = inactive_outstanding_key_record.key;
         log::info!(
             self.logger,
             "Trying to activate an idle node with inactive outstanding key: {:?}",
             &inactive_outstanding_key
         );
-        for ingest_summary_node_mapping in &ingest_summary_node_mappings {
-            let node_ingress_key = match CompressedRistrettoPublic::try_from(
-                ingest_summary_node_mapping
-                    .ingest_summary
-                    .get_ingress_pubkey(),
-            ) {
-                Ok(key) => key,
-                Err(_) => continue,
-            };
-            if inactive_outstanding_key.eq(&node_ingress_key) {
-                let node = &self.ingest_clients[ingest_summary_node_mapping.node_index];
-                match node.activate() {
-                    Ok(_) => {
-                        log::info!(
-                            self.logger,
-                            "Successfully activated node {}.",
-                            node.get_uri()
-                        );
-                        return Ok(());
-                    }
-                    Err(err) => {
-                        let error_message = format!(
-                            "Tried activating node {}, but it failed: {}.",
-                            node.get_uri(),
-                            err
-                        );
-                        return Err(OverseerError::ActivateNode(error_message));
-                    }
-                }
+        let key_holder_node_indices: Vec<usize> = ingest_summary_node_mappings
+            .iter()
+            .filter(|ingest_summary_node_mapping| {
+                get_ingress_key(&ingest_summary_node_mapping.ingest_summary)
+                    == Some(inactive_outstanding_key)
+            })
+            .map(|ingest_summary_node_mapping| ingest_summary_node_mapping.node_index)
+            .collect();
+
+        for node_index in key_holder_node_indices.iter() {
+            match self.activate_a_node(*node_index) {
+                Ok(()) => return Ok(*node_index),
+                Err(err) => log::warn!(self.logger, "{}", err),
             }
         }
 
+        if !key_holder_node_indices.is_empty() {
+            // The key still exists, so it must not be reported lost. Instead,
+            // copy it to a node which doesn't have it yet.
+            return self.sync_key_to_a_node(
+                inactive_outstanding_key,
+                &key_holder_node_indices,
+                ingest_summary_node_mappings,
+            );
+        }
+
         // We've gone through all the Fog Ingest nodes' keys,
         // and none of them matches the inactive outstanding key. We must
         // report the inactive outstanding key as lost, set new keys
@@ -442,18 +525,93 @@ where
             "Could not find a node that has the inactive outstanding key: {:?}",
             &inactive_outstanding_key
         );
-        self.report_lost_ingress_key(inactive_outstanding_key)?;
-        let activated_node_index = self.set_new_key_on_a_node()?;
+        self.report_lost_ingress_key(inactive_outstanding_key_record)?;
+        let activated_node_index = self.set_new_key_on_a_node(ingest_summary_node_mappings)?;
         self.activate_a_node(activated_node_index)?;
 
-        Ok(())
+        Ok(activated_node_index)
+    }
+
+    /// Tries to sync an ingress key from one of the nodes holding it to
+    /// another idle node, and to activate that node.
+    ///
+    /// Returns the index of the activated node.
+    fn sync_key_to_a_node(
+        &self,
+        ingress_key: CompressedRistrettoPublic,
+        key_holder_node_indices: &[usize],
+        ingest_summary_node_mappings: &[IngestSummaryNodeMapping],
+    ) -> Result<usize, OverseerError> {
+        let peer_uris = &self.failover_config.ingest_peer_uris;
+        if peer_uris.is_empty() {
+            return Err(OverseerError::SyncKeys(
+                "None of the nodes with the outstanding key could be activated, and the key can't be synced to other nodes without --ingest-peer-uris.".to_string(),
+            ));
+        }
+
+        for key_holder_node_index in key_holder_node_indices {
+            let peer_uri = &peer_uris[*key_holder_node_index];
+            for ingest_summary_node_mapping in ingest_summary_node_mappings {
+                let node_index = ingest_summary_node_mapping.node_index;
+                if key_holder_node_indices.contains(&node_index) {
+                    continue;
+                }
+
+                let ingest_client = &self.ingest_clients[node_index];
+                match ingest_client.sync_keys_from_remote(peer_uri.to_string()) {
+                    Ok(ingest_summary) if get_ingress_key(&ingest_summary) == Some(ingress_key) => {
+                        log::info!(
+                            self.logger,
+                            "Node {} synced the outstanding key from {}.",
+                            ingest_client.get_uri(),
+                            peer_uri
+                        );
+                        self.audit_log.record(AuditEvent::KeysSynced {
+                            node_uri: ingest_client.get_uri().clone(),
+                            peer_uri: peer_uri.clone(),
+                            ingress_key: ingress_key.to_string(),
+                        });
+                    }
+                    Ok(_) => {
+                        log::warn!(
+                            self.logger,
+                            "Node {} synced keys from {}, but didn't get the outstanding key.",
+                            ingest_client.get_uri(),
+                            peer_uri
+                        );
+                        continue;
+                    }
+                    Err(err) => {
+                        log::warn!(
+                            self.logger,
+                            "Node {} failed to sync keys from {}: {}",
+                            ingest_client.get_uri(),
+                            peer_uri,
+                            err
+                        );
+                        continue;
+                    }
+                }
+
+                match self.activate_a_node(node_index) {
+                    Ok(()) => return Ok(node_index),
+                    Err(err) => log::warn!(self.logger, "{}", err),
+                }
+            }
+        }
+
+        Err(OverseerError::SyncKeys(format!(
+            "The outstanding key {} could not be synced to a node which could then be activated.",
+            ingress_key
+        )))
     }
 
     /// Tries to report a lost ingress key.
     fn report_lost_ingress_key(
         &self,
-        inactive_outstanding_key: CompressedRistrettoPublic,
+        inactive_outstanding_key_record: &IngressPublicKeyRecord,
     ) -> Result<(), OverseerError> {
+        let inactive_outstanding_key = inactive_outstanding_key_record.key;
         let result = retry_with_index(
             Fixed::from_millis(200).take(Self::NUMBER_OF_TRIES),
             |current_try| match self
@@ -475,25 +633,36 @@ where
                 }
             },
         );
+        result?;
 
-        Ok(result?)
+        self.audit_log.record(AuditEvent::IngressKeyReportedLost {
+            ingress_key: inactive_outstanding_key.to_string(),
+            missed_block_range: missed_block_range(inactive_outstanding_key_record),
+        });
+        Ok(())
     }
 
-    /// Tries to set a new ingress key on a node. The node is assumed to be
-    /// idle.
-    fn set_new_key_on_a_node(&self) -> Result<usize, OverseerError> {
-        for (i, ingest_client) in self.ingest_clients.iter().enumerate() {
+    /// Tries to set a new ingress key on one of the given nodes, which are
+    /// assumed to be idle.
+    ///
+    /// Returns the index of the node with the new key.
+    fn set_new_key_on_a_node(
+        &self,
+        ingest_summary_node_mappings: &[IngestSummaryNodeMapping],
+    ) -> Result<usize, OverseerError> {
+        for ingest_summary_node_mapping in ingest_summary_node_mappings {
+            let ingest_client = &self.ingest_clients[ingest_summary_node_mapping.node_index];
             let result = retry_with_index(
                 Fixed::from_millis(200).take(Self::NUMBER_OF_TRIES),
                 |current_try| {
                     match ingest_client.new_keys() {
-                        Ok(_) => {
+                        Ok(ingest_summary) => {
                             log::info!(
                                 self.logger,
                                 "New keys successfully set on the ingest node {}.",
                                 ingest_client.get_uri()
                             );
-                            OperationResult::Ok(())
+                            OperationResult::Ok(ingest_summary)
                         }
                         // TODO: We'll need to alert Ops to take manual action at this point.
                         Err(err) => {
@@ -506,8 +675,12 @@ where
                 },
             );
 
-            if result.is_ok() {
-                return Ok(i);
+            if let Ok(ingest_summary) = result {
+                self.audit_log.record(AuditEvent::NewKeysSet {
+                    node_uri: ingest_client.get_uri().clone(),
+                    ingress_key: format_ingress_key(&ingest_summary),
+                });
+                return Ok(ingest_summary_node_mapping.node_index);
             }
         }
 
@@ -518,18 +691,18 @@ where
 
     /// Tries to activate a node. The node is assumed to be idle.
     fn activate_a_node(&self, activated_node_index: usize) -> Result<(), OverseerError> {
+        let ingest_client = &self.ingest_clients[activated_node_index];
         let result = retry_with_index(
             Fixed::from_millis(200).take(Self::NUMBER_OF_TRIES),
             |current_try| {
-                let ingest_client = &self.ingest_clients[activated_node_index];
                 match ingest_client.activate() {
-                    Ok(_) => {
+                    Ok(ingest_summary) => {
                         log::info!(
                             self.logger,
                             "Node {} successfully activated.",
                             ingest_client.get_uri(),
                         );
-                        OperationResult::Ok(())
+                        OperationResult::Ok(ingest_summary)
                     }
                     // TODO: Alert Ops to take manual action at this point.
                     Err(err) => {
@@ -547,6 +720,123 @@ where
             },
         );
 
-        Ok(result?)
+        let ingest_summary = result?;
+        self.audit_log.record(AuditEvent::NodeActivated {
+            node_uri: ingest_client.get_uri().clone(),
+            ingress_key: format_ingress_key(&ingest_summary),
+        });
+        Ok(())
+    }
+
+    /// Sets the peers of a node to the whole cluster, if the peer uris are
+    /// configured, so that it backs its key up to every other node.
+    ///
+    /// This is best effort: failing to do so doesn't undo the failover.
+    fn set_peers_on_a_node(&self, node_index: usize) {
+        let peer_uris = &self.failover_config.ingest_peer_uris;
+        if peer_uris.is_empty() {
+            return;
+        }
+
+        let ingest_client = &self.ingest_clients[node_index];
+        let peer_uri_strings: Vec<String> = peer_uris.iter().map(|uri| uri.to_string()).collect();
+        match ingest_client.set_peers(&peer_uri_strings) {
+            Ok(_) => {
+                log::info!(
+                    self.logger,
+                    "Peers successfully set on node {}.",
+                    ingest_client.get_uri()
+                );
+                self.audit_log.record(AuditEvent::PeersSet {
+                    node_uri: ingest_client.get_uri().clone(),
+                    peer_uris: peer_uris.clone(),
+                });
+            }
+            Err(err) => {
+                let error =
+                    OverseerError::SetPeers(format!("Node {}: {}", ingest_client.get_uri(), err));
+                log::error!(self.logger, "{}", error);
+            }
+        }
+    }
+}
+
+impl NodeHealth {
+    /// Whether the node was active when it last responded.
+    fn was_active(&self) -> bool {
+        self.last_ingest_summary
+            .as_ref()
+            .map_or(false, |ingest_summary| {
+                ingest_summary.mode == IngestControllerMode::Active
+            })
+    }
+}
+
+/// The ingress key of a node, if its summary holds a valid one.
+fn get_ingress_key(ingest_summary: &IngestSummary) -> Option<CompressedRistrettoPublic> {
+    CompressedRistrettoPublic::try_from(ingest_summary.get_ingress_pubkey()).ok()
+}
+
+fn format_ingress_key(ingest_summary: &IngestSummary) -> String {
+    get_ingress_key(ingest_summary)
+        .map(|key| key.to_string())
+        .unwrap_or_default()
+}
+
+/// The blocks which won't be scanned once a key is reported lost: the ones
+/// after the last block scanned with the key, up to its pubkey expiry. This is
+/// the range the recovery db records as missed blocks.
+fn missed_block_range(record: &IngressPublicKeyRecord) -> Option<BlockRange> {
+    let start_block = record
+        .last_scanned_block
+        .map_or(record.status.start_block, |last_scanned_block| {
+            max(record.status.start_block, last_scanned_block + 1)
+        });
+    let block_range = BlockRange::new(start_block, record.status.pubkey_expiry);
+    block_range.is_valid().then(|| block_range)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mc_fog_recovery_db_iface::IngressPublicKeyStatus;
+    use mc_util_from_random::FromRandom;
+    use rand_core::SeedableRng;
+    use rand_hc::Hc128Rng;
+
+    fn record(
+        start_block: u64,
+        pubkey_expiry: u64,
+        last_scanned_block: Option<u64>,
+    ) -> IngressPublicKeyRecord {
+        let mut rng = Hc128Rng::from_seed([1u8; 32]);
+        IngressPublicKeyRecord {
+            key: CompressedRistrettoPublic::from_random(&mut rng),
+            status: IngressPublicKeyStatus {
+                start_block,
+                pubkey_expiry,
+                retired: false,
+                lost: false,
+            },
+            last_scanned_block,
+        }
+    }
+
+    #[test]
+    fn missed_block_range_starts_after_last_scanned_block() {
+        assert_eq!(
+            missed_block_range(&record(10, 30, Some(19))),
+            Some(BlockRange::new(20, 30))
+        );
+        assert_eq!(
+            missed_block_range(&record(10, 30, None)),
+            Some(BlockRange::new(10, 30))
+        );
+    }
+
+    #[test]
+    fn missed_block_range_is_none_when_nothing_is_missed() {
+        assert_eq!(missed_block_range(&record(10, 30, Some(29))), None);
+        assert_eq!(missed_block_range(&record(10, 0, None)), None);
     }
 }
@@ -0,0 +1,298 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! Tests the automatic failover of Fog Overseer against mock Fog Ingest nodes.
+
+mod mock_ingest;
+
+use mc_common::logger::{test_with_logger, Logger};
+use mc_crypto_keys::{CompressedRistrettoPublic, RistrettoPublic};
+use mc_fog_api::ingest_common::IngestControllerMode;
+use mc_fog_overseer_server::{
+    audit::{AuditEvent, AuditRecord},
+    config::FailoverConfig,
+    service::OverseerService,
+};
+use mc_fog_recovery_db_iface::{RecoveryDb, ReportData, ReportDb};
+use mc_fog_sql_recovery_db::{test_utils::SqlRecoveryDbTestContext, SqlRecoveryDb};
+use mc_fog_test_infra::db_tests::{random_block, random_kex_rng_pubkey};
+use mc_fog_types::common::BlockRange;
+use mc_util_from_random::FromRandom;
+use mock_ingest::{MockIngestCluster, MockIngestNode};
+use rand_core::{RngCore, SeedableRng};
+use rand_hc::Hc128Rng;
+use std::{
+    thread::sleep,
+    time::{Duration, Instant},
+};
+
+/// The pubkey expiry of the outstanding key.
+const PUBKEY_EXPIRY: u64 = 20;
+
+/// The number of blocks scanned with the outstanding key.
+const SCANNED_BLOCKS: u64 = 5;
+
+/// Adds a key to the recovery db which has been used to scan some blocks, but
+/// not up to its pubkey expiry, so that Fog Ingest is still obligated to scan
+/// blocks with it.
+fn add_outstanding_key(db: &SqlRecoveryDb, rng: &mut impl RngCore) -> CompressedRistrettoPublic {
+    let ingress_key = CompressedRistrettoPublic::from(RistrettoPublic::from_random(rng));
+    db.new_ingress_key(&ingress_key, 0).unwrap();
+    db.set_report(
+        &ingress_key,
+        "",
+        &ReportData {
+            pubkey_expiry: PUBKEY_EXPIRY,
+            ingest_invocation_id: None,
+            report: Default::default(),
+        },
+    )
+    .unwrap();
+
+    let invocation_id = db
+        .new_ingest_invocation(None, &ingress_key, &random_kex_rng_pubkey(rng), 0)
+        .unwrap();
+    for block_index in 0..SCANNED_BLOCKS {
+        let (block, records) = random_block(rng, block_index, 3);
+        db.add_block_data(&invocation_id, &block, 0, &records)
+            .unwrap();
+    }
+
+    ingress_key
+}
+
+fn random_key(rng: &mut impl RngCore) -> CompressedRistrettoPublic {
+    CompressedRistrettoPublic::from(RistrettoPublic::from_random(rng))
+}
+
+/// Starts an enabled OverseerService for the given nodes.
+fn start_overseer(
+    nodes: &[MockIngestNode],
+    db: SqlRecoveryDb,
+    active_node_timeout: Duration,
+    logger: Logger,
+) -> OverseerService<SqlRecoveryDb> {
+    let failover_config = FailoverConfig {
+        poll_interval: Duration::from_millis(200),
+        active_node_timeout,
+        ingest_retry_duration: Duration::from_secs(1),
+        ingest_peer_uris: nodes.iter().map(|node| node.peer_uri.clone()).collect(),
+    };
+    let ingest_uris = nodes.iter().map(|node| node.client_uri.clone()).collect();
+    let mut overseer_service = OverseerService::new(ingest_uris, db, failover_config, logger);
+    overseer_service
+        .start()
+        .expect("OverseerService failed to start");
+    overseer_service.enable().unwrap();
+    overseer_service
+}
+
+fn audit_events(overseer_service: &OverseerService<SqlRecoveryDb>) -> Vec<AuditEvent> {
+    overseer_service
+        .get_audit_events()
+        .unwrap()
+        .events
+        .into_iter()
+        .map(|record: AuditRecord| record.event)
+        .collect()
+}
+
+/// Waits until the condition holds, panicking after 30 seconds.
+fn wait_for(description: &str, mut condition: impl FnMut() -> bool) {
+    let deadline = Instant::now() + Duration::from_secs(30);
+    while !condition() {
+        if Instant::now() > deadline {
+            panic!("Timed out waiting for: {}", description);
+        }
+        sleep(Duration::from_millis(100));
+    }
+}
+
+fn active_node_indices(nodes: &[MockIngestNode]) -> Vec<usize> {
+    nodes
+        .iter()
+        .enumerate()
+        .filter(|(_, node)| node.mode() == IngestControllerMode::Active)
+        .map(|(index, _)| index)
+        .collect()
+}
+
+// The active node dies, and the idle nodes have its key. An idle node should be
+// activated, and the key should not be reported lost.
+#[test_with_logger]
+fn idle_node_with_outstanding_key_is_activated(logger: Logger) {
+    let mut rng = Hc128Rng::from_seed([1u8; 32]);
+    let db_test_context = SqlRecoveryDbTestContext::new(logger.clone());
+    let db = db_test_context.get_db_instance();
+    let ingress_key = add_outstanding_key(&db, &mut rng);
+
+    let cluster = MockIngestCluster::default();
+    let mut nodes: Vec<MockIngestNode> = (0..3)
+        .map(|_| cluster.make_node(&ingress_key, logger.clone()))
+        .collect();
+    nodes[0].set_mode(IngestControllerMode::Active);
+    let overseer_service =
+        start_overseer(&nodes, db.clone(), Duration::from_secs(1), logger.clone());
+
+    nodes[0].stop();
+    wait_for("an idle node to be activated", || {
+        !active_node_indices(&nodes[1..]).is_empty()
+    });
+
+    let active_node_indices = active_node_indices(&nodes[1..]);
+    assert_eq!(active_node_indices.len(), 1);
+    let active_node = &nodes[1 + active_node_indices[0]];
+    assert_eq!(active_node.ingress_key(), ingress_key);
+
+    let status = db.get_ingress_key_status(&ingress_key).unwrap().unwrap();
+    assert!(!status.lost);
+
+    wait_for("the failover to complete", || {
+        audit_events(&overseer_service).contains(&AuditEvent::FailoverCompleted {
+            node_uri: active_node.client_uri.clone(),
+        })
+    });
+    let expected_peers: Vec<String> = nodes.iter().map(|node| node.peer_uri.to_string()).collect();
+    assert_eq!(active_node.peers(), expected_peers);
+
+    let events = audit_events(&overseer_service);
+    assert!(events.contains(&AuditEvent::NodeUnresponsive {
+        node_uri: nodes[0].client_uri.clone(),
+        was_active: true,
+    }));
+    assert!(events.contains(&AuditEvent::FailoverStarted));
+    assert!(!events
+        .iter()
+        .any(|event| matches!(event, AuditEvent::IngressKeyReportedLost { .. })));
+}
+
+// The active node dies, and none of the idle nodes have its key. The key should
+// be reported lost, with the blocks it didn't scan, and a node should be
+// activated with a new key.
+#[test_with_logger]
+fn outstanding_key_is_reported_lost_when_no_node_has_it(logger: Logger) {
+    let mut rng = Hc128Rng::from_seed([2u8; 32]);
+    let db_test_context = SqlRecoveryDbTestContext::new(logger.clone());
+    let db = db_test_context.get_db_instance();
+    let ingress_key = add_outstanding_key(&db, &mut rng);
+
+    let cluster = MockIngestCluster::default();
+    let mut nodes = vec![
+        cluster.make_node(&ingress_key, logger.clone()),
+        cluster.make_node(&random_key(&mut rng), logger.clone()),
+        cluster.make_node(&random_key(&mut rng), logger.clone()),
+    ];
+    nodes[0].set_mode(IngestControllerMode::Active);
+    let overseer_service =
+        start_overseer(&nodes, db.clone(), Duration::from_secs(1), logger.clone());
+
+    nodes[0].stop();
+    wait_for("an idle node to be activated", || {
+        !active_node_indices(&nodes[1..]).is_empty()
+    });
+
+    let status = db.get_ingress_key_status(&ingress_key).unwrap().unwrap();
+    assert!(status.lost);
+    assert_eq!(
+        db.get_missed_block_ranges().unwrap(),
+        vec![BlockRange::new(SCANNED_BLOCKS, PUBKEY_EXPIRY)]
+    );
+
+    let active_node = &nodes[1 + active_node_indices(&nodes[1..])[0]];
+    assert_ne!(active_node.ingress_key(), ingress_key);
+
+    wait_for("the failover to complete", || {
+        audit_events(&overseer_service).contains(&AuditEvent::FailoverCompleted {
+            node_uri: active_node.client_uri.clone(),
+        })
+    });
+    let events = audit_events(&overseer_service);
+    assert!(events.contains(&AuditEvent::IngressKeyReportedLost {
+        ingress_key: ingress_key.to_string(),
+        missed_block_range: Some(BlockRange::new(SCANNED_BLOCKS, PUBKEY_EXPIRY)),
+    }));
+    assert!(events.contains(&AuditEvent::NewKeysSet {
+        node_uri: active_node.client_uri.clone(),
+        ingress_key: active_node.ingress_key().to_string(),
+    }));
+}
+
+// The active node dies, and the only idle node with its key can't be
+// activated. The key should be synced to another idle node, which should then
+// be activated, and the key should not be reported lost.
+#[test_with_logger]
+fn outstanding_key_is_synced_when_its_holder_fails_to_activate(logger: Logger) {
+    let mut rng = Hc128Rng::from_seed([3u8; 32]);
+    let db_test_context = SqlRecoveryDbTestContext::new(logger.clone());
+    let db = db_test_context.get_db_instance();
+    let ingress_key = add_outstanding_key(&db, &mut rng);
+
+    let cluster = MockIngestCluster::default();
+    let mut nodes = vec![
+        cluster.make_node(&ingress_key, logger.clone()),
+        cluster.make_node(&ingress_key, logger.clone()),
+        cluster.make_node(&random_key(&mut rng), logger.clone()),
+    ];
+    nodes[0].set_mode(IngestControllerMode::Active);
+    nodes[1].set_fail_activate(true);
+    let overseer_service =
+        start_overseer(&nodes, db.clone(), Duration::from_secs(1), logger.clone());
+
+    nodes[0].stop();
+    wait_for("the node without the key to be activated", || {
+        nodes[2].mode() == IngestControllerMode::Active
+    });
+
+    assert_eq!(nodes[1].mode(), IngestControllerMode::Idle);
+    assert_eq!(nodes[2].ingress_key(), ingress_key);
+    let status = db.get_ingress_key_status(&ingress_key).unwrap().unwrap();
+    assert!(!status.lost);
+
+    wait_for("the failover to complete", || {
+        audit_events(&overseer_service).contains(&AuditEvent::FailoverCompleted {
+            node_uri: nodes[2].client_uri.clone(),
+        })
+    });
+    assert!(
+        audit_events(&overseer_service).contains(&AuditEvent::KeysSynced {
+            node_uri: nodes[2].client_uri.clone(),
+            peer_uri: nodes[1].peer_uri.clone(),
+            ingress_key: ingress_key.to_string(),
+        })
+    );
+}
+
+// The active node stops responding for less than the active node timeout. No
+// other node should be activated in the meantime.
+#[test_with_logger]
+fn brief_outage_of_active_node_does_not_fail_over(logger: Logger) {
+    let mut rng = Hc128Rng::from_seed([4u8; 32]);
+    let db_test_context = SqlRecoveryDbTestContext::new(logger.clone());
+    let db = db_test_context.get_db_instance();
+    let ingress_key = add_outstanding_key(&db, &mut rng);
+
+    let cluster = MockIngestCluster::default();
+    let mut nodes: Vec<MockIngestNode> = (0..3)
+        .map(|_| cluster.make_node(&ingress_key, logger.clone()))
+        .collect();
+    nodes[0].set_mode(IngestControllerMode::Active);
+    let overseer_service = start_overseer(&nodes, db, Duration::from_secs(60), logger.clone());
+
+    nodes[0].stop();
+    wait_for("the active node to be found unresponsive", || {
+        audit_events(&overseer_service).contains(&AuditEvent::NodeUnresponsive {
+            node_uri: nodes[0].client_uri.clone(),
+            was_active: true,
+        })
+    });
+    // Give overseer a few polls to (wrongly) fail over.
+    sleep(Duration::from_secs(2));
+    nodes[0].start();
+    wait_for("the active node to respond again", || {
+        audit_events(&overseer_service).contains(&AuditEvent::NodeResponsive {
+            node_uri: nodes[0].client_uri.clone(),
+        })
+    });
+
+    assert_eq!(active_node_indices(&nodes), vec![0]);
+    assert!(!audit_events(&overseer_service).contains(&AuditEvent::FailoverStarted));
+}
@@ -0,0 +1,316 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! Mock Fog Ingest nodes, which let the overseer failover logic be tested
+//! without enclaves.
+//!
+//! A mock node only keeps an IngestSummary. It supports the calls overseer
+//! makes, and syncs keys from the other mock nodes of the same
+//! MockIngestCluster by looking them up by peer uri.
+
+use grpcio::{RpcContext, RpcStatus, RpcStatusCode, Server, ServerBuilder, UnarySink};
+use mc_common::logger::Logger;
+use mc_crypto_keys::{CompressedRistrettoPublic, RistrettoPublic};
+use mc_fog_api::{
+    ingest::{
+        GetIngressKeyRecordsRequest, GetIngressKeyRecordsResponse, GetMissedBlockRangesResponse,
+        ReportLostIngressKeyRequest, SetPubkeyExpiryWindowRequest, SyncKeysFromRemoteRequest,
+    },
+    ingest_common::{IngestControllerMode, IngestSummary, SetPeersRequest},
+    ingest_grpc::{self, AccountIngestApi},
+    Empty,
+};
+use mc_fog_uri::{FogIngestUri, IngestPeerUri};
+use mc_util_from_random::FromRandom;
+use mc_util_grpc::{send_result, ConnectionUriGrpcioServer};
+use rand_core::SeedableRng;
+use rand_hc::Hc128Rng;
+use std::{
+    collections::HashMap,
+    str::FromStr,
+    sync::{Arc, Mutex},
+};
+
+/// The state of a mock node.
+#[derive(Default)]
+pub struct MockIngestState {
+    /// What the node reports from GetStatus.
+    pub summary: IngestSummary,
+
+    /// If true, Activate fails.
+    pub fail_activate: bool,
+
+    /// How many keys the node generated so far.
+    new_keys_count: u64,
+}
+
+type Registry = Arc<Mutex<HashMap<String, Arc<Mutex<MockIngestState>>>>>;
+
+/// A set of mock nodes which can sync keys from each other.
+#[derive(Default)]
+pub struct MockIngestCluster {
+    registry: Registry,
+}
+
+impl MockIngestCluster {
+    /// Start a new mock node of this cluster, which is idle and has the given
+    /// ingress key.
+    pub fn make_node(
+        &self,
+        ingress_key: &CompressedRistrettoPublic,
+        logger: Logger,
+    ) -> MockIngestNode {
+        let client_port = portpicker::pick_unused_port().expect("No free ports");
+        let peer_port = portpicker::pick_unused_port().expect("No free ports");
+        let client_uri =
+            FogIngestUri::from_str(&format!("insecure-fog-ingest://127.0.0.1:{}/", client_port))
+                .unwrap();
+        let peer_uri =
+            IngestPeerUri::from_str(&format!("insecure-igp://127.0.0.1:{}/", peer_port)).unwrap();
+
+        let mut summary = IngestSummary::new();
+        summary.set_mode(IngestControllerMode::Idle);
+        summary.set_ingress_pubkey(ingress_key.into());
+        let state = Arc::new(Mutex::new(MockIngestState {
+            summary,
+            ..Default::default()
+        }));
+        self.registry
+            .lock()
+            .unwrap()
+            .insert(peer_uri.to_string(), state.clone());
+
+        let mut node = MockIngestNode {
+            client_uri,
+            peer_uri,
+            state,
+            registry: self.registry.clone(),
+            server: None,
+            logger,
+        };
+        node.start();
+        node
+    }
+}
+
+/// A mock Fog Ingest node serving the client API.
+pub struct MockIngestNode {
+    pub client_uri: FogIngestUri,
+    pub peer_uri: IngestPeerUri,
+    pub state: Arc<Mutex<MockIngestState>>,
+    registry: Registry,
+    server: Option<Server>,
+    logger: Logger,
+}
+
+impl MockIngestNode {
+    /// Start serving, if the node isn't already.
+    pub fn start(&mut self) {
+        if self.server.is_some() {
+            return;
+        }
+
+        let service = ingest_grpc::create_account_ingest_api(MockIngestService {
+            port: self.client_uri.port(),
+            state: self.state.clone(),
+            registry: self.registry.clone(),
+            logger: self.logger.clone(),
+        });
+        let env = Arc::new(grpcio::EnvBuilder::new().build());
+        let mut server = ServerBuilder::new(env)
+            .register_service(service)
+            .bind_using_uri(&self.client_uri, self.logger.clone())
+            .build()
+            .expect("Could not build mock ingest server");
+        server.start();
+        self.server = Some(server);
+    }
+
+    /// Stop serving, as if the node died.
+    pub fn stop(&mut self) {
+        self.server = None;
+    }
+
+    pub fn mode(&self) -> IngestControllerMode {
+        self.state.lock().unwrap().summary.get_mode()
+    }
+
+    pub fn ingress_key(&self) -> CompressedRistrettoPublic {
+        let state = self.state.lock().unwrap();
+        CompressedRistrettoPublic::try_from(state.summary.get_ingress_pubkey()).unwrap()
+    }
+
+    pub fn peers(&self) -> Vec<String> {
+        self.state.lock().unwrap().summary.get_peers().to_vec()
+    }
+
+    pub fn set_mode(&self, mode: IngestControllerMode) {
+        self.state.lock().unwrap().summary.set_mode(mode);
+    }
+
+    pub fn set_fail_activate(&self, fail_activate: bool) {
+        self.state.lock().unwrap().fail_activate = fail_activate;
+    }
+}
+
+#[derive(Clone)]
+struct MockIngestService {
+    port: u16,
+    state: Arc<Mutex<MockIngestState>>,
+    registry: Registry,
+    logger: Logger,
+}
+
+impl MockIngestService {
+    fn new_keys_impl(&self) -> Result<IngestSummary, RpcStatus> {
+        let mut state = self.state.lock().unwrap();
+        if state.summary.get_mode() != IngestControllerMode::Idle {
+            return Err(precondition_error("new_keys requires an idle node"));
+        }
+        state.new_keys_count += 1;
+        let mut rng = Hc128Rng::seed_from_u64(self.port as u64 * 1000 + state.new_keys_count);
+        let ingress_key = CompressedRistrettoPublic::from(RistrettoPublic::from_random(&mut rng));
+        state.summary.set_ingress_pubkey((&ingress_key).into());
+        Ok(state.summary.clone())
+    }
+
+    fn activate_impl(&self) -> Result<IngestSummary, RpcStatus> {
+        let mut state = self.state.lock().unwrap();
+        if state.fail_activate {
+            return Err(RpcStatus::with_message(
+                RpcStatusCode::INTERNAL,
+                "activation failure".to_string(),
+            ));
+        }
+        if state.summary.get_mode() != IngestControllerMode::Idle {
+            return Err(precondition_error("activate requires an idle node"));
+        }
+        state.summary.set_mode(IngestControllerMode::Active);
+        Ok(state.summary.clone())
+    }
+
+    fn set_peers_impl(&self, request: SetPeersRequest) -> Result<IngestSummary, RpcStatus> {
+        let mut state = self.state.lock().unwrap();
+        state.summary.set_peers(request.ingest_peer_uris);
+        Ok(state.summary.clone())
+    }
+
+    fn sync_keys_from_remote_impl(
+        &self,
+        request: SyncKeysFromRemoteRequest,
+    ) -> Result<IngestSummary, RpcStatus> {
+        let remote_state = self
+            .registry
+            .lock()
+            .unwrap()
+            .get(request.get_peer_uri())
+            .cloned()
+            .ok_or_else(|| {
+                RpcStatus::with_message(RpcStatusCode::UNAVAILABLE, "unknown peer".to_string())
+            })?;
+        let ingress_pubkey = remote_state
+            .lock()
+            .unwrap()
+            .summary
+            .get_ingress_pubkey()
+            .clone();
+
+        let mut state = self.state.lock().unwrap();
+        if state.summary.get_mode() != IngestControllerMode::Idle {
+            return Err(precondition_error(
+                "sync_keys_from_remote requires an idle node",
+            ));
+        }
+        state.summary.set_ingress_pubkey(ingress_pubkey);
+        Ok(state.summary.clone())
+    }
+}
+
+fn precondition_error(message: &str) -> RpcStatus {
+    RpcStatus::with_message(RpcStatusCode::FAILED_PRECONDITION, message.to_string())
+}
+
+fn unimplemented<T>() -> Result<T, RpcStatus> {
+    Err(RpcStatus::new(RpcStatusCode::UNIMPLEMENTED))
+}
+
+impl AccountIngestApi for MockIngestService {
+    fn get_status(&mut self, ctx: RpcContext, _request: Empty, sink: UnarySink<IngestSummary>) {
+        let summary = self.state.lock().unwrap().summary.clone();
+        send_result(ctx, sink, Ok(summary), &self.logger)
+    }
+
+    fn new_keys(&mut self, ctx: RpcContext, _request: Empty, sink: UnarySink<IngestSummary>) {
+        send_result(ctx, sink, self.new_keys_impl(), &self.logger)
+    }
+
+    fn set_pubkey_expiry_window(
+        &mut self,
+        ctx: RpcContext,
+        _request: SetPubkeyExpiryWindowRequest,
+        sink: UnarySink<IngestSummary>,
+    ) {
+        send_result(ctx, sink, unimplemented(), &self.logger)
+    }
+
+    fn set_peers(
+        &mut self,
+        ctx: RpcContext,
+        request: SetPeersRequest,
+        sink: UnarySink<IngestSummary>,
+    ) {
+        send_result(ctx, sink, self.set_peers_impl(request), &self.logger)
+    }
+
+    fn activate(&mut self, ctx: RpcContext, _request: Empty, sink: UnarySink<IngestSummary>) {
+        send_result(ctx, sink, self.activate_impl(), &self.logger)
+    }
+
+    fn retire(&mut self, ctx: RpcContext, _request: Empty, sink: UnarySink<IngestSummary>) {
+        send_result(ctx, sink, unimplemented(), &self.logger)
+    }
+
+    fn unretire(&mut self, ctx: RpcContext, _request: Empty, sink: UnarySink<IngestSummary>) {
+        send_result(ctx, sink, unimplemented(), &self.logger)
+    }
+
+    fn report_lost_ingress_key(
+        &mut self,
+        ctx: RpcContext,
+        _request: ReportLostIngressKeyRequest,
+        sink: UnarySink<Empty>,
+    ) {
+        send_result(ctx, sink, unimplemented(), &self.logger)
+    }
+
+    fn get_missed_block_ranges(
+        &mut self,
+        ctx: RpcContext,
+        _request: Empty,
+        sink: UnarySink<GetMissedBlockRangesResponse>,
+    ) {
+        send_result(ctx, sink, unimplemented(), &self.logger)
+    }
+
+    fn sync_keys_from_remote(
+        &mut self,
+        ctx: RpcContext,
+        request: SyncKeysFromRemoteRequest,
+        sink: UnarySink<IngestSummary>,
+    ) {
+        send_result(
+            ctx,
+            sink,
+            self.sync_keys_from_remote_impl(request),
+            &self.logger,
+        )
+    }
+
+    fn get_ingress_key_records(
+        &mut self,
+        ctx: RpcContext,
+        _request: GetIngressKeyRecordsRequest,
+        sink: UnarySink<GetIngressKeyRecordsResponse>,
+    ) {
+        send_result(ctx, sink, unimplemented(), &self.logger)
+    }
+}
@@ -4,6 +4,7 @@
 
 use mc_fog_ingest_server_test_utils::{IngestServerTestHelper, TestIngestNode};
 use mc_fog_overseer_server::{
+    config::FailoverConfig,
     server::{initialize_rocket_server, OverseerState},
     service::OverseerService,
 };
@@ -25,8 +26,18 @@ pub trait TestHelperExt {
 
 impl TestHelperExt for IngestServerTestHelper {
     fn enable_overseer(&self, ingest_uris: Vec<FogIngestUri>) -> Client {
-        let mut overseer_service =
-            OverseerService::new(ingest_uris, self.recovery_db.clone(), self.logger.clone());
+        // These tests stop nodes for good, so fail over as soon as no node is
+        // active.
+        let failover_config = FailoverConfig {
+            active_node_timeout: Duration::ZERO,
+            ..Default::default()
+        };
+        let mut overseer_service = OverseerService::new(
+            ingest_uris,
+            self.recovery_db.clone(),
+            failover_config,
+            self.logger.clone(),
+        );
         overseer_service
             .start()
             .expect("OverseerService failed to start");
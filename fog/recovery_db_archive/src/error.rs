@@ -0,0 +1,39 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+use displaydoc::Display;
+use prost::{DecodeError, EncodeError};
+use std::io::Error as IoError;
+
+/// Enum for error types.
+#[derive(Display, Debug)]
+pub enum Error {
+    /// IO error: {0}
+    Io(IoError),
+
+    /// The archive file could not be decoded: {0}
+    Decode(DecodeError),
+
+    /// The blocks could not be encoded for the archive: {0}
+    Encode(EncodeError),
+
+    /// Archived blocks must be sorted, contiguous and not empty
+    InvalidBlocks,
+}
+
+impl From<IoError> for Error {
+    fn from(src: IoError) -> Self {
+        Self::Io(src)
+    }
+}
+
+impl From<DecodeError> for Error {
+    fn from(src: DecodeError) -> Self {
+        Self::Decode(src)
+    }
+}
+
+impl From<EncodeError> for Error {
+    fn from(src: EncodeError) -> Self {
+        Self::Encode(src)
+    }
+}
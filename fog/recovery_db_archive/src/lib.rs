@@ -0,0 +1,319 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+#![deny(missing_docs)]
+
+//! Archive of the ETxOutRecords moved out of the fog recovery db.
+//!
+//! The archive is a directory with a sub-directory per ingress key, named
+//! after the hex encoding of the key. Each file in it holds a contiguous range
+//! of blocks ingested with that key, as gzip-compressed length-delimited
+//! ArchivedBlock protobufs, and is named `<start_block>-<end_block>.gz` after
+//! that range (the end block is not included).
+
+mod error;
+
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use mc_crypto_keys::CompressedRistrettoPublic;
+use mc_fog_types::{common::BlockRange, ETxOutRecord};
+use prost::Message;
+use std::{
+    fs::{self, File},
+    io::{BufReader, BufWriter, Read, Write},
+    path::PathBuf,
+};
+
+pub use error::Error;
+
+/// The extension of archive files.
+const ARCHIVE_FILE_EXTENSION: &str = "gz";
+
+/// A block ingested with some ingress key, as stored in the archive.
+#[derive(Clone, Eq, PartialEq, Message)]
+pub struct ArchivedBlock {
+    /// The ingest invocation which ingested this block.
+    #[prost(int64, tag = 1)]
+    pub ingest_invocation_id: i64,
+
+    /// The index of the block.
+    #[prost(uint64, tag = 2)]
+    pub block_index: u64,
+
+    /// The cumulative txo count of the block.
+    #[prost(uint64, tag = 3)]
+    pub cumulative_txo_count: u64,
+
+    /// The timestamp of the block signature.
+    #[prost(uint64, tag = 4)]
+    pub block_signature_timestamp: u64,
+
+    /// The ETxOutRecords fog ingest emitted for this block.
+    #[prost(message, repeated, tag = 5)]
+    pub e_tx_out_records: Vec<ETxOutRecord>,
+}
+
+/// A directory holding archived blocks.
+pub struct Archive {
+    /// The directory of the archive.
+    root: PathBuf,
+
+    /// The blocks of the archive file read last, since blocks are usually read
+    /// in order.
+    cached_file: Option<CachedFile>,
+}
+
+struct CachedFile {
+    ingress_key: CompressedRistrettoPublic,
+    block_range: BlockRange,
+    blocks: Vec<ArchivedBlock>,
+}
+
+impl Archive {
+    /// Open the archive at the given directory, which is created when blocks
+    /// are first written to it.
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            cached_file: None,
+        }
+    }
+
+    /// Write blocks ingested with an ingress key to a new archive file.
+    ///
+    /// The file is synced to disk before returning, so the blocks can then be
+    /// deleted from the recovery db.
+    ///
+    /// Arguments:
+    /// * ingress_key: The ingress key the blocks were ingested with
+    /// * blocks: The blocks, sorted by index and without gaps
+    ///
+    /// Returns:
+    /// * The path of the new archive file
+    pub fn write_blocks(
+        &self,
+        ingress_key: &CompressedRistrettoPublic,
+        blocks: &[ArchivedBlock],
+    ) -> Result<PathBuf, Error> {
+        let block_range = match (blocks.first(), blocks.last()) {
+            (Some(first), Some(last)) => BlockRange::new(first.block_index, last.block_index + 1),
+            _ => return Err(Error::InvalidBlocks),
+        };
+        if blocks
+            .windows(2)
+            .any(|pair| pair[1].block_index != pair[0].block_index + 1)
+        {
+            return Err(Error::InvalidBlocks);
+        }
+
+        let dir = self.key_dir(ingress_key);
+        fs::create_dir_all(&dir)?;
+
+        // Write to a temporary file first, so that readers never see a partial
+        // archive file.
+        let path = dir.join(file_name(&block_range));
+        let tmp_path = path.with_extension("tmp");
+        {
+            let file = File::create(&tmp_path)?;
+            let mut encoder = GzEncoder::new(BufWriter::new(file), Compression::default());
+            let mut bytes = Vec::new();
+            for block in blocks {
+                bytes.clear();
+                block.encode_length_delimited(&mut bytes)?;
+                encoder.write_all(&bytes)?;
+            }
+            let file = encoder
+                .finish()?
+                .into_inner()
+                .map_err(|err| err.into_error())?;
+            file.sync_all()?;
+        }
+        fs::rename(&tmp_path, &path)?;
+
+        Ok(path)
+    }
+
+    /// Read a block ingested with an ingress key from the archive.
+    ///
+    /// Returns:
+    /// * The block, or None if no archive file holds it
+    pub fn read_block(
+        &mut self,
+        ingress_key: &CompressedRistrettoPublic,
+        block_index: u64,
+    ) -> Result<Option<ArchivedBlock>, Error> {
+        let cached = self.cached_file.as_ref().map_or(false, |cached| {
+            cached.ingress_key == *ingress_key && cached.block_range.contains(block_index)
+        });
+        if !cached {
+            let block_range = match self.find_file(ingress_key, block_index)? {
+                Some(block_range) => block_range,
+                None => return Ok(None),
+            };
+            let blocks = self.read_file(ingress_key, &block_range)?;
+            self.cached_file = Some(CachedFile {
+                ingress_key: *ingress_key,
+                block_range,
+                blocks,
+            });
+        }
+
+        Ok(self.cached_file.as_ref().and_then(|cached| {
+            cached
+                .blocks
+                .iter()
+                .find(|block| block.block_index == block_index)
+                .cloned()
+        }))
+    }
+
+    /// Find the block range of an archive file holding a given block.
+    fn find_file(
+        &self,
+        ingress_key: &CompressedRistrettoPublic,
+        block_index: u64,
+    ) -> Result<Option<BlockRange>, Error> {
+        let dir = self.key_dir(ingress_key);
+        if !dir.exists() {
+            return Ok(None);
+        }
+
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some(ARCHIVE_FILE_EXTENSION) {
+                continue;
+            }
+            let block_range = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .and_then(parse_file_stem);
+            if let Some(block_range) = block_range {
+                if block_range.contains(block_index) {
+                    return Ok(Some(block_range));
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn read_file(
+        &self,
+        ingress_key: &CompressedRistrettoPublic,
+        block_range: &BlockRange,
+    ) -> Result<Vec<ArchivedBlock>, Error> {
+        let file = File::open(self.key_dir(ingress_key).join(file_name(block_range)))?;
+        let mut bytes = Vec::new();
+        GzDecoder::new(BufReader::new(file)).read_to_end(&mut bytes)?;
+
+        let mut buf = bytes.as_slice();
+        let mut blocks = Vec::new();
+        while !buf.is_empty() {
+            blocks.push(ArchivedBlock::decode_length_delimited(&mut buf)?);
+        }
+        Ok(blocks)
+    }
+
+    fn key_dir(&self, ingress_key: &CompressedRistrettoPublic) -> PathBuf {
+        self.root.join(hex::encode(ingress_key))
+    }
+}
+
+fn file_name(block_range: &BlockRange) -> String {
+    format!(
+        "{}-{}.{}",
+        block_range.start_block, block_range.end_block, ARCHIVE_FILE_EXTENSION
+    )
+}
+
+fn parse_file_stem(stem: &str) -> Option<BlockRange> {
+    let (start_block, end_block) = stem.split_once('-')?;
+    Some(BlockRange::new(
+        start_block.parse().ok()?,
+        end_block.parse().ok()?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mc_fog_test_infra::db_tests::random_tx_row;
+    use mc_util_from_random::FromRandom;
+    use rand::{rngs::StdRng, SeedableRng};
+    use tempdir::TempDir;
+
+    fn archived_blocks(rng: &mut StdRng, block_range: BlockRange) -> Vec<ArchivedBlock> {
+        (block_range.start_block..block_range.end_block)
+            .map(|block_index| ArchivedBlock {
+                ingest_invocation_id: 1,
+                block_index,
+                cumulative_txo_count: block_index * 3,
+                block_signature_timestamp: block_index * 10,
+                e_tx_out_records: (0..3).map(|_| random_tx_row(rng)).collect(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn write_and_read_blocks() {
+        let mut rng: StdRng = SeedableRng::from_seed([7u8; 32]);
+        let dir = TempDir::new("archive").unwrap();
+        let mut archive = Archive::new(dir.path());
+
+        let ingress_key1 = CompressedRistrettoPublic::from_random(&mut rng);
+        let ingress_key2 = CompressedRistrettoPublic::from_random(&mut rng);
+        let blocks1 = archived_blocks(&mut rng, BlockRange::new(10, 20));
+        let blocks2 = archived_blocks(&mut rng, BlockRange::new(20, 25));
+        let blocks3 = archived_blocks(&mut rng, BlockRange::new(10, 15));
+
+        archive.write_blocks(&ingress_key1, &blocks1).unwrap();
+        archive.write_blocks(&ingress_key1, &blocks2).unwrap();
+        archive.write_blocks(&ingress_key2, &blocks3).unwrap();
+
+        for block in blocks1.iter().chain(blocks2.iter()) {
+            assert_eq!(
+                archive
+                    .read_block(&ingress_key1, block.block_index)
+                    .unwrap()
+                    .as_ref(),
+                Some(block)
+            );
+        }
+        for block in blocks3.iter() {
+            assert_eq!(
+                archive
+                    .read_block(&ingress_key2, block.block_index)
+                    .unwrap()
+                    .as_ref(),
+                Some(block)
+            );
+        }
+
+        assert_eq!(archive.read_block(&ingress_key1, 9).unwrap(), None);
+        assert_eq!(archive.read_block(&ingress_key1, 25).unwrap(), None);
+        assert_eq!(archive.read_block(&ingress_key2, 15).unwrap(), None);
+        assert_eq!(
+            archive
+                .read_block(&CompressedRistrettoPublic::from_random(&mut rng), 10)
+                .unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn write_rejects_invalid_blocks() {
+        let mut rng: StdRng = SeedableRng::from_seed([7u8; 32]);
+        let dir = TempDir::new("archive").unwrap();
+        let archive = Archive::new(dir.path());
+        let ingress_key = CompressedRistrettoPublic::from_random(&mut rng);
+
+        assert!(matches!(
+            archive.write_blocks(&ingress_key, &[]),
+            Err(Error::InvalidBlocks)
+        ));
+
+        let mut blocks = archived_blocks(&mut rng, BlockRange::new(10, 20));
+        blocks.remove(5);
+        assert!(matches!(
+            archive.write_blocks(&ingress_key, &blocks),
+            Err(Error::InvalidBlocks)
+        ));
+    }
+}
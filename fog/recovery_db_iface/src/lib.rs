@@ -18,8 +18,9 @@ use mc_fog_types::view::TxOutSearchResult;
 pub use mc_blockchain_types::Block;
 pub use mc_fog_types::{common::BlockRange, ETxOutRecord};
 pub use types::{
-    AddBlockDataStatus, FogUserEvent, IngestInvocationId, IngestableRange, IngressPublicKeyRecord,
-    IngressPublicKeyStatus, ReportData,
+    filter_user_events_for_account, select_served_reports, AddBlockDataStatus, ArchivedBlockRange,
+    FogUserEvent, IngestInvocationId, IngestableRange, IngressPublicKeyRecord,
+    IngressPublicKeyStatus, ReportData, ReportKeyState, ReportRecord,
 };
 
 /// Contains fields that are used as filters in  queries for ingress keys.
@@ -201,6 +202,19 @@ pub trait RecoveryDb {
     /// * A vector of missing block ranges.
     fn get_missed_block_ranges(&self) -> Result<Vec<BlockRange>, Self::Error>;
 
+    /// Remove a missed block range, once the lost ingress key which missed it
+    /// was restored and has scanned its blocks after the fact.
+    ///
+    /// If the same range was recorded several times, only one of them is
+    /// removed.
+    ///
+    /// Arguments:
+    /// * block_range: The missed block range to remove.
+    ///
+    /// Returns:
+    /// * Whether the missed block range was found and removed.
+    fn remove_missed_block_range(&self, block_range: &BlockRange) -> Result<bool, Self::Error>;
+
     /// Get any events which are new after `start_after_event_id`.
     ///
     /// Arguments:
@@ -247,7 +261,8 @@ pub trait RecoveryDb {
     /// * block_index: The block we need ETxOutRecords from
     ///
     /// Returns:
-    /// * Ok(None) if this block has not been scanned with this key.
+    /// * Ok(None) if this block has not been scanned with this key, or if its
+    ///   ETxOutRecords were archived (see get_archived_block_ranges).
     ///   Ok(Some(data)) with the ETxOutRecord's from when this block was added,
     ///   An error if there is a database error
     fn get_tx_outs_by_block_and_key(
@@ -309,6 +324,13 @@ pub trait RecoveryDb {
 
     /// Get the highest block index for which we have any data at all.
     fn get_highest_known_block_index(&self) -> Result<Option<u64>, Self::Error>;
+
+    /// Get the ranges of blocks, per ingress key, whose ETxOutRecords were
+    /// moved out of the database into an archive.
+    ///
+    /// The other data of these blocks (e.g. their cumulative txo count) is
+    /// still available from the database.
+    fn get_archived_block_ranges(&self) -> Result<Vec<ArchivedBlockRange>, Self::Error>;
 }
 
 /// The report database interface.
@@ -323,12 +345,23 @@ pub trait ReportDb {
     /// If there are many reports, then this should be redesigned to use an
     /// oblivious lookup strategy inside of an sgx enclave.
     ///
+    /// There may be several reports with the same report id, one per ingress
+    /// key. See `select_served_reports` for which of them are served: the key
+    /// of the next ingest cluster is only served once its ingest has started,
+    /// and the current key keeps being served until then.
+    ///
     /// Returns:
-    /// * Pairs of the form report-id, report-data
+    /// * Pairs of the form report-id, report-data, ordered by report id, then
+    ///   by decreasing pubkey expiry.
     fn get_all_reports(&self) -> Result<Vec<(String, ReportData)>, Self::Error>;
 
-    /// Set report data associated with a given report id, unless the public key
-    /// is retired.
+    /// Get all the reports in the database, including the ones which are not
+    /// served, ordered by report id, then by decreasing pubkey expiry.
+    fn get_report_records(&self) -> Result<Vec<ReportRecord>, Self::Error>;
+
+    /// Set report data associated with a given report id and public key, unless
+    /// the public key is retired. Whether the report is pinned or revoked is
+    /// left unchanged.
     ///
     /// Arguments:
     /// * ingress_public_key - the public key signed by this report
@@ -349,4 +382,22 @@ pub trait ReportDb {
 
     /// Remove report data associated with a given report id.
     fn remove_report(&self, report_id: &str) -> Result<(), Self::Error>;
+
+    /// Set or clear the pinned flag of the report with the given report id and
+    /// public key.
+    fn set_report_pinned(
+        &self,
+        ingress_public_key: &CompressedRistrettoPublic,
+        report_id: &str,
+        pinned: bool,
+    ) -> Result<(), Self::Error>;
+
+    /// Set or clear the revoked flag of the report with the given report id
+    /// and public key.
+    fn set_report_revoked(
+        &self,
+        ingress_public_key: &CompressedRistrettoPublic,
+        report_id: &str,
+        revoked: bool,
+    ) -> Result<(), Self::Error>;
 }
@@ -3,6 +3,11 @@
 //! Database API types
 //! These are not user-facing, the user facing versions are in fog-types crate.
 
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    string::String,
+    vec::Vec,
+};
 use core::{fmt, ops::Deref};
 use mc_attest_core::VerificationReport;
 use mc_crypto_keys::CompressedRistrettoPublic;
@@ -44,6 +49,16 @@ pub struct AddBlockDataStatus {
     pub block_already_scanned_with_this_key: bool,
 }
 
+/// A range of blocks ingested with an ingress key, whose ETxOutRecords were
+/// moved out of the database into an archive.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct ArchivedBlockRange {
+    /// The ingress key the blocks were ingested with
+    pub ingress_key: CompressedRistrettoPublic,
+    /// The archived blocks
+    pub block_range: BlockRange,
+}
+
 /// IngressPublicKeyRecord
 ///
 /// This is returned by get_ingress_public_key_records, and augments the
@@ -133,6 +148,72 @@ pub enum FogUserEvent {
     MissingBlocks(BlockRange),
 }
 
+/// Drop the user events which can't concern an account that only received
+/// TxOuts from account_start_block onward.
+///
+/// * Missed block ranges which end before account_start_block are dropped, and
+///   the others are clipped to start at account_start_block at the earliest.
+/// * RNG records of ingest invocations whose last ingested block is before
+///   account_start_block are dropped, together with the decommission events of
+///   these invocations. This only happens if both events are in `events`, so
+///   that users are still told about the decommissioning of RNGs they got
+///   earlier.
+///
+/// If account_start_block is 0, all the events are kept.
+pub fn filter_user_events_for_account(
+    events: Vec<FogUserEvent>,
+    account_start_block: u64,
+) -> Vec<FogUserEvent> {
+    if account_start_block == 0 {
+        return events;
+    }
+
+    let new_invocation_ids: BTreeSet<i64> = events
+        .iter()
+        .filter_map(|event| match event {
+            FogUserEvent::NewRngRecord(rng_record) => Some(rng_record.ingest_invocation_id),
+            _ => None,
+        })
+        .collect();
+    let irrelevant_invocation_ids: BTreeSet<i64> = events
+        .iter()
+        .filter_map(|event| match event {
+            FogUserEvent::DecommissionIngestInvocation(decommissioned)
+                if decommissioned.last_ingested_block < account_start_block
+                    && new_invocation_ids.contains(&decommissioned.ingest_invocation_id) =>
+            {
+                Some(decommissioned.ingest_invocation_id)
+            }
+            _ => None,
+        })
+        .collect();
+
+    events
+        .into_iter()
+        .filter_map(|event| match event {
+            FogUserEvent::NewRngRecord(ref rng_record)
+                if irrelevant_invocation_ids.contains(&rng_record.ingest_invocation_id) =>
+            {
+                None
+            }
+            FogUserEvent::DecommissionIngestInvocation(ref decommissioned)
+                if irrelevant_invocation_ids.contains(&decommissioned.ingest_invocation_id) =>
+            {
+                None
+            }
+            FogUserEvent::MissingBlocks(range) => {
+                (range.end_block > account_start_block).then(|| {
+                    FogUserEvent::MissingBlocks(BlockRange::new(
+                        core::cmp::max(range.start_block, account_start_block),
+                        range.end_block,
+                    ))
+                })
+            }
+            event => Some(event),
+        })
+        .collect()
+}
+
 /// An ingest invocation begins consuming the blockchain at some particular
 /// block index, and eventually stops. The IngestableRange tracks the start
 /// block, what the last scanned block is, and whether it has stopped.
@@ -225,9 +306,98 @@ pub struct ReportData {
     pub pubkey_expiry: u64,
 }
 
+/// A report as stored in the database, together with the flags an operator
+/// can set on it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReportRecord {
+    /// The fog_report_id of the users this report is for.
+    pub report_id: String,
+
+    /// The ingress public key signed by this report.
+    pub ingress_public_key: CompressedRistrettoPublic,
+
+    /// The report data.
+    pub data: ReportData,
+
+    /// Whether this report is pinned. If any report with a given report id is
+    /// pinned, only the pinned ones are served.
+    pub pinned: bool,
+
+    /// Whether this report is revoked. Revoked reports are never served.
+    pub revoked: bool,
+}
+
+/// The state of the ingress key of a report, as far as serving the report is
+/// concerned. States are ordered from the most to the least preferred.
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub enum ReportKeyState {
+    /// An ingest invocation which is not decommissioned uses this key, and the
+    /// key is not retired.
+    Active,
+    /// The key is retired, but fog ingest still scans with it until its pubkey
+    /// expiry.
+    Retired,
+    /// No ingest invocation uses this key yet.
+    Pending,
+    /// The key is lost, so its report must not be served.
+    Lost,
+}
+
+/// Select the reports which the report server serves, out of reports ordered
+/// by report id, then by decreasing pubkey expiry. For each report id:
+///
+/// * Revoked reports and reports of lost keys are never served.
+/// * If any of the remaining reports is pinned, only the pinned ones are
+///   served.
+/// * Otherwise, only the reports whose key is in the most preferred state are
+///   served. The key of the next ingest cluster is thus only advertised once
+///   its ingest has started, and the current key keeps being served until then,
+///   even if it is retired.
+pub fn select_served_reports(
+    reports: Vec<(ReportRecord, ReportKeyState)>,
+) -> Vec<(String, ReportData)> {
+    let is_candidate = |record: &ReportRecord, state: ReportKeyState| {
+        !record.revoked && state != ReportKeyState::Lost
+    };
+
+    // For each report id, whether a report is pinned, and the most preferred
+    // key state.
+    let mut best: BTreeMap<&str, (bool, ReportKeyState)> = BTreeMap::new();
+    for (record, state) in reports.iter() {
+        if !is_candidate(record, *state) {
+            continue;
+        }
+        let entry = best
+            .entry(record.report_id.as_str())
+            .or_insert((false, ReportKeyState::Lost));
+        entry.0 |= record.pinned;
+        entry.1 = core::cmp::min(entry.1, *state);
+    }
+
+    let served: Vec<bool> = reports
+        .iter()
+        .map(|(record, state)| {
+            is_candidate(record, *state)
+                && match best.get(record.report_id.as_str()) {
+                    Some((true, _)) => record.pinned,
+                    Some((false, best_state)) => state == best_state,
+                    None => false,
+                }
+        })
+        .collect();
+
+    reports
+        .into_iter()
+        .zip(served)
+        .filter(|(_, served)| *served)
+        .map(|((record, _), _)| (record.report_id, record.data))
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use alloc::vec;
 
     #[test]
     fn active_ingress_public_key_record_covers_block_index() {
@@ -406,4 +576,170 @@ mod tests {
             }
         }
     }
+
+    fn rng_record(ingest_invocation_id: i64, start_block: u64) -> FogUserEvent {
+        FogUserEvent::NewRngRecord(RngRecord {
+            ingest_invocation_id,
+            start_block,
+            ..Default::default()
+        })
+    }
+
+    fn decommissioned(ingest_invocation_id: i64, last_ingested_block: u64) -> FogUserEvent {
+        FogUserEvent::DecommissionIngestInvocation(DecommissionedIngestInvocation {
+            ingest_invocation_id,
+            last_ingested_block,
+        })
+    }
+
+    fn missing_blocks(start_block: u64, end_block: u64) -> FogUserEvent {
+        FogUserEvent::MissingBlocks(BlockRange::new(start_block, end_block))
+    }
+
+    #[test]
+    fn filter_user_events_for_account_keeps_everything_without_start_block() {
+        let events = vec![
+            rng_record(1, 0),
+            missing_blocks(5, 10),
+            decommissioned(1, 4),
+            rng_record(2, 10),
+        ];
+        assert_eq!(filter_user_events_for_account(events.clone(), 0), events);
+    }
+
+    #[test]
+    fn filter_user_events_for_account_drops_events_before_start_block() {
+        let events = vec![
+            rng_record(1, 0),
+            missing_blocks(5, 10),
+            decommissioned(1, 4),
+            rng_record(2, 10),
+            missing_blocks(12, 20),
+            decommissioned(2, 19),
+            rng_record(3, 20),
+            // Decommissioning of an invocation the user may have seen before
+            decommissioned(0, 3),
+        ];
+        assert_eq!(
+            filter_user_events_for_account(events, 15),
+            vec![
+                rng_record(2, 10),
+                missing_blocks(15, 20),
+                decommissioned(2, 19),
+                rng_record(3, 20),
+                decommissioned(0, 3),
+            ]
+        );
+    }
+
+    #[test]
+    fn filter_user_events_for_account_keeps_rngs_scanning_start_block() {
+        let events = vec![
+            rng_record(1, 0),
+            missing_blocks(5, 10),
+            decommissioned(1, 10),
+        ];
+        assert_eq!(
+            filter_user_events_for_account(events, 10),
+            vec![rng_record(1, 0), decommissioned(1, 10)]
+        );
+    }
+
+    fn report_record(
+        report_id: &str,
+        pubkey_expiry: u64,
+        pinned: bool,
+        revoked: bool,
+    ) -> ReportRecord {
+        ReportRecord {
+            report_id: report_id.into(),
+            ingress_public_key: Default::default(),
+            data: ReportData {
+                ingest_invocation_id: None,
+                report: Default::default(),
+                pubkey_expiry,
+            },
+            pinned,
+            revoked,
+        }
+    }
+
+    fn served_expiries(reports: Vec<(ReportRecord, ReportKeyState)>) -> Vec<(String, u64)> {
+        select_served_reports(reports)
+            .into_iter()
+            .map(|(report_id, data)| (report_id, data.pubkey_expiry))
+            .collect()
+    }
+
+    #[test]
+    fn select_served_reports_prefers_active_keys() {
+        use ReportKeyState::*;
+
+        // The next key is not advertised until its ingest has started.
+        assert_eq!(
+            served_expiries(vec![
+                (report_record("", 20, false, false), Pending),
+                (report_record("", 10, false, false), Active),
+            ]),
+            vec![("".into(), 10)]
+        );
+
+        // The current key is still served once retired, until the next one is
+        // active.
+        assert_eq!(
+            served_expiries(vec![
+                (report_record("", 20, false, false), Pending),
+                (report_record("", 10, false, false), Retired),
+            ]),
+            vec![("".into(), 10)]
+        );
+        assert_eq!(
+            served_expiries(vec![
+                (report_record("", 20, false, false), Active),
+                (report_record("", 10, false, false), Retired),
+            ]),
+            vec![("".into(), 20)]
+        );
+
+        // Several active keys are all served.
+        assert_eq!(
+            served_expiries(vec![
+                (report_record("", 20, false, false), Active),
+                (report_record("", 10, false, false), Active),
+                (report_record("other", 5, false, false), Pending),
+            ]),
+            vec![("".into(), 20), ("".into(), 10), ("other".into(), 5)]
+        );
+    }
+
+    #[test]
+    fn select_served_reports_honors_flags() {
+        use ReportKeyState::*;
+
+        // Lost keys and revoked reports are never served.
+        assert_eq!(
+            served_expiries(vec![
+                (report_record("", 30, false, false), Lost),
+                (report_record("", 20, false, true), Active),
+                (report_record("", 10, false, false), Pending),
+            ]),
+            vec![("".into(), 10)]
+        );
+
+        // Pinned reports are served whatever the state of the other keys.
+        assert_eq!(
+            served_expiries(vec![
+                (report_record("", 20, false, false), Active),
+                (report_record("", 10, true, false), Retired),
+            ]),
+            vec![("".into(), 10)]
+        );
+        assert_eq!(
+            served_expiries(vec![
+                (report_record("", 20, false, false), Active),
+                (report_record("", 10, true, true), Retired),
+            ]),
+            vec![("".into(), 20)]
+        );
+    }
 }
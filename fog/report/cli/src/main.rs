@@ -13,6 +13,10 @@
 //! and report id, and not fully validate the pubkey, but that would require
 //! code changes in the GrpcFogPubkeyResolver object. It might make this a more
 //! useful diagnostic tool.
+//!
+//! It also has admin subcommands, which act on the reports in the recovery db
+//! configured via DATABASE_URL, so that an operator can inspect them, and pin
+//! or revoke reports while ingest keys are rotated.
 
 use grpcio::EnvBuilder;
 use mc_account_keys::{AccountKey, PublicAddress};
@@ -20,14 +24,17 @@ use mc_attest_verifier::{Verifier, DEBUG_ENCLAVE};
 use mc_common::logger::{create_root_logger, log, Logger};
 use mc_crypto_keys::{CompressedRistrettoPublic, RistrettoPublic};
 use mc_fog_api::report_parse::try_extract_unvalidated_ingress_pubkey_from_fog_report;
+use mc_fog_recovery_db_iface::ReportDb;
 use mc_fog_report_connection::{Error, GrpcFogReportConnection};
 use mc_fog_report_validation::{
     FogPubkeyResolver, FogReportResponses, FogResolver, FullyValidatedFogPubkey,
 };
+use mc_fog_sql_recovery_db::{SqlRecoveryDb, SqlRecoveryDbConnectionConfig};
 use mc_util_cli::ParserWithBuildInfo;
 use mc_util_uri::FogUri;
 use std::{
     convert::TryFrom,
+    env,
     path::PathBuf,
     process::exit,
     str::FromStr,
@@ -89,6 +96,75 @@ struct Config {
     /// and fog authority signature.
     #[clap(long, short, env = "MC_NO_VALIDATE")]
     pub no_validate: bool,
+
+    /// An admin command to run against the recovery db, instead of fetching
+    /// the report from a fog report server.
+    #[clap(subcommand)]
+    pub command: Option<AdminCommand>,
+}
+
+/// Admin commands, which act on the reports in the recovery db configured via
+/// DATABASE_URL.
+#[derive(Debug, clap::Subcommand)]
+enum AdminCommand {
+    /// Print all the reports in the database, one json object per line,
+    /// including the ones which are not served.
+    Inspect {
+        /// Postgres config
+        #[clap(flatten)]
+        postgres_config: SqlRecoveryDbConnectionConfig,
+    },
+
+    /// Pin a report, so that it is the only one served with its report id
+    /// (along with any other pinned report with that id).
+    Pin {
+        /// The report to pin.
+        #[clap(flatten)]
+        report: ReportSelector,
+
+        /// Unpin the report instead.
+        #[clap(long)]
+        unpin: bool,
+
+        /// Postgres config
+        #[clap(flatten)]
+        postgres_config: SqlRecoveryDbConnectionConfig,
+    },
+
+    /// Revoke a report, so that it is not served anymore, even if fog ingest
+    /// publishes it again.
+    Revoke {
+        /// The report to revoke.
+        #[clap(flatten)]
+        report: ReportSelector,
+
+        /// Restore the report instead.
+        #[clap(long)]
+        unrevoke: bool,
+
+        /// Postgres config
+        #[clap(flatten)]
+        postgres_config: SqlRecoveryDbConnectionConfig,
+    },
+}
+
+/// Selects a report in the recovery db.
+#[derive(Debug, clap::Args)]
+struct ReportSelector {
+    /// The fog report id of the report.
+    #[clap(long, short = 'i', default_value = "")]
+    pub report_id: String,
+
+    /// The ingress public key signed by the report, in hex.
+    #[clap(long, short = 'k', parse(try_from_str = parse_ristretto_hex))]
+    pub ingress_key: CompressedRistrettoPublic,
+}
+
+fn parse_ristretto_hex(src: &str) -> Result<CompressedRistrettoPublic, String> {
+    let mut key_bytes = [0u8; 32];
+    hex::decode_to_slice(src.as_bytes(), &mut key_bytes[..])
+        .map_err(|err| format!("Hex decode error: {:?}", err))?;
+    Ok(CompressedRistrettoPublic::from(&key_bytes))
 }
 
 /// Get fog response with retries, retrying if NoReports error occurs
@@ -155,10 +231,12 @@ fn get_unvalidated_pubkey(
     let resp = responses
         .get(&fog_uri.to_string())
         .expect("Didn't find response from this URI");
+    // Like FogResolver, prefer the report with the longest pubkey expiry
     let rep = resp
         .reports
         .iter()
-        .find(|rep| rep.fog_report_id == fog_report_id)
+        .filter(|rep| rep.fog_report_id == fog_report_id)
+        .max_by_key(|rep| rep.pubkey_expiry)
         .expect("Didn't find report with the right report id");
     let pubkey_expiry = rep.pubkey_expiry;
     // This parses the fog report and extracts the ingress key
@@ -169,12 +247,81 @@ fn get_unvalidated_pubkey(
     (pubkey, pubkey_expiry)
 }
 
+/// Connect to the recovery db configured via DATABASE_URL
+fn open_recovery_db(
+    postgres_config: &SqlRecoveryDbConnectionConfig,
+    logger: &Logger,
+) -> SqlRecoveryDb {
+    let database_url = env::var("DATABASE_URL").expect("Missing DATABASE_URL environment variable");
+    SqlRecoveryDb::new_from_url(&database_url, postgres_config.clone(), logger.clone())
+        .expect("Could not connect to the recovery db")
+}
+
+/// Run an admin command against the reports in the recovery db
+fn run_admin_command(command: &AdminCommand, logger: &Logger) {
+    match command {
+        AdminCommand::Inspect { postgres_config } => {
+            let db = open_recovery_db(postgres_config, logger);
+            for record in db
+                .get_report_records()
+                .expect("Could not get reports from the recovery db")
+            {
+                println!(
+                    "{{ \"report_id\": {:?}, \"pubkey\": \"{}\", \"pubkey_expiry\": {}, \"pinned\": {}, \"revoked\": {} }}",
+                    record.report_id,
+                    hex::encode(record.ingress_public_key.as_bytes()),
+                    record.data.pubkey_expiry,
+                    record.pinned,
+                    record.revoked
+                );
+            }
+        }
+        AdminCommand::Pin {
+            report,
+            unpin,
+            postgres_config,
+        } => {
+            let db = open_recovery_db(postgres_config, logger);
+            db.set_report_pinned(&report.ingress_key, &report.report_id, !unpin)
+                .expect("Could not set the pinned flag of the report");
+            log::info!(
+                logger,
+                "Report {:?} of ingress key {} is pinned: {}",
+                report.report_id,
+                report.ingress_key,
+                !unpin
+            );
+        }
+        AdminCommand::Revoke {
+            report,
+            unrevoke,
+            postgres_config,
+        } => {
+            let db = open_recovery_db(postgres_config, logger);
+            db.set_report_revoked(&report.ingress_key, &report.report_id, !unrevoke)
+                .expect("Could not set the revoked flag of the report");
+            log::info!(
+                logger,
+                "Report {:?} of ingress key {} is revoked: {}",
+                report.report_id,
+                report.ingress_key,
+                !unrevoke
+            );
+        }
+    }
+}
+
 fn main() {
     // Logging must go to stderr to not interfere with STDOUT
     std::env::set_var("MC_LOG_STDERR", "1");
     let config = Config::parse();
     let logger = create_root_logger();
 
+    if let Some(ref command) = config.command {
+        run_admin_command(command, &logger);
+        return;
+    }
+
     // Get public address either from a file, or synthesize from BOTH fog-url and
     // spki. If we only have fog-url, we can't make a public address and we
     // won't do any validation.
@@ -4,7 +4,7 @@
 
 use mc_common::{logger, sentry};
 use mc_fog_report_server::{Config, Materials, Server};
-use mc_fog_sql_recovery_db::SqlRecoveryDb;
+use mc_fog_sql_recovery_db::AnyRecoveryDb;
 use mc_util_cli::ParserWithBuildInfo;
 use mc_util_grpc::AdminServer;
 use std::{convert::TryFrom, env, sync::Arc};
@@ -20,7 +20,7 @@ fn main() {
     let materials = Materials::try_from(&config).expect("Could not read cryptographic materials");
 
     let database_url = env::var("DATABASE_URL").expect("DATABASE_URL environment variable missing");
-    let db = SqlRecoveryDb::new_from_url(
+    let db = AnyRecoveryDb::new_shared_from_url(
         &database_url,
         config.postgres_config.clone(),
         logger.clone(),
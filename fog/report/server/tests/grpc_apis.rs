@@ -155,3 +155,92 @@ fn report_server_grpc_tests(logger: Logger) {
     );
     assert_eq!(resp.reports[0].get_pubkey_expiry(), report2.pubkey_expiry);
 }
+
+#[test_with_logger]
+fn report_server_serves_reports_of_next_key(logger: Logger) {
+    let mut rng: StdRng = SeedableRng::from_seed([123u8; 32]);
+    let db_test_context = SqlRecoveryDbTestContext::new(logger.clone());
+
+    let db = db_test_context.get_db_instance();
+    let current_key = CompressedRistrettoPublic::from(&RistrettoPublic::from_random(&mut rng));
+    let next_key = CompressedRistrettoPublic::from(&RistrettoPublic::from_random(&mut rng));
+    db.new_ingress_key(&current_key, 1).unwrap();
+    db.new_ingress_key(&next_key, 1).unwrap();
+    db.new_ingest_invocation(None, &current_key, &random_kex_rng_pubkey(&mut rng), 1)
+        .unwrap();
+
+    let (pem_chain, signing_keypair) = mc_crypto_x509_test_vectors::ok_rsa_chain_25519_leaf();
+    let materials = Materials::from_pem_keypair(pem_chain, signing_keypair)
+        .expect("Could not parse x509 test vectors key");
+
+    let client_uri = FogUri::from_str("insecure-fog://0.0.0.0:3401").unwrap();
+    let mut server = Server::new(db, &client_uri, materials, logger.clone());
+    server.start();
+
+    let env = Arc::new(grpcio::EnvBuilder::new().build());
+
+    let report_client = {
+        let ch = ChannelBuilder::default_channel_builder(env).connect_to_uri(&client_uri, &logger);
+        report_grpc::ReportApiClient::new(ch)
+    };
+
+    let db = db_test_context.get_db_instance();
+    let current_report = ReportData {
+        ingest_invocation_id: None,
+        report: VerificationReport {
+            sig: Default::default(),
+            chain: vec![b"current".to_vec()],
+            http_body: "current body".to_string(),
+        },
+        pubkey_expiry: 100,
+    };
+    let next_report = ReportData {
+        ingest_invocation_id: None,
+        report: VerificationReport {
+            sig: Default::default(),
+            chain: vec![b"next".to_vec()],
+            http_body: "next body".to_string(),
+        },
+        pubkey_expiry: 200,
+    };
+    db.set_report(&current_key, "", &current_report).unwrap();
+    db.set_report(&next_key, "", &next_report).unwrap();
+
+    // The next key is not advertised until its ingest has started.
+    let resp = report_client
+        .get_reports(&ProtobufReportRequest::new())
+        .unwrap();
+    assert_eq!(resp.reports.len(), 1);
+    assert_eq!(
+        VerificationReport::from(resp.reports[0].get_report()),
+        current_report.report
+    );
+
+    // Then both keys are advertised, the one with the longest pubkey expiry
+    // first.
+    db.new_ingest_invocation(None, &next_key, &random_kex_rng_pubkey(&mut rng), 1)
+        .unwrap();
+    let resp = report_client
+        .get_reports(&ProtobufReportRequest::new())
+        .unwrap();
+    assert_eq!(resp.reports.len(), 2);
+    assert_eq!(
+        VerificationReport::from(resp.reports[0].get_report()),
+        next_report.report
+    );
+    assert_eq!(
+        VerificationReport::from(resp.reports[1].get_report()),
+        current_report.report
+    );
+
+    // Once the next key is revoked, only the current one is advertised.
+    db.set_report_revoked(&next_key, "", true).unwrap();
+    let resp = report_client
+        .get_reports(&ProtobufReportRequest::new())
+        .unwrap();
+    assert_eq!(resp.reports.len(), 1);
+    assert_eq!(
+        VerificationReport::from(resp.reports[0].get_report()),
+        current_report.report
+    );
+}
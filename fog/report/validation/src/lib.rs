@@ -27,11 +27,12 @@ use crate::ingest_report::IngestReportVerifier;
 use alloc::{
     collections::BTreeMap,
     string::{String, ToString},
+    vec::Vec,
 };
 use core::str::FromStr;
 use mc_account_keys::PublicAddress;
 use mc_attest_verifier::Verifier;
-use mc_fog_report_types::ReportResponse;
+use mc_fog_report_types::{Report, ReportResponse};
 use mc_fog_sig::Verifier as FogSigVerifier;
 use mc_util_uri::{FogUri, UriParseError};
 use serde::{Deserialize, Serialize};
@@ -116,20 +117,29 @@ impl FogPubkeyResolver for FogResolver {
             if let Some(result) = self.responses.get(&url) {
                 // Verify the authority signature chain
                 recipient.verify_fog_sig(result)?;
-                // Get the report corresponding to our ID
+                // Get the reports corresponding to our ID. There may be several of
+                // them while a new ingest key is rolled out, and we use the valid one
+                // with the longest pubkey expiry.
                 let report_id = recipient.fog_report_id().unwrap_or("").to_string();
-                for report in result.reports.iter() {
-                    if report_id == report.fog_report_id {
-                        let pubkey = self
-                            .verifier
-                            .validate_ingest_ias_report(report.report.clone())?;
-                        return Ok(FullyValidatedFogPubkey {
-                            pubkey,
-                            pubkey_expiry: report.pubkey_expiry,
-                        });
+                let mut last_err = None;
+                for report in reports_by_pubkey_expiry(&result.reports, &report_id) {
+                    match self
+                        .verifier
+                        .validate_ingest_ias_report(report.report.clone())
+                    {
+                        Ok(pubkey) => {
+                            return Ok(FullyValidatedFogPubkey {
+                                pubkey,
+                                pubkey_expiry: report.pubkey_expiry,
+                            })
+                        }
+                        Err(err) => last_err = Some(err),
                     }
                 }
-                Err(FogPubkeyError::NoMatchingReportId(url, report_id))
+                match last_err {
+                    Some(err) => Err(err.into()),
+                    None => Err(FogPubkeyError::NoMatchingReportId(url, report_id)),
+                }
             } else {
                 Err(FogPubkeyError::NoMatchingReportResponse(url))
             }
@@ -138,3 +148,45 @@ impl FogPubkeyResolver for FogResolver {
         }
     }
 }
+
+/// The reports with the given report id, from the longest pubkey expiry to the
+/// shortest.
+fn reports_by_pubkey_expiry<'a>(reports: &'a [Report], report_id: &str) -> Vec<&'a Report> {
+    let mut matching_reports: Vec<&Report> = reports
+        .iter()
+        .filter(|report| report.fog_report_id == report_id)
+        .collect();
+    matching_reports.sort_by(|a, b| b.pubkey_expiry.cmp(&a.pubkey_expiry));
+    matching_reports
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    fn report(fog_report_id: &str, pubkey_expiry: u64) -> Report {
+        Report {
+            fog_report_id: fog_report_id.to_string(),
+            report: Default::default(),
+            pubkey_expiry,
+        }
+    }
+
+    #[test]
+    fn reports_by_pubkey_expiry_prefers_longest_expiry() {
+        let reports = vec![
+            report("", 10),
+            report("other", 50),
+            report("", 30),
+            report("", 20),
+        ];
+        let expiries: Vec<u64> = reports_by_pubkey_expiry(&reports, "")
+            .into_iter()
+            .map(|report| report.pubkey_expiry)
+            .collect();
+        assert_eq!(expiries, vec![30, 20, 10]);
+
+        assert!(reports_by_pubkey_expiry(&reports, "missing").is_empty());
+    }
+}
@@ -0,0 +1,4 @@
+-- Copyright (c) 2018-2022 The MobileCoin Foundation
+
+DROP INDEX idx_ingested_blocks__archived;
+ALTER TABLE ingested_blocks DROP COLUMN archived;
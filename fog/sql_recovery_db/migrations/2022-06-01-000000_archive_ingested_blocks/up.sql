@@ -0,0 +1,7 @@
+-- Copyright (c) 2018-2022 The MobileCoin Foundation
+
+-- Blocks whose ETxOutRecords were moved to an archive. Their proto_ingested_block_data is emptied,
+-- but the rows are kept, since their other columns are still needed.
+ALTER TABLE ingested_blocks ADD COLUMN archived BOOLEAN NOT NULL DEFAULT FALSE;
+
+CREATE INDEX idx_ingested_blocks__archived ON ingested_blocks (ingress_public_key, block_number) WHERE archived;
@@ -0,0 +1,12 @@
+-- Copyright (c) 2018-2022 The MobileCoin Foundation
+
+ALTER TABLE reports DROP COLUMN revoked;
+ALTER TABLE reports DROP COLUMN pinned;
+
+-- Only keep the report with the largest pubkey expiry for each fog_report_id.
+DELETE FROM reports a USING reports b
+    WHERE a.fog_report_id = b.fog_report_id
+    AND (a.pubkey_expiry < b.pubkey_expiry OR (a.pubkey_expiry = b.pubkey_expiry AND a.id < b.id));
+
+ALTER TABLE reports DROP CONSTRAINT reports__fog_report_id_ingress_public_key;
+ALTER TABLE reports ADD CONSTRAINT reports_fog_report_id_key UNIQUE (fog_report_id);
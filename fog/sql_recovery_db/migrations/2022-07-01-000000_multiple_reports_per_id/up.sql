@@ -0,0 +1,13 @@
+-- Copyright (c) 2018-2022 The MobileCoin Foundation
+
+-- Allow several reports with the same fog_report_id, one per ingress key, so that the key of the next
+-- ingest cluster can be advertised while the current one is still in use.
+ALTER TABLE reports DROP CONSTRAINT reports_fog_report_id_key;
+ALTER TABLE reports ADD CONSTRAINT reports__fog_report_id_ingress_public_key UNIQUE (fog_report_id, ingress_public_key);
+
+-- Whether an operator pinned this report. If any report with a given fog_report_id is pinned, only the
+-- pinned ones are served.
+ALTER TABLE reports ADD COLUMN pinned BOOLEAN NOT NULL DEFAULT false;
+-- Whether an operator revoked this report. Revoked reports are never served, and stay revoked when
+-- ingest publishes them again.
+ALTER TABLE reports ADD COLUMN revoked BOOLEAN NOT NULL DEFAULT false;
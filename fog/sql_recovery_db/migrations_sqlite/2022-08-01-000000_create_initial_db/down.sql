@@ -0,0 +1,7 @@
+-- Copyright (c) 2018-2022 The MobileCoin Foundation
+
+DROP TABLE reports;
+DROP TABLE user_events;
+DROP TABLE ingested_blocks;
+DROP TABLE ingest_invocations;
+DROP TABLE ingress_keys;
@@ -0,0 +1,64 @@
+-- Copyright (c) 2018-2022 The MobileCoin Foundation
+
+-- The SQLite version of the fog recovery db schema. It matches the PostgreSQL schema after all the migrations in
+-- ../../migrations, see there for a description of the columns.
+--
+-- Ids use AUTOINCREMENT so that they are never reused, since clients use user event ids as cursors.
+
+CREATE TABLE ingress_keys (
+    ingress_public_key BLOB PRIMARY KEY NOT NULL,
+    start_block BIGINT NOT NULL,
+    pubkey_expiry BIGINT NOT NULL DEFAULT 0,
+    retired BOOLEAN NOT NULL DEFAULT false,
+    lost BOOLEAN NOT NULL DEFAULT false
+);
+
+CREATE TABLE ingest_invocations (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    ingress_public_key BLOB NOT NULL REFERENCES ingress_keys(ingress_public_key),
+    egress_public_key BLOB NOT NULL UNIQUE,
+    last_active_at TIMESTAMP NOT NULL,
+    start_block BIGINT NOT NULL,
+    decommissioned BOOLEAN NOT NULL DEFAULT false,
+    rng_version INTEGER NOT NULL
+);
+
+CREATE TABLE ingested_blocks (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    ingest_invocation_id BIGINT NOT NULL REFERENCES ingest_invocations(id),
+    ingress_public_key BLOB NOT NULL REFERENCES ingress_keys(ingress_public_key),
+    block_number BIGINT NOT NULL,
+    cumulative_txo_count BIGINT NOT NULL,
+    block_signature_timestamp BIGINT NOT NULL,
+    proto_ingested_block_data BLOB NOT NULL,
+    archived BOOLEAN NOT NULL DEFAULT false,
+    UNIQUE (ingest_invocation_id, block_number),
+    UNIQUE (ingress_public_key, block_number)
+);
+
+CREATE INDEX idx_ingested_blocks__block_number ON ingested_blocks (block_number);
+CREATE INDEX idx_ingested_blocks__archived ON ingested_blocks (ingress_public_key, block_number) WHERE archived;
+
+CREATE TABLE user_events (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    event_type TEXT NOT NULL CHECK (event_type IN ('new_ingest_invocation', 'decommission_ingest_invocation', 'missing_blocks')),
+    new_ingest_invocation_id BIGINT NULL UNIQUE REFERENCES ingest_invocations(id),
+    decommission_ingest_invocation_id BIGINT NULL UNIQUE REFERENCES ingest_invocations(id),
+    missing_blocks_start BIGINT NULL,
+    missing_blocks_end BIGINT NULL,
+    UNIQUE (missing_blocks_start, missing_blocks_end)
+);
+
+CREATE INDEX idx_user_events__event_type__id ON user_events (event_type, id);
+
+CREATE TABLE reports (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    ingress_public_key BLOB NOT NULL REFERENCES ingress_keys(ingress_public_key),
+    ingest_invocation_id BIGINT NULL REFERENCES ingest_invocations(id),
+    fog_report_id VARCHAR(64) NOT NULL,
+    report BLOB NOT NULL,
+    pubkey_expiry BIGINT NOT NULL,
+    pinned BOOLEAN NOT NULL DEFAULT false,
+    revoked BOOLEAN NOT NULL DEFAULT false,
+    UNIQUE (fog_report_id, ingress_public_key)
+);
@@ -0,0 +1,326 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! A recovery db whose backend is selected by the scheme of the database URL.
+
+use crate::{is_sqlite_url, Error, SqlRecoveryDb, SqlRecoveryDbConnectionConfig, SqliteRecoveryDb};
+use displaydoc::Display;
+use mc_blockchain_types::Block;
+use mc_common::logger::{log, Logger};
+use mc_crypto_keys::CompressedRistrettoPublic;
+use mc_fog_in_memory_recovery_db::{
+    is_in_memory_url, Error as InMemoryRecoveryDbError, InMemoryRecoveryDb,
+};
+use mc_fog_kex_rng::KexRngPubkey;
+use mc_fog_recovery_db_iface::{
+    AddBlockDataStatus, ArchivedBlockRange, FogUserEvent, IngestInvocationId, IngestableRange,
+    IngressPublicKeyRecord, IngressPublicKeyRecordFilters, IngressPublicKeyStatus, RecoveryDb,
+    RecoveryDbError, ReportData, ReportDb, ReportRecord,
+};
+use mc_fog_types::{common::BlockRange, view::TxOutSearchResult, ETxOutRecord};
+
+/// A recovery db backed by PostgreSQL, by a SQLite database file (with a
+/// `sqlite://<path>` database URL), or by memory (with a `memory://` database
+/// URL).
+///
+/// PostgreSQL and SQLite databases can be shared by fog servers running in
+/// separate processes, SQLite only on the same host.
+///
+/// The in-memory backend lives in the current process: each call to
+/// `new_from_url` with a `memory://` URL creates a new, empty database, and
+/// only clones of the returned instance share its data. It is meant for tests
+/// and single-process tooling which run every service against the same
+/// instance, so fog servers use `new_shared_from_url`, which rejects it.
+#[derive(Clone)]
+pub enum AnyRecoveryDb {
+    /// PostgreSQL backend
+    Sql(SqlRecoveryDb),
+    /// SQLite backend
+    Sqlite(SqliteRecoveryDb),
+    /// In-memory backend
+    InMemory(InMemoryRecoveryDb),
+}
+
+impl AnyRecoveryDb {
+    /// Create a new instance using a database URL, and connection parameters
+    /// which don't apply to the in-memory backend.
+    pub fn new_from_url(
+        database_url: &str,
+        config: SqlRecoveryDbConnectionConfig,
+        logger: Logger,
+    ) -> Result<Self, Error> {
+        if is_in_memory_url(database_url) {
+            log::warn!(
+                logger,
+                "Using an in-memory recovery db, its data is only shared with clones of this instance"
+            );
+            Ok(Self::InMemory(InMemoryRecoveryDb::new()))
+        } else if is_sqlite_url(database_url) {
+            Ok(Self::Sqlite(SqliteRecoveryDb::new_from_url(
+                database_url,
+                config,
+                logger,
+            )?))
+        } else {
+            Ok(Self::Sql(SqlRecoveryDb::new_from_url(
+                database_url,
+                config,
+                logger,
+            )?))
+        }
+    }
+
+    /// Create a new instance which other processes can share, like fog
+    /// servers do. This rejects `memory://` database URLs.
+    pub fn new_shared_from_url(
+        database_url: &str,
+        config: SqlRecoveryDbConnectionConfig,
+        logger: Logger,
+    ) -> Result<Self, Error> {
+        if is_in_memory_url(database_url) {
+            return Err(Error::InMemoryUrl(database_url.to_owned()));
+        }
+        Self::new_from_url(database_url, config, logger)
+    }
+}
+
+/// An error from a backend of an AnyRecoveryDb.
+#[derive(Display, Debug)]
+pub enum AnyRecoveryDbError {
+    /// Sql: {0}
+    Sql(Error),
+
+    /// In memory: {0}
+    InMemory(InMemoryRecoveryDbError),
+}
+
+impl RecoveryDbError for AnyRecoveryDbError {
+    fn should_retry(&self) -> bool {
+        match self {
+            Self::Sql(err) => err.should_retry(),
+            Self::InMemory(err) => err.should_retry(),
+        }
+    }
+}
+
+impl From<Error> for AnyRecoveryDbError {
+    fn from(src: Error) -> Self {
+        Self::Sql(src)
+    }
+}
+
+impl From<InMemoryRecoveryDbError> for AnyRecoveryDbError {
+    fn from(src: InMemoryRecoveryDbError) -> Self {
+        Self::InMemory(src)
+    }
+}
+
+/// Forward a call to the backend of an AnyRecoveryDb.
+macro_rules! forward {
+    ($self:ident.$method:ident($($arg:expr),*)) => {
+        match $self {
+            Self::Sql(db) => db.$method($($arg),*).map_err(AnyRecoveryDbError::from),
+            Self::Sqlite(db) => db.$method($($arg),*).map_err(AnyRecoveryDbError::from),
+            Self::InMemory(db) => db.$method($($arg),*).map_err(AnyRecoveryDbError::from),
+        }
+    };
+}
+
+/// See trait `fog_recovery_db_iface::RecoveryDb` for documentation.
+impl RecoveryDb for AnyRecoveryDb {
+    type Error = AnyRecoveryDbError;
+
+    fn get_ingress_key_status(
+        &self,
+        key: &CompressedRistrettoPublic,
+    ) -> Result<Option<IngressPublicKeyStatus>, Self::Error> {
+        forward!(self.get_ingress_key_status(key))
+    }
+
+    fn new_ingress_key(
+        &self,
+        key: &CompressedRistrettoPublic,
+        start_block_count: u64,
+    ) -> Result<u64, Self::Error> {
+        forward!(self.new_ingress_key(key, start_block_count))
+    }
+
+    fn retire_ingress_key(
+        &self,
+        key: &CompressedRistrettoPublic,
+        set_retired: bool,
+    ) -> Result<(), Self::Error> {
+        forward!(self.retire_ingress_key(key, set_retired))
+    }
+
+    fn get_last_scanned_block_index(
+        &self,
+        key: &CompressedRistrettoPublic,
+    ) -> Result<Option<u64>, Self::Error> {
+        forward!(self.get_last_scanned_block_index(key))
+    }
+
+    fn get_ingress_key_records(
+        &self,
+        start_block_at_least: u64,
+        ingress_public_key_record_filters: &IngressPublicKeyRecordFilters,
+    ) -> Result<Vec<IngressPublicKeyRecord>, Self::Error> {
+        forward!(
+            self.get_ingress_key_records(start_block_at_least, ingress_public_key_record_filters)
+        )
+    }
+
+    fn new_ingest_invocation(
+        &self,
+        prev_ingest_invocation_id: Option<IngestInvocationId>,
+        ingress_public_key: &CompressedRistrettoPublic,
+        egress_public_key: &KexRngPubkey,
+        start_block: u64,
+    ) -> Result<IngestInvocationId, Self::Error> {
+        forward!(self.new_ingest_invocation(
+            prev_ingest_invocation_id,
+            ingress_public_key,
+            egress_public_key,
+            start_block
+        ))
+    }
+
+    fn get_ingestable_ranges(&self) -> Result<Vec<IngestableRange>, Self::Error> {
+        forward!(self.get_ingestable_ranges())
+    }
+
+    fn decommission_ingest_invocation(
+        &self,
+        ingest_invocation_id: &IngestInvocationId,
+    ) -> Result<(), Self::Error> {
+        forward!(self.decommission_ingest_invocation(ingest_invocation_id))
+    }
+
+    fn add_block_data(
+        &self,
+        ingest_invocation_id: &IngestInvocationId,
+        block: &Block,
+        block_signature_timestamp: u64,
+        txs: &[ETxOutRecord],
+    ) -> Result<AddBlockDataStatus, Self::Error> {
+        forward!(self.add_block_data(ingest_invocation_id, block, block_signature_timestamp, txs))
+    }
+
+    fn report_lost_ingress_key(
+        &self,
+        lost_ingress_key: CompressedRistrettoPublic,
+    ) -> Result<(), Self::Error> {
+        forward!(self.report_lost_ingress_key(lost_ingress_key))
+    }
+
+    fn get_missed_block_ranges(&self) -> Result<Vec<BlockRange>, Self::Error> {
+        forward!(self.get_missed_block_ranges())
+    }
+
+    fn remove_missed_block_range(&self, block_range: &BlockRange) -> Result<bool, Self::Error> {
+        forward!(self.remove_missed_block_range(block_range))
+    }
+
+    fn search_user_events(
+        &self,
+        start_from_user_event_id: i64,
+    ) -> Result<(Vec<FogUserEvent>, i64), Self::Error> {
+        forward!(self.search_user_events(start_from_user_event_id))
+    }
+
+    fn get_tx_outs(
+        &self,
+        start_block: u64,
+        search_keys: &[Vec<u8>],
+    ) -> Result<Vec<TxOutSearchResult>, Self::Error> {
+        forward!(self.get_tx_outs(start_block, search_keys))
+    }
+
+    fn update_last_active_at(
+        &self,
+        ingest_invocation_id: &IngestInvocationId,
+    ) -> Result<(), Self::Error> {
+        forward!(self.update_last_active_at(ingest_invocation_id))
+    }
+
+    fn get_tx_outs_by_block_and_key(
+        &self,
+        ingress_key: CompressedRistrettoPublic,
+        block_index: u64,
+    ) -> Result<Option<Vec<ETxOutRecord>>, Self::Error> {
+        forward!(self.get_tx_outs_by_block_and_key(ingress_key, block_index))
+    }
+
+    fn get_invocation_id_by_block_and_key(
+        &self,
+        ingress_key: CompressedRistrettoPublic,
+        block_index: u64,
+    ) -> Result<Option<IngestInvocationId>, Self::Error> {
+        forward!(self.get_invocation_id_by_block_and_key(ingress_key, block_index))
+    }
+
+    fn get_cumulative_txo_count_for_block(
+        &self,
+        block_index: u64,
+    ) -> Result<Option<u64>, Self::Error> {
+        forward!(self.get_cumulative_txo_count_for_block(block_index))
+    }
+
+    fn get_block_signature_timestamp_for_block(
+        &self,
+        block_index: u64,
+    ) -> Result<Option<u64>, Self::Error> {
+        forward!(self.get_block_signature_timestamp_for_block(block_index))
+    }
+
+    fn get_highest_known_block_index(&self) -> Result<Option<u64>, Self::Error> {
+        forward!(self.get_highest_known_block_index())
+    }
+
+    fn get_archived_block_ranges(&self) -> Result<Vec<ArchivedBlockRange>, Self::Error> {
+        forward!(self.get_archived_block_ranges())
+    }
+}
+
+/// See trait `fog_recovery_db_iface::ReportDb` for documentation.
+impl ReportDb for AnyRecoveryDb {
+    type Error = AnyRecoveryDbError;
+
+    fn get_all_reports(&self) -> Result<Vec<(String, ReportData)>, Self::Error> {
+        forward!(self.get_all_reports())
+    }
+
+    fn get_report_records(&self) -> Result<Vec<ReportRecord>, Self::Error> {
+        forward!(self.get_report_records())
+    }
+
+    fn set_report(
+        &self,
+        ingress_key: &CompressedRistrettoPublic,
+        report_id: &str,
+        data: &ReportData,
+    ) -> Result<IngressPublicKeyStatus, Self::Error> {
+        forward!(self.set_report(ingress_key, report_id, data))
+    }
+
+    fn remove_report(&self, report_id: &str) -> Result<(), Self::Error> {
+        forward!(self.remove_report(report_id))
+    }
+
+    fn set_report_pinned(
+        &self,
+        ingress_key: &CompressedRistrettoPublic,
+        report_id: &str,
+        pinned: bool,
+    ) -> Result<(), Self::Error> {
+        forward!(self.set_report_pinned(ingress_key, report_id, pinned))
+    }
+
+    fn set_report_revoked(
+        &self,
+        ingress_key: &CompressedRistrettoPublic,
+        report_id: &str,
+        revoked: bool,
+    ) -> Result<(), Self::Error> {
+        forward!(self.set_report_revoked(ingress_key, report_id, revoked))
+    }
+}
@@ -0,0 +1,139 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! Moves the ETxOutRecords of old blocks of retired or lost ingress keys from
+//! the recovery db configured via DATABASE_URL to an archive directory.
+//!
+//! Runs once, or periodically as a background job when `--period` is set.
+
+use clap::Parser;
+use displaydoc::Display;
+use mc_common::logger::{create_app_logger, log, o, Logger};
+use mc_fog_recovery_db_archive::{Archive, Error as ArchiveError};
+use mc_fog_sql_recovery_db::{Error as DbError, SqlRecoveryDb, SqlRecoveryDbConnectionConfig};
+use mc_fog_types::common::BlockRange;
+use mc_util_parse::parse_duration_in_seconds;
+use std::{
+    env,
+    path::PathBuf,
+    thread::sleep,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// Configuration of the recovery db archiver.
+#[derive(Clone, Debug, Parser)]
+#[clap(version)]
+struct Config {
+    /// The directory of the archive.
+    #[clap(long, env = "MC_ARCHIVE_DIR")]
+    archive_dir: PathBuf,
+
+    /// The age (in seconds) after which the ETxOutRecords of a block can be
+    /// archived. Defaults to 90 days.
+    #[clap(long, default_value = "7776000", parse(try_from_str = parse_duration_in_seconds), env = "MC_MAX_AGE")]
+    max_age: Duration,
+
+    /// The maximum number of blocks to archive per database query.
+    #[clap(long, default_value = "1000", env = "MC_BATCH_SIZE")]
+    batch_size: usize,
+
+    /// If set, archive blocks again after each period (in seconds), instead of
+    /// exiting.
+    #[clap(long, parse(try_from_str = parse_duration_in_seconds), env = "MC_PERIOD")]
+    period: Option<Duration>,
+
+    /// Postgres config
+    #[clap(flatten)]
+    postgres_config: SqlRecoveryDbConnectionConfig,
+}
+
+/// An error while archiving blocks.
+#[derive(Display, Debug)]
+enum Error {
+    /// Recovery db: {0}
+    Db(DbError),
+
+    /// Archive: {0}
+    Archive(ArchiveError),
+}
+
+impl From<DbError> for Error {
+    fn from(src: DbError) -> Self {
+        Self::Db(src)
+    }
+}
+
+impl From<ArchiveError> for Error {
+    fn from(src: ArchiveError) -> Self {
+        Self::Archive(src)
+    }
+}
+
+fn main() {
+    let config = Config::parse();
+    let (logger, _global_logger_guard) = create_app_logger(o!());
+
+    let database_url = env::var("DATABASE_URL").expect("Missing DATABASE_URL environment variable");
+    let db = SqlRecoveryDb::new_from_url(
+        &database_url,
+        config.postgres_config.clone(),
+        logger.clone(),
+    )
+    .expect("Failed connecting to database");
+    let archive = Archive::new(&config.archive_dir);
+
+    match config.period {
+        None => {
+            let num_blocks =
+                archive_blocks(&db, &archive, &config, &logger).expect("Failed archiving blocks");
+            log::info!(logger, "Archived {} blocks", num_blocks);
+        }
+        Some(period) => loop {
+            match archive_blocks(&db, &archive, &config, &logger) {
+                Ok(num_blocks) => log::info!(logger, "Archived {} blocks", num_blocks),
+                Err(err) => log::error!(logger, "Failed archiving blocks: {}", err),
+            }
+            sleep(period);
+        },
+    }
+}
+
+/// Archive all the blocks which are old enough, returning how many were
+/// archived.
+fn archive_blocks(
+    db: &SqlRecoveryDb,
+    archive: &Archive,
+    config: &Config,
+    logger: &Logger,
+) -> Result<usize, Error> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("System time is before the unix epoch");
+    let max_block_signature_timestamp = now.saturating_sub(config.max_age).as_secs();
+
+    let mut num_blocks = 0;
+    loop {
+        let batch = db.get_archivable_blocks(max_block_signature_timestamp, config.batch_size)?;
+        if batch.is_empty() {
+            return Ok(num_blocks);
+        }
+
+        for (ingress_key, blocks) in batch {
+            let block_range = BlockRange::new(
+                blocks[0].block_index,
+                blocks[blocks.len() - 1].block_index + 1,
+            );
+            // The blocks must be durably archived before they are deleted from
+            // the database.
+            let path = archive.write_blocks(&ingress_key, &blocks)?;
+            db.mark_blocks_archived(&ingress_key, &block_range)?;
+            log::info!(
+                logger,
+                "Archived blocks {} of ingress key {} to {:?}",
+                block_range,
+                ingress_key,
+                path
+            );
+            num_blocks += blocks.len();
+        }
+    }
+}
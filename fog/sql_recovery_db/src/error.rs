@@ -1,6 +1,7 @@
 // Copyright (c) 2018-2022 The MobileCoin Foundation
 
 use diesel::{result::Error as DieselError, ConnectionError};
+use diesel_migrations::RunMigrationsError;
 use displaydoc::Display;
 use mc_crypto_keys::CompressedRistrettoPublic;
 use mc_fog_recovery_db_iface::RecoveryDbError;
@@ -20,9 +21,15 @@ pub enum Error {
     /// Connection error: {0}
     Connection(ConnectionError),
 
+    /// Migrations: {0}
+    Migrations(RunMigrationsError),
+
     /// The following ingress key was not found: {0:?}
     MissingIngressKey(CompressedRistrettoPublic),
 
+    /// The report {0:?} for the following ingress key was not found: {1:?}
+    MissingReport(String, CompressedRistrettoPublic),
+
     /// UserEvent schema violation on row #{0}: {1}
     UserEventSchemaViolation(i64, &'static str),
 
@@ -49,6 +56,15 @@ pub enum Error {
 
     /// The data could not be encoded for storage in the database: {0:?}
     Encode(EncodeError),
+
+    /**
+     * {0} selects a process-local in-memory recovery db, which fog servers
+     * can't share, so a PostgreSQL or SQLite database URL is required
+     */
+    InMemoryUrl(String),
+
+    /// Invalid SQLite database URL {0}, expected sqlite://<path>
+    InvalidSqliteUrl(String),
 }
 
 impl RecoveryDbError for Error {
@@ -58,6 +74,8 @@ impl RecoveryDbError for Error {
             Self::Orm(DieselError::DatabaseError(_, info)) => {
                 info.message() == "no connection to the server\n"
                     || info.message() == "terminating connection due to administrator command"
+                    // Another process held a SQLite database file lock for too long
+                    || info.message() == "database is locked"
             }
             Self::R2d2(_) => true,
             _ => false,
@@ -83,6 +101,12 @@ impl From<ConnectionError> for Error {
     }
 }
 
+impl From<RunMigrationsError> for Error {
+    fn from(src: RunMigrationsError) -> Self {
+        Self::Migrations(src)
+    }
+}
+
 impl From<DecodeError> for Error {
     fn from(src: DecodeError) -> Self {
         Self::Decode(src)
@@ -1,7 +1,7 @@
 // Copyright (c) 2018-2022 The MobileCoin Foundation
 #![deny(missing_docs)]
 
-//! Recovery db implementation using a PostgreSQL database backend.
+//! Recovery db implementation using a PostgreSQL or SQLite database backend.
 
 #[macro_use]
 extern crate diesel;
@@ -11,11 +11,13 @@ extern crate diesel_migrations;
 
 pub mod test_utils;
 
+mod any_recovery_db;
 mod error;
 mod models;
 mod proto_types;
 mod schema;
 mod sql_types;
+mod sqlite_recovery_db;
 
 use crate::sql_types::{SqlCompressedRistrettoPublic, UserEventType};
 use clap::Parser;
@@ -28,14 +30,16 @@ use mc_attest_core::VerificationReport;
 use mc_blockchain_types::Block;
 use mc_common::{
     logger::{log, Logger},
-    HashMap,
+    HashMap, HashSet,
 };
 use mc_crypto_keys::CompressedRistrettoPublic;
 use mc_fog_kex_rng::KexRngPubkey;
+use mc_fog_recovery_db_archive::ArchivedBlock;
 use mc_fog_recovery_db_iface::{
-    AddBlockDataStatus, FogUserEvent, IngestInvocationId, IngressPublicKeyRecord,
-    IngressPublicKeyRecordFilters, IngressPublicKeyStatus, RecoveryDb, RecoveryDbError, ReportData,
-    ReportDb,
+    select_served_reports, AddBlockDataStatus, ArchivedBlockRange, FogUserEvent,
+    IngestInvocationId, IngressPublicKeyRecord, IngressPublicKeyRecordFilters,
+    IngressPublicKeyStatus, RecoveryDb, RecoveryDbError, ReportData, ReportDb, ReportKeyState,
+    ReportRecord,
 };
 use mc_fog_types::{
     common::BlockRange,
@@ -49,7 +53,9 @@ use retry::{delay, Error as RetryError, OperationResult};
 use serde::Serialize;
 use std::{cmp::max, time::Duration};
 
+pub use any_recovery_db::{AnyRecoveryDb, AnyRecoveryDbError};
 pub use error::Error;
+pub use sqlite_recovery_db::{is_sqlite_url, SqliteRecoveryDb, SQLITE_URL_SCHEME};
 
 /// Maximum number of parameters PostgreSQL allows in a single query.
 /// The actual limit is 65535. This value is more conservative, resulting on
@@ -673,6 +679,30 @@ impl SqlRecoveryDb {
         self.get_missed_block_ranges_impl(&conn)
     }
 
+    fn remove_missed_block_range_retriable(&self, block_range: &BlockRange) -> Result<bool, Error> {
+        let conn = self.pool.get()?;
+
+        conn.build_transaction().read_write().run(|| {
+            use schema::user_events::dsl;
+            let maybe_id: Option<i64> = dsl::user_events
+                .filter(dsl::event_type.eq(UserEventType::MissingBlocks))
+                .filter(dsl::missing_blocks_start.eq(block_range.start_block as i64))
+                .filter(dsl::missing_blocks_end.eq(block_range.end_block as i64))
+                .select(dsl::id)
+                .order_by(dsl::id)
+                .first(&conn)
+                .optional()?;
+
+            match maybe_id {
+                Some(id) => {
+                    diesel::delete(dsl::user_events.filter(dsl::id.eq(id))).execute(&conn)?;
+                    Ok(true)
+                }
+                None => Ok(false),
+            }
+        })
+    }
+
     fn search_user_events_retriable(
         &self,
         start_from_user_event_id: i64,
@@ -904,7 +934,7 @@ impl SqlRecoveryDb {
     ///
     /// Returns:
     /// * The ETxOutRecord's from when this block was added, or None if the
-    ///   block doesn't exist yet or, an error
+    ///   block doesn't exist yet or was archived, or an error
     fn get_tx_outs_by_block_and_key_retriable(
         &self,
         ingress_key: CompressedRistrettoPublic,
@@ -916,6 +946,7 @@ impl SqlRecoveryDb {
         let query = schema::ingested_blocks::dsl::ingested_blocks
             .filter(schema::ingested_blocks::dsl::ingress_public_key.eq(key_bytes))
             .filter(schema::ingested_blocks::dsl::block_number.eq(block_index as i64))
+            .filter(schema::ingested_blocks::dsl::archived.eq(false))
             .select(schema::ingested_blocks::dsl::proto_ingested_block_data);
 
         // The result of load should be 0 or 1, since there is a database constraint
@@ -1026,6 +1057,150 @@ impl SqlRecoveryDb {
         SqlRecoveryDb::get_highest_known_block_index_impl(&conn)
     }
 
+    /// Get the ranges of blocks whose ETxOutRecords were archived, per ingress
+    /// key.
+    fn get_archived_block_ranges_retriable(&self) -> Result<Vec<ArchivedBlockRange>, Error> {
+        let conn = self.pool.get()?;
+
+        // Blocks are archived in order, so the archived blocks of each key are
+        // contiguous.
+        let rows: Vec<models::ArchivedBlockRange> = diesel::sql_query(
+            "SELECT ingress_public_key,
+                MIN(block_number) AS first_block,
+                MAX(block_number) AS last_block
+            FROM ingested_blocks
+            WHERE archived
+            GROUP BY ingress_public_key",
+        )
+        .load(&conn)?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| ArchivedBlockRange {
+                ingress_key: *row.ingress_public_key,
+                block_range: BlockRange::new(row.first_block as u64, row.last_block as u64 + 1),
+            })
+            .collect())
+    }
+
+    fn get_archivable_blocks_retriable(
+        &self,
+        max_block_signature_timestamp: u64,
+        max_blocks: usize,
+    ) -> Result<Vec<(CompressedRistrettoPublic, Vec<ArchivedBlock>)>, Error> {
+        let conn = self.pool.get()?;
+
+        // Only retired or lost keys stop receiving blocks once their ingest
+        // invocations are decommissioned.
+        let keys: Vec<SqlCompressedRistrettoPublic> = {
+            use schema::ingress_keys::dsl;
+            dsl::ingress_keys
+                .filter(dsl::retired.eq(true).or(dsl::lost.eq(true)))
+                .select(dsl::ingress_public_key)
+                .load(&conn)?
+        };
+
+        let mut result = Vec::new();
+        let mut num_blocks = 0;
+        for key in keys {
+            if num_blocks >= max_blocks {
+                break;
+            }
+            let key_bytes: &[u8] = key.as_ref();
+
+            // The archived blocks of a key must stay contiguous, so archiving
+            // resumes right after the last archived block.
+            let last_archived_block: Option<i64> = {
+                use schema::ingested_blocks::dsl;
+                dsl::ingested_blocks
+                    .filter(dsl::ingress_public_key.eq(key_bytes))
+                    .filter(dsl::archived.eq(true))
+                    .select(diesel::dsl::max(dsl::block_number))
+                    .first(&conn)?
+            };
+
+            let rows: Vec<(i64, i64, i64, i64, Vec<u8>, bool)> = {
+                use schema::{ingest_invocations, ingested_blocks};
+                ingested_blocks::table
+                    .inner_join(ingest_invocations::table)
+                    .filter(ingested_blocks::dsl::ingress_public_key.eq(key_bytes))
+                    .filter(ingested_blocks::dsl::archived.eq(false))
+                    .order_by(ingested_blocks::dsl::block_number)
+                    .limit((max_blocks - num_blocks) as i64)
+                    .select((
+                        ingested_blocks::dsl::ingest_invocation_id,
+                        ingested_blocks::dsl::block_number,
+                        ingested_blocks::dsl::cumulative_txo_count,
+                        ingested_blocks::dsl::block_signature_timestamp,
+                        ingested_blocks::dsl::proto_ingested_block_data,
+                        ingest_invocations::dsl::decommissioned,
+                    ))
+                    .load(&conn)?
+            };
+
+            let mut blocks = Vec::new();
+            let mut next_block_number = last_archived_block.map(|val| val + 1);
+            for (
+                ingest_invocation_id,
+                block_number,
+                cumulative_txo_count,
+                block_signature_timestamp,
+                proto_ingested_block_data,
+                decommissioned,
+            ) in rows
+            {
+                // Stop at the first block which is too recent, might still be
+                // written to, or follows a gap.
+                if !decommissioned
+                    || block_signature_timestamp as u64 >= max_block_signature_timestamp
+                    || next_block_number.map_or(false, |next| next != block_number)
+                {
+                    break;
+                }
+
+                let proto = ProtoIngestedBlockData::decode(&*proto_ingested_block_data)?;
+                blocks.push(ArchivedBlock {
+                    ingest_invocation_id,
+                    block_index: block_number as u64,
+                    cumulative_txo_count: cumulative_txo_count as u64,
+                    block_signature_timestamp: block_signature_timestamp as u64,
+                    e_tx_out_records: proto.e_tx_out_records,
+                });
+                next_block_number = Some(block_number + 1);
+            }
+
+            if !blocks.is_empty() {
+                num_blocks += blocks.len();
+                result.push((*key, blocks));
+            }
+        }
+
+        Ok(result)
+    }
+
+    fn mark_blocks_archived_retriable(
+        &self,
+        ingress_key: &CompressedRistrettoPublic,
+        block_range: &BlockRange,
+    ) -> Result<usize, Error> {
+        let conn = self.pool.get()?;
+
+        let key_bytes: &[u8] = ingress_key.as_ref();
+        use schema::ingested_blocks::dsl;
+        Ok(diesel::update(
+            dsl::ingested_blocks
+                .filter(dsl::ingress_public_key.eq(key_bytes))
+                .filter(dsl::block_number.ge(block_range.start_block as i64))
+                .filter(dsl::block_number.lt(block_range.end_block as i64))
+                .filter(dsl::archived.eq(false)),
+        )
+        .set((
+            dsl::archived.eq(true),
+            dsl::proto_ingested_block_data.eq(Vec::<u8>::new()),
+        ))
+        .execute(&conn)?)
+    }
+
     ////
     // ReportDb functions that are meant to be retriable (don't take a conn as
     // argument)
@@ -1034,29 +1209,141 @@ impl SqlRecoveryDb {
     fn get_all_reports_retriable(&self) -> Result<Vec<(String, ReportData)>, Error> {
         let conn = self.pool.get()?;
 
-        let query = schema::reports::dsl::reports
+        use schema::{ingest_invocations, ingress_keys, reports::dsl};
+
+        // The keys which an ingest invocation that is not decommissioned uses.
+        let live_keys: HashSet<SqlCompressedRistrettoPublic> = ingest_invocations::table
+            .filter(ingest_invocations::dsl::decommissioned.eq(false))
+            .select(ingest_invocations::dsl::ingress_public_key)
+            .distinct()
+            .load::<SqlCompressedRistrettoPublic>(&conn)?
+            .into_iter()
+            .collect();
+
+        let query = dsl::reports
+            .inner_join(ingress_keys::table)
+            .select((
+                dsl::fog_report_id,
+                dsl::ingress_public_key,
+                dsl::ingest_invocation_id,
+                dsl::report,
+                dsl::pubkey_expiry,
+                dsl::pinned,
+                dsl::revoked,
+                ingress_keys::dsl::retired,
+                ingress_keys::dsl::lost,
+            ))
+            .order_by((dsl::fog_report_id, dsl::pubkey_expiry.desc(), dsl::id));
+
+        let reports = query
+            .load::<(
+                String,
+                SqlCompressedRistrettoPublic,
+                Option<i64>,
+                Vec<u8>,
+                i64,
+                bool,
+                bool,
+                bool,
+                bool,
+            )>(&conn)?
+            .into_iter()
+            .map(
+                |(
+                    report_id,
+                    ingress_public_key,
+                    ingest_invocation_id,
+                    report,
+                    pubkey_expiry,
+                    pinned,
+                    revoked,
+                    retired,
+                    lost,
+                )| {
+                    let key_state = if lost {
+                        ReportKeyState::Lost
+                    } else if retired {
+                        ReportKeyState::Retired
+                    } else if live_keys.contains(&ingress_public_key) {
+                        ReportKeyState::Active
+                    } else {
+                        ReportKeyState::Pending
+                    };
+                    let report = VerificationReport::decode(&*report)?;
+                    Ok((
+                        ReportRecord {
+                            report_id,
+                            ingress_public_key: *ingress_public_key,
+                            data: ReportData {
+                                ingest_invocation_id: ingest_invocation_id
+                                    .map(IngestInvocationId::from),
+                                report,
+                                pubkey_expiry: pubkey_expiry as u64,
+                            },
+                            pinned,
+                            revoked,
+                        },
+                        key_state,
+                    ))
+                },
+            )
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        Ok(select_served_reports(reports))
+    }
+
+    fn get_report_records_retriable(&self) -> Result<Vec<ReportRecord>, Error> {
+        let conn = self.pool.get()?;
+
+        use schema::reports::dsl;
+        let query = dsl::reports
             .select((
-                schema::reports::dsl::ingest_invocation_id,
-                schema::reports::dsl::fog_report_id,
-                schema::reports::dsl::report,
-                schema::reports::dsl::pubkey_expiry,
+                dsl::fog_report_id,
+                dsl::ingress_public_key,
+                dsl::ingest_invocation_id,
+                dsl::report,
+                dsl::pubkey_expiry,
+                dsl::pinned,
+                dsl::revoked,
             ))
-            .order_by(schema::reports::dsl::id);
+            .order_by((dsl::fog_report_id, dsl::pubkey_expiry.desc(), dsl::id));
 
         query
-            .load::<(Option<i64>, String, Vec<u8>, i64)>(&conn)?
+            .load::<(
+                String,
+                SqlCompressedRistrettoPublic,
+                Option<i64>,
+                Vec<u8>,
+                i64,
+                bool,
+                bool,
+            )>(&conn)?
             .into_iter()
-            .map(|(ingest_invocation_id, report_id, report, pubkey_expiry)| {
-                let report = VerificationReport::decode(&*report)?;
-                Ok((
+            .map(
+                |(
                     report_id,
-                    ReportData {
-                        ingest_invocation_id: ingest_invocation_id.map(IngestInvocationId::from),
-                        report,
-                        pubkey_expiry: pubkey_expiry as u64,
-                    },
-                ))
-            })
+                    ingress_public_key,
+                    ingest_invocation_id,
+                    report,
+                    pubkey_expiry,
+                    pinned,
+                    revoked,
+                )| {
+                    let report = VerificationReport::decode(&*report)?;
+                    Ok(ReportRecord {
+                        report_id,
+                        ingress_public_key: *ingress_public_key,
+                        data: ReportData {
+                            ingest_invocation_id: ingest_invocation_id
+                                .map(IngestInvocationId::from),
+                            report,
+                            pubkey_expiry: pubkey_expiry as u64,
+                        },
+                        pinned,
+                        revoked,
+                    })
+                },
+            )
             .collect()
     }
 
@@ -1129,10 +1416,12 @@ impl SqlRecoveryDb {
 
                 diesel::insert_into(schema::reports::dsl::reports)
                     .values(&report)
-                    .on_conflict(schema::reports::dsl::fog_report_id)
+                    .on_conflict((
+                        schema::reports::dsl::fog_report_id,
+                        schema::reports::dsl::ingress_public_key,
+                    ))
                     .do_update()
                     .set((
-                        schema::reports::dsl::ingress_public_key.eq(report.ingress_public_key),
                         schema::reports::dsl::ingest_invocation_id.eq(report.ingest_invocation_id),
                         schema::reports::dsl::report.eq(report_bytes.clone()),
                         schema::reports::dsl::pubkey_expiry.eq(report.pubkey_expiry),
@@ -1151,6 +1440,54 @@ impl SqlRecoveryDb {
         .execute(&conn)?;
         Ok(())
     }
+
+    /// Set the pinned flag of the report with a given report id and key.
+    fn set_report_pinned_retriable(
+        &self,
+        ingress_key: &CompressedRistrettoPublic,
+        report_id: &str,
+        pinned: bool,
+    ) -> Result<(), Error> {
+        let key_bytes: &[u8] = ingress_key.as_ref();
+
+        let conn = self.pool.get()?;
+        use schema::reports::dsl;
+        let num_updated = diesel::update(
+            dsl::reports
+                .filter(dsl::fog_report_id.eq(report_id))
+                .filter(dsl::ingress_public_key.eq(key_bytes)),
+        )
+        .set(dsl::pinned.eq(pinned))
+        .execute(&conn)?;
+        if num_updated == 0 {
+            return Err(Error::MissingReport(report_id.to_owned(), *ingress_key));
+        }
+        Ok(())
+    }
+
+    /// Set the revoked flag of the report with a given report id and key.
+    fn set_report_revoked_retriable(
+        &self,
+        ingress_key: &CompressedRistrettoPublic,
+        report_id: &str,
+        revoked: bool,
+    ) -> Result<(), Error> {
+        let key_bytes: &[u8] = ingress_key.as_ref();
+
+        let conn = self.pool.get()?;
+        use schema::reports::dsl;
+        let num_updated = diesel::update(
+            dsl::reports
+                .filter(dsl::fog_report_id.eq(report_id))
+                .filter(dsl::ingress_public_key.eq(key_bytes)),
+        )
+        .set(dsl::revoked.eq(revoked))
+        .execute(&conn)?;
+        if num_updated == 0 {
+            return Err(Error::MissingReport(report_id.to_owned(), *ingress_key));
+        }
+        Ok(())
+    }
 }
 
 /// See trait `fog_recovery_db_iface::RecoveryDb` for documentation.
@@ -1281,6 +1618,12 @@ impl RecoveryDb for SqlRecoveryDb {
         })
     }
 
+    fn remove_missed_block_range(&self, block_range: &BlockRange) -> Result<bool, Self::Error> {
+        our_retry(self.get_retries(), || {
+            self.remove_missed_block_range_retriable(block_range)
+        })
+    }
+
     fn search_user_events(
         &self,
         start_from_user_event_id: i64,
@@ -1402,6 +1745,55 @@ impl RecoveryDb for SqlRecoveryDb {
             self.get_highest_known_block_index_retriable()
         })
     }
+
+    fn get_archived_block_ranges(&self) -> Result<Vec<ArchivedBlockRange>, Self::Error> {
+        our_retry(self.get_retries(), || {
+            self.get_archived_block_ranges_retriable()
+        })
+    }
+}
+
+/// Archival of old ETxOutRecords, see the fog-recovery-db-archiver binary.
+impl SqlRecoveryDb {
+    /// Get blocks whose ETxOutRecords can be moved to the archive.
+    ///
+    /// These are blocks of retired or lost ingress keys, ingested by
+    /// decommissioned ingest invocations, and signed before a cutoff. For
+    /// each key, the blocks are contiguous and directly follow the blocks
+    /// already archived.
+    ///
+    /// Arguments:
+    /// * max_block_signature_timestamp: Only blocks signed before this time (in
+    ///   seconds since the unix epoch) are returned
+    /// * max_blocks: The maximum number of blocks to return
+    ///
+    /// Returns:
+    /// * The blocks to archive, grouped by ingress key
+    pub fn get_archivable_blocks(
+        &self,
+        max_block_signature_timestamp: u64,
+        max_blocks: usize,
+    ) -> Result<Vec<(CompressedRistrettoPublic, Vec<ArchivedBlock>)>, Error> {
+        our_retry(self.get_retries(), || {
+            self.get_archivable_blocks_retriable(max_block_signature_timestamp, max_blocks)
+        })
+    }
+
+    /// Mark blocks of an ingress key as archived, deleting their ETxOutRecords
+    /// from the database. This should only be called once the blocks were
+    /// durably written to the archive.
+    ///
+    /// Returns:
+    /// * The number of blocks which were marked as archived
+    pub fn mark_blocks_archived(
+        &self,
+        ingress_key: &CompressedRistrettoPublic,
+        block_range: &BlockRange,
+    ) -> Result<usize, Error> {
+        our_retry(self.get_retries(), || {
+            self.mark_blocks_archived_retriable(ingress_key, block_range)
+        })
+    }
 }
 
 /// See trait `fog_recovery_db_iface::ReportDb` for documentation.
@@ -1412,6 +1804,10 @@ impl ReportDb for SqlRecoveryDb {
         our_retry(self.get_retries(), || self.get_all_reports_retriable())
     }
 
+    fn get_report_records(&self) -> Result<Vec<ReportRecord>, Self::Error> {
+        our_retry(self.get_retries(), || self.get_report_records_retriable())
+    }
+
     /// Set report data associated with a given report id.
     fn set_report(
         &self,
@@ -1430,6 +1826,28 @@ impl ReportDb for SqlRecoveryDb {
             self.remove_report_retriable(report_id)
         })
     }
+
+    fn set_report_pinned(
+        &self,
+        ingress_key: &CompressedRistrettoPublic,
+        report_id: &str,
+        pinned: bool,
+    ) -> Result<(), Self::Error> {
+        our_retry(self.get_retries(), || {
+            self.set_report_pinned_retriable(ingress_key, report_id, pinned)
+        })
+    }
+
+    fn set_report_revoked(
+        &self,
+        ingress_key: &CompressedRistrettoPublic,
+        report_id: &str,
+        revoked: bool,
+    ) -> Result<(), Self::Error> {
+        our_retry(self.get_retries(), || {
+            self.set_report_revoked_retriable(ingress_key, report_id, revoked)
+        })
+    }
 }
 
 // Helper for using the retry crate's retry function
@@ -2286,6 +2704,103 @@ mod tests {
         assert_eq!(tx_outs, records2);
     }
 
+    #[test_with_logger]
+    fn test_archive_blocks(logger: Logger) {
+        let mut rng: StdRng = SeedableRng::from_seed([123u8; 32]);
+        let db_test_context = test_utils::SqlRecoveryDbTestContext::new(logger);
+        let db = db_test_context.get_db_instance();
+
+        let ingress_key = CompressedRistrettoPublic::from(RistrettoPublic::from_random(&mut rng));
+        db.new_ingress_key(&ingress_key, 10).unwrap();
+
+        let invoc_id = db
+            .new_ingest_invocation(None, &ingress_key, &random_kex_rng_pubkey(&mut rng), 10)
+            .unwrap();
+
+        // Blocks 10..15 are signed at time 100..105.
+        let mut records = Vec::new();
+        for block_index in 10..15 {
+            let (block, block_records) = random_block(&mut rng, block_index, 5);
+            db.add_block_data(&invoc_id, &block, 90 + block_index, &block_records)
+                .unwrap();
+            records.push(block_records);
+        }
+
+        // Nothing can be archived while the key is in use.
+        assert!(db.get_archivable_blocks(200, 100).unwrap().is_empty());
+
+        db.retire_ingress_key(&ingress_key, true).unwrap();
+        assert!(db.get_archivable_blocks(200, 100).unwrap().is_empty());
+
+        // Once the invocation is decommissioned, blocks signed before the cutoff
+        // can be archived.
+        db.decommission_ingest_invocation(&invoc_id).unwrap();
+        let archivable = db.get_archivable_blocks(103, 100).unwrap();
+        assert_eq!(archivable.len(), 1);
+        assert_eq!(archivable[0].0, ingress_key);
+        let blocks = &archivable[0].1;
+        assert_eq!(
+            blocks
+                .iter()
+                .map(|block| block.block_index)
+                .collect::<Vec<_>>(),
+            vec![10, 11, 12]
+        );
+        for (block, block_records) in blocks.iter().zip(records.iter()) {
+            assert_eq!(block.ingest_invocation_id, *invoc_id);
+            assert_eq!(block.block_signature_timestamp, 90 + block.block_index);
+            assert_eq!(&block.e_tx_out_records, block_records);
+        }
+
+        // The batch size is respected.
+        let archivable = db.get_archivable_blocks(103, 2).unwrap();
+        assert_eq!(archivable[0].1.len(), 2);
+
+        assert_eq!(
+            db.mark_blocks_archived(&ingress_key, &BlockRange::new(10, 12))
+                .unwrap(),
+            2
+        );
+        assert_eq!(
+            db.get_archived_block_ranges().unwrap(),
+            vec![ArchivedBlockRange {
+                ingress_key,
+                block_range: BlockRange::new(10, 12),
+            }]
+        );
+
+        // Archived blocks are no longer served by the database, but their
+        // metadata is kept.
+        assert_eq!(
+            db.get_tx_outs_by_block_and_key(ingress_key, 10).unwrap(),
+            None
+        );
+        assert_eq!(
+            db.get_tx_outs_by_block_and_key(ingress_key, 12).unwrap(),
+            Some(records[2].clone())
+        );
+        assert_eq!(
+            db.get_invocation_id_by_block_and_key(ingress_key, 11)
+                .unwrap(),
+            Some(invoc_id)
+        );
+        assert_eq!(
+            db.get_last_scanned_block_index(&ingress_key).unwrap(),
+            Some(14)
+        );
+
+        // Archiving resumes after the archived blocks.
+        let archivable = db.get_archivable_blocks(200, 100).unwrap();
+        assert_eq!(
+            archivable[0]
+                .1
+                .iter()
+                .map(|block| block.block_index)
+                .collect::<Vec<_>>(),
+            vec![12, 13, 14]
+        );
+    }
+
     #[test_with_logger]
     fn test_get_highest_block_index(logger: Logger) {
         let mut rng: StdRng = SeedableRng::from_seed([123u8; 32]);
@@ -2452,6 +2967,166 @@ mod tests {
         );
     }
 
+    #[test_with_logger]
+    fn test_reports_db_multiple_keys(logger: Logger) {
+        let mut rng: StdRng = SeedableRng::from_seed([123u8; 32]);
+        let db_test_context = test_utils::SqlRecoveryDbTestContext::new(logger);
+        let db = db_test_context.get_db_instance();
+
+        let current_key = CompressedRistrettoPublic::from(RistrettoPublic::from_random(&mut rng));
+        let next_key = CompressedRistrettoPublic::from(RistrettoPublic::from_random(&mut rng));
+        db.new_ingress_key(&current_key, 10).unwrap();
+        db.new_ingress_key(&next_key, 20).unwrap();
+
+        let report_id = "";
+        let current_report = ReportData {
+            ingest_invocation_id: None,
+            report: create_report("current"),
+            pubkey_expiry: 30,
+        };
+        let next_report = ReportData {
+            ingest_invocation_id: None,
+            report: create_report("next"),
+            pubkey_expiry: 40,
+        };
+        db.set_report(&current_key, report_id, &current_report)
+            .unwrap();
+        db.set_report(&next_key, report_id, &next_report).unwrap();
+
+        // Both reports are served, the one with the longest pubkey expiry first.
+        assert_eq!(
+            db.get_all_reports().unwrap(),
+            vec![
+                (report_id.into(), next_report.clone()),
+                (report_id.into(), current_report.clone()),
+            ]
+        );
+
+        // Pinning a report hides the other ones with the same report id.
+        db.set_report_pinned(&current_key, report_id, true).unwrap();
+        assert_eq!(
+            db.get_all_reports().unwrap(),
+            vec![(report_id.into(), current_report.clone())]
+        );
+
+        // Pinned and revoked flags are kept when the report is published again.
+        let current_report = ReportData {
+            pubkey_expiry: 35,
+            ..current_report
+        };
+        db.set_report(&current_key, report_id, &current_report)
+            .unwrap();
+        db.set_report_revoked(&next_key, report_id, true).unwrap();
+        db.set_report_pinned(&current_key, report_id, false)
+            .unwrap();
+        assert_eq!(
+            db.get_all_reports().unwrap(),
+            vec![(report_id.into(), current_report.clone())]
+        );
+
+        // All the reports, including the revoked one, can still be inspected.
+        assert_eq!(
+            db.get_report_records().unwrap(),
+            vec![
+                ReportRecord {
+                    report_id: report_id.into(),
+                    ingress_public_key: next_key,
+                    data: next_report,
+                    pinned: false,
+                    revoked: true,
+                },
+                ReportRecord {
+                    report_id: report_id.into(),
+                    ingress_public_key: current_key,
+                    data: current_report.clone(),
+                    pinned: false,
+                    revoked: false,
+                },
+            ]
+        );
+
+        // The report of a retired key is still served while no other key is
+        // active, and the reports of lost keys are not.
+        db.retire_ingress_key(&current_key, true).unwrap();
+        assert_eq!(
+            db.get_all_reports().unwrap(),
+            vec![(report_id.into(), current_report)]
+        );
+        db.report_lost_ingress_key(current_key).unwrap();
+        assert_eq!(db.get_all_reports().unwrap(), vec![]);
+
+        // Flags can't be set on reports which don't exist.
+        let unknown_key = CompressedRistrettoPublic::from(RistrettoPublic::from_random(&mut rng));
+        assert!(matches!(
+            db.set_report_pinned(&unknown_key, report_id, true),
+            Err(Error::MissingReport(_, _))
+        ));
+    }
+
+    #[test_with_logger]
+    fn test_reports_db_key_rotation(logger: Logger) {
+        let mut rng: StdRng = SeedableRng::from_seed([123u8; 32]);
+        let db_test_context = test_utils::SqlRecoveryDbTestContext::new(logger);
+        let db = db_test_context.get_db_instance();
+
+        let current_key = CompressedRistrettoPublic::from(RistrettoPublic::from_random(&mut rng));
+        let next_key = CompressedRistrettoPublic::from(RistrettoPublic::from_random(&mut rng));
+        db.new_ingress_key(&current_key, 10).unwrap();
+        db.new_ingress_key(&next_key, 20).unwrap();
+        db.new_ingest_invocation(None, &current_key, &random_kex_rng_pubkey(&mut rng), 10)
+            .unwrap();
+
+        let report_id = "";
+        let current_report = ReportData {
+            ingest_invocation_id: None,
+            report: create_report("current"),
+            pubkey_expiry: 30,
+        };
+        let next_report = ReportData {
+            ingest_invocation_id: None,
+            report: create_report("next"),
+            pubkey_expiry: 40,
+        };
+        db.set_report(&current_key, report_id, &current_report)
+            .unwrap();
+        db.set_report(&next_key, report_id, &next_report).unwrap();
+
+        // The next key is not advertised until its ingest has started.
+        assert_eq!(
+            db.get_all_reports().unwrap(),
+            vec![(report_id.into(), current_report.clone())]
+        );
+
+        // Once it has started, both keys are served, the one with the longest
+        // pubkey expiry first.
+        let next_invocation_id = db
+            .new_ingest_invocation(None, &next_key, &random_kex_rng_pubkey(&mut rng), 20)
+            .unwrap();
+        assert_eq!(
+            db.get_all_reports().unwrap(),
+            vec![
+                (report_id.into(), next_report.clone()),
+                (report_id.into(), current_report.clone()),
+            ]
+        );
+
+        // Once the current key is retired, only the next one is served.
+        db.retire_ingress_key(&current_key, true).unwrap();
+        assert_eq!(
+            db.get_all_reports().unwrap(),
+            vec![(report_id.into(), next_report.clone())]
+        );
+
+        // If the ingest of the next key stops, the retired key is served again
+        // rather than a key which nothing scans with.
+        db.decommission_ingest_invocation(&next_invocation_id)
+            .unwrap();
+        assert_eq!(
+            db.get_all_reports().unwrap(),
+            vec![(report_id.into(), current_report)]
+        );
+    }
+
     #[test_with_logger]
     fn test_get_ingress_key_records(logger: Logger) {
         let mut rng: StdRng = SeedableRng::from_seed([123u8; 32]);
@@ -3169,6 +3844,79 @@ mod tests {
         assert_eq!(accepted_start_block, ingress_key_status.start_block);
     }
 
+    #[test_with_logger]
+    fn test_lost_ingress_key_covers_blocks_scanned_after_it_was_lost(logger: Logger) {
+        let db_test_context = test_utils::SqlRecoveryDbTestContext::new(logger);
+        let db = db_test_context.get_db_instance();
+        let mut rng: StdRng = SeedableRng::from_seed([123u8; 32]);
+        let ingress_key = CompressedRistrettoPublic::from(RistrettoPublic::from_random(&mut rng));
+        db.new_ingress_key(&ingress_key, 0).unwrap();
+        db.set_report(
+            &ingress_key,
+            "",
+            &ReportData {
+                pubkey_expiry: 5,
+                ingest_invocation_id: None,
+                report: Default::default(),
+            },
+        )
+        .unwrap();
+
+        // Scan blocks 0 and 1, then lose the key.
+        let invoc_id = db
+            .new_ingest_invocation(None, &ingress_key, &random_kex_rng_pubkey(&mut rng), 0)
+            .unwrap();
+        for block_index in 0..2 {
+            let (block, records) = random_block(&mut rng, block_index, 1);
+            db.add_block_data(&invoc_id, &block, 0, &records).unwrap();
+        }
+        db.decommission_ingest_invocation(&invoc_id).unwrap();
+        db.report_lost_ingress_key(ingress_key).unwrap();
+        assert_eq!(
+            db.get_missed_block_ranges().unwrap(),
+            vec![BlockRange::new(2, 5)]
+        );
+
+        let get_record = || {
+            db.get_ingress_key_records(
+                0,
+                &IngressPublicKeyRecordFilters {
+                    should_include_lost_keys: true,
+                    should_include_retired_keys: true,
+                    should_only_include_unexpired_keys: false,
+                },
+            )
+            .unwrap()
+            .into_iter()
+            .find(|record| record.key == ingress_key)
+            .unwrap()
+        };
+        assert!(get_record().covers_block_index(1));
+        assert!(!get_record().covers_block_index(2));
+
+        // Once the key is restored and scans the missed blocks, it covers them, and
+        // the missed block range can be removed.
+        let backfill_invoc_id = db
+            .new_ingest_invocation(None, &ingress_key, &random_kex_rng_pubkey(&mut rng), 2)
+            .unwrap();
+        for block_index in 2..5 {
+            let (block, records) = random_block(&mut rng, block_index, 1);
+            db.add_block_data(&backfill_invoc_id, &block, 0, &records)
+                .unwrap();
+        }
+        let record = get_record();
+        assert!(record.covers_block_index(4));
+        assert!(!record.covers_block_index(5));
+
+        assert!(db
+            .remove_missed_block_range(&BlockRange::new(2, 5))
+            .unwrap());
+        assert!(db.get_missed_block_ranges().unwrap().is_empty());
+        assert!(!db
+            .remove_missed_block_range(&BlockRange::new(2, 5))
+            .unwrap());
+    }
+
     #[test_with_logger]
     fn test_new_ingress_key_proposed_higher_than_highest_known_accepts_proposed(logger: Logger) {
         let db_test_context = test_utils::SqlRecoveryDbTestContext::new(logger);
@@ -54,6 +54,7 @@ pub struct IngestedBlock {
     pub cumulative_txo_count: i64,
     pub block_signature_timestamp: i64,
     pub proto_ingested_block_data: Vec<u8>,
+    pub archived: bool,
 }
 
 #[derive(Debug, Insertable)]
@@ -120,3 +121,13 @@ pub struct NewReport<'a> {
     pub report: &'a [u8],
     pub pubkey_expiry: i64,
 }
+
+#[derive(Debug, QueryableByName)]
+pub struct ArchivedBlockRange {
+    #[sql_type = "diesel::sql_types::Binary"]
+    pub ingress_public_key: SqlCompressedRistrettoPublic,
+    #[sql_type = "diesel::sql_types::BigInt"]
+    pub first_block: i64,
+    #[sql_type = "diesel::sql_types::BigInt"]
+    pub last_block: i64,
+}
@@ -29,6 +29,7 @@ table! {
         cumulative_txo_count -> Int8,
         block_signature_timestamp -> Int8,
         proto_ingested_block_data -> Bytea,
+        archived -> Bool,
     }
 }
 
@@ -56,6 +57,8 @@ table! {
         fog_report_id -> Varchar,
         report -> Bytea,
         pubkey_expiry -> Int8,
+        pinned -> Bool,
+        revoked -> Bool,
     }
 }
 
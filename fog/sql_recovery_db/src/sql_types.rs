@@ -5,6 +5,7 @@ use diesel::{
     deserialize::{self, FromSql},
     pg::Pg,
     serialize::{self, Output, ToSql},
+    sqlite::Sqlite,
 };
 use diesel_derive_enum::DbEnum;
 use mc_crypto_keys::CompressedRistrettoPublic;
@@ -49,11 +50,8 @@ impl fmt::Display for SqlCompressedRistrettoPublic {
     }
 }
 
-impl<DB: Backend<RawValue = [u8]>> FromSql<diesel::sql_types::Binary, DB>
-    for SqlCompressedRistrettoPublic
-{
-    fn from_sql(bytes: Option<&DB::RawValue>) -> deserialize::Result<Self> {
-        let vec = <Vec<u8> as FromSql<diesel::sql_types::Binary, Pg>>::from_sql(bytes)?;
+impl SqlCompressedRistrettoPublic {
+    fn from_sql_bytes(vec: Vec<u8>) -> deserialize::Result<Self> {
         if vec.len() != 32 {
             return Err("SqlCompressedRistrettoPublic: Invalid array length".into());
         }
@@ -67,8 +65,31 @@ impl<DB: Backend<RawValue = [u8]>> FromSql<diesel::sql_types::Binary, DB>
     }
 }
 
+impl FromSql<diesel::sql_types::Binary, Pg> for SqlCompressedRistrettoPublic {
+    fn from_sql(bytes: Option<&<Pg as Backend>::RawValue>) -> deserialize::Result<Self> {
+        Self::from_sql_bytes(<Vec<u8> as FromSql<diesel::sql_types::Binary, Pg>>::from_sql(bytes)?)
+    }
+}
+
+impl FromSql<diesel::sql_types::Binary, Sqlite> for SqlCompressedRistrettoPublic {
+    fn from_sql(bytes: Option<&<Sqlite as Backend>::RawValue>) -> deserialize::Result<Self> {
+        Self::from_sql_bytes(
+            <Vec<u8> as FromSql<diesel::sql_types::Binary, Sqlite>>::from_sql(bytes)?,
+        )
+    }
+}
+
 impl ToSql<diesel::sql_types::Binary, Pg> for SqlCompressedRistrettoPublic {
     fn to_sql<W: Write>(&self, out: &mut Output<W, Pg>) -> serialize::Result {
         <Vec<u8> as ToSql<diesel::sql_types::Binary, Pg>>::to_sql(&self.0.to_bytes().to_vec(), out)
     }
 }
+
+impl ToSql<diesel::sql_types::Binary, Sqlite> for SqlCompressedRistrettoPublic {
+    fn to_sql<W: Write>(&self, out: &mut Output<W, Sqlite>) -> serialize::Result {
+        <Vec<u8> as ToSql<diesel::sql_types::Binary, Sqlite>>::to_sql(
+            &self.0.to_bytes().to_vec(),
+            out,
+        )
+    }
+}
@@ -0,0 +1,1426 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! Recovery db implementation using a SQLite database file.
+//!
+//! Unlike the in-memory recovery db, the database file can be shared by fog
+//! servers running in separate processes on the same host, which makes it
+//! suitable for small deployments without a PostgreSQL server. Writes are
+//! serialized by SQLite, so it does not scale like PostgreSQL.
+
+use crate::{
+    models, our_retry,
+    proto_types::ProtoIngestedBlockData,
+    schema,
+    sql_types::{SqlCompressedRistrettoPublic, UserEventType},
+    Error, SqlRecoveryDbConnectionConfig,
+};
+use diesel::{
+    connection::SimpleConnection,
+    prelude::*,
+    r2d2::{self, ConnectionManager, Pool},
+    SqliteConnection,
+};
+use mc_attest_core::VerificationReport;
+use mc_blockchain_types::Block;
+use mc_common::{
+    logger::{log, Logger},
+    HashMap, HashSet,
+};
+use mc_crypto_keys::CompressedRistrettoPublic;
+use mc_fog_kex_rng::KexRngPubkey;
+use mc_fog_recovery_db_iface::{
+    select_served_reports, AddBlockDataStatus, ArchivedBlockRange, FogUserEvent,
+    IngestInvocationId, IngestableRange, IngressPublicKeyRecord, IngressPublicKeyRecordFilters,
+    IngressPublicKeyStatus, RecoveryDb, ReportData, ReportDb, ReportKeyState, ReportRecord,
+};
+use mc_fog_types::{
+    common::BlockRange,
+    view::{TxOutSearchResult, TxOutSearchResultCode},
+    ETxOutRecord,
+};
+use prost::Message;
+use retry::delay;
+use std::{cmp::max, time::Duration};
+
+embed_migrations!("migrations_sqlite/");
+
+/// The URL scheme selecting a SQLite recovery db, e.g.
+/// `sqlite:///var/lib/fog/recovery.db`.
+pub const SQLITE_URL_SCHEME: &str = "sqlite";
+
+/// How long a connection waits for another process to release its lock on
+/// the database file.
+const SQLITE_BUSY_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Check whether a database URL selects a SQLite recovery db.
+pub fn is_sqlite_url(database_url: &str) -> bool {
+    database_url
+        .split_once(':')
+        .map(|(scheme, _)| scheme == SQLITE_URL_SCHEME)
+        .unwrap_or(false)
+}
+
+/// Set up every connection to the database file so that several processes can
+/// share it.
+#[derive(Debug)]
+struct SqliteConnectionCustomizer;
+
+impl r2d2::CustomizeConnection<SqliteConnection, r2d2::Error> for SqliteConnectionCustomizer {
+    fn on_acquire(&self, conn: &mut SqliteConnection) -> Result<(), r2d2::Error> {
+        conn.batch_execute(&format!(
+            "
+            PRAGMA busy_timeout = {};   -- wait for writers in other processes
+            PRAGMA journal_mode = WAL;  -- readers don't block the writer
+            PRAGMA synchronous = NORMAL;
+            PRAGMA foreign_keys = ON;
+            ",
+            SQLITE_BUSY_TIMEOUT.as_millis()
+        ))
+        .map_err(r2d2::Error::QueryError)
+    }
+}
+
+/// SQLite-backed recovery database.
+#[derive(Clone)]
+pub struct SqliteRecoveryDb {
+    pool: Pool<ConnectionManager<SqliteConnection>>,
+    config: SqlRecoveryDbConnectionConfig,
+    logger: Logger,
+}
+
+impl SqliteRecoveryDb {
+    /// Create a new instance using a `sqlite://<path>` database URL, and
+    /// connection parameters. The database file is created if it does not
+    /// exist, and pending migrations are applied to it.
+    pub fn new_from_url(
+        database_url: &str,
+        config: SqlRecoveryDbConnectionConfig,
+        logger: Logger,
+    ) -> Result<Self, Error> {
+        let path = database_url
+            .strip_prefix("sqlite://")
+            .filter(|path| !path.is_empty())
+            .ok_or_else(|| Error::InvalidSqliteUrl(database_url.to_owned()))?;
+
+        let manager = ConnectionManager::<SqliteConnection>::new(path);
+        let pool = Pool::builder()
+            .max_size(config.postgres_max_connections)
+            .idle_timeout(Some(config.postgres_idle_timeout))
+            .max_lifetime(Some(config.postgres_max_lifetime))
+            .connection_timeout(config.postgres_connection_timeout)
+            .connection_customizer(Box::new(SqliteConnectionCustomizer))
+            .test_on_check_out(true)
+            .build(manager)?;
+
+        // Take the write lock first, so that servers starting at the same time
+        // don't both try to apply the migrations.
+        let conn = pool.get()?;
+        conn.immediate_transaction(|| embedded_migrations::run(&*conn))?;
+
+        log::info!(logger, "Opened SQLite recovery db at {}", path);
+        Ok(Self {
+            pool,
+            config,
+            logger,
+        })
+    }
+
+    // Helper function for retries config
+    fn get_retries(&self) -> Box<dyn Iterator<Item = Duration>> {
+        Box::new(
+            delay::Fixed::from_millis(self.config.postgres_retry_millis)
+                .take(self.config.postgres_retry_count)
+                .map(delay::jitter),
+        )
+    }
+
+    /// Mark a given ingest invocation as decommissioned.
+    fn decommission_ingest_invocation_impl(
+        conn: &SqliteConnection,
+        ingest_invocation_id: &IngestInvocationId,
+    ) -> Result<(), Error> {
+        // Mark the ingest invocation as decommissioned.
+        diesel::update(
+            schema::ingest_invocations::dsl::ingest_invocations
+                .filter(schema::ingest_invocations::dsl::id.eq(**ingest_invocation_id)),
+        )
+        .set((
+            schema::ingest_invocations::dsl::decommissioned.eq(true),
+            schema::ingest_invocations::dsl::last_active_at.eq(diesel::expression::dsl::now),
+        ))
+        .execute(conn)?;
+
+        // Write a user event.
+        let new_event =
+            models::NewUserEvent::decommission_ingest_invocation(**ingest_invocation_id);
+
+        diesel::insert_into(schema::user_events::table)
+            .values(&new_event)
+            .execute(conn)?;
+
+        Ok(())
+    }
+
+    /// Mark a given ingest invocation as still being alive.
+    fn update_last_active_at_impl(
+        conn: &SqliteConnection,
+        ingest_invocation_id: &IngestInvocationId,
+    ) -> Result<(), Error> {
+        diesel::update(
+            schema::ingest_invocations::dsl::ingest_invocations
+                .filter(schema::ingest_invocations::dsl::id.eq(**ingest_invocation_id)),
+        )
+        .set(schema::ingest_invocations::dsl::last_active_at.eq(diesel::expression::dsl::now))
+        .execute(conn)?;
+
+        Ok(())
+    }
+
+    fn get_ingress_key_status_impl(
+        conn: &SqliteConnection,
+        key: &CompressedRistrettoPublic,
+    ) -> Result<Option<IngressPublicKeyStatus>, Error> {
+        let key_bytes: &[u8] = key.as_ref();
+        use schema::ingress_keys::dsl;
+        let key_records: Vec<models::IngressKey> = dsl::ingress_keys
+            .filter(dsl::ingress_public_key.eq(key_bytes))
+            .load(conn)?;
+
+        if key_records.is_empty() {
+            Ok(None)
+        } else if key_records.len() == 1 {
+            Ok(Some(IngressPublicKeyStatus {
+                start_block: key_records[0].start_block as u64,
+                pubkey_expiry: key_records[0].pubkey_expiry as u64,
+                retired: key_records[0].retired,
+                lost: key_records[0].lost,
+            }))
+        } else {
+            Err(Error::IngressKeysSchemaViolation(format!(
+                "Found multiple entries for key: {:?}",
+                key
+            )))
+        }
+    }
+
+    fn get_highest_known_block_index_impl(conn: &SqliteConnection) -> Result<Option<u64>, Error> {
+        Ok(schema::ingested_blocks::dsl::ingested_blocks
+            .select(diesel::dsl::max(schema::ingested_blocks::dsl::block_number))
+            .first::<Option<i64>>(conn)?
+            .map(|val| val as u64))
+    }
+
+    fn get_last_scanned_block_index_impl(
+        conn: &SqliteConnection,
+        key_bytes: &[u8],
+    ) -> Result<Option<u64>, Error> {
+        use schema::ingested_blocks::dsl;
+        let maybe_index: Option<i64> = dsl::ingested_blocks
+            .filter(dsl::ingress_public_key.eq(key_bytes))
+            .select(diesel::dsl::max(dsl::block_number))
+            .first(conn)?;
+
+        Ok(maybe_index.map(|val| val as u64))
+    }
+
+    /// The id of the row inserted last on this connection.
+    fn last_insert_rowid(conn: &SqliteConnection) -> Result<i64, Error> {
+        Ok(
+            diesel::select(diesel::dsl::sql::<diesel::sql_types::BigInt>(
+                "last_insert_rowid()",
+            ))
+            .get_result(conn)?,
+        )
+    }
+
+    ////
+    // RecoveryDb functions that are meant to be retriable (don't take a conn as
+    // argument)
+    ////
+
+    fn get_ingress_key_status_retriable(
+        &self,
+        key: &CompressedRistrettoPublic,
+    ) -> Result<Option<IngressPublicKeyStatus>, Error> {
+        let conn = self.pool.get()?;
+        Self::get_ingress_key_status_impl(&conn, key)
+    }
+
+    fn new_ingress_key_retriable(
+        &self,
+        key: &CompressedRistrettoPublic,
+        start_block_count: u64,
+    ) -> Result<u64, Error> {
+        let conn = self.pool.get()?;
+        conn.immediate_transaction(|| -> Result<u64, Error> {
+            let highest_known_block_count: u64 = Self::get_highest_known_block_index_impl(&conn)?
+                .map(|index| index + 1)
+                .unwrap_or(0);
+
+            let accepted_start_block_count = max(start_block_count, highest_known_block_count);
+            let obj = models::NewIngressKey {
+                ingress_public_key: (*key).into(),
+                start_block: accepted_start_block_count as i64,
+                pubkey_expiry: 0,
+                retired: false,
+                lost: false,
+            };
+
+            let inserted_row_count = diesel::insert_or_ignore_into(schema::ingress_keys::table)
+                .values(&obj)
+                .execute(&conn)?;
+
+            if inserted_row_count > 0 {
+                Ok(accepted_start_block_count)
+            } else {
+                Err(Error::IngressKeyUnsuccessfulInsert(format!(
+                    "Unable to insert ingress key: {:?}",
+                    key
+                )))
+            }
+        })
+    }
+
+    fn retire_ingress_key_retriable(
+        &self,
+        key: &CompressedRistrettoPublic,
+        set_retired: bool,
+    ) -> Result<(), Error> {
+        let key_bytes: &[u8] = key.as_ref();
+
+        let conn = self.pool.get()?;
+        use schema::ingress_keys::dsl;
+        diesel::update(dsl::ingress_keys.filter(dsl::ingress_public_key.eq(key_bytes)))
+            .set(dsl::retired.eq(set_retired))
+            .execute(&conn)?;
+        Ok(())
+    }
+
+    fn get_last_scanned_block_index_retriable(
+        &self,
+        key: &CompressedRistrettoPublic,
+    ) -> Result<Option<u64>, Error> {
+        let conn = self.pool.get()?;
+        Self::get_last_scanned_block_index_impl(&conn, key.as_ref())
+    }
+
+    fn get_ingress_key_records_retriable(
+        &self,
+        start_block_at_least: u64,
+        ingress_public_key_record_filters: &IngressPublicKeyRecordFilters,
+    ) -> Result<Vec<IngressPublicKeyRecord>, Error> {
+        let conn = self.pool.get()?;
+
+        use schema::ingress_keys::dsl;
+        let last_scanned_block = diesel::dsl::sql::<diesel::sql_types::BigInt>(
+                    "(SELECT MAX(block_number) FROM ingested_blocks WHERE ingress_keys.ingress_public_key = ingested_blocks.ingress_public_key)"
+                );
+        let mut query = dsl::ingress_keys
+            .select((
+                dsl::ingress_public_key,
+                dsl::start_block,
+                dsl::pubkey_expiry,
+                dsl::retired,
+                dsl::lost,
+                last_scanned_block.clone().nullable(),
+            ))
+            .filter(dsl::start_block.ge(start_block_at_least as i64))
+            .into_boxed();
+
+        if ingress_public_key_record_filters.should_only_include_unexpired_keys {
+            query = query
+                .filter(last_scanned_block.clone().is_not_null())
+                .filter(dsl::pubkey_expiry.gt(last_scanned_block));
+        }
+        if !ingress_public_key_record_filters.should_include_lost_keys {
+            query = query.filter(dsl::lost.eq(false));
+        }
+        if !ingress_public_key_record_filters.should_include_retired_keys {
+            query = query.filter(dsl::retired.eq(false));
+        }
+
+        // The list of fields here must match the .select() clause above.
+        Ok(query
+            .load::<(
+                SqlCompressedRistrettoPublic,
+                i64,
+                i64,
+                bool,
+                bool,
+                Option<i64>,
+            )>(&conn)?
+            .into_iter()
+            .map(
+                |(
+                    ingress_public_key,
+                    start_block,
+                    pubkey_expiry,
+                    retired,
+                    lost,
+                    last_scanned_block,
+                )| IngressPublicKeyRecord {
+                    key: *ingress_public_key,
+                    status: IngressPublicKeyStatus {
+                        start_block: start_block as u64,
+                        pubkey_expiry: pubkey_expiry as u64,
+                        retired,
+                        lost,
+                    },
+                    last_scanned_block: last_scanned_block.map(|v| v as u64),
+                },
+            )
+            .collect())
+    }
+
+    fn new_ingest_invocation_retriable(
+        &self,
+        prev_ingest_invocation_id: Option<IngestInvocationId>,
+        ingress_public_key: &CompressedRistrettoPublic,
+        egress_public_key: &KexRngPubkey,
+        start_block: u64,
+    ) -> Result<IngestInvocationId, Error> {
+        let conn = self.pool.get()?;
+        conn.immediate_transaction(|| {
+            // Optionally decommission old invocation.
+            if let Some(prev_ingest_invocation_id) = prev_ingest_invocation_id {
+                Self::decommission_ingest_invocation_impl(&conn, &prev_ingest_invocation_id)?;
+            }
+
+            // Write new invocation.
+            let now =
+                diesel::select(diesel::dsl::now).get_result::<chrono::NaiveDateTime>(&conn)?;
+
+            let obj = models::NewIngestInvocation {
+                ingress_public_key: (*ingress_public_key).into(),
+                egress_public_key: egress_public_key.public_key.clone(),
+                last_active_at: now,
+                start_block: start_block as i64,
+                decommissioned: false,
+                rng_version: egress_public_key.version as i32,
+            };
+
+            diesel::insert_into(schema::ingest_invocations::table)
+                .values(&obj)
+                .execute(&conn)?;
+            let ingest_invocation_id = Self::last_insert_rowid(&conn)?;
+
+            // Write a user event.
+            let new_event = models::NewUserEvent::new_ingest_invocation(ingest_invocation_id);
+
+            diesel::insert_into(schema::user_events::table)
+                .values(&new_event)
+                .execute(&conn)?;
+
+            // Success.
+            Ok(IngestInvocationId::from(ingest_invocation_id))
+        })
+    }
+
+    fn get_ingestable_ranges_retriable(&self) -> Result<Vec<IngestableRange>, Error> {
+        let conn = self.pool.get()?;
+
+        // For each ingest invocation we are aware of get its id, start block, is
+        // decommissioned and the max block number it has ingested (if
+        // available).
+        let query = schema::ingest_invocations::dsl::ingest_invocations
+            .select((
+                schema::ingest_invocations::dsl::id,
+                schema::ingest_invocations::dsl::start_block,
+                schema::ingest_invocations::dsl::decommissioned,
+                diesel::dsl::sql::<diesel::sql_types::BigInt>(
+                    "(SELECT MAX(block_number) FROM ingested_blocks WHERE ingest_invocations.id = ingested_blocks.ingest_invocation_id)"
+                ).nullable(),
+            ))
+            .order_by(schema::ingest_invocations::dsl::id);
+
+        // The list of fields here must match the .select() clause above.
+        let data = query.load::<(i64, i64, bool, Option<i64>)>(&conn)?;
+        Ok(data
+            .into_iter()
+            .map(
+                |(ingest_invocation_id, start_block, decommissioned, last_ingested_block)| {
+                    IngestableRange {
+                        id: IngestInvocationId::from(ingest_invocation_id),
+                        start_block: start_block as u64,
+                        decommissioned,
+                        last_ingested_block: last_ingested_block.map(|v| v as u64),
+                    }
+                },
+            )
+            .collect())
+    }
+
+    fn decommission_ingest_invocation_retriable(
+        &self,
+        ingest_invocation_id: &IngestInvocationId,
+    ) -> Result<(), Error> {
+        let conn = self.pool.get()?;
+        conn.immediate_transaction(|| {
+            Self::decommission_ingest_invocation_impl(&conn, ingest_invocation_id)
+        })
+    }
+
+    fn add_block_data_retriable(
+        &self,
+        ingest_invocation_id: &IngestInvocationId,
+        block: &Block,
+        block_signature_timestamp: u64,
+        txs: &[ETxOutRecord],
+    ) -> Result<AddBlockDataStatus, Error> {
+        let conn = self.pool.get()?;
+
+        match conn.immediate_transaction(|| -> Result<(), Error> {
+            // Get ingress pubkey of this ingest invocation id, which is also stored in the
+            // ingested_block record
+            let ingress_key_bytes: Vec<u8> = schema::ingest_invocations::table
+                .filter(schema::ingest_invocations::dsl::id.eq(**ingest_invocation_id))
+                .select(schema::ingest_invocations::ingress_public_key)
+                .first(&conn)?;
+
+            // Get bytes of encoded proto ingested block data
+            let proto_bytes = {
+                let proto_ingested_block_data = ProtoIngestedBlockData {
+                    e_tx_out_records: txs.to_vec(),
+                };
+                let mut bytes = Vec::<u8>::with_capacity(proto_ingested_block_data.encoded_len());
+                proto_ingested_block_data.encode(&mut bytes)?;
+                bytes
+            };
+
+            // Add an IngestedBlock record.
+            let new_ingested_block = models::NewIngestedBlock {
+                ingress_public_key: ingress_key_bytes,
+                ingest_invocation_id: **ingest_invocation_id,
+                block_number: block.index as i64,
+                cumulative_txo_count: block.cumulative_txo_count as i64,
+                block_signature_timestamp: block_signature_timestamp as i64,
+                proto_ingested_block_data: proto_bytes,
+            };
+
+            diesel::insert_into(schema::ingested_blocks::table)
+                .values(&new_ingested_block)
+                .execute(&conn)?;
+
+            // Update last active at.
+            Self::update_last_active_at_impl(&conn, ingest_invocation_id)?;
+
+            // Success.
+            Ok(())
+        }) {
+            Ok(()) => Ok(AddBlockDataStatus {
+                block_already_scanned_with_this_key: false,
+            }),
+            // If a unique constraint is violated, the block was already scanned
+            // with this key, see SqlRecoveryDb.
+            Err(Error::Orm(diesel::result::Error::DatabaseError(
+                diesel::result::DatabaseErrorKind::UniqueViolation,
+                _,
+            ))) => Ok(AddBlockDataStatus {
+                block_already_scanned_with_this_key: true,
+            }),
+            Err(err) => Err(err),
+        }
+    }
+
+    fn report_lost_ingress_key_retriable(
+        &self,
+        lost_ingress_key: CompressedRistrettoPublic,
+    ) -> Result<(), Error> {
+        let conn = self.pool.get()?;
+
+        conn.immediate_transaction(|| {
+            // Find the ingress key and update it to be marked lost
+            let key_bytes: &[u8] = lost_ingress_key.as_ref();
+            {
+                use schema::ingress_keys::dsl;
+                diesel::update(dsl::ingress_keys.filter(dsl::ingress_public_key.eq(key_bytes)))
+                    .set(dsl::lost.eq(true))
+                    .execute(&conn)?;
+            }
+
+            // Compute a missed block range based on looking at the key status,
+            // which is correct if no blocks have actually been scanned using the key.
+            let key_status = Self::get_ingress_key_status_impl(&conn, &lost_ingress_key)?
+                .ok_or(Error::MissingIngressKey(lost_ingress_key))?;
+            let mut missed_block_range = BlockRange {
+                start_block: key_status.start_block,
+                end_block: key_status.pubkey_expiry,
+            };
+
+            // Find the last scanned block index (if any block has been scanned with this
+            // key)
+            if let Some(block_index) = Self::get_last_scanned_block_index_impl(&conn, key_bytes)? {
+                if block_index + 1 >= missed_block_range.end_block {
+                    // There aren't actually any blocks that need to be scanned, so we are done
+                    // without creating a user event.
+                    return Ok(());
+                }
+                // If we did actually scan some blocks, then report a smaller range
+                if block_index + 1 > missed_block_range.start_block {
+                    missed_block_range.start_block = block_index + 1;
+                }
+            }
+
+            // If the missed block range is invalid (empty), we don't have to add it.
+            // This can happen if the ingress key was never actually published to the report
+            // server, and then pubkey_expiry is zero.
+            if !missed_block_range.is_valid() {
+                return Ok(());
+            }
+
+            // Add new range.
+            let new_event = models::NewUserEvent::missing_blocks(&missed_block_range);
+
+            diesel::insert_into(schema::user_events::table)
+                .values(&new_event)
+                .execute(&conn)?;
+
+            Ok(())
+        })
+    }
+
+    fn get_missed_block_ranges_retriable(&self) -> Result<Vec<BlockRange>, Error> {
+        let conn = self.pool.get()?;
+
+        let rows = schema::user_events::dsl::user_events
+            .filter(schema::user_events::dsl::event_type.eq(UserEventType::MissingBlocks))
+            .select((
+                schema::user_events::dsl::id,
+                schema::user_events::dsl::missing_blocks_start,
+                schema::user_events::dsl::missing_blocks_end,
+            ))
+            .order_by(schema::user_events::dsl::id)
+            .load::<(i64, Option<i64>, Option<i64>)>(&conn)?;
+
+        rows.iter()
+            .map(|row| match row {
+                (_, Some(start_index), Some(end_index)) => {
+                    Ok(BlockRange::new(*start_index as u64, *end_index as u64))
+                }
+                (id, _, _) => Err(Error::UserEventSchemaViolation(
+                    *id,
+                    "missing start or end block indices",
+                )),
+            })
+            .collect()
+    }
+
+    fn remove_missed_block_range_retriable(&self, block_range: &BlockRange) -> Result<bool, Error> {
+        let conn = self.pool.get()?;
+
+        conn.immediate_transaction(|| {
+            use schema::user_events::dsl;
+            let maybe_id: Option<i64> = dsl::user_events
+                .filter(dsl::event_type.eq(UserEventType::MissingBlocks))
+                .filter(dsl::missing_blocks_start.eq(block_range.start_block as i64))
+                .filter(dsl::missing_blocks_end.eq(block_range.end_block as i64))
+                .select(dsl::id)
+                .order_by(dsl::id)
+                .first(&conn)
+                .optional()?;
+
+            match maybe_id {
+                Some(id) => {
+                    diesel::delete(dsl::user_events.filter(dsl::id.eq(id))).execute(&conn)?;
+                    Ok(true)
+                }
+                None => Ok(false),
+            }
+        })
+    }
+
+    fn search_user_events_retriable(
+        &self,
+        start_from_user_event_id: i64,
+    ) -> Result<(Vec<FogUserEvent>, i64), Error> {
+        // Early return if start_from_user_event_id is max
+        if start_from_user_event_id == i64::MAX {
+            return Ok((Default::default(), i64::MAX));
+        }
+
+        let conn = self.pool.get()?;
+
+        // Collect all events of interest, see SqlRecoveryDb for the details of
+        // this query.
+        let query = schema::user_events::dsl::user_events
+            .left_join(
+                schema::ingest_invocations::dsl::ingest_invocations.on(
+                    schema::user_events::dsl::new_ingest_invocation_id.eq(
+                        schema::ingest_invocations::dsl::id.nullable()
+                    )
+                )
+            )
+            .filter(schema::user_events::dsl::id.gt(start_from_user_event_id))
+            .select((
+                // Fields for every event type
+                schema::user_events::dsl::id,
+                schema::user_events::dsl::event_type,
+                // Fields for NewIngestInvocation events
+                schema::ingest_invocations::dsl::id.nullable(),
+                schema::ingest_invocations::dsl::egress_public_key.nullable(),
+                schema::ingest_invocations::dsl::rng_version.nullable(),
+                schema::ingest_invocations::dsl::start_block.nullable(),
+                // Fields for DecommissionIngestInvocation
+                schema::user_events::dsl::decommission_ingest_invocation_id,
+                diesel::dsl::sql::<diesel::sql_types::BigInt>("(SELECT COALESCE(MAX(block_number), 0) FROM ingested_blocks WHERE user_events.event_type = 'decommission_ingest_invocation' AND ingested_blocks.ingest_invocation_id = user_events.decommission_ingest_invocation_id)"),
+                // Fields for MissingBlocks events
+                schema::user_events::dsl::missing_blocks_start,
+                schema::user_events::dsl::missing_blocks_end,
+            ))
+            .order_by(schema::user_events::dsl::id);
+
+        // The list of fields here must match the .select() clause above.
+        let data = query.load::<(
+            i64,
+            UserEventType,
+            Option<i64>,
+            Option<Vec<u8>>,
+            Option<i32>,
+            Option<i64>,
+            Option<i64>,
+            i64,
+            Option<i64>,
+            Option<i64>,
+        )>(&conn)?;
+
+        // If no events are found, return start_from_user_event_id and not 0
+        let mut max_user_event_id = start_from_user_event_id;
+        let mut events = Vec::with_capacity(data.len());
+        for (
+            user_event_id,
+            user_event_type,
+            rng_record_ingest_invocation_id,
+            rng_record_egress_public_key,
+            rng_record_rng_version,
+            rng_record_start_block,
+            decommission_ingest_invocation_id,
+            decommission_ingest_invocation_max_block,
+            missing_blocks_start,
+            missing_blocks_end,
+        ) in data
+        {
+            max_user_event_id = max(max_user_event_id, user_event_id);
+
+            let missing = |field| Error::UserEventSchemaViolation(user_event_id, field);
+            events.push(match user_event_type {
+                UserEventType::NewIngestInvocation => {
+                    FogUserEvent::NewRngRecord(mc_fog_types::view::RngRecord {
+                        ingest_invocation_id: rng_record_ingest_invocation_id
+                            .ok_or_else(|| missing("missing rng_record_ingest_invocation_id"))?,
+                        pubkey: mc_fog_types::view::KexRngPubkey {
+                            public_key: rng_record_egress_public_key
+                                .ok_or_else(|| missing("missing rng_record_egress_public_key"))?,
+                            version: rng_record_rng_version
+                                .ok_or_else(|| missing("missing rng_record_rng_version"))?
+                                as u32,
+                        },
+                        start_block: rng_record_start_block
+                            .ok_or_else(|| missing("missing rng_record_start_block"))?
+                            as u64,
+                    })
+                }
+                UserEventType::DecommissionIngestInvocation => {
+                    FogUserEvent::DecommissionIngestInvocation(
+                        mc_fog_types::view::DecommissionedIngestInvocation {
+                            ingest_invocation_id: decommission_ingest_invocation_id.ok_or_else(
+                                || missing("missing decommission_ingest_invocation_id"),
+                            )?,
+                            last_ingested_block: decommission_ingest_invocation_max_block as u64,
+                        },
+                    )
+                }
+                UserEventType::MissingBlocks => FogUserEvent::MissingBlocks(BlockRange {
+                    start_block: missing_blocks_start
+                        .ok_or_else(|| missing("missing missing_blocks_start"))?
+                        as u64,
+                    end_block: missing_blocks_end
+                        .ok_or_else(|| missing("missing missing_blocks_end"))?
+                        as u64,
+                }),
+            });
+        }
+
+        Ok((events, max_user_event_id))
+    }
+
+    fn get_tx_outs_retriable(
+        &self,
+        start_block: u64,
+        search_keys: &[Vec<u8>],
+    ) -> Result<Vec<TxOutSearchResult>, Error> {
+        let conn = self.pool.get()?;
+
+        let query = schema::ingested_blocks::dsl::ingested_blocks
+            .filter(schema::ingested_blocks::dsl::block_number.ge(start_block as i64))
+            .select(schema::ingested_blocks::dsl::proto_ingested_block_data);
+
+        let mut search_key_to_payload = HashMap::<Vec<u8>, Vec<u8>>::default();
+        for proto_bytes in query.load::<Vec<u8>>(&conn)? {
+            let proto = ProtoIngestedBlockData::decode(&*proto_bytes)?;
+            for e_tx_out_record in proto.e_tx_out_records {
+                search_key_to_payload.insert(e_tx_out_record.search_key, e_tx_out_record.payload);
+            }
+        }
+
+        Ok(search_keys
+            .iter()
+            .map(|search_key| match search_key_to_payload.get(search_key) {
+                Some(payload) => TxOutSearchResult {
+                    search_key: search_key.clone(),
+                    result_code: TxOutSearchResultCode::Found as u32,
+                    ciphertext: payload.clone(),
+                },
+                None => TxOutSearchResult {
+                    search_key: search_key.clone(),
+                    result_code: TxOutSearchResultCode::NotFound as u32,
+                    ciphertext: Default::default(),
+                },
+            })
+            .collect())
+    }
+
+    fn update_last_active_at_retriable(
+        &self,
+        ingest_invocation_id: &IngestInvocationId,
+    ) -> Result<(), Error> {
+        let conn = self.pool.get()?;
+        Self::update_last_active_at_impl(&conn, ingest_invocation_id)
+    }
+
+    fn get_tx_outs_by_block_and_key_retriable(
+        &self,
+        ingress_key: CompressedRistrettoPublic,
+        block_index: u64,
+    ) -> Result<Option<Vec<ETxOutRecord>>, Error> {
+        let conn = self.pool.get()?;
+
+        let key_bytes: &[u8] = ingress_key.as_ref();
+        let protos: Vec<Vec<u8>> = schema::ingested_blocks::dsl::ingested_blocks
+            .filter(schema::ingested_blocks::dsl::ingress_public_key.eq(key_bytes))
+            .filter(schema::ingested_blocks::dsl::block_number.eq(block_index as i64))
+            .filter(schema::ingested_blocks::dsl::archived.eq(false))
+            .select(schema::ingested_blocks::dsl::proto_ingested_block_data)
+            .load(&conn)?;
+
+        // The result of load should be 0 or 1, since there is a database constraint
+        // around ingress keys and block indices
+        if protos.is_empty() {
+            Ok(None)
+        } else if protos.len() == 1 {
+            let proto = ProtoIngestedBlockData::decode(&*protos[0])?;
+            Ok(Some(proto.e_tx_out_records))
+        } else {
+            Err(Error::IngestedBlockSchemaViolation(format!("Found {} different entries for ingress_key {:?} and block_index {}, which goes against the constraint", protos.len(), ingress_key, block_index)))
+        }
+    }
+
+    fn get_invocation_id_by_block_and_key_retriable(
+        &self,
+        ingress_key: CompressedRistrettoPublic,
+        block_index: u64,
+    ) -> Result<Option<IngestInvocationId>, Error> {
+        let conn = self.pool.get()?;
+
+        let key_bytes: &[u8] = ingress_key.as_ref();
+        let iids: Vec<i64> = schema::ingested_blocks::dsl::ingested_blocks
+            .filter(schema::ingested_blocks::dsl::ingress_public_key.eq(key_bytes))
+            .filter(schema::ingested_blocks::dsl::block_number.eq(block_index as i64))
+            .select(schema::ingested_blocks::dsl::ingest_invocation_id)
+            .load(&conn)?;
+
+        // The result of load should be 0 or 1, since there is a database constraint
+        // around ingress keys and block indices
+        if iids.is_empty() {
+            Ok(None)
+        } else if iids.len() == 1 {
+            Ok(Some(iids[0].into()))
+        } else {
+            Err(Error::IngestedBlockSchemaViolation(format!("Found {} different entries for ingress_key {:?} and block_index {}, which goes against the constraint", iids.len(), ingress_key, block_index)))
+        }
+    }
+
+    fn get_cumulative_txo_count_for_block_retriable(
+        &self,
+        block_index: u64,
+    ) -> Result<Option<u64>, Error> {
+        let conn = self.pool.get()?;
+
+        let data: Vec<i64> = schema::ingested_blocks::dsl::ingested_blocks
+            .filter(schema::ingested_blocks::dsl::block_number.eq(block_index as i64))
+            .select(schema::ingested_blocks::dsl::cumulative_txo_count)
+            .load(&conn)?;
+
+        match data.first() {
+            None => Ok(None),
+            Some(cumulative_txo_count) if data.iter().all(|val| val == cumulative_txo_count) => {
+                Ok(Some(*cumulative_txo_count as u64))
+            }
+            Some(_) => Err(Error::IngestedBlockSchemaViolation(format!(
+                "Found multiple cumulative_txo_count values for block {}: {:?}",
+                block_index, data
+            ))),
+        }
+    }
+
+    fn get_block_signature_timestamp_for_block_retriable(
+        &self,
+        block_index: u64,
+    ) -> Result<Option<u64>, Error> {
+        let conn = self.pool.get()?;
+
+        let data: Vec<i64> = schema::ingested_blocks::dsl::ingested_blocks
+            .filter(schema::ingested_blocks::dsl::block_number.eq(block_index as i64))
+            .select(schema::ingested_blocks::dsl::block_signature_timestamp)
+            .load(&conn)?;
+        Ok(data.first().map(|val| *val as u64))
+    }
+
+    fn get_highest_known_block_index_retriable(&self) -> Result<Option<u64>, Error> {
+        let conn = self.pool.get()?;
+        Self::get_highest_known_block_index_impl(&conn)
+    }
+
+    fn get_archived_block_ranges_retriable(&self) -> Result<Vec<ArchivedBlockRange>, Error> {
+        let conn = self.pool.get()?;
+
+        // Blocks are archived in order, so the archived blocks of each key are
+        // contiguous.
+        let rows: Vec<models::ArchivedBlockRange> = diesel::sql_query(
+            "SELECT ingress_public_key,
+                MIN(block_number) AS first_block,
+                MAX(block_number) AS last_block
+            FROM ingested_blocks
+            WHERE archived
+            GROUP BY ingress_public_key",
+        )
+        .load(&conn)?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| ArchivedBlockRange {
+                ingress_key: *row.ingress_public_key,
+                block_range: BlockRange::new(row.first_block as u64, row.last_block as u64 + 1),
+            })
+            .collect())
+    }
+
+    ////
+    // ReportDb functions that are meant to be retriable (don't take a conn as
+    // argument)
+    ////
+
+    fn get_all_reports_retriable(&self) -> Result<Vec<(String, ReportData)>, Error> {
+        let conn = self.pool.get()?;
+
+        use schema::{ingest_invocations, ingress_keys, reports::dsl};
+
+        // The keys which an ingest invocation that is not decommissioned uses.
+        let live_keys: HashSet<SqlCompressedRistrettoPublic> = ingest_invocations::table
+            .filter(ingest_invocations::dsl::decommissioned.eq(false))
+            .select(ingest_invocations::dsl::ingress_public_key)
+            .distinct()
+            .load::<SqlCompressedRistrettoPublic>(&conn)?
+            .into_iter()
+            .collect();
+
+        let reports = dsl::reports
+            .inner_join(ingress_keys::table)
+            .select((
+                dsl::fog_report_id,
+                dsl::ingress_public_key,
+                dsl::ingest_invocation_id,
+                dsl::report,
+                dsl::pubkey_expiry,
+                dsl::pinned,
+                dsl::revoked,
+                ingress_keys::dsl::retired,
+                ingress_keys::dsl::lost,
+            ))
+            .order_by((dsl::fog_report_id, dsl::pubkey_expiry.desc(), dsl::id))
+            .load::<(
+                String,
+                SqlCompressedRistrettoPublic,
+                Option<i64>,
+                Vec<u8>,
+                i64,
+                bool,
+                bool,
+                bool,
+                bool,
+            )>(&conn)?
+            .into_iter()
+            .map(
+                |(
+                    report_id,
+                    ingress_public_key,
+                    ingest_invocation_id,
+                    report,
+                    pubkey_expiry,
+                    pinned,
+                    revoked,
+                    retired,
+                    lost,
+                )| {
+                    let key_state = if lost {
+                        ReportKeyState::Lost
+                    } else if retired {
+                        ReportKeyState::Retired
+                    } else if live_keys.contains(&ingress_public_key) {
+                        ReportKeyState::Active
+                    } else {
+                        ReportKeyState::Pending
+                    };
+                    let report = VerificationReport::decode(&*report)?;
+                    Ok((
+                        ReportRecord {
+                            report_id,
+                            ingress_public_key: *ingress_public_key,
+                            data: ReportData {
+                                ingest_invocation_id: ingest_invocation_id
+                                    .map(IngestInvocationId::from),
+                                report,
+                                pubkey_expiry: pubkey_expiry as u64,
+                            },
+                            pinned,
+                            revoked,
+                        },
+                        key_state,
+                    ))
+                },
+            )
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        Ok(select_served_reports(reports))
+    }
+
+    fn get_report_records_retriable(&self) -> Result<Vec<ReportRecord>, Error> {
+        let conn = self.pool.get()?;
+
+        use schema::reports::dsl;
+        dsl::reports
+            .select((
+                dsl::fog_report_id,
+                dsl::ingress_public_key,
+                dsl::ingest_invocation_id,
+                dsl::report,
+                dsl::pubkey_expiry,
+                dsl::pinned,
+                dsl::revoked,
+            ))
+            .order_by((dsl::fog_report_id, dsl::pubkey_expiry.desc(), dsl::id))
+            .load::<(
+                String,
+                SqlCompressedRistrettoPublic,
+                Option<i64>,
+                Vec<u8>,
+                i64,
+                bool,
+                bool,
+            )>(&conn)?
+            .into_iter()
+            .map(
+                |(
+                    report_id,
+                    ingress_public_key,
+                    ingest_invocation_id,
+                    report,
+                    pubkey_expiry,
+                    pinned,
+                    revoked,
+                )| {
+                    let report = VerificationReport::decode(&*report)?;
+                    Ok(ReportRecord {
+                        report_id,
+                        ingress_public_key: *ingress_public_key,
+                        data: ReportData {
+                            ingest_invocation_id: ingest_invocation_id
+                                .map(IngestInvocationId::from),
+                            report,
+                            pubkey_expiry: pubkey_expiry as u64,
+                        },
+                        pinned,
+                        revoked,
+                    })
+                },
+            )
+            .collect()
+    }
+
+    fn set_report_retriable(
+        &self,
+        ingress_key: &CompressedRistrettoPublic,
+        report_id: &str,
+        data: &ReportData,
+    ) -> Result<IngressPublicKeyStatus, Error> {
+        let conn = self.pool.get()?;
+        let key_bytes: &[u8] = ingress_key.as_ref();
+
+        conn.immediate_transaction(|| -> Result<IngressPublicKeyStatus, Error> {
+            // First, try to update the pubkey_expiry value on this ingress key, only
+            // allowing it to increase, and only if it is not retired
+            {
+                use schema::ingress_keys::dsl;
+                diesel::update(
+                    dsl::ingress_keys
+                        .filter(dsl::ingress_public_key.eq(key_bytes))
+                        .filter(dsl::retired.eq(false))
+                        .filter(dsl::pubkey_expiry.lt(data.pubkey_expiry as i64)),
+                )
+                .set(dsl::pubkey_expiry.eq(data.pubkey_expiry as i64))
+                .execute(&conn)?;
+            }
+
+            let result = Self::get_ingress_key_status_impl(&conn, ingress_key)?
+                .ok_or(Error::MissingIngressKey(*ingress_key))?;
+
+            log::info!(self.logger, "Got status for key: {:?}", result);
+            if result.retired {
+                log::info!(self.logger, "Cannot publish key because it is retired");
+                return Ok(result);
+            }
+
+            let mut report_bytes = Vec::with_capacity(data.report.encoded_len());
+            data.report.encode(&mut report_bytes)?;
+
+            // Update the report if there is one for this report id and key, and
+            // otherwise insert it, leaving the pinned and revoked flags unchanged.
+            use schema::reports::dsl;
+            let num_updated = diesel::update(
+                dsl::reports
+                    .filter(dsl::fog_report_id.eq(report_id))
+                    .filter(dsl::ingress_public_key.eq(key_bytes)),
+            )
+            .set((
+                dsl::ingest_invocation_id.eq(data.ingest_invocation_id.map(i64::from)),
+                dsl::report.eq(report_bytes.as_slice()),
+                dsl::pubkey_expiry.eq(data.pubkey_expiry as i64),
+            ))
+            .execute(&conn)?;
+
+            if num_updated == 0 {
+                let report = models::NewReport {
+                    ingress_public_key: key_bytes,
+                    ingest_invocation_id: data.ingest_invocation_id.map(i64::from),
+                    fog_report_id: report_id,
+                    report: report_bytes.as_slice(),
+                    pubkey_expiry: data.pubkey_expiry as i64,
+                };
+                diesel::insert_into(dsl::reports)
+                    .values(&report)
+                    .execute(&conn)?;
+            }
+            Ok(result)
+        })
+    }
+
+    fn remove_report_retriable(&self, report_id: &str) -> Result<(), Error> {
+        let conn = self.pool.get()?;
+        diesel::delete(
+            schema::reports::dsl::reports.filter(schema::reports::dsl::fog_report_id.eq(report_id)),
+        )
+        .execute(&conn)?;
+        Ok(())
+    }
+
+    fn set_report_pinned_retriable(
+        &self,
+        ingress_key: &CompressedRistrettoPublic,
+        report_id: &str,
+        pinned: bool,
+    ) -> Result<(), Error> {
+        let key_bytes: &[u8] = ingress_key.as_ref();
+
+        let conn = self.pool.get()?;
+        use schema::reports::dsl;
+        let num_updated = diesel::update(
+            dsl::reports
+                .filter(dsl::fog_report_id.eq(report_id))
+                .filter(dsl::ingress_public_key.eq(key_bytes)),
+        )
+        .set(dsl::pinned.eq(pinned))
+        .execute(&conn)?;
+        if num_updated == 0 {
+            return Err(Error::MissingReport(report_id.to_owned(), *ingress_key));
+        }
+        Ok(())
+    }
+
+    fn set_report_revoked_retriable(
+        &self,
+        ingress_key: &CompressedRistrettoPublic,
+        report_id: &str,
+        revoked: bool,
+    ) -> Result<(), Error> {
+        let key_bytes: &[u8] = ingress_key.as_ref();
+
+        let conn = self.pool.get()?;
+        use schema::reports::dsl;
+        let num_updated = diesel::update(
+            dsl::reports
+                .filter(dsl::fog_report_id.eq(report_id))
+                .filter(dsl::ingress_public_key.eq(key_bytes)),
+        )
+        .set(dsl::revoked.eq(revoked))
+        .execute(&conn)?;
+        if num_updated == 0 {
+            return Err(Error::MissingReport(report_id.to_owned(), *ingress_key));
+        }
+        Ok(())
+    }
+}
+
+/// See trait `fog_recovery_db_iface::RecoveryDb` for documentation.
+impl RecoveryDb for SqliteRecoveryDb {
+    type Error = Error;
+
+    fn get_ingress_key_status(
+        &self,
+        key: &CompressedRistrettoPublic,
+    ) -> Result<Option<IngressPublicKeyStatus>, Self::Error> {
+        our_retry(self.get_retries(), || {
+            self.get_ingress_key_status_retriable(key)
+        })
+    }
+
+    fn new_ingress_key(
+        &self,
+        key: &CompressedRistrettoPublic,
+        start_block_count: u64,
+    ) -> Result<u64, Self::Error> {
+        our_retry(self.get_retries(), || {
+            self.new_ingress_key_retriable(key, start_block_count)
+        })
+    }
+
+    fn retire_ingress_key(
+        &self,
+        key: &CompressedRistrettoPublic,
+        set_retired: bool,
+    ) -> Result<(), Self::Error> {
+        our_retry(self.get_retries(), || {
+            self.retire_ingress_key_retriable(key, set_retired)
+        })
+    }
+
+    fn get_last_scanned_block_index(
+        &self,
+        key: &CompressedRistrettoPublic,
+    ) -> Result<Option<u64>, Self::Error> {
+        our_retry(self.get_retries(), || {
+            self.get_last_scanned_block_index_retriable(key)
+        })
+    }
+
+    fn get_ingress_key_records(
+        &self,
+        start_block_at_least: u64,
+        ingress_public_key_record_filters: &IngressPublicKeyRecordFilters,
+    ) -> Result<Vec<IngressPublicKeyRecord>, Self::Error> {
+        our_retry(self.get_retries(), || {
+            self.get_ingress_key_records_retriable(
+                start_block_at_least,
+                ingress_public_key_record_filters,
+            )
+        })
+    }
+
+    fn new_ingest_invocation(
+        &self,
+        prev_ingest_invocation_id: Option<IngestInvocationId>,
+        ingress_public_key: &CompressedRistrettoPublic,
+        egress_public_key: &KexRngPubkey,
+        start_block: u64,
+    ) -> Result<IngestInvocationId, Self::Error> {
+        our_retry(self.get_retries(), || {
+            self.new_ingest_invocation_retriable(
+                prev_ingest_invocation_id,
+                ingress_public_key,
+                egress_public_key,
+                start_block,
+            )
+        })
+    }
+
+    fn get_ingestable_ranges(&self) -> Result<Vec<IngestableRange>, Self::Error> {
+        our_retry(self.get_retries(), || {
+            self.get_ingestable_ranges_retriable()
+        })
+    }
+
+    fn decommission_ingest_invocation(
+        &self,
+        ingest_invocation_id: &IngestInvocationId,
+    ) -> Result<(), Self::Error> {
+        our_retry(self.get_retries(), || {
+            self.decommission_ingest_invocation_retriable(ingest_invocation_id)
+        })
+    }
+
+    fn add_block_data(
+        &self,
+        ingest_invocation_id: &IngestInvocationId,
+        block: &Block,
+        block_signature_timestamp: u64,
+        txs: &[ETxOutRecord],
+    ) -> Result<AddBlockDataStatus, Self::Error> {
+        our_retry(self.get_retries(), || {
+            self.add_block_data_retriable(
+                ingest_invocation_id,
+                block,
+                block_signature_timestamp,
+                txs,
+            )
+        })
+    }
+
+    fn report_lost_ingress_key(
+        &self,
+        lost_ingress_key: CompressedRistrettoPublic,
+    ) -> Result<(), Self::Error> {
+        our_retry(self.get_retries(), || {
+            self.report_lost_ingress_key_retriable(lost_ingress_key)
+        })
+    }
+
+    fn get_missed_block_ranges(&self) -> Result<Vec<BlockRange>, Self::Error> {
+        our_retry(self.get_retries(), || {
+            self.get_missed_block_ranges_retriable()
+        })
+    }
+
+    fn remove_missed_block_range(&self, block_range: &BlockRange) -> Result<bool, Self::Error> {
+        our_retry(self.get_retries(), || {
+            self.remove_missed_block_range_retriable(block_range)
+        })
+    }
+
+    fn search_user_events(
+        &self,
+        start_from_user_event_id: i64,
+    ) -> Result<(Vec<FogUserEvent>, i64), Self::Error> {
+        our_retry(self.get_retries(), || {
+            self.search_user_events_retriable(start_from_user_event_id)
+        })
+    }
+
+    fn get_tx_outs(
+        &self,
+        start_block: u64,
+        search_keys: &[Vec<u8>],
+    ) -> Result<Vec<TxOutSearchResult>, Self::Error> {
+        our_retry(self.get_retries(), || {
+            self.get_tx_outs_retriable(start_block, search_keys)
+        })
+    }
+
+    fn update_last_active_at(
+        &self,
+        ingest_invocation_id: &IngestInvocationId,
+    ) -> Result<(), Self::Error> {
+        our_retry(self.get_retries(), || {
+            self.update_last_active_at_retriable(ingest_invocation_id)
+        })
+    }
+
+    fn get_tx_outs_by_block_and_key(
+        &self,
+        ingress_key: CompressedRistrettoPublic,
+        block_index: u64,
+    ) -> Result<Option<Vec<ETxOutRecord>>, Self::Error> {
+        our_retry(self.get_retries(), || {
+            self.get_tx_outs_by_block_and_key_retriable(ingress_key, block_index)
+        })
+    }
+
+    fn get_invocation_id_by_block_and_key(
+        &self,
+        ingress_key: CompressedRistrettoPublic,
+        block_index: u64,
+    ) -> Result<Option<IngestInvocationId>, Self::Error> {
+        our_retry(self.get_retries(), || {
+            self.get_invocation_id_by_block_and_key_retriable(ingress_key, block_index)
+        })
+    }
+
+    fn get_cumulative_txo_count_for_block(
+        &self,
+        block_index: u64,
+    ) -> Result<Option<u64>, Self::Error> {
+        our_retry(self.get_retries(), || {
+            self.get_cumulative_txo_count_for_block_retriable(block_index)
+        })
+    }
+
+    fn get_block_signature_timestamp_for_block(
+        &self,
+        block_index: u64,
+    ) -> Result<Option<u64>, Self::Error> {
+        our_retry(self.get_retries(), || {
+            self.get_block_signature_timestamp_for_block_retriable(block_index)
+        })
+    }
+
+    fn get_highest_known_block_index(&self) -> Result<Option<u64>, Self::Error> {
+        our_retry(self.get_retries(), || {
+            self.get_highest_known_block_index_retriable()
+        })
+    }
+
+    fn get_archived_block_ranges(&self) -> Result<Vec<ArchivedBlockRange>, Self::Error> {
+        our_retry(self.get_retries(), || {
+            self.get_archived_block_ranges_retriable()
+        })
+    }
+}
+
+/// See trait `fog_recovery_db_iface::ReportDb` for documentation.
+impl ReportDb for SqliteRecoveryDb {
+    type Error = Error;
+
+    fn get_all_reports(&self) -> Result<Vec<(String, ReportData)>, Self::Error> {
+        our_retry(self.get_retries(), || self.get_all_reports_retriable())
+    }
+
+    fn get_report_records(&self) -> Result<Vec<ReportRecord>, Self::Error> {
+        our_retry(self.get_retries(), || self.get_report_records_retriable())
+    }
+
+    fn set_report(
+        &self,
+        ingress_key: &CompressedRistrettoPublic,
+        report_id: &str,
+        data: &ReportData,
+    ) -> Result<IngressPublicKeyStatus, Self::Error> {
+        our_retry(self.get_retries(), || {
+            self.set_report_retriable(ingress_key, report_id, data)
+        })
+    }
+
+    fn remove_report(&self, report_id: &str) -> Result<(), Self::Error> {
+        our_retry(self.get_retries(), || {
+            self.remove_report_retriable(report_id)
+        })
+    }
+
+    fn set_report_pinned(
+        &self,
+        ingress_key: &CompressedRistrettoPublic,
+        report_id: &str,
+        pinned: bool,
+    ) -> Result<(), Self::Error> {
+        our_retry(self.get_retries(), || {
+            self.set_report_pinned_retriable(ingress_key, report_id, pinned)
+        })
+    }
+
+    fn set_report_revoked(
+        &self,
+        ingress_key: &CompressedRistrettoPublic,
+        report_id: &str,
+        revoked: bool,
+    ) -> Result<(), Self::Error> {
+        our_retry(self.get_retries(), || {
+            self.set_report_revoked_retriable(ingress_key, report_id, revoked)
+        })
+    }
+}
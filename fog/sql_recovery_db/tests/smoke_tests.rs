@@ -1,8 +1,22 @@
 // Copyright (c) 2018-2022 The MobileCoin Foundation
 
 use mc_common::logger::{test_with_logger, Logger};
-use mc_fog_sql_recovery_db::test_utils::SqlRecoveryDbTestContext;
+use mc_crypto_keys::{CompressedRistrettoPublic, RistrettoPublic};
+use mc_fog_recovery_db_iface::RecoveryDb;
+use mc_fog_sql_recovery_db::{test_utils::SqlRecoveryDbTestContext, AnyRecoveryDb, Error};
 use mc_fog_test_infra::db_tests::*;
+use mc_util_from_random::FromRandom;
+use tempdir::TempDir;
+
+/// Open a SQLite recovery db in a new temporary directory.
+fn sqlite_recovery_db(logger: &Logger) -> (TempDir, String, AnyRecoveryDb) {
+    let dir = TempDir::new("fog_recovery_db").expect("Could not make tempdir for recovery db");
+    let url = format!("sqlite://{}", dir.path().join("recovery.db").display());
+    let db = AnyRecoveryDb::new_shared_from_url(&url, Default::default(), logger.clone())
+        .expect("Could not create SQLite recovery db");
+    assert!(matches!(db, AnyRecoveryDb::Sqlite(_)));
+    (dir, url, db)
+}
 
 #[test_with_logger]
 fn sql_recovery_db_smoke_tests_new_apis(logger: Logger) {
@@ -43,3 +57,83 @@ fn sql_recovery_db_ingress_keys(logger: Logger) {
         test_recovery_db_ingress_keys(&mut rng, &db);
     })
 }
+
+#[test_with_logger]
+fn any_recovery_db_in_memory_smoke_tests_new_apis(logger: Logger) {
+    mc_util_test_helper::run_with_several_seeds(|mut rng| {
+        let db = AnyRecoveryDb::new_from_url("memory://", Default::default(), logger.clone())
+            .expect("Could not create in-memory recovery db");
+        assert!(matches!(db, AnyRecoveryDb::InMemory(_)));
+
+        recovery_db_smoke_tests_new_apis(&mut rng, &db);
+    })
+}
+
+#[test_with_logger]
+fn any_recovery_db_shared_rejects_in_memory_url(logger: Logger) {
+    assert!(matches!(
+        AnyRecoveryDb::new_shared_from_url("memory://", Default::default(), logger),
+        Err(Error::InMemoryUrl(_))
+    ));
+}
+
+#[test_with_logger]
+fn any_recovery_db_sqlite_smoke_tests_new_apis(logger: Logger) {
+    mc_util_test_helper::run_with_several_seeds(|mut rng| {
+        let (_dir, _url, db) = sqlite_recovery_db(&logger);
+
+        recovery_db_smoke_tests_new_apis(&mut rng, &db);
+    })
+}
+
+#[test_with_logger]
+fn any_recovery_db_sqlite_missed_blocks_reporting(logger: Logger) {
+    mc_util_test_helper::run_with_several_seeds(|mut rng| {
+        let (_dir, _url, db) = sqlite_recovery_db(&logger);
+
+        recovery_db_missed_blocks_reporting(&mut rng, &db);
+    })
+}
+
+#[test_with_logger]
+fn any_recovery_db_sqlite_rng_records_decommissioning(logger: Logger) {
+    mc_util_test_helper::run_with_several_seeds(|mut rng| {
+        let (_dir, _url, db) = sqlite_recovery_db(&logger);
+
+        recovery_db_rng_records_decommissioning(&mut rng, &db);
+    })
+}
+
+#[test_with_logger]
+fn any_recovery_db_sqlite_ingress_keys(logger: Logger) {
+    mc_util_test_helper::run_with_several_seeds(|mut rng| {
+        let (_dir, _url, db) = sqlite_recovery_db(&logger);
+
+        test_recovery_db_ingress_keys(&mut rng, &db);
+    })
+}
+
+// Separate instances opened on the same file, like fog servers in separate
+// processes, see each other's data.
+#[test_with_logger]
+fn any_recovery_db_sqlite_is_shared(logger: Logger) {
+    mc_util_test_helper::run_with_several_seeds(|mut rng| {
+        let (_dir, url, db) = sqlite_recovery_db(&logger);
+        let other_db = AnyRecoveryDb::new_shared_from_url(&url, Default::default(), logger.clone())
+            .expect("Could not open SQLite recovery db again");
+
+        let ingress_key = CompressedRistrettoPublic::from(RistrettoPublic::from_random(&mut rng));
+        db.new_ingress_key(&ingress_key, 10).unwrap();
+        let invocation_id = other_db
+            .new_ingest_invocation(None, &ingress_key, &random_kex_rng_pubkey(&mut rng), 10)
+            .unwrap();
+
+        let ranges = db.get_ingestable_ranges().unwrap();
+        assert_eq!(ranges.len(), 1);
+        assert_eq!(ranges[0].id, invocation_id);
+        assert_eq!(
+            other_db.get_ingress_key_status(&ingress_key).unwrap(),
+            db.get_ingress_key_status(&ingress_key).unwrap()
+        );
+    })
+}
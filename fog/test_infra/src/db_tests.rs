@@ -9,7 +9,10 @@ use mc_fog_recovery_db_iface::{
     ETxOutRecord, FogUserEvent, IngestInvocationId, IngressPublicKeyStatus, RecoveryDb, ReportData,
     ReportDb,
 };
-use mc_fog_types::view::{RngRecord, TxOutSearchResultCode};
+use mc_fog_types::{
+    common::BlockRange,
+    view::{RngRecord, TxOutSearchResultCode},
+};
 use mc_util_from_random::FromRandom;
 use rand_core::{CryptoRng, RngCore};
 
@@ -229,6 +232,34 @@ pub fn recovery_db_missed_blocks_reporting(
             .any(|range| range.start_block == 15 && range.end_block == 25),
         "Didn't find a missed block range that we expected to find"
     );
+
+    // Remove the first range, as if its blocks were scanned after the fact
+    assert!(db
+        .remove_missed_block_range(&BlockRange::new(10, 20))
+        .unwrap());
+    assert!(!db
+        .remove_missed_block_range(&BlockRange::new(10, 20))
+        .unwrap());
+
+    let missed_block_ranges = db.get_missed_block_ranges().unwrap();
+    assert!(
+        !missed_block_ranges
+            .iter()
+            .any(|range| range.start_block == 10 && range.end_block == 20),
+        "Found a missed block range that was removed"
+    );
+    assert!(
+        missed_block_ranges
+            .iter()
+            .any(|range| range.start_block == 15 && range.end_block == 25),
+        "Didn't find a missed block range that we expected to find"
+    );
+
+    let (user_events, _) = db.search_user_events(0).unwrap();
+    assert!(!user_events.iter().any(|event| matches!(
+        event,
+        FogUserEvent::MissingBlocks(range) if range.start_block == 10 && range.end_block == 20
+    )));
 }
 
 /// Basic tests that rng records decommissioning works as expected
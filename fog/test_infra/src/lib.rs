@@ -218,3 +218,24 @@ pub fn test_polling_recovery<C: FogViewConnection>(users: &mut UserPool, view: &
         assert_eq!(result, expected_result);
     }
 }
+
+/// Throw all the user phones in a pool, restore them knowing that they could
+/// not receive TxOuts before account_start_block, and check that they recover
+/// all their TxOuts from that block on via the standard polling API
+pub fn test_polling_recovery_from_block<C: FogViewConnection>(
+    users: &mut UserPool,
+    view: &mut C,
+    account_start_block: u64,
+) {
+    let zero_checkpoint = users.get_zero_checkpoint();
+    let expected_result = users.compute_delta_from_block(&zero_checkpoint, account_start_block);
+    users.restore_user_phones_from_block(account_start_block);
+    users.poll(view);
+    {
+        let result = users.compute_delta_from_block(&zero_checkpoint, account_start_block);
+        assert_eq!(result, expected_result);
+    }
+    // Recover all the transactions again, so that the users can keep being tested
+    users.trash_user_phones();
+    users.poll(view);
+}
@@ -2,7 +2,7 @@
 
 //! Mock Fog client
 
-use mc_fog_recovery_db_iface::{FogUserEvent, RecoveryDb};
+use mc_fog_recovery_db_iface::{filter_user_events_for_account, FogUserEvent, RecoveryDb};
 use mc_fog_types::view::QueryResponse;
 use mc_fog_view_protocol::FogViewConnection;
 
@@ -29,10 +29,12 @@ impl<R: RecoveryDb> FogViewConnection for PassThroughViewClient<R> {
         &mut self,
         start_from_user_event_id: i64,
         start_from_block_index: u64,
+        account_start_block: u64,
         search_keys: Vec<Vec<u8>>,
     ) -> Result<QueryResponse, Self::Error> {
         let (user_events, next_start_from_user_event_id) =
             self.db.search_user_events(start_from_user_event_id)?;
+        let user_events = filter_user_events_for_account(user_events, account_start_block);
 
         let highest_known_block_count = self
             .db
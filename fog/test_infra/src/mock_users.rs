@@ -148,6 +148,17 @@ impl UserPool {
         }
     }
 
+    /// Trash all user tx's and rng states, and restore the users knowing that
+    /// they could not receive TxOuts before account_start_block
+    pub fn restore_user_phones_from_block(&mut self, account_start_block: u64) {
+        for pair in self.users.iter_mut() {
+            pair.1 = UserData {
+                rngs: UserRngSet::new_from_account_start_block(account_start_block),
+                txs: Default::default(),
+            }
+        }
+    }
+
     /// Get a checkpoint
     pub fn get_checkpoint(&self) -> Checkpoint {
         self.users
@@ -185,6 +196,21 @@ impl UserPool {
             .collect()
     }
 
+    /// Compute a delta of current state against a checkpoint, only counting
+    /// txos in or after the given block
+    pub fn compute_delta_from_block(&self, checkpoint: &Checkpoint, block_index: u64) -> Delta {
+        self.compute_delta(checkpoint)
+            .into_iter()
+            .filter_map(|(upriv, txos)| {
+                let txos: HashSet<TxOutRecord> = txos
+                    .into_iter()
+                    .filter(|txo| txo.block_index >= block_index)
+                    .collect();
+                (!txos.is_empty()).then(|| (upriv, txos))
+            })
+            .collect()
+    }
+
     /// Make a random test block, to be submitted as a block to ingest
     pub fn random_test_block<T: RngCore + CryptoRng>(
         &self,
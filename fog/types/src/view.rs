@@ -34,6 +34,12 @@ pub struct QueryRequestAAD {
     // TODO this is currently unused
     #[prost(uint64, tag = "2")]
     pub start_from_block_index: u64,
+
+    /// The first block in which the account could have received TxOuts. If
+    /// this is larger than 0, events which only concern earlier blocks are
+    /// omitted from the response.
+    #[prost(uint64, tag = "3")]
+    pub account_start_block: u64,
 }
 
 /// The QueryRequest structure, which should be passed as the encrypted data
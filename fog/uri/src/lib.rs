@@ -18,6 +18,20 @@ impl UriScheme for FogViewScheme {
     const DEFAULT_INSECURE_PORT: u16 = 3225;
 }
 
+/// Fog View Store Uri Scheme
+#[derive(Debug, Hash, Ord, PartialOrd, Eq, PartialEq, Clone)]
+pub struct FogViewStoreScheme {}
+
+impl UriScheme for FogViewStoreScheme {
+    /// The part before the '://' of a URL.
+    const SCHEME_SECURE: &'static str = "fog-view-store";
+    const SCHEME_INSECURE: &'static str = "insecure-fog-view-store";
+
+    /// Default port numbers
+    const DEFAULT_SECURE_PORT: u16 = 443;
+    const DEFAULT_INSECURE_PORT: u16 = 3227;
+}
+
 /// Fog Ledger Uri Scheme
 #[derive(Debug, Hash, Ord, PartialOrd, Eq, PartialEq, Clone)]
 pub struct FogLedgerScheme {}
@@ -32,6 +46,20 @@ impl UriScheme for FogLedgerScheme {
     const DEFAULT_INSECURE_PORT: u16 = 3223;
 }
 
+/// Fog Ledger Key Image Store Uri Scheme
+#[derive(Debug, Hash, Ord, PartialOrd, Eq, PartialEq, Clone)]
+pub struct KeyImageStoreScheme {}
+
+impl UriScheme for KeyImageStoreScheme {
+    /// The part before the '://' of a URL.
+    const SCHEME_SECURE: &'static str = "key-image-store";
+    const SCHEME_INSECURE: &'static str = "insecure-key-image-store";
+
+    /// Default port numbers
+    const DEFAULT_SECURE_PORT: u16 = 443;
+    const DEFAULT_INSECURE_PORT: u16 = 3228;
+}
+
 /// Fog Ingest Uri Scheme
 #[derive(Debug, Hash, Ord, PartialOrd, Eq, PartialEq, Clone)]
 pub struct FogIngestScheme {}
@@ -62,9 +90,15 @@ impl UriScheme for IngestPeerScheme {
 /// Uri used when talking to fog-view service, with the right default ports and
 /// scheme.
 pub type FogViewUri = Uri<FogViewScheme>;
+/// Uri used when a fog-view router talks to a fog-view store shard, with the
+/// right default ports and scheme.
+pub type FogViewStoreUri = Uri<FogViewStoreScheme>;
 /// Uri used when talking to fog-ledger service, with the right default ports
 /// and scheme.
 pub type FogLedgerUri = Uri<FogLedgerScheme>;
+/// Uri used when a fog-ledger router talks to a key image store shard, with
+/// the right default ports and scheme.
+pub type KeyImageStoreUri = Uri<KeyImageStoreScheme>;
 /// Uri used when talking to fog-ingest service, with the right default ports
 /// and scheme.
 pub type FogIngestUri = Uri<FogIngestScheme>;
@@ -73,7 +107,7 @@ pub type IngestPeerUri = Uri<IngestPeerScheme>;
 
 #[cfg(test)]
 mod tests {
-    use super::{FogLedgerUri, FogViewUri};
+    use super::{FogLedgerUri, FogViewStoreUri, FogViewUri, KeyImageStoreUri};
     use crate::ConnectionUri;
     use core::str::FromStr;
     use mc_common::ResponderId;
@@ -231,6 +265,51 @@ mod tests {
         assert!(FogViewUri::from_str("fog-view://    /").is_err());
     }
 
+    #[test]
+    fn test_valid_fog_view_store_uris() {
+        let uri = FogViewStoreUri::from_str("fog-view-store://node1.test.mobilecoin.com/").unwrap();
+        assert_eq!(uri.addr(), "node1.test.mobilecoin.com:443");
+        assert!(uri.use_tls());
+
+        let uri = FogViewStoreUri::from_str("insecure-fog-view-store://127.0.0.1/").unwrap();
+        assert_eq!(uri.addr(), "127.0.0.1:3227");
+        assert!(!uri.use_tls());
+
+        // The responder id of a store is the client responder id of the shard.
+        let uri = FogViewStoreUri::from_str(
+            "insecure-fog-view-store://127.0.0.1:3227/?responder-id=shard1.test.mobilecoin.com:443",
+        )
+        .unwrap();
+        assert_eq!(uri.addr(), "127.0.0.1:3227");
+        assert_eq!(
+            uri.responder_id().unwrap(),
+            ResponderId::from_str("shard1.test.mobilecoin.com:443").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_valid_key_image_store_uris() {
+        let uri =
+            KeyImageStoreUri::from_str("key-image-store://node1.test.mobilecoin.com/").unwrap();
+        assert_eq!(uri.addr(), "node1.test.mobilecoin.com:443");
+        assert!(uri.use_tls());
+
+        let uri = KeyImageStoreUri::from_str("insecure-key-image-store://127.0.0.1/").unwrap();
+        assert_eq!(uri.addr(), "127.0.0.1:3228");
+        assert!(!uri.use_tls());
+
+        // The responder id of a store is the client responder id of the shard.
+        let uri = KeyImageStoreUri::from_str(
+            "insecure-key-image-store://127.0.0.1:3228/?responder-id=shard1.test.mobilecoin.com:443",
+        )
+        .unwrap();
+        assert_eq!(uri.addr(), "127.0.0.1:3228");
+        assert_eq!(
+            uri.responder_id().unwrap(),
+            ResponderId::from_str("shard1.test.mobilecoin.com:443").unwrap()
+        );
+    }
+
     #[test]
     fn test_fog_view_tls_override() {
         assert_eq!(
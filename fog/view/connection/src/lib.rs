@@ -71,6 +71,7 @@ impl FogViewConnection for FogViewGrpcClient {
         &mut self,
         start_from_user_event_id: i64,
         start_from_block_index: u64,
+        account_start_block: u64,
         search_keys: Vec<Vec<u8>>,
     ) -> Result<QueryResponse, Self::Error> {
         tracer!().in_span("fog_view_grpc_request", |_cx_| {
@@ -78,9 +79,10 @@ impl FogViewConnection for FogViewGrpcClient {
 
             log::trace!(
                 self.logger,
-                "request: start_from_user_event_id={} start_from_block_index={} num_search_keys={}",
+                "request: start_from_user_event_id={} start_from_block_index={} account_start_block={} num_search_keys={}",
                 start_from_user_event_id,
                 start_from_block_index,
+                account_start_block,
                 search_keys.len()
             );
 
@@ -91,6 +93,7 @@ impl FogViewConnection for FogViewGrpcClient {
             let req_aad = QueryRequestAAD {
                 start_from_user_event_id,
                 start_from_block_index,
+                account_start_block,
             };
 
             let aad_bytes = mc_util_serial::encode(&req_aad);
@@ -14,7 +14,7 @@ use displaydoc::Display;
 use mc_attest_core::{Quote, Report, SgxError, TargetInfo, VerificationReport};
 use mc_attest_enclave_api::{
     ClientAuthRequest, ClientAuthResponse, ClientSession, EnclaveMessage,
-    Error as AttestEnclaveError,
+    Error as AttestEnclaveError, PeerAuthRequest, PeerAuthResponse, PeerSession,
 };
 use mc_common::ResponderId;
 use mc_crypto_keys::X25519Public;
@@ -83,6 +83,27 @@ pub enum ViewEnclaveRequest {
     Query(EnclaveMessage<ClientSession>, UntrustedQueryResponse),
     /// Request from untrusted to add encrypted tx out records to ORAM
     AddRecords(Vec<ETxOutRecord>),
+
+    // Sharding related
+    /// Begin an attested connection to a view store, as a router
+    ViewStoreInit(ResponderId),
+    /// Complete an attested connection to a view store, as a router
+    ViewStoreConnect(ResponderId, PeerAuthResponse),
+    /// Accept an attested connection from a router, as a view store
+    RouterAccept(PeerAuthRequest),
+    /// Decrypt a client's QueryRequest and encrypt it for each of the given
+    /// view store sessions, as a router
+    CreateViewStoreQueries(EnclaveMessage<ClientSession>, Vec<PeerSession>),
+    /// An encrypted fog_types::view::QueryRequest from a router
+    /// Respond with the fog_types::view::TxOutSearchResult's of this store
+    QueryStore(EnclaveMessage<PeerSession>),
+    /// Merge the responses of the view stores into a client's
+    /// fog_types::view::QueryResponse, as a router
+    CollateViewStoreResponses(
+        ClientSession,
+        UntrustedQueryResponse,
+        Vec<EnclaveMessage<PeerSession>>,
+    ),
 }
 
 /// The parameters needed to initialize the view enclave
@@ -134,6 +155,50 @@ pub trait ViewEnclaveApi: ReportableEnclave {
     /// Add encrypted tx out records from the fog recovery db to the view
     /// enclave's ORAM
     fn add_records(&self, records: Vec<ETxOutRecord>) -> Result<()>;
+
+    // SHARDING
+    //
+    // A router enclave holds no records. It decrypts client queries, forwards
+    // them to view store enclaves over attested peer sessions, and merges the
+    // TxOutSearchResult's they return.
+
+    /// Begin an attested connection to the view store with the given
+    /// responder id
+    fn view_store_init(&self, view_store_id: ResponderId) -> Result<PeerAuthRequest>;
+
+    /// Complete an attested connection to the view store with the given
+    /// responder id
+    fn view_store_connect(
+        &self,
+        view_store_id: ResponderId,
+        view_store_auth_response: PeerAuthResponse,
+    ) -> Result<PeerSession>;
+
+    /// Accept an attested connection from a router
+    fn router_accept(&self, req: PeerAuthRequest) -> Result<(PeerAuthResponse, PeerSession)>;
+
+    /// Decrypt a client's encrypted QueryRequest and re-encrypt it for each of
+    /// the given view store sessions. The results are in the same order as
+    /// the sessions.
+    fn create_view_store_queries(
+        &self,
+        client_query: EnclaveMessage<ClientSession>,
+        view_store_sessions: Vec<PeerSession>,
+    ) -> Result<Vec<EnclaveMessage<PeerSession>>>;
+
+    /// Service a router's encrypted QueryRequest, returning an encrypted
+    /// QueryResponse which only holds this store's TxOutSearchResult's
+    fn query_store(&self, msg: EnclaveMessage<PeerSession>) -> Result<EnclaveMessage<PeerSession>>;
+
+    /// Merge the encrypted responses of the view stores to a query created by
+    /// create_view_store_queries into a QueryResponse encrypted for the
+    /// client
+    fn collate_view_store_responses(
+        &self,
+        client_session: ClientSession,
+        untrusted_query_response: UntrustedQueryResponse,
+        view_store_responses: Vec<EnclaveMessage<PeerSession>>,
+    ) -> Result<Vec<u8>>;
 }
 
 /// Helper trait which reduces boiler-plate in untrusted side
@@ -187,6 +252,8 @@ pub enum Error {
     Poison,
     /// Enclave not initialized
     EnclaveNotInitialized,
+    /// View store responses do not match the query
+    ViewStoreResponseMismatch,
 }
 
 impl From<SgxError> for Error {
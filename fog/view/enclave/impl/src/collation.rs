@@ -0,0 +1,210 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! Merging of the TxOutSearchResult's returned by the view stores of a router.
+//!
+//! Each view store holds the records of a distinct block range, so at most one
+//! of them finds the record for a given search key. The merge is done without
+//! branching on the result codes, so that the host does not learn which store
+//! holds the record.
+
+use crate::e_tx_out_store::ValueSize;
+use aligned_cmov::{
+    subtle::{ConditionallySelectable, ConstantTimeEq},
+    typenum::Unsigned,
+    A8Bytes, CMov,
+};
+use alloc::vec::Vec;
+use mc_fog_types::view::{TxOutSearchResult, TxOutSearchResultCode};
+use mc_fog_view_enclave_api::Error;
+
+/// Merge the TxOutSearchResult's of several view stores, which were queried
+/// for the same search keys in the same order.
+pub fn collate_tx_out_search_results(
+    view_store_results: Vec<Vec<TxOutSearchResult>>,
+) -> Result<Vec<TxOutSearchResult>, Error> {
+    let mut view_store_results = view_store_results.into_iter();
+    let mut collated = match view_store_results.next() {
+        Some(results) => results
+            .into_iter()
+            .map(PaddedSearchResult::new)
+            .collect::<Result<Vec<_>, _>>()?,
+        None => return Ok(Vec::new()),
+    };
+
+    for results in view_store_results {
+        if results.len() != collated.len() {
+            return Err(Error::ViewStoreResponseMismatch);
+        }
+
+        for (collated_result, result) in collated.iter_mut().zip(results) {
+            if collated_result.search_key != result.search_key {
+                return Err(Error::ViewStoreResponseMismatch);
+            }
+            merge_result(collated_result, PaddedSearchResult::new(result)?);
+        }
+    }
+
+    Ok(collated
+        .into_iter()
+        .map(PaddedSearchResult::into_search_result)
+        .collect())
+}
+
+/// A TxOutSearchResult whose ciphertext is padded to a fixed length, so that
+/// results of different view stores can be merged without branching on their
+/// ciphertext lengths.
+///
+/// Stores pad the ciphertexts they return to the size of the last record they
+/// stored, which differs from store to store. The padded ciphertext holds the
+/// ciphertext length in its first byte, followed by the ciphertext and zeros.
+struct PaddedSearchResult {
+    search_key: Vec<u8>,
+    result_code: u32,
+    ciphertext: A8Bytes<ValueSize>,
+}
+
+impl PaddedSearchResult {
+    /// The largest ciphertext a view store returns.
+    const MAX_CIPHERTEXT_LEN: usize = ValueSize::USIZE - 1;
+
+    fn new(result: TxOutSearchResult) -> Result<Self, Error> {
+        let len = result.ciphertext.len();
+        if len > Self::MAX_CIPHERTEXT_LEN {
+            return Err(Error::ViewStoreResponseMismatch);
+        }
+
+        let mut ciphertext = A8Bytes::<ValueSize>::default();
+        ciphertext[0] = len as u8;
+        ciphertext[1..=len].copy_from_slice(&result.ciphertext);
+
+        Ok(Self {
+            search_key: result.search_key,
+            result_code: result.result_code,
+            ciphertext,
+        })
+    }
+
+    fn into_search_result(self) -> TxOutSearchResult {
+        let len = self.ciphertext[0] as usize;
+        TxOutSearchResult {
+            search_key: self.search_key,
+            result_code: self.result_code,
+            ciphertext: self.ciphertext[1..=len].to_vec(),
+        }
+    }
+}
+
+/// Replace `collated_result` with `result` if `result` was found, in constant
+/// time.
+fn merge_result(collated_result: &mut PaddedSearchResult, result: PaddedSearchResult) {
+    let found = result
+        .result_code
+        .ct_eq(&(TxOutSearchResultCode::Found as u32));
+
+    collated_result
+        .result_code
+        .conditional_assign(&result.result_code, found);
+    collated_result.ciphertext.cmov(found, &result.ciphertext);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    fn search_result(
+        search_key: u8,
+        code: TxOutSearchResultCode,
+        payload: u8,
+    ) -> TxOutSearchResult {
+        sized_search_result(search_key, code, payload, 8)
+    }
+
+    fn sized_search_result(
+        search_key: u8,
+        code: TxOutSearchResultCode,
+        payload: u8,
+        len: usize,
+    ) -> TxOutSearchResult {
+        TxOutSearchResult {
+            search_key: vec![search_key; 16],
+            result_code: code as u32,
+            ciphertext: vec![payload; len],
+        }
+    }
+
+    #[test]
+    fn collate_picks_found_results() {
+        let store_a = vec![
+            search_result(1, TxOutSearchResultCode::Found, 0xa1),
+            search_result(2, TxOutSearchResultCode::NotFound, 0),
+            search_result(3, TxOutSearchResultCode::NotFound, 0),
+        ];
+        let store_b = vec![
+            search_result(1, TxOutSearchResultCode::NotFound, 0),
+            search_result(2, TxOutSearchResultCode::NotFound, 0),
+            search_result(3, TxOutSearchResultCode::Found, 0xb3),
+        ];
+
+        let collated = collate_tx_out_search_results(vec![store_a.clone(), store_b]).unwrap();
+        assert_eq!(
+            collated,
+            vec![
+                store_a[0].clone(),
+                search_result(2, TxOutSearchResultCode::NotFound, 0),
+                search_result(3, TxOutSearchResultCode::Found, 0xb3),
+            ]
+        );
+    }
+
+    #[test]
+    fn collate_rejects_mismatched_results() {
+        let store_a = vec![search_result(1, TxOutSearchResultCode::NotFound, 0)];
+        let store_b = vec![search_result(2, TxOutSearchResultCode::NotFound, 0)];
+
+        assert!(matches!(
+            collate_tx_out_search_results(vec![store_a.clone(), store_b]),
+            Err(Error::ViewStoreResponseMismatch)
+        ));
+        assert!(matches!(
+            collate_tx_out_search_results(vec![store_a, vec![]]),
+            Err(Error::ViewStoreResponseMismatch)
+        ));
+    }
+
+    #[test]
+    fn collate_merges_ciphertexts_of_different_lengths() {
+        let store_a = vec![
+            sized_search_result(1, TxOutSearchResultCode::NotFound, 0, 100),
+            sized_search_result(2, TxOutSearchResultCode::Found, 0xa2, 100),
+        ];
+        let store_b = vec![
+            sized_search_result(1, TxOutSearchResultCode::Found, 0xb1, 120),
+            sized_search_result(2, TxOutSearchResultCode::NotFound, 0, 120),
+        ];
+
+        let collated = collate_tx_out_search_results(vec![store_a.clone(), store_b]).unwrap();
+        assert_eq!(
+            collated,
+            vec![
+                sized_search_result(1, TxOutSearchResultCode::Found, 0xb1, 120),
+                store_a[1].clone(),
+            ]
+        );
+    }
+
+    #[test]
+    fn collate_rejects_oversized_ciphertexts() {
+        let store_a = vec![sized_search_result(
+            1,
+            TxOutSearchResultCode::Found,
+            0xa1,
+            ValueSize::USIZE,
+        )];
+
+        assert!(matches!(
+            collate_tx_out_search_results(vec![store_a]),
+            Err(Error::ViewStoreResponseMismatch)
+        ));
+    }
+}
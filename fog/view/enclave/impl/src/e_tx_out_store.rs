@@ -27,7 +27,7 @@ use mc_oblivious_traits::{
 // KeySize and ValueSize reflect the needs of e_tx_out_store
 // We must choose an oblivious map algorithm that can support that
 type KeySize = U16;
-type ValueSize = U240;
+pub(crate) type ValueSize = U240;
 // BlockSize is a tuning parameter for OMap which must become the ValueSize of
 // the selected ORAM
 type BlockSize = U1024;
@@ -6,18 +6,26 @@
 
 extern crate alloc;
 
+mod collation;
 mod e_tx_out_store;
+use collation::collate_tx_out_search_results;
 use e_tx_out_store::{ETxOutStore, StorageDataSize, StorageMetaSize};
 
 use alloc::vec::Vec;
 use mc_attest_core::{IasNonce, Quote, QuoteNonce, Report, TargetInfo, VerificationReport};
-use mc_attest_enclave_api::{ClientAuthRequest, ClientAuthResponse, ClientSession, EnclaveMessage};
-use mc_common::logger::{log, Logger};
+use mc_attest_enclave_api::{
+    ClientAuthRequest, ClientAuthResponse, ClientSession, EnclaveMessage, PeerAuthRequest,
+    PeerAuthResponse, PeerSession,
+};
+use mc_common::{
+    logger::{log, Logger},
+    ResponderId,
+};
 use mc_crypto_ake_enclave::{AkeEnclaveState, NullIdentity};
 use mc_crypto_keys::X25519Public;
 use mc_fog_recovery_db_iface::FogUserEvent;
 use mc_fog_types::{
-    view::{QueryRequest, QueryResponse},
+    view::{QueryRequest, QueryResponse, TxOutSearchResult},
     ETxOutRecord,
 };
 use mc_fog_view_enclave_api::{
@@ -52,6 +60,62 @@ where
             logger,
         }
     }
+
+    /// Decode an encrypted QueryRequest
+    fn decode_query_request(&self, plaintext: &[u8]) -> Result<QueryRequest> {
+        mc_util_serial::decode(plaintext).map_err(|e| {
+            log::error!(self.logger, "Could not decode user request: {}", e);
+            Error::ProstDecode
+        })
+    }
+
+    /// Search the ORAM for the records of the given search keys
+    fn search_tx_outs(&self, search_keys: &[Vec<u8>]) -> Result<Vec<TxOutSearchResult>> {
+        let mut lk = self.e_tx_out_store.lock()?;
+        let store = lk.as_mut().ok_or(Error::EnclaveNotInitialized)?;
+
+        Ok(search_keys
+            .iter()
+            .map(|key| store.find_record(&key[..]))
+            .collect())
+    }
+
+    /// Build the response to a client query, out of the untrusted part of the
+    /// response and the TxOutSearchResult's
+    fn build_query_response(
+        untrusted_query_response: UntrustedQueryResponse,
+        tx_out_search_results: Vec<TxOutSearchResult>,
+    ) -> QueryResponse {
+        let mut missed_block_ranges = Vec::new();
+        let mut rng_records = Vec::new();
+        let mut decommissioned_ingest_invocations = Vec::new();
+
+        for event in untrusted_query_response.user_events.into_iter() {
+            match event {
+                FogUserEvent::NewRngRecord(rng_record) => rng_records.push(rng_record),
+
+                FogUserEvent::DecommissionIngestInvocation(decommissioned_ingest_invocation) => {
+                    decommissioned_ingest_invocations.push(decommissioned_ingest_invocation)
+                }
+
+                FogUserEvent::MissingBlocks(range) => missed_block_ranges.push(range),
+            }
+        }
+
+        QueryResponse {
+            highest_processed_block_count: untrusted_query_response.highest_processed_block_count,
+            highest_processed_block_signature_timestamp: untrusted_query_response
+                .highest_processed_block_signature_timestamp,
+            next_start_from_user_event_id: untrusted_query_response.next_start_from_user_event_id,
+            missed_block_ranges,
+            rng_records,
+            decommissioned_ingest_invocations,
+            tx_out_search_results,
+            last_known_block_count: untrusted_query_response.last_known_block_count,
+            last_known_block_cumulative_txo_count: untrusted_query_response
+                .last_known_block_cumulative_txo_count,
+        }
+    }
 }
 
 impl<OSC> ReportableEnclave for ViewEnclave<OSC>
@@ -84,7 +148,11 @@ where
         // Note: eid is passed to sgx_enclave_id crate earlier in the system, because
         // that crate is not under sgx_compat and isn't meant to be used outside of
         // enclave
-        self.ake.init(Default::default(), params.self_client_id)?;
+        //
+        // The client responder id doubles as the peer responder id, which routers
+        // use when connecting to this enclave as a view store.
+        self.ake
+            .init(params.self_client_id.clone(), params.self_client_id)?;
         {
             let mut lk = self.e_tx_out_store.lock()?;
             *lk = Some(ETxOutStore::new(
@@ -119,53 +187,10 @@ where
         let channel_id = msg.channel_id.clone();
         let user_plaintext = self.ake.client_decrypt(msg)?;
 
-        let req: QueryRequest = mc_util_serial::decode(&user_plaintext).map_err(|e| {
-            log::error!(self.logger, "Could not decode user request: {}", e);
-            Error::ProstDecode
-        })?;
-
-        // Prepare the untrusted part of the response.
-        let mut missed_block_ranges = Vec::new();
-        let mut rng_records = Vec::new();
-        let mut decommissioned_ingest_invocations = Vec::new();
-
-        for event in untrusted_query_response.user_events.into_iter() {
-            match event {
-                FogUserEvent::NewRngRecord(rng_record) => rng_records.push(rng_record),
+        let req = self.decode_query_request(&user_plaintext)?;
 
-                FogUserEvent::DecommissionIngestInvocation(decommissioned_ingest_invocation) => {
-                    decommissioned_ingest_invocations.push(decommissioned_ingest_invocation)
-                }
-
-                FogUserEvent::MissingBlocks(range) => missed_block_ranges.push(range),
-            }
-        }
-
-        let mut resp = QueryResponse {
-            highest_processed_block_count: untrusted_query_response.highest_processed_block_count,
-            highest_processed_block_signature_timestamp: untrusted_query_response
-                .highest_processed_block_signature_timestamp,
-            next_start_from_user_event_id: untrusted_query_response.next_start_from_user_event_id,
-            missed_block_ranges,
-            rng_records,
-            decommissioned_ingest_invocations,
-            tx_out_search_results: Default::default(),
-            last_known_block_count: untrusted_query_response.last_known_block_count,
-            last_known_block_cumulative_txo_count: untrusted_query_response
-                .last_known_block_cumulative_txo_count,
-        };
-
-        // Do the txos part, scope lock of e_tx_out_store
-        {
-            let mut lk = self.e_tx_out_store.lock()?;
-            let store = lk.as_mut().ok_or(Error::EnclaveNotInitialized)?;
-
-            resp.tx_out_search_results = req
-                .get_txos
-                .iter()
-                .map(|key| store.find_record(&key[..]))
-                .collect();
-        }
+        let tx_out_search_results = self.search_tx_outs(&req.get_txos)?;
+        let resp = Self::build_query_response(untrusted_query_response, tx_out_search_results);
 
         let response_plaintext_bytes = mc_util_serial::encode(&resp);
 
@@ -184,4 +209,94 @@ where
         }
         Ok(())
     }
+
+    // Sharding
+
+    fn view_store_init(&self, view_store_id: ResponderId) -> Result<PeerAuthRequest> {
+        Ok(self.ake.peer_init(&view_store_id)?)
+    }
+
+    fn view_store_connect(
+        &self,
+        view_store_id: ResponderId,
+        view_store_auth_response: PeerAuthResponse,
+    ) -> Result<PeerSession> {
+        let (peer_session, _verification_report) = self
+            .ake
+            .peer_connect(&view_store_id, view_store_auth_response)?;
+        Ok(peer_session)
+    }
+
+    fn router_accept(&self, req: PeerAuthRequest) -> Result<(PeerAuthResponse, PeerSession)> {
+        Ok(self.ake.peer_accept(req)?)
+    }
+
+    fn create_view_store_queries(
+        &self,
+        client_query: EnclaveMessage<ClientSession>,
+        view_store_sessions: Vec<PeerSession>,
+    ) -> Result<Vec<EnclaveMessage<PeerSession>>> {
+        // The client session is used as aad, and echoed back by the view stores. This
+        // prevents the host from collating view store responses into a response for
+        // another client.
+        let aad: Vec<u8> = client_query.channel_id.clone().into();
+        let user_plaintext = self.ake.client_decrypt(client_query)?;
+
+        // Make sure the request is valid before forwarding it.
+        self.decode_query_request(&user_plaintext)?;
+
+        view_store_sessions
+            .iter()
+            .map(|session| Ok(self.ake.peer_encrypt(session, &aad, &user_plaintext)?))
+            .collect()
+    }
+
+    fn query_store(&self, msg: EnclaveMessage<PeerSession>) -> Result<EnclaveMessage<PeerSession>> {
+        let peer_session = msg.channel_id.clone();
+        let aad = msg.aad.clone();
+        let router_plaintext = self.ake.peer_decrypt(msg)?;
+
+        let req = self.decode_query_request(&router_plaintext)?;
+
+        let resp = QueryResponse {
+            tx_out_search_results: self.search_tx_outs(&req.get_txos)?,
+            ..Default::default()
+        };
+
+        let response_plaintext_bytes = mc_util_serial::encode(&resp);
+        Ok(self
+            .ake
+            .peer_encrypt(&peer_session, &aad, &response_plaintext_bytes)?)
+    }
+
+    fn collate_view_store_responses(
+        &self,
+        client_session: ClientSession,
+        untrusted_query_response: UntrustedQueryResponse,
+        view_store_responses: Vec<EnclaveMessage<PeerSession>>,
+    ) -> Result<Vec<u8>> {
+        let view_store_results = view_store_responses
+            .into_iter()
+            .map(|msg| {
+                if msg.aad != client_session.as_ref() {
+                    return Err(Error::ViewStoreResponseMismatch);
+                }
+                let plaintext = self.ake.peer_decrypt(msg)?;
+                let resp: QueryResponse =
+                    mc_util_serial::decode(&plaintext).map_err(|_| Error::ProstDecode)?;
+                Ok(resp.tx_out_search_results)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let tx_out_search_results = collate_tx_out_search_results(view_store_results)?;
+        let resp = Self::build_query_response(untrusted_query_response, tx_out_search_results);
+
+        let response_plaintext_bytes = mc_util_serial::encode(&resp);
+
+        let response = self
+            .ake
+            .client_encrypt(&client_session, &[], &response_plaintext_bytes)?;
+
+        Ok(response.data)
+    }
 }
@@ -11,7 +11,10 @@ use std::{path, result::Result as StdResult, sync::Arc};
 use mc_attest_core::{
     IasNonce, Quote, QuoteNonce, Report, SgxError, TargetInfo, VerificationReport,
 };
-use mc_attest_enclave_api::{ClientAuthRequest, ClientAuthResponse, ClientSession, EnclaveMessage};
+use mc_attest_enclave_api::{
+    ClientAuthRequest, ClientAuthResponse, ClientSession, EnclaveMessage, PeerAuthRequest,
+    PeerAuthResponse, PeerSession,
+};
 use mc_attest_verifier::DEBUG_ENCLAVE;
 use mc_common::{logger::Logger, ResponderId};
 use mc_crypto_keys::X25519Public;
@@ -179,4 +182,63 @@ impl ViewEnclaveApi for SgxViewEnclave {
         let outbuf = self.enclave_call(&inbuf)?;
         mc_util_serial::deserialize(&outbuf[..])?
     }
+
+    fn view_store_init(&self, view_store_id: ResponderId) -> Result<PeerAuthRequest> {
+        let inbuf = mc_util_serial::serialize(&ViewEnclaveRequest::ViewStoreInit(view_store_id))?;
+        let outbuf = self.enclave_call(&inbuf)?;
+        mc_util_serial::deserialize(&outbuf[..])?
+    }
+
+    fn view_store_connect(
+        &self,
+        view_store_id: ResponderId,
+        view_store_auth_response: PeerAuthResponse,
+    ) -> Result<PeerSession> {
+        let inbuf = mc_util_serial::serialize(&ViewEnclaveRequest::ViewStoreConnect(
+            view_store_id,
+            view_store_auth_response,
+        ))?;
+        let outbuf = self.enclave_call(&inbuf)?;
+        mc_util_serial::deserialize(&outbuf[..])?
+    }
+
+    fn router_accept(&self, req: PeerAuthRequest) -> Result<(PeerAuthResponse, PeerSession)> {
+        let inbuf = mc_util_serial::serialize(&ViewEnclaveRequest::RouterAccept(req))?;
+        let outbuf = self.enclave_call(&inbuf)?;
+        mc_util_serial::deserialize(&outbuf[..])?
+    }
+
+    fn create_view_store_queries(
+        &self,
+        client_query: EnclaveMessage<ClientSession>,
+        view_store_sessions: Vec<PeerSession>,
+    ) -> Result<Vec<EnclaveMessage<PeerSession>>> {
+        let inbuf = mc_util_serial::serialize(&ViewEnclaveRequest::CreateViewStoreQueries(
+            client_query,
+            view_store_sessions,
+        ))?;
+        let outbuf = self.enclave_call(&inbuf)?;
+        mc_util_serial::deserialize(&outbuf[..])?
+    }
+
+    fn query_store(&self, msg: EnclaveMessage<PeerSession>) -> Result<EnclaveMessage<PeerSession>> {
+        let inbuf = mc_util_serial::serialize(&ViewEnclaveRequest::QueryStore(msg))?;
+        let outbuf = self.enclave_call(&inbuf)?;
+        mc_util_serial::deserialize(&outbuf[..])?
+    }
+
+    fn collate_view_store_responses(
+        &self,
+        client_session: ClientSession,
+        untrusted_query_response: UntrustedQueryResponse,
+        view_store_responses: Vec<EnclaveMessage<PeerSession>>,
+    ) -> Result<Vec<u8>> {
+        let inbuf = mc_util_serial::serialize(&ViewEnclaveRequest::CollateViewStoreResponses(
+            client_session,
+            untrusted_query_response,
+            view_store_responses,
+        ))?;
+        let outbuf = self.enclave_call(&inbuf)?;
+        mc_util_serial::deserialize(&outbuf[..])?
+    }
 }
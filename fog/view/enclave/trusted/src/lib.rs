@@ -120,6 +120,26 @@ pub fn ecall_dispatcher(inbuf: &[u8]) -> Result<Vec<u8>, sgx_status_t> {
             serialize(&ENCLAVE.query(req, untrusted_query_response))
         }
         ViewEnclaveRequest::AddRecords(records) => serialize(&ENCLAVE.add_records(records)),
+        ViewEnclaveRequest::ViewStoreInit(view_store_id) => {
+            serialize(&ENCLAVE.view_store_init(view_store_id))
+        }
+        ViewEnclaveRequest::ViewStoreConnect(view_store_id, view_store_auth_response) => {
+            serialize(&ENCLAVE.view_store_connect(view_store_id, view_store_auth_response))
+        }
+        ViewEnclaveRequest::RouterAccept(req) => serialize(&ENCLAVE.router_accept(req)),
+        ViewEnclaveRequest::CreateViewStoreQueries(client_query, view_store_sessions) => {
+            serialize(&ENCLAVE.create_view_store_queries(client_query, view_store_sessions))
+        }
+        ViewEnclaveRequest::QueryStore(msg) => serialize(&ENCLAVE.query_store(msg)),
+        ViewEnclaveRequest::CollateViewStoreResponses(
+            client_session,
+            untrusted_query_response,
+            view_store_responses,
+        ) => serialize(&ENCLAVE.collate_view_store_responses(
+            client_session,
+            untrusted_query_response,
+            view_store_responses,
+        )),
     }
     .or(Err(sgx_status_t::SGX_ERROR_UNEXPECTED))
 }
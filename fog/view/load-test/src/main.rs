@@ -61,7 +61,7 @@ fn worker_thread(
     let mut fog_view_client = build_fog_view_conn(&uri, grpc_retry_config, &logger);
 
     let resp = fog_view_client
-        .request(0, 0, Default::default())
+        .request(0, 0, 0, Default::default())
         .expect("request");
     let rng_record = &(resp.rng_records[0]);
     let rng = VersionedKexRng::try_from_kex_pubkey(
@@ -73,7 +73,7 @@ fn worker_thread(
 
     loop {
         let _resp = fog_view_client
-            .request(0, 0, search_keys.clone())
+            .request(0, 0, 0, search_keys.clone())
             .expect("request");
         num_reqs.fetch_add(1, Ordering::SeqCst);
     }
@@ -42,11 +42,14 @@ pub trait FogViewConnection {
     /// - start_from_block_index: Limit ETxOutRecord search for only tx outs
     ///   that appeared in or
     /// after start_from_block_index
+    /// - account_start_block: Omit the events which only concern blocks before
+    ///   this one, or return all events if it is 0
     /// - search_keys: ETxOutRecord search keys
     fn request(
         &mut self,
         start_from_user_event_id: i64,
         start_from_block_index: u64,
+        account_start_block: u64,
         search_keys: Vec<Vec<u8>>,
     ) -> Result<QueryResponse, Self::Error>;
 
@@ -80,6 +83,7 @@ pub trait FogViewConnection {
                 .request(
                     user_rng_set.get_next_start_from_user_event_id(),
                     user_rng_set.get_highest_processed_block_count().into(),
+                    user_rng_set.get_account_start_block(),
                     Default::default(),
                 )
                 .map_err(TxOutPollingError::Conn)
@@ -152,6 +156,7 @@ pub trait FogViewConnection {
             let resp = match self.request(
                 i64::MAX, // We don't care about any events, we just want to search for TXOs.
                 user_rng_set.get_highest_processed_block_count().into(),
+                user_rng_set.get_account_start_block(),
                 search_keys,
             ) {
                 Ok(resp) => resp,
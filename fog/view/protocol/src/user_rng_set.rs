@@ -26,6 +26,10 @@ pub struct UserRngSet {
 
     /// Last next_start_from_user_event_id reported by the server
     next_start_from_user_event_id: i64,
+
+    /// The first block in which the account could have received TxOuts, or 0
+    /// if it is unknown. Events about earlier blocks are not requested.
+    account_start_block: u64,
 }
 
 impl UserRngSet {
@@ -34,6 +38,16 @@ impl UserRngSet {
             rngs: HashMap::default(),
             highest_processed_block_count: 0,
             next_start_from_user_event_id: 0,
+            account_start_block: 0,
+        }
+    }
+
+    /// Create an rng set for an account which could not receive TxOuts before
+    /// account_start_block, e.g. because it was created then.
+    pub fn new_from_account_start_block(account_start_block: u64) -> Self {
+        Self {
+            account_start_block,
+            ..Self::new()
         }
     }
 
@@ -57,6 +71,16 @@ impl UserRngSet {
         self.next_start_from_user_event_id = val;
     }
 
+    pub fn get_account_start_block(&self) -> u64 {
+        self.account_start_block
+    }
+
+    /// Add an rng which was saved earlier, e.g. by a client which persists its
+    /// state, replacing any rng with the same nonce
+    pub fn insert_rng(&mut self, nonce: Vec<u8>, rng: VersionedKexRng) {
+        self.rngs.insert(nonce, rng);
+    }
+
     // Take a nonce and initialize a new rng from it if there isn't one
     // already
     // TODO: Also update the start_block and end_block values
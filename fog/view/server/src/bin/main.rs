@@ -4,7 +4,7 @@
 //! MobileCoin Fog View target
 use mc_attest_net::{Client, RaClient};
 use mc_common::{logger::log, time::SystemTimeProvider};
-use mc_fog_sql_recovery_db::SqlRecoveryDb;
+use mc_fog_sql_recovery_db::AnyRecoveryDb;
 use mc_fog_view_enclave::{SgxViewEnclave, ENCLAVE_FILE};
 use mc_fog_view_server::{config::MobileAcctViewConfig, server::ViewServer};
 use mc_util_cli::ParserWithBuildInfo;
@@ -17,9 +17,14 @@ fn main() {
     let (logger, _global_logger_guard) =
         mc_common::logger::create_app_logger(mc_common::logger::o!());
     let config = MobileAcctViewConfig::parse();
+    assert!(
+        config.block_range().is_valid(),
+        "Invalid block range {}",
+        config.block_range()
+    );
 
     let database_url = env::var("DATABASE_URL").expect("Missing DATABASE_URL environment variable");
-    let recovery_db = SqlRecoveryDb::new_from_url(
+    let recovery_db = AnyRecoveryDb::new_shared_from_url(
         &database_url,
         config.postgres_config.clone(),
         logger.clone(),
@@ -3,6 +3,7 @@
 use mc_common::logger::{log, Logger};
 use mc_crypto_keys::CompressedRistrettoPublic;
 use mc_fog_recovery_db_iface::IngressPublicKeyRecord;
+use mc_fog_types::common::BlockRange;
 use std::collections::HashMap;
 
 /// A utility object that keeps track of which block number was processed for
@@ -12,17 +13,28 @@ use std::collections::HashMap;
 /// - Finding out what is the highest block index we have encountered so far.
 /// - Finding out for which block index have we processed data for all ingress
 ///   keys, while taking into account ingress keys reported lost
+///
+/// A tracker can be restricted to a block range, in which case blocks outside
+/// of the range are never processed.
 pub struct BlockTracker {
     processed_block_per_ingress_key: HashMap<CompressedRistrettoPublic, u64>,
     last_highest_processed_block_count: u64,
+    block_range: BlockRange,
     logger: Logger,
 }
 
 impl BlockTracker {
     pub fn new(logger: Logger) -> Self {
+        Self::with_block_range(BlockRange::new(0, u64::MAX), logger)
+    }
+
+    /// Create a tracker that only processes blocks within `block_range`.
+    /// Blocks before the range are considered processed.
+    pub fn with_block_range(block_range: BlockRange, logger: Logger) -> Self {
         Self {
             processed_block_per_ingress_key: HashMap::default(),
-            last_highest_processed_block_count: 0,
+            last_highest_processed_block_count: block_range.start_block,
+            block_range,
             logger,
         }
     }
@@ -41,16 +53,17 @@ impl BlockTracker {
                 // next one can be provided by it, and if so add it to the list of next blocks
                 // we would like to process.
                 let next_block = last_processed_block + 1;
-                if rec.covers_block_index(next_block) {
+                if rec.covers_block_index(next_block) && self.block_range.contains(next_block) {
                     next_blocks.insert(rec.key, next_block);
                 }
             } else {
                 // No block has been processed for this ingress key, so the next block is the
-                // first one, assuming it can actually be provided by the ingress key.
-                // (It will not be able to provide the start block if it got lost
+                // first one within our block range, assuming it can actually be provided by the
+                // ingress key. (It will not be able to provide the start block if it got lost
                 // immediately after starting before scanning any blocks)
-                if rec.covers_block_index(rec.status.start_block) {
-                    next_blocks.insert(rec.key, rec.status.start_block);
+                let first_block = rec.status.start_block.max(self.block_range.start_block);
+                if rec.covers_block_index(first_block) && self.block_range.contains(first_block) {
+                    next_blocks.insert(rec.key, first_block);
                 }
             }
         }
@@ -85,8 +98,8 @@ impl BlockTracker {
     /// * missing_block_ranges: Any manually entered missing block ranges.
     ///
     /// Returns:
-    /// * The highest fully processed block count, which may be 0 if nothing is
-    ///   processed
+    /// * The highest fully processed block count, which may be the start of the
+    ///   block range (0 unless restricted) if nothing is processed
     /// * Optionally, an IngressPublicKeyRecord which is the *reason* that the
     ///   previous number is less than highest_known_block_index -- the next
     ///   thing we are waiting on for data.
@@ -117,7 +130,10 @@ impl BlockTracker {
             // If the next block index we are checking doesn't exist yet, then we definitely
             // can't advance the highest processed block count.
             // This breaks the loop if ingress_keys set is empty.
-            if highest_known_block_count < next_block_count {
+            // Blocks past our block range are never processed, so we stop there too.
+            if highest_known_block_count < next_block_count
+                || !self.block_range.contains(next_block_index)
+            {
                 log::trace!(
                     self.logger,
                     "We processed everything up to highest known block count"
@@ -1319,4 +1335,46 @@ mod tests {
         block_tracker.block_processed(CompressedRistrettoPublic::from_random(&mut rng), 101);
         assert_eq!(block_tracker.highest_known_block_count(), 102);
     }
+
+    // A tracker restricted to a block range only processes blocks within it.
+    #[test_with_logger]
+    fn block_range_restricts_processing(logger: Logger) {
+        let mut rng: StdRng = SeedableRng::from_seed([123u8; 32]);
+        let mut block_tracker = BlockTracker::with_block_range(BlockRange::new(110, 120), logger);
+        let rec = IngressPublicKeyRecord {
+            key: CompressedRistrettoPublic::from_random(&mut rng),
+            status: IngressPublicKeyStatus {
+                start_block: 100,
+                pubkey_expiry: 200,
+                retired: false,
+                lost: false,
+            },
+            last_scanned_block: None,
+        };
+
+        // Blocks before the range are skipped and considered processed.
+        assert_eq!(
+            block_tracker.next_blocks(&[rec.clone()]),
+            HashMap::from_iter(vec![(rec.key, 110)])
+        );
+        assert_eq!(
+            block_tracker.highest_fully_processed_block_count(&[rec.clone()]),
+            (110, None)
+        );
+
+        for block_index in 110..120 {
+            assert_eq!(
+                block_tracker.next_blocks(&[rec.clone()]),
+                HashMap::from_iter(vec![(rec.key, block_index)])
+            );
+            block_tracker.block_processed(rec.key, block_index);
+        }
+
+        // Blocks past the range are never processed.
+        assert!(block_tracker.next_blocks(&[rec.clone()]).is_empty());
+        assert_eq!(
+            block_tracker.highest_fully_processed_block_count(&[rec]),
+            (120, None)
+        );
+    }
 }
@@ -1,17 +1,18 @@
 // Copyright (c) 2018-2022 The MobileCoin Foundation
 
-//! Configuration parameters for the MobileCoin Fog View Node
+//! Configuration parameters for the MobileCoin Fog View Node and Router
 #![deny(missing_docs)]
 
 use clap::Parser;
 use mc_attest_core::ProviderId;
 use mc_common::ResponderId;
 use mc_fog_sql_recovery_db::SqlRecoveryDbConnectionConfig;
-use mc_fog_uri::FogViewUri;
+use mc_fog_types::common::BlockRange;
+use mc_fog_uri::{FogViewStoreUri, FogViewUri};
 use mc_util_parse::parse_duration_in_seconds;
 use mc_util_uri::AdminUri;
 use serde::Serialize;
-use std::time::Duration;
+use std::{path::PathBuf, time::Duration};
 
 /// Configuration parameters for the MobileCoin Fog View Node
 #[derive(Clone, Parser, Serialize)]
@@ -64,6 +65,99 @@ pub struct MobileAcctViewConfig {
     #[clap(long, default_value = "1048576", env = "MC_OMAP_CAPACITY")]
     pub omap_capacity: u64,
 
+    /// Optional gRPC listening URI for fog view routers. When set, this node
+    /// serves as a view store shard for the routers, and stops serving view
+    /// queries to clients. The client listening URI then only serves health
+    /// checks.
+    ///
+    /// The responder id routers use for this store must be this node's
+    /// client responder id.
+    #[clap(long, env = "MC_STORE_LISTEN_URI")]
+    pub store_listen_uri: Option<FogViewStoreUri>,
+
+    /// The first block of the range of blocks this node loads records for.
+    #[clap(long, default_value = "0", env = "MC_BLOCK_RANGE_START")]
+    pub block_range_start: u64,
+
+    /// One past the last block of the range of blocks this node loads records
+    /// for. Defaults to an unbounded range.
+    #[clap(long, env = "MC_BLOCK_RANGE_END")]
+    pub block_range_end: Option<u64>,
+
+    /// Optional directory of the archive written by fog-recovery-db-archiver.
+    /// When set, records of archived blocks are loaded from it, otherwise
+    /// loading stops at the first archived block of an ingress key, and
+    /// clients fall back to downloading the blocks that follow.
+    #[clap(long, env = "MC_ARCHIVE_DIR")]
+    pub archive_dir: Option<PathBuf>,
+
+    /// Postgres config
+    #[clap(flatten)]
+    pub postgres_config: SqlRecoveryDbConnectionConfig,
+}
+
+impl MobileAcctViewConfig {
+    /// The range of blocks this node loads records for.
+    pub fn block_range(&self) -> BlockRange {
+        BlockRange::new(
+            self.block_range_start,
+            self.block_range_end.unwrap_or(u64::MAX),
+        )
+    }
+}
+
+/// Configuration parameters for the MobileCoin Fog View Router
+#[derive(Clone, Parser, Serialize)]
+#[clap(version)]
+pub struct FogViewRouterConfig {
+    /// The ID with which to respond to client attestation requests.
+    ///
+    /// This ID needs to match the host:port clients use in their URI when
+    /// referencing this node.
+    #[clap(long, env = "MC_CLIENT_RESPONDER_ID")]
+    pub client_responder_id: ResponderId,
+
+    /// PEM-formatted keypair to send with an Attestation Request.
+    #[clap(long, env = "MC_IAS_API_KEY")]
+    pub ias_api_key: String,
+
+    /// The IAS SPID to use when getting a quote
+    #[clap(long, env = "MC_IAS_SPID")]
+    pub ias_spid: ProviderId,
+
+    /// gRPC listening URI for client requests.
+    #[clap(long, env = "MC_CLIENT_LISTEN_URI")]
+    pub client_listen_uri: FogViewUri,
+
+    /// Optional admin listening URI.
+    #[clap(long, env = "MC_ADMIN_LISTEN_URI")]
+    pub admin_listen_uri: Option<AdminUri>,
+
+    /// Enables authenticating client requests using Authorization tokens using
+    /// the provided hex-encoded 32 bytes shared secret.
+    #[clap(long, parse(try_from_str = hex::FromHex::from_hex), env = "MC_CLIENT_AUTH_TOKEN_SECRET")]
+    pub client_auth_token_secret: Option<[u8; 32]>,
+
+    /// Maximal client authentication token lifetime, in seconds (only relevant
+    /// when --client-auth-token-secret is used. Defaults to 86400 - 24
+    /// hours).
+    #[clap(long, default_value = "86400", parse(try_from_str = parse_duration_in_seconds), env = "MC_CLIENT_AUTH_TOKEN_MAX_LIFETIME")]
+    pub client_auth_token_max_lifetime: Duration,
+
+    /// The view store shards to fan client queries out to. Together, their
+    /// block ranges should cover the whole chain without gaps.
+    ///
+    /// The responder id of each uri must be the client responder id of the
+    /// shard, e.g.
+    /// insecure-fog-view-store://10.0.0.1:3227/?responder-id=shard1.fog.com:443
+    #[clap(
+        long = "shard-uri",
+        required = true,
+        use_value_delimiter = true,
+        env = "MC_SHARD_URIS"
+    )]
+    pub shard_uris: Vec<FogViewStoreUri>,
+
     /// Postgres config
     #[clap(flatten)]
     pub postgres_config: SqlRecoveryDbConnectionConfig,
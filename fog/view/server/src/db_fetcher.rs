@@ -5,8 +5,11 @@
 use crate::{block_tracker::BlockTracker, counters};
 use mc_common::logger::{log, Logger};
 use mc_crypto_keys::CompressedRistrettoPublic;
-use mc_fog_recovery_db_iface::{IngressPublicKeyRecord, IngressPublicKeyRecordFilters, RecoveryDb};
-use mc_fog_types::ETxOutRecord;
+use mc_fog_recovery_db_archive::Archive;
+use mc_fog_recovery_db_iface::{
+    ArchivedBlockRange, IngressPublicKeyRecord, IngressPublicKeyRecordFilters, RecoveryDb,
+};
+use mc_fog_types::{common::BlockRange, ETxOutRecord};
 use mc_util_grpc::ReadinessIndicator;
 use std::{
     sync::{
@@ -75,8 +78,14 @@ pub struct DbFetcher {
 }
 
 impl DbFetcher {
+    /// Create a new DbFetcher that fetches the records of blocks within
+    /// `block_range`. Records of archived blocks are read from `archive`.
+    /// Without one, loading stops at the first archived block of an ingress
+    /// key.
     pub fn new<DB: RecoveryDb + Clone + Send + Sync + 'static>(
         db: DB,
+        block_range: BlockRange,
+        archive: Option<Archive>,
         readiness_indicator: ReadinessIndicator,
         logger: Logger,
     ) -> Self {
@@ -98,6 +107,8 @@ impl DbFetcher {
                 .spawn(move || {
                     DbFetcherThread::start(
                         db,
+                        block_range,
+                        archive,
                         thread_stop_requested,
                         thread_shared_state,
                         thread_num_queued_records_limiter,
@@ -170,6 +181,9 @@ struct DbFetcherThread<DB: RecoveryDb + Clone + Send + Sync + 'static> {
     stop_requested: Arc<AtomicBool>,
     shared_state: Arc<Mutex<DbFetcherSharedState>>,
     block_tracker: BlockTracker,
+    archive: Option<Archive>,
+    archived_block_ranges: Vec<ArchivedBlockRange>,
+    reported_missing_archive: bool,
     num_queued_records_limiter: Arc<(Mutex<usize>, Condvar)>,
     readiness_indicator: ReadinessIndicator,
     logger: Logger,
@@ -180,6 +194,8 @@ struct DbFetcherThread<DB: RecoveryDb + Clone + Send + Sync + 'static> {
 impl<DB: RecoveryDb + Clone + Send + Sync + 'static> DbFetcherThread<DB> {
     pub fn start(
         db: DB,
+        block_range: BlockRange,
+        archive: Option<Archive>,
         stop_requested: Arc<AtomicBool>,
         shared_state: Arc<Mutex<DbFetcherSharedState>>,
         num_queued_records_limiter: Arc<(Mutex<usize>, Condvar)>,
@@ -190,7 +206,10 @@ impl<DB: RecoveryDb + Clone + Send + Sync + 'static> DbFetcherThread<DB> {
             db,
             stop_requested,
             shared_state,
-            block_tracker: BlockTracker::new(logger.clone()),
+            block_tracker: BlockTracker::with_block_range(block_range, logger.clone()),
+            archive,
+            archived_block_ranges: Vec::new(),
+            reported_missing_archive: false,
             num_queued_records_limiter,
             readiness_indicator,
             logger,
@@ -207,6 +226,7 @@ impl<DB: RecoveryDb + Clone + Send + Sync + 'static> DbFetcherThread<DB> {
             }
 
             self.load_ingress_keys();
+            self.load_archived_block_ranges();
 
             // Each call to load_block_data attempts to load one block for each known ingest
             // invocation. We want to keep loading blocks as long as we have data to load,
@@ -253,6 +273,20 @@ impl<DB: RecoveryDb + Clone + Send + Sync + 'static> DbFetcherThread<DB> {
         }
     }
 
+    /// Sync the ranges of blocks whose records were moved from the database to
+    /// the archive.
+    fn load_archived_block_ranges(&mut self) {
+        match self.db.get_archived_block_ranges() {
+            Ok(archived_block_ranges) => {
+                self.archived_block_ranges = archived_block_ranges;
+            }
+
+            Err(err) => {
+                log::warn!(self.logger, "Failed getting archived block ranges: {}", err);
+            }
+        }
+    }
+
     /// Attempt to load the next block for each of the ingest invocations we are
     /// aware of and tracking.
     /// Returns true if we might have more block data to load.
@@ -280,11 +314,7 @@ impl<DB: RecoveryDb + Clone + Send + Sync + 'static> DbFetcherThread<DB> {
 
         for (ingress_key, block_index) in next_block_index_per_ingress_key.into_iter() {
             // Attempt to load data for the next block.
-            let get_tx_outs_by_block_result = {
-                let _metrics_timer = counters::GET_TX_OUTS_BY_BLOCK_TIME.start_timer();
-                self.db
-                    .get_tx_outs_by_block_and_key(ingress_key, block_index)
-            };
+            let get_tx_outs_by_block_result = self.get_tx_outs(ingress_key, block_index);
 
             match get_tx_outs_by_block_result {
                 Ok(Some(tx_outs)) => {
@@ -358,6 +388,47 @@ impl<DB: RecoveryDb + Clone + Send + Sync + 'static> DbFetcherThread<DB> {
         may_have_more_work
     }
 
+    /// Get the records of a block, from the archive if the block was archived.
+    fn get_tx_outs(
+        &mut self,
+        ingress_key: CompressedRistrettoPublic,
+        block_index: u64,
+    ) -> Result<Option<Vec<ETxOutRecord>>, String> {
+        let archived = self.archived_block_ranges.iter().any(|archived| {
+            archived.ingress_key == ingress_key && archived.block_range.contains(block_index)
+        });
+        if !archived {
+            let _metrics_timer = counters::GET_TX_OUTS_BY_BLOCK_TIME.start_timer();
+            return self
+                .db
+                .get_tx_outs_by_block_and_key(ingress_key, block_index)
+                .map_err(|err| err.to_string());
+        }
+
+        match self.archive.as_mut() {
+            // Without an archive, the block cannot be loaded. Treat it as not yet
+            // available rather than as processed with no records, so that clients
+            // do not skip its TxOuts and instead fall back to downloading it.
+            None => {
+                if !self.reported_missing_archive {
+                    log::error!(
+                        self.logger,
+                        "ingress_key {:?} block {} was archived, but no archive is configured",
+                        ingress_key,
+                        block_index
+                    );
+                    self.reported_missing_archive = true;
+                }
+                Ok(None)
+            }
+            Some(archive) => match archive.read_block(&ingress_key, block_index) {
+                Ok(Some(block)) => Ok(Some(block.e_tx_out_records)),
+                Ok(None) => Err("block is missing from the archive".to_owned()),
+                Err(err) => Err(err.to_string()),
+            },
+        }
+    }
+
     fn shared_state(&self) -> MutexGuard<DbFetcherSharedState> {
         self.shared_state.lock().expect("mutex poisoned")
     }
@@ -374,13 +445,20 @@ mod tests {
     use mc_util_from_random::FromRandom;
     use rand::{rngs::StdRng, SeedableRng};
     use std::{thread::sleep, time::Duration};
+    use tempdir::TempDir;
 
     #[test_with_logger]
     fn basic_single_ingress_key(logger: Logger) {
         let mut rng: StdRng = SeedableRng::from_seed([123u8; 32]);
         let db_test_context = SqlRecoveryDbTestContext::new(logger.clone());
         let db = db_test_context.get_db_instance();
-        let db_fetcher = DbFetcher::new(db.clone(), Default::default(), logger);
+        let db_fetcher = DbFetcher::new(
+            db.clone(),
+            BlockRange::new(0, u64::MAX),
+            None,
+            Default::default(),
+            logger,
+        );
 
         // Initially, our database starts empty.
         let ingress_keys = db_fetcher.get_highest_processed_block_context();
@@ -610,7 +688,13 @@ mod tests {
         let mut rng: StdRng = SeedableRng::from_seed([123u8; 32]);
         let db_test_context = SqlRecoveryDbTestContext::new(logger.clone());
         let db = db_test_context.get_db_instance();
-        let db_fetcher = DbFetcher::new(db.clone(), Default::default(), logger);
+        let db_fetcher = DbFetcher::new(
+            db.clone(),
+            BlockRange::new(0, u64::MAX),
+            None,
+            Default::default(),
+            logger,
+        );
 
         // Register two ingress keys that have some overlap:
         // key_id1 starts at block 0, key2 starts at block 5.
@@ -667,7 +751,13 @@ mod tests {
         let mut rng: StdRng = SeedableRng::from_seed([123u8; 32]);
         let db_test_context = SqlRecoveryDbTestContext::new(logger.clone());
         let db = db_test_context.get_db_instance();
-        let db_fetcher = DbFetcher::new(db.clone(), Default::default(), logger);
+        let db_fetcher = DbFetcher::new(
+            db.clone(),
+            BlockRange::new(0, u64::MAX),
+            None,
+            Default::default(),
+            logger,
+        );
 
         // Register two ingress keys that have some overlap:
         // invoc_id1 starts at block 0, invoc_id2 starts at block 50.
@@ -739,4 +829,73 @@ mod tests {
             http_body: format!("{} body", name),
         }
     }
+
+    #[test_with_logger]
+    fn test_archived_blocks(logger: Logger) {
+        let mut rng: StdRng = SeedableRng::from_seed([123u8; 32]);
+        let db_test_context = SqlRecoveryDbTestContext::new(logger.clone());
+        let db = db_test_context.get_db_instance();
+        let archive_dir = TempDir::new("archive").unwrap();
+
+        let key = CompressedRistrettoPublic::from_random(&mut rng);
+        db.new_ingress_key(&key, 0).unwrap();
+        let invoc_id = db
+            .new_ingest_invocation(None, &key, &random_kex_rng_pubkey(&mut rng), 0)
+            .unwrap();
+
+        let mut blocks_and_records = Vec::new();
+        for i in 0..10 {
+            let (block, records) = random_block(&mut rng, i, 5); // 5 outputs per block
+            db.add_block_data(&invoc_id, &block, 0, &records).unwrap();
+            blocks_and_records.push((block, records));
+        }
+
+        // Archive the first 6 blocks.
+        db.report_lost_ingress_key(key).unwrap();
+        db.decommission_ingest_invocation(&invoc_id).unwrap();
+        let mut archivable = db.get_archivable_blocks(u64::MAX, 6).unwrap();
+        let (archived_key, archived_blocks) = archivable.pop().unwrap();
+        assert_eq!(archived_key, key);
+        Archive::new(archive_dir.path())
+            .write_blocks(&key, &archived_blocks)
+            .unwrap();
+        db.mark_blocks_archived(&key, &BlockRange::new(0, 6))
+            .unwrap();
+
+        let fetch_records = |archive: Option<Archive>, num_expected_blocks: usize| {
+            let db_fetcher = DbFetcher::new(
+                db.clone(),
+                BlockRange::new(0, u64::MAX),
+                archive,
+                Default::default(),
+                logger.clone(),
+            );
+            for _i in 0..500 {
+                let num_fetched_records = db_fetcher.shared_state().fetched_records.len();
+                if num_fetched_records >= num_expected_blocks {
+                    break;
+                }
+
+                sleep(Duration::from_millis(10));
+            }
+            db_fetcher.get_pending_fetched_records()
+        };
+
+        // With the archive, all records are loaded.
+        let fetched_records = fetch_records(
+            Some(Archive::new(archive_dir.path())),
+            blocks_and_records.len(),
+        );
+        assert_eq!(fetched_records.len(), blocks_and_records.len());
+        for (fetched_record, (block, records)) in fetched_records.iter().zip(&blocks_and_records) {
+            assert_eq!(fetched_record.ingress_key, key);
+            assert_eq!(fetched_record.block_index, block.index);
+            assert_eq!(&fetched_record.records, records);
+        }
+
+        // Without it, loading stops at the first archived block instead of reporting
+        // archived blocks as having no records.
+        let fetched_records = fetch_records(None, 1);
+        assert!(fetched_records.is_empty());
+    }
 }
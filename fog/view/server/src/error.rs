@@ -1,6 +1,7 @@
 // Copyright (c) 2018-2022 The MobileCoin Foundation
 
 use displaydoc::Display;
+use grpcio::Error as GrpcError;
 use mc_fog_view_enclave::Error as ViewEnclaveError;
 use mc_sgx_report_cache_untrusted::Error as ReportCacheError;
 
@@ -27,3 +28,24 @@ impl From<ReportCacheError> for ViewServerError {
         Self::ReportCache(src)
     }
 }
+
+/// An error on the attested connection from a router to a view store
+#[derive(Debug, Display)]
+pub enum ViewStoreConnectionError {
+    /// View Enclave error: {0}
+    Enclave(ViewEnclaveError),
+    /// GRPC error: {0}
+    Grpc(GrpcError),
+}
+
+impl From<ViewEnclaveError> for ViewStoreConnectionError {
+    fn from(src: ViewEnclaveError) -> Self {
+        Self::Enclave(src)
+    }
+}
+
+impl From<GrpcError> for ViewStoreConnectionError {
+    fn from(src: GrpcError) -> Self {
+        Self::Grpc(src)
+    }
+}
@@ -0,0 +1,355 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! Serves fog view clients by fanning out their queries to the view store
+//! shards, and merging the results.
+
+use crate::{
+    fog_view_service::enclave_err_to_rpc_status, view_store_connection::ViewStoreConnection,
+};
+use futures::{executor::block_on, future::join_all};
+use grpcio::{RpcContext, RpcStatus, RpcStatusCode, UnarySink};
+use mc_attest_api::attest;
+use mc_attest_enclave_api::ClientSession;
+use mc_common::logger::{log, Logger};
+use mc_fog_api::{view::ViewStoreQueryResponse, view_grpc::FogViewApi};
+use mc_fog_recovery_db_iface::{filter_user_events_for_account, RecoveryDb};
+use mc_fog_types::view::QueryRequestAAD;
+use mc_fog_view_enclave::ViewEnclaveProxy;
+use mc_fog_view_enclave_api::UntrustedQueryResponse;
+use mc_util_grpc::{
+    rpc_internal_error, rpc_logger, rpc_permissions_error, rpc_unavailable_error, send_result,
+    Authenticator,
+};
+use mc_util_metrics::SVC_COUNTERS;
+use mc_util_telemetry::{tracer, Tracer};
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone)]
+pub struct FogViewRouterService<E: ViewEnclaveProxy, DB: RecoveryDb + Send + Sync> {
+    /// Router enclave
+    enclave: E,
+
+    /// Recovery DB.
+    db: Arc<DB>,
+
+    /// Attested connections to the view stores.
+    view_stores: Arc<Vec<Mutex<ViewStoreConnection<E>>>>,
+
+    /// GRPC request authenticator.
+    authenticator: Arc<dyn Authenticator + Send + Sync>,
+
+    /// Slog logger object
+    logger: Logger,
+}
+
+impl<E: ViewEnclaveProxy, DB: RecoveryDb + Send + Sync> FogViewRouterService<E, DB> {
+    /// Creates a new fog-view-router-service (but does not create sockets and
+    /// start it etc.)
+    pub fn new(
+        enclave: E,
+        db: Arc<DB>,
+        view_stores: Arc<Vec<Mutex<ViewStoreConnection<E>>>>,
+        authenticator: Arc<dyn Authenticator + Send + Sync>,
+        logger: Logger,
+    ) -> Self {
+        Self {
+            enclave,
+            db,
+            view_stores,
+            authenticator,
+            logger,
+        }
+    }
+
+    /// Fan the query out to the view stores, and collate their responses
+    pub fn query_impl(&mut self, request: attest::Message) -> Result<attest::Message, RpcStatus> {
+        log::trace!(self.logger, "Getting encrypted request");
+        let tracer = tracer!();
+
+        tracer.in_span("router_query_impl", |_cx| {
+            // Attempt and deserialize the untrusted portion of this request.
+            let query_request_aad: QueryRequestAAD = mc_util_serial::decode(request.get_aad())
+                .map_err(|err| {
+                    RpcStatus::with_message(
+                        RpcStatusCode::INVALID_ARGUMENT,
+                        format!("AAD deserialization error: {}", err),
+                    )
+                })?;
+
+            let (user_events, next_start_from_user_event_id) =
+                tracer.in_span("search_user_events", |_cx| {
+                    self.db
+                        .search_user_events(query_request_aad.start_from_user_event_id)
+                        .map_err(|e| rpc_internal_error("search_user_events", e, &self.logger))
+                })?;
+            let user_events =
+                filter_user_events_for_account(user_events, query_request_aad.account_start_block);
+
+            let client_session = ClientSession::from(request.get_channel_id());
+
+            let view_store_responses =
+                tracer.in_span("query_view_stores", |_cx| self.query_view_stores(request))?;
+
+            let view_store_state = ViewStoreState::merge(&view_store_responses);
+            let untrusted_query_response = UntrustedQueryResponse {
+                user_events,
+                next_start_from_user_event_id,
+                highest_processed_block_count: view_store_state.highest_processed_block_count,
+                highest_processed_block_signature_timestamp: view_store_state
+                    .highest_processed_block_signature_timestamp,
+                last_known_block_count: view_store_state.last_known_block_count,
+                last_known_block_cumulative_txo_count: view_store_state
+                    .last_known_block_cumulative_txo_count,
+            };
+
+            let result_blob = tracer.in_span("enclave_collate", |_cx| {
+                self.enclave
+                    .collate_view_store_responses(
+                        client_session,
+                        untrusted_query_response,
+                        view_store_responses
+                            .into_iter()
+                            .map(|mut response| response.take_query_response().into())
+                            .collect(),
+                    )
+                    .map_err(|e| enclave_err_to_rpc_status("enclave collate", e, &self.logger))
+            })?;
+
+            let mut resp = attest::Message::new();
+            resp.set_data(result_blob);
+            Ok(resp)
+        })
+    }
+
+    /// Query all view stores concurrently.
+    fn query_view_stores(
+        &self,
+        request: attest::Message,
+    ) -> Result<Vec<ViewStoreQueryResponse>, RpcStatus> {
+        // Attest to any view store we are not connected to yet. The locks are only
+        // held while attesting, so that queries can be served concurrently.
+        let mut view_store_sessions = Vec::with_capacity(self.view_stores.len());
+        let mut view_store_clients = Vec::with_capacity(self.view_stores.len());
+        for view_store in self.view_stores.iter() {
+            let mut view_store = view_store.lock().expect("mutex poisoned");
+            let session = view_store.attested_session().map_err(|err| {
+                rpc_unavailable_error(
+                    "view_store_attest",
+                    format!("{}: {}", view_store, err),
+                    &self.logger,
+                )
+            })?;
+            view_store_sessions.push(session);
+            view_store_clients.push(view_store.view_store_api_client());
+        }
+
+        let view_store_queries = self
+            .enclave
+            .create_view_store_queries(request.into(), view_store_sessions)
+            .map_err(|e| enclave_err_to_rpc_status("enclave request", e, &self.logger))?;
+
+        let receivers = view_store_clients
+            .iter()
+            .zip(view_store_queries)
+            .map(|(client, query)| client.query_async(&query.into()))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|err| rpc_internal_error("view_store_query", err, &self.logger))?;
+
+        let results = block_on(join_all(receivers));
+
+        let mut view_store_responses = Vec::with_capacity(results.len());
+        for (view_store, result) in self.view_stores.iter().zip(results) {
+            match result {
+                Ok(response) => view_store_responses.push(response),
+                Err(err) => {
+                    // The view store may have lost our session, e.g. because it restarted.
+                    let mut view_store = view_store.lock().expect("mutex poisoned");
+                    view_store.deattest();
+                    return Err(rpc_unavailable_error(
+                        "view_store_query",
+                        format!("{}: {}", view_store, err),
+                        &self.logger,
+                    ));
+                }
+            }
+        }
+
+        Ok(view_store_responses)
+    }
+}
+
+/// The untrusted state of the view stores, merged into the state of a single
+/// fog view node.
+#[derive(Debug, Default, Eq, PartialEq)]
+struct ViewStoreState {
+    highest_processed_block_count: u64,
+    highest_processed_block_signature_timestamp: u64,
+    last_known_block_count: u64,
+    last_known_block_cumulative_txo_count: u64,
+}
+
+impl ViewStoreState {
+    /// Merge the state reported by the view stores.
+    ///
+    /// The highest processed block count is the end of the longest run of
+    /// blocks, starting at the origin block, that the stores have loaded
+    /// without gaps. A store that has processed up to some block count has
+    /// loaded all the blocks from the start of its range up to it.
+    fn merge(view_store_responses: &[ViewStoreQueryResponse]) -> Self {
+        let mut state = Self::default();
+
+        while let Some(response) = view_store_responses
+            .iter()
+            .filter(|response| {
+                response.get_block_range().start_block <= state.highest_processed_block_count
+                    && response.highest_processed_block_count > state.highest_processed_block_count
+            })
+            .max_by_key(|response| response.highest_processed_block_count)
+        {
+            state.highest_processed_block_count = response.highest_processed_block_count;
+            state.highest_processed_block_signature_timestamp =
+                response.highest_processed_block_signature_timestamp;
+        }
+
+        if let Some(response) = view_store_responses
+            .iter()
+            .max_by_key(|response| response.last_known_block_count)
+        {
+            state.last_known_block_count = response.last_known_block_count;
+            state.last_known_block_cumulative_txo_count =
+                response.last_known_block_cumulative_txo_count;
+        }
+
+        state
+    }
+}
+
+// Implement grpc trait
+impl<E: ViewEnclaveProxy, DB: RecoveryDb + Send + Sync> FogViewApi for FogViewRouterService<E, DB> {
+    fn auth(
+        &mut self,
+        ctx: RpcContext,
+        mut request: attest::AuthMessage,
+        sink: UnarySink<attest::AuthMessage>,
+    ) {
+        let _timer = SVC_COUNTERS.req(&ctx);
+        mc_common::logger::scoped_global_logger(&rpc_logger(&ctx, &self.logger), |logger| {
+            if let Err(err) = self.authenticator.authenticate_rpc(&ctx) {
+                return send_result(ctx, sink, err.into(), logger);
+            }
+
+            match self.enclave.client_accept(request.take_data().into()) {
+                Ok((response, _)) => {
+                    let mut result = attest::AuthMessage::new();
+                    result.set_data(response.into());
+                    send_result(ctx, sink, Ok(result), logger);
+                }
+                Err(client_error) => {
+                    // This is debug because there's no requirement on the remote party to trigger
+                    // it.
+                    log::debug!(
+                        logger,
+                        "ViewEnclaveApi::client_accept failed: {}",
+                        client_error
+                    );
+                    send_result(
+                        ctx,
+                        sink,
+                        Err(rpc_permissions_error(
+                            "client_auth",
+                            format!("Permission denied: {}", client_error),
+                            logger,
+                        )),
+                        logger,
+                    );
+                }
+            }
+        });
+    }
+
+    fn query(
+        &mut self,
+        ctx: RpcContext,
+        request: attest::Message,
+        sink: UnarySink<attest::Message>,
+    ) {
+        let _timer = SVC_COUNTERS.req(&ctx);
+        mc_common::logger::scoped_global_logger(&rpc_logger(&ctx, &self.logger), |logger| {
+            if let Err(err) = self.authenticator.authenticate_rpc(&ctx) {
+                return send_result(ctx, sink, err.into(), logger);
+            }
+
+            send_result(ctx, sink, self.query_impl(request), logger)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mc_fog_types::common::BlockRange;
+
+    fn view_store_response(
+        block_range: BlockRange,
+        highest_processed_block_count: u64,
+        last_known_block_count: u64,
+    ) -> ViewStoreQueryResponse {
+        let mut response = ViewStoreQueryResponse::new();
+        response.set_block_range((&block_range).into());
+        response.set_highest_processed_block_count(highest_processed_block_count);
+        response
+            .set_highest_processed_block_signature_timestamp(highest_processed_block_count * 10);
+        response.set_last_known_block_count(last_known_block_count);
+        response.set_last_known_block_cumulative_txo_count(last_known_block_count * 100);
+        response
+    }
+
+    #[test]
+    fn merge_contiguous_view_stores() {
+        let responses = vec![
+            view_store_response(BlockRange::new(100, 200), 150, 150),
+            view_store_response(BlockRange::new(0, 100), 100, 100),
+        ];
+        assert_eq!(
+            ViewStoreState::merge(&responses),
+            ViewStoreState {
+                highest_processed_block_count: 150,
+                highest_processed_block_signature_timestamp: 1500,
+                last_known_block_count: 150,
+                last_known_block_cumulative_txo_count: 15000,
+            }
+        );
+    }
+
+    #[test]
+    fn merge_stops_at_gaps() {
+        // The first store is still loading its range, so the blocks of the second
+        // store cannot be counted yet.
+        let responses = vec![
+            view_store_response(BlockRange::new(0, 100), 80, 80),
+            view_store_response(BlockRange::new(100, 200), 150, 150),
+        ];
+        let state = ViewStoreState::merge(&responses);
+        assert_eq!(state.highest_processed_block_count, 80);
+        assert_eq!(state.highest_processed_block_signature_timestamp, 800);
+        assert_eq!(state.last_known_block_count, 150);
+
+        // Overlapping stores cover for each other.
+        let responses = vec![
+            view_store_response(BlockRange::new(0, 100), 80, 80),
+            view_store_response(BlockRange::new(0, 100), 100, 100),
+            view_store_response(BlockRange::new(100, 200), 150, 150),
+        ];
+        assert_eq!(
+            ViewStoreState::merge(&responses).highest_processed_block_count,
+            150
+        );
+
+        // Nothing is processed if no store covers the origin block.
+        let responses = vec![view_store_response(BlockRange::new(100, 200), 150, 150)];
+        assert_eq!(
+            ViewStoreState::merge(&responses).highest_processed_block_count,
+            0
+        );
+    }
+}
@@ -5,7 +5,7 @@ use grpcio::{RpcContext, RpcStatus, RpcStatusCode, UnarySink};
 use mc_attest_api::attest;
 use mc_common::logger::{log, Logger};
 use mc_fog_api::view_grpc::FogViewApi;
-use mc_fog_recovery_db_iface::RecoveryDb;
+use mc_fog_recovery_db_iface::{filter_user_events_for_account, RecoveryDb};
 use mc_fog_types::view::QueryRequestAAD;
 use mc_fog_view_enclave::{Error as ViewEnclaveError, ViewEnclaveProxy};
 use mc_fog_view_enclave_api::UntrustedQueryResponse;
@@ -75,6 +75,8 @@ impl<E: ViewEnclaveProxy, DB: RecoveryDb + Send + Sync> FogViewService<E, DB> {
                         .search_user_events(query_request_aad.start_from_user_event_id)
                         .map_err(|e| rpc_internal_error("search_user_events", e, &self.logger))
                 })?;
+            let user_events =
+                filter_user_events_for_account(user_events, query_request_aad.account_start_block);
 
             let (
                 highest_processed_block_count,
@@ -103,7 +105,7 @@ impl<E: ViewEnclaveProxy, DB: RecoveryDb + Send + Sync> FogViewService<E, DB> {
             let result_blob = tracer.in_span("enclave_query", |_cx| {
                 self.enclave
                     .query(request.into(), untrusted_query_response)
-                    .map_err(|e| self.enclave_err_to_rpc_status("enclave request", e))
+                    .map_err(|e| enclave_err_to_rpc_status("enclave request", e, &self.logger))
             })?;
 
             let mut resp = attest::Message::new();
@@ -111,21 +113,23 @@ impl<E: ViewEnclaveProxy, DB: RecoveryDb + Send + Sync> FogViewService<E, DB> {
             Ok(resp)
         })
     }
+}
 
-    // Helper function that is common
-    fn enclave_err_to_rpc_status(&self, context: &str, src: ViewEnclaveError) -> RpcStatus {
-        // Treat prost-decode error as an invalid arg,
-        // treat attest error as permission denied,
-        // everything else is an internal error
-        match src {
-            ViewEnclaveError::ProstDecode => {
-                rpc_invalid_arg_error(context, "Prost decode failed", &self.logger)
-            }
-            ViewEnclaveError::AttestEnclave(err) => {
-                rpc_permissions_error(context, err, &self.logger)
-            }
-            other => rpc_internal_error(context, format!("{}", &other), &self.logger),
+/// Convert a view enclave error into an RpcStatus.
+pub(crate) fn enclave_err_to_rpc_status(
+    context: &str,
+    src: ViewEnclaveError,
+    logger: &Logger,
+) -> RpcStatus {
+    // Treat prost-decode error as an invalid arg,
+    // treat attest error as permission denied,
+    // everything else is an internal error
+    match src {
+        ViewEnclaveError::ProstDecode => {
+            rpc_invalid_arg_error(context, "Prost decode failed", logger)
         }
+        ViewEnclaveError::AttestEnclave(err) => rpc_permissions_error(context, err, logger),
+        other => rpc_internal_error(context, format!("{}", &other), logger),
     }
 }
 
@@ -0,0 +1,128 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! Serves fog view routers, when this node acts as a view store shard.
+
+use crate::{fog_view_service::enclave_err_to_rpc_status, server::DbPollSharedState};
+use grpcio::{RpcContext, RpcStatus, UnarySink};
+use mc_attest_api::{attest, attest_grpc::AttestedApi};
+use mc_common::logger::{log, Logger};
+use mc_fog_api::{view::ViewStoreQueryResponse, view_grpc::FogViewStoreApi};
+use mc_fog_types::common::BlockRange;
+use mc_fog_view_enclave::ViewEnclaveProxy;
+use mc_util_grpc::{rpc_logger, rpc_permissions_error, send_result};
+use mc_util_metrics::SVC_COUNTERS;
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone)]
+pub struct FogViewStoreService<E: ViewEnclaveProxy> {
+    /// Enclave holding the records of our block range
+    enclave: E,
+
+    /// The block range we load records for
+    block_range: BlockRange,
+
+    /// Shared state from db polling thread.
+    db_poll_shared_state: Arc<Mutex<DbPollSharedState>>,
+
+    /// Slog logger object
+    logger: Logger,
+}
+
+impl<E: ViewEnclaveProxy> FogViewStoreService<E> {
+    /// Creates a new fog-view-store-service (but does not create sockets and
+    /// start it etc.)
+    pub fn new(
+        enclave: E,
+        block_range: BlockRange,
+        db_poll_shared_state: Arc<Mutex<DbPollSharedState>>,
+        logger: Logger,
+    ) -> Self {
+        Self {
+            enclave,
+            block_range,
+            db_poll_shared_state,
+            logger,
+        }
+    }
+
+    /// Forward to enclave, and attach the state of our block range
+    pub fn query_impl(
+        &mut self,
+        request: attest::Message,
+    ) -> Result<ViewStoreQueryResponse, RpcStatus> {
+        // Grab the shared state before querying the enclave, so that everything
+        // up to the reported block count was loaded when the query is served.
+        let mut response = ViewStoreQueryResponse::new();
+        {
+            let shared_state = self.db_poll_shared_state.lock().expect("mutex poisoned");
+            response.set_highest_processed_block_count(shared_state.highest_processed_block_count);
+            response.set_highest_processed_block_signature_timestamp(
+                shared_state.highest_processed_block_signature_timestamp,
+            );
+            response.set_last_known_block_count(shared_state.last_known_block_count);
+            response.set_last_known_block_cumulative_txo_count(
+                shared_state.last_known_block_cumulative_txo_count,
+            );
+        }
+        response.set_block_range((&self.block_range).into());
+
+        let query_response = self
+            .enclave
+            .query_store(request.into())
+            .map_err(|e| enclave_err_to_rpc_status("enclave request", e, &self.logger))?;
+        response.set_query_response(query_response.into());
+
+        Ok(response)
+    }
+}
+
+impl<E: ViewEnclaveProxy> FogViewStoreApi for FogViewStoreService<E> {
+    fn query(
+        &mut self,
+        ctx: RpcContext,
+        request: attest::Message,
+        sink: UnarySink<ViewStoreQueryResponse>,
+    ) {
+        let _timer = SVC_COUNTERS.req(&ctx);
+        mc_common::logger::scoped_global_logger(&rpc_logger(&ctx, &self.logger), |logger| {
+            send_result(ctx, sink, self.query_impl(request), logger)
+        })
+    }
+}
+
+impl<E: ViewEnclaveProxy> AttestedApi for FogViewStoreService<E> {
+    fn auth(
+        &mut self,
+        ctx: RpcContext,
+        request: attest::AuthMessage,
+        sink: UnarySink<attest::AuthMessage>,
+    ) {
+        let _timer = SVC_COUNTERS.req(&ctx);
+        mc_common::logger::scoped_global_logger(&rpc_logger(&ctx, &self.logger), |logger| {
+            match self.enclave.router_accept(request.into()) {
+                Ok((response, _session_id)) => {
+                    send_result(ctx, sink, Ok(response.into()), logger);
+                }
+                Err(peer_error) => {
+                    // This is debug because there's no requirement on the remote party to trigger
+                    // it.
+                    log::debug!(
+                        logger,
+                        "ViewEnclaveApi::router_accept failed: {}",
+                        peer_error
+                    );
+                    send_result(
+                        ctx,
+                        sink,
+                        Err(rpc_permissions_error(
+                            "router_auth",
+                            "Permission denied",
+                            logger,
+                        )),
+                        logger,
+                    );
+                }
+            }
+        });
+    }
+}
@@ -4,9 +4,13 @@
 
 pub mod config;
 pub mod error;
+pub mod fog_view_router_service;
 pub mod fog_view_service;
+pub mod fog_view_store_service;
+pub mod router_server;
 pub mod server;
 
 mod block_tracker;
 mod counters;
 mod db_fetcher;
+mod view_store_connection;
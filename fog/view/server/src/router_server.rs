@@ -0,0 +1,170 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! Server object containing a view router
+//! Constructible from config (for testability) and with a mechanism for
+//! stopping it
+
+use crate::{
+    config::FogViewRouterConfig, counters, fog_view_router_service::FogViewRouterService,
+    view_store_connection::ViewStoreConnection,
+};
+use futures::executor::block_on;
+use mc_attest_net::RaClient;
+use mc_common::{
+    logger::{log, Logger},
+    time::TimeProvider,
+};
+use mc_fog_api::view_grpc;
+use mc_fog_recovery_db_iface::RecoveryDb;
+use mc_fog_uri::ConnectionUri;
+use mc_fog_view_enclave::ViewEnclaveProxy;
+use mc_sgx_report_cache_untrusted::ReportCacheThread;
+use mc_util_grpc::{
+    AnonymousAuthenticator, Authenticator, ConnectionUriGrpcioServer, TokenAuthenticator,
+};
+use std::sync::{Arc, Mutex};
+
+pub struct ViewRouter<E, RC, DB>
+where
+    E: ViewEnclaveProxy,
+    RC: RaClient + Send + Sync + 'static,
+    DB: RecoveryDb + Clone + Send + Sync + 'static,
+{
+    config: FogViewRouterConfig,
+    server: grpcio::Server,
+    enclave: E,
+    ra_client: RC,
+    report_cache_thread: Option<ReportCacheThread>,
+    logger: Logger,
+    _db: std::marker::PhantomData<DB>,
+}
+
+impl<E, RC, DB> ViewRouter<E, RC, DB>
+where
+    E: ViewEnclaveProxy,
+    RC: RaClient + Send + Sync + 'static,
+    DB: RecoveryDb + Clone + Send + Sync + 'static,
+{
+    /// Make a new view router instance
+    pub fn new(
+        config: FogViewRouterConfig,
+        enclave: E,
+        recovery_db: DB,
+        ra_client: RC,
+        time_provider: impl TimeProvider + 'static,
+        logger: Logger,
+    ) -> ViewRouter<E, RC, DB> {
+        let env = Arc::new(
+            grpcio::EnvBuilder::new()
+                .name_prefix("Main-RPC".to_string())
+                .build(),
+        );
+
+        let view_store_env = Arc::new(
+            grpcio::EnvBuilder::new()
+                .name_prefix("ViewStore-RPC".to_string())
+                .build(),
+        );
+        let view_stores = Arc::new(
+            config
+                .shard_uris
+                .iter()
+                .map(|shard_uri| {
+                    Mutex::new(ViewStoreConnection::new(
+                        enclave.clone(),
+                        shard_uri.clone(),
+                        view_store_env.clone(),
+                        logger.clone(),
+                    ))
+                })
+                .collect::<Vec<_>>(),
+        );
+
+        let client_authenticator: Arc<dyn Authenticator + Sync + Send> =
+            if let Some(shared_secret) = config.client_auth_token_secret.as_ref() {
+                Arc::new(TokenAuthenticator::new(
+                    *shared_secret,
+                    config.client_auth_token_max_lifetime,
+                    time_provider,
+                ))
+            } else {
+                Arc::new(AnonymousAuthenticator::default())
+            };
+
+        let fog_view_router_service = view_grpc::create_fog_view_api(FogViewRouterService::new(
+            enclave.clone(),
+            Arc::new(recovery_db),
+            view_stores,
+            client_authenticator,
+            logger.clone(),
+        ));
+        log::debug!(logger, "Constructed View Router GRPC Service");
+
+        // Health check service
+        let health_service = mc_util_grpc::HealthService::new(None, logger.clone()).into_service();
+
+        // Package service into grpc server
+        log::info!(
+            logger,
+            "Starting View router on {}",
+            config.client_listen_uri.addr(),
+        );
+        let server_builder = grpcio::ServerBuilder::new(env)
+            .register_service(fog_view_router_service)
+            .register_service(health_service)
+            .bind_using_uri(&config.client_listen_uri, logger.clone());
+
+        let server = server_builder.build().unwrap();
+
+        Self {
+            config,
+            server,
+            enclave,
+            ra_client,
+            report_cache_thread: None,
+            logger,
+            _db: Default::default(),
+        }
+    }
+
+    /// Start the server, which starts all the worker threads
+    pub fn start(&mut self) {
+        // Attestation to view stores requires our enclave to have a verification
+        // report.
+        self.report_cache_thread = Some(
+            ReportCacheThread::start(
+                self.enclave.clone(),
+                self.ra_client.clone(),
+                self.config.ias_spid,
+                &counters::ENCLAVE_REPORT_TIMESTAMP,
+                self.logger.clone(),
+            )
+            .expect("failed starting report cache thread"),
+        );
+
+        self.server.start();
+        for (host, port) in self.server.bind_addrs() {
+            log::info!(self.logger, "API listening on {}:{}", host, port);
+        }
+    }
+
+    /// Stop the server and all worker threads
+    pub fn stop(&mut self) {
+        if let Some(ref mut thread) = self.report_cache_thread.take() {
+            thread.stop().expect("Could not stop report cache thread");
+        }
+
+        block_on(self.server.shutdown()).expect("Could not stop grpc server");
+    }
+}
+
+impl<E, RC, DB> Drop for ViewRouter<E, RC, DB>
+where
+    E: ViewEnclaveProxy,
+    RC: RaClient + Send + Sync + 'static,
+    DB: RecoveryDb + Clone + Send + Sync + 'static,
+{
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
@@ -6,9 +6,10 @@
 
 use crate::{
     block_tracker::BlockTracker, config::MobileAcctViewConfig, counters, db_fetcher::DbFetcher,
-    fog_view_service::FogViewService,
+    fog_view_service::FogViewService, fog_view_store_service::FogViewStoreService,
 };
 use futures::executor::block_on;
+use mc_attest_api::attest_grpc;
 use mc_attest_net::RaClient;
 use mc_common::{
     logger::{log, Logger},
@@ -17,8 +18,9 @@ use mc_common::{
 };
 use mc_crypto_keys::CompressedRistrettoPublic;
 use mc_fog_api::view_grpc;
+use mc_fog_recovery_db_archive::Archive;
 use mc_fog_recovery_db_iface::RecoveryDb;
-use mc_fog_types::ETxOutRecord;
+use mc_fog_types::{common::BlockRange, ETxOutRecord};
 use mc_fog_uri::ConnectionUri;
 use mc_fog_view_enclave::ViewEnclaveProxy;
 use mc_sgx_report_cache_untrusted::ReportCacheThread;
@@ -30,6 +32,7 @@ use mc_util_telemetry::{
     block_span_builder, start_block_span, telemetry_static_key, tracer, Key, Span,
 };
 use std::{
+    path::PathBuf,
     sync::{
         atomic::{AtomicBool, Ordering},
         Arc, Mutex,
@@ -46,6 +49,7 @@ where
 {
     config: MobileAcctViewConfig,
     server: grpcio::Server,
+    store_server: Option<grpcio::Server>,
     enclave: E,
     ra_client: RC,
     report_cache_thread: Option<ReportCacheThread>,
@@ -73,6 +77,8 @@ where
         let db_poll_thread = DbPollThread::new(
             enclave.clone(),
             recovery_db.clone(),
+            config.block_range(),
+            config.archive_dir.clone(),
             readiness_indicator.clone(),
             logger.clone(),
         );
@@ -94,15 +100,6 @@ where
                 Arc::new(AnonymousAuthenticator::default())
             };
 
-        let fog_view_service = view_grpc::create_fog_view_api(FogViewService::new(
-            enclave.clone(),
-            Arc::new(recovery_db),
-            db_poll_thread.get_shared_state(),
-            client_authenticator,
-            logger.clone(),
-        ));
-        log::debug!(logger, "Constructed View GRPC Service");
-
         // Health check service
         let health_service =
             mc_util_grpc::HealthService::new(Some(readiness_indicator.into()), logger.clone())
@@ -114,16 +111,55 @@ where
             "Starting View server on {}",
             config.client_listen_uri.addr(),
         );
-        let server_builder = grpcio::ServerBuilder::new(env)
-            .register_service(fog_view_service)
-            .register_service(health_service)
-            .bind_using_uri(&config.client_listen_uri, logger.clone());
+        let mut server_builder =
+            grpcio::ServerBuilder::new(env.clone()).register_service(health_service);
+        // A view store shard only holds the records of its block range, so
+        // clients must query it through a router.
+        if config.store_listen_uri.is_none() {
+            let fog_view_service = view_grpc::create_fog_view_api(FogViewService::new(
+                enclave.clone(),
+                Arc::new(recovery_db),
+                db_poll_thread.get_shared_state(),
+                client_authenticator,
+                logger.clone(),
+            ));
+            log::debug!(logger, "Constructed View GRPC Service");
+            server_builder = server_builder.register_service(fog_view_service);
+        }
+        let server_builder =
+            server_builder.bind_using_uri(&config.client_listen_uri, logger.clone());
 
         let server = server_builder.build().unwrap();
 
+        // When acting as a view store shard, routers are served separately from
+        // clients.
+        let store_server = config.store_listen_uri.as_ref().map(|store_listen_uri| {
+            let fog_view_store_service = FogViewStoreService::new(
+                enclave.clone(),
+                config.block_range(),
+                db_poll_thread.get_shared_state(),
+                logger.clone(),
+            );
+
+            log::info!(
+                logger,
+                "Starting View store server on {}",
+                store_listen_uri.addr(),
+            );
+            grpcio::ServerBuilder::new(env)
+                .register_service(view_grpc::create_fog_view_store_api(
+                    fog_view_store_service.clone(),
+                ))
+                .register_service(attest_grpc::create_attested_api(fog_view_store_service))
+                .bind_using_uri(store_listen_uri, logger.clone())
+                .build()
+                .unwrap()
+        });
+
         Self {
             config,
             server,
+            store_server,
             enclave,
             ra_client,
             report_cache_thread: None,
@@ -151,6 +187,13 @@ where
         for (host, port) in self.server.bind_addrs() {
             log::info!(self.logger, "API listening on {}:{}", host, port);
         }
+
+        if let Some(store_server) = self.store_server.as_mut() {
+            store_server.start();
+            for (host, port) in store_server.bind_addrs() {
+                log::info!(self.logger, "Store API listening on {}:{}", host, port);
+            }
+        }
     }
 
     /// Stop the server and all worker threads
@@ -164,6 +207,10 @@ where
             .expect("Could not stop db poll thread");
 
         block_on(self.server.shutdown()).expect("Could not stop grpc server");
+
+        if let Some(mut store_server) = self.store_server.take() {
+            block_on(store_server.shutdown()).expect("Could not stop store grpc server");
+        }
     }
 
     /// Get the highest block count for which we can guarantee we have loaded
@@ -215,6 +262,12 @@ where
     /// Recovery db.
     db: DB,
 
+    /// The range of blocks to load records for.
+    block_range: BlockRange,
+
+    /// The directory of the archive to load archived blocks from, if any.
+    archive_dir: Option<PathBuf>,
+
     /// Join handle used to wait for the thread to terminate.
     join_handle: Option<JoinHandle<()>>,
 
@@ -248,6 +301,8 @@ where
     pub fn new(
         enclave: E,
         db: DB,
+        block_range: BlockRange,
+        archive_dir: Option<PathBuf>,
         readiness_indicator: ReadinessIndicator,
         logger: Logger,
     ) -> Self {
@@ -257,6 +312,8 @@ where
         Self {
             enclave,
             db,
+            block_range,
+            archive_dir,
             join_handle: None,
             stop_requested,
             shared_state,
@@ -276,6 +333,8 @@ where
 
         let thread_enclave = self.enclave.clone();
         let thread_db = self.db.clone();
+        let thread_block_range = self.block_range.clone();
+        let thread_archive_dir = self.archive_dir.clone();
         let thread_stop_requested = self.stop_requested.clone();
         let thread_shared_state = self.shared_state.clone();
         let thread_readiness_indicator = self.readiness_indicator.clone();
@@ -288,6 +347,8 @@ where
                     Self::thread_entrypoint(
                         thread_enclave,
                         thread_db,
+                        thread_block_range,
+                        thread_archive_dir,
                         thread_stop_requested,
                         thread_shared_state,
                         thread_readiness_indicator,
@@ -311,6 +372,8 @@ where
     fn thread_entrypoint(
         enclave: E,
         db: DB,
+        block_range: BlockRange,
+        archive_dir: Option<PathBuf>,
         stop_requested: Arc<AtomicBool>,
         shared_state: Arc<Mutex<DbPollSharedState>>,
         readiness_indicator: ReadinessIndicator,
@@ -322,6 +385,8 @@ where
             stop_requested,
             enclave,
             db,
+            block_range,
+            archive_dir,
             shared_state,
             readiness_indicator,
             logger.clone(),
@@ -404,6 +469,8 @@ where
         stop_requested: Arc<AtomicBool>,
         enclave: E,
         db: DB,
+        block_range: BlockRange,
+        archive_dir: Option<PathBuf>,
         shared_state: Arc<Mutex<DbPollSharedState>>,
         readiness_indicator: ReadinessIndicator,
         logger: Logger,
@@ -413,8 +480,14 @@ where
             enclave,
             db: db.clone(),
             shared_state,
-            db_fetcher: DbFetcher::new(db, readiness_indicator, logger.clone()),
-            enclave_block_tracker: BlockTracker::new(logger.clone()),
+            db_fetcher: DbFetcher::new(
+                db,
+                block_range.clone(),
+                archive_dir.map(Archive::new),
+                readiness_indicator,
+                logger.clone(),
+            ),
+            enclave_block_tracker: BlockTracker::with_block_range(block_range, logger.clone()),
             last_unblocked_at: Instant::now(),
             logger,
         }
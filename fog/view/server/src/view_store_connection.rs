@@ -0,0 +1,106 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! An attested connection from a fog view router to a fog view store.
+
+use crate::error::ViewStoreConnectionError;
+use grpcio::{ChannelBuilder, Environment};
+use mc_attest_api::attest_grpc::AttestedApiClient;
+use mc_attest_enclave_api::PeerSession;
+use mc_common::{
+    logger::{log, o, Logger},
+    ResponderId,
+};
+use mc_fog_api::view_grpc::FogViewStoreApiClient;
+use mc_fog_uri::{ConnectionUri, FogViewStoreUri};
+use mc_fog_view_enclave::ViewEnclaveProxy;
+use mc_util_grpc::ConnectionUriGrpcioChannel;
+use std::{
+    fmt::{Display, Formatter, Result as FmtResult},
+    sync::Arc,
+};
+
+/// A connection to a view store, attested by the local (router) enclave.
+pub struct ViewStoreConnection<E: ViewEnclaveProxy> {
+    /// The local enclave.
+    enclave: E,
+
+    /// The responder id of the view store enclave.
+    view_store_id: ResponderId,
+
+    /// The session established with the view store enclave, if attested.
+    peer_session: Option<PeerSession>,
+
+    /// The view store's URI.
+    uri: FogViewStoreUri,
+
+    /// The gRPC client used to attest to the view store.
+    attested_api_client: AttestedApiClient,
+
+    /// The gRPC client used to query the view store once attested.
+    view_store_api_client: FogViewStoreApiClient,
+
+    /// Logger.
+    logger: Logger,
+}
+
+impl<E: ViewEnclaveProxy> ViewStoreConnection<E> {
+    /// Create a new, unattested, connection to the view store at `uri`.
+    pub fn new(enclave: E, uri: FogViewStoreUri, env: Arc<Environment>, logger: Logger) -> Self {
+        let view_store_id = uri.responder_id().unwrap_or_else(|_| {
+            panic!("Could not get responder id from uri {:?}", uri.to_string())
+        });
+
+        let logger = logger.new(o!("mc.fog.view_store" => uri.to_string()));
+
+        let ch = ChannelBuilder::default_channel_builder(env).connect_to_uri(&uri, &logger);
+
+        let attested_api_client = AttestedApiClient::new(ch.clone());
+        let view_store_api_client = FogViewStoreApiClient::new(ch);
+
+        Self {
+            enclave,
+            view_store_id,
+            peer_session: None,
+            uri,
+            attested_api_client,
+            view_store_api_client,
+            logger,
+        }
+    }
+
+    /// Get the session with the view store enclave, attesting first if needed.
+    pub fn attested_session(&mut self) -> Result<PeerSession, ViewStoreConnectionError> {
+        if let Some(peer_session) = self.peer_session.as_ref() {
+            return Ok(peer_session.clone());
+        }
+
+        log::debug!(self.logger, "Attesting to view store");
+        let req = self.enclave.view_store_init(self.view_store_id.clone())?;
+        let res = self.attested_api_client.auth(&req.into())?;
+        let peer_session = self
+            .enclave
+            .view_store_connect(self.view_store_id.clone(), res.into())?;
+
+        self.peer_session = Some(peer_session.clone());
+        Ok(peer_session)
+    }
+
+    /// Tear down the session with the view store enclave, so that the next
+    /// query attests again.
+    pub fn deattest(&mut self) {
+        if self.peer_session.take().is_some() {
+            log::trace!(self.logger, "Tearing down existing attested connection.");
+        }
+    }
+
+    /// The gRPC client used to query the view store.
+    pub fn view_store_api_client(&self) -> FogViewStoreApiClient {
+        self.view_store_api_client.clone()
+    }
+}
+
+impl<E: ViewEnclaveProxy> Display for ViewStoreConnection<E> {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "{}", self.uri)
+    }
+}
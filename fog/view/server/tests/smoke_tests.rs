@@ -64,6 +64,10 @@ fn get_test_environment(
             client_listen_uri: uri.clone(),
             client_auth_token_secret: None,
             omap_capacity: view_omap_capacity,
+            store_listen_uri: None,
+            block_range_start: 0,
+            block_range_end: None,
+            archive_dir: None,
             ias_spid: Default::default(),
             ias_api_key: Default::default(),
             admin_listen_uri: Default::default(),
@@ -289,7 +293,7 @@ fn test_view_integration(view_omap_capacity: u64, logger: Logger) {
     }
 
     // Now make some requests against view_client
-    let result = view_client.request(0, 0, Default::default()).unwrap();
+    let result = view_client.request(0, 0, 0, Default::default()).unwrap();
     assert_eq!(result.highest_processed_block_count, 6);
     // 4 events are expected (in the following order):
     // - 1 new rng record (for invoc_id1)
@@ -305,7 +309,7 @@ fn test_view_integration(view_omap_capacity: u64, logger: Logger) {
     assert_eq!(result.missed_block_ranges[0], BlockRange::new(3, 4));
     assert_eq!(result.last_known_block_count, 6);
 
-    let result = view_client.request(0, 0, Default::default()).unwrap();
+    let result = view_client.request(0, 0, 0, Default::default()).unwrap();
     assert_eq!(result.highest_processed_block_count, 6);
     assert_eq!(result.next_start_from_user_event_id, 4);
     assert_eq!(result.rng_records.len(), 2);
@@ -316,10 +320,22 @@ fn test_view_integration(view_omap_capacity: u64, logger: Logger) {
     assert_eq!(result.missed_block_ranges[0], BlockRange::new(3, 4));
     assert_eq!(result.last_known_block_count, 6);
 
+    // An account which started at block 4 doesn't need invoc_id1, which was
+    // decommissioned after block 1, or the missed block 3
+    let result = view_client.request(0, 0, 4, Default::default()).unwrap();
+    assert_eq!(result.highest_processed_block_count, 6);
+    assert_eq!(result.next_start_from_user_event_id, 4);
+    assert_eq!(result.rng_records.len(), 1);
+    assert_eq!(result.rng_records[0].pubkey, pubkey2);
+    assert_eq!(result.decommissioned_ingest_invocations.len(), 0);
+    assert_eq!(result.tx_out_search_results.len(), 0);
+    assert_eq!(result.missed_block_ranges.len(), 0);
+    assert_eq!(result.last_known_block_count, 6);
+
     let result = view_client
         // starting at user event id 2 skips invoc_id1
         // (event id 1 is for invoc_id1)
-        .request(1, 0, Default::default())
+        .request(1, 0, 0, Default::default())
         .unwrap();
     assert_eq!(result.highest_processed_block_count, 6);
     assert_eq!(result.next_start_from_user_event_id, 4);
@@ -332,7 +348,7 @@ fn test_view_integration(view_omap_capacity: u64, logger: Logger) {
     assert_eq!(result.last_known_block_count, 6);
 
     // No events after event id 4
-    let result = view_client.request(4, 0, Default::default()).unwrap();
+    let result = view_client.request(4, 0, 0, Default::default()).unwrap();
     assert_eq!(result.highest_processed_block_count, 6);
     assert_eq!(result.next_start_from_user_event_id, 4);
     assert_eq!(result.rng_records.len(), 0);
@@ -340,7 +356,7 @@ fn test_view_integration(view_omap_capacity: u64, logger: Logger) {
     assert_eq!(result.missed_block_ranges.len(), 0);
     assert_eq!(result.last_known_block_count, 6);
 
-    let result = view_client.request(80, 0, Default::default()).unwrap();
+    let result = view_client.request(80, 0, 0, Default::default()).unwrap();
     assert_eq!(result.highest_processed_block_count, 6);
     assert_eq!(result.next_start_from_user_event_id, 80);
     assert_eq!(result.rng_records.len(), 0);
@@ -349,7 +365,7 @@ fn test_view_integration(view_omap_capacity: u64, logger: Logger) {
     assert_eq!(result.last_known_block_count, 6);
 
     let result = view_client
-        .request(4, 0, vec![vec![1u8; 16], vec![2u8; 16], vec![3u8; 16]])
+        .request(4, 0, 0, vec![vec![1u8; 16], vec![2u8; 16], vec![3u8; 16]])
         .unwrap();
     assert_eq!(result.highest_processed_block_count, 6);
     assert_eq!(result.next_start_from_user_event_id, 4);
@@ -374,7 +390,7 @@ fn test_view_integration(view_omap_capacity: u64, logger: Logger) {
     assert_eq!(result.last_known_block_count, 6);
 
     let result = view_client
-        .request(4, 0, vec![vec![5u8; 16], vec![8u8; 16], vec![200u8; 16]])
+        .request(4, 0, 0, vec![vec![5u8; 16], vec![8u8; 16], vec![200u8; 16]])
         .unwrap();
     assert_eq!(result.highest_processed_block_count, 6);
     assert_eq!(result.next_start_from_user_event_id, 4);
@@ -399,7 +415,7 @@ fn test_view_integration(view_omap_capacity: u64, logger: Logger) {
     assert_eq!(result.missed_block_ranges.len(), 0); // no range reported since we started at event id 4
     assert_eq!(result.last_known_block_count, 6);
 
-    let result = view_client.request(0, 0, vec![vec![200u8; 17]]).unwrap();
+    let result = view_client.request(0, 0, 0, vec![vec![200u8; 17]]).unwrap();
     assert_eq!(result.highest_processed_block_count, 6);
     assert_eq!(result.next_start_from_user_event_id, 4);
     assert_eq!(result.rng_records.len(), 2);
@@ -467,7 +483,7 @@ fn assert_e_tx_out_records_sanity(
 
     let mut allowed_tries = 60usize;
     loop {
-        let result = client.request(0, 0, search_keys.clone()).unwrap();
+        let result = client.request(0, 0, 0, search_keys.clone()).unwrap();
         if result.tx_out_search_results == expected_results {
             break;
         }
@@ -548,7 +564,7 @@ fn test_overlapping_ingest_ranges(logger: Logger) {
     assert_eq!(server.highest_processed_block_count(), 5);
 
     // See that we get a sane client response.
-    let result = view_client.request(0, 0, Default::default()).unwrap();
+    let result = view_client.request(0, 0, 0, Default::default()).unwrap();
     assert_eq!(result.highest_processed_block_count, 5);
     assert_eq!(result.last_known_block_count, 15); // The last known block is not tied to the serial processing of blocks.
 
@@ -591,7 +607,7 @@ fn test_overlapping_ingest_ranges(logger: Logger) {
             continue;
         }
 
-        let result = view_client.request(0, 0, Default::default()).unwrap();
+        let result = view_client.request(0, 0, 0, Default::default()).unwrap();
         if result.last_known_block_count != 20 {
             continue;
         }
@@ -602,7 +618,7 @@ fn test_overlapping_ingest_ranges(logger: Logger) {
     assert_eq!(server.highest_processed_block_count(), 15);
 
     // See that we get a sane client response.
-    let result = view_client.request(0, 0, Default::default()).unwrap();
+    let result = view_client.request(0, 0, 0, Default::default()).unwrap();
     assert_eq!(result.highest_processed_block_count, 15);
     assert_eq!(result.last_known_block_count, 20); // The last known block is not tied to the serial processing of blocks.
 
@@ -640,7 +656,7 @@ fn test_overlapping_ingest_ranges(logger: Logger) {
     // See that we get a sane client response.
     let mut allowed_tries = 60usize;
     loop {
-        let result = view_client.request(0, 0, Default::default()).unwrap();
+        let result = view_client.request(0, 0, 0, Default::default()).unwrap();
         if result.highest_processed_block_count == 20 && result.last_known_block_count == 30 {
             break;
         }
@@ -687,7 +703,7 @@ fn test_start_with_missing_range(logger: Logger) {
     // See that we get a sane client response.
     let mut allowed_tries = 60usize;
     loop {
-        let result = view_client.request(0, 0, Default::default()).unwrap();
+        let result = view_client.request(0, 0, 0, Default::default()).unwrap();
         if result.highest_processed_block_count == 0 && result.last_known_block_count == 0 {
             break;
         }
@@ -708,7 +724,7 @@ fn test_start_with_missing_range(logger: Logger) {
 
     let mut allowed_tries = 60usize;
     loop {
-        let result = view_client.request(0, 0, Default::default()).unwrap();
+        let result = view_client.request(0, 0, 0, Default::default()).unwrap();
         if result.highest_processed_block_count == 15 && result.last_known_block_count == 15 {
             break;
         }
@@ -766,7 +782,7 @@ fn test_middle_missing_range_with_decommission(logger: Logger) {
     // associated to it yet.
     let mut allowed_tries = 60usize;
     loop {
-        let result = view_client.request(0, 0, Default::default()).unwrap();
+        let result = view_client.request(0, 0, 0, Default::default()).unwrap();
         if result.highest_processed_block_count == 5 && result.last_known_block_count == 5 {
             break;
         }
@@ -805,7 +821,7 @@ fn test_middle_missing_range_with_decommission(logger: Logger) {
     // block 10 but the last known block should be 15.
     let mut allowed_tries = 60usize;
     loop {
-        let result = view_client.request(0, 0, Default::default()).unwrap();
+        let result = view_client.request(0, 0, 0, Default::default()).unwrap();
         if result.highest_processed_block_count == 15 && result.last_known_block_count == 15 {
             break;
         }
@@ -824,7 +840,7 @@ fn test_middle_missing_range_with_decommission(logger: Logger) {
 
     let mut allowed_tries = 60usize;
     loop {
-        let result = view_client.request(0, 0, Default::default()).unwrap();
+        let result = view_client.request(0, 0, 0, Default::default()).unwrap();
         if result.highest_processed_block_count == 15 && result.last_known_block_count == 15 {
             break;
         }
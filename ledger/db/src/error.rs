@@ -76,6 +76,9 @@ pub enum Error {
      */
     MintLimitExceeded(u64, u64, u64),
 
+    /// Mint limit window exceeded: Attempted to mint {0}
+    MintLimitWindowExceeded(u64),
+
     /// Total minted amount cannot decrease: {0} < {1}
     TotalMintedAmountCannotDecrease(u64, u64),
 
@@ -391,14 +391,18 @@ impl Ledger for LedgerDB {
     }
 
     /// Attempt to get an active mint configuration that is able to verify and
-    /// accommodate a given MintTx.
+    /// accommodate a given MintTx in the next block.
     fn get_active_mint_config_for_mint_tx(
         &self,
         mint_tx: &MintTx,
     ) -> Result<ActiveMintConfig, Error> {
         let db_transaction = self.env.begin_ro_txn()?;
-        self.mint_config_store
-            .get_active_mint_config_for_mint_tx(mint_tx, &db_transaction)
+        let block_index = key_bytes_to_u64(db_transaction.get(self.counts, &NUM_BLOCKS_KEY)?);
+        self.mint_config_store.get_active_mint_config_for_mint_tx(
+            mint_tx,
+            block_index,
+            &db_transaction,
+        )
     }
 }
 
@@ -23,7 +23,9 @@ pub use crate::{
     ledger_db::{key_bytes_to_u64, u64_to_key_bytes, LedgerDB},
     ledger_trait::{Ledger, MockLedger},
     metrics::LedgerMetrics,
-    mint_config_store::{ActiveMintConfig, ActiveMintConfigs, MintConfigStore},
+    mint_config_store::{
+        ActiveMintConfig, ActiveMintConfigs, MintConfigStore, MintLimitWindowCapacity, RecentMint,
+    },
     mint_tx_store::MintTxStore,
     tx_out_store::TxOutStore,
 };
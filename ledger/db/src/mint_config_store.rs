@@ -8,7 +8,8 @@
 //!      1) It allows transaction validation code to figure out if a mint
 //! transaction is allowed to mint.
 //!      2) It enables keeping track of how much was minted using a given
-//! configuration. This is used to enforce the per-configuration mint limit.
+//! configuration. This is used to enforce the per-configuration mint limit and
+//! mint limit windows.
 //! 2) A mapping of nonce -> block index of the block containing the
 //! MintConfigTx with that nonce. This is mainly used to prevent replay
 //! attacks.
@@ -20,7 +21,10 @@ use lmdb::{Cursor, Database, DatabaseFlags, Environment, RwTransaction, Transact
 use mc_blockchain_types::BlockIndex;
 use mc_common::HashMap;
 use mc_transaction_core::{
-    mint::{MintConfig, MintConfigTx, MintTx, ValidatedMintConfigTx},
+    mint::{
+        validate_against_mint_limit_windows, MintConfig, MintConfigTx, MintLimitWindow, MintRecord,
+        MintTx, ValidatedMintConfigTx,
+    },
     TokenId,
 };
 use mc_util_serial::{decode, encode, Message};
@@ -56,6 +60,41 @@ pub struct ActiveMintConfigs {
     /// The original MintConfigTx that this object was created from.
     #[prost(message, required, tag = "2")]
     pub mint_config_tx: MintConfigTx,
+
+    /// Mints made by configurations that have mint limit windows, for as long
+    /// as they fall within one of those windows.
+    #[prost(message, repeated, tag = "3")]
+    pub recent_mints: Vec<RecentMint>,
+}
+
+/// An amount minted by one of the configurations in an ActiveMintConfigs.
+#[derive(Clone, Eq, Message, PartialEq)]
+pub struct RecentMint {
+    /// The index of the configuration that minted.
+    #[prost(uint64, tag = "1")]
+    pub config_index: u64,
+
+    /// The index of the block the mint was included in.
+    #[prost(uint64, tag = "2")]
+    pub block_index: u64,
+
+    /// The amount minted.
+    #[prost(uint64, tag = "3")]
+    pub amount: u64,
+}
+
+/// The amount minted and remaining within a mint limit window of a
+/// configuration.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MintLimitWindowCapacity {
+    /// The mint limit window.
+    pub window: MintLimitWindow,
+
+    /// How many tokens have been minted within the window.
+    pub minted: u64,
+
+    /// How many tokens can still be minted within the window.
+    pub remaining: u64,
 }
 
 impl ActiveMintConfigs {
@@ -80,11 +119,83 @@ impl ActiveMintConfigs {
         }
     }
 
+    /// Get the recent mints made by the configuration at the given index.
+    pub fn recent_mints(&self, config_index: usize) -> Vec<MintRecord> {
+        self.recent_mints
+            .iter()
+            .filter(|recent_mint| recent_mint.config_index == config_index as u64)
+            .map(|recent_mint| MintRecord {
+                block_index: recent_mint.block_index,
+                amount: recent_mint.amount,
+            })
+            .collect()
+    }
+
+    /// Get the minted and remaining amounts of each mint limit window of the
+    /// configuration at the given index, for windows ending with the block at
+    /// `block_index`.
+    pub fn mint_limit_window_capacities(
+        &self,
+        config_index: usize,
+        block_index: u64,
+    ) -> Vec<MintLimitWindowCapacity> {
+        let recent_mints = self.recent_mints(config_index);
+        self.configs
+            .get(config_index)
+            .map(|active_mint_config| {
+                active_mint_config
+                    .mint_config
+                    .mint_limit_windows
+                    .iter()
+                    .map(|window| MintLimitWindowCapacity {
+                        window: window.clone(),
+                        minted: window.minted(block_index, &recent_mints),
+                        remaining: window.remaining(block_index, &recent_mints),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Record an amount minted by the configuration at the given index in the
+    /// block at `block_index`, and forget mints that no longer fall within any
+    /// window.
+    pub fn record_mint(&mut self, config_index: usize, block_index: u64, amount: u64) {
+        let configs = &self.configs;
+        let longest_window = |config_index: u64| {
+            configs
+                .get(config_index as usize)
+                .and_then(|active_mint_config| {
+                    active_mint_config
+                        .mint_config
+                        .mint_limit_windows
+                        .iter()
+                        .map(|window| window.num_blocks)
+                        .max()
+                })
+                .unwrap_or(0)
+        };
+
+        if longest_window(config_index as u64) > 0 {
+            self.recent_mints.push(RecentMint {
+                config_index: config_index as u64,
+                block_index,
+                amount,
+            });
+        }
+
+        self.recent_mints.retain(|recent_mint| {
+            block_index.saturating_sub(recent_mint.block_index)
+                < longest_window(recent_mint.config_index)
+        });
+    }
+
     /// Attempt to get an ActiveMintConfig that that is capable of minting the
-    /// given amount of tokens.
+    /// given amount of tokens in the block at `block_index`.
     pub fn get_active_mint_config_for_mint_tx(
         &self,
         mint_tx: &MintTx,
+        block_index: u64,
     ) -> Result<ActiveMintConfig, Error> {
         // Check if the amount minted is going to tip us over the limit.
         if !self.can_mint(mint_tx.prefix.amount) {
@@ -122,7 +233,7 @@ impl ActiveMintConfigs {
         let mut error = Error::NotFound;
 
         let message = mint_tx.prefix.hash();
-        for active_mint_config in &self.configs {
+        for (config_index, active_mint_config) in self.configs.iter().enumerate() {
             // See if this mint config has signed the mint tx.
             if active_mint_config
                 .mint_config
@@ -142,7 +253,20 @@ impl ActiveMintConfigs {
                 .checked_add(mint_tx.prefix.amount)
             {
                 if new_total_minted <= active_mint_config.mint_config.mint_limit {
-                    return Ok(active_mint_config.clone());
+                    // It must also fit within the mint limit windows.
+                    if validate_against_mint_limit_windows(
+                        mint_tx,
+                        &active_mint_config.mint_config,
+                        block_index,
+                        &self.recent_mints(config_index),
+                    )
+                    .is_ok()
+                    {
+                        return Ok(active_mint_config.clone());
+                    }
+
+                    error = Error::MintLimitWindowExceeded(mint_tx.prefix.amount);
+                    continue;
                 }
             }
 
@@ -172,6 +296,7 @@ impl From<&MintConfigTx> for ActiveMintConfigs {
                 })
                 .collect(),
             mint_config_tx: mint_config_tx.clone(),
+            recent_mints: vec![],
         }
     }
 }
@@ -337,17 +462,52 @@ impl MintConfigStore {
     }
 
     // Attempt to get a MintConfig that is active and is capable of minting the
-    // given amount of tokens.
+    // given amount of tokens in the block at `block_index`.
     pub fn get_active_mint_config_for_mint_tx(
         &self,
         mint_tx: &MintTx,
+        block_index: u64,
         db_transaction: &impl Transaction,
     ) -> Result<ActiveMintConfig, Error> {
         let active_mint_configs = self
             .get_active_mint_configs(TokenId::from(mint_tx.prefix.token_id), db_transaction)?
             .ok_or(Error::NotFound)?;
 
-        active_mint_configs.get_active_mint_config_for_mint_tx(mint_tx)
+        active_mint_configs.get_active_mint_config_for_mint_tx(mint_tx, block_index)
+    }
+
+    /// Record an amount minted by a given MintConfig in the block at
+    /// `block_index`, for enforcing its mint limit windows.
+    pub fn record_mint(
+        &self,
+        mint_config: &MintConfig,
+        amount: u64,
+        block_index: u64,
+        db_transaction: &mut RwTransaction,
+    ) -> Result<(), Error> {
+        // Get the active mint configs for the given token.
+        let mut active_mint_configs = self
+            .get_active_mint_configs(TokenId::from(mint_config.token_id), db_transaction)?
+            .ok_or(Error::NotFound)?;
+
+        // Find the active mint config that matches the mint config we were given.
+        let config_index = active_mint_configs
+            .configs
+            .iter()
+            .position(|active_mint_config| active_mint_config.mint_config == *mint_config)
+            .ok_or(Error::NotFound)?;
+
+        active_mint_configs.record_mint(config_index, block_index, amount);
+
+        // Write to db.
+        db_transaction.put(
+            self.active_mint_configs_by_token_id,
+            &u64_to_key_bytes(mint_config.token_id),
+            &encode(&active_mint_configs),
+            WriteFlags::empty(),
+        )?;
+
+        Ok(())
     }
 
     /// Update the total minted amount for a given MintConfig.
@@ -949,7 +1109,7 @@ pub mod tests {
             &mut rng,
         );
         assert_eq!(
-            mint_config_store.get_active_mint_config_for_mint_tx(&mint_tx1, &db_transaction),
+            mint_config_store.get_active_mint_config_for_mint_tx(&mint_tx1, 0, &db_transaction),
             Ok(ActiveMintConfig {
                 mint_config: test_tx_1.prefix.configs[0].clone(),
                 total_minted: 0,
@@ -965,7 +1125,7 @@ pub mod tests {
             &mut rng,
         );
         assert_eq!(
-            mint_config_store.get_active_mint_config_for_mint_tx(&mint_tx2, &db_transaction),
+            mint_config_store.get_active_mint_config_for_mint_tx(&mint_tx2, 0, &db_transaction),
             Ok(ActiveMintConfig {
                 mint_config: test_tx_1.prefix.configs[1].clone(),
                 total_minted: 0,
@@ -981,7 +1141,7 @@ pub mod tests {
             &mut rng,
         );
         assert_eq!(
-            mint_config_store.get_active_mint_config_for_mint_tx(&mint_tx3, &db_transaction),
+            mint_config_store.get_active_mint_config_for_mint_tx(&mint_tx3, 0, &db_transaction),
             Err(Error::NotFound)
         );
 
@@ -989,7 +1149,7 @@ pub mod tests {
         // token id.
         let mint_tx4 = create_mint_tx(token_id2, &signers1, 10, &mut rng);
         assert_eq!(
-            mint_config_store.get_active_mint_config_for_mint_tx(&mint_tx4, &db_transaction),
+            mint_config_store.get_active_mint_config_for_mint_tx(&mint_tx4, 0, &db_transaction),
             Err(Error::NotFound)
         );
     }
@@ -1025,7 +1185,7 @@ pub mod tests {
                 &mut rng,
             );
             assert_eq!(
-                mint_config_store.get_active_mint_config_for_mint_tx(&mint_tx, &db_transaction),
+                mint_config_store.get_active_mint_config_for_mint_tx(&mint_tx, 0, &db_transaction),
                 Err(Error::MintLimitExceeded(
                     mint_tx.prefix.amount,
                     0,
@@ -1052,7 +1212,7 @@ pub mod tests {
                 &mut rng,
             );
             assert_eq!(
-                mint_config_store.get_active_mint_config_for_mint_tx(&mint_tx, &db_transaction),
+                mint_config_store.get_active_mint_config_for_mint_tx(&mint_tx, 0, &db_transaction),
                 Err(Error::MintLimitExceeded(
                     mint_tx.prefix.amount,
                     10, // 10 is the amount that was previously minted
@@ -1072,7 +1232,7 @@ pub mod tests {
                 &mut rng,
             );
             assert_eq!(
-                mint_config_store.get_active_mint_config_for_mint_tx(&mint_tx, &db_transaction),
+                mint_config_store.get_active_mint_config_for_mint_tx(&mint_tx, 0, &db_transaction),
                 Ok(ActiveMintConfig {
                     mint_config: test_tx_1.prefix.configs[0].clone(),
                     total_minted: 10,
@@ -1126,7 +1286,7 @@ pub mod tests {
                 &mut rng,
             );
             assert_eq!(
-                mint_config_store.get_active_mint_config_for_mint_tx(&mint_tx, &db_transaction),
+                mint_config_store.get_active_mint_config_for_mint_tx(&mint_tx, 0, &db_transaction),
                 Ok(ActiveMintConfig {
                     mint_config: test_tx_1.prefix.configs[1].clone(),
                     total_minted: 9,
@@ -1168,7 +1328,7 @@ pub mod tests {
                 &mut rng,
             );
             assert_eq!(
-                mint_config_store.get_active_mint_config_for_mint_tx(&mint_tx, &db_transaction),
+                mint_config_store.get_active_mint_config_for_mint_tx(&mint_tx, 0, &db_transaction),
                 Err(Error::MintLimitExceeded(
                     mint_tx.prefix.amount,
                     0,
@@ -1199,7 +1359,7 @@ pub mod tests {
                 &mut rng,
             );
             assert_eq!(
-                mint_config_store.get_active_mint_config_for_mint_tx(&mint_tx, &db_transaction),
+                mint_config_store.get_active_mint_config_for_mint_tx(&mint_tx, 0, &db_transaction),
                 Err(Error::MintLimitExceeded(
                     mint_tx.prefix.amount,
                     10,
@@ -1219,7 +1379,7 @@ pub mod tests {
             );
             assert_eq!(
                 mint_config_store
-                    .get_active_mint_config_for_mint_tx(&mint_tx, &db_transaction)
+                    .get_active_mint_config_for_mint_tx(&mint_tx, 0, &db_transaction)
                     .unwrap()
                     .mint_config,
                 test_tx_1.prefix.configs[0],
@@ -1479,4 +1639,133 @@ pub mod tests {
             );
         }
     }
+
+    #[test]
+    fn get_active_mint_config_for_mint_tx_enforces_mint_limit_windows() {
+        let (mint_config_store, env) = init_mint_config_store();
+        let mut rng: StdRng = SeedableRng::from_seed([1u8; 32]);
+        let token_id1 = TokenId::from(1);
+
+        let (mut test_tx_1, signers1) = create_mint_config_tx_and_signers(token_id1, &mut rng);
+        let window = MintLimitWindow {
+            num_blocks: 10,
+            limit: 100,
+        };
+        test_tx_1.prefix.configs[0].mint_limit = 1000;
+        test_tx_1.prefix.configs[0].mint_limit_windows = vec![window.clone()];
+        test_tx_1.prefix.total_mint_limit = 1000;
+
+        // Store mint config
+        {
+            let mut db_transaction = env.begin_rw_txn().unwrap();
+            mint_config_store
+                .write_validated_mint_config_txs(
+                    0,
+                    &[to_validated(&test_tx_1)],
+                    &mut db_transaction,
+                )
+                .unwrap();
+            db_transaction.commit().unwrap();
+        }
+
+        // Mint 60 tokens in block 1, and 5 tokens using a config that has no windows.
+        {
+            let mut db_transaction = env.begin_rw_txn().unwrap();
+            mint_config_store
+                .update_total_minted(&test_tx_1.prefix.configs[0], 60, &mut db_transaction)
+                .unwrap();
+            mint_config_store
+                .record_mint(&test_tx_1.prefix.configs[0], 60, 1, &mut db_transaction)
+                .unwrap();
+            mint_config_store
+                .update_total_minted(&test_tx_1.prefix.configs[1], 5, &mut db_transaction)
+                .unwrap();
+            mint_config_store
+                .record_mint(&test_tx_1.prefix.configs[1], 5, 1, &mut db_transaction)
+                .unwrap();
+            db_transaction.commit().unwrap();
+        }
+
+        let mint_tx = create_mint_tx(
+            token_id1,
+            &[Ed25519Pair::from(signers1[0].private_key())],
+            50,
+            &mut rng,
+        );
+
+        // Only 40 tokens remain in the window during block 5.
+        {
+            let db_transaction = env.begin_ro_txn().unwrap();
+            assert_eq!(
+                mint_config_store.get_active_mint_config_for_mint_tx(&mint_tx, 5, &db_transaction),
+                Err(Error::MintLimitWindowExceeded(50))
+            );
+
+            let active_mint_configs = mint_config_store
+                .get_active_mint_configs(token_id1, &db_transaction)
+                .unwrap()
+                .unwrap();
+            assert_eq!(active_mint_configs.recent_mints.len(), 1);
+            assert_eq!(
+                active_mint_configs.mint_limit_window_capacities(0, 5),
+                vec![MintLimitWindowCapacity {
+                    window: window.clone(),
+                    minted: 60,
+                    remaining: 40,
+                }]
+            );
+            assert_eq!(
+                active_mint_configs.mint_limit_window_capacities(1, 5),
+                vec![]
+            );
+        }
+
+        // By block 11 the earlier mint is no longer within the window.
+        {
+            let db_transaction = env.begin_ro_txn().unwrap();
+            assert_eq!(
+                mint_config_store.get_active_mint_config_for_mint_tx(&mint_tx, 11, &db_transaction),
+                Ok(ActiveMintConfig {
+                    mint_config: test_tx_1.prefix.configs[0].clone(),
+                    total_minted: 60,
+                })
+            );
+        }
+
+        // Recording a mint in block 11 forgets the mint from block 1.
+        {
+            let mut db_transaction = env.begin_rw_txn().unwrap();
+            mint_config_store
+                .update_total_minted(&test_tx_1.prefix.configs[0], 110, &mut db_transaction)
+                .unwrap();
+            mint_config_store
+                .record_mint(&test_tx_1.prefix.configs[0], 50, 11, &mut db_transaction)
+                .unwrap();
+            db_transaction.commit().unwrap();
+        }
+
+        {
+            let db_transaction = env.begin_ro_txn().unwrap();
+            let active_mint_configs = mint_config_store
+                .get_active_mint_configs(token_id1, &db_transaction)
+                .unwrap()
+                .unwrap();
+            assert_eq!(
+                active_mint_configs.recent_mints,
+                vec![RecentMint {
+                    config_index: 0,
+                    block_index: 11,
+                    amount: 50,
+                }]
+            );
+            assert_eq!(
+                active_mint_configs.mint_limit_window_capacities(0, 12),
+                vec![MintLimitWindowCapacity {
+                    window,
+                    minted: 50,
+                    remaining: 50,
+                }]
+            );
+        }
+    }
 }
@@ -88,12 +88,15 @@ impl MintTxStore {
         )?;
 
         // For each mint transaction, we need to locate the matching mint configuration
-        // and update the total minted count. We also need to ensure the nonce is
-        // unique.
+        // and update the total minted count and recent mints. We also need to ensure
+        // the nonce is unique.
         for mint_tx in mint_txs {
             // Update total minted.
-            let active_mint_config =
-                mint_config_store.get_active_mint_config_for_mint_tx(mint_tx, db_transaction)?;
+            let active_mint_config = mint_config_store.get_active_mint_config_for_mint_tx(
+                mint_tx,
+                block_index,
+                db_transaction,
+            )?;
 
             let new_total_minted = active_mint_config
                 .total_minted
@@ -106,6 +109,14 @@ impl MintTxStore {
                 db_transaction,
             )?;
 
+            // Record the mint for enforcing mint limit windows.
+            mint_config_store.record_mint(
+                &active_mint_config.mint_config,
+                mint_tx.prefix.amount,
+                block_index,
+                db_transaction,
+            )?;
+
             // Ensure nonce uniqueness
             db_transaction.put(
                 self.block_index_by_mint_tx_nonce,
@@ -38,6 +38,33 @@ ssize_t mc_printable_wrapper_b58_decode(
 )
 MC_ATTRIBUTE_NONNULL(1);
 
+/* ==== PaymentRequest ==== */
+
+/// # Preconditions
+///
+/// * `payment_request_proto_bytes` - must be a valid binary-serialized `printable.PaymentRequest`
+///     Protobuf.
+char* MC_NULLABLE mc_payment_request_uri_encode(
+  const McBuffer* MC_NONNULL payment_request_proto_bytes
+)
+MC_ATTRIBUTE_NONNULL(1);
+
+/// # Preconditions
+///
+/// * `uri` - must be a nul-terminated C string containing valid UTF-8.
+/// * `out_payment_request_proto_bytes` - must be null or else length must be >=
+///     `payment_request_bytes.len`.
+///
+/// # Errors
+///
+/// * `LibMcError::InvalidInput`
+ssize_t mc_payment_request_uri_decode(
+  const char* MC_NONNULL uri,
+  McMutableBuffer* MC_NULLABLE out_payment_request_proto_bytes,
+  McError* MC_NULLABLE * MC_NULLABLE out_error
+)
+MC_ATTRIBUTE_NONNULL(1);
+
 #ifdef __cplusplus
 }
 #endif
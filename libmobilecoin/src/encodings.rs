@@ -2,7 +2,7 @@
 
 use crate::{common::*, LibMcError};
 use libc::ssize_t;
-use mc_api::printable::PrintableWrapper;
+use mc_api::printable::{PaymentRequest, PrintableWrapper};
 use mc_util_ffi::*;
 use protobuf::Message;
 
@@ -72,3 +72,67 @@ pub extern "C" fn mc_printable_wrapper_b58_decode(
             .expect("wrapper_bytes.len could not be converted to ssize_t"))
     })
 }
+
+/* ==== PaymentRequest ==== */
+
+impl<'a> TryFromFfi<&McBuffer<'a>> for PaymentRequest {
+    type Error = LibMcError;
+
+    fn try_from_ffi(src: &McBuffer<'a>) -> Result<Self, Self::Error> {
+        Self::parse_from_bytes(src).map_err(|err| LibMcError::InvalidInput(format!("{:?}", err)))
+    }
+}
+
+/// # Preconditions
+///
+/// * `payment_request_proto_bytes` - must be a valid binary-serialized
+///   `printable.PaymentRequest` Protobuf.
+#[no_mangle]
+pub extern "C" fn mc_payment_request_uri_encode(
+    payment_request_proto_bytes: FfiRefPtr<McBuffer>,
+) -> FfiOptOwnedStr {
+    ffi_boundary(|| {
+        let payment_request = PaymentRequest::try_from_ffi(&payment_request_proto_bytes)
+            .expect("payment_request_proto_bytes could not be converted to PaymentRequest");
+        let encoded = payment_request
+            .uri_encode()
+            .expect("payment_request could not be encoded as a URI");
+
+        FfiOwnedStr::ffi_try_from(encoded)
+            .expect("Resulting encoded string could not be converted to a C string")
+    })
+}
+
+/// # Preconditions
+///
+/// * `uri` - must be a nul-terminated C string containing valid UTF-8.
+/// * `out_payment_request_proto_bytes` - must be null or else length must be >=
+///   `payment_request_bytes.len`.
+///
+/// # Errors
+///
+/// * `LibMcError::InvalidInput`
+#[no_mangle]
+pub extern "C" fn mc_payment_request_uri_decode(
+    uri: FfiStr,
+    out_payment_request_proto_bytes: FfiOptMutPtr<McMutableBuffer>,
+    out_error: FfiOptMutPtr<FfiOptOwnedPtr<McError>>,
+) -> ssize_t {
+    ffi_boundary_with_error(out_error, || {
+        let uri = String::try_from_ffi(uri).expect("uri is invalid");
+
+        let payment_request = PaymentRequest::uri_decode(&uri)?;
+        let payment_request_bytes = payment_request.write_to_bytes()?;
+
+        if let Some(out_payment_request_proto_bytes) = out_payment_request_proto_bytes.into_option()
+        {
+            out_payment_request_proto_bytes
+                .into_mut()
+                .as_slice_mut_of_len(payment_request_bytes.len())
+                .expect("out_payment_request_proto_bytes length is insufficient")
+                .copy_from_slice(&payment_request_bytes);
+        }
+        Ok(ssize_t::ffi_try_from(payment_request_bytes.len())
+            .expect("payment_request_bytes.len could not be converted to ssize_t"))
+    })
+}
@@ -153,7 +153,8 @@ impl MintAuditorDb {
                         // happen.
                     }
                     Err(err @ LedgerDbError::NotFound)
-                    | Err(err @ LedgerDbError::MintLimitExceeded(_, _, _)) => {
+                    | Err(err @ LedgerDbError::MintLimitExceeded(_, _, _))
+                    | Err(err @ LedgerDbError::MintLimitWindowExceeded(_)) => {
                         log::crit!(
                             self.logger,
                             "Block {}: Found mint tx {} that did not match any active mint config: {}",
@@ -292,7 +292,8 @@ fn public_address(
     Ok(Json(JsonPublicAddressResponse::from(&resp)))
 }
 
-/// Generates a request code with an optional value and memo
+/// Generates a request code, and the equivalent `mob:` URI, with an optional
+/// value, memo, token id, payment request id and expiry
 #[post("/codes/request", format = "json", data = "<request>")]
 fn create_request_code(
     state: &rocket::State<State>,
@@ -310,6 +311,15 @@ fn create_request_code(
     if let Some(memo) = request.memo.clone() {
         req.set_memo(memo);
     }
+    if let Some(token_id) = request.token_id {
+        req.set_token_id(u64::from(token_id));
+    }
+    if let Some(payment_request_id) = request.payment_request_id {
+        req.set_payment_request_id(u64::from(payment_request_id));
+    }
+    if let Some(expiry) = request.expiry {
+        req.set_expiry(u64::from(expiry));
+    }
 
     let resp = state
         .mobilecoind_api_client
@@ -319,7 +329,8 @@ fn create_request_code(
     Ok(Json(JsonCreateRequestCodeResponse::from(&resp)))
 }
 
-/// Retrieves the data in a request b58_code
+/// Retrieves the data in a request b58_code, or in a percent-encoded `mob:`
+/// payment request URI
 #[get("/codes/request/<b58_code>")]
 fn parse_request_code(
     state: &rocket::State<State>,
@@ -3,9 +3,9 @@
 //! Serializeable data types that wrap the mobilecoind API.
 
 use mc_api::external::{
-    CompressedRistretto, EncryptedFogHint, EncryptedMemo, InputRules, KeyImage, MaskedAmount,
-    PublicAddress, RingMLSAG, SignatureRctBulletproofs, Tx, TxIn, TxOutMembershipElement,
-    TxOutMembershipHash, TxOutMembershipProof, TxPrefix,
+    AmountMasks, CompressedRistretto, EncryptedFogHint, EncryptedMemo, InputRules, KeyImage,
+    MaskedAmount, PublicAddress, RevealedTxOut, RingMLSAG, SignatureRctBulletproofs, Tx, TxIn,
+    TxOutMembershipElement, TxOutMembershipHash, TxOutMembershipProof, TxPrefix,
 };
 use mc_util_serial::JsonU64;
 use protobuf::RepeatedField;
@@ -222,17 +222,22 @@ pub struct JsonCreateRequestCodeRequest {
     pub receiver: JsonPublicAddress,
     pub value: Option<JsonU64>,
     pub memo: Option<String>,
+    pub token_id: Option<JsonU64>,
+    pub payment_request_id: Option<JsonU64>,
+    pub expiry: Option<JsonU64>,
 }
 
 #[derive(Serialize, Default, Debug)]
 pub struct JsonCreateRequestCodeResponse {
     pub b58_request_code: String,
+    pub request_uri: String,
 }
 
 impl From<&mc_mobilecoind_api::CreateRequestCodeResponse> for JsonCreateRequestCodeResponse {
     fn from(src: &mc_mobilecoind_api::CreateRequestCodeResponse) -> Self {
         Self {
             b58_request_code: String::from(src.get_b58_code()),
+            request_uri: String::from(src.get_uri()),
         }
     }
 }
@@ -339,6 +344,12 @@ pub struct JsonParseRequestCodeResponse {
     pub receiver: JsonPublicAddress,
     pub value: JsonU64,
     pub memo: String,
+    #[serde(default)]
+    pub token_id: JsonU64,
+    #[serde(default)]
+    pub payment_request_id: JsonU64,
+    #[serde(default)]
+    pub expiry: JsonU64,
 }
 
 impl From<&mc_mobilecoind_api::ParseRequestCodeResponse> for JsonParseRequestCodeResponse {
@@ -347,6 +358,9 @@ impl From<&mc_mobilecoind_api::ParseRequestCodeResponse> for JsonParseRequestCod
             receiver: JsonPublicAddress::from(src.get_receiver()),
             value: JsonU64(src.get_value()),
             memo: src.get_memo().to_string(),
+            token_id: JsonU64(src.get_token_id()),
+            payment_request_id: JsonU64(src.get_payment_request_id()),
+            expiry: JsonU64(src.get_expiry()),
         }
     }
 }
@@ -684,10 +698,76 @@ pub struct JsonMembershipProofResponse {
     pub membership_proofs: Vec<JsonTxOutMembershipProof>,
 }
 
+#[derive(Deserialize, Serialize, Default, Debug, Clone)]
+pub struct JsonAmountMasks {
+    pub value_mask: JsonU64,
+    pub token_id_mask: JsonU64,
+    pub blinding: String,
+}
+
+impl From<&AmountMasks> for JsonAmountMasks {
+    fn from(src: &AmountMasks) -> Self {
+        Self {
+            value_mask: JsonU64(src.get_value_mask()),
+            token_id_mask: JsonU64(src.get_token_id_mask()),
+            blinding: hex::encode(src.get_blinding().get_data()),
+        }
+    }
+}
+
+impl TryFrom<&JsonAmountMasks> for AmountMasks {
+    type Error = String;
+
+    fn try_from(src: &JsonAmountMasks) -> Result<AmountMasks, String> {
+        let mut blinding = mc_api::external::CurveScalar::new();
+        blinding.set_data(
+            hex::decode(&src.blinding)
+                .map_err(|err| format!("Failed to decode blinding hex: {}", err))?,
+        );
+        let mut amount_masks = AmountMasks::new();
+        amount_masks.set_value_mask(src.value_mask.into());
+        amount_masks.set_token_id_mask(src.token_id_mask.into());
+        amount_masks.set_blinding(blinding);
+        Ok(amount_masks)
+    }
+}
+
+#[derive(Deserialize, Serialize, Default, Debug, Clone)]
+pub struct JsonRevealedTxOut {
+    pub tx_out: JsonTxOut,
+    pub amount_masks: JsonAmountMasks,
+}
+
+impl From<&RevealedTxOut> for JsonRevealedTxOut {
+    fn from(src: &RevealedTxOut) -> Self {
+        Self {
+            tx_out: src.get_tx_out().into(),
+            amount_masks: src.get_amount_masks().into(),
+        }
+    }
+}
+
+impl TryFrom<&JsonRevealedTxOut> for RevealedTxOut {
+    type Error = String;
+
+    fn try_from(src: &JsonRevealedTxOut) -> Result<RevealedTxOut, String> {
+        let mut revealed_tx_out = RevealedTxOut::new();
+        revealed_tx_out.set_tx_out(mc_api::external::TxOut::try_from(&src.tx_out)?);
+        revealed_tx_out.set_amount_masks(AmountMasks::try_from(&src.amount_masks)?);
+        Ok(revealed_tx_out)
+    }
+}
+
 #[derive(Deserialize, Serialize, Default, Debug, Clone)]
 pub struct JsonInputRules {
     pub required_outputs: Vec<JsonTxOut>,
     pub max_tombstone_block: u64,
+    #[serde(default)]
+    pub partial_fill_outputs: Vec<JsonRevealedTxOut>,
+    #[serde(default)]
+    pub partial_fill_change: Option<JsonRevealedTxOut>,
+    #[serde(default)]
+    pub min_partial_fill_value: u64,
 }
 
 impl From<&InputRules> for JsonInputRules {
@@ -699,6 +779,16 @@ impl From<&InputRules> for JsonInputRules {
                 .map(JsonTxOut::from)
                 .collect(),
             max_tombstone_block: src.max_tombstone_block,
+            partial_fill_outputs: src
+                .get_partial_fill_outputs()
+                .iter()
+                .map(JsonRevealedTxOut::from)
+                .collect(),
+            partial_fill_change: src
+                .partial_fill_change
+                .as_ref()
+                .map(JsonRevealedTxOut::from),
+            min_partial_fill_value: src.min_partial_fill_value,
         }
     }
 }
@@ -718,6 +808,16 @@ impl TryFrom<&JsonInputRules> for InputRules {
                 .collect::<Result<_, String>>()?,
         );
         input_rules.max_tombstone_block = src.max_tombstone_block;
+        input_rules.set_partial_fill_outputs(
+            src.partial_fill_outputs
+                .iter()
+                .map(RevealedTxOut::try_from)
+                .collect::<Result<_, String>>()?,
+        );
+        if let Some(partial_fill_change) = src.partial_fill_change.as_ref() {
+            input_rules.set_partial_fill_change(RevealedTxOut::try_from(partial_fill_change)?);
+        }
+        input_rules.min_partial_fill_value = src.min_partial_fill_value;
         Ok(input_rules)
     }
 }
@@ -5,6 +5,7 @@
 use crate::{
     db_crypto::DbCryptoProvider,
     error::Error,
+    gift_code_store::{GiftCodeData, GiftCodeStore},
     monitor_store::{MonitorData, MonitorId, MonitorStore},
     processed_block_store::{ProcessedBlockStore, ProcessedTxOut},
     subaddress_store::{SubaddressId, SubaddressSPKId, SubaddressStore},
@@ -17,6 +18,7 @@ use mc_common::{
     logger::{log, Logger},
     HashMap,
 };
+use mc_crypto_keys::CompressedRistrettoPublic;
 use mc_transaction_core::ring_signature::KeyImage;
 use mc_util_lmdb::{MetadataStore, MetadataStoreSettings};
 use std::{path::Path, sync::Arc};
@@ -63,6 +65,9 @@ pub struct Database {
     /// Processed block store.
     processed_block_store: ProcessedBlockStore,
 
+    /// Gift code store.
+    gift_code_store: GiftCodeStore,
+
     /// Logger.
     logger: Logger,
 }
@@ -97,6 +102,8 @@ impl Database {
         let subaddress_store = SubaddressStore::new(env.clone(), logger.clone())?;
         let utxo_store = UtxoStore::new(env.clone(), logger.clone())?;
         let processed_block_store = ProcessedBlockStore::new(env.clone(), logger.clone())?;
+        let gift_code_store =
+            GiftCodeStore::new(env.clone(), crypto_provider.clone(), logger.clone())?;
 
         Ok(Self {
             env,
@@ -105,6 +112,7 @@ impl Database {
             subaddress_store,
             utxo_store,
             processed_block_store,
+            gift_code_store,
             logger,
         })
     }
@@ -134,8 +142,9 @@ impl Database {
     pub fn re_encrypt(&self, new_password: &[u8]) -> Result<(), Error> {
         let mut db_txn = self.env.begin_rw_txn()?;
 
-        // Currently only the monitor store stores encrypted data.
+        // Currently only the monitor and gift code stores store encrypted data.
         self.monitor_store.re_encrypt(&mut db_txn, new_password)?;
+        self.gift_code_store.re_encrypt(&mut db_txn, new_password)?;
 
         // set_password consumes the transaction to ensure atomicity.
         self.crypto_provider.change_password(db_txn, new_password)?;
@@ -342,6 +351,44 @@ impl Database {
         self.processed_block_store
             .get_processed_block(&db_txn, monitor_id, block_num)
     }
+
+    pub fn add_gift_code(&self, data: &GiftCodeData) -> Result<(), Error> {
+        let mut db_txn = self.env.begin_rw_txn()?;
+        self.gift_code_store.add(&mut db_txn, data)?;
+        db_txn.commit()?;
+        Ok(())
+    }
+
+    pub fn remove_gift_code(
+        &self,
+        tx_out_public_key: &CompressedRistrettoPublic,
+    ) -> Result<(), Error> {
+        let mut db_txn = self.env.begin_rw_txn()?;
+        self.gift_code_store
+            .remove(&mut db_txn, tx_out_public_key)?;
+        db_txn.commit()?;
+        Ok(())
+    }
+
+    pub fn get_gift_code(
+        &self,
+        tx_out_public_key: &CompressedRistrettoPublic,
+    ) -> Result<GiftCodeData, Error> {
+        let db_txn = self.env.begin_ro_txn()?;
+        self.gift_code_store.get(&db_txn, tx_out_public_key)
+    }
+
+    pub fn get_gift_codes(&self) -> Result<Vec<GiftCodeData>, Error> {
+        let db_txn = self.env.begin_ro_txn()?;
+        self.gift_code_store.get_all(&db_txn)
+    }
+
+    pub fn update_gift_code(&self, data: &GiftCodeData) -> Result<(), Error> {
+        let mut db_txn = self.env.begin_rw_txn()?;
+        self.gift_code_store.set(&mut db_txn, data)?;
+        db_txn.commit()?;
+        Ok(())
+    }
 }
 
 #[cfg(test)]
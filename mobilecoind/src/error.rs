@@ -111,6 +111,12 @@ pub enum Error {
 
     /// Db encryption: {0}
     DbCrypto(DbCryptoError),
+
+    /// An entry in GiftCodeStore already exists for this TxOut public key
+    GiftCodeExists,
+
+    /// No matching key in GiftCodeStore was found
+    GiftCodeNotFound,
 }
 
 impl From<RetryError<ConnectionError>> for Error {
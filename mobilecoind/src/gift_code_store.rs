@@ -0,0 +1,303 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! Database storage for gift codes
+//! * Provides the gift codes funded by a monitor, keyed by the public key of
+//!   the gift code TxOut.
+//! * The status of a gift code is not stored, it is derived from the ledger.
+
+use crate::{db_crypto::DbCryptoProvider, error::Error, monitor_store::MonitorId};
+use lmdb::{Cursor, Database, DatabaseFlags, Environment, RwTransaction, Transaction, WriteFlags};
+use mc_common::logger::{log, Logger};
+use mc_crypto_keys::{CompressedRistrettoPublic, RistrettoPrivate, RistrettoPublic};
+use mc_ledger_db::{Error as LedgerError, Ledger};
+use mc_transaction_core::{ring_signature::KeyImage, TxOutGiftCode};
+use mc_util_serial::Message;
+use std::sync::Arc;
+
+// LMDB Database Names
+pub const TX_OUT_PUBLIC_KEY_TO_GIFT_CODE_DATA_DB_NAME: &str =
+    "mobilecoind_db:gift_code_store:tx_out_public_key_to_gift_code_data";
+
+/// Type used as the stored data in the tx_out_public_key_to_gift_code_data
+/// database.
+#[derive(Clone, Message)]
+pub struct GiftCodeData {
+    /// The monitor which funded the gift code.
+    #[prost(message, required, tag = "1")]
+    pub monitor_id: MonitorId,
+
+    /// The subaddress which funded the gift code, and which receives it back
+    /// when the gift code is cancelled.
+    #[prost(uint64, tag = "2")]
+    pub subaddress_index: u64,
+
+    /// The public key of the gift code TxOut.
+    #[prost(message, required, tag = "3")]
+    pub tx_out_public_key: CompressedRistrettoPublic,
+
+    /// The one-time private key which can be used to spend the gift code
+    /// TxOut.
+    #[prost(message, required, tag = "4")]
+    pub onetime_private_key: RistrettoPrivate,
+
+    /// The shared secret which can be used to un-blind the amount of the gift
+    /// code TxOut.
+    #[prost(message, required, tag = "5")]
+    pub shared_secret: RistrettoPublic,
+
+    /// The value of the gift code.
+    #[prost(uint64, tag = "6")]
+    pub value: u64,
+
+    /// The token id of the gift code.
+    #[prost(uint64, tag = "7")]
+    pub token_id: u64,
+
+    /// The note written in the funding memo.
+    #[prost(string, tag = "8")]
+    pub note: String,
+
+    /// The tombstone block of the funding transaction.
+    #[prost(uint64, tag = "9")]
+    pub funding_tombstone_block: u64,
+
+    /// The public keys of the change outputs of the cancellation transactions
+    /// generated for this gift code. At most one of them can land in the
+    /// ledger, since they all spend the gift code TxOut.
+    #[prost(message, repeated, tag = "10")]
+    pub cancellation_tx_out_public_keys: Vec<CompressedRistrettoPublic>,
+}
+
+/// The status of a gift code, as seen in the ledger.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum GiftCodeStatus {
+    /// The funding transaction is not in the ledger yet.
+    FundingPending,
+
+    /// The funding transaction reached its tombstone block without landing in
+    /// the ledger.
+    FundingFailed,
+
+    /// The gift code TxOut is in the ledger and has not been spent.
+    Available,
+
+    /// The gift code TxOut was spent by someone else.
+    Claimed,
+
+    /// The gift code TxOut was spent back to the sender.
+    Cancelled,
+}
+
+impl GiftCodeData {
+    /// The key image of the gift code TxOut.
+    pub fn key_image(&self) -> KeyImage {
+        KeyImage::from(&self.onetime_private_key)
+    }
+
+    /// Get the status of the gift code, and its global index once it is in the
+    /// ledger.
+    pub fn status(&self, ledger: &impl Ledger) -> Result<(GiftCodeStatus, Option<u64>), Error> {
+        let global_index = match ledger.get_tx_out_index_by_public_key(&self.tx_out_public_key) {
+            Ok(global_index) => global_index,
+            Err(LedgerError::NotFound) => {
+                let status = if ledger.num_blocks()? >= self.funding_tombstone_block {
+                    GiftCodeStatus::FundingFailed
+                } else {
+                    GiftCodeStatus::FundingPending
+                };
+                return Ok((status, None));
+            }
+            Err(err) => return Err(err.into()),
+        };
+
+        if ledger.check_key_image(&self.key_image())?.is_none() {
+            return Ok((GiftCodeStatus::Available, Some(global_index)));
+        }
+
+        let mut cancelled = false;
+        for public_key in self.cancellation_tx_out_public_keys.iter() {
+            if ledger.contains_tx_out_public_key(public_key)? {
+                cancelled = true;
+                break;
+            }
+        }
+        let status = if cancelled {
+            GiftCodeStatus::Cancelled
+        } else {
+            GiftCodeStatus::Claimed
+        };
+        Ok((status, Some(global_index)))
+    }
+
+    /// The gift code which can be handed to the receiver, given the global
+    /// index of the gift code TxOut.
+    pub fn gift_code(&self, global_index: u64) -> TxOutGiftCode {
+        TxOutGiftCode::new(global_index, self.onetime_private_key, self.shared_secret)
+    }
+}
+
+/// Wrapper for the tx_out_public_key_to_gift_code_data database
+#[derive(Clone)]
+pub struct GiftCodeStore {
+    /// Retain a reference to the Environment so the Database handles are valid.
+    _env: Arc<Environment>,
+
+    /// Crypto provider, used for managing database encryption.
+    crypto_provider: DbCryptoProvider,
+
+    /// Mapping of TxOut public key -> GiftCodeData
+    tx_out_public_key_to_gift_code_data: Database,
+
+    /// Logger.
+    logger: Logger,
+}
+
+impl GiftCodeStore {
+    pub fn new(
+        env: Arc<Environment>,
+        crypto_provider: DbCryptoProvider,
+        logger: Logger,
+    ) -> Result<Self, Error> {
+        let tx_out_public_key_to_gift_code_data = env.create_db(
+            Some(TX_OUT_PUBLIC_KEY_TO_GIFT_CODE_DATA_DB_NAME),
+            DatabaseFlags::empty(),
+        )?;
+
+        Ok(Self {
+            _env: env,
+            crypto_provider,
+            tx_out_public_key_to_gift_code_data,
+            logger,
+        })
+    }
+
+    /// Add a new gift code.
+    pub fn add<'env>(
+        &self,
+        db_txn: &mut RwTransaction<'env>,
+        data: &GiftCodeData,
+    ) -> Result<(), Error> {
+        let value_bytes = self
+            .crypto_provider
+            .encrypt(&mc_util_serial::encode(data))?;
+
+        log::trace!(
+            self.logger,
+            "adding new gift code {:?} for monitor {}",
+            data.tx_out_public_key,
+            data.monitor_id
+        );
+
+        match db_txn.put(
+            self.tx_out_public_key_to_gift_code_data,
+            &data.tx_out_public_key,
+            &value_bytes,
+            WriteFlags::NO_OVERWRITE,
+        ) {
+            Ok(_) => Ok(()),
+            Err(lmdb::Error::KeyExist) => Err(Error::GiftCodeExists),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Delete a gift code.
+    pub fn remove<'env>(
+        &self,
+        db_txn: &mut RwTransaction<'env>,
+        tx_out_public_key: &CompressedRistrettoPublic,
+    ) -> Result<(), Error> {
+        match db_txn.del(
+            self.tx_out_public_key_to_gift_code_data,
+            tx_out_public_key,
+            None,
+        ) {
+            Ok(_) => Ok(()),
+            Err(lmdb::Error::NotFound) => Err(Error::GiftCodeNotFound),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Get the GiftCodeData for a given gift code TxOut public key.
+    pub fn get(
+        &self,
+        db_txn: &impl Transaction,
+        tx_out_public_key: &CompressedRistrettoPublic,
+    ) -> Result<GiftCodeData, Error> {
+        match db_txn.get(self.tx_out_public_key_to_gift_code_data, tx_out_public_key) {
+            Ok(value_bytes) => {
+                let value_bytes = self.crypto_provider.decrypt(value_bytes)?;
+                let data: GiftCodeData = mc_util_serial::decode(&value_bytes)?;
+                Ok(data)
+            }
+            Err(lmdb::Error::NotFound) => Err(Error::GiftCodeNotFound),
+            Err(err) => Err(Error::Lmdb(err)),
+        }
+    }
+
+    /// Get all gift codes in the database.
+    pub fn get_all(&self, db_txn: &impl Transaction) -> Result<Vec<GiftCodeData>, Error> {
+        let mut cursor = db_txn.open_ro_cursor(self.tx_out_public_key_to_gift_code_data)?;
+
+        cursor
+            .iter()
+            .map(|result| {
+                result
+                    .map_err(Error::from)
+                    .and_then(|(_key_bytes, value_bytes)| {
+                        let value_bytes = self.crypto_provider.decrypt(value_bytes)?;
+                        let data: GiftCodeData = mc_util_serial::decode(&value_bytes)?;
+                        Ok(data)
+                    })
+            })
+            .collect::<Result<Vec<_>, Error>>()
+    }
+
+    /// Set the GiftCodeData for an existing gift code.
+    pub fn set<'env>(
+        &self,
+        db_txn: &mut RwTransaction<'env>,
+        data: &GiftCodeData,
+    ) -> Result<(), Error> {
+        match db_txn.get(
+            self.tx_out_public_key_to_gift_code_data,
+            &data.tx_out_public_key,
+        ) {
+            Ok(_value_bytes) => {
+                let new_value_bytes = self
+                    .crypto_provider
+                    .encrypt(&mc_util_serial::encode(data))?;
+                db_txn.put(
+                    self.tx_out_public_key_to_gift_code_data,
+                    &data.tx_out_public_key,
+                    &new_value_bytes,
+                    WriteFlags::empty(),
+                )?;
+                Ok(())
+            }
+            Err(lmdb::Error::NotFound) => Err(Error::GiftCodeNotFound),
+            Err(err) => Err(Error::Lmdb(err)),
+        }
+    }
+
+    /// Re-encrypt the encrypted parts of the database with a new password.
+    /// This will fail if the current password is not set in the crypto_provider
+    /// since part of the re-encryption process relies on being able to
+    /// decrypt the existing data.
+    pub fn re_encrypt<'env>(
+        &self,
+        db_txn: &mut RwTransaction<'env>,
+        new_password: &[u8],
+    ) -> Result<(), Error> {
+        let mut cursor = db_txn.open_rw_cursor(self.tx_out_public_key_to_gift_code_data)?;
+
+        for (key_bytes, value_bytes) in cursor.iter().filter_map(|r| r.ok()) {
+            let decrypted_bytes = self.crypto_provider.decrypt(value_bytes)?;
+            let encrypted_bytes = self
+                .crypto_provider
+                .encrypt_with_password(new_password, &decrypted_bytes)?;
+            cursor.put(&key_bytes, &encrypted_bytes, WriteFlags::CURRENT)?;
+        }
+
+        Ok(())
+    }
+}
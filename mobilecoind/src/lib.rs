@@ -13,6 +13,7 @@ mod conversions;
 mod database_key;
 mod db_crypto;
 mod error;
+mod gift_code_store;
 mod monitor_store;
 mod processed_block_store;
 mod subaddress_store;
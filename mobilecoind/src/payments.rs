@@ -2,8 +2,11 @@
 
 //! Construct and submit transactions to the validator network.
 
-use crate::{database::Database, error::Error, monitor_store::MonitorId, utxo_store::UnspentTxOut};
-use mc_account_keys::{AccountKey, PublicAddress};
+use crate::{
+    database::Database, error::Error, gift_code_store::GiftCodeData, monitor_store::MonitorId,
+    utxo_store::UnspentTxOut,
+};
+use mc_account_keys::{AccountKey, PublicAddress, GIFT_CODE_SUBADDRESS_INDEX};
 use mc_blockchain_types::{BlockIndex, BlockVersion};
 use mc_common::{
     logger::{log, o, Logger},
@@ -19,13 +22,15 @@ use mc_fog_report_validation::FogPubkeyResolver;
 use mc_ledger_db::{Error as LedgerError, Ledger, LedgerDB};
 use mc_transaction_core::{
     constants::{MAX_INPUTS, MILLIMOB_TO_PICOMOB, RING_SIZE},
-    onetime_keys::recover_onetime_private_key,
+    onetime_keys::{create_shared_secret, recover_onetime_private_key},
     ring_signature::KeyImage,
     tx::{Tx, TxOut, TxOutConfirmationNumber, TxOutMembershipProof},
-    Amount, TokenId,
+    Amount, TokenId, TxOutGiftCode,
 };
 use mc_transaction_std::{
-    EmptyMemoBuilder, InputCredentials, MemoBuilder, ReservedSubaddresses, TransactionBuilder,
+    EmptyMemoBuilder, GiftCodeCancellationMemoBuilder, GiftCodeFundingMemoBuilder,
+    GiftCodeSenderMemoBuilder, InputCredentials, MemoBuilder, ReservedSubaddresses,
+    TransactionBuilder,
 };
 use mc_util_uri::FogUri;
 use rand::Rng;
@@ -568,6 +573,287 @@ impl<T: BlockchainConnection + UserTxConnection + 'static, FPR: FogPubkeyResolve
         Ok(tx_proposal)
     }
 
+    /// Create a TxProposal which funds a gift code, by sending its value to the
+    /// sender's own gift code subaddress. Returns the TxProposal along with the
+    /// data of the gift code, which becomes usable once the transaction lands
+    /// in the ledger.
+    ///
+    /// # Arguments
+    /// * `sender_monitor_id` - Indicates the the account key funding the gift
+    ///   code.
+    /// * `token_id` - The token id of the gift code.
+    /// * `subaddress_index` - Subaddress of the inputs. Also the recipient of
+    ///   any change, and of the gift code if it gets cancelled.
+    /// * `inputs` - UTXOs that may be spent by the transaction.
+    /// * `value` - The value of the gift code.
+    /// * `last_block_infos` - Last block info responses from the network, for
+    ///   determining fees. This should normally come from polling_network_state
+    /// * `opt_fee` - Transaction fee in picoMOB. If zero, defaults to MIN_FEE.
+    /// * `opt_tombstone` - Tombstone block. If zero, sets to default.
+    /// * `note` - Note written in the funding memo of the change output.
+    pub fn build_gift_code_funding_transaction(
+        &self,
+        sender_monitor_id: &MonitorId,
+        token_id: TokenId,
+        subaddress_index: u64,
+        inputs: &[UnspentTxOut],
+        value: u64,
+        last_block_infos: &[BlockInfo],
+        opt_fee: u64,
+        opt_tombstone: u64,
+        note: &str,
+    ) -> Result<(TxProposal, GiftCodeData), Error> {
+        let sender_monitor_data = self.mobilecoind_db.get_monitor_data(sender_monitor_id)?;
+        let account_key = &sender_monitor_data.account_key;
+
+        let memo_builder = GiftCodeFundingMemoBuilder::new(note)
+            .map_err(|err| Error::InvalidArgument("note".to_string(), err.to_string()))?;
+
+        let outlays = vec![Outlay {
+            receiver: account_key.gift_code_subaddress(),
+            value,
+        }];
+
+        let tx_proposal = self.build_transaction(
+            sender_monitor_id,
+            token_id,
+            subaddress_index,
+            inputs,
+            &outlays,
+            last_block_infos,
+            opt_fee,
+            opt_tombstone,
+            Some(Box::new(memo_builder)),
+        )?;
+
+        // The gift code TxOut is the output of the only outlay.
+        let tx_out_index = tx_proposal
+            .outlay_index_to_tx_out_index
+            .get(&0)
+            .ok_or_else(|| Error::TxBuild("Missing gift code output".into()))?;
+        let tx_out = &tx_proposal.tx.prefix.outputs[*tx_out_index];
+        let public_key = RistrettoPublic::try_from(&tx_out.public_key)?;
+
+        let gift_code_data = GiftCodeData {
+            monitor_id: *sender_monitor_id,
+            subaddress_index,
+            tx_out_public_key: tx_out.public_key,
+            onetime_private_key: recover_onetime_private_key(
+                &public_key,
+                account_key.view_private_key(),
+                &account_key.gift_code_subaddress_spend_private(),
+            ),
+            shared_secret: create_shared_secret(&public_key, account_key.view_private_key()),
+            value,
+            token_id: *token_id,
+            note: note.to_string(),
+            funding_tombstone_block: tx_proposal.tx.prefix.tombstone_block,
+            cancellation_tx_out_public_keys: vec![],
+        };
+
+        Ok((tx_proposal, gift_code_data))
+    }
+
+    /// Create a TxProposal which claims a gift code, sending its value minus
+    /// the fee to the receiver.
+    ///
+    /// # Arguments
+    /// * `gift_code` - The gift code to claim.
+    /// * `receiver_monitor_id` - Indicates the account claiming the gift code.
+    /// * `subaddress_index` - Subaddress receiving the gift code.
+    /// * `note` - Note written in the sender memo of the output.
+    /// * `last_block_infos` - Last block info responses from the network, for
+    ///   determining fees. This should normally come from polling_network_state
+    /// * `opt_fee` - Transaction fee. If zero, defaults to the network fee.
+    pub fn build_gift_code_claim_transaction(
+        &self,
+        gift_code: &TxOutGiftCode,
+        receiver_monitor_id: &MonitorId,
+        subaddress_index: u64,
+        note: &str,
+        last_block_infos: &[BlockInfo],
+        opt_fee: u64,
+    ) -> Result<TxProposal, Error> {
+        let receiver_monitor_data = self.mobilecoind_db.get_monitor_data(receiver_monitor_id)?;
+
+        let memo_builder = GiftCodeSenderMemoBuilder::new(note)
+            .map_err(|err| Error::InvalidArgument("note".to_string(), err.to_string()))?;
+
+        self.build_gift_code_spend_transaction(
+            gift_code,
+            &receiver_monitor_data.account_key,
+            subaddress_index,
+            Box::new(memo_builder),
+            last_block_infos,
+            opt_fee,
+        )
+    }
+
+    /// Create a TxProposal which cancels a gift code, sending its value minus
+    /// the fee back to the subaddress which funded it.
+    ///
+    /// # Arguments
+    /// * `gift_code_data` - The gift code to cancel.
+    /// * `global_index` - The global index of the gift code TxOut.
+    /// * `last_block_infos` - Last block info responses from the network, for
+    ///   determining fees. This should normally come from polling_network_state
+    /// * `opt_fee` - Transaction fee. If zero, defaults to the network fee.
+    pub fn build_gift_code_cancellation_transaction(
+        &self,
+        gift_code_data: &GiftCodeData,
+        global_index: u64,
+        last_block_infos: &[BlockInfo],
+        opt_fee: u64,
+    ) -> Result<TxProposal, Error> {
+        let sender_monitor_data = self
+            .mobilecoind_db
+            .get_monitor_data(&gift_code_data.monitor_id)?;
+
+        self.build_gift_code_spend_transaction(
+            &gift_code_data.gift_code(global_index),
+            &sender_monitor_data.account_key,
+            gift_code_data.subaddress_index,
+            Box::new(GiftCodeCancellationMemoBuilder::new(global_index)),
+            last_block_infos,
+            opt_fee,
+        )
+    }
+
+    /// Create a TxProposal which spends a gift code TxOut into a single change
+    /// output.
+    fn build_gift_code_spend_transaction(
+        &self,
+        gift_code: &TxOutGiftCode,
+        account_key: &AccountKey,
+        change_subaddress: u64,
+        memo_builder: Box<dyn MemoBuilder + 'static + Send + Sync>,
+        last_block_infos: &[BlockInfo],
+        opt_fee: u64,
+    ) -> Result<TxProposal, Error> {
+        let logger = self
+            .logger
+            .new(o!("gift_code_global_index" => gift_code.global_index));
+        log::trace!(logger, "Building gift code spend transaction...");
+
+        // The gift code must be able to spend the TxOut at its global index.
+        let tx_out = self.ledger_db.get_tx_out_by_index(gift_code.global_index)?;
+        if RistrettoPublic::try_from(&tx_out.target_key)?
+            != RistrettoPublic::from(&gift_code.onetime_private_key)
+        {
+            return Err(Error::InvalidArgument(
+                "gift_code".to_string(),
+                "The one-time private key does not match the TxOut".to_string(),
+            ));
+        }
+        let amount = gift_code
+            .unblind_amount(tx_out.masked_amount.clone())
+            .map_err(|err| Error::InvalidArgument("gift_code".to_string(), err.to_string()))?;
+
+        // Figure out the block_version and fee (involves network round-trips to
+        // consensus, unless opt_fee is non-zero)
+        let (fee, block_version) =
+            self.get_network_fee_and_block_version(amount.token_id, opt_fee, last_block_infos)?;
+
+        // Confirm that we understand this block version
+        let block_version =
+            BlockVersion::try_from(block_version).map_err(|err| Error::TxBuild(err.to_string()))?;
+
+        if amount.value <= fee {
+            return Err(Error::InsufficientFunds);
+        }
+
+        // A ring of mixins, with the gift code TxOut as its first element. This is
+        // safe because TransactionBuilder sorts each ring.
+        let proof = self
+            .ledger_db
+            .get_tx_out_proof_of_memberships(&[gift_code.global_index])?
+            .pop()
+            .ok_or_else(|| Error::TxBuild("Missing gift code proof of membership".into()))?;
+        let (mut ring, mut membership_proofs): (Vec<TxOut>, Vec<TxOutMembershipProof>) = self
+            .get_rings(DEFAULT_RING_SIZE, 1, &[gift_code.global_index])?
+            .pop()
+            .unwrap_or_default()
+            .into_iter()
+            .unzip();
+        if ring.is_empty() {
+            ring.push(tx_out.clone());
+            membership_proofs.push(proof);
+        } else {
+            ring[0] = tx_out.clone();
+            membership_proofs[0] = proof;
+        }
+        log::trace!(logger, "Got ring of {} elements", ring.len());
+
+        let fog_resolver = {
+            let change_address = account_key.subaddress(change_subaddress);
+            let fog_uris: Vec<FogUri> = extract_fog_uri(&change_address)?.into_iter().collect();
+            (self.fog_resolver_factory)(&fog_uris).map_err(Error::Fog)?
+        };
+
+        let mut tx_builder = TransactionBuilder::new_with_box(
+            block_version,
+            Amount::new(fee, amount.token_id),
+            fog_resolver,
+            memo_builder,
+        )
+        .map_err(|err| Error::TxBuild(format!("Error creating transaction builder: {}", err)))?;
+
+        tx_builder.add_input(
+            InputCredentials::new_with_shared_secret(
+                ring,
+                membership_proofs,
+                0,
+                gift_code.onetime_private_key,
+                &gift_code.shared_secret,
+            )
+            .map_err(|_| Error::TxBuild("failed creating InputCredentials".into()))?,
+        );
+
+        let mut rng = rand::thread_rng();
+        let change_dest =
+            ReservedSubaddresses::from_subaddress_index(account_key, Some(change_subaddress), None);
+        tx_builder
+            .add_change_output(
+                Amount::new(amount.value - fee, amount.token_id),
+                &change_dest,
+                &mut rng,
+            )
+            .map_err(|err| Error::TxBuild(format!("failed adding output (change): {}", err)))?;
+
+        // Come up with tombstone block.
+        let tombstone_block = self.ledger_db.num_blocks()? + DEFAULT_NEW_TX_BLOCK_ATTEMPTS;
+        log::trace!(logger, "Tombstone block set to {}", tombstone_block);
+        tx_builder.set_tombstone_block(tombstone_block);
+
+        let tx = tx_builder
+            .build(&NoKeysRingSigner {}, &mut rng)
+            .map_err(|err| Error::TxBuild(format!("build tx failed: {}", err)))?;
+        log::trace!(
+            logger,
+            "Gift code spend tx constructed, hash={}",
+            tx.tx_hash()
+        );
+
+        // The gift code TxOut belongs to the gift code subaddress of its sender.
+        let utxo = UnspentTxOut {
+            tx_out,
+            subaddress_index: GIFT_CODE_SUBADDRESS_INDEX,
+            key_image: KeyImage::from(&gift_code.onetime_private_key),
+            value: amount.value,
+            attempted_spend_height: 0,
+            attempted_spend_tombstone: 0,
+            token_id: *amount.token_id,
+        };
+
+        Ok(TxProposal {
+            utxos: vec![utxo],
+            outlays: vec![],
+            tx,
+            outlay_index_to_tx_out_index: HashMap::default(),
+            outlay_confirmation_numbers: vec![],
+        })
+    }
+
     /// Submit a previously built tx proposal to the network.
     pub fn submit_tx_proposal(&self, tx_proposal: &TxProposal) -> Result<u64, Error> {
         // Pick a peer to submit to.
@@ -967,8 +1253,15 @@ impl<T: BlockchainConnection + UserTxConnection + 'static, FPR: FogPubkeyResolve
             );
         }
 
+        let reserved_subaddresses = ReservedSubaddresses::from_subaddress_index(
+            from_account_key,
+            Some(change_subaddress),
+            None,
+        );
+
         // Add outputs to our destinations.
         let mut total_value = 0;
+        let mut funds_gift_code = false;
         let mut tx_out_to_outlay_index = HashMap::default();
         let mut outlay_confirmation_numbers = Vec::default();
         for (i, outlay) in destinations.iter().enumerate() {
@@ -978,8 +1271,15 @@ impl<T: BlockchainConnection + UserTxConnection + 'static, FPR: FogPubkeyResolve
                 value: outlay.value,
                 token_id,
             };
-            let (tx_out, confirmation_number) = tx_builder
-                .add_output(amount, &outlay.receiver, rng)
+            // An outlay to the sender's own gift code subaddress funds a gift code,
+            // whose fog hint goes to the sender's primary address.
+            let (tx_out, confirmation_number) =
+                if outlay.receiver == reserved_subaddresses.gift_code_subaddress {
+                    funds_gift_code = true;
+                    tx_builder.add_gift_code_output(amount, &reserved_subaddresses, rng)
+                } else {
+                    tx_builder.add_output(amount, &outlay.receiver, rng)
+                }
                 .map_err(|err| Error::TxBuild(format!("failed adding output: {}", err)))?;
 
             tx_out_to_outlay_index.insert(tx_out, i);
@@ -998,9 +1298,11 @@ impl<T: BlockchainConnection + UserTxConnection + 'static, FPR: FogPubkeyResolve
         let change = input_value - total_value - tx_builder.get_fee();
 
         // If we do have nonzero change, add an output for that as well.
+        // Gift code funding always has a change output, since that is where the
+        // funding memo is written.
         // TODO (GH #1522): Should the exchange write destination memos?
         // If so then we must always write a change output, even if the change is zero
-        if change > 0 {
+        if change > 0 || funds_gift_code {
             // TODO: If you want to support mixed transactions, use outlay-specific token id
             // here
             let change_amount = Amount {
@@ -1008,14 +1310,8 @@ impl<T: BlockchainConnection + UserTxConnection + 'static, FPR: FogPubkeyResolve
                 token_id,
             };
 
-            let change_dest = ReservedSubaddresses::from_subaddress_index(
-                from_account_key,
-                Some(change_subaddress),
-                None,
-            );
-
             tx_builder
-                .add_change_output(change_amount, &change_dest, rng)
+                .add_change_output(change_amount, &reserved_subaddresses, rng)
                 .map_err(|err| Error::TxBuild(format!("failed adding output (change): {}", err)))?;
         }
 
@@ -9,6 +9,7 @@
 use crate::{
     database::Database,
     error::Error,
+    gift_code_store::{GiftCodeData, GiftCodeStatus},
     monitor_store::{MonitorData, MonitorId},
     payments::{Outlay, TransactionsManager, TxProposal},
     sync::SyncThread,
@@ -38,7 +39,7 @@ use mc_transaction_core::{
     onetime_keys::recover_onetime_private_key,
     ring_signature::KeyImage,
     tx::{TxOut, TxOutConfirmationNumber, TxOutMembershipProof},
-    TokenId,
+    TokenId, TxOutGiftCode,
 };
 use mc_transaction_std::{BurnRedemptionMemo, BurnRedemptionMemoBuilder};
 use mc_util_from_random::FromRandom;
@@ -475,6 +476,17 @@ impl<T: BlockchainConnection + UserTxConnection + 'static, FPR: FogPubkeyResolve
         &mut self,
         request: mc_mobilecoind_api::ParseRequestCodeRequest,
     ) -> Result<mc_mobilecoind_api::ParseRequestCodeResponse, RpcStatus> {
+        // A payment request URI carries the same data as a payment request code. The
+        // b58 alphabet has no ':', so anything containing one is treated as a URI.
+        if request.get_b58_code().contains(':') {
+            let payment_request =
+                mc_mobilecoind_api::printable::PaymentRequest::uri_decode(request.get_b58_code())
+                    .map_err(|err| {
+                    rpc_invalid_arg_error("PaymentRequest.uri_decode", err, &self.logger)
+                })?;
+            return Ok(payment_request_to_parse_response(&payment_request));
+        }
+
         let wrapper = mc_mobilecoind_api::printable::PrintableWrapper::b58_decode(
             request.get_b58_code().to_string(),
         )
@@ -482,13 +494,9 @@ impl<T: BlockchainConnection + UserTxConnection + 'static, FPR: FogPubkeyResolve
 
         // A request code could be a public address or a payment request
         if wrapper.has_payment_request() {
-            let payment_request = wrapper.get_payment_request();
-            let mut response = mc_mobilecoind_api::ParseRequestCodeResponse::new();
-            response.set_receiver(payment_request.get_public_address().clone());
-            response.set_value(payment_request.get_value());
-            response.set_memo(payment_request.get_memo().to_string());
-            response.set_token_id(payment_request.get_token_id());
-            Ok(response)
+            Ok(payment_request_to_parse_response(
+                wrapper.get_payment_request(),
+            ))
         } else if wrapper.has_public_address() {
             let public_address = wrapper.get_public_address();
             let mut response = mc_mobilecoind_api::ParseRequestCodeResponse::new();
@@ -516,6 +524,12 @@ impl<T: BlockchainConnection + UserTxConnection + 'static, FPR: FogPubkeyResolve
         payment_request.set_value(request.get_value());
         payment_request.set_memo(request.get_memo().to_string());
         payment_request.set_token_id(request.get_token_id());
+        payment_request.set_payment_request_id(request.get_payment_request_id());
+        payment_request.set_expiry(request.get_expiry());
+
+        let uri = payment_request
+            .uri_encode()
+            .map_err(|err| rpc_internal_error("uri_encode", err, &self.logger))?;
 
         let mut wrapper = mc_mobilecoind_api::printable::PrintableWrapper::new();
         wrapper.set_payment_request(payment_request);
@@ -526,6 +540,7 @@ impl<T: BlockchainConnection + UserTxConnection + 'static, FPR: FogPubkeyResolve
 
         let mut response = mc_mobilecoind_api::CreateRequestCodeResponse::new();
         response.set_b58_code(encoded);
+        response.set_uri(uri);
         Ok(response)
     }
 
@@ -1323,6 +1338,343 @@ impl<T: BlockchainConnection + UserTxConnection + 'static, FPR: FogPubkeyResolve
         Ok(response)
     }
 
+    /// Convert a gift code from the database to its API representation, along
+    /// with its current status.
+    fn gift_code_to_proto(
+        &self,
+        gift_code_data: &GiftCodeData,
+    ) -> Result<mc_mobilecoind_api::GiftCode, RpcStatus> {
+        let (status, global_index) = gift_code_data
+            .status(&self.ledger_db)
+            .map_err(|err| rpc_internal_error("gift_code_data.status", err, &self.logger))?;
+
+        let mut gift_code = mc_mobilecoind_api::GiftCode::new();
+        gift_code.set_monitor_id(gift_code_data.monitor_id.to_vec());
+        gift_code.set_subaddress_index(gift_code_data.subaddress_index);
+        gift_code.set_tx_public_key((&gift_code_data.tx_out_public_key).into());
+        gift_code.set_value(gift_code_data.value);
+        gift_code.set_token_id(gift_code_data.token_id);
+        gift_code.set_note(gift_code_data.note.clone());
+        gift_code.set_status(match status {
+            GiftCodeStatus::FundingPending => mc_mobilecoind_api::GiftCodeStatus::FundingPending,
+            GiftCodeStatus::FundingFailed => mc_mobilecoind_api::GiftCodeStatus::FundingFailed,
+            GiftCodeStatus::Available => mc_mobilecoind_api::GiftCodeStatus::Available,
+            GiftCodeStatus::Claimed => mc_mobilecoind_api::GiftCodeStatus::Claimed,
+            GiftCodeStatus::Cancelled => mc_mobilecoind_api::GiftCodeStatus::Cancelled,
+        });
+
+        // The gift code can only be encoded once its TxOut is in the ledger.
+        if let Some(global_index) = global_index {
+            let mut wrapper = mc_mobilecoind_api::printable::PrintableWrapper::new();
+            wrapper.set_tx_out_gift_code((&gift_code_data.gift_code(global_index)).into());
+
+            let b58_code = wrapper
+                .b58_encode()
+                .map_err(|err| rpc_internal_error("b58_encode", err, &self.logger))?;
+            gift_code.set_b58_code(b58_code);
+        }
+
+        Ok(gift_code)
+    }
+
+    fn generate_gift_code_funding_tx_impl(
+        &mut self,
+        request: mc_mobilecoind_api::GenerateGiftCodeFundingTxRequest,
+    ) -> Result<mc_mobilecoind_api::GenerateGiftCodeFundingTxResponse, RpcStatus> {
+        // Get sender monitor id from request.
+        let sender_monitor_id = MonitorId::try_from(&request.sender_monitor_id)
+            .map_err(|err| rpc_internal_error("monitor_id.try_from.bytes", err, &self.logger))?;
+
+        // Get monitor data for this monitor.
+        let sender_monitor_data = self
+            .mobilecoind_db
+            .get_monitor_data(&sender_monitor_id)
+            .map_err(|err| {
+                rpc_internal_error("mobilecoind_db.get_monitor_data", err, &self.logger)
+            })?;
+
+        // Check that change_subaddress is covered by this monitor.
+        if !sender_monitor_data
+            .subaddress_indexes()
+            .contains(&request.change_subaddress)
+        {
+            return Err(RpcStatus::with_message(
+                RpcStatusCode::INVALID_ARGUMENT,
+                "change_subaddress".into(),
+            ));
+        }
+
+        // Get the list of potential inputs passed to.
+        let input_list: Vec<UnspentTxOut> = request
+            .get_input_list()
+            .iter()
+            .enumerate()
+            .map(|(i, proto_utxo)| {
+                // Proto -> Rust struct conversion.
+                let utxo = UnspentTxOut::try_from(proto_utxo).map_err(|err| {
+                    rpc_internal_error(format!("unspent_tx_out[{}].try_from", i), err, &self.logger)
+                })?;
+
+                // Verify token id matches.
+                if utxo.token_id != request.token_id {
+                    return Err(RpcStatus::with_message(
+                        RpcStatusCode::INVALID_ARGUMENT,
+                        format!("input_list[{}].token_id", i),
+                    ));
+                }
+
+                // Verify this output belongs to the monitor.
+                let subaddress_id = self
+                    .mobilecoind_db
+                    .get_subaddress_id_by_utxo_id(&UtxoId::from(&utxo))
+                    .map_err(|err| {
+                        rpc_internal_error(
+                            "mobilecoind_db.get_subaddress_id_by_utxo_id",
+                            err,
+                            &self.logger,
+                        )
+                    })?;
+
+                if subaddress_id.monitor_id != sender_monitor_id {
+                    return Err(RpcStatus::with_message(
+                        RpcStatusCode::INVALID_ARGUMENT,
+                        format!("input_list[{}].monitor_id", i),
+                    ));
+                }
+
+                // Success.
+                Ok(utxo)
+            })
+            .collect::<Result<Vec<UnspentTxOut>, RpcStatus>>()?;
+
+        // Attempt to construct a transaction.
+        let (tx_proposal, gift_code_data) = self
+            .transactions_manager
+            .build_gift_code_funding_transaction(
+                &sender_monitor_id,
+                TokenId::from(request.token_id),
+                request.change_subaddress,
+                &input_list,
+                request.value,
+                &self.get_last_block_infos(),
+                request.fee,
+                request.tombstone,
+                request.get_note(),
+            )
+            .map_err(|err| {
+                rpc_internal_error(
+                    "transactions_manager.build_gift_code_funding_transaction",
+                    err,
+                    &self.logger,
+                )
+            })?;
+
+        // Store the gift code, so that it can be handed out once funded.
+        self.mobilecoind_db
+            .add_gift_code(&gift_code_data)
+            .map_err(|err| rpc_internal_error("mobilecoind_db.add_gift_code", err, &self.logger))?;
+
+        // Success.
+        let mut response = mc_mobilecoind_api::GenerateGiftCodeFundingTxResponse::new();
+        response.set_tx_proposal((&tx_proposal).into());
+        response.set_gift_code(self.gift_code_to_proto(&gift_code_data)?);
+        Ok(response)
+    }
+
+    fn get_gift_code_impl(
+        &mut self,
+        request: mc_mobilecoind_api::GetGiftCodeRequest,
+    ) -> Result<mc_mobilecoind_api::GetGiftCodeResponse, RpcStatus> {
+        let tx_public_key = CompressedRistrettoPublic::try_from(request.get_tx_public_key())
+            .map_err(|err| rpc_invalid_arg_error("tx_public_key", err, &self.logger))?;
+
+        let gift_code_data = self
+            .mobilecoind_db
+            .get_gift_code(&tx_public_key)
+            .map_err(|err| rpc_internal_error("mobilecoind_db.get_gift_code", err, &self.logger))?;
+
+        let mut response = mc_mobilecoind_api::GetGiftCodeResponse::new();
+        response.set_gift_code(self.gift_code_to_proto(&gift_code_data)?);
+        Ok(response)
+    }
+
+    fn get_all_gift_codes_impl(
+        &mut self,
+        _request: mc_mobilecoind_api::Empty,
+    ) -> Result<mc_mobilecoind_api::GetAllGiftCodesResponse, RpcStatus> {
+        let gift_code_list = self
+            .mobilecoind_db
+            .get_gift_codes()
+            .map_err(|err| rpc_internal_error("mobilecoind_db.get_gift_codes", err, &self.logger))?
+            .iter()
+            .map(|gift_code_data| self.gift_code_to_proto(gift_code_data))
+            .collect::<Result<Vec<_>, RpcStatus>>()?;
+
+        let mut response = mc_mobilecoind_api::GetAllGiftCodesResponse::new();
+        response.set_gift_code_list(RepeatedField::from_vec(gift_code_list));
+        Ok(response)
+    }
+
+    fn remove_gift_code_impl(
+        &mut self,
+        request: mc_mobilecoind_api::RemoveGiftCodeRequest,
+    ) -> Result<mc_mobilecoind_api::Empty, RpcStatus> {
+        let tx_public_key = CompressedRistrettoPublic::try_from(request.get_tx_public_key())
+            .map_err(|err| rpc_invalid_arg_error("tx_public_key", err, &self.logger))?;
+
+        self.mobilecoind_db
+            .remove_gift_code(&tx_public_key)
+            .map_err(|err| {
+                rpc_internal_error("mobilecoind_db.remove_gift_code", err, &self.logger)
+            })?;
+
+        Ok(mc_mobilecoind_api::Empty::new())
+    }
+
+    fn generate_gift_code_claim_tx_impl(
+        &mut self,
+        request: mc_mobilecoind_api::GenerateGiftCodeClaimTxRequest,
+    ) -> Result<mc_mobilecoind_api::GenerateGiftCodeClaimTxResponse, RpcStatus> {
+        let wrapper = mc_mobilecoind_api::printable::PrintableWrapper::b58_decode(
+            request.get_b58_code().to_string(),
+        )
+        .map_err(|err| rpc_internal_error("PrintableWrapper.b58_decode", err, &self.logger))?;
+
+        if !wrapper.has_tx_out_gift_code() {
+            return Err(RpcStatus::with_message(
+                RpcStatusCode::INVALID_ARGUMENT,
+                "has_tx_out_gift_code".into(),
+            ));
+        }
+        let gift_code = TxOutGiftCode::try_from(wrapper.get_tx_out_gift_code())
+            .map_err(|err| rpc_invalid_arg_error("tx_out_gift_code", err, &self.logger))?;
+
+        // Get receiver monitor id from request.
+        let receiver_monitor_id = MonitorId::try_from(&request.receiver_monitor_id)
+            .map_err(|err| rpc_internal_error("monitor_id.try_from.bytes", err, &self.logger))?;
+
+        // Get monitor data for this monitor.
+        let receiver_monitor_data = self
+            .mobilecoind_db
+            .get_monitor_data(&receiver_monitor_id)
+            .map_err(|err| {
+                rpc_internal_error("mobilecoind_db.get_monitor_data", err, &self.logger)
+            })?;
+
+        // Check that subaddress_index is covered by this monitor.
+        if !receiver_monitor_data
+            .subaddress_indexes()
+            .contains(&request.subaddress_index)
+        {
+            return Err(RpcStatus::with_message(
+                RpcStatusCode::INVALID_ARGUMENT,
+                "subaddress_index".into(),
+            ));
+        }
+
+        // A gift code can only be claimed once.
+        let key_image = KeyImage::from(&gift_code.onetime_private_key);
+        if self
+            .ledger_db
+            .contains_key_image(&key_image)
+            .map_err(|err| rpc_internal_error("ledger_db.contains_key_image", err, &self.logger))?
+        {
+            return Err(RpcStatus::with_message(
+                RpcStatusCode::INVALID_ARGUMENT,
+                "gift code was already claimed".into(),
+            ));
+        }
+
+        // Attempt to construct a transaction.
+        let tx_proposal = self
+            .transactions_manager
+            .build_gift_code_claim_transaction(
+                &gift_code,
+                &receiver_monitor_id,
+                request.subaddress_index,
+                request.get_note(),
+                &self.get_last_block_infos(),
+                request.fee,
+            )
+            .map_err(|err| {
+                rpc_internal_error(
+                    "transactions_manager.build_gift_code_claim_transaction",
+                    err,
+                    &self.logger,
+                )
+            })?;
+
+        // Success.
+        let mut response = mc_mobilecoind_api::GenerateGiftCodeClaimTxResponse::new();
+        response.set_tx_proposal((&tx_proposal).into());
+        Ok(response)
+    }
+
+    fn generate_gift_code_cancellation_tx_impl(
+        &mut self,
+        request: mc_mobilecoind_api::GenerateGiftCodeCancellationTxRequest,
+    ) -> Result<mc_mobilecoind_api::GenerateGiftCodeCancellationTxResponse, RpcStatus> {
+        let tx_public_key = CompressedRistrettoPublic::try_from(request.get_tx_public_key())
+            .map_err(|err| rpc_invalid_arg_error("tx_public_key", err, &self.logger))?;
+
+        let mut gift_code_data = self
+            .mobilecoind_db
+            .get_gift_code(&tx_public_key)
+            .map_err(|err| rpc_internal_error("mobilecoind_db.get_gift_code", err, &self.logger))?;
+
+        // Only gift codes which are in the ledger and unclaimed can be cancelled.
+        let global_index = match gift_code_data
+            .status(&self.ledger_db)
+            .map_err(|err| rpc_internal_error("gift_code_data.status", err, &self.logger))?
+        {
+            (GiftCodeStatus::Available, Some(global_index)) => global_index,
+            (status, _) => {
+                return Err(RpcStatus::with_message(
+                    RpcStatusCode::INVALID_ARGUMENT,
+                    format!("gift code is not available: {:?}", status),
+                ));
+            }
+        };
+
+        // Attempt to construct a transaction.
+        let tx_proposal = self
+            .transactions_manager
+            .build_gift_code_cancellation_transaction(
+                &gift_code_data,
+                global_index,
+                &self.get_last_block_infos(),
+                request.fee,
+            )
+            .map_err(|err| {
+                rpc_internal_error(
+                    "transactions_manager.build_gift_code_cancellation_transaction",
+                    err,
+                    &self.logger,
+                )
+            })?;
+
+        // Remember the cancellation output, so that the gift code is reported as
+        // cancelled rather than claimed once the transaction lands.
+        gift_code_data.cancellation_tx_out_public_keys.extend(
+            tx_proposal
+                .tx
+                .prefix
+                .outputs
+                .iter()
+                .map(|tx_out| tx_out.public_key),
+        );
+        self.mobilecoind_db
+            .update_gift_code(&gift_code_data)
+            .map_err(|err| {
+                rpc_internal_error("mobilecoind_db.update_gift_code", err, &self.logger)
+            })?;
+
+        // Success.
+        let mut response = mc_mobilecoind_api::GenerateGiftCodeCancellationTxResponse::new();
+        response.set_tx_proposal((&tx_proposal).into());
+        Ok(response)
+    }
+
     fn get_ledger_info_impl(
         &mut self,
         _request: mc_mobilecoind_api::Empty,
@@ -2035,6 +2387,20 @@ impl<T: BlockchainConnection + UserTxConnection + 'static, FPR: FogPubkeyResolve
     }
 }
 
+/// Convert a payment request to a ParseRequestCodeResponse.
+fn payment_request_to_parse_response(
+    payment_request: &mc_mobilecoind_api::printable::PaymentRequest,
+) -> mc_mobilecoind_api::ParseRequestCodeResponse {
+    let mut response = mc_mobilecoind_api::ParseRequestCodeResponse::new();
+    response.set_receiver(payment_request.get_public_address().clone());
+    response.set_value(payment_request.get_value());
+    response.set_memo(payment_request.get_memo().to_string());
+    response.set_token_id(payment_request.get_token_id());
+    response.set_payment_request_id(payment_request.get_payment_request_id());
+    response.set_expiry(payment_request.get_expiry());
+    response
+}
+
 macro_rules! build_api {
     ($( $service_function_name:ident $service_request_type:ident $service_response_type:ident $service_function_impl:ident ),+)
     =>
@@ -2093,6 +2459,14 @@ build_api! {
     generate_burn_redemption_tx GenerateBurnRedemptionTxRequest GenerateBurnRedemptionTxResponse generate_burn_redemption_tx_impl,
     submit_tx SubmitTxRequest SubmitTxResponse submit_tx_impl,
 
+    // Gift codes
+    generate_gift_code_funding_tx GenerateGiftCodeFundingTxRequest GenerateGiftCodeFundingTxResponse generate_gift_code_funding_tx_impl,
+    get_gift_code GetGiftCodeRequest GetGiftCodeResponse get_gift_code_impl,
+    get_all_gift_codes Empty GetAllGiftCodesResponse get_all_gift_codes_impl,
+    remove_gift_code RemoveGiftCodeRequest Empty remove_gift_code_impl,
+    generate_gift_code_claim_tx GenerateGiftCodeClaimTxRequest GenerateGiftCodeClaimTxResponse generate_gift_code_claim_tx_impl,
+    generate_gift_code_cancellation_tx GenerateGiftCodeCancellationTxRequest GenerateGiftCodeCancellationTxResponse generate_gift_code_cancellation_tx_impl,
+
     // Databases
     get_ledger_info Empty GetLedgerInfoResponse get_ledger_info_impl,
     get_block_info GetBlockInfoRequest GetBlockInfoResponse get_block_info_impl,
@@ -4267,6 +4641,221 @@ mod test {
         }
     }
 
+    #[test_with_logger]
+    fn test_gift_codes(logger: Logger) {
+        let mut rng: StdRng = SeedableRng::from_seed([23u8; 32]);
+
+        let sender = AccountKey::random(&mut rng);
+        let sender_data = MonitorData::new(
+            sender.clone(),
+            0,  // first_subaddress
+            20, // num_subaddresses
+            0,  // first_block
+            "", // name
+        )
+        .unwrap();
+
+        let receiver = AccountKey::random(&mut rng);
+        let receiver_data = MonitorData::new(
+            receiver.clone(),
+            0,  // first_subaddress
+            20, // num_subaddresses
+            0,  // first_block
+            "", // name
+        )
+        .unwrap();
+
+        // 1 known recipient, 3 random recipients and no monitors.
+        let (mut ledger_db, mobilecoind_db, client, _server, _server_conn_manager) =
+            get_testing_environment(
+                BLOCK_VERSION,
+                3,
+                &[sender.default_subaddress()],
+                &[],
+                logger.clone(),
+                &mut rng,
+            );
+
+        // Insert into database.
+        let sender_monitor_id = mobilecoind_db.add_monitor(&sender_data).unwrap();
+        let receiver_monitor_id = mobilecoind_db.add_monitor(&receiver_data).unwrap();
+
+        // Allow the new monitors to process the ledger.
+        wait_for_monitors(&mobilecoind_db, &ledger_db, &logger);
+
+        // Append a transaction to the ledger.
+        let append_tx = |ledger_db: &mut LedgerDB, tx: &Tx| {
+            let block_contents = BlockContents {
+                key_images: tx.key_images(),
+                outputs: tx.prefix.outputs.clone(),
+                ..Default::default()
+            };
+            let num_blocks = ledger_db.num_blocks().unwrap();
+            let parent = ledger_db.get_block(num_blocks - 1).unwrap();
+            let new_block = Block::new_with_parent(
+                BLOCK_VERSION,
+                &parent,
+                &Default::default(),
+                &block_contents,
+            );
+            ledger_db
+                .append_block(&new_block, &block_contents, None)
+                .unwrap();
+        };
+
+        // Get list of unspent tx outs
+        let utxos = mobilecoind_db
+            .get_utxos_for_subaddress(&sender_monitor_id, 0)
+            .unwrap();
+        assert!(!utxos.is_empty());
+
+        // Fund a gift code.
+        let gift_code_value = 100 * Mob::MINIMUM_FEE;
+        let mut request = mc_mobilecoind_api::GenerateGiftCodeFundingTxRequest::new();
+        request.set_sender_monitor_id(sender_monitor_id.to_vec());
+        request.set_change_subaddress(0);
+        request.set_input_list(RepeatedField::from_vec(
+            utxos
+                .iter()
+                .map(mc_mobilecoind_api::UnspentTxOut::from)
+                .collect(),
+        ));
+        request.set_value(gift_code_value);
+        request.set_fee(Mob::MINIMUM_FEE);
+        request.set_note("happy birthday".to_owned());
+
+        let response = client.generate_gift_code_funding_tx(&request).unwrap();
+        let gift_code = response.get_gift_code();
+        assert_eq!(
+            gift_code.get_status(),
+            mc_mobilecoind_api::GiftCodeStatus::FundingPending
+        );
+        assert_eq!(gift_code.get_value(), gift_code_value);
+        assert_eq!(gift_code.get_note(), "happy birthday");
+        assert!(gift_code.get_b58_code().is_empty());
+
+        // The gift code goes to the sender's gift code subaddress, and the funding
+        // memo to the change output.
+        let funding_tx = Tx::try_from(response.get_tx_proposal().get_tx()).unwrap();
+        assert_eq!(funding_tx.prefix.outputs.len(), 2);
+        let tx_public_key =
+            CompressedRistrettoPublic::try_from(gift_code.get_tx_public_key()).unwrap();
+        let gift_code_tx_out = funding_tx
+            .prefix
+            .outputs
+            .iter()
+            .find(|tx_out| tx_out.public_key == tx_public_key)
+            .unwrap();
+        assert_eq!(
+            recover_public_subaddress_spend_key(
+                sender.view_private_key(),
+                &RistrettoPublic::try_from(&gift_code_tx_out.target_key).unwrap(),
+                &RistrettoPublic::try_from(&gift_code_tx_out.public_key).unwrap(),
+            ),
+            *sender.gift_code_subaddress().spend_public_key()
+        );
+        let change_tx_out = funding_tx
+            .prefix
+            .outputs
+            .iter()
+            .find(|tx_out| tx_out.public_key != tx_public_key)
+            .unwrap();
+        let ss = get_tx_out_shared_secret(
+            sender.view_private_key(),
+            &RistrettoPublic::try_from(&change_tx_out.public_key).unwrap(),
+        );
+        let memo = change_tx_out.e_memo.unwrap().decrypt(&ss);
+        assert_matches!(
+            MemoType::try_from(&memo).unwrap(),
+            MemoType::GiftCodeFunding(memo) if memo.funding_note().unwrap() == "happy birthday"
+        );
+
+        // The gift code can't be cancelled before it is funded.
+        let mut cancel_request = mc_mobilecoind_api::GenerateGiftCodeCancellationTxRequest::new();
+        cancel_request.set_tx_public_key(gift_code.get_tx_public_key().clone());
+        cancel_request.set_fee(Mob::MINIMUM_FEE);
+        assert!(client
+            .generate_gift_code_cancellation_tx(&cancel_request)
+            .is_err());
+
+        // Once the funding transaction lands, the gift code is available.
+        append_tx(&mut ledger_db, &funding_tx);
+
+        let mut get_request = mc_mobilecoind_api::GetGiftCodeRequest::new();
+        get_request.set_tx_public_key(gift_code.get_tx_public_key().clone());
+        let gift_code = client.get_gift_code(&get_request).unwrap().take_gift_code();
+        assert_eq!(
+            gift_code.get_status(),
+            mc_mobilecoind_api::GiftCodeStatus::Available
+        );
+        assert!(!gift_code.get_b58_code().is_empty());
+
+        let all_gift_codes = client
+            .get_all_gift_codes(&mc_mobilecoind_api::Empty::new())
+            .unwrap();
+        assert_eq!(all_gift_codes.get_gift_code_list(), &[gift_code.clone()]);
+
+        // The receiver can claim the gift code.
+        let mut claim_request = mc_mobilecoind_api::GenerateGiftCodeClaimTxRequest::new();
+        claim_request.set_b58_code(gift_code.get_b58_code().to_owned());
+        claim_request.set_receiver_monitor_id(receiver_monitor_id.to_vec());
+        claim_request.set_subaddress_index(0);
+        claim_request.set_note("thanks".to_owned());
+        claim_request.set_fee(Mob::MINIMUM_FEE);
+
+        let claim_response = client.generate_gift_code_claim_tx(&claim_request).unwrap();
+        let claim_tx = Tx::try_from(claim_response.get_tx_proposal().get_tx()).unwrap();
+        assert_eq!(claim_tx.prefix.outputs.len(), 1);
+        let (amount, _) = claim_tx.prefix.outputs[0]
+            .view_key_match(receiver.view_private_key())
+            .unwrap();
+        assert_eq!(amount.value, gift_code_value - Mob::MINIMUM_FEE);
+
+        // The sender can cancel the gift code instead.
+        let cancel_response = client
+            .generate_gift_code_cancellation_tx(&cancel_request)
+            .unwrap();
+        let cancel_tx = Tx::try_from(cancel_response.get_tx_proposal().get_tx()).unwrap();
+        assert_eq!(cancel_tx.prefix.outputs.len(), 1);
+        let cancel_tx_out = &cancel_tx.prefix.outputs[0];
+        let (amount, _) = cancel_tx_out
+            .view_key_match(sender.view_private_key())
+            .unwrap();
+        assert_eq!(amount.value, gift_code_value - Mob::MINIMUM_FEE);
+        let ss = get_tx_out_shared_secret(
+            sender.view_private_key(),
+            &RistrettoPublic::try_from(&cancel_tx_out.public_key).unwrap(),
+        );
+        let memo = cancel_tx_out.e_memo.unwrap().decrypt(&ss);
+        let global_index = ledger_db
+            .get_tx_out_index_by_public_key(&tx_public_key)
+            .unwrap();
+        assert_matches!(
+            MemoType::try_from(&memo).unwrap(),
+            MemoType::GiftCodeCancellation(memo) if memo.cancelled_gift_code_index() == global_index
+        );
+
+        // Once the cancellation lands, the gift code is cancelled and can no longer
+        // be claimed.
+        append_tx(&mut ledger_db, &cancel_tx);
+
+        let gift_code = client.get_gift_code(&get_request).unwrap().take_gift_code();
+        assert_eq!(
+            gift_code.get_status(),
+            mc_mobilecoind_api::GiftCodeStatus::Cancelled
+        );
+        assert!(client.generate_gift_code_claim_tx(&claim_request).is_err());
+        assert!(client
+            .generate_gift_code_cancellation_tx(&cancel_request)
+            .is_err());
+
+        // Removing the gift code forgets about it.
+        let mut remove_request = mc_mobilecoind_api::RemoveGiftCodeRequest::new();
+        remove_request.set_tx_public_key(gift_code.get_tx_public_key().clone());
+        client.remove_gift_code(&remove_request).unwrap();
+        assert!(client.get_gift_code(&get_request).is_err());
+    }
+
     #[test_with_logger]
     fn test_generate_optimization_tx(logger: Logger) {
         let mut rng: StdRng = SeedableRng::from_seed([23u8; 32]);
@@ -5532,6 +6121,49 @@ mod test {
             assert_eq!(response.get_token_id(), 123);
         }
 
+        // Try with a payment request id and expiry, parsing both the code and the
+        // URI.
+        {
+            // Generate a request code
+            let mut request = mc_mobilecoind_api::CreateRequestCodeRequest::new();
+            request.set_receiver(mc_api::external::PublicAddress::from(&receiver));
+            request.set_value(1234567890);
+            request.set_memo("invoice #7".to_owned());
+            request.set_token_id(123);
+            request.set_payment_request_id(77);
+            request.set_expiry(1_700_000_000);
+
+            let response = client.create_request_code(&request).unwrap();
+            assert!(response.get_uri().starts_with("mob:"));
+
+            for code in [response.get_b58_code(), response.get_uri()] {
+                // Attempt to decode it.
+                let mut request = mc_mobilecoind_api::ParseRequestCodeRequest::new();
+                request.set_b58_code(code.to_string());
+
+                let response = client.parse_request_code(&request).unwrap();
+
+                // Check that input equals output.
+                assert_eq!(
+                    PublicAddress::try_from(response.get_receiver()).unwrap(),
+                    receiver
+                );
+                assert_eq!(response.value, 1234567890);
+                assert_eq!(response.get_memo(), "invoice #7");
+                assert_eq!(response.get_token_id(), 123);
+                assert_eq!(response.get_payment_request_id(), 77);
+                assert_eq!(response.get_expiry(), 1_700_000_000);
+            }
+        }
+
+        // Attempting to decode a junk URI should fail
+        {
+            let mut request = mc_mobilecoind_api::ParseRequestCodeRequest::new();
+            request.set_b58_code("mob:junk?amount=1".to_owned());
+
+            assert!(client.parse_request_code(&request).is_err());
+        }
+
         // Attempting to decode junk data should fail
         {
             let mut request = mc_mobilecoind_api::ParseRequestCodeRequest::new();
@@ -10,9 +10,9 @@ use crate::{
 use core::fmt::{Display, Formatter, Result as FmtResult};
 use grpcio::{ChannelBuilder, Environment, Error as GrpcError};
 use mc_attest_api::attest_grpc::AttestedApiClient;
-use mc_attest_core::VerificationReport;
+use mc_attest_core::{VerificationReport, VerificationReportData};
 use mc_attest_enclave_api::PeerSession;
-use mc_blockchain_types::{Block, BlockID, BlockIndex};
+use mc_blockchain_types::{Block, BlockData, BlockID, BlockIndex};
 use mc_common::{
     logger::{log, o, Logger},
     trace_time, NodeID, ResponderId,
@@ -33,6 +33,7 @@ use mc_consensus_api::{
     ConversionError,
 };
 use mc_consensus_enclave_api::{ConsensusEnclave, TxContext, WellFormedEncryptedTx};
+use mc_crypto_keys::Ed25519Public;
 use mc_transaction_core::tx::TxHash;
 use mc_util_grpc::ConnectionUriGrpcioChannel;
 use mc_util_serial::{deserialize, serialize};
@@ -66,6 +67,10 @@ pub struct PeerConnection<Enclave: ConsensusEnclave + Clone + Send + Sync> {
     /// The remote node's URI.
     uri: PeerUri,
 
+    /// The key the remote enclave signs blocks with, taken from its
+    /// attestation report.
+    remote_block_signer: Option<Ed25519Public>,
+
     /// The logger instance we will be using.
     logger: Logger,
 
@@ -106,6 +111,7 @@ impl<Enclave: ConsensusEnclave + Clone + Send + Sync> PeerConnection<Enclave> {
             local_node_id,
             remote_responder_id,
             uri,
+            remote_block_signer: None,
             channel_id: None,
             logger,
             attested_api_client,
@@ -190,6 +196,7 @@ impl<Enclave: ConsensusEnclave + Clone + Send + Sync> AttestedConnection
             .enclave
             .peer_connect(&self.remote_responder_id(), res.into())?;
         self.channel_id = Some(peer_session);
+        self.remote_block_signer = block_signer_from_report(&verification_report);
 
         Ok(verification_report)
     }
@@ -275,6 +282,10 @@ impl<Enclave: ConsensusEnclave + Clone + Send + Sync> ConsensusConnection
         self.local_node_id.clone()
     }
 
+    fn remote_block_signer(&self) -> Option<Ed25519Public> {
+        self.remote_block_signer
+    }
+
     fn send_consensus_msg(&mut self, msg: &ConsensusMsg) -> Result<ConsensusMsgResponse> {
         let mut grpc_msg = GrpcConsensusMsg::default();
         grpc_msg.set_from_responder_id(self.local_node_id.responder_id.to_string());
@@ -359,4 +370,33 @@ impl<Enclave: ConsensusEnclave + Clone + Send + Sync> ConsensusConnection
             Ok(Some(msg))
         }
     }
+
+    fn fetch_block_data(&mut self, range: Range<BlockIndex>) -> Result<Vec<BlockData>> {
+        trace_time!(self.logger, "PeerConnection::fetch_block_data");
+
+        let mut request = BlocksRequest::new();
+        request.set_offset(range.start);
+        let limit = u32::try_from(range.end - range.start).or(Err(Error::RequestTooLarge))?;
+        request.set_limit(limit);
+
+        let archive_blocks = self.log_attested_call("get_block_data", |this| {
+            this.consensus_api_client.get_block_data(&request)
+        })?;
+
+        Ok(Vec::<BlockData>::try_from(&archive_blocks)?)
+    }
+}
+
+/// Get the block signer key out of a VerificationReport. The enclave's report
+/// data holds its key exchange identity followed by its signing identity.
+fn block_signer_from_report(verification_report: &VerificationReport) -> Option<Ed25519Public> {
+    let report_data = VerificationReportData::try_from(verification_report).ok()?;
+    let report_body = report_data.quote.report_body().ok()?;
+    let custom_data = report_body.report_data();
+    let custom_data_bytes: &[u8] = custom_data.as_ref();
+    if custom_data_bytes.len() != 64 {
+        return None;
+    }
+
+    Ed25519Public::try_from(&custom_data_bytes[32..]).ok()
 }
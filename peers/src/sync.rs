@@ -7,12 +7,14 @@ use crate::{
     error::RetryResult,
     traits::{ConsensusConnection, RetryableConsensusConnection},
 };
+use mc_blockchain_types::{BlockData, BlockIndex};
 use mc_common::{NodeID, ResponderId};
 use mc_connection::{impl_sync_connection_retry, SyncConnection};
 use mc_consensus_api::consensus_peer::ConsensusMsgResponse;
 use mc_consensus_enclave_api::{TxContext, WellFormedEncryptedTx};
+use mc_crypto_keys::Ed25519Public;
 use mc_transaction_core::tx::TxHash;
-use std::time::Duration;
+use std::{ops::Range, time::Duration};
 
 /// Blanket implementation of RetryableConsensusConnection for SyncConnection
 /// objects which own a ConsensusConnection.
@@ -21,6 +23,10 @@ impl<CC: ConsensusConnection> RetryableConsensusConnection for SyncConnection<CC
         self.read().remote_responder_id()
     }
 
+    fn remote_block_signer(&self) -> Option<Ed25519Public> {
+        self.read().remote_block_signer()
+    }
+
     fn send_consensus_msg(
         &self,
         msg: &ConsensusMsg,
@@ -76,4 +82,18 @@ impl<CC: ConsensusConnection> RetryableConsensusConnection for SyncConnection<CC
             retry_iterator
         )
     }
+
+    fn fetch_block_data(
+        &self,
+        range: Range<BlockIndex>,
+        retry_iterator: impl IntoIterator<Item = Duration>,
+    ) -> RetryResult<Vec<BlockData>> {
+        impl_sync_connection_retry!(
+            self.write(),
+            self.logger(),
+            fetch_block_data,
+            retry_iterator,
+            range.clone()
+        )
+    }
 }
@@ -67,22 +67,8 @@ impl<RP: RetryPolicy> ThreadedBroadcaster<RP> {
         let peer_threads: Vec<PeerThread> = manager
             .conns()
             .into_iter()
-            .filter(|conn| {
-                conn.uri()
-                    .get_param("broadcast-consensus-msgs")
-                    .unwrap_or_else(|| "1".to_string())
-                    == "1"
-            })
-            .map(|conn| {
-                let peer_name = conn.to_string();
-                PeerThread::new(
-                    conn,
-                    retry_policy,
-                    logger.new(o!(
-                        "mc.peers.peer_name" => peer_name,
-                    )),
-                )
-            })
+            .filter(Self::should_broadcast_to)
+            .map(|conn| Self::start_peer_thread(conn, retry_policy, &logger))
             .collect();
         Self {
             peer_threads,
@@ -99,6 +85,81 @@ impl<RP: RetryPolicy> ThreadedBroadcaster<RP> {
         }
     }
 
+    /// Bring the set of peers we broadcast to in line with the connections
+    /// currently held by `manager`: peer threads are started for connections
+    /// that were added, stopped for connections that were removed, and
+    /// restarted for connections whose URI changed (e.g. a peer which was
+    /// re-added with a new message signing key).
+    pub fn update_peers<CC: ConsensusConnection + 'static>(
+        &mut self,
+        manager: &ConnectionManager<CC>,
+    ) {
+        let conns: Vec<SyncConnection<CC>> = manager
+            .conns()
+            .into_iter()
+            .filter(Self::should_broadcast_to)
+            .collect();
+        let uris: Vec<String> = conns.iter().map(|conn| conn.uri().to_string()).collect();
+
+        // Stop threads for peers that are gone, or whose URI changed.
+        let (mut removed, retained): (Vec<_>, Vec<_>) = self
+            .peer_threads
+            .drain(..)
+            .partition(|peer_thread| !uris.iter().any(|uri| uri == peer_thread.uri()));
+        self.peer_threads = retained;
+        for peer_thread in removed.iter_mut() {
+            log::info!(
+                self.logger,
+                "No longer broadcasting to {}",
+                peer_thread.responder_id()
+            );
+            peer_thread.stop();
+        }
+
+        // Start threads for new peers, and for peers whose URI changed.
+        for conn in conns {
+            let uri = conn.uri().to_string();
+            if self
+                .peer_threads
+                .iter()
+                .any(|peer_thread| peer_thread.uri() == uri)
+            {
+                continue;
+            }
+            log::info!(
+                self.logger,
+                "Starting to broadcast to {}",
+                conn.remote_responder_id()
+            );
+            let peer_thread = Self::start_peer_thread(conn, &self.retry_policy, &self.logger);
+            self.peer_threads.push(peer_thread);
+        }
+    }
+
+    /// Peers can opt out of receiving broadcasted consensus messages via the
+    /// `broadcast-consensus-msgs` URI parameter.
+    fn should_broadcast_to<CC: ConsensusConnection + 'static>(conn: &SyncConnection<CC>) -> bool {
+        conn.uri()
+            .get_param("broadcast-consensus-msgs")
+            .unwrap_or_else(|| "1".to_string())
+            == "1"
+    }
+
+    fn start_peer_thread<CC: ConsensusConnection + 'static>(
+        conn: SyncConnection<CC>,
+        retry_policy: &RP,
+        logger: &Logger,
+    ) -> PeerThread {
+        let peer_name = conn.to_string();
+        PeerThread::new(
+            conn,
+            retry_policy,
+            logger.new(o!(
+                "mc.peers.peer_name" => peer_name,
+            )),
+        )
+    }
+
     /// Broadcasts a propose transaction message.
     ///
     /// # Arguments
@@ -256,6 +317,7 @@ enum ThreadMsg {
 /// A single peer thread.
 struct PeerThread {
     responder_id: ResponderId,
+    uri: String,
     sender: crossbeam_channel::Sender<ThreadMsg>,
     join_handle: Option<thread::JoinHandle<()>>,
 }
@@ -269,6 +331,7 @@ impl PeerThread {
         let (sender, receiver) = crossbeam_channel::unbounded();
 
         let responder_id = conn.remote_responder_id();
+        let uri = conn.uri().to_string();
 
         let retry_policy = retry_policy.clone();
 
@@ -282,6 +345,7 @@ impl PeerThread {
         );
         Self {
             responder_id,
+            uri,
             sender,
             join_handle,
         }
@@ -291,6 +355,11 @@ impl PeerThread {
         &self.responder_id
     }
 
+    /// The URI of the connection this thread broadcasts to.
+    pub fn uri(&self) -> &str {
+        &self.uri
+    }
+
     pub fn send_consensus_msg(
         &self,
         msg: Arc<ConsensusMsg>,
@@ -6,12 +6,14 @@ use crate::{
     error::{Result, RetryResult},
     ConsensusMsg,
 };
+use mc_blockchain_types::{BlockData, BlockIndex};
 use mc_common::{NodeID, ResponderId};
 use mc_connection::Connection;
 use mc_consensus_api::consensus_peer::ConsensusMsgResponse;
 use mc_consensus_enclave_api::{TxContext, WellFormedEncryptedTx};
+use mc_crypto_keys::Ed25519Public;
 use mc_transaction_core::tx::TxHash;
-use std::time::Duration;
+use std::{ops::Range, time::Duration};
 
 /// A trait which describes a connection from one consensus node to another.
 pub trait ConsensusConnection: Connection {
@@ -21,6 +23,11 @@ pub trait ConsensusConnection: Connection {
     /// Retrieve the local node ID.
     fn local_node_id(&self) -> NodeID;
 
+    /// Retrieve the key the remote peer's enclave signs blocks with, as proven
+    /// by its attestation report. This is only known once the connection has
+    /// been attested.
+    fn remote_block_signer(&self) -> Option<Ed25519Public>;
+
     /// Send the given consensus message to the remote peer.
     fn send_consensus_msg(&mut self, msg: &ConsensusMsg) -> Result<ConsensusMsgResponse>;
 
@@ -36,6 +43,10 @@ pub trait ConsensusConnection: Connection {
 
     /// Retrieve the most recent consensus message sent by this peer.
     fn fetch_latest_msg(&mut self) -> Result<Option<ConsensusMsg>>;
+
+    /// Retrieve the blocks in the given range, along with their contents and
+    /// signatures.
+    fn fetch_block_data(&mut self, range: Range<BlockIndex>) -> Result<Vec<BlockData>>;
 }
 
 /// Retriable versions of the ConsensusConnection methods
@@ -43,6 +54,10 @@ pub trait RetryableConsensusConnection {
     /// Retrieve the remote peer ResponderId.
     fn remote_responder_id(&self) -> ResponderId;
 
+    /// Retrieve the key the remote peer's enclave signs blocks with, if the
+    /// connection has been attested.
+    fn remote_block_signer(&self) -> Option<Ed25519Public>;
+
     /// Retryable version of the consensus message transmitter
     fn send_consensus_msg(
         &self,
@@ -69,4 +84,10 @@ pub trait RetryableConsensusConnection {
         &self,
         retry_iterator: impl IntoIterator<Item = Duration>,
     ) -> RetryResult<Option<ConsensusMsg>>;
+
+    fn fetch_block_data(
+        &self,
+        range: Range<BlockIndex>,
+        retry_iterator: impl IntoIterator<Item = Duration>,
+    ) -> RetryResult<Vec<BlockData>>;
 }
@@ -4,7 +4,7 @@
 
 pub use mc_consensus_scp::test_utils::{test_node_id, test_node_id_and_signer};
 
-use mc_blockchain_types::{Block, BlockID, BlockIndex};
+use mc_blockchain_types::{Block, BlockData, BlockID, BlockIndex};
 use mc_common::{NodeID, ResponderId};
 use mc_connection::{
     BlockInfo, BlockchainConnection, Connection, Error as ConnectionError,
@@ -60,6 +60,7 @@ pub struct MockPeerConnection<L: Ledger + Sync = MockLedger> {
     pub ledger: L,
     pub latency_millis: u64,
     pub state: Arc<Mutex<MockPeerState>>,
+    pub block_signer: Option<Ed25519Public>,
 }
 
 impl<L: Ledger + Sync> MockPeerConnection<L> {
@@ -77,6 +78,7 @@ impl<L: Ledger + Sync> MockPeerConnection<L> {
             ledger,
             latency_millis,
             state: Arc::new(Mutex::new(MockPeerState::default())),
+            block_signer: None,
         }
     }
 
@@ -192,6 +194,10 @@ impl<L: Ledger + Sync> ConsensusConnection for MockPeerConnection<L> {
         self.local_node_id.clone()
     }
 
+    fn remote_block_signer(&self) -> Option<Ed25519Public> {
+        self.block_signer
+    }
+
     fn send_consensus_msg(&mut self, msg: &ConsensusMsg) -> PeerResult<ConsensusMsgResponse> {
         let mut locked_state = self.state.lock().expect("Locked poisoned");
         locked_state.send_consensus_msg_call_count += 1;
@@ -221,6 +227,20 @@ impl<L: Ledger + Sync> ConsensusConnection for MockPeerConnection<L> {
     fn fetch_latest_msg(&mut self) -> PeerResult<Option<ConsensusMsg>> {
         unimplemented!()
     }
+
+    fn fetch_block_data(&mut self, range: Range<BlockIndex>) -> PeerResult<Vec<BlockData>> {
+        thread::sleep(Duration::from_millis(self.latency_millis));
+
+        let num_blocks = self.ledger.num_blocks().unwrap();
+        if range.start >= num_blocks {
+            return Err(PeerError::NotFound);
+        }
+
+        (range.start..min(range.end, num_blocks))
+            .map(|block_index| self.ledger.get_block_data(block_index))
+            .collect::<Result<Vec<BlockData>, _>>()
+            .or(Err(PeerError::NotFound))
+    }
 }
 pub fn create_consensus_msg(
     ledger: &impl Ledger,
@@ -379,6 +399,49 @@ mod peer_manager_tests {
         assert!(ret.is_ok());
         assert_eq!(peer.state().send_consensus_msg_call_count, 5);
     }
+
+    #[test_with_logger]
+    // add_conn should add or replace the connection for a responder id, and
+    // remove_conn should remove it.
+    fn add_conn_and_remove_conn(logger: Logger) {
+        let (local_node_id, _) = test_node_id_and_signer(1);
+        let ledger = get_mock_ledger(1);
+        let peer2 =
+            MockPeerConnection::new(test_peer_uri(2), local_node_id.clone(), ledger.clone(), 0);
+        let peer3 =
+            MockPeerConnection::new(test_peer_uri(3), local_node_id.clone(), ledger.clone(), 0);
+
+        let peer_manager = ConnectionManager::new(vec![peer2.clone()], logger);
+        let responder_id2 = peer2.uri.responder_id().unwrap();
+        let responder_id3 = peer3.uri.responder_id().unwrap();
+        assert_eq!(peer_manager.len(), 1);
+
+        // Adding a new peer.
+        assert_eq!(peer_manager.add_conn(peer3.clone()), responder_id3);
+        assert_eq!(peer_manager.len(), 2);
+        assert_eq!(peer_manager.conn(&responder_id3).unwrap().uri(), peer3.uri);
+
+        // Re-adding a peer with a different URI replaces its connection.
+        let (_, new_signer) = test_node_id_and_signer(22);
+        let new_peer2 = MockPeerConnection::new(
+            test_peer_uri_with_key(2, &new_signer.public_key()),
+            local_node_id,
+            ledger,
+            0,
+        );
+        assert_eq!(peer_manager.add_conn(new_peer2.clone()), responder_id2);
+        assert_eq!(peer_manager.len(), 2);
+        assert_eq!(
+            peer_manager.conn(&responder_id2).unwrap().uri(),
+            new_peer2.uri
+        );
+
+        // Removing a peer.
+        assert!(peer_manager.remove_conn(&responder_id3).is_some());
+        assert!(peer_manager.conn(&responder_id3).is_none());
+        assert_eq!(peer_manager.len(), 1);
+        assert!(peer_manager.remove_conn(&responder_id3).is_none());
+    }
 }
 
 #[cfg(test)]
@@ -667,4 +730,82 @@ mod threaded_broadcaster_tests {
             assert_eq!(peer3.state().send_consensus_msg_call_count, 2);
         }
     }
+
+    #[test_with_logger]
+    // update_peers should start broadcasting to added peers, stop broadcasting
+    // to removed peers, and restart the peer thread of a peer whose URI changed.
+    fn test_update_peers(logger: Logger) {
+        let (local_node_id, _) = test_node_id_and_signer(1);
+        let node2_uri = test_peer_uri(2);
+        let node2 = NodeID::from(&node2_uri);
+        let node3_uri = test_peer_uri(3);
+        let node3 = NodeID::from(&node3_uri);
+        let node4_uri = test_peer_uri(4);
+
+        let quorum_set = QuorumSet::new_with_node_ids(2, vec![node2, node3]);
+        let ledger = get_mock_ledger(1);
+        let peer2 = MockPeerConnection::new(node2_uri, local_node_id.clone(), ledger.clone(), 0);
+        let peer3 = MockPeerConnection::new(node3_uri, local_node_id.clone(), ledger.clone(), 0);
+        let peer4 = MockPeerConnection::new(node4_uri, local_node_id.clone(), ledger.clone(), 0);
+
+        let peer_manager =
+            ConnectionManager::new(vec![peer2.clone(), peer3.clone()], logger.clone());
+
+        let mut broadcaster = ThreadedBroadcaster::new(
+            &peer_manager,
+            &FibonacciRetryPolicy::default(),
+            logger.clone(),
+        );
+
+        let mut seeded_rng: FixedRng = SeedableRng::from_seed([1u8; 32]);
+        let local_signer_key = Ed25519Pair::from_random(&mut seeded_rng);
+        let broadcast = |broadcaster: &mut ThreadedBroadcaster, msg: &str| {
+            let msg = create_consensus_msg(
+                &ledger,
+                local_node_id.clone(),
+                quorum_set.clone(),
+                1,
+                msg,
+                &local_signer_key,
+            );
+            broadcaster.broadcast_consensus_msg(&msg, msg.issuer_responder_id());
+            broadcaster.barrier();
+        };
+
+        // Remove peer3 and add peer4.
+        peer_manager.remove_conn(&peer3.uri.responder_id().unwrap());
+        peer_manager.add_conn(peer4.clone());
+        broadcaster.update_peers(&peer_manager);
+        broadcast(&mut broadcaster, "msg1");
+
+        assert_eq!(peer2.msgs().len(), 1);
+        assert!(peer3.msgs().is_empty());
+        assert_eq!(peer4.msgs().len(), 1);
+
+        // Replace peer2 with a connection to the same responder id, but with a
+        // different message signing key.
+        let (_, new_signer) = test_node_id_and_signer(22);
+        let new_peer2 = MockPeerConnection::new(
+            test_peer_uri_with_key(2, &new_signer.public_key()),
+            local_node_id.clone(),
+            ledger.clone(),
+            0,
+        );
+        peer_manager.add_conn(new_peer2.clone());
+        broadcaster.update_peers(&peer_manager);
+        broadcast(&mut broadcaster, "msg2");
+
+        assert_eq!(peer2.msgs().len(), 1);
+        assert_eq!(new_peer2.msgs().len(), 1);
+        assert_eq!(peer4.msgs().len(), 2);
+
+        // Updating without any changes keeps broadcasting to the same peers.
+        broadcaster.update_peers(&peer_manager);
+        broadcast(&mut broadcaster, "msg3");
+
+        assert_eq!(peer2.msgs().len(), 1);
+        assert_eq!(new_peer2.msgs().len(), 2);
+        assert!(peer3.msgs().is_empty());
+        assert_eq!(peer4.msgs().len(), 3);
+    }
 }
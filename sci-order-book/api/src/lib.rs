@@ -0,0 +1,36 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! SCI order book gRPC API.
+
+use mc_util_uri::{Uri, UriScheme};
+
+mod autogenerated_code {
+    // Expose proto data types from included third-party/external proto files.
+    pub use mc_api::external;
+    pub use protobuf::well_known_types::Empty;
+
+    // Needed due to how to the auto-generated code references the Empty message.
+    pub mod empty {
+        pub use protobuf::well_known_types::Empty;
+    }
+
+    // Include the auto-generated code.
+    include!(concat!(env!("OUT_DIR"), "/protos-auto-gen/mod.rs"));
+}
+
+pub use autogenerated_code::{sci_order_book::*, *};
+
+pub type SciOrderBookUri = Uri<SciOrderBookScheme>;
+
+/// SCI order book Uri Scheme
+#[derive(Debug, Hash, Ord, PartialOrd, Eq, PartialEq, Clone)]
+pub struct SciOrderBookScheme {}
+impl UriScheme for SciOrderBookScheme {
+    /// The part before the '://' of a URL.
+    const SCHEME_SECURE: &'static str = "sci-order-book";
+    const SCHEME_INSECURE: &'static str = "insecure-sci-order-book";
+
+    /// Default port numbers
+    const DEFAULT_SECURE_PORT: u16 = 7775;
+    const DEFAULT_INSECURE_PORT: u16 = 7776;
+}
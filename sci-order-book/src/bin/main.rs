@@ -0,0 +1,119 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! An order book of signed contingent inputs.
+
+use clap::Parser;
+use grpcio::{EnvBuilder, ServerBuilder};
+use mc_common::logger::{log, o};
+use mc_ledger_db::LedgerDB;
+use mc_sci_order_book::{
+    OrderBook, SciOrderBookService, DEFAULT_MAX_ORDERS, DEFAULT_MAX_ORDERS_PER_BOOK,
+    DEFAULT_SUBSCRIBER_BUFFER_SIZE,
+};
+use mc_sci_order_book_api::SciOrderBookUri;
+use mc_util_grpc::{AdminServer, BuildInfoService, ConnectionUriGrpcioServer, HealthService};
+use mc_util_parse::parse_duration_in_seconds;
+use mc_util_uri::AdminUri;
+use std::{path::PathBuf, sync::Arc, thread::sleep, time::Duration};
+
+/// Configuration for the SCI order book.
+#[derive(Clone, Parser)]
+#[clap(
+    name = "mc-sci-order-book",
+    about = "An order book of signed contingent inputs."
+)]
+pub struct Config {
+    /// Path to ledger db. Syncing this ledger should happen externally via
+    /// mobilecoind.
+    #[clap(long, parse(from_os_str), env = "MC_LEDGER_DB")]
+    ledger_db: PathBuf,
+
+    /// How many seconds to wait between polling the ledger.
+    #[clap(long, default_value = "1", parse(try_from_str = parse_duration_in_seconds), env = "MC_POLL_INTERVAL")]
+    poll_interval: Duration,
+
+    /// The maximum number of orders per token pair.
+    #[clap(long, default_value_t = DEFAULT_MAX_ORDERS_PER_BOOK, env = "MC_MAX_ORDERS_PER_BOOK")]
+    max_orders_per_book: usize,
+
+    /// The maximum number of orders across all token pairs.
+    #[clap(long, default_value_t = DEFAULT_MAX_ORDERS, env = "MC_MAX_ORDERS")]
+    max_orders: usize,
+
+    /// The number of order events buffered for each subscriber. Subscribers
+    /// falling further behind are disconnected.
+    #[clap(long, default_value_t = DEFAULT_SUBSCRIBER_BUFFER_SIZE, env = "MC_SUBSCRIBER_BUFFER_SIZE")]
+    subscriber_buffer_size: usize,
+
+    /// GRPC listen URI.
+    #[clap(long, env = "MC_LISTEN_URI")]
+    listen_uri: SciOrderBookUri,
+
+    /// Optional admin service listening URI.
+    #[clap(long, env = "MC_ADMIN_LISTEN_URI")]
+    admin_listen_uri: Option<AdminUri>,
+}
+
+fn main() {
+    mc_common::setup_panic_handler();
+    let _sentry_guard = mc_common::sentry::init();
+    let config = Config::parse();
+    let (logger, _global_logger_guard) = mc_common::logger::create_app_logger(o!());
+
+    let ledger_db = LedgerDB::open(&config.ledger_db).expect("Could not open ledger DB");
+    let order_book = Arc::new(
+        OrderBook::new(
+            ledger_db,
+            config.max_orders_per_book,
+            config.max_orders,
+            config.subscriber_buffer_size,
+            logger.clone(),
+        )
+        .expect("Could not create order book"),
+    );
+
+    // Create RPC services.
+    let build_info_service = BuildInfoService::new(logger.clone()).into_service();
+    let health_service = HealthService::new(None, logger.clone()).into_service();
+    let sci_order_book_service =
+        SciOrderBookService::new(order_book.clone(), logger.clone()).into_service();
+
+    // Package services into grpc server.
+    log::info!(logger, "Starting API service on {}", config.listen_uri);
+    let env = Arc::new(EnvBuilder::new().name_prefix("RPC".to_string()).build());
+
+    let server_builder = ServerBuilder::new(env)
+        .register_service(build_info_service)
+        .register_service(health_service)
+        .register_service(sci_order_book_service)
+        .bind_using_uri(&config.listen_uri, logger.clone());
+
+    let mut server = server_builder.build().unwrap();
+    server.start();
+
+    let _admin_server = config.admin_listen_uri.as_ref().map(|admin_listen_uri| {
+        let local_hostname = hostname::get()
+            .expect("failed getting local hostname")
+            .to_str()
+            .expect("failed getting hostname as str")
+            .to_string();
+
+        AdminServer::start(
+            None,
+            admin_listen_uri,
+            "SCI Order Book".to_owned(),
+            local_hostname,
+            None,
+            logger.clone(),
+        )
+        .expect("Failed starting admin grpc server")
+    });
+
+    // Evict orders as their key images appear in the ledger.
+    loop {
+        if let Err(err) = order_book.sync() {
+            log::error!(logger, "Failed syncing order book with the ledger: {}", err);
+        }
+        sleep(config.poll_interval);
+    }
+}
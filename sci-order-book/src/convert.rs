@@ -0,0 +1,59 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! Convert order book types to mc_sci_order_book_api types.
+
+use crate::{Order, OrderBookEvent, OrderRemovedReason};
+use mc_api::external;
+use mc_sci_order_book_api as api;
+
+/// Convert Order --> mc_sci_order_book_api::Order
+impl From<&Order> for api::Order {
+    fn from(src: &Order) -> Self {
+        let mut dst = api::Order::new();
+        dst.set_sci(external::SignedContingentInput::from(&src.sci));
+        dst.set_offered_token_id(*src.offered.token_id);
+        dst.set_offered_value(src.offered.value);
+        dst.set_requested_token_id(*src.requested.token_id);
+        dst.set_requested_value(src.requested.value);
+        dst.set_partial_fill(src.partial_fill);
+        dst.set_min_fill_value(src.min_fill_value);
+        dst.set_max_tombstone_block(src.max_tombstone_block);
+        dst
+    }
+}
+
+/// Convert OrderRemovedReason --> mc_sci_order_book_api::OrderRemovedReason
+impl From<OrderRemovedReason> for api::OrderRemovedReason {
+    fn from(src: OrderRemovedReason) -> Self {
+        match src {
+            OrderRemovedReason::Spent => Self::Spent,
+            OrderRemovedReason::Expired => Self::Expired,
+        }
+    }
+}
+
+/// Convert OrderBookEvent --> mc_sci_order_book_api::OrderEvent
+impl From<&OrderBookEvent> for api::OrderEvent {
+    fn from(src: &OrderBookEvent) -> Self {
+        let mut dst = api::OrderEvent::new();
+        match src {
+            OrderBookEvent::Added(order) => dst.set_added(order.into()),
+            OrderBookEvent::Removed {
+                key_image,
+                offered_token_id,
+                requested_token_id,
+                reason,
+                block_index,
+            } => {
+                let mut removed = api::OrderRemoved::new();
+                removed.set_key_image(key_image.into());
+                removed.set_offered_token_id(**offered_token_id);
+                removed.set_requested_token_id(**requested_token_id);
+                removed.set_reason((*reason).into());
+                removed.set_block_index(*block_index);
+                dst.set_removed(removed);
+            }
+        }
+        dst
+    }
+}
@@ -0,0 +1,74 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! SCI order book error data type.
+
+use displaydoc::Display;
+use mc_blockchain_types::BlockIndex;
+use mc_ledger_db::Error as LedgerDbError;
+use mc_transaction_core::{InputRuleError, SignedContingentInputError, TokenId};
+use mc_transaction_std::TxBuilderError;
+
+/// SCI order book error data type.
+#[derive(Debug, Display)]
+pub enum Error {
+    /// Invalid signed contingent input: {0}
+    InvalidSci(SignedContingentInputError),
+
+    /// The signed contingent input has no input rules
+    MissingRules,
+
+    /// The key image of the signed contingent input was spent in block {0}
+    KeyImageSpent(BlockIndex),
+
+    /// The max tombstone block {0} of the signed contingent input was reached
+    /// (ledger has {1} blocks)
+    Expired(BlockIndex, u64),
+
+    /// The signed contingent input does not trade one token for another
+    UnsupportedTokenPair,
+
+    /// The signed contingent input does not offer anything
+    NothingOffered,
+
+    /// An order with the same key image is already in the order book
+    DuplicateOrder,
+
+    /// The order book is full
+    OrderBookFull,
+
+    /// Not enough of token {0} to balance the transaction: missing {1}
+    InsufficientFunds(TokenId, u64),
+
+    /// Value overflow
+    ValueOverflow,
+
+    /// Ledger DB: {0}
+    LedgerDb(LedgerDbError),
+
+    /// Tx builder: {0}
+    TxBuilder(TxBuilderError),
+}
+
+impl From<SignedContingentInputError> for Error {
+    fn from(err: SignedContingentInputError) -> Self {
+        Self::InvalidSci(err)
+    }
+}
+
+impl From<InputRuleError> for Error {
+    fn from(err: InputRuleError) -> Self {
+        Self::InvalidSci(err.into())
+    }
+}
+
+impl From<LedgerDbError> for Error {
+    fn from(err: LedgerDbError) -> Self {
+        Self::LedgerDb(err)
+    }
+}
+
+impl From<TxBuilderError> for Error {
+    fn from(err: TxBuilderError) -> Self {
+        Self::TxBuilder(err)
+    }
+}
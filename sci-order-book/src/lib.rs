@@ -0,0 +1,27 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! An order book of signed contingent inputs (SCIs), and a helper for takers
+//! to fill the orders in it.
+
+#![deny(missing_docs)]
+
+mod convert;
+mod error;
+mod order;
+mod order_book;
+mod service;
+mod taker;
+
+#[cfg(test)]
+mod test_utils;
+
+pub use crate::{
+    error::Error,
+    order::Order,
+    order_book::{
+        OrderBook, OrderBookEvent, OrderFilter, OrderRemovedReason, DEFAULT_MAX_ORDERS,
+        DEFAULT_MAX_ORDERS_PER_BOOK, DEFAULT_SUBSCRIBER_BUFFER_SIZE,
+    },
+    service::SciOrderBookService,
+    taker::TakerTxBuilder,
+};
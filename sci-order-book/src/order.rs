@@ -0,0 +1,121 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! An order, backed by a signed contingent input.
+
+use crate::{order_book::PriceKey, Error};
+use mc_transaction_core::{ring_signature::KeyImage, Amount, SignedContingentInput};
+use std::{cmp::Ordering, convert::TryFrom};
+
+/// An order, backed by a signed contingent input (SCI).
+///
+/// The signer of the SCI offers some value of one token, in exchange for some
+/// value of another token. The offered and requested values are those of a
+/// full fill. Orders with partial fill rules can also be filled in part, in
+/// which case the signer gets a pro rata share of the partial fill outputs.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Order {
+    /// The signed contingent input.
+    pub sci: SignedContingentInput,
+
+    /// What the signer of the SCI offers.
+    pub offered: Amount,
+
+    /// What the signer of the SCI asks for in return for the entire offer.
+    pub requested: Amount,
+
+    /// Whether the order can be partially filled.
+    pub partial_fill: bool,
+
+    /// The minimum fill value, when the order can be partially filled.
+    pub min_fill_value: u64,
+
+    /// The max tombstone block of the SCI, or 0 if there is no limit.
+    pub max_tombstone_block: u64,
+}
+
+impl Order {
+    /// The key image of the SCI, which identifies the order.
+    pub fn key_image(&self) -> KeyImage {
+        self.sci.key_image()
+    }
+
+    /// Compare the prices of two orders, i.e. the requested value per offered
+    /// value. A lower price is better for the taker.
+    pub fn cmp_price(&self, other: &Order) -> Ordering {
+        PriceKey::from(self).cmp_price(&PriceKey::from(other))
+    }
+
+    /// Whether the order is expired when the ledger has `num_blocks` blocks,
+    /// i.e. when a transaction including it could no longer be added to the
+    /// next block.
+    pub fn is_expired(&self, num_blocks: u64) -> bool {
+        self.max_tombstone_block != 0 && num_blocks >= self.max_tombstone_block
+    }
+}
+
+/// Create an order from an SCI.
+///
+/// Note: This does not validate the SCI, see [SignedContingentInput::validate].
+impl TryFrom<SignedContingentInput> for Order {
+    type Error = Error;
+
+    fn try_from(sci: SignedContingentInput) -> Result<Self, Self::Error> {
+        let rules = sci.tx_in.input_rules.as_ref().ok_or(Error::MissingRules)?;
+
+        // Everything the signer gets back in the token of their input reduces
+        // the offer, and everything else is what they request.
+        let mut offered = Amount::from(&sci.pseudo_output_amount);
+        let mut requested: Option<Amount> = None;
+
+        let mut requirements: Vec<Amount> = sci
+            .required_output_amounts
+            .iter()
+            .map(Amount::from)
+            .collect();
+        for partial_fill_output in rules.partial_fill_outputs.iter() {
+            requirements.push(partial_fill_output.reveal_amount()?.0);
+        }
+
+        for amount in requirements {
+            if amount.token_id == offered.token_id {
+                offered.value = offered
+                    .value
+                    .checked_sub(amount.value)
+                    .ok_or(Error::NothingOffered)?;
+                continue;
+            }
+            match requested.as_mut() {
+                None => requested = Some(amount),
+                Some(requested) if requested.token_id == amount.token_id => {
+                    requested.value = requested
+                        .value
+                        .checked_add(amount.value)
+                        .ok_or(Error::ValueOverflow)?;
+                }
+                Some(_) => return Err(Error::UnsupportedTokenPair),
+            }
+        }
+
+        let requested = requested.ok_or(Error::UnsupportedTokenPair)?;
+        if offered.value == 0 {
+            return Err(Error::NothingOffered);
+        }
+
+        let partial_fill = rules.has_partial_fill_rules();
+        let min_fill_value = if partial_fill {
+            rules.min_partial_fill_value
+        } else {
+            0
+        };
+        let max_tombstone_block = rules.max_tombstone_block;
+
+        Ok(Self {
+            sci,
+            offered,
+            requested,
+            partial_fill,
+            min_fill_value,
+            max_tombstone_block,
+        })
+    }
+}
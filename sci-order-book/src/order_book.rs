@@ -0,0 +1,652 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! The order book keeps validated orders, indexed by token pair and price, and
+//! evicts them once their key image appears in the ledger or their max
+//! tombstone block is reached.
+//!
+//! The number of orders is capped, and subscribers that don't keep up with the
+//! events are dropped, so that clients can't make the order book grow without
+//! bound.
+
+use crate::{Error, Order};
+use futures::channel::mpsc::{channel, Receiver, Sender};
+use mc_blockchain_types::BlockIndex;
+use mc_common::logger::{log, Logger};
+use mc_ledger_db::Ledger;
+use mc_transaction_core::{ring_signature::KeyImage, SignedContingentInput, TokenId};
+use std::{
+    cmp::Ordering,
+    collections::{BTreeSet, HashMap},
+    convert::TryFrom,
+    sync::Mutex,
+};
+
+/// The default maximum number of orders per token pair.
+pub const DEFAULT_MAX_ORDERS_PER_BOOK: usize = 10_000;
+
+/// The default maximum number of orders across all token pairs.
+pub const DEFAULT_MAX_ORDERS: usize = 100_000;
+
+/// The default number of events buffered for each subscriber. Subscribers
+/// falling further behind are dropped.
+pub const DEFAULT_SUBSCRIBER_BUFFER_SIZE: usize = 1_000;
+
+/// Why an order was removed from the order book.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum OrderRemovedReason {
+    /// The key image of the SCI appeared in the ledger.
+    Spent,
+
+    /// The max tombstone block of the SCI was reached.
+    Expired,
+}
+
+/// An order book event.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum OrderBookEvent {
+    /// An order was added to the order book.
+    Added(Order),
+
+    /// An order was removed from the order book.
+    Removed {
+        /// The key image of the removed order.
+        key_image: KeyImage,
+        /// The token the removed order was offering.
+        offered_token_id: TokenId,
+        /// The token the removed order was requesting.
+        requested_token_id: TokenId,
+        /// Why the order was removed.
+        reason: OrderRemovedReason,
+        /// The block index at which the order was removed.
+        block_index: BlockIndex,
+    },
+}
+
+impl OrderBookEvent {
+    /// The (offered, requested) token pair of the order this event is about.
+    pub fn token_pair(&self) -> (TokenId, TokenId) {
+        match self {
+            Self::Added(order) => (order.offered.token_id, order.requested.token_id),
+            Self::Removed {
+                offered_token_id,
+                requested_token_id,
+                ..
+            } => (*offered_token_id, *requested_token_id),
+        }
+    }
+}
+
+/// Selects which order book events a subscriber hears about.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct OrderFilter {
+    /// Only orders offering this token, if set.
+    pub offered_token_id: Option<TokenId>,
+
+    /// Only orders requesting this token, if set.
+    pub requested_token_id: Option<TokenId>,
+}
+
+impl OrderFilter {
+    /// Whether an event passes this filter.
+    pub fn matches(&self, event: &OrderBookEvent) -> bool {
+        let (offered_token_id, requested_token_id) = event.token_pair();
+        self.offered_token_id
+            .map_or(true, |token_id| token_id == offered_token_id)
+            && self
+                .requested_token_id
+                .map_or(true, |token_id| token_id == requested_token_id)
+    }
+}
+
+/// Sort key of an order within its token pair: best price first, then by key
+/// image so that orders with the same price are kept apart.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) struct PriceKey {
+    offered_value: u64,
+    requested_value: u64,
+    key_image: KeyImage,
+}
+
+impl From<&Order> for PriceKey {
+    fn from(order: &Order) -> Self {
+        Self {
+            offered_value: order.offered.value,
+            requested_value: order.requested.value,
+            key_image: order.key_image(),
+        }
+    }
+}
+
+impl PriceKey {
+    /// Compare prices only, i.e. the requested value per offered value.
+    pub(crate) fn cmp_price(&self, other: &Self) -> Ordering {
+        // This can't overflow, because the product of two u64 fits in a u128.
+        ((self.requested_value as u128) * (other.offered_value as u128))
+            .cmp(&((other.requested_value as u128) * (self.offered_value as u128)))
+    }
+}
+
+impl Ord for PriceKey {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.cmp_price(other)
+            .then_with(|| self.key_image.cmp(&other.key_image))
+    }
+}
+
+impl PartialOrd for PriceKey {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[derive(Default)]
+struct OrderBookState {
+    /// Orders, by key image.
+    orders: HashMap<KeyImage, Order>,
+
+    /// Key images of orders, by (offered, requested) token pair, best price
+    /// first.
+    by_token_pair: HashMap<(TokenId, TokenId), BTreeSet<PriceKey>>,
+
+    /// Subscribers waiting to hear about order book events.
+    subscribers: Vec<(OrderFilter, Sender<OrderBookEvent>)>,
+
+    /// The number of ledger blocks that were processed.
+    num_blocks_processed: u64,
+}
+
+impl OrderBookState {
+    fn notify(&mut self, event: OrderBookEvent) {
+        // Drop subscribers that went away, and those whose buffer is full
+        // because they don't keep up.
+        self.subscribers.retain_mut(|(filter, sender)| {
+            !filter.matches(&event) || sender.try_send(event.clone()).is_ok()
+        });
+    }
+
+    fn insert(&mut self, order: Order) {
+        self.by_token_pair
+            .entry((order.offered.token_id, order.requested.token_id))
+            .or_default()
+            .insert(PriceKey::from(&order));
+        self.orders.insert(order.key_image(), order.clone());
+        self.notify(OrderBookEvent::Added(order));
+    }
+
+    fn remove(
+        &mut self,
+        key_image: &KeyImage,
+        reason: OrderRemovedReason,
+        block_index: BlockIndex,
+    ) -> Option<Order> {
+        let order = self.orders.remove(key_image)?;
+        let token_pair = (order.offered.token_id, order.requested.token_id);
+        if let Some(price_keys) = self.by_token_pair.get_mut(&token_pair) {
+            price_keys.remove(&PriceKey::from(&order));
+            if price_keys.is_empty() {
+                self.by_token_pair.remove(&token_pair);
+            }
+        }
+        self.notify(OrderBookEvent::Removed {
+            key_image: *key_image,
+            offered_token_id: token_pair.0,
+            requested_token_id: token_pair.1,
+            reason,
+            block_index,
+        });
+        Some(order)
+    }
+}
+
+/// An order book of signed contingent inputs.
+pub struct OrderBook<L: Ledger> {
+    /// The ledger orders are checked against.
+    ledger: L,
+
+    /// Orders and subscribers.
+    state: Mutex<OrderBookState>,
+
+    /// The maximum number of orders per token pair.
+    max_orders_per_book: usize,
+
+    /// The maximum number of orders across all token pairs.
+    max_orders: usize,
+
+    /// The number of events buffered for each subscriber.
+    subscriber_buffer_size: usize,
+
+    /// Logger.
+    logger: Logger,
+}
+
+impl<L: Ledger> OrderBook<L> {
+    /// Create an empty order book, which only watches for key images in
+    /// blocks added to the ledger from now on.
+    ///
+    /// # Arguments
+    /// * `ledger` - The ledger orders are checked against.
+    /// * `max_orders_per_book` - The maximum number of orders per token pair.
+    /// * `max_orders` - The maximum number of orders across all token pairs.
+    /// * `subscriber_buffer_size` - The number of events buffered for each
+    ///   subscriber. Subscribers falling further behind are dropped.
+    /// * `logger` - Logger.
+    pub fn new(
+        ledger: L,
+        max_orders_per_book: usize,
+        max_orders: usize,
+        subscriber_buffer_size: usize,
+        logger: Logger,
+    ) -> Result<Self, Error> {
+        let state = OrderBookState {
+            num_blocks_processed: ledger.num_blocks()?,
+            ..Default::default()
+        };
+        Ok(Self {
+            ledger,
+            state: Mutex::new(state),
+            max_orders_per_book,
+            max_orders,
+            subscriber_buffer_size,
+            logger,
+        })
+    }
+
+    /// Validate a signed contingent input and add it to the order book.
+    ///
+    /// The SCI must be well-formed, its key image must not be spent, its max
+    /// tombstone block must not be reached yet, and it must trade one token
+    /// for another. The order book must have room for it.
+    pub fn submit(&self, sci: SignedContingentInput) -> Result<Order, Error> {
+        sci.validate()?;
+        let order = Order::try_from(sci)?;
+
+        // Checking the ledger while holding the lock ensures that a key image
+        // that lands in the ledger is either found here, or seen by sync().
+        let mut state = self.state.lock().expect("mutex poisoned");
+        if state.orders.contains_key(&order.key_image()) {
+            return Err(Error::DuplicateOrder);
+        }
+        if let Some(block_index) = self.ledger.check_key_image(&order.key_image())? {
+            return Err(Error::KeyImageSpent(block_index));
+        }
+        let num_blocks = self.ledger.num_blocks()?;
+        if order.is_expired(num_blocks) {
+            return Err(Error::Expired(order.max_tombstone_block, num_blocks));
+        }
+        if state.orders.len() >= self.max_orders {
+            return Err(Error::OrderBookFull);
+        }
+        let num_orders_in_book = state
+            .by_token_pair
+            .get(&(order.offered.token_id, order.requested.token_id))
+            .map_or(0, BTreeSet::len);
+        if num_orders_in_book >= self.max_orders_per_book {
+            return Err(Error::OrderBookFull);
+        }
+
+        state.insert(order.clone());
+        Ok(order)
+    }
+
+    /// Get an order by the key image of its SCI.
+    pub fn get_order(&self, key_image: &KeyImage) -> Option<Order> {
+        self.state
+            .lock()
+            .expect("mutex poisoned")
+            .orders
+            .get(key_image)
+            .cloned()
+    }
+
+    /// Get the orders offering one token in exchange for another, best price
+    /// first.
+    ///
+    /// # Arguments
+    /// * `offered_token_id` - The token being offered.
+    /// * `requested_token_id` - The token being requested in return.
+    /// * `limit` - The maximum number of orders to return, or 0 for all.
+    pub fn get_orders(
+        &self,
+        offered_token_id: TokenId,
+        requested_token_id: TokenId,
+        limit: usize,
+    ) -> Vec<Order> {
+        let state = self.state.lock().expect("mutex poisoned");
+        let limit = if limit == 0 { usize::MAX } else { limit };
+        state
+            .by_token_pair
+            .get(&(offered_token_id, requested_token_id))
+            .map(|price_keys| {
+                price_keys
+                    .iter()
+                    .take(limit)
+                    .filter_map(|price_key| state.orders.get(&price_key.key_image).cloned())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// The number of orders in the order book.
+    pub fn len(&self) -> usize {
+        self.state.lock().expect("mutex poisoned").orders.len()
+    }
+
+    /// Whether the order book is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Subscribe to order book events passing a filter.
+    ///
+    /// The stream ends if the subscriber falls too far behind, see
+    /// [OrderBook::new].
+    pub fn subscribe(&self, filter: OrderFilter) -> Receiver<OrderBookEvent> {
+        let (sender, receiver) = channel(self.subscriber_buffer_size);
+        self.state
+            .lock()
+            .expect("mutex poisoned")
+            .subscribers
+            .push((filter, sender));
+        receiver
+    }
+
+    /// Process the blocks added to the ledger since the last call, evicting
+    /// orders whose key image was spent or whose max tombstone block was
+    /// reached.
+    ///
+    /// Returns the number of evicted orders.
+    pub fn sync(&self) -> Result<usize, Error> {
+        let mut state = self.state.lock().expect("mutex poisoned");
+        let num_blocks = self.ledger.num_blocks()?;
+        let mut num_evicted = 0;
+
+        while state.num_blocks_processed < num_blocks {
+            let block_index = state.num_blocks_processed;
+            let block_contents = self.ledger.get_block_contents(block_index)?;
+
+            for key_image in block_contents.key_images.iter() {
+                if state
+                    .remove(key_image, OrderRemovedReason::Spent, block_index)
+                    .is_some()
+                {
+                    num_evicted += 1;
+                }
+            }
+
+            let expired: Vec<KeyImage> = state
+                .orders
+                .values()
+                .filter(|order| order.is_expired(block_index + 1))
+                .map(Order::key_image)
+                .collect();
+            for key_image in expired.iter() {
+                state.remove(key_image, OrderRemovedReason::Expired, block_index);
+                num_evicted += 1;
+            }
+
+            state.num_blocks_processed += 1;
+        }
+
+        if num_evicted > 0 {
+            log::debug!(
+                self.logger,
+                "Evicted {} orders, {} remaining",
+                num_evicted,
+                state.orders.len()
+            );
+        }
+        Ok(num_evicted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{append_key_images, create_sci};
+    use futures::executor::block_on_stream;
+    use mc_common::logger::{test_with_logger, Logger};
+    use mc_ledger_db::test_utils::get_mock_ledger;
+    use mc_transaction_core::{tokens::Mob, Amount, Token};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test_with_logger]
+    fn orders_are_sorted_by_price(logger: Logger) {
+        let mut rng: StdRng = SeedableRng::from_seed([1u8; 32]);
+        let ledger = get_mock_ledger(2);
+        let order_book = OrderBook::new(
+            ledger,
+            DEFAULT_MAX_ORDERS_PER_BOOK,
+            DEFAULT_MAX_ORDERS,
+            DEFAULT_SUBSCRIBER_BUFFER_SIZE,
+            logger,
+        )
+        .unwrap();
+        let token2 = TokenId::from(2);
+
+        // 1000 MOB for 300, 200 and 400 of token 2
+        for requested_value in [300, 200, 400] {
+            let sci = create_sci(
+                Amount::new(1000, Mob::ID),
+                Amount::new(requested_value, token2),
+                u64::MAX,
+                &mut rng,
+            );
+            order_book.submit(sci).unwrap();
+        }
+        // 1000 of token 2 for 500 MOB, which is a different token pair
+        let sci = create_sci(
+            Amount::new(1000, token2),
+            Amount::new(500, Mob::ID),
+            u64::MAX,
+            &mut rng,
+        );
+        order_book.submit(sci.clone()).unwrap();
+        assert_eq!(order_book.len(), 4);
+
+        let orders = order_book.get_orders(Mob::ID, token2, 0);
+        let requested_values: Vec<u64> = orders.iter().map(|order| order.requested.value).collect();
+        assert_eq!(requested_values, vec![200, 300, 400]);
+        assert_eq!(order_book.get_orders(Mob::ID, token2, 2).len(), 2);
+
+        let orders = order_book.get_orders(token2, Mob::ID, 0);
+        assert_eq!(orders.len(), 1);
+        assert_eq!(orders[0].offered, Amount::new(1000, token2));
+        assert_eq!(orders[0].requested, Amount::new(500, Mob::ID));
+
+        // Submitting the same SCI twice doesn't work
+        assert!(matches!(order_book.submit(sci), Err(Error::DuplicateOrder)));
+    }
+
+    #[test_with_logger]
+    fn submit_rejects_invalid_scis(logger: Logger) {
+        let mut rng: StdRng = SeedableRng::from_seed([2u8; 32]);
+        let mut ledger = get_mock_ledger(2);
+        let order_book = OrderBook::new(
+            ledger.clone(),
+            DEFAULT_MAX_ORDERS_PER_BOOK,
+            DEFAULT_MAX_ORDERS,
+            DEFAULT_SUBSCRIBER_BUFFER_SIZE,
+            logger,
+        )
+        .unwrap();
+        let token2 = TokenId::from(2);
+
+        // A tampered SCI doesn't validate
+        let mut sci = create_sci(
+            Amount::new(1000, Mob::ID),
+            Amount::new(300, token2),
+            u64::MAX,
+            &mut rng,
+        );
+        sci.pseudo_output_amount.value += 1;
+        assert!(matches!(order_book.submit(sci), Err(Error::InvalidSci(_))));
+
+        // An SCI whose key image was spent is rejected
+        let sci = create_sci(
+            Amount::new(1000, Mob::ID),
+            Amount::new(300, token2),
+            u64::MAX,
+            &mut rng,
+        );
+        append_key_images(&mut ledger, vec![sci.key_image()]);
+        assert!(matches!(
+            order_book.submit(sci),
+            Err(Error::KeyImageSpent(2))
+        ));
+
+        // An SCI whose max tombstone block was reached is rejected
+        let sci = create_sci(
+            Amount::new(1000, Mob::ID),
+            Amount::new(300, token2),
+            3,
+            &mut rng,
+        );
+        assert!(matches!(order_book.submit(sci), Err(Error::Expired(3, 3))));
+
+        // An SCI asking for the token it offers is rejected
+        let sci = create_sci(
+            Amount::new(1000, Mob::ID),
+            Amount::new(300, Mob::ID),
+            u64::MAX,
+            &mut rng,
+        );
+        assert!(matches!(
+            order_book.submit(sci),
+            Err(Error::UnsupportedTokenPair)
+        ));
+
+        assert!(order_book.is_empty());
+    }
+
+    #[test_with_logger]
+    fn order_book_limits(logger: Logger) {
+        let mut rng: StdRng = SeedableRng::from_seed([4u8; 32]);
+        let ledger = get_mock_ledger(2);
+        // At most 2 orders per token pair, 3 in total, and 1 buffered event per
+        // subscriber (plus one slot for its sender).
+        let order_book = OrderBook::new(ledger, 2, 3, 1, logger).unwrap();
+        let token2 = TokenId::from(2);
+        let token3 = TokenId::from(3);
+
+        let slow_subscriber = order_book.subscribe(OrderFilter::default());
+
+        for requested_value in [300, 200] {
+            let sci = create_sci(
+                Amount::new(1000, Mob::ID),
+                Amount::new(requested_value, token2),
+                u64::MAX,
+                &mut rng,
+            );
+            order_book.submit(sci).unwrap();
+        }
+
+        // The MOB for token 2 book is full
+        let sci = create_sci(
+            Amount::new(1000, Mob::ID),
+            Amount::new(100, token2),
+            u64::MAX,
+            &mut rng,
+        );
+        assert!(matches!(order_book.submit(sci), Err(Error::OrderBookFull)));
+
+        // Other books aren't, until the total is reached
+        let sci = create_sci(
+            Amount::new(1000, token2),
+            Amount::new(500, Mob::ID),
+            u64::MAX,
+            &mut rng,
+        );
+        order_book.submit(sci).unwrap();
+        let sci = create_sci(
+            Amount::new(1000, token3),
+            Amount::new(500, Mob::ID),
+            u64::MAX,
+            &mut rng,
+        );
+        assert!(matches!(order_book.submit(sci), Err(Error::OrderBookFull)));
+        assert_eq!(order_book.len(), 3);
+
+        // The subscriber didn't read its events, so it was dropped once its buffer
+        // was full, which ends its stream.
+        let events: Vec<OrderBookEvent> = block_on_stream(slow_subscriber).collect();
+        assert_eq!(events.len(), 2);
+    }
+
+    #[test_with_logger]
+    fn sync_evicts_spent_and_expired_orders(logger: Logger) {
+        let mut rng: StdRng = SeedableRng::from_seed([3u8; 32]);
+        let mut ledger = get_mock_ledger(2);
+        let order_book = OrderBook::new(
+            ledger.clone(),
+            DEFAULT_MAX_ORDERS_PER_BOOK,
+            DEFAULT_MAX_ORDERS,
+            DEFAULT_SUBSCRIBER_BUFFER_SIZE,
+            logger,
+        )
+        .unwrap();
+        let token2 = TokenId::from(2);
+
+        let spent_sci = create_sci(
+            Amount::new(1000, Mob::ID),
+            Amount::new(300, token2),
+            u64::MAX,
+            &mut rng,
+        );
+        let expiring_sci = create_sci(
+            Amount::new(1000, Mob::ID),
+            Amount::new(200, token2),
+            4,
+            &mut rng,
+        );
+        let remaining_sci = create_sci(
+            Amount::new(1000, Mob::ID),
+            Amount::new(400, token2),
+            u64::MAX,
+            &mut rng,
+        );
+        for sci in [&spent_sci, &expiring_sci, &remaining_sci] {
+            order_book.submit(sci.clone()).unwrap();
+        }
+
+        let events = order_book.subscribe(OrderFilter {
+            offered_token_id: Some(Mob::ID),
+            requested_token_id: None,
+        });
+
+        // Block 2 spends the first SCI
+        append_key_images(&mut ledger, vec![spent_sci.key_image()]);
+        assert_eq!(order_book.sync().unwrap(), 1);
+        assert!(order_book.get_order(&spent_sci.key_image()).is_none());
+
+        // Block 3 is the last block the second SCI can be included in
+        append_key_images(&mut ledger, vec![]);
+        assert_eq!(order_book.sync().unwrap(), 1);
+        assert!(order_book.get_order(&expiring_sci.key_image()).is_none());
+
+        let orders = order_book.get_orders(Mob::ID, token2, 0);
+        assert_eq!(orders.len(), 1);
+        assert_eq!(orders[0].sci, remaining_sci);
+
+        drop(order_book);
+        let events: Vec<OrderBookEvent> = block_on_stream(events).collect();
+        assert_eq!(
+            events,
+            vec![
+                OrderBookEvent::Removed {
+                    key_image: spent_sci.key_image(),
+                    offered_token_id: Mob::ID,
+                    requested_token_id: token2,
+                    reason: OrderRemovedReason::Spent,
+                    block_index: 2,
+                },
+                OrderBookEvent::Removed {
+                    key_image: expiring_sci.key_image(),
+                    offered_token_id: Mob::ID,
+                    requested_token_id: token2,
+                    reason: OrderRemovedReason::Expired,
+                    block_index: 3,
+                },
+            ]
+        );
+    }
+}
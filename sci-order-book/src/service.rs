@@ -0,0 +1,142 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! SCI order book GRPC service implementation.
+
+use crate::{Error, OrderBook, OrderFilter};
+use futures::{FutureExt, SinkExt, StreamExt, TryFutureExt};
+use grpcio::{
+    RpcContext, RpcStatus, RpcStatusCode, ServerStreamingSink, Service, UnarySink, WriteFlags,
+};
+use mc_common::logger::{log, Logger};
+use mc_ledger_db::Ledger;
+use mc_sci_order_book_api::{
+    sci_order_book_grpc::{create_sci_order_book_api, SciOrderBookApi},
+    GetOrdersRequest, GetOrdersResponse, Order as GrpcOrder, OrderEvent, SubmitOrderRequest,
+    SubmitOrderResponse, SubscribeOrdersRequest,
+};
+use mc_transaction_core::{SignedContingentInput, TokenId};
+use mc_util_grpc::{rpc_logger, send_result};
+use std::{convert::TryFrom, sync::Arc};
+
+/// SCI order book GRPC service implementation.
+pub struct SciOrderBookService<L: Ledger + Sync + 'static> {
+    /// The order book.
+    order_book: Arc<OrderBook<L>>,
+
+    /// Logger.
+    logger: Logger,
+}
+
+impl<L: Ledger + Sync + 'static> Clone for SciOrderBookService<L> {
+    fn clone(&self) -> Self {
+        Self {
+            order_book: self.order_book.clone(),
+            logger: self.logger.clone(),
+        }
+    }
+}
+
+impl<L: Ledger + Sync + 'static> SciOrderBookService<L> {
+    /// Create a new SCI order book service.
+    pub fn new(order_book: Arc<OrderBook<L>>, logger: Logger) -> Self {
+        Self { order_book, logger }
+    }
+
+    /// Convert into a grpc service
+    pub fn into_service(self) -> Service {
+        create_sci_order_book_api(self)
+    }
+
+    fn submit_order_impl(
+        &self,
+        req: &SubmitOrderRequest,
+    ) -> Result<SubmitOrderResponse, RpcStatus> {
+        let sci = SignedContingentInput::try_from(req.get_sci()).map_err(|err| {
+            RpcStatus::with_message(RpcStatusCode::INVALID_ARGUMENT, err.to_string())
+        })?;
+
+        let order = self.order_book.submit(sci).map_err(|err| {
+            let code = match err {
+                Error::LedgerDb(_) => RpcStatusCode::INTERNAL,
+                Error::DuplicateOrder => RpcStatusCode::ALREADY_EXISTS,
+                Error::OrderBookFull => RpcStatusCode::RESOURCE_EXHAUSTED,
+                Error::KeyImageSpent(_) | Error::Expired(_, _) => {
+                    RpcStatusCode::FAILED_PRECONDITION
+                }
+                _ => RpcStatusCode::INVALID_ARGUMENT,
+            };
+            RpcStatus::with_message(code, err.to_string())
+        })?;
+
+        let mut resp = SubmitOrderResponse::new();
+        resp.set_order(GrpcOrder::from(&order));
+        Ok(resp)
+    }
+
+    fn get_orders_impl(&self, req: &GetOrdersRequest) -> Result<GetOrdersResponse, RpcStatus> {
+        let orders = self.order_book.get_orders(
+            TokenId::from(req.get_offered_token_id()),
+            TokenId::from(req.get_requested_token_id()),
+            req.get_limit() as usize,
+        );
+
+        let mut resp = GetOrdersResponse::new();
+        resp.set_orders(orders.iter().map(GrpcOrder::from).collect());
+        Ok(resp)
+    }
+}
+
+impl<L: Ledger + Sync + 'static> SciOrderBookApi for SciOrderBookService<L> {
+    fn submit_order(
+        &mut self,
+        ctx: RpcContext,
+        req: SubmitOrderRequest,
+        sink: UnarySink<SubmitOrderResponse>,
+    ) {
+        let logger = rpc_logger(&ctx, &self.logger);
+        send_result(ctx, sink, self.submit_order_impl(&req), &logger)
+    }
+
+    fn get_orders(
+        &mut self,
+        ctx: RpcContext,
+        req: GetOrdersRequest,
+        sink: UnarySink<GetOrdersResponse>,
+    ) {
+        let logger = rpc_logger(&ctx, &self.logger);
+        send_result(ctx, sink, self.get_orders_impl(&req), &logger)
+    }
+
+    fn subscribe_orders(
+        &mut self,
+        ctx: RpcContext,
+        req: SubscribeOrdersRequest,
+        mut sink: ServerStreamingSink<OrderEvent>,
+    ) {
+        let logger = rpc_logger(&ctx, &self.logger);
+
+        let filter = OrderFilter {
+            offered_token_id: req
+                .get_filter_by_offered_token_id()
+                .then(|| TokenId::from(req.get_offered_token_id())),
+            requested_token_id: req
+                .get_filter_by_requested_token_id()
+                .then(|| TokenId::from(req.get_requested_token_id())),
+        };
+
+        let mut events = self
+            .order_book
+            .subscribe(filter)
+            .map(|event| Ok((OrderEvent::from(&event), WriteFlags::default())));
+        ctx.spawn(
+            async move {
+                sink.send_all(&mut events).await?;
+                sink.close().await
+            }
+            .map_err(move |err: grpcio::Error| {
+                log::debug!(logger, "failed streaming order events: {}", err)
+            })
+            .map(|_| ()),
+        );
+    }
+}
@@ -0,0 +1,286 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! Helper for takers, building a transaction which fills one or more orders.
+
+use crate::Error;
+use mc_crypto_ring_signature_signer::RingSigner;
+use mc_fog_report_validation::FogPubkeyResolver;
+use mc_ledger_db::Ledger;
+use mc_transaction_core::{
+    tx::Tx, Amount, BlockVersion, InputRuleError, SignedContingentInput,
+    SignedContingentInputError, TokenId,
+};
+use mc_transaction_std::{InputCredentials, MemoBuilder, ReservedSubaddresses, TransactionBuilder};
+use rand_core::{CryptoRng, RngCore};
+use std::collections::BTreeMap;
+
+/// Builds a transaction which fills one or more orders, using the taker's own
+/// inputs to pay for them and for the fee.
+///
+/// Whatever is left over, including what the taker gets from the orders, is
+/// sent back to the taker as change.
+pub struct TakerTxBuilder<FPR: FogPubkeyResolver> {
+    /// The underlying transaction builder.
+    builder: TransactionBuilder<FPR>,
+
+    /// Value going into the transaction, by token id.
+    inputs: BTreeMap<TokenId, u128>,
+
+    /// Value going out of the transaction, by token id.
+    outputs: BTreeMap<TokenId, u128>,
+}
+
+impl<FPR: FogPubkeyResolver> TakerTxBuilder<FPR> {
+    /// Create a new taker transaction builder.
+    ///
+    /// # Arguments
+    /// * `block_version` - The block version to use.
+    /// * `fee` - The fee to pay.
+    /// * `fog_resolver` - Source of validated fog keys for the change outputs.
+    /// * `memo_builder` - Builds the memos of the change outputs. It must allow
+    ///   one change output for each token involved.
+    pub fn new<MB: MemoBuilder + 'static + Send + Sync>(
+        block_version: BlockVersion,
+        fee: Amount,
+        fog_resolver: FPR,
+        memo_builder: MB,
+    ) -> Result<Self, Error> {
+        let builder = TransactionBuilder::new(block_version, fee, fog_resolver, memo_builder)?;
+        let mut taker_tx_builder = Self {
+            builder,
+            inputs: Default::default(),
+            outputs: Default::default(),
+        };
+        taker_tx_builder.add_outflow(fee);
+        Ok(taker_tx_builder)
+    }
+
+    /// Fill an order.
+    ///
+    /// Proofs of membership for the ring of the SCI are taken from the ledger.
+    ///
+    /// # Arguments
+    /// * `ledger` - The ledger to get proofs of membership from.
+    /// * `sci` - The SCI backing the order.
+    /// * `fill_value` - How much of the partial fill change to take, for orders
+    ///   with partial fill rules. None fills the order entirely.
+    pub fn fill_order<L: Ledger>(
+        &mut self,
+        ledger: &L,
+        mut sci: SignedContingentInput,
+        fill_value: Option<u64>,
+    ) -> Result<(), Error> {
+        sci.tx_in.proofs = ledger.get_tx_out_proof_of_memberships(&sci.tx_out_global_indices)?;
+
+        let rules = sci.tx_in.input_rules.clone().ok_or(Error::MissingRules)?;
+
+        let pseudo_output_amount = Amount::from(&sci.pseudo_output_amount);
+        let required_output_amounts: Vec<Amount> = sci
+            .required_output_amounts
+            .iter()
+            .map(Amount::from)
+            .collect();
+
+        match fill_value {
+            Some(fill_value) => self
+                .builder
+                .add_presigned_partial_fill_input(sci, fill_value)?,
+            None => self.builder.add_presigned_input(sci)?,
+        };
+
+        self.add_inflow(pseudo_output_amount);
+        for amount in required_output_amounts {
+            self.add_outflow(amount);
+        }
+
+        if let Some(partial_fill_change) = rules.partial_fill_change.as_ref() {
+            let (change_amount, _) = partial_fill_change.reveal_amount()?;
+            if change_amount.value == 0 {
+                return Err(InputRuleError::ZeroPartialFillChange.into());
+            }
+            let fill_value = fill_value.unwrap_or(change_amount.value);
+            let fractional_change_value = change_amount
+                .value
+                .checked_sub(fill_value)
+                .ok_or(SignedContingentInputError::FillValueExceedsPartialFillChange)?;
+            self.add_outflow(Amount::new(fractional_change_value, change_amount.token_id));
+
+            for partial_fill_output in rules.partial_fill_outputs.iter() {
+                self.add_outflow(
+                    partial_fill_output.min_fractional_amount(fill_value, change_amount.value)?,
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Add one of the taker's own inputs.
+    pub fn add_input(&mut self, input_credentials: InputCredentials) {
+        self.add_inflow(input_credentials.input_secret.amount);
+        self.builder.add_input(input_credentials);
+    }
+
+    /// Consume the builder and return the transaction.
+    ///
+    /// # Arguments
+    /// * `change_destination` - The taker's addresses, which receive what is
+    ///   left over.
+    /// * `tombstone_block` - The tombstone block, which is lowered to the
+    ///   smallest max tombstone block of the filled orders.
+    /// * `ring_signer` - Signs the taker's inputs.
+    /// * `rng` - Randomness.
+    pub fn build<RNG: CryptoRng + RngCore, S: RingSigner + ?Sized>(
+        mut self,
+        change_destination: &ReservedSubaddresses,
+        tombstone_block: u64,
+        ring_signer: &S,
+        rng: &mut RNG,
+    ) -> Result<Tx, Error> {
+        for (token_id, output_value) in self.outputs.iter() {
+            let input_value = self.inputs.get(token_id).copied().unwrap_or_default();
+            if *output_value > input_value {
+                let missing = u64::try_from(output_value - input_value).unwrap_or(u64::MAX);
+                return Err(Error::InsufficientFunds(*token_id, missing));
+            }
+        }
+
+        for (token_id, input_value) in self.inputs.iter() {
+            let output_value = self.outputs.get(token_id).copied().unwrap_or_default();
+            if *input_value > output_value {
+                let change_value =
+                    u64::try_from(input_value - output_value).map_err(|_| Error::ValueOverflow)?;
+                self.builder.add_change_output(
+                    Amount::new(change_value, *token_id),
+                    change_destination,
+                    rng,
+                )?;
+            }
+        }
+
+        self.builder.set_tombstone_block(tombstone_block);
+        Ok(self.builder.build(ring_signer, rng)?)
+    }
+
+    fn add_inflow(&mut self, amount: Amount) {
+        *self.inputs.entry(amount.token_id).or_default() += amount.value as u128;
+    }
+
+    fn add_outflow(&mut self, amount: Amount) {
+        *self.outputs.entry(amount.token_id).or_default() += amount.value as u128;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::create_sci;
+    use mc_account_keys::AccountKey;
+    use mc_crypto_ring_signature_signer::NoKeysRingSigner;
+    use mc_fog_report_validation_test_utils::MockFogResolver;
+    use mc_ledger_db::test_utils::get_mock_ledger;
+    use mc_transaction_core::{
+        tokens::Mob,
+        tx::TxOutMembershipProof,
+        validation::{validate_all_input_rules, validate_signature},
+        Token,
+    };
+    use mc_transaction_std::{test_utils::get_input_credentials, EmptyMemoBuilder};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn take_order() {
+        let mut rng: StdRng = SeedableRng::from_seed([1u8; 32]);
+        let block_version = BlockVersion::MAX;
+        let ledger = get_mock_ledger(2);
+        let bob = AccountKey::random(&mut rng);
+        let fog_resolver = MockFogResolver(Default::default());
+        let token2 = TokenId::from(2);
+
+        // The SCI ring members are given global index 0 by the test helpers.
+        ledger
+            .lock()
+            .membership_proofs
+            .insert(0, TxOutMembershipProof::default());
+
+        // Alice offers 10 fees worth of MOB for 300 of token 2
+        let offered_value = 10 * Mob::MINIMUM_FEE;
+        let sci = create_sci(
+            Amount::new(offered_value, Mob::ID),
+            Amount::new(300, token2),
+            u64::MAX,
+            &mut rng,
+        );
+
+        let new_builder = || {
+            TakerTxBuilder::new(
+                block_version,
+                Amount::new(Mob::MINIMUM_FEE, Mob::ID),
+                fog_resolver.clone(),
+                EmptyMemoBuilder::default(),
+            )
+            .unwrap()
+        };
+
+        // Bob doesn't have enough of token 2
+        let mut builder = new_builder();
+        builder.fill_order(&ledger, sci.clone(), None).unwrap();
+        builder.add_input(get_input_credentials(
+            block_version,
+            Amount::new(299, token2),
+            &bob,
+            &fog_resolver,
+            &mut rng,
+        ));
+        assert!(matches!(
+            builder.build(
+                &ReservedSubaddresses::from(&bob),
+                10,
+                &NoKeysRingSigner {},
+                &mut rng
+            ),
+            Err(Error::InsufficientFunds(token_id, 1)) if token_id == token2
+        ));
+
+        // Bob has 500 of token 2, and pays the fee out of what he gets from Alice
+        let mut builder = new_builder();
+        builder.fill_order(&ledger, sci, None).unwrap();
+        builder.add_input(get_input_credentials(
+            block_version,
+            Amount::new(500, token2),
+            &bob,
+            &fog_resolver,
+            &mut rng,
+        ));
+        let tx = builder
+            .build(
+                &ReservedSubaddresses::from(&bob),
+                10,
+                &NoKeysRingSigner {},
+                &mut rng,
+            )
+            .unwrap();
+
+        validate_signature(block_version, &tx, &mut rng).unwrap();
+        validate_all_input_rules(block_version, &tx).unwrap();
+        assert_eq!(tx.prefix.tombstone_block, 10);
+
+        // Alice's required output, and Bob's change in MOB and token 2
+        assert_eq!(tx.prefix.outputs.len(), 3);
+        let mut bob_amounts: Vec<Amount> = tx
+            .prefix
+            .outputs
+            .iter()
+            .filter_map(|tx_out| tx_out.view_key_match(bob.view_private_key()).ok())
+            .map(|(amount, _)| amount)
+            .collect();
+        bob_amounts.sort_by_key(|amount| amount.token_id);
+        assert_eq!(
+            bob_amounts,
+            vec![
+                Amount::new(offered_value - Mob::MINIMUM_FEE, Mob::ID),
+                Amount::new(200, token2),
+            ]
+        );
+    }
+}
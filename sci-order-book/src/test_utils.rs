@@ -0,0 +1,58 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! Helpers for order book tests.
+
+use mc_account_keys::AccountKey;
+use mc_blockchain_types::{Block, BlockContents};
+use mc_crypto_ring_signature_signer::NoKeysRingSigner;
+use mc_fog_report_validation_test_utils::MockFogResolver;
+use mc_ledger_db::{test_utils::MockLedger, Ledger};
+use mc_transaction_core::{ring_signature::KeyImage, Amount, BlockVersion, SignedContingentInput};
+use mc_transaction_std::{
+    test_utils::get_input_credentials, EmptyMemoBuilder, SignedContingentInputBuilder,
+};
+use rand_core::{CryptoRng, RngCore};
+
+/// Create an SCI offering `offered` in exchange for `requested`, which can be
+/// used until `max_tombstone_block` (or forever, if it is u64::MAX).
+pub fn create_sci<RNG: CryptoRng + RngCore>(
+    offered: Amount,
+    requested: Amount,
+    max_tombstone_block: u64,
+    rng: &mut RNG,
+) -> SignedContingentInput {
+    let alice = AccountKey::random(rng);
+    let fog_resolver = MockFogResolver(Default::default());
+    let input_credentials =
+        get_input_credentials(BlockVersion::MAX, offered, &alice, &fog_resolver, rng);
+
+    let mut builder = SignedContingentInputBuilder::new(
+        BlockVersion::MAX,
+        input_credentials,
+        fog_resolver,
+        EmptyMemoBuilder::default(),
+    )
+    .unwrap();
+    builder
+        .add_required_output(requested, &alice.default_subaddress(), rng)
+        .unwrap();
+    builder.set_tombstone_block(max_tombstone_block);
+    builder.build(&NoKeysRingSigner {}, rng).unwrap()
+}
+
+/// Append a block spending the given key images to the ledger.
+pub fn append_key_images(ledger: &mut MockLedger, key_images: Vec<KeyImage>) {
+    let num_blocks = ledger.num_blocks().unwrap();
+    let parent = ledger.get_block(num_blocks - 1).unwrap();
+    let block_contents = BlockContents {
+        key_images,
+        ..Default::default()
+    };
+    let block = Block::new_with_parent(
+        BlockVersion::MAX,
+        &parent,
+        &Default::default(),
+        &block_contents,
+    );
+    ledger.append_block(&block, &block_contents, None).unwrap();
+}
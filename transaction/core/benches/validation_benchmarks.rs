@@ -0,0 +1,103 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use mc_ledger_db::Ledger;
+use mc_transaction_core::{
+    tokens::Mob,
+    tx::{Tx, TxOutMembershipProof},
+    validation::{validate, validate_batch, TxValidationBatchItem},
+    BlockVersion, Token,
+};
+use mc_transaction_core_test_utils::{
+    create_ledger, create_transaction, initialize_ledger, AccountKey,
+};
+use rand::{rngs::StdRng, SeedableRng};
+
+/// The largest number of transactions validated at once.
+const MAX_NUM_TXS: usize = 64;
+
+fn validation_benchmarks(c: &mut Criterion) {
+    let mut rng: StdRng = SeedableRng::from_seed([100u8; 32]);
+    let block_version = BlockVersion::MAX;
+
+    // Spend the first output of each block of the ledger.
+    let sender = AccountKey::random(&mut rng);
+    let recipient = AccountKey::random(&mut rng);
+    let mut ledger = create_ledger();
+    let n_blocks = MAX_NUM_TXS as u64;
+    initialize_ledger(block_version, &mut ledger, n_blocks, &sender, &mut rng);
+
+    let txs_and_proofs: Vec<(Tx, Vec<TxOutMembershipProof>)> = (0..n_blocks)
+        .map(|block_index| {
+            let block_contents = ledger.get_block_contents(block_index).unwrap();
+            let tx = create_transaction(
+                block_version,
+                &mut ledger,
+                &block_contents.outputs[0],
+                &sender,
+                &recipient.default_subaddress(),
+                n_blocks + 1,
+                &mut rng,
+            );
+            let root_proofs = ledger
+                .get_tx_out_proof_of_memberships(&tx.get_membership_proof_highest_indices())
+                .unwrap();
+            (tx, root_proofs)
+        })
+        .collect();
+    let items: Vec<TxValidationBatchItem> = txs_and_proofs
+        .iter()
+        .map(|(tx, root_proofs)| TxValidationBatchItem {
+            tx,
+            root_proofs,
+            minimum_fee: Mob::MINIMUM_FEE,
+        })
+        .collect();
+    let current_block_index = n_blocks;
+
+    let mut group = c.benchmark_group("Tx validation");
+    group.sample_size(10);
+
+    for num_txs in [1, 4, 16, MAX_NUM_TXS] {
+        let items = &items[..num_txs];
+        group.throughput(Throughput::Elements(num_txs as u64));
+
+        group.bench_with_input(BenchmarkId::new("validate", num_txs), items, |b, items| {
+            b.iter(|| {
+                for item in items {
+                    validate(
+                        item.tx,
+                        current_block_index,
+                        block_version,
+                        item.root_proofs,
+                        item.minimum_fee,
+                        &mut rng,
+                    )
+                    .unwrap();
+                }
+            })
+        });
+
+        group.bench_with_input(
+            BenchmarkId::new("validate_batch", num_txs),
+            items,
+            |b, items| {
+                b.iter(|| {
+                    let results =
+                        validate_batch(items, current_block_index, block_version, &mut rng);
+                    assert!(results.iter().all(Result::is_ok));
+                })
+            },
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default();
+    targets = validation_benchmarks
+}
+
+criterion_main!(benches);
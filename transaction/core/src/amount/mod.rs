@@ -19,7 +19,7 @@ use crc::Crc;
 use mc_crypto_digestible::Digestible;
 use mc_crypto_hashes::{Blake2b512, Digest};
 use mc_crypto_keys::RistrettoPublic;
-use mc_crypto_ring_signature::{generators, CompressedCommitment, Scalar};
+use mc_crypto_ring_signature::{generators, CompressedCommitment, CurveScalar, Scalar};
 use prost::Message;
 use serde::{Deserialize, Serialize};
 use zeroize::Zeroize;
@@ -61,9 +61,22 @@ impl MaskedAmount {
     pub fn new(
         amount: Amount,
         shared_secret: &RistrettoPublic,
+    ) -> Result<MaskedAmount, AmountError> {
+        Self::new_with_masks(amount, &AmountMasks::new(shared_secret))
+    }
+
+    /// Creates a commitment `value*H + blinding*G`, and "masks" the commitment
+    /// secrets, using the masks derived from a shared secret.
+    ///
+    /// # Arguments
+    /// * `amount` - The amount information to be masked
+    /// * `masks` - The masks of the amount
+    pub fn new_with_masks(
+        amount: Amount,
+        masks: &AmountMasks,
     ) -> Result<MaskedAmount, AmountError> {
         // The blinding is `Blake2B("blinding" | shared_secret)`
-        let blinding: Scalar = get_blinding(shared_secret);
+        let blinding: Scalar = masks.blinding.scalar;
 
         // Pedersen generators
         let generator = generators(*amount.token_id);
@@ -73,11 +86,11 @@ impl MaskedAmount {
 
         // The value is XORed with the first 8 bytes of the mask.
         // `v XOR_8 Scalar::from_hash(Blake2B(value_mask | shared_secret))`
-        let masked_value: u64 = amount.value ^ get_value_mask(shared_secret);
+        let masked_value: u64 = amount.value ^ masks.value_mask;
 
         // The token_id is XORed with the first 8 bytes of the mask.
         // `v XOR_4 Blake2B(token_id_mask | shared_secret)`
-        let masked_token_id_val: u64 = *amount.token_id ^ get_token_id_mask(shared_secret);
+        let masked_token_id_val: u64 = *amount.token_id ^ masks.token_id_mask;
         let masked_token_id = masked_token_id_val.to_le_bytes().to_vec();
 
         Ok(MaskedAmount {
@@ -97,9 +110,21 @@ impl MaskedAmount {
     pub fn get_value(
         &self,
         shared_secret: &RistrettoPublic,
+    ) -> Result<(Amount, Scalar), AmountError> {
+        self.get_value_with_masks(&AmountMasks::new(shared_secret))
+    }
+
+    /// Returns the amount underlying the masked amount, given the masks
+    /// derived from the shared secret.
+    ///
+    /// # Arguments
+    /// * `masks` - The masks of the amount
+    pub fn get_value_with_masks(
+        &self,
+        masks: &AmountMasks,
     ) -> Result<(Amount, Scalar), AmountError> {
         let (expected_commitment, amount, blinding) =
-            Self::compute_commitment(self.masked_value, &self.masked_token_id, shared_secret)?;
+            Self::compute_commitment(self.masked_value, &self.masked_token_id, masks)?;
         if self.commitment != expected_commitment {
             // The commitment does not agree with the provided value and blinding.
             // This either means that the commitment does not correspond to the shared
@@ -139,8 +164,11 @@ impl MaskedAmount {
         masked_token_id: &[u8],
         shared_secret: &RistrettoPublic,
     ) -> Result<(Self, Amount), AmountError> {
-        let (expected_commitment, amount, _) =
-            Self::compute_commitment(masked_value, masked_token_id, shared_secret)?;
+        let (expected_commitment, amount, _) = Self::compute_commitment(
+            masked_value,
+            masked_token_id,
+            &AmountMasks::new(shared_secret),
+        )?;
 
         let result = Self {
             commitment: expected_commitment,
@@ -152,16 +180,16 @@ impl MaskedAmount {
     }
 
     /// Compute the expected commitment corresponding to a masked value, masked
-    /// token id, and shared secret, returning errors if the masked token id
+    /// token id, and amount masks, returning errors if the masked token id
     /// is malformed.
     fn compute_commitment(
         masked_value: u64,
         masked_token_id: &[u8],
-        shared_secret: &RistrettoPublic,
+        masks: &AmountMasks,
     ) -> Result<(CompressedCommitment, Amount, Scalar), AmountError> {
-        let token_id = TokenId::from(Self::unmask_token_id(masked_token_id, shared_secret)?);
-        let value: u64 = Self::unmask_value(masked_value, shared_secret);
-        let blinding = get_blinding(shared_secret);
+        let token_id = TokenId::from(Self::unmask_token_id(masked_token_id, masks)?);
+        let value: u64 = Self::unmask_value(masked_value, masks);
+        let blinding = masks.blinding.scalar;
 
         // Pedersen generators
         let generator = generators(*token_id);
@@ -176,26 +204,58 @@ impl MaskedAmount {
     }
 
     /// Reveals `masked_value`.
-    fn unmask_value(masked_value: u64, shared_secret: &RistrettoPublic) -> u64 {
-        masked_value ^ get_value_mask(shared_secret)
+    fn unmask_value(masked_value: u64, masks: &AmountMasks) -> u64 {
+        masked_value ^ masks.value_mask
     }
 
     /// Reveals `masked_token_id`, with backwards compat
-    fn unmask_token_id(
-        masked_token_id: &[u8],
-        shared_secret: &RistrettoPublic,
-    ) -> Result<u64, AmountError> {
+    fn unmask_token_id(masked_token_id: &[u8], masks: &AmountMasks) -> Result<u64, AmountError> {
         match masked_token_id.len() {
             0 => Ok(0),
             TokenId::NUM_BYTES => {
                 let masked_token_id_val = u64::from_le_bytes(masked_token_id.try_into().unwrap());
-                Ok(masked_token_id_val ^ get_token_id_mask(shared_secret))
+                Ok(masked_token_id_val ^ masks.token_id_mask)
             }
             _ => Err(AmountError::InvalidMaskedTokenId),
         }
     }
 }
 
+/// The secrets which mask an amount: the masks of its value and of its token
+/// id, and the blinding of its commitment.
+///
+/// They are derived from the shared secret of the TxOut, but unlike it, they
+/// don't reveal its memo or its confirmation number. Revealing them lets others
+/// read the amount, and mask other amounts in the same way.
+#[derive(Clone, Deserialize, Digestible, Eq, Message, PartialEq, Serialize, Zeroize)]
+pub struct AmountMasks {
+    /// `Blake2B(value_mask | shared_secret)`, which masks the value
+    #[prost(fixed64, required, tag = "1")]
+    pub value_mask: u64,
+
+    /// `Blake2B(token_id_mask | shared_secret)`, which masks the token id
+    #[prost(fixed64, required, tag = "2")]
+    pub token_id_mask: u64,
+
+    /// `Blake2B("blinding" | shared_secret)`, the blinding of the commitment
+    #[prost(message, required, tag = "3")]
+    pub blinding: CurveScalar,
+}
+
+impl AmountMasks {
+    /// Derive the amount masks from a shared secret.
+    ///
+    /// # Arguments
+    /// * `shared_secret` - The shared secret, e.g. `rB`.
+    pub fn new(shared_secret: &RistrettoPublic) -> Self {
+        Self {
+            value_mask: get_value_mask(shared_secret),
+            token_id_mask: get_token_id_mask(shared_secret),
+            blinding: get_blinding(shared_secret).into(),
+        }
+    }
+}
+
 /// Computes `Blake2B(value_mask | shared_secret)`, hashed to a Ristretto
 /// scalar, then interprets the first 8 canonical bytes as a u64 number in
 /// little-endian representation.
@@ -239,7 +299,7 @@ fn get_blinding(shared_secret: &RistrettoPublic) -> Scalar {
 #[cfg(test)]
 mod amount_tests {
     use crate::{
-        amount::{get_blinding, Amount, AmountError, MaskedAmount},
+        amount::{get_blinding, Amount, AmountError, AmountMasks, MaskedAmount},
         proptest_fixtures::*,
         ring_signature::generators,
         CompressedCommitment,
@@ -283,7 +343,10 @@ mod amount_tests {
                 let masked_amount = MaskedAmount::new(amount, &shared_secret).unwrap();
                 assert_eq!(
                     value,
-                    MaskedAmount::unmask_value(masked_amount.masked_value, &shared_secret)
+                    MaskedAmount::unmask_value(
+                        masked_amount.masked_value,
+                        &AmountMasks::new(&shared_secret)
+                    )
                 );
             }
 
@@ -301,6 +364,34 @@ mod amount_tests {
                 assert_eq!(result, expected);
             }
 
+            #[test]
+            /// The amount masks should mask and unmask amounts like the shared secret
+            /// they are derived from.
+            fn test_amount_masks(
+                value in any::<u64>(),
+                token_id in any::<u64>(),
+                other_value in any::<u64>(),
+                shared_secret in arbitrary_ristretto_public()) {
+                let masks = AmountMasks::new(&shared_secret);
+                let amount = Amount { value, token_id: token_id.into() };
+                let masked_amount = MaskedAmount::new(amount, &shared_secret).unwrap();
+                assert_eq!(
+                    MaskedAmount::new_with_masks(amount, &masks).unwrap(),
+                    masked_amount
+                );
+                assert_eq!(
+                    masked_amount.get_value_with_masks(&masks),
+                    masked_amount.get_value(&shared_secret)
+                );
+
+                let other_amount = Amount { value: other_value, token_id: token_id.into() };
+                let other_masked_amount = MaskedAmount::new_with_masks(other_amount, &masks).unwrap();
+                assert_eq!(
+                    other_masked_amount.get_value(&shared_secret),
+                    Ok((other_amount, get_blinding(&shared_secret)))
+                );
+            }
+
 
             #[test]
             /// get_value should return InconsistentCommitment if the masked value is incorrect.
@@ -7,14 +7,24 @@
 //! coming from some parties, and some inputs come from others. They give
 //! participants a way to make their signature contingent on certain rules being
 //! followed, to facilitate trustless interactions.
+//!
+//! Partial fill rules let a counterparty take only a fraction of what the
+//! signer offers. The signer reveals the amount of some of the outputs that
+//! they require, which lets the counterparty create "fractional" versions of
+//! them, with the same keys and memo but a smaller amount, and lets consensus
+//! check that the amounts of the fractional outputs are in proportion to the
+//! fraction of the offer which was taken.
 
 use crate::{
+    amount::{AmountMasks, MaskedAmount},
+    ring_ct::OutputSecret,
     tx::{Tx, TxOut},
-    BlockVersion,
+    Amount, BlockVersion,
 };
 use alloc::vec::Vec;
 use displaydoc::Display;
 use mc_crypto_digestible::Digestible;
+use mc_crypto_ring_signature::Scalar;
 use prost::Message;
 use serde::{Deserialize, Serialize};
 
@@ -34,11 +44,31 @@ pub struct InputRules {
     /// transaction to be valid
     #[prost(fixed64, tag = "2")]
     pub max_tombstone_block: u64,
+
+    /// Outputs whose fractional versions are required to appear in the Tx
+    /// prefix, with a value in proportion to the fraction of the offer which
+    /// was filled
+    #[prost(message, repeated, tag = "3")]
+    pub partial_fill_outputs: Vec<RevealedTxOut>,
+
+    /// A change output returning the whole offer to the signer, whose
+    /// fractional version is required to appear in the Tx prefix. The value
+    /// of the fractional change determines the fraction of the offer which was
+    /// filled.
+    #[prost(message, optional, tag = "4")]
+    pub partial_fill_change: Option<RevealedTxOut>,
+
+    /// The minimum value of the offer which must be filled, i.e. the minimum
+    /// difference between the values of the partial fill change and of its
+    /// fractional version
+    #[prost(fixed64, tag = "5")]
+    #[digestible(omit_when = 0)]
+    pub min_partial_fill_value: u64,
 }
 
 impl InputRules {
     /// Verify that a Tx conforms to the rules.
-    pub fn verify(&self, _block_version: BlockVersion, tx: &Tx) -> Result<(), InputRuleError> {
+    pub fn verify(&self, block_version: BlockVersion, tx: &Tx) -> Result<(), InputRuleError> {
         // NOTE: If this function gets too busy, we should split it into several smaller
         // functions NOTE: The tests for this function are in
         // transaction/core/tests/input_rules.rs
@@ -53,8 +83,154 @@ impl InputRules {
                 return Err(InputRuleError::MissingRequiredOutput);
             }
         }
+        // Verify partial fill rules
+        if self.has_partial_fill_rules() {
+            if !block_version.partial_fill_rules_are_supported() {
+                return Err(InputRuleError::PartialFillRulesNotAllowed);
+            }
+            self.verify_partial_fill_rules(tx)?;
+        }
         Ok(())
     }
+
+    /// Whether any of the partial fill rules are set
+    pub fn has_partial_fill_rules(&self) -> bool {
+        !self.partial_fill_outputs.is_empty()
+            || self.partial_fill_change.is_some()
+            || self.min_partial_fill_value != 0
+    }
+
+    // Verify that the fractional outputs of the partial fill outputs and of the
+    // partial fill change appear in the Tx, with correct amounts.
+    fn verify_partial_fill_rules(&self, tx: &Tx) -> Result<(), InputRuleError> {
+        let partial_fill_change = self
+            .partial_fill_change
+            .as_ref()
+            .ok_or(InputRuleError::MissingPartialFillChange)?;
+        let (change_amount, _) = partial_fill_change.reveal_amount()?;
+        if change_amount.value == 0 {
+            return Err(InputRuleError::ZeroPartialFillChange);
+        }
+
+        // The fractional change determines the fill fraction, which is
+        // fill_value / change_amount.value
+        let fractional_change_amount = partial_fill_change.find_fractional_amount(tx)?;
+        if fractional_change_amount.value > change_amount.value {
+            return Err(InputRuleError::FractionalChangeExceedsPartialFillChange);
+        }
+        let fill_value = change_amount.value - fractional_change_amount.value;
+        if fill_value < self.min_partial_fill_value {
+            return Err(InputRuleError::MinPartialFillValueNotReached);
+        }
+
+        for partial_fill_output in self.partial_fill_outputs.iter() {
+            let (amount, _) = partial_fill_output.reveal_amount()?;
+            let fractional_amount = partial_fill_output.find_fractional_amount(tx)?;
+            // The fractional output must be worth at least amount * fill_value /
+            // change_amount.value, which we check without division.
+            // This can't overflow, because the product of two u64 fits in a u128.
+            if (fractional_amount.value as u128) * (change_amount.value as u128)
+                < (amount.value as u128) * (fill_value as u128)
+            {
+                return Err(InputRuleError::FractionalOutputValueTooLow);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A TxOut together with the masks of its amount, which reveal its value and
+/// token id to anyone holding the input rules it appears in.
+///
+/// Only the amount masks are revealed, not the shared secret of the TxOut, so
+/// its memo and confirmation number stay private.
+#[derive(Clone, Digestible, PartialEq, Eq, Message, Serialize, Deserialize)]
+pub struct RevealedTxOut {
+    /// The TxOut
+    #[prost(message, required, tag = "1")]
+    pub tx_out: TxOut,
+
+    /// The masks of the amount of the TxOut
+    #[prost(message, required, tag = "2")]
+    pub amount_masks: AmountMasks,
+}
+
+impl RevealedTxOut {
+    /// Get the amount and blinding of the TxOut, using the revealed amount
+    /// masks
+    pub fn reveal_amount(&self) -> Result<(Amount, Scalar), InputRuleError> {
+        self.tx_out
+            .masked_amount
+            .get_value_with_masks(&self.amount_masks)
+            .map_err(|_| InputRuleError::InvalidRevealedTxOut)
+    }
+
+    /// Create the fractional version of this TxOut: the same TxOut with a
+    /// different value, masked with the same amount masks. Returns the
+    /// fractional TxOut and its output secret.
+    pub fn fractional_output(&self, value: u64) -> Result<(TxOut, OutputSecret), InputRuleError> {
+        let (amount, blinding) = self.reveal_amount()?;
+        let amount = Amount::new(value, amount.token_id);
+        let masked_amount = MaskedAmount::new_with_masks(amount, &self.amount_masks)
+            .map_err(|_| InputRuleError::InvalidRevealedTxOut)?;
+
+        let mut tx_out = self.tx_out.clone();
+        tx_out.masked_amount = masked_amount;
+        Ok((tx_out, OutputSecret { amount, blinding }))
+    }
+
+    /// Get the amount of the smallest fractional version of this TxOut which
+    /// satisfies the partial fill rules, when `fill_value` of a partial fill
+    /// change worth `change_value` is filled.
+    ///
+    /// The value is rounded up, so that it is never worth less than what the
+    /// rules require.
+    pub fn min_fractional_amount(
+        &self,
+        fill_value: u64,
+        change_value: u64,
+    ) -> Result<Amount, InputRuleError> {
+        if change_value == 0 {
+            return Err(InputRuleError::ZeroPartialFillChange);
+        }
+        if fill_value > change_value {
+            return Err(InputRuleError::FractionalChangeExceedsPartialFillChange);
+        }
+        let (amount, _) = self.reveal_amount()?;
+        // This can't overflow because the result is at most amount.value.
+        let value = ((amount.value as u128) * (fill_value as u128) + (change_value as u128 - 1))
+            / (change_value as u128);
+        Ok(Amount::new(value as u64, amount.token_id))
+    }
+
+    /// Whether a TxOut is a fractional version of this TxOut, i.e. it is the
+    /// same except possibly for its amount
+    pub fn is_fractional_output(&self, tx_out: &TxOut) -> bool {
+        tx_out.public_key == self.tx_out.public_key
+            && tx_out.target_key == self.tx_out.target_key
+            && tx_out.e_fog_hint == self.tx_out.e_fog_hint
+            && tx_out.e_memo == self.tx_out.e_memo
+    }
+
+    // Find the fractional version of this TxOut in a Tx, and get its amount.
+    // Its token id must be the same as the token id of this TxOut.
+    fn find_fractional_amount(&self, tx: &Tx) -> Result<Amount, InputRuleError> {
+        let tx_out = tx
+            .prefix
+            .outputs
+            .iter()
+            .find(|tx_out| self.is_fractional_output(tx_out))
+            .ok_or(InputRuleError::MissingFractionalOutput)?;
+        let (amount, _) = tx_out
+            .masked_amount
+            .get_value_with_masks(&self.amount_masks)
+            .map_err(|_| InputRuleError::InvalidFractionalOutput)?;
+        let (revealed_amount, _) = self.reveal_amount()?;
+        if amount.token_id != revealed_amount.token_id {
+            return Err(InputRuleError::InvalidFractionalOutput);
+        }
+        Ok(amount)
+    }
 }
 
 /// An error that occurs when checking input rules
@@ -64,4 +240,22 @@ pub enum InputRuleError {
     MissingRequiredOutput,
     /// The tombstone block exceeds the limit
     MaxTombstoneBlockExceeded,
+    /// Partial fill rules are not allowed at this block version
+    PartialFillRulesNotAllowed,
+    /// Partial fill rules are set without a partial fill change
+    MissingPartialFillChange,
+    /// The partial fill change has a value of zero
+    ZeroPartialFillChange,
+    /// The amount masks of a revealed TxOut do not unmask its amount
+    InvalidRevealedTxOut,
+    /// The transaction is missing a fractional output
+    MissingFractionalOutput,
+    /// The amount of a fractional output is invalid
+    InvalidFractionalOutput,
+    /// The fractional change exceeds the partial fill change
+    FractionalChangeExceedsPartialFillChange,
+    /// The minimum partial fill value is not reached
+    MinPartialFillValueNotReached,
+    /// The value of a fractional output is too low for the fill fraction
+    FractionalOutputValueTooLow,
 }
@@ -19,6 +19,7 @@ mod amount;
 mod domain_separators;
 mod input_rules;
 mod memo;
+mod partial_tx;
 mod signed_contingent_input;
 mod token;
 mod tx_error;
@@ -37,9 +38,10 @@ pub mod validation;
 #[cfg(test)]
 pub mod proptest_fixtures;
 
-pub use amount::{AmountError, MaskedAmount};
-pub use input_rules::{InputRuleError, InputRules};
+pub use amount::{AmountError, AmountMasks, MaskedAmount};
+pub use input_rules::{InputRuleError, InputRules, RevealedTxOut};
 pub use memo::{EncryptedMemo, MemoError, MemoPayload};
+pub use partial_tx::{PartialTx, PartialTxError, PartialTxOutput, PARTIAL_TX_VERSION};
 pub use signed_contingent_input::{
     SignedContingentInput, SignedContingentInputError, UnmaskedAmount,
 };
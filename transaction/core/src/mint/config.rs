@@ -30,6 +30,66 @@ pub struct MintConfig {
     /// been applied.
     #[prost(uint64, tag = "3")]
     pub mint_limit: u64,
+
+    /// Rolling-window limits on the amount this configuration can mint.
+    /// Requires block version 5 or later.
+    #[prost(message, repeated, tag = "4")]
+    #[serde(default)]
+    pub mint_limit_windows: Vec<MintLimitWindow>,
+}
+
+/// A limit on the amount a minting configuration can mint within any window of
+/// consecutive blocks.
+#[derive(
+    Clone, Deserialize, Digestible, Eq, Hash, Message, Ord, PartialEq, PartialOrd, Serialize,
+)]
+pub struct MintLimitWindow {
+    /// The number of consecutive blocks the window spans.
+    #[prost(uint64, tag = "1")]
+    pub num_blocks: u64,
+
+    /// The maximal amount that can be minted within the window.
+    #[prost(uint64, tag = "2")]
+    pub limit: u64,
+}
+
+impl MintLimitWindow {
+    /// Whether a mint in the block at `mint_block_index` falls within the
+    /// window that ends with the block at `block_index`.
+    pub fn contains(&self, mint_block_index: u64, block_index: u64) -> bool {
+        mint_block_index <= block_index && block_index - mint_block_index < self.num_blocks
+    }
+
+    /// The amount minted within the window that ends with the block at
+    /// `block_index`.
+    pub fn minted(&self, block_index: u64, recent_mints: &[MintRecord]) -> u64 {
+        recent_mints
+            .iter()
+            .filter(|record| self.contains(record.block_index, block_index))
+            .fold(0u64, |total, record| total.saturating_add(record.amount))
+    }
+
+    /// The amount that can still be minted within the window that ends with
+    /// the block at `block_index`.
+    pub fn remaining(&self, block_index: u64, recent_mints: &[MintRecord]) -> u64 {
+        self.limit
+            .saturating_sub(self.minted(block_index, recent_mints))
+    }
+}
+
+/// A record of an amount minted by a minting configuration, used to enforce
+/// its mint limit windows.
+#[derive(
+    Clone, Deserialize, Digestible, Eq, Hash, Message, Ord, PartialEq, PartialOrd, Serialize,
+)]
+pub struct MintRecord {
+    /// The index of the block the mint was included in.
+    #[prost(uint64, tag = "1")]
+    pub block_index: u64,
+
+    /// The amount minted.
+    #[prost(uint64, tag = "2")]
+    pub amount: u64,
 }
 
 /// The contents of a mint-config transaction. This transaction alters the
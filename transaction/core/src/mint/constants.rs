@@ -10,3 +10,10 @@ pub const MAX_MINT_TXS_PER_BLOCK: usize = 10;
 
 /// Maximum number of MintConfigTx that may be included in a Block.
 pub const MAX_MINT_CONFIG_TXS_PER_BLOCK: usize = 10;
+
+/// Maximum number of nested signer set levels in the signer set of a
+/// MintConfig.
+pub const MAX_SIGNER_SET_DEPTH: usize = 3;
+
+/// Maximum number of mint limit windows in a MintConfig.
+pub const MAX_MINT_LIMIT_WINDOWS: usize = 4;
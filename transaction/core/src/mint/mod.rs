@@ -8,8 +8,13 @@ mod validation;
 
 pub mod constants;
 
-pub use config::{MintConfig, MintConfigTx, MintConfigTxPrefix, ValidatedMintConfigTx};
+pub use config::{
+    MintConfig, MintConfigTx, MintConfigTxPrefix, MintLimitWindow, MintRecord,
+    ValidatedMintConfigTx,
+};
 pub use tx::{MintTx, MintTxPrefix};
 pub use validation::{
-    config::validate_mint_config_tx, error::Error as MintValidationError, tx::validate_mint_tx,
+    config::validate_mint_config_tx,
+    error::Error as MintValidationError,
+    tx::{validate_against_mint_limit_windows, validate_mint_tx},
 };
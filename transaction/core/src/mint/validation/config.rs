@@ -4,7 +4,8 @@
 
 use crate::{
     mint::{
-        config::{MintConfig, MintConfigTx},
+        config::{MintConfig, MintConfigTx, MintLimitWindow},
+        constants::{MAX_MINT_LIMIT_WINDOWS, MAX_SIGNER_SET_DEPTH},
         validation::{
             common::{
                 validate_block_version, validate_nonce, validate_token_id, validate_tombstone,
@@ -41,7 +42,7 @@ pub fn validate_mint_config_tx(
     let token_id = TokenId::from(tx.prefix.token_id);
     validate_token_id(token_id)?;
 
-    validate_configs(token_id, &tx.prefix.configs)?;
+    validate_configs(token_id, &tx.prefix.configs, block_version)?;
 
     validate_nonce(&tx.prefix.nonce)?;
 
@@ -55,21 +56,76 @@ pub fn validate_mint_config_tx(
 }
 
 /// The minting configurations must all point to the same token id, and must
-/// have a valid signer set.
+/// have a valid signer set and valid mint limit windows. Nested signer sets and
+/// mint limit windows are only allowed from the block version that supports
+/// them.
 ///
 /// # Arguments
 /// * `token_id` - The token id we are trying to mint.
 /// * `configs` - The minting configurations to validate.
-fn validate_configs(token_id: TokenId, configs: &[MintConfig]) -> Result<(), Error> {
+/// * `block_version` - The version of the block that is being built.
+fn validate_configs(
+    token_id: TokenId,
+    configs: &[MintConfig],
+    block_version: BlockVersion,
+) -> Result<(), Error> {
     for config in configs {
         if config.token_id != token_id {
             return Err(Error::InvalidTokenId(config.token_id.into()));
         }
 
-        let num_signers = config.signer_set.signers().len();
-        if num_signers == 0 || num_signers < config.signer_set.threshold() as usize {
+        let depth = config.signer_set.depth();
+        if depth > 0 && !block_version.nested_signer_sets_are_supported() {
+            return Err(Error::NestedSignerSetsNotSupported(block_version));
+        }
+        if depth > MAX_SIGNER_SET_DEPTH {
             return Err(Error::InvalidSignerSet);
         }
+
+        validate_signer_set(&config.signer_set)?;
+
+        validate_mint_limit_windows(&config.mint_limit_windows, block_version)?;
+    }
+
+    Ok(())
+}
+
+/// Mint limit windows must span at least one block, and there may only be a
+/// limited number of them.
+///
+/// # Arguments
+/// * `windows` - The mint limit windows to validate.
+/// * `block_version` - The version of the block that is being built.
+fn validate_mint_limit_windows(
+    windows: &[MintLimitWindow],
+    block_version: BlockVersion,
+) -> Result<(), Error> {
+    if windows.is_empty() {
+        return Ok(());
+    }
+    if !block_version.mint_limit_windows_are_supported() {
+        return Err(Error::MintLimitWindowsNotSupported(block_version));
+    }
+    if windows.len() > MAX_MINT_LIMIT_WINDOWS || windows.iter().any(|w| w.num_blocks == 0) {
+        return Err(Error::InvalidMintLimitWindow);
+    }
+
+    Ok(())
+}
+
+/// A signer set, and each of its nested signer sets, must have at least one
+/// potential signer and a threshold that can be met.
+///
+/// # Arguments
+/// * `signer_set` - The signer set to validate.
+fn validate_signer_set(signer_set: &SignerSet<Ed25519Public>) -> Result<(), Error> {
+    let num_signers = signer_set.signers().len() + signer_set.multi_signers().len();
+    if num_signers == 0 || num_signers < signer_set.threshold() as usize {
+        return Err(Error::InvalidSignerSet);
+    }
+
+    for multi_signer in signer_set.multi_signers() {
+        validate_signer_set(multi_signer)?;
     }
 
     Ok(())
@@ -116,35 +172,40 @@ mod tests {
             token_id: *token_id,
             signer_set: SignerSet::new(vec![signer_1.public_key()], 1),
             mint_limit: 10,
+            mint_limit_windows: vec![],
         };
 
         let mint_config2 = MintConfig {
             token_id: *token_id,
             signer_set: SignerSet::new(vec![signer_2.public_key()], 1),
             mint_limit: 15,
+            mint_limit_windows: vec![],
         };
 
         let mint_config3 = MintConfig {
             token_id: *token_id,
             signer_set: SignerSet::new(vec![signer_2.public_key(), signer_3.public_key()], 1),
             mint_limit: 15,
+            mint_limit_windows: vec![],
         };
         let mint_config4 = MintConfig {
             token_id: *token_id,
             signer_set: SignerSet::new(vec![signer_2.public_key(), signer_3.public_key()], 2),
             mint_limit: 15,
+            mint_limit_windows: vec![],
         };
 
         assert!(validate_configs(
             token_id,
-            &[mint_config1, mint_config2, mint_config3, mint_config4]
+            &[mint_config1, mint_config2, mint_config3, mint_config4],
+            BlockVersion::MAX
         )
         .is_ok());
     }
 
     #[test]
     fn validate_configs_accepts_no_configs() {
-        assert!(validate_configs(123.into(), &[]).is_ok());
+        assert!(validate_configs(123.into(), &[], BlockVersion::MAX).is_ok());
     }
 
     #[test]
@@ -157,21 +218,27 @@ mod tests {
             token_id: 123,
             signer_set: SignerSet::new(vec![signer_1.public_key()], 1),
             mint_limit: 10,
+            mint_limit_windows: vec![],
         };
 
         let mint_config2 = MintConfig {
             token_id: 234,
             signer_set: SignerSet::new(vec![signer_2.public_key()], 1),
             mint_limit: 15,
+            mint_limit_windows: vec![],
         };
 
         assert_eq!(
-            validate_configs(123.into(), &[mint_config1.clone(), mint_config2.clone()]),
+            validate_configs(
+                123.into(),
+                &[mint_config1.clone(), mint_config2.clone()],
+                BlockVersion::MAX
+            ),
             Err(Error::InvalidTokenId(234.into()))
         );
 
         assert_eq!(
-            validate_configs(1.into(), &[mint_config1, mint_config2]),
+            validate_configs(1.into(), &[mint_config1, mint_config2], BlockVersion::MAX),
             Err(Error::InvalidTokenId(123.into()))
         );
     }
@@ -187,24 +254,144 @@ mod tests {
             signer_set: SignerSet::new(vec![signer_1.public_key()], 2), /* threshold > number of
                                                                          * signers */
             mint_limit: 10,
+            mint_limit_windows: vec![],
         };
 
         let mint_config2 = MintConfig {
             token_id: *token_id,
             signer_set: SignerSet::new(vec![], 1), // no signers
             mint_limit: 15,
+            mint_limit_windows: vec![],
+        };
+
+        assert_eq!(
+            validate_configs(token_id, &[mint_config1], BlockVersion::MAX),
+            Err(Error::InvalidSignerSet)
+        );
+        assert_eq!(
+            validate_configs(token_id, &[mint_config2], BlockVersion::MAX),
+            Err(Error::InvalidSignerSet)
+        );
+    }
+
+    #[test]
+    fn validate_configs_nested_signer_sets() {
+        let mut rng = get_seeded_rng();
+        let ops = SignerSet::new(
+            (0..3)
+                .map(|_| Ed25519Pair::from_random(&mut rng).public_key())
+                .collect(),
+            2,
+        );
+        let compliance = SignerSet::new(
+            (0..2)
+                .map(|_| Ed25519Pair::from_random(&mut rng).public_key())
+                .collect(),
+            1,
+        );
+        let token_id = TokenId::from(123);
+
+        let mint_config = MintConfig {
+            token_id: *token_id,
+            signer_set: SignerSet::new_with_multi(vec![], vec![ops.clone(), compliance.clone()], 2),
+            mint_limit: 10,
+            mint_limit_windows: vec![],
         };
 
+        assert!(validate_configs(token_id, &[mint_config.clone()], BlockVersion::FIVE).is_ok());
+        assert_eq!(
+            validate_configs(token_id, &[mint_config], BlockVersion::FOUR),
+            Err(Error::NestedSignerSetsNotSupported(BlockVersion::FOUR))
+        );
+
+        // Threshold larger than the number of nested signer sets.
+        let mint_config = MintConfig {
+            token_id: *token_id,
+            signer_set: SignerSet::new_with_multi(vec![], vec![ops.clone(), compliance.clone()], 3),
+            mint_limit: 10,
+            mint_limit_windows: vec![],
+        };
+        assert_eq!(
+            validate_configs(token_id, &[mint_config], BlockVersion::FIVE),
+            Err(Error::InvalidSignerSet)
+        );
+
+        // Invalid nested signer set.
+        let mint_config = MintConfig {
+            token_id: *token_id,
+            signer_set: SignerSet::new_with_multi(
+                vec![],
+                vec![ops.clone(), SignerSet::new(vec![], 1)],
+                1,
+            ),
+            mint_limit: 10,
+            mint_limit_windows: vec![],
+        };
         assert_eq!(
-            validate_configs(token_id, &[mint_config1]),
+            validate_configs(token_id, &[mint_config], BlockVersion::FIVE),
             Err(Error::InvalidSignerSet)
         );
+
+        // Too many levels of nesting.
+        let mut signer_set = ops;
+        for _ in 0..=MAX_SIGNER_SET_DEPTH {
+            signer_set = SignerSet::new_with_multi(vec![], vec![signer_set], 1);
+        }
+        let mint_config = MintConfig {
+            token_id: *token_id,
+            signer_set,
+            mint_limit: 10,
+            mint_limit_windows: vec![],
+        };
         assert_eq!(
-            validate_configs(token_id, &[mint_config2]),
+            validate_configs(token_id, &[mint_config], BlockVersion::FIVE),
             Err(Error::InvalidSignerSet)
         );
     }
 
+    #[test]
+    fn validate_configs_mint_limit_windows() {
+        let mut rng = get_seeded_rng();
+        let signer_1 = Ed25519Pair::from_random(&mut rng);
+        let token_id = TokenId::from(123);
+
+        let window = MintLimitWindow {
+            num_blocks: 100,
+            limit: 5,
+        };
+        let mint_config = MintConfig {
+            token_id: *token_id,
+            signer_set: SignerSet::new(vec![signer_1.public_key()], 1),
+            mint_limit: 10,
+            mint_limit_windows: vec![window.clone()],
+        };
+
+        assert!(validate_configs(token_id, &[mint_config.clone()], BlockVersion::FIVE).is_ok());
+        assert_eq!(
+            validate_configs(token_id, &[mint_config.clone()], BlockVersion::FOUR),
+            Err(Error::MintLimitWindowsNotSupported(BlockVersion::FOUR))
+        );
+
+        // Windows must span at least one block.
+        let mut invalid_config = mint_config.clone();
+        invalid_config.mint_limit_windows.push(MintLimitWindow {
+            num_blocks: 0,
+            limit: 5,
+        });
+        assert_eq!(
+            validate_configs(token_id, &[invalid_config], BlockVersion::FIVE),
+            Err(Error::InvalidMintLimitWindow)
+        );
+
+        // Too many windows.
+        let mut invalid_config = mint_config;
+        invalid_config.mint_limit_windows = vec![window; MAX_MINT_LIMIT_WINDOWS + 1];
+        assert_eq!(
+            validate_configs(token_id, &[invalid_config], BlockVersion::FIVE),
+            Err(Error::InvalidMintLimitWindow)
+        );
+    }
+
     #[test]
     fn validate_signature_accepts_valid_signature() {
         let mut rng = get_seeded_rng();
@@ -216,12 +403,14 @@ mod tests {
             token_id: 123,
             signer_set: SignerSet::new(vec![signer_1.public_key()], 1),
             mint_limit: 10,
+            mint_limit_windows: vec![],
         };
 
         let mint_config2 = MintConfig {
             token_id: 234,
             signer_set: SignerSet::new(vec![signer_2.public_key()], 1),
             mint_limit: 15,
+            mint_limit_windows: vec![],
         };
 
         let governor_1 = Ed25519Pair::from_random(&mut rng);
@@ -333,12 +522,14 @@ mod tests {
             token_id: 123,
             signer_set: SignerSet::new(vec![signer_1.public_key()], 1),
             mint_limit: 10,
+            mint_limit_windows: vec![],
         };
 
         let mint_config2 = MintConfig {
             token_id: 234,
             signer_set: SignerSet::new(vec![signer_2.public_key()], 1),
             mint_limit: 15,
+            mint_limit_windows: vec![],
         };
 
         let governor_1 = Ed25519Pair::from_random(&mut rng);
@@ -408,12 +599,14 @@ mod tests {
             token_id: 123,
             signer_set: SignerSet::new(vec![signer_1.public_key()], 1),
             mint_limit: 10,
+            mint_limit_windows: vec![],
         };
 
         let mint_config2 = MintConfig {
             token_id: 234,
             signer_set: SignerSet::new(vec![signer_2.public_key()], 1),
             mint_limit: 15,
+            mint_limit_windows: vec![],
         };
 
         let governor_1 = Ed25519Pair::from_random(&mut rng);
@@ -491,12 +684,14 @@ mod tests {
             token_id: 123,
             signer_set: SignerSet::new(vec![signer_1.public_key()], 1),
             mint_limit: 10,
+            mint_limit_windows: vec![],
         };
 
         let mint_config2 = MintConfig {
             token_id: 234,
             signer_set: SignerSet::new(vec![signer_2.public_key()], 1),
             mint_limit: 15,
+            mint_limit_windows: vec![],
         };
 
         let governor_1 = Ed25519Pair::from_random(&mut rng);
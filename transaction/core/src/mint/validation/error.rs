@@ -44,4 +44,16 @@ pub enum Error {
 
     /// No matching mint configuration
     NoMatchingMintConfig,
+
+    /// Nested signer sets are not supported at block version {0}
+    NestedSignerSetsNotSupported(BlockVersion),
+
+    /// Mint limit windows are not supported at block version {0}
+    MintLimitWindowsNotSupported(BlockVersion),
+
+    /// Invalid mint limit window
+    InvalidMintLimitWindow,
+
+    /// Amount exceeds mint limit window
+    AmountExceedsMintLimitWindow,
 }
@@ -4,7 +4,7 @@
 
 use crate::{
     mint::{
-        config::MintConfig,
+        config::{MintConfig, MintRecord},
         tx::MintTx,
         validation::{
             common::{
@@ -71,6 +71,33 @@ pub fn validate_against_mint_config(tx: &MintTx, mint_config: &MintConfig) -> Re
     Ok(())
 }
 
+/// Validate the transaction against the mint limit windows of a specific mint
+/// config.
+///
+/// # Arguments
+/// * `tx` - A pending transaction that is being validated.
+/// * `mint_config` - The mint config that the transaction is being validated
+///   against.
+/// * `current_block_index` - The index of the current block that is being
+///   built.
+/// * `recent_mints` - Mints previously made using the mint config.
+pub fn validate_against_mint_limit_windows(
+    tx: &MintTx,
+    mint_config: &MintConfig,
+    current_block_index: u64,
+    recent_mints: &[MintRecord],
+) -> Result<(), Error> {
+    // The amount must not exceed the remaining capacity of any window.
+    for window in mint_config.mint_limit_windows.iter() {
+        if tx.prefix.amount > window.remaining(current_block_index, recent_mints) {
+            return Err(Error::AmountExceedsMintLimitWindow);
+        }
+    }
+
+    // All good
+    Ok(())
+}
+
 /// The transaction must be properly signed by the signer set.
 ///
 /// # Arguments
@@ -88,7 +115,7 @@ fn validate_signature(tx: &MintTx, signer_set: &SignerSet<Ed25519Public>) -> Res
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::mint::{constants::NONCE_LENGTH, MintTxPrefix};
+    use crate::mint::{constants::NONCE_LENGTH, MintLimitWindow, MintTxPrefix};
     use mc_crypto_keys::{Ed25519Pair, RistrettoPublic, Signer};
     use mc_crypto_multisig::MultiSig;
     use mc_util_from_random::FromRandom;
@@ -113,6 +140,7 @@ mod tests {
                 2,
             ),
             mint_limit: 500,
+            mint_limit_windows: vec![],
         };
 
         let prefix = MintTxPrefix {
@@ -133,6 +161,65 @@ mod tests {
         assert_eq!(validate_against_mint_config(&tx, &mint_config), Ok(()));
     }
 
+    #[test]
+    fn validate_against_mint_config_with_nested_signer_set() {
+        let mut rng = get_seeded_rng();
+        let token_id = 123;
+        let ops = (0..3)
+            .map(|_| Ed25519Pair::from_random(&mut rng))
+            .collect::<Vec<_>>();
+        let compliance = (0..2)
+            .map(|_| Ed25519Pair::from_random(&mut rng))
+            .collect::<Vec<_>>();
+
+        // 2 of (3 ops keys) AND 1 of (2 compliance keys)
+        let mint_config = MintConfig {
+            token_id,
+            signer_set: SignerSet::new_with_multi(
+                vec![],
+                vec![
+                    SignerSet::new(ops.iter().map(|s| s.public_key()).collect(), 2),
+                    SignerSet::new(compliance.iter().map(|s| s.public_key()).collect(), 1),
+                ],
+                2,
+            ),
+            mint_limit: 500,
+            mint_limit_windows: vec![],
+        };
+
+        let prefix = MintTxPrefix {
+            token_id,
+            amount: 100,
+            view_public_key: RistrettoPublic::from_random(&mut rng),
+            spend_public_key: RistrettoPublic::from_random(&mut rng),
+            nonce: vec![1u8; NONCE_LENGTH],
+            tombstone_block: 10,
+        };
+        let message = prefix.hash();
+        let sign = |signers: &[&Ed25519Pair]| MintTx {
+            prefix: prefix.clone(),
+            signature: MultiSig::new(
+                signers
+                    .iter()
+                    .map(|signer| signer.try_sign(message.as_ref()).unwrap())
+                    .collect(),
+            ),
+        };
+
+        assert_eq!(
+            validate_against_mint_config(&sign(&[&ops[0], &ops[1], &compliance[1]]), &mint_config),
+            Ok(())
+        );
+        assert_eq!(
+            validate_against_mint_config(&sign(&[&ops[0], &ops[1], &ops[2]]), &mint_config),
+            Err(Error::InvalidSignature)
+        );
+        assert_eq!(
+            validate_against_mint_config(&sign(&[&ops[0], &compliance[0]]), &mint_config),
+            Err(Error::InvalidSignature)
+        );
+    }
+
     #[test]
     fn validate_against_mint_config_rejects_token_id_mismatch() {
         let mut rng = get_seeded_rng();
@@ -152,6 +239,7 @@ mod tests {
                 2,
             ),
             mint_limit: 500,
+            mint_limit_windows: vec![],
         };
 
         let prefix = MintTxPrefix {
@@ -194,6 +282,7 @@ mod tests {
                 2,
             ),
             mint_limit: 500,
+            mint_limit_windows: vec![],
         };
 
         let prefix = MintTxPrefix {
@@ -217,6 +306,96 @@ mod tests {
         );
     }
 
+    #[test]
+    fn validate_against_mint_limit_windows_enforces_remaining_capacity() {
+        let mut rng = get_seeded_rng();
+        let token_id = 123;
+        let signer_1 = Ed25519Pair::from_random(&mut rng);
+
+        let mint_config = MintConfig {
+            token_id,
+            signer_set: SignerSet::new(vec![signer_1.public_key()], 1),
+            mint_limit: 500,
+            mint_limit_windows: vec![
+                MintLimitWindow {
+                    num_blocks: 10,
+                    limit: 100,
+                },
+                MintLimitWindow {
+                    num_blocks: 100,
+                    limit: 200,
+                },
+            ],
+        };
+
+        let prefix = MintTxPrefix {
+            token_id,
+            amount: 60,
+            view_public_key: RistrettoPublic::from_random(&mut rng),
+            spend_public_key: RistrettoPublic::from_random(&mut rng),
+            nonce: vec![1u8; NONCE_LENGTH],
+            tombstone_block: 1000,
+        };
+        let message = prefix.hash();
+        let signature = MultiSig::new(vec![signer_1.try_sign(message.as_ref()).unwrap()]);
+        let tx = MintTx { prefix, signature };
+
+        // Nothing minted yet.
+        assert_eq!(
+            validate_against_mint_limit_windows(&tx, &mint_config, 50, &[]),
+            Ok(())
+        );
+
+        // 50 minted within the last 10 blocks leaves only 50 in the first window.
+        let recent_mints = vec![
+            MintRecord {
+                block_index: 10,
+                amount: 90,
+            },
+            MintRecord {
+                block_index: 45,
+                amount: 50,
+            },
+        ];
+        assert_eq!(
+            validate_against_mint_limit_windows(&tx, &mint_config, 50, &recent_mints),
+            Err(Error::AmountExceedsMintLimitWindow)
+        );
+
+        // Once the mint at block 45 leaves the first window, only the second
+        // window applies and it has 60 remaining.
+        assert_eq!(
+            validate_against_mint_limit_windows(&tx, &mint_config, 55, &recent_mints),
+            Ok(())
+        );
+
+        // A mint in the block that is being built also counts.
+        let recent_mints = vec![
+            MintRecord {
+                block_index: 10,
+                amount: 90,
+            },
+            MintRecord {
+                block_index: 45,
+                amount: 50,
+            },
+            MintRecord {
+                block_index: 55,
+                amount: 1,
+            },
+        ];
+        assert_eq!(
+            validate_against_mint_limit_windows(&tx, &mint_config, 55, &recent_mints),
+            Err(Error::AmountExceedsMintLimitWindow)
+        );
+
+        // Once the mint at block 10 leaves the second window there is room again.
+        assert_eq!(
+            validate_against_mint_limit_windows(&tx, &mint_config, 110, &recent_mints),
+            Ok(())
+        );
+    }
+
     #[test]
     fn validate_against_mint_config_rejects_signature_mismatch() {
         let mut rng = get_seeded_rng();
@@ -236,6 +415,7 @@ mod tests {
                 2,
             ),
             mint_limit: 500,
+            mint_limit_windows: vec![],
         };
 
         let prefix = MintTxPrefix {
@@ -0,0 +1,356 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! A partially constructed transaction, which several parties contribute to.
+//!
+//! The roles in the construction of a transaction are:
+//! * The creator makes an empty `PartialTx`, fixing the block version, fee and
+//!   tombstone block of the transaction.
+//! * Contributors add outputs, together with their amounts, which the
+//!   transaction must include.
+//! * Signers sign their inputs into signed contingent inputs, whose rules
+//!   require the outputs they want in exchange, and add them. A signed input
+//!   must allow the tombstone block of the transaction. Signed inputs with
+//!   partial fill rules are added together with the value of their partial fill
+//!   change that the transaction takes.
+//! * The finalizer adds membership proofs for the rings of the signed inputs,
+//!   then builds the transaction from the `PartialTx`, adding their own inputs
+//!   and outputs to balance it and pay the fee.
+//!
+//! Every addition is validated, so that each party can check the `PartialTx`
+//! before passing it on: output amounts must match their commitments, signed
+//! inputs must be valid and allow the tombstone block, and fill values must
+//! respect the partial fill rules of their signed input.
+
+use crate::{
+    tx::TxOut, Amount, BlockVersion, BlockVersionError, InputRuleError, SignedContingentInput,
+    SignedContingentInputError, TokenId, UnmaskedAmount,
+};
+use alloc::vec::Vec;
+use displaydoc::Display;
+use mc_crypto_ring_signature::{Commitment, CompressedCommitment, GeneratorCache};
+use prost::Message;
+
+/// The version of the `PartialTx` format.
+pub const PARTIAL_TX_VERSION: u32 = 1;
+
+/// An output which a partial transaction must include, with its amount.
+#[derive(Clone, Eq, Message, PartialEq)]
+pub struct PartialTxOutput {
+    /// The output
+    #[prost(message, required, tag = 1)]
+    pub tx_out: TxOut,
+
+    /// The amount and blinding of the output
+    #[prost(message, required, tag = 2)]
+    pub amount: UnmaskedAmount,
+}
+
+/// A transaction under construction by several parties.
+#[derive(Clone, Eq, Message, PartialEq)]
+pub struct PartialTx {
+    /// The version of the `PartialTx` format
+    #[prost(uint32, tag = 1)]
+    pub version: u32,
+
+    /// The block version rules the transaction is built for
+    #[prost(uint32, tag = 2)]
+    pub block_version: u32,
+
+    /// The value of the fee
+    #[prost(fixed64, tag = 3)]
+    pub fee_value: u64,
+
+    /// The token id of the fee
+    #[prost(fixed64, tag = 4)]
+    pub fee_token_id: u64,
+
+    /// The tombstone block of the transaction
+    #[prost(fixed64, tag = 5)]
+    pub tombstone_block: u64,
+
+    /// The signed contingent inputs added by signers
+    #[prost(message, repeated, tag = 6)]
+    pub signed_inputs: Vec<SignedContingentInput>,
+
+    /// The outputs added by contributors
+    #[prost(message, repeated, tag = 7)]
+    pub outputs: Vec<PartialTxOutput>,
+
+    /// The value of the partial fill change taken from each signed input, in
+    /// the same order as `signed_inputs`. Zero for signed inputs without
+    /// partial fill rules.
+    #[prost(fixed64, repeated, tag = 8)]
+    pub fill_values: Vec<u64>,
+}
+
+impl PartialTx {
+    /// Create an empty partial transaction.
+    ///
+    /// # Arguments
+    /// * `block_version` - The block version rules the transaction is built
+    ///   for. These must support signed input rules.
+    /// * `fee` - The fee of the transaction
+    /// * `tombstone_block` - The tombstone block of the transaction
+    pub fn new(
+        block_version: BlockVersion,
+        fee: Amount,
+        tombstone_block: u64,
+    ) -> Result<Self, PartialTxError> {
+        let partial_tx = Self {
+            version: PARTIAL_TX_VERSION,
+            block_version: *block_version,
+            fee_value: fee.value,
+            fee_token_id: *fee.token_id,
+            tombstone_block,
+            signed_inputs: Vec::new(),
+            outputs: Vec::new(),
+            fill_values: Vec::new(),
+        };
+        partial_tx.get_block_version()?;
+        Ok(partial_tx)
+    }
+
+    /// The block version rules the transaction is built for.
+    pub fn get_block_version(&self) -> Result<BlockVersion, PartialTxError> {
+        let block_version = BlockVersion::try_from(self.block_version)?;
+        if !block_version.signed_input_rules_are_supported() {
+            return Err(PartialTxError::SignedInputRulesNotSupported(
+                self.block_version,
+            ));
+        }
+        Ok(block_version)
+    }
+
+    /// The fee of the transaction.
+    pub fn get_fee(&self) -> Amount {
+        Amount::new(self.fee_value, TokenId::from(self.fee_token_id))
+    }
+
+    /// Add an output which the transaction must include.
+    pub fn add_output(
+        &mut self,
+        tx_out: TxOut,
+        amount: UnmaskedAmount,
+    ) -> Result<(), PartialTxError> {
+        let output = PartialTxOutput { tx_out, amount };
+        self.validate_output(&output)?;
+        if self
+            .outputs
+            .iter()
+            .any(|other| other.tx_out.public_key == output.tx_out.public_key)
+        {
+            return Err(PartialTxError::DuplicateOutput);
+        }
+        self.outputs.push(output);
+        Ok(())
+    }
+
+    /// Add a signed contingent input to the transaction. Signed inputs with
+    /// partial fill rules are filled entirely.
+    ///
+    /// Membership proofs are not required yet, they are added by the
+    /// finalizer.
+    pub fn add_signed_input(&mut self, sci: SignedContingentInput) -> Result<(), PartialTxError> {
+        let fill_value = match sci
+            .tx_in
+            .input_rules
+            .as_ref()
+            .and_then(|rules| rules.partial_fill_change.as_ref())
+        {
+            Some(partial_fill_change) => partial_fill_change.reveal_amount()?.0.value,
+            None => 0,
+        };
+        self.add_signed_input_impl(sci, fill_value)
+    }
+
+    /// Add a signed contingent input with partial fill rules to the
+    /// transaction, taking `fill_value` of its partial fill change.
+    ///
+    /// Membership proofs are not required yet, they are added by the
+    /// finalizer.
+    pub fn add_partial_fill_signed_input(
+        &mut self,
+        sci: SignedContingentInput,
+        fill_value: u64,
+    ) -> Result<(), PartialTxError> {
+        if !sci
+            .tx_in
+            .input_rules
+            .as_ref()
+            .map(|rules| rules.has_partial_fill_rules())
+            .unwrap_or(false)
+        {
+            return Err(InputRuleError::MissingPartialFillChange.into());
+        }
+        self.add_signed_input_impl(sci, fill_value)
+    }
+
+    fn add_signed_input_impl(
+        &mut self,
+        sci: SignedContingentInput,
+        fill_value: u64,
+    ) -> Result<(), PartialTxError> {
+        self.validate_signed_input(&sci)?;
+        Self::validate_fill_value(&sci, fill_value)?;
+        if self
+            .signed_inputs
+            .iter()
+            .any(|other| other.key_image() == sci.key_image())
+        {
+            return Err(PartialTxError::DuplicateKeyImage);
+        }
+        self.signed_inputs.push(sci);
+        self.fill_values.push(fill_value);
+        Ok(())
+    }
+
+    /// Validate the partial transaction: its version and block version, each
+    /// of its outputs, signed inputs and fill values, and that the outputs and
+    /// key images are unique.
+    pub fn validate(&self) -> Result<(), PartialTxError> {
+        if self.version != PARTIAL_TX_VERSION {
+            return Err(PartialTxError::UnsupportedVersion(self.version));
+        }
+        self.get_block_version()?;
+
+        for (index, output) in self.outputs.iter().enumerate() {
+            self.validate_output(output)?;
+            if self.outputs[..index]
+                .iter()
+                .any(|other| other.tx_out.public_key == output.tx_out.public_key)
+            {
+                return Err(PartialTxError::DuplicateOutput);
+            }
+        }
+
+        if self.fill_values.len() != self.signed_inputs.len() {
+            return Err(PartialTxError::WrongNumberOfFillValues);
+        }
+        for (index, (sci, fill_value)) in self
+            .signed_inputs
+            .iter()
+            .zip(self.fill_values.iter())
+            .enumerate()
+        {
+            self.validate_signed_input(sci)?;
+            Self::validate_fill_value(sci, *fill_value)?;
+            if self.signed_inputs[..index]
+                .iter()
+                .any(|other| other.key_image() == sci.key_image())
+            {
+                return Err(PartialTxError::DuplicateKeyImage);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn validate_output(&self, output: &PartialTxOutput) -> Result<(), PartialTxError> {
+        let mut generator_cache = GeneratorCache::default();
+        let generator = generator_cache.get(TokenId::from(output.amount.token_id));
+        let expected_commitment = CompressedCommitment::from(&Commitment::new(
+            output.amount.value,
+            output.amount.blinding.into(),
+            generator,
+        ));
+        if expected_commitment != output.tx_out.masked_amount.commitment {
+            return Err(PartialTxError::OutputAmountMismatch);
+        }
+
+        Ok(())
+    }
+
+    fn validate_signed_input(&self, sci: &SignedContingentInput) -> Result<(), PartialTxError> {
+        if sci.block_version != self.block_version {
+            return Err(PartialTxError::BlockVersionMismatch(
+                sci.block_version,
+                self.block_version,
+            ));
+        }
+
+        sci.validate()?;
+
+        if let Some(rules) = sci.tx_in.input_rules.as_ref() {
+            if rules.max_tombstone_block != 0 && rules.max_tombstone_block < self.tombstone_block {
+                return Err(PartialTxError::TombstoneBlockTooLarge(
+                    self.tombstone_block,
+                    rules.max_tombstone_block,
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn validate_fill_value(
+        sci: &SignedContingentInput,
+        fill_value: u64,
+    ) -> Result<(), PartialTxError> {
+        let rules = match sci.tx_in.input_rules.as_ref() {
+            Some(rules) if rules.has_partial_fill_rules() => rules,
+            _ if fill_value == 0 => return Ok(()),
+            _ => return Err(PartialTxError::UnexpectedFillValue),
+        };
+
+        let partial_fill_change = rules
+            .partial_fill_change
+            .as_ref()
+            .ok_or(InputRuleError::MissingPartialFillChange)?;
+        let (change_amount, _) = partial_fill_change.reveal_amount()?;
+        if fill_value > change_amount.value {
+            return Err(SignedContingentInputError::FillValueExceedsPartialFillChange.into());
+        }
+        if fill_value < rules.min_partial_fill_value {
+            return Err(InputRuleError::MinPartialFillValueNotReached.into());
+        }
+
+        Ok(())
+    }
+}
+
+/// An error which can occur when constructing a partial transaction
+#[derive(Display, Debug, Clone)]
+pub enum PartialTxError {
+    /// Unsupported partial transaction version: {0}
+    UnsupportedVersion(u32),
+    /// Block version: {0}
+    BlockVersion(BlockVersionError),
+    /// Signed input rules are not supported at block version {0}
+    SignedInputRulesNotSupported(u32),
+    /// The block version of a signed input ({0}) differs from the transaction
+    /// ({1})
+    BlockVersionMismatch(u32, u32),
+    /// The tombstone block ({0}) exceeds the max tombstone block of a signed
+    /// input ({1})
+    TombstoneBlockTooLarge(u64, u64),
+    /// The amount of an output does not match its commitment
+    OutputAmountMismatch,
+    /// An output was added twice
+    DuplicateOutput,
+    /// A key image appears in two signed inputs
+    DuplicateKeyImage,
+    /// The number of fill values does not match the number of signed inputs
+    WrongNumberOfFillValues,
+    /// A signed input without partial fill rules has a nonzero fill value
+    UnexpectedFillValue,
+    /// Signed contingent input: {0}
+    SignedContingentInput(SignedContingentInputError),
+}
+
+impl From<BlockVersionError> for PartialTxError {
+    fn from(src: BlockVersionError) -> Self {
+        Self::BlockVersion(src)
+    }
+}
+
+impl From<SignedContingentInputError> for PartialTxError {
+    fn from(src: SignedContingentInputError) -> Self {
+        Self::SignedContingentInput(src)
+    }
+}
+
+impl From<InputRuleError> for PartialTxError {
+    fn from(src: InputRuleError) -> Self {
+        Self::SignedContingentInput(src.into())
+    }
+}
@@ -0,0 +1,686 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! Batch verification of range proofs.
+//!
+//! Verifying a Bulletproof amounts to checking that a single multiscalar
+//! multiplication is the identity. The checks of several proofs can be
+//! combined, each with a random weight, into one multiscalar multiplication in
+//! which the scalars of the Bulletproof generators (shared by all proofs) are
+//! summed. This is much faster than verifying each proof on its own, but only
+//! tells whether all of the proofs are valid.
+//!
+//! `bulletproofs_og::RangeProof` does not expose batch verification, so this
+//! follows its verifier: the same transcript, the same verification equation,
+//! and generators derived in the same way as `BulletproofGens`.
+
+#![allow(non_snake_case)]
+
+extern crate alloc;
+
+use super::{resize_slice_to_pow2, Error, BP_GENERATORS_CAPACITY, BP_PARTY_CAPACITY};
+use crate::domain_separators::BULLETPROOF_DOMAIN_TAG;
+use alloc::{vec, vec::Vec};
+use bulletproofs_og::{ProofError, RangeProof};
+use curve25519_dalek::{
+    ristretto::{CompressedRistretto, RistrettoPoint},
+    scalar::Scalar,
+    traits::{IsIdentity, VartimeMultiscalarMul},
+};
+use mc_crypto_ring_signature::PedersenGens;
+use merlin::Transcript;
+use rand_core::{CryptoRng, RngCore};
+use sha3::{
+    digest::{ExtendableOutput, Update, XofReader},
+    Shake256,
+};
+
+/// The number of bits proven to be in range by each commitment.
+const BITS: usize = 64;
+
+lazy_static! {
+    /// The points of `BP_GENERATORS`.
+    static ref GENERATORS: BatchGenerators = BatchGenerators::new();
+}
+
+/// A range proof to check as part of a batch.
+#[derive(Clone)]
+pub struct RangeProofBatchItem {
+    /// The range proof.
+    pub range_proof: RangeProof,
+
+    /// Commitments to secret values that lie in the range [0,2^64).
+    pub commitments: Vec<CompressedRistretto>,
+
+    /// Pedersen generators on which the commitments are based.
+    pub pedersen_generators: PedersenGens,
+}
+
+/// Verifies a batch of aggregated 64-bit RangeProofs.
+///
+/// Succeeds if and only if each proof would be accepted by
+/// `check_range_proofs`. When this fails, the proofs must be checked one by
+/// one to find the invalid ones.
+///
+/// # Arguments
+/// `items` - The range proofs, with their commitments and generators.
+/// `rng` - Randomness.
+pub fn check_range_proofs_batch<T: RngCore + CryptoRng>(
+    items: &[RangeProofBatchItem],
+    rng: &mut T,
+) -> Result<(), Error> {
+    if items.is_empty() {
+        return Ok(());
+    }
+
+    let mut batch = Batch::default();
+    for item in items {
+        // The first proof does not need to be randomized.
+        let weight = if batch.scalars.is_empty() {
+            Scalar::one()
+        } else {
+            Scalar::random(rng)
+        };
+        batch.add(item, weight, rng)?;
+    }
+    batch.check()
+}
+
+/// The multiscalar multiplication which checks every proof of a batch.
+#[derive(Default)]
+struct Batch {
+    /// Scalars of points which are specific to one proof.
+    scalars: Vec<Scalar>,
+
+    /// Points which are specific to one proof.
+    points: Vec<RistrettoPoint>,
+
+    /// Scalars of the G generators, indexed by party and then by bit.
+    g_scalars: Vec<Vec<Scalar>>,
+
+    /// Scalars of the H generators, indexed by party and then by bit.
+    h_scalars: Vec<Vec<Scalar>>,
+}
+
+impl Batch {
+    /// Add the verification equation of a proof, multiplied by `weight`.
+    fn add<T: RngCore + CryptoRng>(
+        &mut self,
+        item: &RangeProofBatchItem,
+        weight: Scalar,
+        rng: &mut T,
+    ) -> Result<(), Error> {
+        let proof = ProofParts::from_bytes(&item.range_proof.to_bytes())?;
+
+        if item.commitments.is_empty() {
+            return Err(ProofError::VerificationError.into());
+        }
+        // The number of commitments must be a power of 2, as in
+        // `check_range_proofs`.
+        let commitments = resize_slice_to_pow2::<CompressedRistretto>(&item.commitments)?;
+        let n = BITS;
+        let m = commitments.len();
+        if m > BP_PARTY_CAPACITY {
+            return Err(ProofError::InvalidGeneratorsLength.into());
+        }
+        let nm = n * m;
+
+        let mut transcript = Transcript::new(BULLETPROOF_DOMAIN_TAG.as_ref());
+        transcript.append_message(b"dom-sep", b"rangeproof v1");
+        transcript.append_u64(b"n", n as u64);
+        transcript.append_u64(b"m", m as u64);
+        for commitment in commitments.iter() {
+            // Commitments are allowed to be the identity.
+            transcript.append_message(b"V", commitment.as_bytes());
+        }
+        validate_and_append_point(&mut transcript, b"A", &proof.A)?;
+        validate_and_append_point(&mut transcript, b"S", &proof.S)?;
+        let y = challenge_scalar(&mut transcript, b"y");
+        let z = challenge_scalar(&mut transcript, b"z");
+        let zz = z * z;
+        validate_and_append_point(&mut transcript, b"T_1", &proof.T_1)?;
+        validate_and_append_point(&mut transcript, b"T_2", &proof.T_2)?;
+        let x = challenge_scalar(&mut transcript, b"x");
+        transcript.append_message(b"t_x", proof.t_x.as_bytes());
+        transcript.append_message(b"t_x_blinding", proof.t_x_blinding.as_bytes());
+        transcript.append_message(b"e_blinding", proof.e_blinding.as_bytes());
+        let w = challenge_scalar(&mut transcript, b"w");
+
+        // Challenge for combining the statements of this proof.
+        let c = Scalar::random(rng);
+
+        // Inner product proof verification scalars.
+        let lg_nm = proof.L_vec.len();
+        if nm != 1 << lg_nm {
+            return Err(ProofError::VerificationError.into());
+        }
+        transcript.append_message(b"dom-sep", b"ipp v1");
+        transcript.append_u64(b"n", nm as u64);
+        let mut u_sq = Vec::with_capacity(lg_nm);
+        let mut u_inv_sq = Vec::with_capacity(lg_nm);
+        let mut all_u_inv = Scalar::one();
+        for (L, R) in proof.L_vec.iter().zip(proof.R_vec.iter()) {
+            validate_and_append_point(&mut transcript, b"L", L)?;
+            validate_and_append_point(&mut transcript, b"R", R)?;
+            let u = challenge_scalar(&mut transcript, b"u");
+            let u_inv = u.invert();
+            all_u_inv *= u_inv;
+            u_sq.push(u * u);
+            u_inv_sq.push(u_inv * u_inv);
+        }
+        let mut s = Vec::with_capacity(nm);
+        s.push(all_u_inv);
+        for i in 1..nm {
+            let lg_i = (32 - 1 - (i as u32).leading_zeros()) as usize;
+            let k = 1 << lg_i;
+            s.push(s[i - k] * u_sq[(lg_nm - 1) - lg_i]);
+        }
+
+        // Scalars of the proof's own points.
+        let A = decompress(&proof.A)?;
+        let S = decompress(&proof.S)?;
+        let T_1 = decompress(&proof.T_1)?;
+        let T_2 = decompress(&proof.T_2)?;
+        self.push(weight, A);
+        self.push(weight * x, S);
+        self.push(weight * c * x, T_1);
+        self.push(weight * c * x * x, T_2);
+        for (L, u_sq) in proof.L_vec.iter().zip(u_sq.iter()) {
+            self.push(weight * u_sq, decompress(L)?);
+        }
+        for (R, u_inv_sq) in proof.R_vec.iter().zip(u_inv_sq.iter()) {
+            self.push(weight * u_inv_sq, decompress(R)?);
+        }
+        self.push(
+            weight * (-proof.e_blinding - c * proof.t_x_blinding),
+            item.pedersen_generators.B_blinding,
+        );
+        self.push(
+            weight * (w * (proof.t_x - proof.a * proof.b) + c * (delta(n, m, &y, &z) - proof.t_x)),
+            item.pedersen_generators.B,
+        );
+        let mut z_exp = Scalar::one();
+        for commitment in commitments.iter() {
+            self.push(weight * c * zz * z_exp, decompress(commitment)?);
+            z_exp *= z;
+        }
+
+        // Scalars of the shared generators.
+        if self.g_scalars.len() < m {
+            self.g_scalars.resize(m, vec![Scalar::zero(); n]);
+            self.h_scalars.resize(m, vec![Scalar::zero(); n]);
+        }
+        let minus_z = -z;
+        let y_inv = y.invert();
+        let mut y_inv_exp = Scalar::one();
+        let mut z_exp = Scalar::one();
+        for j in 0..m {
+            let mut two_exp = Scalar::one();
+            for k in 0..n {
+                let i = j * n + k;
+                self.g_scalars[j][k] += weight * (minus_z - proof.a * s[i]);
+                self.h_scalars[j][k] +=
+                    weight * (z + y_inv_exp * (zz * z_exp * two_exp - proof.b * s[nm - 1 - i]));
+                y_inv_exp *= y_inv;
+                two_exp += two_exp;
+            }
+            z_exp *= z;
+        }
+
+        Ok(())
+    }
+
+    fn push(&mut self, scalar: Scalar, point: RistrettoPoint) {
+        self.scalars.push(scalar);
+        self.points.push(point);
+    }
+
+    /// Check that the combined verification equation holds.
+    fn check(self) -> Result<(), Error> {
+        let generators = &*GENERATORS;
+        let g_points = self
+            .g_scalars
+            .iter()
+            .enumerate()
+            .flat_map(|(j, scalars)| generators.G[j][..scalars.len()].iter());
+        let h_points = self
+            .h_scalars
+            .iter()
+            .enumerate()
+            .flat_map(|(j, scalars)| generators.H[j][..scalars.len()].iter());
+
+        let result = RistrettoPoint::vartime_multiscalar_mul(
+            self.scalars
+                .iter()
+                .chain(self.g_scalars.iter().flatten())
+                .chain(self.h_scalars.iter().flatten()),
+            self.points.iter().chain(g_points).chain(h_points),
+        );
+        if result.is_identity() {
+            Ok(())
+        } else {
+            Err(ProofError::VerificationError.into())
+        }
+    }
+}
+
+/// The parts of a serialized `RangeProof`.
+struct ProofParts {
+    A: CompressedRistretto,
+    S: CompressedRistretto,
+    T_1: CompressedRistretto,
+    T_2: CompressedRistretto,
+    t_x: Scalar,
+    t_x_blinding: Scalar,
+    e_blinding: Scalar,
+    L_vec: Vec<CompressedRistretto>,
+    R_vec: Vec<CompressedRistretto>,
+    a: Scalar,
+    b: Scalar,
+}
+
+impl ProofParts {
+    /// Parse the output of `RangeProof::to_bytes`, which is
+    /// `A || S || T_1 || T_2 || t_x || t_x_blinding || e_blinding` followed by
+    /// the inner product proof `L_0 || R_0 || ... || L_k || R_k || a || b`.
+    fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() % 32 != 0 || bytes.len() < 9 * 32 {
+            return Err(ProofError::FormatError.into());
+        }
+        let chunks: Vec<[u8; 32]> = bytes
+            .chunks_exact(32)
+            .map(|chunk| {
+                let mut bytes = [0u8; 32];
+                bytes.copy_from_slice(chunk);
+                bytes
+            })
+            .collect();
+        let point = |i: usize| CompressedRistretto(chunks[i]);
+        let scalar = |i: usize| {
+            Scalar::from_canonical_bytes(chunks[i])
+                .ok_or(Error::ProofError(ProofError::FormatError))
+        };
+
+        let num_ipp_elements = chunks.len() - 7;
+        if num_ipp_elements % 2 != 0 {
+            return Err(ProofError::FormatError.into());
+        }
+        let lg_n = (num_ipp_elements - 2) / 2;
+        if lg_n >= 32 {
+            return Err(ProofError::FormatError.into());
+        }
+
+        Ok(Self {
+            A: point(0),
+            S: point(1),
+            T_1: point(2),
+            T_2: point(3),
+            t_x: scalar(4)?,
+            t_x_blinding: scalar(5)?,
+            e_blinding: scalar(6)?,
+            L_vec: (0..lg_n).map(|i| point(7 + 2 * i)).collect(),
+            R_vec: (0..lg_n).map(|i| point(8 + 2 * i)).collect(),
+            a: scalar(7 + 2 * lg_n)?,
+            b: scalar(8 + 2 * lg_n)?,
+        })
+    }
+}
+
+/// The G and H generators of `BP_GENERATORS`, indexed by party and then by
+/// bit.
+struct BatchGenerators {
+    G: Vec<Vec<RistrettoPoint>>,
+    H: Vec<Vec<RistrettoPoint>>,
+}
+
+impl BatchGenerators {
+    fn new() -> Self {
+        let chain = |kind: u8, party: usize| {
+            let mut label = [kind, 0, 0, 0, 0];
+            label[1..].copy_from_slice(&(party as u32).to_le_bytes());
+            generators_chain(&label, BP_GENERATORS_CAPACITY)
+        };
+        Self {
+            G: (0..BP_PARTY_CAPACITY).map(|j| chain(b'G', j)).collect(),
+            H: (0..BP_PARTY_CAPACITY).map(|j| chain(b'H', j)).collect(),
+        }
+    }
+}
+
+/// The first `count` points of the chain of generators used by
+/// `BulletproofGens` for `label`.
+fn generators_chain(label: &[u8], count: usize) -> Vec<RistrettoPoint> {
+    let mut shake = Shake256::default();
+    shake.update(b"GeneratorsChain");
+    shake.update(label);
+    let mut reader = shake.finalize_xof();
+    (0..count)
+        .map(|_| {
+            let mut uniform_bytes = [0u8; 64];
+            reader.read(&mut uniform_bytes);
+            RistrettoPoint::from_uniform_bytes(&uniform_bytes)
+        })
+        .collect()
+}
+
+fn validate_and_append_point(
+    transcript: &mut Transcript,
+    label: &'static [u8],
+    point: &CompressedRistretto,
+) -> Result<(), Error> {
+    if point.is_identity() {
+        return Err(ProofError::VerificationError.into());
+    }
+    transcript.append_message(label, point.as_bytes());
+    Ok(())
+}
+
+fn challenge_scalar(transcript: &mut Transcript, label: &'static [u8]) -> Scalar {
+    let mut bytes = [0u8; 64];
+    transcript.challenge_bytes(label, &mut bytes);
+    Scalar::from_bytes_mod_order_wide(&bytes)
+}
+
+fn decompress(point: &CompressedRistretto) -> Result<RistrettoPoint, Error> {
+    point
+        .decompress()
+        .ok_or(Error::ProofError(ProofError::VerificationError))
+}
+
+/// delta(y,z) = (z - z^2) * <1, y^nm> - z^3 * <1, 2^n> * <1, z^m>
+fn delta(n: usize, m: usize, y: &Scalar, z: &Scalar) -> Scalar {
+    let sum_y = sum_of_powers(y, n * m);
+    let sum_2 = sum_of_powers(&Scalar::from(2u64), n);
+    let sum_z = sum_of_powers(z, m);
+    (z - z * z) * sum_y - z * z * z * sum_2 * sum_z
+}
+
+/// 1 + x + ... + x^(n-1)
+fn sum_of_powers(x: &Scalar, n: usize) -> Scalar {
+    let mut sum = Scalar::zero();
+    let mut x_exp = Scalar::one();
+    for _ in 0..n {
+        sum += x_exp;
+        x_exp *= x;
+    }
+    sum
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        range_proofs::{check_range_proofs, generate_range_proofs},
+        ring_signature::generators,
+    };
+    use curve25519_dalek::traits::Identity;
+    use mc_util_test_helper::{get_seeded_rng, RngCore};
+
+    fn random_item<T: RngCore + CryptoRng>(
+        num_values: usize,
+        token_id: u64,
+        rng: &mut T,
+    ) -> RangeProofBatchItem {
+        let values: Vec<u64> = (0..num_values).map(|_| rng.next_u64()).collect();
+        let blindings: Vec<Scalar> = values.iter().map(|_| Scalar::random(rng)).collect();
+        let pedersen_generators = generators(token_id);
+        let (range_proof, mut commitments) =
+            generate_range_proofs(&values, &blindings, &pedersen_generators, rng).unwrap();
+        // Drop the padding, as callers do.
+        commitments.truncate(num_values);
+        RangeProofBatchItem {
+            range_proof,
+            commitments,
+            pedersen_generators,
+        }
+    }
+
+    fn random_batch<T: RngCore + CryptoRng>(rng: &mut T) -> Vec<RangeProofBatchItem> {
+        [(1, 0), (2, 0), (3, 1), (9, 2), (16, 0)]
+            .iter()
+            .map(|(num_values, token_id)| random_item(*num_values, *token_id, rng))
+            .collect()
+    }
+
+    #[test]
+    // `check_range_proofs_batch` should accept proofs which are individually valid.
+    fn test_batch_accepts_valid_proofs() {
+        let mut rng = get_seeded_rng();
+        let items = random_batch(&mut rng);
+        for item in items.iter() {
+            check_range_proofs(
+                &item.range_proof,
+                &item.commitments,
+                &item.pedersen_generators,
+                &mut rng,
+            )
+            .unwrap();
+            check_range_proofs_batch(&[item.clone()], &mut rng).unwrap();
+        }
+        check_range_proofs_batch(&items, &mut rng).unwrap();
+        check_range_proofs_batch(&[], &mut rng).unwrap();
+    }
+
+    #[test]
+    // `check_range_proofs_batch` should reject a batch where the commitments of
+    // one proof were modified.
+    fn test_batch_rejects_wrong_commitments() {
+        let mut rng = get_seeded_rng();
+        let mut items = random_batch(&mut rng);
+        items[3].commitments[4] = RistrettoPoint::random(&mut rng).compress();
+
+        assert!(check_range_proofs_batch(&items, &mut rng).is_err());
+    }
+
+    #[test]
+    // `check_range_proofs_batch` should reject a proof checked against the
+    // generators of another token id.
+    fn test_batch_rejects_wrong_generators() {
+        let mut rng = get_seeded_rng();
+        let mut items = random_batch(&mut rng);
+        items[2].pedersen_generators = generators(5);
+
+        assert!(check_range_proofs_batch(&items, &mut rng).is_err());
+    }
+
+    #[test]
+    // `check_range_proofs_batch` should reject proofs which were swapped between
+    // commitment sets of the same size.
+    fn test_batch_rejects_swapped_proofs() {
+        let mut rng = get_seeded_rng();
+        let mut items = vec![random_item(2, 0, &mut rng), random_item(2, 0, &mut rng)];
+        let range_proof = items[0].range_proof.clone();
+        items[0].range_proof = items[1].range_proof.clone();
+        items[1].range_proof = range_proof;
+
+        assert!(check_range_proofs_batch(&items, &mut rng).is_err());
+    }
+
+    /// Assert that `check_range_proofs_batch` accepts `items` if and only if
+    /// `check_range_proofs` accepts each of them.
+    fn assert_agrees_with_check_range_proofs<T: RngCore + CryptoRng>(
+        items: &[RangeProofBatchItem],
+        rng: &mut T,
+    ) {
+        let individually_valid = items.iter().all(|item| {
+            check_range_proofs(
+                &item.range_proof,
+                &item.commitments,
+                &item.pedersen_generators,
+                rng,
+            )
+            .is_ok()
+        });
+        assert_eq!(
+            check_range_proofs_batch(items, rng).is_ok(),
+            individually_valid
+        );
+    }
+
+    /// Check `tampered` on its own, and surrounded by valid proofs.
+    fn assert_tampered_item_agrees<T: RngCore + CryptoRng>(
+        tampered: RangeProofBatchItem,
+        rng: &mut T,
+    ) {
+        assert_agrees_with_check_range_proofs(&[tampered.clone()], rng);
+        let items = vec![random_item(2, 0, rng), tampered, random_item(5, 1, rng)];
+        assert_agrees_with_check_range_proofs(&items, rng);
+    }
+
+    #[test]
+    // `check_range_proofs_batch` should agree with `check_range_proofs` for every
+    // number of commitments up to the party capacity, and beyond it.
+    fn test_batch_agrees_on_number_of_commitments() {
+        let mut rng = get_seeded_rng();
+        let items: Vec<RangeProofBatchItem> =
+            [1, 2, 3, 4, 5, 7, 8, 9, 15, 16, 17, 31, 32, 33, 63, 64]
+                .iter()
+                .map(|num_values| random_item(*num_values, 0, &mut rng))
+                .collect();
+        for item in items.iter() {
+            assert_agrees_with_check_range_proofs(&[item.clone()], &mut rng);
+        }
+        assert_agrees_with_check_range_proofs(&items, &mut rng);
+
+        // More commitments than the generators allow.
+        let mut too_many = items[items.len() - 1].clone();
+        too_many
+            .commitments
+            .push(RistrettoPoint::random(&mut rng).compress());
+        assert_tampered_item_agrees(too_many, &mut rng);
+
+        // No commitments at all.
+        let mut empty = items[0].clone();
+        empty.commitments.clear();
+        assert_tampered_item_agrees(empty, &mut rng);
+    }
+
+    #[test]
+    // `check_range_proofs_batch` should agree with `check_range_proofs` when any
+    // element of a serialized proof is tampered with.
+    fn test_batch_agrees_on_tampered_proofs() {
+        let mut rng = get_seeded_rng();
+        let item = random_item(3, 0, &mut rng);
+        let bytes = item.range_proof.to_bytes();
+        let num_chunks = bytes.len() / 32;
+
+        let mut tampered_bytes: Vec<Vec<u8>> = Vec::new();
+        for chunk in 0..num_chunks {
+            let range = chunk * 32..(chunk + 1) * 32;
+
+            // Flip a bit.
+            let mut flipped = bytes.clone();
+            flipped[range.start] ^= 1;
+            tampered_bytes.push(flipped);
+
+            // Replace the element with a random point.
+            let mut random_point = bytes.clone();
+            random_point[range.clone()]
+                .copy_from_slice(RistrettoPoint::random(&mut rng).compress().as_bytes());
+            tampered_bytes.push(random_point);
+
+            // Replace the element with the identity, or zero.
+            let mut zeroed = bytes.clone();
+            zeroed[range].copy_from_slice(&[0u8; 32]);
+            tampered_bytes.push(zeroed);
+        }
+
+        // Drop, or repeat, the last round of the inner product proof.
+        let ipp_end = bytes.len() - 2 * 32;
+        let mut dropped = bytes[..ipp_end - 2 * 32].to_vec();
+        dropped.extend_from_slice(&bytes[ipp_end..]);
+        tampered_bytes.push(dropped);
+        let mut repeated = bytes[..ipp_end].to_vec();
+        repeated.extend_from_slice(&bytes[ipp_end - 2 * 32..]);
+        tampered_bytes.push(repeated);
+
+        for tampered in tampered_bytes {
+            // Elements which can't be deserialized never reach the verifiers.
+            if let Ok(range_proof) = RangeProof::from_bytes(&tampered) {
+                let tampered = RangeProofBatchItem {
+                    range_proof,
+                    ..item.clone()
+                };
+                assert_tampered_item_agrees(tampered, &mut rng);
+            }
+        }
+    }
+
+    #[test]
+    // `check_range_proofs_batch` should agree with `check_range_proofs` when the
+    // commitments are tampered with.
+    fn test_batch_agrees_on_tampered_commitments() {
+        let mut rng = get_seeded_rng();
+        let item = random_item(3, 0, &mut rng);
+        let other_item = random_item(3, 0, &mut rng);
+
+        let mut tampered_commitments = vec![other_item.commitments];
+        for i in 0..item.commitments.len() {
+            let mut random_point = item.commitments.clone();
+            random_point[i] = RistrettoPoint::random(&mut rng).compress();
+            tampered_commitments.push(random_point);
+
+            let mut identity = item.commitments.clone();
+            identity[i] = CompressedRistretto::identity();
+            tampered_commitments.push(identity);
+
+            let mut invalid_point = item.commitments.clone();
+            invalid_point[i] = CompressedRistretto([0xff; 32]);
+            tampered_commitments.push(invalid_point);
+
+            let mut swapped = item.commitments.clone();
+            swapped.swap(i, (i + 1) % item.commitments.len());
+            tampered_commitments.push(swapped);
+
+            let mut dropped = item.commitments.clone();
+            dropped.remove(i);
+            tampered_commitments.push(dropped);
+
+            // Repeating the last commitment is the same as padding, so that one is
+            // valid.
+            let mut repeated = item.commitments.clone();
+            repeated.push(item.commitments[i]);
+            tampered_commitments.push(repeated);
+        }
+
+        for commitments in tampered_commitments {
+            let tampered = RangeProofBatchItem {
+                commitments,
+                ..item.clone()
+            };
+            assert_tampered_item_agrees(tampered, &mut rng);
+        }
+    }
+
+    #[test]
+    // `check_range_proofs_batch` should agree with `check_range_proofs` when the
+    // Pedersen generators are tampered with.
+    fn test_batch_agrees_on_tampered_generators() {
+        let mut rng = get_seeded_rng();
+        let item = random_item(3, 0, &mut rng);
+        let generators_0 = item.pedersen_generators;
+
+        let tampered_generators = [
+            generators(1),
+            PedersenGens {
+                B: generators_0.B_blinding,
+                B_blinding: generators_0.B,
+            },
+            PedersenGens {
+                B: RistrettoPoint::random(&mut rng),
+                ..generators_0
+            },
+            PedersenGens {
+                B_blinding: RistrettoPoint::random(&mut rng),
+                ..generators_0
+            },
+        ];
+
+        for pedersen_generators in tampered_generators {
+            let tampered = RangeProofBatchItem {
+                pedersen_generators,
+                ..item.clone()
+            };
+            assert_tampered_item_agrees(tampered, &mut rng);
+        }
+    }
+}
@@ -15,16 +15,25 @@ use mc_crypto_ring_signature::PedersenGens;
 use merlin::Transcript;
 use rand_core::{CryptoRng, RngCore};
 
+mod batch;
 pub mod error;
 use crate::domain_separators::BULLETPROOF_DOMAIN_TAG;
 use error::Error;
 
+pub use batch::{check_range_proofs_batch, RangeProofBatchItem};
+
+/// The `gens_capacity` of `BP_GENERATORS`, the maximum number of bits in range.
+const BP_GENERATORS_CAPACITY: usize = 64;
+
+/// The `party_capacity` of `BP_GENERATORS`.
+const BP_PARTY_CAPACITY: usize = 64;
+
 lazy_static! {
     /// Generators (base points) for Bulletproofs.
     /// The `party_capacity` is the maximum number of values in one proof. It should
     /// be at least 2 * MAX_INPUTS + MAX_OUTPUTS, which allows for inputs, pseudo outputs, and outputs.
     pub static ref BP_GENERATORS: BulletproofGens =
-        BulletproofGens::new(64, 64);
+        BulletproofGens::new(BP_GENERATORS_CAPACITY, BP_PARTY_CAPACITY);
 }
 
 /// Create an aggregated 64-bit rangeproof for a set of values.
@@ -99,13 +108,17 @@ pub fn check_range_proofs<T: RngCore + CryptoRng>(
 /// Return a vector which is the slice plus enough of the final element such
 /// that the length of the vector is a power of two.
 ///
-/// If the next power of two is greater than the type's maximum value, an Error
-/// is returned.
+/// If the slice is empty, or if the next power of two is greater than the
+/// type's maximum value, an Error is returned.
 ///
 /// # Arguments
 /// `slice` - (in) the slice with the data to use
 fn resize_slice_to_pow2<T: Clone>(slice: &[T]) -> Result<Vec<T>, Error> {
     let len: usize = slice.len();
+    if len == 0 {
+        // There is no final element to pad with.
+        return Err(Error::ResizeError);
+    }
     if let Some(next_power_of_two) = len.checked_next_power_of_two() {
         let diff = next_power_of_two - len;
         let mut pow2_slice: Vec<T> = Vec::with_capacity(next_power_of_two);
@@ -18,7 +18,8 @@ use curve25519_dalek::{
 use mc_common::HashSet;
 use mc_crypto_digestible::{DigestTranscript, Digestible, MerlinTranscript};
 use mc_crypto_ring_signature::{
-    Commitment, CompressedCommitment, GeneratorCache, KeyImage, ReducedTxOut, RingMLSAG, Scalar,
+    Commitment, CompressedCommitment, GeneratorCache, KeyImage, ReducedTxOut, RingMLSAG,
+    RingMemberCache, Scalar,
 };
 use mc_crypto_ring_signature_signer::{RingSigner, SignableInputRing};
 use mc_util_serial::prost::Message;
@@ -30,7 +31,9 @@ use zeroize::Zeroize;
 use crate::{
     constants::FEE_BLINDING,
     domain_separators::EXTENDED_MESSAGE_DOMAIN_TAG,
-    range_proofs::{check_range_proofs, generate_range_proofs},
+    range_proofs::{
+        check_range_proofs, check_range_proofs_batch, generate_range_proofs, RangeProofBatchItem,
+    },
     ring_ct::Error,
     Amount, BlockVersion,
 };
@@ -95,6 +98,44 @@ pub struct SignedInputRing {
     pub signed_digest: Option<[u8; 32]>,
 }
 
+/// A signature to verify as part of a batch, with the data it is verified
+/// against (see `SignatureRctBulletproofs::verify`).
+#[derive(Clone, Debug)]
+pub struct SignatureBatchItem<'a> {
+    /// The signature
+    pub signature: &'a SignatureRctBulletproofs,
+    /// The message which was signed
+    pub message: &'a [u8; 32],
+    /// The rings which were signed to create the signature
+    pub rings: &'a [SignedInputRing],
+    /// Output amount commitments
+    pub output_commitments: &'a [CompressedCommitment],
+    /// Amount of the implicit fee output
+    pub fee: Amount,
+}
+
+/// The checks of a signature which remain once its structure is known to be
+/// valid, apart from the ring signatures.
+struct PendingChecks {
+    /// Range proofs for the pseudo output and output commitments.
+    range_proofs: Vec<RangeProofBatchItem>,
+
+    /// Output commitments + fee commitment - pseudo output commitments.
+    balance: RistrettoPoint,
+}
+
+impl PendingChecks {
+    /// The transaction must be balanced, i.e. `balance` must be zero.
+    fn check_balance(&self) -> Result<(), Error> {
+        // RistrettoPoint::identity() is the zero point of Ristretto group, this is the
+        // same as generator.commit(Zero, Zero) and is faster.
+        if self.balance != RistrettoPoint::identity() {
+            return Err(Error::ValueNotConserved);
+        }
+        Ok(())
+    }
+}
+
 /// An RCT_TYPE_BULLETPROOFS_2 signature
 #[derive(Clone, Digestible, Eq, PartialEq, Serialize, Deserialize, Message)]
 pub struct SignatureRctBulletproofs {
@@ -189,6 +230,87 @@ impl SignatureRctBulletproofs {
         fee: Amount,
         rng: &mut CSPRNG,
     ) -> Result<(), Error> {
+        let pending_checks = self.check_structure(block_version, rings, output_commitments, fee)?;
+
+        // pseudo_output_commitments and output commitments must be in [0, 2^64).
+        for item in pending_checks.range_proofs.iter() {
+            check_range_proofs(
+                &item.range_proof,
+                &item.commitments,
+                &item.pedersen_generators,
+                rng,
+            )?;
+        }
+
+        pending_checks.check_balance()?;
+
+        self.verify_ring_signatures(
+            block_version,
+            message,
+            rings,
+            &mut RingMemberCache::default(),
+        )
+    }
+
+    /// Verify a batch of signatures made at the same block version.
+    ///
+    /// The range proofs of all of the signatures are checked together, in one
+    /// multiscalar multiplication. The MLSAGs are not batched: each one is
+    /// verified as in `verify`, except that they share the decompressed ring
+    /// members. This succeeds if and only if `verify` accepts each of the
+    /// signatures, but when it fails it does not tell which signature is
+    /// invalid.
+    ///
+    /// # Arguments
+    /// * `block_version` - This may influence details of the signatures
+    /// * `items` - The signatures, with the data they are verified against
+    /// * `rng` - randomness
+    pub fn verify_batch<CSPRNG: RngCore + CryptoRng>(
+        block_version: BlockVersion,
+        items: &[SignatureBatchItem],
+        rng: &mut CSPRNG,
+    ) -> Result<(), Error> {
+        let mut range_proofs = Vec::new();
+        let mut balance_checks = Vec::with_capacity(items.len());
+        for item in items {
+            let mut pending_checks = item.signature.check_structure(
+                block_version,
+                item.rings,
+                item.output_commitments,
+                item.fee,
+            )?;
+            range_proofs.append(&mut pending_checks.range_proofs);
+            balance_checks.push(pending_checks);
+        }
+
+        check_range_proofs_batch(&range_proofs, rng)?;
+
+        for pending_checks in balance_checks.iter() {
+            pending_checks.check_balance()?;
+        }
+
+        let mut ring_member_cache = RingMemberCache::default();
+        for item in items {
+            item.signature.verify_ring_signatures(
+                block_version,
+                item.message,
+                item.rings,
+                &mut ring_member_cache,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Check everything which does not involve the range proofs and ring
+    /// signatures, and gather the checks which remain.
+    fn check_structure(
+        &self,
+        block_version: BlockVersion,
+        rings: &[SignedInputRing],
+        output_commitments: &[CompressedCommitment],
+        fee: Amount,
+    ) -> Result<PendingChecks, Error> {
         if !block_version.masked_token_id_feature_is_supported() && fee.token_id != 0 {
             return Err(Error::TokenIdNotAllowed);
         }
@@ -275,6 +397,7 @@ impl SignatureRctBulletproofs {
 
         // pseudo_output_commitments and output commitments must be in [0, 2^64).
         // this is done differently depending on if mixed transactions are supported
+        let mut range_proofs = Vec::new();
         if !block_version.mixed_transactions_are_supported() {
             // Before mixed transactions, we expect the range proof to appear in
             // self.range_proof_bytes, not self.range_proofs
@@ -293,7 +416,11 @@ impl SignatureRctBulletproofs {
             let range_proof = RangeProof::from_bytes(&self.range_proof_bytes)
                 .map_err(|_e| Error::RangeProofDeserialization)?;
 
-            check_range_proofs(&range_proof, &commitments, generator, rng)?
+            range_proofs.push(RangeProofBatchItem {
+                range_proof,
+                commitments,
+                pedersen_generators: *generator,
+            });
         } else {
             // When mixed transactions are supported, self.range_proofs should contain
             // a range proof corresponding to each token id used in the transaction, in
@@ -337,7 +464,11 @@ impl SignatureRctBulletproofs {
                 let range_proof = RangeProof::from_bytes(range_proof)
                     .map_err(|_e| Error::RangeProofDeserialization)?;
 
-                check_range_proofs(&range_proof, &commitments, generator, rng)?
+                range_proofs.push(RangeProofBatchItem {
+                    range_proof,
+                    commitments,
+                    pedersen_generators: *generator,
+                });
             }
         }
 
@@ -378,8 +509,8 @@ impl SignatureRctBulletproofs {
         // or destroyed in any token id, as required.
         //
         // So we don't need to do a separate loop here once per token id, we can just
-        // add everything together and check for zero.
-        {
+        // add everything together and check for zero (see `PendingChecks`).
+        let balance = {
             // Compute sum of pseudo outputs
             let sum_of_pseudo_output_commitments: RistrettoPoint =
                 decompressed_pseudo_output_commitments
@@ -394,15 +525,23 @@ impl SignatureRctBulletproofs {
             // The implicit fee output.
             let generator = generator_cache.get(fee.token_id);
             let fee_commitment = generator.commit(Scalar::from(fee.value), *FEE_BLINDING);
-            let difference =
-                sum_of_output_commitments + fee_commitment - sum_of_pseudo_output_commitments;
-            // RistrettoPoint::identity() is the zero point of Ristretto group, this is the
-            // same as generator.commit(Zero, Zero) and is faster.
-            if difference != RistrettoPoint::identity() {
-                return Err(Error::ValueNotConserved);
-            }
-        }
+            sum_of_output_commitments + fee_commitment - sum_of_pseudo_output_commitments
+        };
 
+        Ok(PendingChecks {
+            range_proofs,
+            balance,
+        })
+    }
+
+    /// Verify the MLSAG of each ring.
+    fn verify_ring_signatures(
+        &self,
+        block_version: BlockVersion,
+        message: &[u8; 32],
+        rings: &[SignedInputRing],
+        ring_member_cache: &mut RingMemberCache,
+    ) -> Result<(), Error> {
         // Extend the message with the range proof and pseudo_output_commitments.
         let extended_message_digest = compute_extended_message_either_version(
             block_version,
@@ -425,7 +564,12 @@ impl SignatureRctBulletproofs {
 
             let ring_signature = &self.ring_signatures[i];
             let pseudo_output = self.pseudo_output_commitments[i];
-            ring_signature.verify(this_was_signed, &ring.members, &pseudo_output)?;
+            ring_signature.verify_with_cache(
+                this_was_signed,
+                &ring.members,
+                &pseudo_output,
+                ring_member_cache,
+            )?;
         }
 
         // Signature is valid.
@@ -1187,6 +1331,66 @@ mod rct_bulletproofs_tests {
             result.unwrap();
         }
 
+        #[test]
+        // `verify_batch` should accept a batch of valid signatures, and reject it when
+        // one of them is invalid.
+        fn verify_batch_accepts_valid_signatures_only(
+            num_inputs in 1..4usize,
+            num_mixins in 1..11usize,
+            num_token_ids in 1..3usize,
+            seed in any::<[u8; 32]>(),
+            block_version in 1..=3u32,
+        ) {
+            let block_version: BlockVersion = block_version.try_into().unwrap();
+            let num_token_ids = if block_version.mixed_transactions_are_supported() {
+                num_token_ids
+            } else {
+                1
+            };
+            let mut rng: RngType = SeedableRng::from_seed(seed);
+            let fee = 0;
+            let all_params: Vec<SignatureParams> = (0..3)
+                .map(|_| SignatureParams::random_mixed(block_version, num_inputs, num_mixins, num_token_ids, &mut rng))
+                .collect();
+            let signatures: Vec<SignatureRctBulletproofs> = all_params
+                .iter()
+                .map(|params| params.sign(fee, &mut rng).unwrap())
+                .collect();
+            let rings: Vec<Vec<SignedInputRing>> = all_params
+                .iter()
+                .map(|params| params.get_signed_input_rings())
+                .collect();
+            let output_commitments: Vec<Vec<CompressedCommitment>> = all_params
+                .iter()
+                .map(|params| params.get_output_commitments())
+                .collect();
+
+            let mut items: Vec<SignatureBatchItem> = all_params
+                .iter()
+                .enumerate()
+                .map(|(i, params)| SignatureBatchItem {
+                    signature: &signatures[i],
+                    message: &params.message,
+                    rings: &rings[i],
+                    output_commitments: &output_commitments[i],
+                    fee: Amount::new(fee, params.fee_token_id),
+                })
+                .collect();
+            SignatureRctBulletproofs::verify_batch(block_version, &items, &mut rng).unwrap();
+
+            // Swap the output commitments of two signatures.
+            items[0].output_commitments = &output_commitments[1];
+            items[1].output_commitments = &output_commitments[0];
+            assert!(SignatureRctBulletproofs::verify_batch(block_version, &items, &mut rng).is_err());
+
+            // Sign the wrong message.
+            let wrong_message = [7u8; 32];
+            items[0].output_commitments = &output_commitments[0];
+            items[1].output_commitments = &output_commitments[1];
+            items[2].message = &wrong_message;
+            assert!(SignatureRctBulletproofs::verify_batch(block_version, &items, &mut rng).is_err());
+        }
+
         #[test]
         // `verify` should reject a signature that contains an invalid MLSAG signature.
         fn test_verify_rejects_signature_signed_with_invalid_mlsag(
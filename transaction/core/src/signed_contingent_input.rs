@@ -5,7 +5,7 @@
 use crate::{
     ring_ct::{OutputSecret, PresignedInputRing, SignedInputRing},
     tx::TxIn,
-    Amount, TokenId,
+    Amount, InputRuleError, TokenId,
 };
 use alloc::vec::Vec;
 use displaydoc::Display;
@@ -76,6 +76,8 @@ impl SignedContingentInput {
     /// * The ring MLSAG actually signs the pseudo-output as claimed
     /// * The required output amounts actually correspond to the required
     ///   outputs
+    /// * The shared secrets of the partial fill outputs and change actually
+    ///   unmask their amounts
     ///
     /// Note: This does check any other rules like tombstone block, or
     /// confirm proofs of membership, which are normally added only when this
@@ -125,6 +127,14 @@ impl SignedContingentInput {
                     return Err(SignedContingentInputError::RequiredOutputMismatch);
                 }
             }
+
+            for revealed_tx_out in rules
+                .partial_fill_outputs
+                .iter()
+                .chain(rules.partial_fill_change.iter())
+            {
+                revealed_tx_out.reveal_amount()?;
+            }
         }
 
         Ok(())
@@ -183,6 +193,10 @@ pub enum SignedContingentInputError {
     MissingProofs,
     /// Invalid Ring signature: {0}
     RingSignature(RingSignatureError),
+    /// Invalid input rules: {0}
+    InputRule(InputRuleError),
+    /// The fill value exceeds the value of the partial fill change
+    FillValueExceedsPartialFillChange,
 }
 
 impl From<RingSignatureError> for SignedContingentInputError {
@@ -190,3 +204,9 @@ impl From<RingSignatureError> for SignedContingentInputError {
         Self::RingSignature(src)
     }
 }
+
+impl From<InputRuleError> for SignedContingentInputError {
+    fn from(src: InputRuleError) -> Self {
+        Self::InputRule(src)
+    }
+}
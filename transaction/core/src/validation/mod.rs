@@ -8,13 +8,13 @@ mod validate;
 pub use self::{
     error::{TransactionValidationError, TransactionValidationResult},
     validate::{
-        validate, validate_all_input_rules, validate_inputs_are_sorted,
+        validate, validate_all_input_rules, validate_batch, validate_inputs_are_sorted,
         validate_key_images_are_unique, validate_masked_token_id_exists,
         validate_membership_proofs, validate_memo_exists, validate_number_of_inputs,
         validate_number_of_outputs, validate_outputs_are_sorted,
         validate_outputs_public_keys_are_unique, validate_ring_elements_are_sorted,
         validate_ring_elements_are_unique, validate_ring_sizes, validate_signature,
         validate_that_no_masked_token_id_exists, validate_that_no_memo_exists, validate_tombstone,
-        validate_transaction_fee, validate_tx_out,
+        validate_transaction_fee, validate_tx_out, TxValidationBatchItem,
     },
 };
@@ -10,6 +10,7 @@ use super::error::{TransactionValidationError, TransactionValidationResult};
 use crate::{
     constants::*,
     membership_proofs::{derive_proof_at_index, is_membership_proof_valid},
+    ring_ct::{SignatureBatchItem, SignatureRctBulletproofs},
     tx::{Tx, TxOut, TxOutMembershipProof, TxPrefix},
     Amount, BlockVersion, TokenId,
 };
@@ -36,6 +37,110 @@ pub fn validate<R: RngCore + CryptoRng>(
     root_proofs: &[TxOutMembershipProof],
     minimum_fee: u64,
     csprng: &mut R,
+) -> TransactionValidationResult<()> {
+    validate_before_signature(tx, block_version, root_proofs)?;
+
+    validate_signature(block_version, tx, csprng)?;
+
+    validate_after_signature(tx, current_block_index, block_version, minimum_fee)
+}
+
+/// A transaction to validate as part of a batch, with the context it is
+/// validated against (see `validate`).
+#[derive(Clone, Copy, Debug)]
+pub struct TxValidationBatchItem<'a> {
+    /// A pending transaction.
+    pub tx: &'a Tx,
+    /// Membership proofs for each input ring element contained in `tx`.
+    pub root_proofs: &'a [TxOutMembershipProof],
+    /// The minimum fee for the token indicated by tx.prefix.fee_token_id
+    pub minimum_fee: u64,
+}
+
+/// Determines if each of a set of transactions is valid, with respect to the
+/// provided context.
+///
+/// This gives the same result for each transaction as `validate`, but the
+/// range proofs of all the transactions are verified together, in one
+/// multiscalar multiplication, which is considerably faster than verifying
+/// them one transaction at a time. The ring signatures are still verified one
+/// at a time, only reusing the ring members decompressed for earlier ones (see
+/// `RingMemberCache`). If the batch fails, the signatures are verified one
+/// transaction at a time to find the offending transactions.
+///
+/// # Arguments
+/// * `items` - The pending transactions, with their membership proofs and
+///   minimum fees.
+/// * `current_block_index` - The index of the current block that is being
+///   built.
+/// * `block_version` - The version of the transaction rules we are testing
+/// * `csprng` - Cryptographically secure random number generator.
+///
+/// Returns the validation result of each transaction, in the order of `items`.
+pub fn validate_batch<R: RngCore + CryptoRng>(
+    items: &[TxValidationBatchItem],
+    current_block_index: u64,
+    block_version: BlockVersion,
+    csprng: &mut R,
+) -> Vec<TransactionValidationResult<()>> {
+    let mut results: Vec<TransactionValidationResult<()>> = items
+        .iter()
+        .map(|item| validate_before_signature(item.tx, block_version, item.root_proofs))
+        .collect();
+
+    // The signatures of the transactions which passed the checks so far.
+    let pending: Vec<usize> = (0..items.len()).filter(|i| results[*i].is_ok()).collect();
+    let rings: Vec<_> = pending
+        .iter()
+        .map(|i| items[*i].tx.prefix.get_input_rings())
+        .collect();
+    let output_commitments: Vec<_> = pending
+        .iter()
+        .map(|i| items[*i].tx.prefix.output_commitments())
+        .collect();
+    let tx_prefix_hashes: Vec<_> = pending.iter().map(|i| items[*i].tx.prefix.hash()).collect();
+    let signatures: Vec<SignatureBatchItem> = pending
+        .iter()
+        .enumerate()
+        .map(|(j, i)| {
+            let tx = items[*i].tx;
+            SignatureBatchItem {
+                signature: &tx.signature,
+                message: tx_prefix_hashes[j].as_bytes(),
+                rings: &rings[j],
+                output_commitments: &output_commitments[j],
+                fee: Amount::new(tx.prefix.fee, TokenId::from(tx.prefix.fee_token_id)),
+            }
+        })
+        .collect();
+
+    if SignatureRctBulletproofs::verify_batch(block_version, &signatures, csprng).is_err() {
+        // Find the transactions whose signatures are invalid.
+        for i in pending.iter() {
+            results[*i] = validate_signature(block_version, items[*i].tx, csprng);
+        }
+    }
+
+    for i in pending {
+        if results[i].is_ok() {
+            let item = &items[i];
+            results[i] = validate_after_signature(
+                item.tx,
+                current_block_index,
+                block_version,
+                item.minimum_fee,
+            );
+        }
+    }
+
+    results
+}
+
+/// The checks made by `validate` before the signature is verified.
+fn validate_before_signature(
+    tx: &Tx,
+    block_version: BlockVersion,
+    root_proofs: &[TxOutMembershipProof],
 ) -> TransactionValidationResult<()> {
     if BlockVersion::MAX < block_version {
         return Err(TransactionValidationError::Ledger(format!(
@@ -56,10 +161,16 @@ pub fn validate<R: RngCore + CryptoRng>(
 
     validate_inputs_are_sorted(&tx.prefix)?;
 
-    validate_membership_proofs(&tx.prefix, root_proofs)?;
-
-    validate_signature(block_version, tx, csprng)?;
+    validate_membership_proofs(&tx.prefix, root_proofs)
+}
 
+/// The checks made by `validate` after the signature is verified.
+fn validate_after_signature(
+    tx: &Tx,
+    current_block_index: u64,
+    block_version: BlockVersion,
+    minimum_fee: u64,
+) -> TransactionValidationResult<()> {
     validate_transaction_fee(tx, minimum_fee)?;
 
     validate_key_images_are_unique(tx)?;
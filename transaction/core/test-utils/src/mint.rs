@@ -40,11 +40,13 @@ pub fn create_mint_config_tx_and_signers(
             token_id: *token_id,
             signer_set: SignerSet::new(vec![signer_1.public_key()], 1),
             mint_limit: rng.next_u32() as u64,
+            mint_limit_windows: vec![],
         },
         MintConfig {
             token_id: *token_id,
             signer_set: SignerSet::new(vec![signer_2.public_key(), signer_3.public_key()], 1),
             mint_limit: rng.next_u32() as u64,
+            mint_limit_windows: vec![],
         },
         MintConfig {
             token_id: *token_id,
@@ -57,6 +59,7 @@ pub fn create_mint_config_tx_and_signers(
                 2,
             ),
             mint_limit: rng.next_u32() as u64,
+            mint_limit_windows: vec![],
         },
     ];
 
@@ -1,6 +1,16 @@
 mod util;
 
-use mc_transaction_core::{tx::Tx, BlockVersion, InputRules};
+use mc_crypto_keys::RistrettoPrivate;
+use mc_transaction_core::{
+    encrypted_fog_hint::EncryptedFogHint,
+    onetime_keys::create_shared_secret,
+    ring_signature::Scalar,
+    tokens::Mob,
+    tx::{Tx, TxOut},
+    Amount, AmountMasks, BlockVersion, InputRuleError, InputRules, RevealedTxOut, Token,
+};
+use mc_transaction_core_test_utils::AccountKey;
+use mc_util_test_helper::{RngType, SeedableRng};
 
 use util::create_test_tx;
 
@@ -9,6 +19,32 @@ fn get_first_rules(tx: &Tx) -> &InputRules {
     tx.prefix.inputs[0].input_rules.as_ref().unwrap()
 }
 
+// Makes a TxOut to the recipient, revealing its amount masks
+fn create_revealed_tx_out(
+    block_version: BlockVersion,
+    amount: Amount,
+    recipient: &AccountKey,
+    rng: &mut RngType,
+) -> RevealedTxOut {
+    let tx_private_key = RistrettoPrivate::from(Scalar::random(rng));
+    let tx_out = TxOut::new(
+        block_version,
+        amount,
+        &recipient.default_subaddress(),
+        &tx_private_key,
+        EncryptedFogHint::fake_onetime_hint(rng),
+    )
+    .unwrap();
+    let shared_secret = create_shared_secret(
+        recipient.default_subaddress().view_public_key(),
+        &tx_private_key,
+    );
+    RevealedTxOut {
+        tx_out,
+        amount_masks: AmountMasks::new(&shared_secret),
+    }
+}
+
 // Gets the set of rules from the first input of a Tx, mutably
 fn get_first_rules_mut(tx: &mut Tx) -> &mut InputRules {
     tx.prefix.inputs[0].input_rules.as_mut().unwrap()
@@ -26,6 +62,7 @@ fn test_input_rules_verify_required_outputs() {
     tx.prefix.inputs[0].input_rules = Some(InputRules {
         required_outputs: vec![],
         max_tombstone_block: 0,
+        ..Default::default()
     });
 
     // Check that the Tx is following input rules (vacuously)
@@ -63,6 +100,7 @@ fn test_input_rules_verify_max_tombstone() {
     tx.prefix.inputs[0].input_rules = Some(InputRules {
         required_outputs: vec![],
         max_tombstone_block: 0,
+        ..Default::default()
     });
 
     // Check that the Tx is following input rules (vacuously)
@@ -72,6 +110,7 @@ fn test_input_rules_verify_max_tombstone() {
     tx.prefix.inputs[0].input_rules = Some(InputRules {
         required_outputs: vec![],
         max_tombstone_block: tx.prefix.tombstone_block - 1,
+        ..Default::default()
     });
 
     assert!(get_first_rules(&tx).verify(block_version, &tx).is_err());
@@ -82,3 +121,98 @@ fn test_input_rules_verify_max_tombstone() {
 
     get_first_rules(&tx).verify(block_version, &tx).unwrap();
 }
+
+// Test that input rules verification is working for partial fill rules
+#[test]
+fn test_input_rules_verify_partial_fill() {
+    let block_version = BlockVersion::FOUR;
+    let mut rng: RngType = SeedableRng::from_seed([2u8; 32]);
+
+    let (mut tx, _ledger) = create_test_tx(block_version);
+    let originator = AccountKey::random(&mut rng);
+
+    // The originator offers 1000 MOB for 500 of token id 2, and at least 100 MOB
+    // must be taken
+    let partial_fill_change = create_revealed_tx_out(
+        block_version,
+        Amount::new(1000, Mob::ID),
+        &originator,
+        &mut rng,
+    );
+    let partial_fill_output = create_revealed_tx_out(
+        block_version,
+        Amount::new(500, 2.into()),
+        &originator,
+        &mut rng,
+    );
+    tx.prefix.inputs[0].input_rules = Some(InputRules {
+        partial_fill_outputs: vec![partial_fill_output.clone()],
+        partial_fill_change: Some(partial_fill_change.clone()),
+        min_partial_fill_value: 100,
+        ..Default::default()
+    });
+
+    // The fractional outputs are missing
+    assert_eq!(
+        get_first_rules(&tx).verify(block_version, &tx),
+        Err(InputRuleError::MissingFractionalOutput)
+    );
+
+    // Taking 400 MOB requires paying at least 200 of token id 2, and the
+    // minimum is rounded up in favor of the originator
+    assert_eq!(
+        partial_fill_output.min_fractional_amount(400, 1000),
+        Ok(Amount::new(200, 2.into()))
+    );
+    assert_eq!(
+        partial_fill_output.min_fractional_amount(401, 1000),
+        Ok(Amount::new(201, 2.into()))
+    );
+    assert_eq!(
+        partial_fill_output.min_fractional_amount(1001, 1000),
+        Err(InputRuleError::FractionalChangeExceedsPartialFillChange)
+    );
+    let num_outputs = tx.prefix.outputs.len();
+    tx.prefix
+        .outputs
+        .push(partial_fill_change.fractional_output(600).unwrap().0);
+    tx.prefix
+        .outputs
+        .push(partial_fill_output.fractional_output(200).unwrap().0);
+    get_first_rules(&tx).verify(block_version, &tx).unwrap();
+
+    // Partial fill rules are not allowed before block version four
+    assert_eq!(
+        get_first_rules(&tx).verify(BlockVersion::THREE, &tx),
+        Err(InputRuleError::PartialFillRulesNotAllowed)
+    );
+
+    // Paying less than that breaks the rules
+    tx.prefix.outputs[num_outputs + 1] = partial_fill_output.fractional_output(199).unwrap().0;
+    assert_eq!(
+        get_first_rules(&tx).verify(block_version, &tx),
+        Err(InputRuleError::FractionalOutputValueTooLow)
+    );
+
+    // Taking less than the minimum fill value breaks the rules
+    tx.prefix.outputs[num_outputs] = partial_fill_change.fractional_output(950).unwrap().0;
+    tx.prefix.outputs[num_outputs + 1] = partial_fill_output.fractional_output(25).unwrap().0;
+    assert_eq!(
+        get_first_rules(&tx).verify(block_version, &tx),
+        Err(InputRuleError::MinPartialFillValueNotReached)
+    );
+
+    // Returning more than the partial fill change breaks the rules
+    tx.prefix.outputs[num_outputs] = partial_fill_change.fractional_output(1001).unwrap().0;
+    assert_eq!(
+        get_first_rules(&tx).verify(block_version, &tx),
+        Err(InputRuleError::FractionalChangeExceedsPartialFillChange)
+    );
+
+    // Partial fill outputs require a partial fill change
+    get_first_rules_mut(&mut tx).partial_fill_change = None;
+    assert_eq!(
+        get_first_rules(&tx).verify(block_version, &tx),
+        Err(InputRuleError::MissingPartialFillChange)
+    );
+}
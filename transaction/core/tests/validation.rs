@@ -19,12 +19,12 @@ use mc_transaction_core::{
     constants::{MAX_TOMBSTONE_BLOCKS, RING_SIZE},
     membership_proofs::Range,
     tokens::Mob,
-    tx::{TxOutMembershipHash, TxOutMembershipProof},
+    tx::{Tx, TxOutMembershipHash, TxOutMembershipProof},
     validation::*,
     BlockVersion, InputRules, Token,
 };
 use mc_transaction_core_test_utils::{InverseTxOutputsOrdering, INITIALIZE_LEDGER_AMOUNT};
-use mc_util_test_helper::get_seeded_rng;
+use mc_util_test_helper::{get_seeded_rng, RngType};
 
 #[test]
 // Should return MissingMemo when memos are missing in an output
@@ -819,6 +819,7 @@ fn test_input_rules_validation() {
     tx.prefix.inputs[0].input_rules = Some(InputRules {
         required_outputs: vec![first_tx_out],
         max_tombstone_block: 0,
+        ..Default::default()
     });
 
     // Check that the Tx is following input rules (the required output is there)
@@ -844,3 +845,71 @@ fn test_input_rules_validation() {
 
     validate_all_input_rules(block_version, &tx).unwrap();
 }
+
+#[test]
+// validate_batch should give the same result as validate for each transaction
+fn test_validate_batch() {
+    let mut rng = get_seeded_rng();
+
+    for block_version in BlockVersion::iterator() {
+        let fees = [Mob::MINIMUM_FEE, Mob::MINIMUM_FEE + 1, Mob::MINIMUM_FEE + 2];
+        let mut txs_and_proofs: Vec<_> = fees
+            .iter()
+            .map(|fee| {
+                let (tx, ledger) =
+                    create_test_tx_with_amount(block_version, INITIALIZE_LEDGER_AMOUNT - fee, *fee);
+                let highest_indices = tx.get_membership_proof_highest_indices();
+                let root_proofs: Vec<TxOutMembershipProof> = ledger
+                    .get_tx_out_proof_of_memberships(&highest_indices)
+                    .expect("failed getting proofs");
+                (tx, root_proofs)
+            })
+            .collect();
+        let current_block_index = txs_and_proofs[0].0.prefix.tombstone_block - 1;
+
+        let validate_all = |txs_and_proofs: &[(Tx, Vec<TxOutMembershipProof>)],
+                            rng: &mut RngType| {
+            let items: Vec<TxValidationBatchItem> = txs_and_proofs
+                .iter()
+                .map(|(tx, root_proofs)| TxValidationBatchItem {
+                    tx,
+                    root_proofs,
+                    minimum_fee: Mob::MINIMUM_FEE,
+                })
+                .collect();
+            let expected: Vec<_> = items
+                .iter()
+                .map(|item| {
+                    validate(
+                        item.tx,
+                        current_block_index,
+                        block_version,
+                        item.root_proofs,
+                        item.minimum_fee,
+                        rng,
+                    )
+                })
+                .collect();
+            let results = validate_batch(&items, current_block_index, block_version, rng);
+            assert_eq!(results, expected);
+            results
+        };
+
+        assert_eq!(
+            validate_all(&txs_and_proofs, &mut rng),
+            vec![Ok(()), Ok(()), Ok(())]
+        );
+
+        // Changing the fee of a transaction invalidates its signature.
+        txs_and_proofs[1].0.prefix.fee += 1;
+        // Missing membership proofs are reported before the signature is checked.
+        txs_and_proofs[2].1.clear();
+        let results = validate_all(&txs_and_proofs, &mut rng);
+        assert_eq!(results[0], Ok(()));
+        assert!(matches!(
+            results[1],
+            Err(TransactionValidationError::InvalidTransactionSignature(_))
+        ));
+        assert!(results[2].is_err());
+    }
+}
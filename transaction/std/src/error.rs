@@ -4,7 +4,8 @@ use displaydoc::Display;
 use mc_crypto_ring_signature_signer::Error as SignerError;
 use mc_fog_report_validation::FogPubkeyError;
 use mc_transaction_core::{
-    ring_ct::Error as RingCtError, AmountError, NewMemoError, NewTxError, TokenId,
+    ring_ct::Error as RingCtError, AmountError, NewMemoError, NewTxError, PartialTxError,
+    SignedContingentInputError, TokenId,
 };
 
 /// An error that can occur when using the TransactionBuilder
@@ -64,8 +65,33 @@ pub enum TxBuilderError {
     /// Missing membership proof
     MissingMembershipProofs,
 
+    /// Partial fill rules require a partial fill change output
+    MissingPartialFillChange,
+
     /// Signer: {0}
     Signer(SignerError),
+
+    /// No fee rate for token id {0}
+    NoFeeRate(TokenId),
+
+    /// Fee computation overflowed
+    FeeOverflow,
+
+    /// Insufficient input value for token id {0}: {1} available, {2} needed
+    InsufficientInputValue(TokenId, u64, u64),
+
+    /// Partial transaction: {0}
+    PartialTx(PartialTxError),
+
+    /// The partial transaction has a different {0}
+    PartialTxMismatch(&'static str),
+
+    /// The fog pubkey of the recipient expires at block {0}, before the
+    /// tombstone block {1}
+    FogPubkeyExpiresBeforeTombstone(u64, u64),
+
+    /// Signed contingent input: {0}
+    SignedContingentInput(SignedContingentInputError),
 }
 
 impl From<mc_util_serial::encode::Error> for TxBuilderError {
@@ -116,6 +142,18 @@ impl From<FogPubkeyError> for TxBuilderError {
     }
 }
 
+impl From<PartialTxError> for TxBuilderError {
+    fn from(src: PartialTxError) -> Self {
+        TxBuilderError::PartialTx(src)
+    }
+}
+
+impl From<SignedContingentInputError> for TxBuilderError {
+    fn from(src: SignedContingentInputError) -> Self {
+        TxBuilderError::SignedContingentInput(src)
+    }
+}
+
 impl From<NewMemoError> for TxBuilderError {
     fn from(src: NewMemoError) -> Self {
         TxBuilderError::Memo(src)
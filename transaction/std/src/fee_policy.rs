@@ -0,0 +1,143 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! Size-dependent fee policies for the transaction builder.
+//!
+//! A fee policy charges, for each token, a minimum fee plus a fee for each
+//! byte of the encoded transaction. The transaction builder knows the encoded
+//! size of the transaction before it is signed (see
+//! `TransactionBuilder::get_tx_size`), and can use a fee policy to set the
+//! fee and the value of the change output.
+
+use crate::TxBuilderError;
+use mc_transaction_core::TokenId;
+use std::collections::BTreeMap;
+
+/// The fee rate of a token.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct FeeRate {
+    /// The fee paid by every transaction, in the smallest representable units.
+    pub minimum_fee: u64,
+    /// The fee paid for each byte of the encoded transaction, in the smallest
+    /// representable units.
+    pub fee_per_byte: u64,
+}
+
+/// Fee rates for each token that fees can be paid in.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct FeePolicy {
+    rates: BTreeMap<TokenId, FeeRate>,
+}
+
+impl FeePolicy {
+    /// Set the fee rate of a token.
+    pub fn set_rate(&mut self, token_id: TokenId, rate: FeeRate) {
+        self.rates.insert(token_id, rate);
+    }
+
+    /// Get the fee rate of a token, if fees can be paid in it.
+    pub fn get_rate(&self, token_id: TokenId) -> Option<FeeRate> {
+        self.rates.get(&token_id).copied()
+    }
+
+    /// Compute the fee of a transaction with the given size, paid in the given
+    /// token.
+    pub fn compute_fee(
+        &self,
+        token_id: TokenId,
+        tx_size: TxSize,
+    ) -> Result<FeeBreakdown, TxBuilderError> {
+        let rate = self
+            .get_rate(token_id)
+            .ok_or(TxBuilderError::NoFeeRate(token_id))?;
+        let size_fee = rate
+            .fee_per_byte
+            .checked_mul(tx_size.encoded_len as u64)
+            .ok_or(TxBuilderError::FeeOverflow)?;
+        let fee = rate
+            .minimum_fee
+            .checked_add(size_fee)
+            .ok_or(TxBuilderError::FeeOverflow)?;
+        Ok(FeeBreakdown {
+            token_id,
+            rate,
+            tx_size,
+            size_fee,
+            fee,
+            change_value: None,
+        })
+    }
+}
+
+/// The size and structure of a transaction.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct TxSize {
+    /// The number of inputs.
+    pub num_inputs: usize,
+    /// The number of outputs.
+    pub num_outputs: usize,
+    /// The length of the protobuf encoding of the transaction, in bytes.
+    pub encoded_len: usize,
+}
+
+/// How the fee of a transaction was computed from a fee policy.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct FeeBreakdown {
+    /// The token the fee is paid in.
+    pub token_id: TokenId,
+    /// The fee rate of the token.
+    pub rate: FeeRate,
+    /// The size of the transaction the fee was computed for.
+    pub tx_size: TxSize,
+    /// The part of the fee charged for the size of the transaction, i.e.
+    /// `rate.fee_per_byte * tx_size.encoded_len`.
+    pub size_fee: u64,
+    /// The fee, i.e. `rate.minimum_fee + size_fee`.
+    pub fee: u64,
+    /// The value of the change output, if the fee was computed when adding it.
+    pub change_value: Option<u64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_matches::assert_matches;
+
+    #[test]
+    fn compute_fee_adds_size_fee_to_minimum_fee() {
+        let mut policy = FeePolicy::default();
+        policy.set_rate(
+            TokenId::from(1),
+            FeeRate {
+                minimum_fee: 1000,
+                fee_per_byte: 3,
+            },
+        );
+        let tx_size = TxSize {
+            num_inputs: 1,
+            num_outputs: 2,
+            encoded_len: 100,
+        };
+
+        let breakdown = policy.compute_fee(TokenId::from(1), tx_size).unwrap();
+        assert_eq!(breakdown.size_fee, 300);
+        assert_eq!(breakdown.fee, 1300);
+        assert_eq!(breakdown.tx_size, tx_size);
+
+        assert_matches!(
+            policy.compute_fee(TokenId::from(2), tx_size),
+            Err(TxBuilderError::NoFeeRate(token_id)) if token_id == TokenId::from(2)
+        );
+
+        policy.set_rate(
+            TokenId::from(2),
+            FeeRate {
+                minimum_fee: 1,
+                fee_per_byte: u64::MAX,
+            },
+        );
+        assert_matches!(
+            policy.compute_fee(TokenId::from(2), tx_size),
+            Err(TxBuilderError::FeeOverflow)
+        );
+    }
+}
@@ -8,10 +8,12 @@
 extern crate core;
 
 mod error;
+mod fee_policy;
 mod input_credentials;
 mod input_materials;
 mod memo;
 mod memo_builder;
+mod partial_tx;
 mod reserved_subaddresses;
 mod signed_contingent_input_builder;
 mod transaction_builder;
@@ -20,6 +22,7 @@ mod transaction_builder;
 pub mod test_utils;
 
 pub use error::{SignedContingentInputBuilderError, TxBuilderError};
+pub use fee_policy::{FeeBreakdown, FeePolicy, FeeRate, TxSize};
 pub use input_credentials::InputCredentials;
 pub use memo::{
     AuthenticatedSenderMemo, AuthenticatedSenderWithPaymentRequestIdMemo, BurnRedemptionMemo,
@@ -31,6 +34,7 @@ pub use memo_builder::{
     BurnRedemptionMemoBuilder, EmptyMemoBuilder, GiftCodeCancellationMemoBuilder,
     GiftCodeFundingMemoBuilder, GiftCodeSenderMemoBuilder, MemoBuilder, RTHMemoBuilder,
 };
+pub use partial_tx::add_partial_tx_output;
 pub use reserved_subaddresses::ReservedSubaddresses;
 pub use signed_contingent_input_builder::SignedContingentInputBuilder;
 pub use transaction_builder::{DefaultTxOutputsOrdering, TransactionBuilder, TxOutputsOrdering};
@@ -0,0 +1,431 @@
+// Copyright (c) 2018-2022 The MobileCoin Foundation
+
+//! Helpers for the contributors of a partial transaction.
+//!
+//! Signers add their inputs with a `SignedContingentInputBuilder` targeting the
+//! block version and tombstone block of the partial transaction, and the
+//! finalizer uses `TransactionBuilder::add_partial_tx`.
+
+use crate::{
+    transaction_builder::{create_fog_hint, create_output_with_fog_hint},
+    TxBuilderError,
+};
+use mc_account_keys::PublicAddress;
+use mc_fog_report_validation::FogPubkeyResolver;
+use mc_transaction_core::{
+    tx::TxOutConfirmationNumber, Amount, MemoPayload, PartialTx, UnmaskedAmount,
+};
+use rand_core::{CryptoRng, RngCore};
+
+/// Add an output to a partial transaction, as a contributor.
+///
+/// The memo of the output is unused.
+///
+/// # Arguments
+/// * `partial_tx` - The partial transaction to add the output to
+/// * `amount` - The amount of the output
+/// * `recipient` - The recipient's public address
+/// * `fog_resolver` - Source of validated fog keys for the recipient
+/// * `rng` - RNG used to generate blinding for commitment
+pub fn add_partial_tx_output<RNG: CryptoRng + RngCore, FPR: FogPubkeyResolver>(
+    partial_tx: &mut PartialTx,
+    amount: Amount,
+    recipient: &PublicAddress,
+    fog_resolver: &FPR,
+    rng: &mut RNG,
+) -> Result<TxOutConfirmationNumber, TxBuilderError> {
+    let block_version = partial_tx.get_block_version()?;
+
+    let (hint, pubkey_expiry) = create_fog_hint(recipient, fog_resolver, rng)?;
+    if pubkey_expiry < partial_tx.tombstone_block {
+        return Err(TxBuilderError::FogPubkeyExpiresBeforeTombstone(
+            pubkey_expiry,
+            partial_tx.tombstone_block,
+        ));
+    }
+
+    let (tx_out, shared_secret) = create_output_with_fog_hint(
+        block_version,
+        amount,
+        recipient,
+        hint,
+        |_| Ok(MemoPayload::default()),
+        rng,
+    )?;
+    let (amount, blinding) = tx_out.masked_amount.get_value(&shared_secret)?;
+
+    partial_tx.add_output(
+        tx_out,
+        UnmaskedAmount {
+            value: amount.value,
+            token_id: *amount.token_id,
+            blinding: blinding.into(),
+        },
+    )?;
+
+    Ok(TxOutConfirmationNumber::from(&shared_secret))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        test_utils::get_input_credentials, EmptyMemoBuilder, ReservedSubaddresses,
+        SignedContingentInputBuilder, TransactionBuilder,
+    };
+    use assert_matches::assert_matches;
+    use mc_account_keys::{AccountKey, DEFAULT_SUBADDRESS_INDEX};
+    use mc_crypto_keys::RistrettoPublic;
+    use mc_crypto_ring_signature_signer::NoKeysRingSigner;
+    use mc_fog_report_validation_test_utils::MockFogResolver;
+    use mc_transaction_core::{
+        constants::MILLIMOB_TO_PICOMOB,
+        get_tx_out_shared_secret, subaddress_matches_tx_out,
+        tokens::Mob,
+        validation::{validate_all_input_rules, validate_signature},
+        BlockVersion, InputRuleError, PartialTxError, SignedContingentInputError, Token, TokenId,
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    // A swap with a broker fee, built by a creator, a contributor, a signer and
+    // a finalizer, should produce a valid transaction
+    fn test_partial_tx_swap() {
+        let mut rng: StdRng = SeedableRng::from_seed([1u8; 32]);
+        let fog_resolver = MockFogResolver::default();
+
+        for block_version in 3..=*BlockVersion::MAX {
+            let block_version = BlockVersion::try_from(block_version).unwrap();
+
+            let alice = AccountKey::random(&mut rng);
+            let bob = AccountKey::random(&mut rng);
+            let dave = AccountKey::random(&mut rng);
+
+            let value = 1475 * MILLIMOB_TO_PICOMOB;
+            let token2 = TokenId::from(2);
+            let amount2 = Amount::new(100_000, token2);
+            let broker_fee = Amount::new(5_000, token2);
+
+            // The creator fixes the block version, fee and tombstone block
+            let mut partial_tx =
+                PartialTx::new(block_version, Amount::new(Mob::MINIMUM_FEE, Mob::ID), 1000)
+                    .unwrap();
+
+            // Dave contributes the output of his broker fee
+            add_partial_tx_output(
+                &mut partial_tx,
+                broker_fee,
+                &dave.default_subaddress(),
+                &fog_resolver,
+                &mut rng,
+            )
+            .unwrap();
+
+            // Alice signs her Mob, requesting amount2 in exchange
+            let input_credentials = get_input_credentials(
+                block_version,
+                Amount::new(value, Mob::ID),
+                &alice,
+                &fog_resolver,
+                &mut rng,
+            );
+            let proofs = input_credentials.membership_proofs.clone();
+            let mut builder = SignedContingentInputBuilder::new(
+                block_version,
+                input_credentials,
+                fog_resolver.clone(),
+                EmptyMemoBuilder::default(),
+            )
+            .unwrap();
+            builder.set_tombstone_block(partial_tx.tombstone_block);
+            builder
+                .add_required_output(amount2, &alice.default_subaddress(), &mut rng)
+                .unwrap();
+            let sci = builder.build(&NoKeysRingSigner {}, &mut rng).unwrap();
+            partial_tx.add_signed_input(sci.clone()).unwrap();
+
+            assert_matches!(
+                partial_tx.add_signed_input(sci),
+                Err(PartialTxError::DuplicateKeyImage)
+            );
+
+            // The partial transaction is passed on to the finalizer
+            let bytes = mc_util_serial::encode(&partial_tx);
+            let mut partial_tx: PartialTx = mc_util_serial::decode(&bytes).unwrap();
+            partial_tx.validate().unwrap();
+
+            // Bob finalizes, paying for Alice's Mob and the broker fee with token id 2
+            partial_tx.signed_inputs[0].tx_in.proofs = proofs;
+            let mut builder = TransactionBuilder::new(
+                block_version,
+                partial_tx.get_fee(),
+                fog_resolver.clone(),
+                EmptyMemoBuilder::default(),
+            )
+            .unwrap();
+            builder.add_partial_tx(partial_tx).unwrap();
+            builder.add_input(get_input_credentials(
+                block_version,
+                Amount::new(300_000, token2),
+                &bob,
+                &fog_resolver,
+                &mut rng,
+            ));
+            builder
+                .add_change_output(
+                    Amount::new(300_000 - amount2.value - broker_fee.value, token2),
+                    &ReservedSubaddresses::from(&bob),
+                    &mut rng,
+                )
+                .unwrap();
+            builder
+                .add_output(
+                    Amount::new(value - Mob::MINIMUM_FEE, Mob::ID),
+                    &bob.default_subaddress(),
+                    &mut rng,
+                )
+                .unwrap();
+
+            let tx = builder.build(&NoKeysRingSigner {}, &mut rng).unwrap();
+
+            validate_signature(block_version, &tx, &mut rng).unwrap();
+            validate_all_input_rules(block_version, &tx).unwrap();
+            assert_eq!(tx.prefix.inputs.len(), 2);
+            assert_eq!(tx.prefix.outputs.len(), 4);
+            assert_eq!(tx.prefix.tombstone_block, 1000);
+
+            let dave_output = tx
+                .prefix
+                .outputs
+                .iter()
+                .find(|tx_out| {
+                    subaddress_matches_tx_out(&dave, DEFAULT_SUBADDRESS_INDEX, tx_out).unwrap()
+                })
+                .expect("Didn't find dave's output");
+            let (amount, _) = dave_output.view_key_match(dave.view_private_key()).unwrap();
+            assert_eq!(amount, broker_fee);
+        }
+    }
+
+    #[test]
+    // A signed input with partial fill rules should be filled by the fill value
+    // recorded in the partial transaction
+    fn test_partial_tx_partial_fill() {
+        let mut rng: StdRng = SeedableRng::from_seed([2u8; 32]);
+        let fog_resolver = MockFogResolver::default();
+
+        for block_version in 4..=*BlockVersion::MAX {
+            let block_version = BlockVersion::try_from(block_version).unwrap();
+
+            let alice = AccountKey::random(&mut rng);
+            let bob = AccountKey::random(&mut rng);
+
+            let value = 1000 * MILLIMOB_TO_PICOMOB;
+            let amount = Amount::new(value, Mob::ID);
+            let token2 = TokenId::from(2);
+
+            let mut partial_tx =
+                PartialTx::new(block_version, Amount::new(Mob::MINIMUM_FEE, Mob::ID), 1000)
+                    .unwrap();
+
+            // Alice offers all of her Mob for 100_000 of token id 2, pro rata, and
+            // at least a tenth of her Mob must be taken
+            let input_credentials =
+                get_input_credentials(block_version, amount, &alice, &fog_resolver, &mut rng);
+            let proofs = input_credentials.membership_proofs.clone();
+            let mut builder = SignedContingentInputBuilder::new(
+                block_version,
+                input_credentials,
+                fog_resolver.clone(),
+                EmptyMemoBuilder::default(),
+            )
+            .unwrap();
+            builder.set_tombstone_block(partial_tx.tombstone_block);
+            builder
+                .add_partial_fill_output(
+                    Amount::new(100_000, token2),
+                    &alice.default_subaddress(),
+                    &mut rng,
+                )
+                .unwrap();
+            builder
+                .add_partial_fill_change_output(
+                    amount,
+                    &ReservedSubaddresses::from(&alice),
+                    &mut rng,
+                )
+                .unwrap();
+            builder.set_min_partial_fill_value(value / 10);
+            let sci = builder.build(&NoKeysRingSigner {}, &mut rng).unwrap();
+
+            // Fill values must respect the partial fill rules
+            assert_matches!(
+                partial_tx.add_partial_fill_signed_input(sci.clone(), value / 20),
+                Err(PartialTxError::SignedContingentInput(
+                    SignedContingentInputError::InputRule(
+                        InputRuleError::MinPartialFillValueNotReached
+                    )
+                ))
+            );
+            assert_matches!(
+                partial_tx.add_partial_fill_signed_input(sci.clone(), value + 1),
+                Err(PartialTxError::SignedContingentInput(
+                    SignedContingentInputError::FillValueExceedsPartialFillChange
+                ))
+            );
+
+            // Bob is going to take a quarter of Alice's Mob
+            partial_tx
+                .add_partial_fill_signed_input(sci, value / 4)
+                .unwrap();
+            assert_eq!(partial_tx.fill_values, vec![value / 4]);
+
+            let bytes = mc_util_serial::encode(&partial_tx);
+            let mut partial_tx: PartialTx = mc_util_serial::decode(&bytes).unwrap();
+            partial_tx.validate().unwrap();
+
+            // Tampering with the fill value is caught
+            let mut bad_partial_tx = partial_tx.clone();
+            bad_partial_tx.fill_values[0] = value / 20;
+            assert_matches!(
+                bad_partial_tx.validate(),
+                Err(PartialTxError::SignedContingentInput(
+                    SignedContingentInputError::InputRule(
+                        InputRuleError::MinPartialFillValueNotReached
+                    )
+                ))
+            );
+            bad_partial_tx.fill_values.clear();
+            assert_matches!(
+                bad_partial_tx.validate(),
+                Err(PartialTxError::WrongNumberOfFillValues)
+            );
+
+            // Bob finalizes, paying 25_000 of token id 2 for a quarter of Alice's Mob
+            partial_tx.signed_inputs[0].tx_in.proofs = proofs;
+            let mut builder = TransactionBuilder::new(
+                block_version,
+                partial_tx.get_fee(),
+                fog_resolver.clone(),
+                EmptyMemoBuilder::default(),
+            )
+            .unwrap();
+            builder.add_partial_tx(partial_tx).unwrap();
+            builder.add_input(get_input_credentials(
+                block_version,
+                Amount::new(300_000, token2),
+                &bob,
+                &fog_resolver,
+                &mut rng,
+            ));
+            builder
+                .add_change_output(
+                    Amount::new(275_000, token2),
+                    &ReservedSubaddresses::from(&bob),
+                    &mut rng,
+                )
+                .unwrap();
+            builder
+                .add_output(
+                    Amount::new(value / 4 - Mob::MINIMUM_FEE, Mob::ID),
+                    &bob.default_subaddress(),
+                    &mut rng,
+                )
+                .unwrap();
+
+            let tx = builder.build(&NoKeysRingSigner {}, &mut rng).unwrap();
+
+            validate_signature(block_version, &tx, &mut rng).unwrap();
+            validate_all_input_rules(block_version, &tx).unwrap();
+            assert_eq!(tx.prefix.outputs.len(), 4);
+
+            // Alice gets three quarters of her Mob back, and a quarter of the token id
+            // 2 that she asked for
+            let mut alice_amounts: Vec<Amount> = tx
+                .prefix
+                .outputs
+                .iter()
+                .filter_map(|tx_out| {
+                    let shared_secret = get_tx_out_shared_secret(
+                        alice.view_private_key(),
+                        &RistrettoPublic::try_from(&tx_out.public_key).unwrap(),
+                    );
+                    tx_out
+                        .masked_amount
+                        .get_value(&shared_secret)
+                        .ok()
+                        .map(|(amount, _)| amount)
+                })
+                .collect();
+            alice_amounts.sort_by_key(|amount| amount.token_id);
+            assert_eq!(
+                alice_amounts,
+                vec![
+                    Amount::new(value - value / 4, Mob::ID),
+                    Amount::new(25_000, token2)
+                ]
+            );
+        }
+    }
+
+    #[test]
+    // Additions which the transaction could not satisfy should be rejected
+    fn test_partial_tx_errors() {
+        let mut rng: StdRng = SeedableRng::from_seed([1u8; 32]);
+        let fog_resolver = MockFogResolver::default();
+        let block_version = BlockVersion::MAX;
+        let alice = AccountKey::random(&mut rng);
+        let fee = Amount::new(Mob::MINIMUM_FEE, Mob::ID);
+
+        assert_matches!(
+            PartialTx::new(BlockVersion::TWO, fee, 1000),
+            Err(PartialTxError::SignedInputRulesNotSupported(2))
+        );
+
+        let mut partial_tx = PartialTx::new(block_version, fee, 1000).unwrap();
+
+        // A signed input which expires before the tombstone block
+        let mut builder = SignedContingentInputBuilder::new(
+            block_version,
+            get_input_credentials(block_version, fee, &alice, &fog_resolver, &mut rng),
+            fog_resolver.clone(),
+            EmptyMemoBuilder::default(),
+        )
+        .unwrap();
+        builder.set_tombstone_block(500);
+        let sci = builder.build(&NoKeysRingSigner {}, &mut rng).unwrap();
+        assert_matches!(
+            partial_tx.add_signed_input(sci),
+            Err(PartialTxError::TombstoneBlockTooLarge(1000, 500))
+        );
+
+        // An output whose amount doesn't match its commitment
+        add_partial_tx_output(
+            &mut partial_tx,
+            fee,
+            &alice.default_subaddress(),
+            &fog_resolver,
+            &mut rng,
+        )
+        .unwrap();
+        let mut output = partial_tx.outputs.pop().unwrap();
+        output.amount.value += 1;
+        assert_matches!(
+            partial_tx.add_output(output.tx_out, output.amount),
+            Err(PartialTxError::OutputAmountMismatch)
+        );
+
+        // A finalizer with a different fee
+        let mut builder = TransactionBuilder::new(
+            block_version,
+            Amount::new(Mob::MINIMUM_FEE + 1, Mob::ID),
+            fog_resolver,
+            EmptyMemoBuilder::default(),
+        )
+        .unwrap();
+        assert_matches!(
+            builder.add_partial_tx(partial_tx),
+            Err(TxBuilderError::PartialTxMismatch("fee"))
+        );
+    }
+}
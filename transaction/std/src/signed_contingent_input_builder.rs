@@ -9,14 +9,15 @@ use crate::{
 };
 use core::cmp::min;
 use mc_account_keys::PublicAddress;
+use mc_crypto_keys::RistrettoPublic;
 use mc_crypto_ring_signature_signer::{RingSigner, SignableInputRing};
 use mc_fog_report_validation::FogPubkeyResolver;
 use mc_transaction_core::{
     ring_ct::OutputSecret,
     ring_signature::Scalar,
     tx::{TxIn, TxOut, TxOutConfirmationNumber},
-    Amount, BlockVersion, InputRules, MemoContext, MemoPayload, NewMemoError,
-    SignedContingentInput, TokenId, UnmaskedAmount,
+    Amount, AmountMasks, BlockVersion, InputRules, MemoContext, MemoPayload, NewMemoError,
+    RevealedTxOut, SignedContingentInput, TokenId, UnmaskedAmount,
 };
 use rand_core::{CryptoRng, RngCore};
 
@@ -37,6 +38,14 @@ pub struct SignedContingentInputBuilder<FPR: FogPubkeyResolver> {
     /// The outputs required by the rules for this signed input, and associated
     /// secrets
     required_outputs_and_secrets: Vec<(TxOut, OutputSecret)>,
+    /// The outputs whose fractional versions are required by the partial fill
+    /// rules for this signed input
+    partial_fill_outputs: Vec<RevealedTxOut>,
+    /// The change output whose fractional version is required by the partial
+    /// fill rules for this signed input
+    partial_fill_change: Option<RevealedTxOut>,
+    /// The minimum value of the partial fill change which must be filled
+    min_partial_fill_value: u64,
     /// The tombstone_block value, a block index in which the signed input
     /// expires, and can no longer be used. (This works by implying a limit
     /// on the tombstone block for any transaction which incorporates the signed
@@ -115,6 +124,9 @@ impl<FPR: FogPubkeyResolver> SignedContingentInputBuilder<FPR> {
             block_version,
             input_credentials,
             required_outputs_and_secrets: Vec::new(),
+            partial_fill_outputs: Vec::new(),
+            partial_fill_change: None,
+            min_partial_fill_value: 0,
             tombstone_block: u64::max_value(),
             fog_resolver,
             fog_tombstone_block_limit: u64::max_value(),
@@ -235,17 +247,8 @@ impl<FPR: FogPubkeyResolver> SignedContingentInputBuilder<FPR> {
         memo_fn: impl FnOnce(MemoContext) -> Result<MemoPayload, NewMemoError>,
         rng: &mut RNG,
     ) -> Result<(TxOut, TxOutConfirmationNumber), TxBuilderError> {
-        let (hint, pubkey_expiry) =
-            crate::transaction_builder::create_fog_hint(fog_hint_address, &self.fog_resolver, rng)?;
-
-        let (tx_out, shared_secret) = crate::transaction_builder::create_output_with_fog_hint(
-            self.block_version,
-            amount,
-            recipient,
-            hint,
-            memo_fn,
-            rng,
-        )?;
+        let (tx_out, shared_secret) =
+            self.create_output(amount, recipient, fog_hint_address, memo_fn, rng)?;
 
         let (amount, blinding) = tx_out
             .masked_amount
@@ -253,8 +256,6 @@ impl<FPR: FogPubkeyResolver> SignedContingentInputBuilder<FPR> {
             .expect("TransactionBuilder created an invalid Amount");
         let output_secret = OutputSecret { amount, blinding };
 
-        self.impose_tombstone_block_limit(pubkey_expiry);
-
         self.required_outputs_and_secrets
             .push((tx_out.clone(), output_secret));
 
@@ -263,6 +264,134 @@ impl<FPR: FogPubkeyResolver> SignedContingentInputBuilder<FPR> {
         Ok((tx_out, confirmation))
     }
 
+    /// Add a partial fill output to the input rules.
+    ///
+    /// The counterparty must include a fractional version of this output in
+    /// their transaction, whose value is in proportion to the fraction of the
+    /// partial fill change which they fill. The amount of this output is
+    /// revealed to anyone who sees the signed contingent input.
+    ///
+    /// If a sender memo credential has been set, this will create an
+    /// authenticated sender memo for the TxOut, which is the same for its
+    /// fractional version. Otherwise the memo will be unused.
+    ///
+    /// # Arguments
+    /// * `amount` - The amount of this output, if the offer is filled entirely
+    /// * `recipient` - The recipient's public address
+    /// * `rng` - RNG used to generate blinding for commitment
+    pub fn add_partial_fill_output<RNG: CryptoRng + RngCore>(
+        &mut self,
+        amount: Amount,
+        recipient: &PublicAddress,
+        rng: &mut RNG,
+    ) -> Result<(TxOut, TxOutConfirmationNumber), TxBuilderError> {
+        let mut mb = self
+            .memo_builder
+            .take()
+            .expect("memo builder is missing, this is a logic error");
+        let result = self.create_output(
+            amount,
+            recipient,
+            recipient,
+            |memo_ctxt| mb.make_memo_for_output(amount, recipient, memo_ctxt),
+            rng,
+        );
+        self.memo_builder = Some(mb);
+        let (tx_out, shared_secret) = result?;
+
+        self.partial_fill_outputs.push(RevealedTxOut {
+            tx_out: tx_out.clone(),
+            amount_masks: AmountMasks::new(&shared_secret),
+        });
+
+        let confirmation = TxOutConfirmationNumber::from(&shared_secret);
+
+        Ok((tx_out, confirmation))
+    }
+
+    /// Add the partial fill change output to the input rules.
+    ///
+    /// This returns the whole value offered by the signed input to the signer.
+    /// The counterparty must include a fractional version of this output in
+    /// their transaction, and the value they take from it determines the
+    /// fraction of the offer which they fill. The amount of this output is
+    /// revealed to anyone who sees the signed contingent input.
+    ///
+    /// Setting the partial fill change a second time replaces it.
+    ///
+    /// # Arguments
+    /// * `amount` - The amount of this change output, which is usually the
+    ///   amount of the signed input.
+    /// * `change_destination` - An object including both a primary address and
+    ///   a change subaddress to use to create this change output.
+    /// * `rng` - RNG used to generate blinding for commitment
+    pub fn add_partial_fill_change_output<RNG: CryptoRng + RngCore>(
+        &mut self,
+        amount: Amount,
+        change_destination: &ReservedSubaddresses,
+        rng: &mut RNG,
+    ) -> Result<(TxOut, TxOutConfirmationNumber), TxBuilderError> {
+        let mut mb = self
+            .memo_builder
+            .take()
+            .expect("memo builder is missing, this is a logic error");
+        let result = self.create_output(
+            amount,
+            &change_destination.change_subaddress,
+            &change_destination.primary_address,
+            |memo_ctxt| mb.make_memo_for_change_output(amount, change_destination, memo_ctxt),
+            rng,
+        );
+        self.memo_builder = Some(mb);
+        let (tx_out, shared_secret) = result?;
+
+        self.partial_fill_change = Some(RevealedTxOut {
+            tx_out: tx_out.clone(),
+            amount_masks: AmountMasks::new(&shared_secret),
+        });
+
+        let confirmation = TxOutConfirmationNumber::from(&shared_secret);
+
+        Ok((tx_out, confirmation))
+    }
+
+    /// Sets the minimum value of the partial fill change which the
+    /// counterparty must fill.
+    ///
+    /// # Arguments
+    /// * `min_partial_fill_value` - The minimum fill value
+    pub fn set_min_partial_fill_value(&mut self, min_partial_fill_value: u64) {
+        self.min_partial_fill_value = min_partial_fill_value;
+    }
+
+    /// Create an output for the rules, using `fog_hint_address` to construct
+    /// the fog hint, and impose the pubkey expiry of the fog hint on the
+    /// tombstone block. Returns the output and its shared secret.
+    fn create_output<RNG: CryptoRng + RngCore>(
+        &mut self,
+        amount: Amount,
+        recipient: &PublicAddress,
+        fog_hint_address: &PublicAddress,
+        memo_fn: impl FnOnce(MemoContext) -> Result<MemoPayload, NewMemoError>,
+        rng: &mut RNG,
+    ) -> Result<(TxOut, RistrettoPublic), TxBuilderError> {
+        let (hint, pubkey_expiry) =
+            crate::transaction_builder::create_fog_hint(fog_hint_address, &self.fog_resolver, rng)?;
+
+        let result = crate::transaction_builder::create_output_with_fog_hint(
+            self.block_version,
+            amount,
+            recipient,
+            hint,
+            memo_fn,
+            rng,
+        )?;
+
+        self.impose_tombstone_block_limit(pubkey_expiry);
+
+        Ok(result)
+    }
+
     /// Sets the tombstone block, clamping to smallest pubkey expiry value.
     ///
     /// # Arguments
@@ -305,6 +434,8 @@ impl<FPR: FogPubkeyResolver> SignedContingentInputBuilder<FPR> {
 
         self.required_outputs_and_secrets
             .sort_by(|(a, _), (b, _)| a.public_key.cmp(&b.public_key));
+        self.partial_fill_outputs
+            .sort_by(|a, b| a.tx_out.public_key.cmp(&b.tx_out.public_key));
 
         let (outputs, output_secrets): (Vec<TxOut>, Vec<_>) =
             self.required_outputs_and_secrets.drain(..).unzip();
@@ -316,8 +447,23 @@ impl<FPR: FogPubkeyResolver> SignedContingentInputBuilder<FPR> {
             } else {
                 self.tombstone_block
             },
+            partial_fill_outputs: self.partial_fill_outputs,
+            partial_fill_change: self.partial_fill_change,
+            min_partial_fill_value: self.min_partial_fill_value,
         };
 
+        if input_rules.has_partial_fill_rules() {
+            if !self.block_version.partial_fill_rules_are_supported() {
+                return Err(TxBuilderError::FeatureNotSupportedAtBlockVersion(
+                    *self.block_version,
+                    "partial fill rules",
+                ));
+            }
+            if input_rules.partial_fill_change.is_none() {
+                return Err(TxBuilderError::MissingPartialFillChange);
+            }
+        }
+
         // Get the tx out indices from the proofs in the input credentials,
         // after sorting has happened
         let tx_out_global_indices: Vec<u64> = self
@@ -1398,6 +1544,165 @@ pub mod tests {
         }
     }
 
+    #[test]
+    // Test that a signed contingent input with partial fill rules can be partially
+    // filled by Tx builder, and that the fractional outputs are checked
+    fn test_partial_fill_contingent_input_spendable_no_memos() {
+        let mut rng: StdRng = SeedableRng::from_seed([1u8; 32]);
+
+        for block_version in 4..=*BlockVersion::MAX {
+            let block_version = BlockVersion::try_from(block_version).unwrap();
+
+            let alice = AccountKey::random(&mut rng);
+            let bob = AccountKey::random(&mut rng);
+            let fog_resolver = MockFogResolver(Default::default());
+
+            let value = 1000 * MILLIMOB_TO_PICOMOB;
+            let amount = Amount::new(value, Mob::ID);
+            let token2 = TokenId::from(2);
+
+            // Alice provides amount of Mob
+            let input_credentials =
+                get_input_credentials(block_version, amount, &alice, &fog_resolver, &mut rng);
+
+            let proofs = input_credentials.membership_proofs.clone();
+
+            let mut builder = SignedContingentInputBuilder::new(
+                block_version,
+                input_credentials,
+                fog_resolver.clone(),
+                EmptyMemoBuilder::default(),
+            )
+            .unwrap();
+
+            // Alice offers all of her Mob for 100_000 of token id 2, pro rata, and
+            // at least a tenth of her Mob must be taken
+            builder
+                .add_partial_fill_output(
+                    Amount::new(100_000, token2),
+                    &alice.default_subaddress(),
+                    &mut rng,
+                )
+                .unwrap();
+            builder
+                .add_partial_fill_change_output(
+                    amount,
+                    &ReservedSubaddresses::from(&alice),
+                    &mut rng,
+                )
+                .unwrap();
+            builder.set_min_partial_fill_value(value / 10);
+
+            let mut sci = builder.build(&NoKeysRingSigner {}, &mut rng).unwrap();
+
+            // The contingent input should have a valid signature.
+            sci.validate().unwrap();
+            sci.tx_in.proofs = proofs;
+
+            // Bob has 300_000 of token id 2
+            let input_credentials = get_input_credentials(
+                block_version,
+                Amount::new(300_000, token2),
+                &bob,
+                &fog_resolver,
+                &mut rng,
+            );
+
+            let mut builder = TransactionBuilder::new(
+                block_version,
+                Amount::new(Mob::MINIMUM_FEE, Mob::ID),
+                fog_resolver.clone(),
+                EmptyMemoBuilder::default(),
+            )
+            .unwrap();
+
+            builder.add_input(input_credentials);
+
+            // Taking less than the minimum fill value doesn't work
+            assert_matches!(
+                builder.add_presigned_partial_fill_input(sci.clone(), value / 20),
+                Err(SignedContingentInputError::InputRule(
+                    InputRuleError::MinPartialFillValueNotReached
+                ))
+            );
+
+            // Bob takes a quarter of Alice's Mob, which costs him 25_000 of token id 2
+            builder
+                .add_presigned_partial_fill_input(sci.clone(), value / 4)
+                .unwrap();
+
+            builder
+                .add_change_output(
+                    Amount::new(275_000, token2),
+                    &ReservedSubaddresses::from(&bob),
+                    &mut rng,
+                )
+                .unwrap();
+            builder
+                .add_output(
+                    Amount::new(value / 4 - Mob::MINIMUM_FEE, Mob::ID),
+                    &bob.default_subaddress(),
+                    &mut rng,
+                )
+                .unwrap();
+
+            let tx = builder.build(&NoKeysRingSigner {}, &mut rng).unwrap();
+
+            // tx should have a valid signature, and pass all input rule checks
+            validate_signature(block_version, &tx, &mut rng).unwrap();
+            validate_all_input_rules(block_version, &tx).unwrap();
+            validate_outputs_are_sorted(&tx.prefix).unwrap();
+
+            // The transaction should have four outputs.
+            assert_eq!(tx.prefix.outputs.len(), 4);
+
+            // Alice gets three quarters of her Mob back, and a quarter of the token id
+            // 2 that she asked for
+            let mut alice_amounts: Vec<Amount> = tx
+                .prefix
+                .outputs
+                .iter()
+                .filter_map(|tx_out| {
+                    let ss = get_tx_out_shared_secret(
+                        alice.view_private_key(),
+                        &RistrettoPublic::try_from(&tx_out.public_key).unwrap(),
+                    );
+                    tx_out
+                        .masked_amount
+                        .get_value(&ss)
+                        .ok()
+                        .map(|(amount, _)| amount)
+                })
+                .collect();
+            alice_amounts.sort_by_key(|amount| amount.token_id);
+            assert_eq!(
+                alice_amounts,
+                vec![
+                    Amount::new(value - value / 4, Mob::ID),
+                    Amount::new(25_000, token2)
+                ]
+            );
+
+            // Paying Alice less than her share doesn't work
+            let rules = sci.tx_in.input_rules.as_ref().unwrap();
+            let mut bad_tx = tx.clone();
+            let (underpaid_output, _) = rules.partial_fill_outputs[0]
+                .fractional_output(24_999)
+                .unwrap();
+            for tx_out in bad_tx.prefix.outputs.iter_mut() {
+                if tx_out.public_key == underpaid_output.public_key {
+                    *tx_out = underpaid_output.clone();
+                }
+            }
+            assert_eq!(
+                validate_all_input_rules(block_version, &bad_tx),
+                Err(TransactionValidationError::InputRule(
+                    InputRuleError::FractionalOutputValueTooLow
+                ))
+            );
+        }
+    }
+
     #[test]
     // Test that if you add a signed contingent input, but don't add any of your own
     // input credentials, it fails with "AllRingsPresigned".
@@ -5,8 +5,8 @@
 //! See https://cryptonote.org/img/cryptonote_transaction.png
 
 use crate::{
-    input_materials::InputMaterials, InputCredentials, MemoBuilder, ReservedSubaddresses,
-    TxBuilderError,
+    input_materials::InputMaterials, FeeBreakdown, FeePolicy, InputCredentials, MemoBuilder,
+    ReservedSubaddresses, TxBuilderError, TxSize,
 };
 use core::{cmp::min, fmt::Debug};
 use mc_account_keys::PublicAddress;
@@ -18,14 +18,16 @@ use mc_transaction_core::{
     fog_hint::FogHint,
     onetime_keys::create_shared_secret,
     ring_ct::{InputRing, OutputSecret, SignatureRctBulletproofs},
+    ring_signature::{CurveScalar, KeyImage, RingMLSAG},
     tokens::Mob,
     tx::{Tx, TxIn, TxOut, TxOutConfirmationNumber, TxPrefix},
-    Amount, BlockVersion, MemoContext, MemoPayload, NewMemoError, SignedContingentInput,
-    SignedContingentInputError, Token, TokenId,
+    Amount, BlockVersion, CompressedCommitment, InputRuleError, MemoContext, MemoPayload,
+    NewMemoError, PartialTx, SignedContingentInput, SignedContingentInputError, Token, TokenId,
 };
 use mc_util_from_random::FromRandom;
+use prost::Message;
 use rand_core::{CryptoRng, RngCore};
-use std::cmp::Ordering;
+use std::{cmp::Ordering, collections::BTreeSet};
 
 /// A trait used to compare the transaction outputs
 pub trait TxOutputsOrdering {
@@ -151,6 +153,9 @@ impl<FPR: FogPubkeyResolver> TransactionBuilder<FPR> {
     /// requirements imposed by the signed rules, so that our transaction
     /// will be valid.
     ///
+    /// If the input has partial fill rules, the offer is filled entirely, i.e.
+    /// the fractional change returned to the signer has a value of zero.
+    ///
     /// Note: Before adding a signed_contingent_input, you probably want to:
     /// * validate it (call .validate())
     /// * check if key image appeared already (call .key_image())
@@ -162,6 +167,63 @@ impl<FPR: FogPubkeyResolver> TransactionBuilder<FPR> {
     pub fn add_presigned_input(
         &mut self,
         sci: SignedContingentInput,
+    ) -> Result<(), SignedContingentInputError> {
+        let fill_value = match sci
+            .tx_in
+            .input_rules
+            .as_ref()
+            .and_then(|rules| rules.partial_fill_change.as_ref())
+        {
+            Some(partial_fill_change) => partial_fill_change.reveal_amount()?.0.value,
+            None => 0,
+        };
+        self.add_presigned_input_impl(sci, fill_value)
+    }
+
+    /// Add a pre-signed Input with partial fill rules to the transaction,
+    /// filling `fill_value` of its partial fill change, and also fulfilling
+    /// any other requirements imposed by the signed rules, so that our
+    /// transaction will be valid.
+    ///
+    /// This adds the fractional change, which returns what we don't take to
+    /// the signer, and the fractional partial fill outputs, whose values are
+    /// rounded up in favor of the signer. The builder computes the values of
+    /// these fractional outputs from `fill_value`. The caller is responsible
+    /// for adding outputs which take `fill_value` for themselves, and for
+    /// adding inputs which pay for the fractional partial fill outputs.
+    ///
+    /// Note: Before adding a signed_contingent_input, you probably want to:
+    /// * validate it (call .validate())
+    /// * check if key image appeared already (call .key_image())
+    /// * provide merkle proofs of membership for each ring member (see
+    ///   .tx_out_global_indices)
+    ///
+    /// # Arguments
+    /// * `signed_contingent_input` - The pre-signed input we are adding
+    /// * `fill_value` - The value of the partial fill change that we take
+    pub fn add_presigned_partial_fill_input(
+        &mut self,
+        sci: SignedContingentInput,
+        fill_value: u64,
+    ) -> Result<(), SignedContingentInputError> {
+        if !sci
+            .tx_in
+            .input_rules
+            .as_ref()
+            .map(|rules| rules.has_partial_fill_rules())
+            .unwrap_or(false)
+        {
+            return Err(InputRuleError::MissingPartialFillChange.into());
+        }
+        self.add_presigned_input_impl(sci, fill_value)
+    }
+
+    // Add a pre-signed input, fulfilling its rules, and filling `fill_value` of
+    // its partial fill change if it has partial fill rules.
+    fn add_presigned_input_impl(
+        &mut self,
+        sci: SignedContingentInput,
+        fill_value: u64,
     ) -> Result<(), SignedContingentInputError> {
         // TODO: If there is a block version change that could cause an incompatibility,
         // we should check for it here, e.g. if sci.block_version differs from
@@ -197,6 +259,41 @@ impl<FPR: FogPubkeyResolver> TransactionBuilder<FPR> {
             if rules.max_tombstone_block != 0 {
                 self.impose_tombstone_block_limit(rules.max_tombstone_block);
             }
+            // 3. Partial fill rules
+            if rules.has_partial_fill_rules() {
+                let partial_fill_change = rules
+                    .partial_fill_change
+                    .as_ref()
+                    .ok_or(InputRuleError::MissingPartialFillChange)?;
+                let (change_amount, _) = partial_fill_change.reveal_amount()?;
+                if change_amount.value == 0 {
+                    return Err(InputRuleError::ZeroPartialFillChange.into());
+                }
+                if fill_value > change_amount.value {
+                    return Err(SignedContingentInputError::FillValueExceedsPartialFillChange);
+                }
+                if fill_value < rules.min_partial_fill_value {
+                    return Err(InputRuleError::MinPartialFillValueNotReached.into());
+                }
+                let mut fractional_outputs =
+                    vec![partial_fill_change.fractional_output(change_amount.value - fill_value)?];
+                for partial_fill_output in rules.partial_fill_outputs.iter() {
+                    let fractional_amount = partial_fill_output
+                        .min_fractional_amount(fill_value, change_amount.value)?;
+                    fractional_outputs
+                        .push(partial_fill_output.fractional_output(fractional_amount.value)?);
+                }
+                for (fractional_output, output_secret) in fractional_outputs {
+                    if !self
+                        .outputs_and_secrets
+                        .iter()
+                        .any(|(output, _sec)| output.public_key == fractional_output.public_key)
+                    {
+                        self.outputs_and_secrets
+                            .push((fractional_output, output_secret));
+                    }
+                }
+            }
         }
 
         self.add_presigned_input_raw(sci);
@@ -219,6 +316,60 @@ impl<FPR: FogPubkeyResolver> TransactionBuilder<FPR> {
         self.input_materials.push(InputMaterials::Presigned(sci));
     }
 
+    /// Add the signed inputs and outputs of a partial transaction, as its
+    /// finalizer, fulfilling the rules of the signed inputs.
+    ///
+    /// The builder must target the block version and fee of the partial
+    /// transaction, and its tombstone block is set to the one of the partial
+    /// transaction. Signed inputs with partial fill rules are filled by their
+    /// fill value in the partial transaction. The caller is responsible for
+    /// providing membership proofs for the rings of the signed inputs, and for
+    /// adding inputs and outputs which balance the transaction.
+    ///
+    /// # Arguments
+    /// * `partial_tx` - The partial transaction to finalize
+    pub fn add_partial_tx(&mut self, partial_tx: PartialTx) -> Result<(), TxBuilderError> {
+        partial_tx.validate()?;
+        if partial_tx.get_block_version()? != self.block_version {
+            return Err(TxBuilderError::PartialTxMismatch("block version"));
+        }
+        if partial_tx.get_fee() != self.fee {
+            return Err(TxBuilderError::PartialTxMismatch("fee"));
+        }
+        self.set_tombstone_block(partial_tx.tombstone_block);
+
+        for output in partial_tx.outputs {
+            if !self
+                .outputs_and_secrets
+                .iter()
+                .any(|(tx_out, _)| tx_out.public_key == output.tx_out.public_key)
+            {
+                self.outputs_and_secrets
+                    .push((output.tx_out, output.amount.into()));
+            }
+        }
+
+        for (sci, fill_value) in partial_tx
+            .signed_inputs
+            .into_iter()
+            .zip(partial_tx.fill_values.into_iter())
+        {
+            let has_partial_fill_rules = sci
+                .tx_in
+                .input_rules
+                .as_ref()
+                .map(|rules| rules.has_partial_fill_rules())
+                .unwrap_or(false);
+            if has_partial_fill_rules {
+                self.add_presigned_partial_fill_input(sci, fill_value)?;
+            } else {
+                self.add_presigned_input(sci)?;
+            }
+        }
+
+        Ok(())
+    }
+
     /// Add a non-change output to the transaction.
     ///
     /// If a sender memo credential has been set, this will create an
@@ -472,6 +623,228 @@ impl<FPR: FogPubkeyResolver> TransactionBuilder<FPR> {
         self.fee.token_id
     }
 
+    /// Gets the size and structure of the transaction that `build` would
+    /// produce from the current inputs, outputs, fee and tombstone block.
+    ///
+    /// The encoded length is exact: the signature is not computed, but its
+    /// size only depends on the ring sizes and on the number of inputs and
+    /// outputs of each token.
+    pub fn get_tx_size(&self) -> TxSize {
+        self.compute_tx_size(self.fee.value, self.tombstone_block, None)
+    }
+
+    /// Sets the transaction fee according to a fee policy, given the size of
+    /// the transaction formed by the current inputs and outputs.
+    ///
+    /// All the outputs, including change, should be added first, since they
+    /// contribute to the size of the transaction. To compute the value of the
+    /// change output together with the fee, use
+    /// `add_change_output_with_fee_policy` instead.
+    ///
+    /// # Arguments
+    /// * `fee_policy` - The fee rate of the fee token.
+    pub fn set_fee_from_policy(
+        &mut self,
+        fee_policy: &FeePolicy,
+    ) -> Result<FeeBreakdown, TxBuilderError> {
+        let breakdown = self.compute_fee_from_policy(fee_policy, self.tombstone_block, None)?;
+        self.set_fee(breakdown.fee)?;
+        Ok(breakdown)
+    }
+
+    /// Add a standard change output to the transaction, in the fee token, and
+    /// set the fee according to a fee policy.
+    ///
+    /// The fee is computed for the size of the transaction including the
+    /// change output, and the change output gets the value of the inputs in the
+    /// fee token which is not sent via other outputs or the fee. Other
+    /// outputs should therefore be added first. See `add_change_output` for
+    /// how change outputs are created.
+    ///
+    /// # Arguments
+    /// * `change_destination` - The primary address and change subaddress to
+    ///   use to create this change output.
+    /// * `fee_policy` - The fee rate of the fee token.
+    /// * `rng` - RNG used to generate blinding for commitment
+    pub fn add_change_output_with_fee_policy<RNG: CryptoRng + RngCore>(
+        &mut self,
+        change_destination: &ReservedSubaddresses,
+        fee_policy: &FeePolicy,
+        rng: &mut RNG,
+    ) -> Result<(TxOut, TxOutConfirmationNumber, FeeBreakdown), TxBuilderError> {
+        let token_id = self.fee.token_id;
+
+        // The size of a TxOut doesn't depend on its value or memo, so a
+        // placeholder with the same fog hint expiry is enough to size the
+        // transaction.
+        let (fog_hint, pubkey_expiry) =
+            create_fog_hint(&change_destination.primary_address, &self.fog_resolver, rng)?;
+        let (placeholder, _) = create_output_with_fog_hint(
+            self.block_version,
+            Amount::new(0, token_id),
+            &change_destination.change_subaddress,
+            fog_hint,
+            |_| Ok(MemoPayload::default()),
+            rng,
+        )?;
+        let tombstone_block = min(self.tombstone_block, pubkey_expiry);
+        let mut breakdown =
+            self.compute_fee_from_policy(fee_policy, tombstone_block, Some(&placeholder))?;
+
+        let input_value = self
+            .input_materials
+            .iter()
+            .map(InputMaterials::amount)
+            .filter(|amount| amount.token_id == token_id)
+            .try_fold(0u64, |sum, amount| sum.checked_add(amount.value))
+            .ok_or(TxBuilderError::FeeOverflow)?;
+        let needed_value = self
+            .outputs_and_secrets
+            .iter()
+            .map(|(_, secret)| secret.amount)
+            .filter(|amount| amount.token_id == token_id)
+            .try_fold(breakdown.fee, |sum, amount| sum.checked_add(amount.value))
+            .ok_or(TxBuilderError::FeeOverflow)?;
+        let change_value =
+            input_value
+                .checked_sub(needed_value)
+                .ok_or(TxBuilderError::InsufficientInputValue(
+                    token_id,
+                    input_value,
+                    needed_value,
+                ))?;
+
+        self.set_fee(breakdown.fee)?;
+        let (tx_out, confirmation) =
+            self.add_change_output(Amount::new(change_value, token_id), change_destination, rng)?;
+        breakdown.change_value = Some(change_value);
+        Ok((tx_out, confirmation, breakdown))
+    }
+
+    /// Compute the fee of the transaction from a fee policy, with the given
+    /// tombstone block and an optional additional output in the fee token.
+    fn compute_fee_from_policy(
+        &self,
+        fee_policy: &FeePolicy,
+        tombstone_block: u64,
+        extra_output: Option<&TxOut>,
+    ) -> Result<FeeBreakdown, TxBuilderError> {
+        // The fee is encoded as a varint, so the size of the transaction depends
+        // on the fee. Both only grow with each other, so iterating from zero
+        // reaches the smallest fee which pays for the size it causes.
+        let mut fee_value = 0;
+        loop {
+            let tx_size = self.compute_tx_size(fee_value, tombstone_block, extra_output);
+            let breakdown = fee_policy.compute_fee(self.fee.token_id, tx_size)?;
+            if breakdown.fee == fee_value {
+                return Ok(breakdown);
+            }
+            fee_value = breakdown.fee;
+        }
+    }
+
+    /// Compute the size of the transaction with the given fee and tombstone
+    /// block, and an optional additional output in the fee token.
+    fn compute_tx_size(
+        &self,
+        fee_value: u64,
+        tombstone_block: u64,
+        extra_output: Option<&TxOut>,
+    ) -> TxSize {
+        let fee = Amount::new(fee_value, self.fee.token_id);
+        let inputs: Vec<TxIn> = self.input_materials.iter().map(TxIn::from).collect();
+        let outputs: Vec<TxOut> = self
+            .outputs_and_secrets
+            .iter()
+            .map(|(tx_out, _)| tx_out)
+            .chain(extra_output)
+            .cloned()
+            .collect();
+        let output_token_ids: Vec<TokenId> = self
+            .outputs_and_secrets
+            .iter()
+            .map(|(_, secret)| secret.amount.token_id)
+            .chain(extra_output.map(|_| fee.token_id))
+            .collect();
+
+        let tx = Tx {
+            prefix: TxPrefix::new(inputs, outputs, fee, tombstone_block),
+            signature: self.placeholder_signature(fee.token_id, &output_token_ids),
+        };
+
+        TxSize {
+            num_inputs: tx.prefix.inputs.len(),
+            num_outputs: tx.prefix.outputs.len(),
+            encoded_len: tx.encoded_len(),
+        }
+    }
+
+    /// A signature with the same encoded size as the one `build` creates for
+    /// the current inputs and outputs with the given token ids.
+    fn placeholder_signature(
+        &self,
+        fee_token_id: TokenId,
+        output_token_ids: &[TokenId],
+    ) -> SignatureRctBulletproofs {
+        let ring_signatures: Vec<RingMLSAG> = self
+            .input_materials
+            .iter()
+            .map(|input| match input {
+                InputMaterials::Signable(input) => RingMLSAG {
+                    c_zero: CurveScalar::default(),
+                    responses: vec![CurveScalar::default(); 2 * input.ring.len()],
+                    key_image: KeyImage::default(),
+                },
+                InputMaterials::Presigned(input) => input.mlsag.clone(),
+            })
+            .collect();
+        let pseudo_output_commitments =
+            vec![CompressedCommitment::default(); self.input_materials.len()];
+        let input_token_ids: Vec<TokenId> = self
+            .input_materials
+            .iter()
+            .map(|input| input.amount().token_id)
+            .collect();
+
+        if !self.block_version.mixed_transactions_are_supported() {
+            let num_values = input_token_ids.len() + output_token_ids.len();
+            return SignatureRctBulletproofs {
+                ring_signatures,
+                pseudo_output_commitments,
+                range_proof_bytes: vec![0u8; range_proof_len(num_values)],
+                ..Default::default()
+            };
+        }
+
+        // One range proof for each token id, as in `build`.
+        let mut token_ids: BTreeSet<TokenId> = input_token_ids
+            .iter()
+            .chain(output_token_ids)
+            .copied()
+            .collect();
+        token_ids.insert(fee_token_id);
+        let range_proofs = token_ids
+            .into_iter()
+            .map(|token_id| {
+                let num_values = input_token_ids
+                    .iter()
+                    .chain(output_token_ids)
+                    .filter(|id| **id == token_id)
+                    .count();
+                vec![0u8; range_proof_len(num_values)]
+            })
+            .collect();
+
+        SignatureRctBulletproofs {
+            ring_signatures,
+            pseudo_output_commitments,
+            range_proof_bytes: vec![],
+            range_proofs,
+            pseudo_output_token_ids: input_token_ids.iter().map(|id| **id).collect(),
+            output_token_ids: output_token_ids.iter().map(|id| **id).collect(),
+        }
+    }
+
     /// Consume the builder and return the transaction.
     pub fn build<RNG: CryptoRng + RngCore, S: RingSigner + ?Sized>(
         self,
@@ -618,6 +991,15 @@ impl<FPR: FogPubkeyResolver> TransactionBuilder<FPR> {
     }
 }
 
+/// The length in bytes of an aggregated 64-bit range proof for `num_values`
+/// values, which are padded to a power of two.
+fn range_proof_len(num_values: usize) -> usize {
+    let num_bits = 64 * num_values.max(1).next_power_of_two();
+    // A, S, T_1, T_2, t_x, t_x_blinding and e_blinding, then the inner product
+    // proof: L and R for each halving of the bit vectors, and a and b.
+    32 * (7 + 2 * num_bits.trailing_zeros() as usize + 2)
+}
+
 /// Creates a TxOut that sends `value` to `recipient` using the provided
 /// `fog_hint`.
 ///
@@ -684,9 +1066,9 @@ pub mod transaction_builder_tests {
     use super::*;
     use crate::{
         test_utils::{create_output, get_input_credentials, get_ring, get_transaction},
-        BurnRedemptionMemoBuilder, EmptyMemoBuilder, GiftCodeCancellationMemoBuilder,
-        GiftCodeFundingMemoBuilder, GiftCodeSenderMemoBuilder, MemoType, RTHMemoBuilder,
-        SenderMemoCredential,
+        BurnRedemptionMemoBuilder, EmptyMemoBuilder, FeePolicy, FeeRate,
+        GiftCodeCancellationMemoBuilder, GiftCodeFundingMemoBuilder, GiftCodeSenderMemoBuilder,
+        MemoType, RTHMemoBuilder, SenderMemoCredential,
     };
     use assert_matches::assert_matches;
     use maplit::btreemap;
@@ -3708,4 +4090,167 @@ pub mod transaction_builder_tests {
             );
         }
     }
+
+    #[test]
+    // get_tx_size should give the size and structure of the built transaction
+    fn test_get_tx_size() {
+        let mut rng: StdRng = SeedableRng::from_seed([1u8; 32]);
+        let fog_resolver = MockFogResolver::default();
+
+        for block_version in BlockVersion::iterator() {
+            let sender = AccountKey::random(&mut rng);
+            let sender_change_dest = ReservedSubaddresses::from(&sender);
+            let recipient = AccountKey::random(&mut rng);
+            let value = 1475 * MILLIMOB_TO_PICOMOB;
+            let change_value = 128 * MILLIMOB_TO_PICOMOB;
+
+            let mut memo_builder = RTHMemoBuilder::default();
+            memo_builder.set_sender_credential(SenderMemoCredential::from(&sender));
+            memo_builder.enable_destination_memo();
+
+            let mut transaction_builder = TransactionBuilder::new(
+                block_version,
+                Amount::new(Mob::MINIMUM_FEE, Mob::ID),
+                fog_resolver.clone(),
+                memo_builder,
+            )
+            .unwrap();
+            transaction_builder.set_tombstone_block(2000);
+
+            for input_value in [value, change_value] {
+                transaction_builder.add_input(get_input_credentials(
+                    block_version,
+                    Amount::new(input_value, Mob::ID),
+                    &sender,
+                    &fog_resolver,
+                    &mut rng,
+                ));
+            }
+            transaction_builder
+                .add_output(
+                    Amount::new(value - Mob::MINIMUM_FEE, Mob::ID),
+                    &recipient.default_subaddress(),
+                    &mut rng,
+                )
+                .unwrap();
+            transaction_builder
+                .add_change_output(
+                    Amount::new(change_value, Mob::ID),
+                    &sender_change_dest,
+                    &mut rng,
+                )
+                .unwrap();
+
+            // Mixed transactions have one range proof for each token
+            if block_version.mixed_transactions_are_supported() {
+                let amount = Amount::new(999999, 2.into());
+                transaction_builder.add_input(get_input_credentials(
+                    block_version,
+                    amount,
+                    &sender,
+                    &fog_resolver,
+                    &mut rng,
+                ));
+                transaction_builder
+                    .add_output(amount, &recipient.default_subaddress(), &mut rng)
+                    .unwrap();
+            }
+
+            let tx_size = transaction_builder.get_tx_size();
+            let tx = transaction_builder
+                .build(&NoKeysRingSigner {}, &mut rng)
+                .unwrap();
+
+            assert_eq!(tx_size.num_inputs, tx.prefix.inputs.len());
+            assert_eq!(tx_size.num_outputs, tx.prefix.outputs.len());
+            assert_eq!(tx_size.encoded_len, tx.encoded_len());
+        }
+    }
+
+    #[test]
+    // add_change_output_with_fee_policy should set the fee for the size of the
+    // transaction, and send the rest of the inputs to the change output
+    fn test_add_change_output_with_fee_policy() {
+        let mut rng: StdRng = SeedableRng::from_seed([1u8; 32]);
+        let fog_resolver = MockFogResolver::default();
+        let rate = FeeRate {
+            minimum_fee: Mob::MINIMUM_FEE,
+            fee_per_byte: 1000,
+        };
+        let mut fee_policy = FeePolicy::default();
+        fee_policy.set_rate(Mob::ID, rate);
+
+        for block_version in BlockVersion::iterator() {
+            let sender = AccountKey::random(&mut rng);
+            let sender_change_dest = ReservedSubaddresses::from(&sender);
+            let recipient = AccountKey::random(&mut rng);
+            let value = 1475 * MILLIMOB_TO_PICOMOB;
+            let outlay_value = 1000 * MILLIMOB_TO_PICOMOB;
+
+            let make_builder = |outlay_value: u64, rng: &mut StdRng| {
+                let mut memo_builder = RTHMemoBuilder::default();
+                memo_builder.set_sender_credential(SenderMemoCredential::from(&sender));
+                memo_builder.enable_destination_memo();
+
+                let mut transaction_builder = TransactionBuilder::new(
+                    block_version,
+                    Amount::new(0, Mob::ID),
+                    fog_resolver.clone(),
+                    memo_builder,
+                )
+                .unwrap();
+                transaction_builder.set_tombstone_block(2000);
+                transaction_builder.add_input(get_input_credentials(
+                    block_version,
+                    Amount::new(value, Mob::ID),
+                    &sender,
+                    &fog_resolver,
+                    rng,
+                ));
+                transaction_builder
+                    .add_output(
+                        Amount::new(outlay_value, Mob::ID),
+                        &recipient.default_subaddress(),
+                        rng,
+                    )
+                    .unwrap();
+                transaction_builder
+            };
+
+            // Paying more than the inputs is an error
+            assert_matches!(
+                make_builder(value, &mut rng).add_change_output_with_fee_policy(
+                    &sender_change_dest,
+                    &fee_policy,
+                    &mut rng
+                ),
+                Err(TxBuilderError::InsufficientInputValue(..))
+            );
+
+            let mut transaction_builder = make_builder(outlay_value, &mut rng);
+            let (change, _confirmation, breakdown) = transaction_builder
+                .add_change_output_with_fee_policy(&sender_change_dest, &fee_policy, &mut rng)
+                .unwrap();
+            assert_eq!(transaction_builder.get_fee(), breakdown.fee);
+            assert_eq!(transaction_builder.get_tx_size(), breakdown.tx_size);
+
+            let tx = transaction_builder
+                .build(&NoKeysRingSigner {}, &mut rng)
+                .unwrap();
+
+            assert_eq!(breakdown.token_id, Mob::ID);
+            assert_eq!(breakdown.rate, rate);
+            assert_eq!(breakdown.tx_size.encoded_len, tx.encoded_len());
+            assert_eq!(
+                breakdown.size_fee,
+                rate.fee_per_byte * tx.encoded_len() as u64
+            );
+            assert_eq!(breakdown.fee, rate.minimum_fee + breakdown.size_fee);
+            assert_eq!(tx.prefix.fee, breakdown.fee);
+
+            let (amount, _) = change.view_key_match(sender.view_private_key()).unwrap();
+            assert_eq!(amount.value, value - outlay_value - breakdown.fee);
+            assert_eq!(breakdown.change_value, Some(amount.value));
+        }
+    }
 }
@@ -57,7 +57,7 @@ impl FromStr for BlockVersion {
 impl BlockVersion {
     /// The maximum value of block_version that this build of
     /// mc-transaction-core has support for
-    pub const MAX: Self = Self(3);
+    pub const MAX: Self = Self(5);
 
     /// Refers to the block version number at network launch.
     pub const ZERO: Self = Self(0);
@@ -71,6 +71,12 @@ impl BlockVersion {
     /// Constant for block version three
     pub const THREE: Self = Self(3);
 
+    /// Constant for block version four
+    pub const FOUR: Self = Self(4);
+
+    /// Constant for block version five
+    pub const FIVE: Self = Self(5);
+
     /// Iterator over block versions from one up to max, inclusive. For use in
     /// tests.
     pub fn iterator() -> BlockVersionIterator {
@@ -119,6 +125,21 @@ impl BlockVersion {
     pub fn signed_input_rules_are_supported(&self) -> bool {
         self.0 >= 3
     }
+
+    /// Partial fill rules in signed input rules are introduced in v4.
+    pub fn partial_fill_rules_are_supported(&self) -> bool {
+        self.0 >= 4
+    }
+
+    /// Nested signer sets in mint configurations are introduced in v5.
+    pub fn nested_signer_sets_are_supported(&self) -> bool {
+        self.0 >= 5
+    }
+
+    /// Mint limit windows in mint configurations are introduced in v5.
+    pub fn mint_limit_windows_are_supported(&self) -> bool {
+        self.0 >= 5
+    }
 }
 
 impl Deref for BlockVersion {
@@ -30,6 +30,20 @@ fn main() {
                     "Memo: {}",
                     printable_wrapper.get_payment_request().get_memo()
                 );
+                println!(
+                    "Token id: {}",
+                    printable_wrapper.get_payment_request().get_token_id()
+                );
+                println!(
+                    "Payment request id: {}",
+                    printable_wrapper
+                        .get_payment_request()
+                        .get_payment_request_id()
+                );
+                println!(
+                    "Expiry: {}",
+                    printable_wrapper.get_payment_request().get_expiry()
+                );
             } else if printable_wrapper.has_transfer_payload() {
                 println!("B58 decoded successfully to a PrintableWrapper with a TransferPayload");
                 println!(
@@ -23,6 +23,28 @@ impl AdminServer {
         id: String,
         get_config_json: Option<GetConfigJsonFn>,
         logger: Logger,
+    ) -> Result<Self, grpcio::Error> {
+        Self::start_with_additional_services(
+            env,
+            admin_listen_uri,
+            name,
+            id,
+            get_config_json,
+            Vec::new(),
+            logger,
+        )
+    }
+
+    /// Initializes and starts the admin server, additionally serving
+    /// service-specific admin APIs.
+    pub fn start_with_additional_services(
+        env: Option<Arc<Environment>>,
+        admin_listen_uri: &AdminUri,
+        name: String,
+        id: String,
+        get_config_json: Option<GetConfigJsonFn>,
+        additional_services: Vec<grpcio::Service>,
+        logger: Logger,
     ) -> Result<Self, grpcio::Error> {
         log::info!(
             logger,
@@ -45,11 +67,14 @@ impl AdminServer {
         let health_service = HealthService::new(None, logger.clone()).into_service();
         let build_info_service = BuildInfoService::new(logger.clone()).into_service();
 
-        let server_builder = grpcio::ServerBuilder::new(env)
-            .register_service(admin_service)
-            .register_service(health_service)
-            .register_service(build_info_service)
-            .bind_using_uri(admin_listen_uri, logger.clone());
+        let server_builder = additional_services.into_iter().fold(
+            grpcio::ServerBuilder::new(env)
+                .register_service(admin_service)
+                .register_service(health_service)
+                .register_service(build_info_service),
+            |server_builder, service| server_builder.register_service(service),
+        );
+        let server_builder = server_builder.bind_using_uri(admin_listen_uri, logger.clone());
 
         let mut server = server_builder.build()?;
         server.start();